@@ -0,0 +1,294 @@
+//! # Multisig Smart Contract
+//!
+//! A multi-signature wallet for the Gillean blockchain platform. A fixed set of
+//! owners submit and confirm transactions, and a transaction can only be executed
+//! once a threshold of owners have confirmed it.
+//!
+//! ## Features
+//!
+//! - Submit transactions (the submitter's confirmation is recorded automatically)
+//! - Confirm transactions; confirming twice is a no-op
+//! - Execute transactions once the confirmation threshold is reached
+//! - `Submission`, `Confirmation` and `Execution` events
+//!
+//! ## Usage
+//!
+//! ```rust
+//! // Deploy with three owners, two of whom must confirm each transaction
+//! let mut wallet = Multisig::new(vec![alice, bob, carol], 2)?;
+//!
+//! // Alice submits a transfer (and confirms it)
+//! let tx_id = wallet.submit(recipient, 500, Vec::new())?;
+//!
+//! // Bob confirms, reaching the threshold
+//! wallet.confirm(tx_id)?;
+//!
+//! // Any owner can now execute it
+//! wallet.execute(tx_id)?;
+//! ```
+
+use gillean_contract::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Multisig smart contract
+#[contract]
+pub struct Multisig {
+    /// Accounts allowed to submit, confirm and execute transactions
+    owners: Vec<Address>,
+    /// Number of confirmations required to execute a transaction
+    threshold: u32,
+    /// Submitted transactions by ID
+    transactions: HashMap<u64, MultisigTransaction>,
+    /// Owners that have confirmed each transaction
+    confirmations: HashMap<u64, Vec<Address>>,
+    /// Next transaction ID
+    next_transaction_id: u64,
+}
+
+/// A transaction awaiting confirmation or already executed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultisigTransaction {
+    /// Transaction ID
+    pub id: u64,
+    /// Recipient of the transferred value
+    pub destination: Address,
+    /// Amount to transfer
+    pub value: u64,
+    /// Call data passed along with the transfer
+    pub data: Vec<u8>,
+    /// Whether the transaction has been executed
+    pub executed: bool,
+}
+
+impl Multisig {
+    /// Create a new multisig wallet
+    #[constructor]
+    pub fn new(owners: Vec<Address>, threshold: u32) -> Result<Self, String> {
+        if owners.is_empty() {
+            return Err("At least one owner is required".to_string());
+        }
+
+        let mut unique = owners.clone();
+        unique.sort();
+        unique.dedup();
+        if unique.len() != owners.len() {
+            return Err("Owners must be unique".to_string());
+        }
+
+        if threshold == 0 || threshold as usize > owners.len() {
+            return Err(format!("Threshold must be between 1 and {}", owners.len()));
+        }
+
+        Ok(Self {
+            owners,
+            threshold,
+            transactions: HashMap::new(),
+            confirmations: HashMap::new(),
+            next_transaction_id: 0,
+        })
+    }
+
+    /// Get the wallet owners
+    #[view]
+    pub fn get_owners(&self) -> Vec<Address> {
+        self.owners.clone()
+    }
+
+    /// Get the number of confirmations required for execution
+    #[view]
+    pub fn get_threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    /// Get a transaction by ID
+    #[view]
+    pub fn get_transaction(&self, transaction_id: u64) -> Option<MultisigTransaction> {
+        self.transactions.get(&transaction_id).cloned()
+    }
+
+    /// Get the number of confirmations a transaction has received
+    #[view]
+    pub fn get_confirmation_count(&self, transaction_id: u64) -> u32 {
+        self.confirmations.get(&transaction_id).map_or(0, |owners| owners.len() as u32)
+    }
+
+    /// Check whether an owner has confirmed a transaction
+    #[view]
+    pub fn is_confirmed_by(&self, transaction_id: u64, owner: Address) -> bool {
+        self.confirmations
+            .get(&transaction_id)
+            .is_some_and(|owners| owners.contains(&owner))
+    }
+
+    /// Submit a transaction, confirming it on behalf of the caller
+    #[payable]
+    pub fn submit(&mut self, destination: Address, value: u64, data: Vec<u8>) -> Result<u64, String> {
+        self.submit_as(get_caller(), destination, value, data)
+    }
+
+    /// Confirm a transaction, returning its confirmation count
+    #[payable]
+    pub fn confirm(&mut self, transaction_id: u64) -> Result<u32, String> {
+        self.confirm_as(get_caller(), transaction_id)
+    }
+
+    /// Execute a transaction that has reached the confirmation threshold
+    #[payable]
+    pub fn execute(&mut self, transaction_id: u64) -> Result<bool, String> {
+        self.execute_as(get_caller(), transaction_id)
+    }
+
+    fn submit_as(&mut self, caller: Address, destination: Address, value: u64, data: Vec<u8>) -> Result<u64, String> {
+        self.require_owner(caller)?;
+
+        let transaction_id = self.next_transaction_id;
+        self.next_transaction_id += 1;
+
+        self.transactions.insert(transaction_id, MultisigTransaction {
+            id: transaction_id,
+            destination,
+            value,
+            data,
+            executed: false,
+        });
+
+        emit_event("Submission", &serde_json::json!({
+            "transaction_id": transaction_id,
+            "destination": destination,
+            "value": value,
+            "caller": caller
+        }));
+
+        self.confirm_as(caller, transaction_id)?;
+        Ok(transaction_id)
+    }
+
+    fn confirm_as(&mut self, caller: Address, transaction_id: u64) -> Result<u32, String> {
+        self.require_owner(caller)?;
+        self.pending_transaction(transaction_id)?;
+
+        let confirmed_by = self.confirmations.entry(transaction_id).or_default();
+        if !confirmed_by.contains(&caller) {
+            confirmed_by.push(caller);
+            emit_event("Confirmation", &serde_json::json!({
+                "transaction_id": transaction_id,
+                "owner": caller
+            }));
+        }
+
+        Ok(self.get_confirmation_count(transaction_id))
+    }
+
+    fn execute_as(&mut self, caller: Address, transaction_id: u64) -> Result<bool, String> {
+        self.require_owner(caller)?;
+        self.pending_transaction(transaction_id)?;
+
+        let confirmations = self.get_confirmation_count(transaction_id);
+        if confirmations < self.threshold {
+            return Err(format!(
+                "Transaction {} has {} of {} required confirmations",
+                transaction_id, confirmations, self.threshold
+            ));
+        }
+
+        let transaction = self.transactions.get_mut(&transaction_id)
+            .ok_or_else(|| format!("Transaction {} does not exist", transaction_id))?;
+        transaction.executed = true;
+
+        if transaction.value > 0 {
+            transfer(transaction.destination, transaction.value);
+        }
+
+        emit_event("Execution", &serde_json::json!({
+            "transaction_id": transaction_id,
+            "destination": transaction.destination,
+            "value": transaction.value,
+            "caller": caller
+        }));
+
+        Ok(true)
+    }
+
+    fn require_owner(&self, caller: Address) -> Result<(), String> {
+        if !self.owners.contains(&caller) {
+            return Err("Only owners can perform this action".to_string());
+        }
+        Ok(())
+    }
+
+    fn pending_transaction(&self, transaction_id: u64) -> Result<&MultisigTransaction, String> {
+        let transaction = self.transactions.get(&transaction_id)
+            .ok_or_else(|| format!("Transaction {} does not exist", transaction_id))?;
+        if transaction.executed {
+            return Err(format!("Transaction {} has already been executed", transaction_id));
+        }
+        Ok(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(byte: u8) -> Address {
+        Address::from([byte; 20])
+    }
+
+    fn wallet() -> Multisig {
+        Multisig::new(vec![address(1), address(2), address(3)], 2).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_configuration() {
+        assert!(Multisig::new(vec![], 1).is_err());
+        assert!(Multisig::new(vec![address(1), address(2)], 3).is_err());
+        assert!(Multisig::new(vec![address(1), address(1)], 1).is_err());
+    }
+
+    #[test]
+    fn test_submission() {
+        let mut wallet = wallet();
+        let transaction_id = wallet.submit_as(address(1), address(9), 100, Vec::new()).unwrap();
+
+        let transaction = wallet.get_transaction(transaction_id).unwrap();
+        assert_eq!(transaction.destination, address(9));
+        assert!(!transaction.executed);
+        assert_eq!(wallet.get_confirmation_count(transaction_id), 1);
+        assert!(wallet.is_confirmed_by(transaction_id, address(1)));
+
+        assert!(wallet.submit_as(address(9), address(9), 100, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_confirm_is_idempotent() {
+        let mut wallet = wallet();
+        let transaction_id = wallet.submit_as(address(1), address(9), 100, Vec::new()).unwrap();
+
+        assert_eq!(wallet.confirm_as(address(1), transaction_id).unwrap(), 1);
+        assert_eq!(wallet.confirm_as(address(1), transaction_id).unwrap(), 1);
+        assert!(wallet.execute_as(address(1), transaction_id).is_err());
+    }
+
+    #[test]
+    fn test_execution_after_threshold() {
+        let mut wallet = wallet();
+        let transaction_id = wallet.submit_as(address(1), address(9), 100, Vec::new()).unwrap();
+
+        assert_eq!(wallet.confirm_as(address(2), transaction_id).unwrap(), 2);
+        assert!(wallet.execute_as(address(3), transaction_id).unwrap());
+        assert!(wallet.get_transaction(transaction_id).unwrap().executed);
+
+        assert!(wallet.execute_as(address(3), transaction_id).is_err());
+        assert!(wallet.confirm_as(address(3), transaction_id).is_err());
+    }
+
+    #[test]
+    fn test_execution_rejected_below_threshold() {
+        let mut wallet = wallet();
+        let transaction_id = wallet.submit_as(address(1), address(9), 100, Vec::new()).unwrap();
+
+        assert!(wallet.execute_as(address(1), transaction_id).is_err());
+        assert!(!wallet.get_transaction(transaction_id).unwrap().executed);
+    }
+}
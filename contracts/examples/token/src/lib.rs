@@ -1,31 +1,298 @@
 //! # Token Smart Contract
-//! 
-//! A placeholder for the token contract implementation.
+//!
+//! An ERC-20 style fungible token for the Gillean blockchain platform.
+//!
+//! ## Features
+//!
+//! - Balances and total supply tracking
+//! - Direct transfers between accounts
+//! - Allowances via `approve` / `transfer_from`
+//! - Owner-restricted minting
+//! - `Transfer` and `Approval` events
+//!
+//! ## Usage
+//!
+//! ```rust
+//! // Deploy the contract; the deployer receives the initial supply
+//! let mut token = Token::new("MyToken".to_string(), "MTK".to_string(), 1_000_000);
+//!
+//! // Transfer tokens
+//! token.transfer(recipient, 100)?;
+//!
+//! // Let a spender move up to 50 tokens on our behalf
+//! token.approve(spender, 50)?;
+//!
+//! // Called by the spender
+//! token.transfer_from(owner, recipient, 50)?;
+//! ```
 
 use gillean_contract::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-/// Token smart contract (placeholder)
+/// Fungible token smart contract
 #[contract]
 pub struct Token {
-    // Placeholder implementation
+    /// Token name
+    name: String,
+    /// Token symbol
+    symbol: String,
+    /// Number of tokens in existence
+    total_supply: u64,
+    /// Account balances
+    balances: HashMap<Address, u64>,
+    /// Remaining amount each spender may move on behalf of an owner
+    allowances: HashMap<Address, HashMap<Address, u64>>,
+    /// Contract owner, the only account allowed to mint
+    owner: Address,
 }
 
 impl Token {
-    /// Create a new token contract
+    /// Create a new token, crediting the initial supply to the deployer
     #[constructor]
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(name: String, symbol: String, initial_supply: u64) -> Self {
+        Self::with_owner(get_caller(), name, symbol, initial_supply)
+    }
+
+    fn with_owner(owner: Address, name: String, symbol: String, initial_supply: u64) -> Self {
+        let mut balances = HashMap::new();
+        if initial_supply > 0 {
+            balances.insert(owner, initial_supply);
+        }
+
+        Self {
+            name,
+            symbol,
+            total_supply: initial_supply,
+            balances,
+            allowances: HashMap::new(),
+            owner,
+        }
+    }
+
+    /// Get the token name
+    #[view]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Get the token symbol
+    #[view]
+    pub fn symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    /// Get the total supply
+    #[view]
+    pub fn total_supply(&self) -> u64 {
+        self.total_supply
+    }
+
+    /// Get the contract owner
+    #[view]
+    pub fn get_owner(&self) -> Address {
+        self.owner
+    }
+
+    /// Get the balance of an account
+    #[view]
+    pub fn balance_of(&self, account: Address) -> u64 {
+        self.balances.get(&account).copied().unwrap_or(0)
+    }
+
+    /// Get the amount `spender` may still transfer on behalf of `owner`
+    #[view]
+    pub fn allowance(&self, owner: Address, spender: Address) -> u64 {
+        self.allowances
+            .get(&owner)
+            .and_then(|spenders| spenders.get(&spender))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Transfer tokens from the caller to `to`
+    #[payable]
+    pub fn transfer(&mut self, to: Address, amount: u64) -> Result<bool, String> {
+        self.transfer_as(get_caller(), to, amount)
+    }
+
+    /// Allow `spender` to transfer up to `amount` of the caller's tokens
+    #[payable]
+    pub fn approve(&mut self, spender: Address, amount: u64) -> Result<bool, String> {
+        self.approve_as(get_caller(), spender, amount)
+    }
+
+    /// Transfer tokens from `from` to `to` using the caller's allowance
+    #[payable]
+    pub fn transfer_from(&mut self, from: Address, to: Address, amount: u64) -> Result<bool, String> {
+        self.transfer_from_as(get_caller(), from, to, amount)
+    }
+
+    /// Create new tokens and credit them to `to` (owner only)
+    #[payable]
+    pub fn mint(&mut self, to: Address, amount: u64) -> Result<u64, String> {
+        self.mint_as(get_caller(), to, amount)
+    }
+
+    fn transfer_as(&mut self, caller: Address, to: Address, amount: u64) -> Result<bool, String> {
+        self.move_tokens(caller, to, amount)?;
+        Ok(true)
+    }
+
+    fn approve_as(&mut self, caller: Address, spender: Address, amount: u64) -> Result<bool, String> {
+        if spender == caller {
+            return Err("Cannot approve yourself as spender".to_string());
+        }
+
+        self.allowances.entry(caller).or_default().insert(spender, amount);
+        emit_event("Approval", &serde_json::json!({
+            "owner": caller,
+            "spender": spender,
+            "amount": amount
+        }));
+
+        Ok(true)
+    }
+
+    fn transfer_from_as(&mut self, caller: Address, from: Address, to: Address, amount: u64) -> Result<bool, String> {
+        let allowed = self.allowance(from, caller);
+        if allowed < amount {
+            return Err(format!("Allowance of {} is insufficient for transfer of {}", allowed, amount));
+        }
+
+        self.move_tokens(from, to, amount)?;
+        self.allowances.entry(from).or_default().insert(caller, allowed - amount);
+
+        Ok(true)
+    }
+
+    fn mint_as(&mut self, caller: Address, to: Address, amount: u64) -> Result<u64, String> {
+        if caller != self.owner {
+            return Err("Only owner can mint tokens".to_string());
+        }
+        if amount == 0 {
+            return Err("Amount must be greater than 0".to_string());
+        }
+
+        let total_supply = self.total_supply
+            .checked_add(amount)
+            .ok_or_else(|| "Mint would overflow total supply".to_string())?;
+        self.total_supply = total_supply;
+        *self.balances.entry(to).or_insert(0) += amount;
+
+        emit_event("Transfer", &serde_json::json!({
+            "from": null,
+            "to": to,
+            "amount": amount
+        }));
+
+        Ok(self.total_supply)
+    }
+
+    fn move_tokens(&mut self, from: Address, to: Address, amount: u64) -> Result<(), String> {
+        if amount == 0 {
+            return Err("Amount must be greater than 0".to_string());
+        }
+
+        let from_balance = self.balance_of(from);
+        if from_balance < amount {
+            return Err(format!("Balance of {} is insufficient for transfer of {}", from_balance, amount));
+        }
+
+        self.balances.insert(from, from_balance - amount);
+        *self.balances.entry(to).or_insert(0) += amount;
+
+        emit_event("Transfer", &serde_json::json!({
+            "from": from,
+            "to": to,
+            "amount": amount
+        }));
+
+        Ok(())
+    }
+
+    /// Get token information
+    #[view]
+    pub fn get_info(&self) -> TokenInfo {
+        TokenInfo {
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            total_supply: self.total_supply,
+            owner: self.owner,
+        }
     }
 }
 
+/// Token information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenInfo {
+    /// Token name
+    pub name: String,
+    /// Token symbol
+    pub symbol: String,
+    /// Number of tokens in existence
+    pub total_supply: u64,
+    /// Contract owner
+    pub owner: Address,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn address(byte: u8) -> Address {
+        Address::from([byte; 20])
+    }
+
+    fn token() -> Token {
+        Token::with_owner(address(1), "MyToken".to_string(), "MTK".to_string(), 1000)
+    }
+
     #[test]
     fn test_token_creation() {
-        let token = Token::new();
-        // Placeholder test
+        let token = token();
+        assert_eq!(token.name(), "MyToken");
+        assert_eq!(token.symbol(), "MTK");
+        assert_eq!(token.total_supply(), 1000);
+        assert_eq!(token.balance_of(address(1)), 1000);
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut token = token();
+        assert!(token.transfer_as(address(1), address(2), 300).unwrap());
+        assert_eq!(token.balance_of(address(1)), 700);
+        assert_eq!(token.balance_of(address(2)), 300);
+        assert_eq!(token.total_supply(), 1000);
+
+        assert!(token.transfer_as(address(2), address(3), 301).is_err());
+        assert_eq!(token.balance_of(address(2)), 300);
+    }
+
+    #[test]
+    fn test_allowance_enforcement() {
+        let mut token = token();
+        assert!(token.transfer_from_as(address(2), address(1), address(3), 1).is_err());
+
+        token.approve_as(address(1), address(2), 100).unwrap();
+        assert_eq!(token.allowance(address(1), address(2)), 100);
+
+        token.transfer_from_as(address(2), address(1), address(3), 60).unwrap();
+        assert_eq!(token.allowance(address(1), address(2)), 40);
+        assert_eq!(token.balance_of(address(3)), 60);
+
+        assert!(token.transfer_from_as(address(2), address(1), address(3), 41).is_err());
+        assert_eq!(token.allowance(address(1), address(2)), 40);
+        assert_eq!(token.balance_of(address(1)), 940);
+    }
+
+    #[test]
+    fn test_mint_restricted_to_owner() {
+        let mut token = token();
+        assert!(token.mint_as(address(2), address(2), 500).is_err());
+        assert_eq!(token.total_supply(), 1000);
+
+        assert_eq!(token.mint_as(address(1), address(2), 500).unwrap(), 1500);
+        assert_eq!(token.balance_of(address(2)), 500);
     }
 }
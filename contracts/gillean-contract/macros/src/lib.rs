@@ -0,0 +1,31 @@
+//! # Gillean Contract Macros
+//!
+//! Attribute macros that mark the parts of a Gillean contract. They leave the
+//! annotated item unchanged; the contract toolkit reads them from the source to
+//! build the contract's ABI.
+
+use proc_macro::TokenStream;
+
+/// Mark a struct as the contract's state
+#[proc_macro_attribute]
+pub fn contract(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+/// Mark an associated function as a contract constructor
+#[proc_macro_attribute]
+pub fn constructor(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+/// Mark a method as a read-only contract method
+#[proc_macro_attribute]
+pub fn view(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+/// Mark a method as a state-changing contract method that may receive funds
+#[proc_macro_attribute]
+pub fn payable(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
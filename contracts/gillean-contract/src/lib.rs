@@ -0,0 +1,150 @@
+//! # Gillean Contract
+//!
+//! Framework for writing Gillean smart contracts in Rust and compiling them to WASM.
+//! Contracts import everything with `use gillean_contract::*;`, which brings in the
+//! attribute macros marking the contract, the `Address` type, the execution
+//! environment functions, and the `HashMap`, `Serialize` and `Deserialize` items
+//! contracts commonly need.
+//!
+//! The execution environment is kept per thread: the caller is set with
+//! `set_caller` before a method runs, and the events the method emits and the
+//! transfers it requests are collected with `take_events` and `take_transfers`
+//! afterwards. Contracts can therefore be unit tested natively.
+
+use std::cell::RefCell;
+use std::fmt;
+
+pub use gillean_contract_macros::{constructor, contract, payable, view};
+pub use serde::{Deserialize, Serialize};
+pub use std::collections::HashMap;
+
+/// Length of an address in bytes
+pub const ADDRESS_LENGTH: usize = 20;
+
+/// Account or contract address
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Address([u8; ADDRESS_LENGTH]);
+
+impl Address {
+    /// Get the raw address bytes
+    ///
+    /// # Returns
+    /// * `&[u8; ADDRESS_LENGTH]` - The address bytes
+    pub fn as_bytes(&self) -> &[u8; ADDRESS_LENGTH] {
+        &self.0
+    }
+}
+
+impl From<[u8; ADDRESS_LENGTH]> for Address {
+    fn from(bytes: [u8; ADDRESS_LENGTH]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x")?;
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Event emitted by a contract method
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    /// Event name
+    pub name: String,
+    /// Event data
+    pub data: serde_json::Value,
+}
+
+/// Transfer of the contract's funds requested by a contract method
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transfer {
+    /// Recipient address
+    pub to: Address,
+    /// Amount to send
+    pub amount: u64,
+}
+
+thread_local! {
+    static CALLER: RefCell<Address> = RefCell::new(Address::default());
+    static EVENTS: RefCell<Vec<Event>> = const { RefCell::new(Vec::new()) };
+    static TRANSFERS: RefCell<Vec<Transfer>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Get the address that called the executing method
+///
+/// # Returns
+/// * `Address` - The caller, or the zero address if none has been set
+pub fn get_caller() -> Address {
+    CALLER.with(|caller| *caller.borrow())
+}
+
+/// Set the address that calls the next methods
+///
+/// # Arguments
+/// * `caller` - The calling address
+pub fn set_caller(caller: Address) {
+    CALLER.with(|current| *current.borrow_mut() = caller);
+}
+
+/// Emit an event from the executing method
+///
+/// # Arguments
+/// * `name` - Event name
+/// * `data` - Event data
+pub fn emit_event(name: &str, data: &serde_json::Value) {
+    EVENTS.with(|events| events.borrow_mut().push(Event {
+        name: name.to_string(),
+        data: data.clone(),
+    }));
+}
+
+/// Take the events emitted since they were last taken
+///
+/// # Returns
+/// * `Vec<Event>` - The emitted events, oldest first
+pub fn take_events() -> Vec<Event> {
+    EVENTS.with(|events| std::mem::take(&mut *events.borrow_mut()))
+}
+
+/// Send funds from the contract to an address
+///
+/// # Arguments
+/// * `to` - Recipient address
+/// * `amount` - Amount to send
+pub fn transfer(to: Address, amount: u64) {
+    TRANSFERS.with(|transfers| transfers.borrow_mut().push(Transfer { to, amount }));
+}
+
+/// Take the transfers requested since they were last taken
+///
+/// # Returns
+/// * `Vec<Transfer>` - The requested transfers, oldest first
+pub fn take_transfers() -> Vec<Transfer> {
+    TRANSFERS.with(|transfers| std::mem::take(&mut *transfers.borrow_mut()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caller_and_events() {
+        assert_eq!(get_caller(), Address::default());
+        set_caller(Address::from([7; ADDRESS_LENGTH]));
+        assert_eq!(get_caller(), Address::from([7; ADDRESS_LENGTH]));
+
+        emit_event("Ping", &serde_json::json!({"caller": get_caller()}));
+        let events = take_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name, "Ping");
+        assert!(take_events().is_empty());
+
+        transfer(Address::from([8; ADDRESS_LENGTH]), 5);
+        assert_eq!(take_transfers(), vec![Transfer { to: Address::from([8; ADDRESS_LENGTH]), amount: 5 }]);
+    }
+}
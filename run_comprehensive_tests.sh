@@ -345,7 +345,7 @@ test_api_endpoints() {
     run_api_test "Get Balance" "GET" "/balance/alice" "" "200"
     
     # Test 10: Add signed transaction (should fail due to insufficient balance)
-    local signed_tx_data='{"sender":"bob","receiver":"charlie","amount":5.0,"message":"signed tx","signature":"test_sig","public_key":"test_pubkey"}'
+    local signed_tx_data='{"sender":"bob","receiver":"charlie","amount":5.0,"message":"signed tx","timestamp":0,"signature":"test_sig","public_key":"test_pubkey"}'
     run_api_test "Add Signed Transaction" "POST" "/transaction/signed" "$signed_tx_data" "400"
     
     # Test 11: Get peers
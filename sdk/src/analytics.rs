@@ -1,4 +1,5 @@
-use super::{SDKResult, SDKConfig, AnalyticsData, AnalyticsMetric, DataPoint, AnalyticsSummary};
+use super::{SDKResult, SDKError, SDKConfig, AnalyticsData, AnalyticsMetric, BucketGranularity, DataPoint, AnalyticsSummary, GilleanClient};
+use super::client::ApiResponse;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -15,13 +16,41 @@ fn simple_random() -> f64 {
 
 /// Analytics client for retrieving blockchain analytics and metrics
 pub struct AnalyticsClient {
-    _config: SDKConfig,
+    config: SDKConfig,
 }
 
 impl AnalyticsClient {
     /// Create a new analytics client
     pub fn new(config: SDKConfig) -> Self {
-        Self { _config: config }
+        Self { config }
+    }
+
+    /// Get a metric over `[from, to)` aggregated by the node into time buckets
+    ///
+    /// Every bucket in the range is returned, with a zero summary if it holds no
+    /// data points. Only metrics derived from the chain (`TransactionVolume` and
+    /// `ContractDeployments`) are available.
+    pub async fn get_bucketed_analytics(
+        &self,
+        metric_type: AnalyticsMetric,
+        from: i64,
+        to: i64,
+        granularity: BucketGranularity,
+    ) -> SDKResult<AnalyticsData> {
+        let metric = serde_json::to_value(&metric_type)?;
+        let bucket = serde_json::to_value(granularity)?;
+        let url = format!(
+            "{}/api/analytics/{}?from={}&to={}&bucket={}",
+            self.config.api_url,
+            metric.as_str().unwrap_or_default(),
+            from,
+            to,
+            bucket.as_str().unwrap_or_default(),
+        );
+
+        let client = GilleanClient::new(self.config.clone()).await?;
+        let envelope: ApiResponse<AnalyticsData> = client.make_request(&url).await?;
+        envelope.data.ok_or(SDKError::NetworkError(envelope.message))
     }
 
     /// Get analytics data for a specific metric
@@ -36,6 +65,7 @@ impl AnalyticsClient {
             data_points,
             summary,
             timestamp: chrono::Utc::now().timestamp(),
+            buckets: Vec::new(),
         };
 
         Ok(result)
@@ -67,6 +97,7 @@ impl AnalyticsClient {
             data_points,
             summary,
             timestamp: chrono::Utc::now().timestamp(),
+            buckets: Vec::new(),
         };
 
         Ok(result)
@@ -328,6 +359,68 @@ mod tests {
         assert_eq!(analytics.len(), 4);
     }
 
+    #[tokio::test]
+    async fn test_bucketed_analytics() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                let read = socket.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let body = serde_json::json!({
+                "success": true,
+                "data": {
+                    "metric_type": "TransactionVolume",
+                    "data_points": [
+                        {"timestamp": 1_704_067_200, "value": 45.0, "label": null},
+                        {"timestamp": 1_704_153_600, "value": 0.0, "label": null},
+                    ],
+                    "summary": {"total": 45.0, "average": 15.0, "min": 5.0, "max": 30.0, "count": 3},
+                    "timestamp": 1_704_300_000,
+                    "buckets": [
+                        {"start": 1_704_067_200, "end": 1_704_153_600, "summary": {"total": 45.0, "average": 15.0, "min": 5.0, "max": 30.0, "count": 3}},
+                        {"start": 1_704_153_600, "end": 1_704_240_000, "summary": {"total": 0.0, "average": 0.0, "min": 0.0, "max": 0.0, "count": 0}},
+                    ],
+                },
+                "message": "Analytics retrieved successfully",
+            }).to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        let config = crate::SDKConfig {
+            api_url: format!("http://{}", address),
+            ..crate::SDKConfig::default()
+        };
+        let analytics_client = AnalyticsClient::new(config);
+        let data = analytics_client.get_bucketed_analytics(
+            AnalyticsMetric::TransactionVolume,
+            1_704_067_200,
+            1_704_240_000,
+            BucketGranularity::Day,
+        ).await.unwrap();
+
+        assert_eq!(data.metric_type, AnalyticsMetric::TransactionVolume);
+        assert_eq!(data.buckets.len(), 2);
+        assert_eq!(data.buckets[0].summary.count, 3);
+        assert_eq!(data.buckets[1].summary.total, 0.0);
+        assert_eq!(data.data_points.len(), 2);
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("GET /api/analytics/TransactionVolume?from=1704067200&to=1704240000&bucket=day "));
+    }
+
     #[tokio::test]
     async fn test_historical_analytics() {
         let config = crate::SDKConfig::default();
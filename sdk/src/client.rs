@@ -1,9 +1,24 @@
-use super::{SDKResult, SDKError, SDKConfig, BlockchainStatus, TransactionInfo, BlockInfo, ShardInfo, BridgeStatus, ContractInfo, MetricsData};
-use reqwest::Client;
+use super::{SDKResult, SDKError, SDKConfig, BlockchainStatus, TransactionInfo, TransactionStatusInfo, BlockInfo, ShardInfo, BridgeStatus, ContractInfo, MetricsData, BatchTransactionRequest, BatchTransactionResult};
+use futures_util::stream::{self, Stream};
+use rand::Rng;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::time::Duration;
 use tokio::sync::mpsc;
 
+/// Delay before the first retry; each further retry doubles it
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// Longest single wait between attempts, including server-requested `Retry-After` delays
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Number of blocks requested per `/chain` page when streaming blocks
+const BLOCK_STREAM_PAGE_SIZE: usize = 100;
+
+/// How often a block stream that has reached the tip checks for new blocks
+const BLOCK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 /// HTTP client for interacting with Gillean blockchain API
 pub struct GilleanClient {
     client: Client,
@@ -40,18 +55,104 @@ impl GilleanClient {
         self.make_request(&url).await
     }
 
+    /// Stream blocks in order, starting at index `from`
+    ///
+    /// Blocks are fetched a page at a time from `/chain` as the stream is polled, so at
+    /// most one page is buffered however slowly the consumer reads. Once the stream
+    /// reaches the tip it keeps polling for new blocks and never ends; request errors
+    /// are yielded as items and the stream resumes where it left off on the next poll.
+    pub fn stream_blocks(&self, from: usize) -> impl Stream<Item = SDKResult<BlockInfo>> + '_ {
+        let state = BlockStreamState {
+            next: from,
+            chain_length: None,
+            buffer: VecDeque::new(),
+        };
+
+        stream::unfold(state, move |mut state| async move {
+            loop {
+                if let Some(block) = state.buffer.pop_front() {
+                    return Some((Ok(block), state));
+                }
+
+                if state.chain_length.is_none_or(|length| state.next >= length) {
+                    match self.get_chain_page(0, 1, "desc").await {
+                        Ok(page) if page.total_blocks > state.next => state.chain_length = Some(page.total_blocks),
+                        Ok(_) => {
+                            tokio::time::sleep(BLOCK_POLL_INTERVAL).await;
+                            continue;
+                        }
+                        Err(e) => return Some((Err(e), state)),
+                    }
+                }
+
+                match self.get_chain_page(state.next, BLOCK_STREAM_PAGE_SIZE, "asc").await {
+                    Ok(page) => {
+                        state.chain_length = Some(page.total_blocks);
+                        state.next += page.blocks.len();
+                        state.buffer.extend(page.blocks.into_iter().map(BlockInfo::from));
+                    }
+                    Err(e) => return Some((Err(e), state)),
+                }
+            }
+        })
+    }
+
+    /// Fetch one page of the chain
+    async fn get_chain_page(&self, offset: usize, limit: usize, order: &str) -> SDKResult<ChainPage> {
+        let url = format!(
+            "{}/api/chain?offset={}&limit={}&order={}",
+            self.config.api_url, offset, limit, order
+        );
+        let envelope: ApiResponse<ChainPage> = self.make_request(&url).await?;
+        envelope.data.ok_or(SDKError::NetworkError(envelope.message))
+    }
+
     /// Get transaction by hash
     pub async fn get_transaction(&self, hash: &str) -> SDKResult<TransactionInfo> {
         let url = format!("{}/api/transaction/{}", self.config.api_url, hash);
         self.make_request(&url).await
     }
 
+    /// Get where a transaction is in its lifecycle, including its confirmations
+    ///
+    /// `required_confirmations` overrides the node's finality depth when reporting
+    /// whether the transaction is final.
+    pub async fn get_transaction_status(&self, id: &str, required_confirmations: Option<u64>) -> SDKResult<TransactionStatusInfo> {
+        let mut url = format!("{}/api/tx/{}/status", self.config.api_url, id);
+        if let Some(required) = required_confirmations {
+            url.push_str(&format!("?confirmations={}", required));
+        }
+        let envelope: ApiResponse<TransactionStatusInfo> = self.make_request(&url).await?;
+        envelope.data.ok_or(SDKError::NetworkError(envelope.message))
+    }
+
     /// Get pending transactions
     pub async fn get_pending_transactions(&self) -> SDKResult<Vec<TransactionInfo>> {
         let url = format!("{}/api/pending", self.config.api_url);
         self.make_request(&url).await
     }
 
+    /// Submit several transactions in one request
+    ///
+    /// The node validates each transaction independently and reports an
+    /// accepted/rejected result per item, so one bad transaction does not fail the batch.
+    pub async fn send_transactions_batch(&self, transactions: &[BatchTransactionRequest]) -> SDKResult<Vec<BatchTransactionResult>> {
+        if transactions.is_empty() {
+            return Err(SDKError::InvalidInput("Batch must contain at least one transaction".to_string()));
+        }
+
+        let url = format!("{}/api/transactions/batch", self.config.api_url);
+        let response = self.send_with_retry(|| self.client.post(&url).json(transactions), false).await?;
+
+        if !response.status().is_success() {
+            return Err(SDKError::TransactionError(format!("Batch rejected: {}", response.status())));
+        }
+
+        let envelope: ApiResponse<Vec<BatchTransactionResult>> = response.json().await
+            .map_err(SDKError::RequestError)?;
+        envelope.data.ok_or(SDKError::TransactionError(envelope.message))
+    }
+
     /// Get shard information
     pub async fn get_shard_info(&self, shard_id: usize) -> SDKResult<ShardInfo> {
         let url = format!("{}/api/shard/{}", self.config.api_url, shard_id);
@@ -98,57 +199,154 @@ impl GilleanClient {
         Ok(rx)
     }
 
-    /// Make HTTP request with retry logic
-    async fn make_request<T>(&self, url: &str) -> SDKResult<T>
+    /// Make a GET request, retrying transient failures
+    pub(crate) async fn make_request<T>(&self, url: &str) -> SDKResult<T>
     where
         T: for<'de> Deserialize<'de>,
     {
-        let mut attempts = 0;
-        let max_attempts = self.config.retry_attempts;
+        let response = self.send_with_retry(|| self.client.get(url), true).await?;
+        let status = response.status();
 
-        loop {
-            attempts += 1;
+        if status.is_success() {
+            response.json::<T>().await.map_err(SDKError::RequestError)
+        } else if status.is_client_error() {
+            Err(SDKError::InvalidInput(format!("Client error: {}", status)))
+        } else {
+            Err(SDKError::NetworkError(format!("Server error: {}", status)))
+        }
+    }
 
-            let response = self.client
-                .get(url)
+    /// Send a request, retrying transient failures up to `retry_attempts` times
+    ///
+    /// Connection failures and `429 Too Many Requests` are retried for every request,
+    /// since the server did not process it. Timeouts and 5xx responses are only retried
+    /// when `idempotent` is set, because a write may already have been applied. Other
+    /// responses, including permanent 4xx errors, are returned immediately. Retries wait
+    /// with exponential backoff and jitter, or for the server's `Retry-After` delay.
+    async fn send_with_retry<F>(&self, build: F, idempotent: bool) -> SDKResult<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut retries = 0;
+
+        loop {
+            let outcome = build()
                 .header("User-Agent", "Gillean-SDK/2.0.0")
                 .send()
                 .await;
+            let exhausted = retries >= self.config.retry_attempts;
 
-            match response {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        let data = response.json::<T>().await
-                            .map_err(SDKError::RequestError)?;
-                        return Ok(data);
-                    } else if response.status().is_client_error() {
-                        return Err(SDKError::InvalidInput(format!("Client error: {}", response.status())));
-                    } else if response.status().is_server_error() {
-                        if attempts < max_attempts {
-                            tokio::time::sleep(Duration::from_secs(attempts as u64)).await;
-                            continue;
-                        }
-                        return Err(SDKError::NetworkError(format!("Server error: {}", response.status())));
+            let delay = match outcome {
+                Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                    if exhausted {
+                        return Err(SDKError::RateLimitExceeded);
                     }
+                    retry_after(&response).unwrap_or_else(|| backoff_delay(retries))
                 }
+                Ok(response) if response.status().is_server_error() && idempotent && !exhausted => {
+                    backoff_delay(retries)
+                }
+                Ok(response) => return Ok(response),
                 Err(e) => {
-                    if attempts < max_attempts {
-                        tokio::time::sleep(Duration::from_secs(attempts as u64)).await;
-                        continue;
+                    let transient = e.is_connect() || (idempotent && e.is_timeout());
+                    if !transient || exhausted {
+                        return Err(SDKError::RequestError(e));
                     }
-                    return Err(SDKError::RequestError(e));
+                    backoff_delay(retries)
                 }
-            }
+            };
+
+            retries += 1;
+            tokio::time::sleep(delay).await;
         }
     }
 }
 
+/// Exponential backoff with jitter for the given retry number (0 for the first retry)
+///
+/// The delay doubles with each retry up to `RETRY_MAX_DELAY` and is then drawn
+/// uniformly from its upper half, so clients failing together spread out.
+fn backoff_delay(retry: u32) -> Duration {
+    let delay = RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(retry))
+        .min(RETRY_MAX_DELAY);
+    rand::thread_rng().gen_range(delay / 2..=delay)
+}
+
+/// Delay requested by a response's `Retry-After` header, in whole seconds
+fn retry_after(response: &Response) -> Option<Duration> {
+    let seconds = response.headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(Duration::from_secs(seconds).min(RETRY_MAX_DELAY))
+}
+
 /// Balance response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct BalanceResponse {
     balance: f64,
 }
 
+/// Position of a block stream in the chain
+struct BlockStreamState {
+    /// Index of the next block to fetch
+    next: usize,
+    /// Chain length reported by the last response
+    chain_length: Option<usize>,
+    /// Fetched blocks not yet yielded
+    buffer: VecDeque<BlockInfo>,
+}
+
+/// Page of blocks returned by the node's `/chain` endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct ChainPage {
+    blocks: Vec<NodeBlock>,
+    total_blocks: usize,
+}
+
+/// Block as serialized by the node
+#[derive(Debug, Clone, Deserialize)]
+struct NodeBlock {
+    index: u64,
+    hash: String,
+    previous_hash: String,
+    timestamp: i64,
+    transactions: Vec<NodeTransaction>,
+}
+
+/// Transaction fields of a node block used by the SDK
+#[derive(Debug, Clone, Deserialize)]
+struct NodeTransaction {
+    id: String,
+    #[serde(default)]
+    gas_limit: Option<u64>,
+}
+
+impl From<NodeBlock> for BlockInfo {
+    fn from(block: NodeBlock) -> Self {
+        Self {
+            number: block.index,
+            hash: block.hash,
+            parent_hash: block.previous_hash,
+            timestamp: block.timestamp,
+            gas_used: 0,
+            gas_limit: block.transactions.iter().filter_map(|tx| tx.gas_limit).sum(),
+            transactions: block.transactions.into_iter().map(|tx| tx.id).collect(),
+        }
+    }
+}
+
+/// Response envelope used by the node API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ApiResponse<T> {
+    pub(crate) data: Option<T>,
+    pub(crate) message: String,
+}
+
 
 
 #[cfg(test)]
@@ -168,4 +366,284 @@ mod tests {
         let response: BalanceResponse = serde_json::from_str(json).unwrap();
         assert_eq!(response.balance, 100.5);
     }
+
+    #[test]
+    fn test_transaction_status_deserialization() {
+        let json = r#"{"status": "Confirmed", "block_number": 7, "confirmations": 3, "finalized": false, "required_confirmations": 6}"#;
+        let info: TransactionStatusInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.status, crate::TransactionStatus::Confirmed);
+        assert_eq!((info.block_number, info.confirmations), (Some(7), 3));
+        assert!(info.is_final(3));
+        assert!(!info.is_final(4));
+
+        let pending: TransactionStatusInfo = serde_json::from_str(r#"{"status": "Pending"}"#).unwrap();
+        assert_eq!(pending.status, crate::TransactionStatus::Pending);
+        assert!(!pending.is_final(0));
+
+        let failed: TransactionStatusInfo = serde_json::from_str(
+            r#"{"status": "Failed", "block_number": 2, "confirmations": 1, "reason": "Out of gas"}"#,
+        ).unwrap();
+        assert_eq!(failed.reason.as_deref(), Some("Out of gas"));
+    }
+
+    /// Start a node that answers each connection with the next canned status line and
+    /// headers, returning its address and the number of requests it received
+    async fn scripted_server(responses: Vec<&'static str>) -> (std::net::SocketAddr, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::Ordering;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let requests = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            for head in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                    let read = socket.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                let body = if head.starts_with("HTTP/1.1 200") {
+                    r#"{"balance": 42.0}"#
+                } else {
+                    "{}"
+                };
+                let response = format!(
+                    "{}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    head,
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (address, requests)
+    }
+
+    async fn client_for(address: std::net::SocketAddr, retry_attempts: u32) -> GilleanClient {
+        let config = crate::SDKConfig {
+            api_url: format!("http://{}", address),
+            retry_attempts,
+            ..crate::SDKConfig::default()
+        };
+        GilleanClient::new(config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() {
+        use std::sync::atomic::Ordering;
+
+        let (address, requests) = scripted_server(vec![
+            "HTTP/1.1 503 Service Unavailable",
+            "HTTP/1.1 429 Too Many Requests\r\nretry-after: 0",
+            "HTTP/1.1 200 OK",
+        ]).await;
+        let client = client_for(address, 2).await;
+
+        assert_eq!(client.get_balance("alice").await.unwrap(), 42.0);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_is_respected() {
+        use std::sync::atomic::Ordering;
+
+        let (address, requests) = scripted_server(vec![
+            "HTTP/1.1 500 Internal Server Error",
+            "HTTP/1.1 502 Bad Gateway",
+            "HTTP/1.1 200 OK",
+        ]).await;
+        let client = client_for(address, 1).await;
+
+        assert!(matches!(client.get_balance("alice").await, Err(SDKError::NetworkError(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_permanent_errors_and_writes_fail_fast() {
+        use std::sync::atomic::Ordering;
+
+        let (address, requests) = scripted_server(vec!["HTTP/1.1 404 Not Found", "HTTP/1.1 200 OK"]).await;
+        let client = client_for(address, 3).await;
+        assert!(matches!(client.get_balance("alice").await, Err(SDKError::InvalidInput(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // A batch the server failed on may have been partly applied, so it is not resent
+        let (address, requests) = scripted_server(vec!["HTTP/1.1 500 Internal Server Error", "HTTP/1.1 200 OK"]).await;
+        let client = client_for(address, 3).await;
+        let batch = vec![BatchTransactionRequest { sender: "alice".to_string(), receiver: "bob".to_string(), amount: 1.0, message: None }];
+        assert!(client.send_transactions_batch(&batch).await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_connection_errors_are_retried() {
+        // Nothing listens on the port, so every attempt fails to connect
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+
+        let client = client_for(address, 2).await;
+        let started = std::time::Instant::now();
+        let error = client.get_balance("alice").await.unwrap_err();
+        assert!(matches!(error, SDKError::RequestError(ref e) if e.is_connect()));
+        // Two retries wait at least half of 100ms and 200ms
+        assert!(started.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_backoff_delay_grows_with_jitter() {
+        for retry in 0..4 {
+            let ceiling = RETRY_BASE_DELAY * 2u32.pow(retry);
+            let delay = backoff_delay(retry);
+            assert!(delay >= ceiling / 2 && delay <= ceiling);
+        }
+        assert!(backoff_delay(40) <= RETRY_MAX_DELAY);
+    }
+
+    /// Start a node serving `/api/chain` pages of at most two blocks from a chain whose
+    /// length the returned counter controls
+    async fn chain_server() -> (std::net::SocketAddr, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::Ordering;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let length = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let chain_length = length.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                    let read = socket.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                let request = String::from_utf8_lossy(&request).to_string();
+                let path = request.split_whitespace().nth(1).unwrap();
+                let url = url::Url::parse(&format!("http://node{}", path)).unwrap();
+                let param = |name: &str| url.query_pairs().find(|(key, _)| key == name).map(|(_, value)| value.to_string()).unwrap();
+
+                let total = chain_length.load(Ordering::SeqCst);
+                let offset: usize = param("offset").parse().unwrap();
+                let indices: Vec<usize> = if param("order") == "desc" {
+                    (0..total).rev().skip(offset).take(1).collect()
+                } else {
+                    (offset..total).take(2).collect()
+                };
+                let blocks: Vec<serde_json::Value> = indices.iter().map(|&index| serde_json::json!({
+                    "index": index,
+                    "hash": format!("hash{}", index),
+                    "previous_hash": format!("hash{}", index.wrapping_sub(1)),
+                    "timestamp": 1_700_000_000 + index as i64,
+                    "transactions": [{"id": format!("tx{}", index), "gas_limit": null}],
+                })).collect();
+                let body = serde_json::json!({
+                    "success": true,
+                    "data": {"blocks": blocks, "total_blocks": total},
+                    "message": "Blockchain retrieved successfully",
+                }).to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (address, length)
+    }
+
+    #[tokio::test]
+    async fn test_stream_blocks_pages_in_order() {
+        use futures_util::StreamExt;
+        use std::sync::atomic::Ordering;
+
+        let (address, length) = chain_server().await;
+        length.store(7, Ordering::SeqCst);
+        let client = client_for(address, 0).await;
+
+        let blocks: Vec<BlockInfo> = client.stream_blocks(1)
+            .take(6)
+            .map(|block| block.unwrap())
+            .collect()
+            .await;
+        let numbers: Vec<u64> = blocks.iter().map(|block| block.number).collect();
+        assert_eq!(numbers, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(blocks[2].parent_hash, "hash2");
+        assert_eq!(blocks[2].transactions, vec!["tx3".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_blocks_follows_the_tip() {
+        use futures_util::StreamExt;
+        use std::sync::atomic::Ordering;
+
+        let (address, length) = chain_server().await;
+        length.store(2, Ordering::SeqCst);
+        let client = client_for(address, 0).await;
+        let mut blocks = Box::pin(client.stream_blocks(0));
+
+        assert_eq!(blocks.next().await.unwrap().unwrap().number, 0);
+        assert_eq!(blocks.next().await.unwrap().unwrap().number, 1);
+
+        // At the tip the stream waits until a new block appears
+        let waiting = tokio::time::timeout(Duration::from_millis(200), blocks.next()).await;
+        assert!(waiting.is_err());
+        length.store(3, Ordering::SeqCst);
+        let block = tokio::time::timeout(Duration::from_secs(5), blocks.next()).await.unwrap();
+        assert_eq!(block.unwrap().unwrap().number, 2);
+    }
+
+    #[tokio::test]
+    async fn test_send_transactions_batch() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Minimal node answering the batch endpoint with a partial success
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            while !String::from_utf8_lossy(&request).contains("\"dave\"") {
+                let read = socket.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let body = r#"{"success":true,"data":[{"index":0,"accepted":true,"transaction_id":"tx0","reason":null},{"index":1,"accepted":false,"transaction_id":null,"reason":"Insufficient balance"}],"message":"1 of 2 transactions accepted"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        let config = crate::SDKConfig {
+            api_url: format!("http://{}", address),
+            ..crate::SDKConfig::default()
+        };
+        let client = GilleanClient::new(config).await.unwrap();
+        let batch = vec![
+            BatchTransactionRequest { sender: "alice".to_string(), receiver: "bob".to_string(), amount: 1.0, message: None },
+            BatchTransactionRequest { sender: "alice".to_string(), receiver: "dave".to_string(), amount: 1e9, message: None },
+        ];
+
+        let results = client.send_transactions_batch(&batch).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].accepted);
+        assert_eq!(results[0].transaction_id.as_deref(), Some("tx0"));
+        assert!(!results[1].accepted);
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /api/transactions/batch"));
+
+        assert!(client.send_transactions_batch(&[]).await.is_err());
+    }
 }
@@ -40,7 +40,7 @@ pub struct SDKConfig {
     pub api_key: Option<String>,
     /// Timeout for requests
     pub timeout: std::time::Duration,
-    /// Retry attempts
+    /// Number of times a request that failed transiently is retried
     pub retry_attempts: u32,
 }
 
@@ -191,6 +191,17 @@ impl GilleanSDK {
         self.analytics_client.get_analytics(metric_type).await
     }
 
+    /// Get analytics data aggregated into time buckets over `[from, to)`
+    pub async fn get_bucketed_analytics(
+        &self,
+        metric_type: AnalyticsMetric,
+        from: i64,
+        to: i64,
+        granularity: BucketGranularity,
+    ) -> SDKResult<AnalyticsData> {
+        self.analytics_client.get_bucketed_analytics(metric_type, from, to, granularity).await
+    }
+
     /// Subscribe to real-time updates
     pub async fn subscribe_to_updates(&self, event_types: Vec<EventType>) -> SDKResult<mpsc::Receiver<Event>> {
         self.client.subscribe_to_updates(event_types).await
@@ -223,6 +234,34 @@ pub struct WalletInfo {
     pub created_at: i64,
 }
 
+/// Lifecycle status of a transaction as reported by the node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionStatusInfo {
+    pub status: TransactionStatus,
+    /// Block holding the transaction, once mined
+    #[serde(default)]
+    pub block_number: Option<u64>,
+    /// Blocks on top of and including the holding block (0 while pending)
+    #[serde(default)]
+    pub confirmations: u64,
+    /// Execution error of a failed transaction
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Whether the node considers the transaction final
+    #[serde(default)]
+    pub finalized: bool,
+    /// Confirmations the node required for finality
+    #[serde(default)]
+    pub required_confirmations: u64,
+}
+
+impl TransactionStatusInfo {
+    /// Whether the transaction is mined with at least `required` confirmations
+    pub fn is_final(&self, required: u64) -> bool {
+        self.status != TransactionStatus::Pending && self.confirmations >= required
+    }
+}
+
 /// Transaction result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionResult {
@@ -233,6 +272,24 @@ pub struct TransactionResult {
     pub timestamp: i64,
 }
 
+/// Transaction submitted as part of a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchTransactionRequest {
+    pub sender: String,
+    pub receiver: String,
+    pub amount: f64,
+    pub message: Option<String>,
+}
+
+/// Outcome of one transaction in a batch submission
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchTransactionResult {
+    pub index: usize,
+    pub accepted: bool,
+    pub transaction_id: Option<String>,
+    pub reason: Option<String>,
+}
+
 /// Private transaction result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PrivateTransactionResult {
@@ -319,6 +376,16 @@ pub enum AnalyticsMetric {
     ContractDeployments,
 }
 
+/// Width of the time buckets analytics are aggregated into
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BucketGranularity {
+    Hour,
+    Day,
+    /// Weeks starting on Monday 00:00 UTC
+    Week,
+}
+
 /// Transaction information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionInfo {
@@ -392,6 +459,17 @@ pub struct AnalyticsData {
     pub data_points: Vec<DataPoint>,
     pub summary: AnalyticsSummary,
     pub timestamp: i64,
+    /// Per-bucket summaries, present when the data was aggregated into time buckets
+    #[serde(default)]
+    pub buckets: Vec<AnalyticsBucket>,
+}
+
+/// Summary of the data points falling in `[start, end)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsBucket {
+    pub start: i64,
+    pub end: i64,
+    pub summary: AnalyticsSummary,
 }
 
 /// Data point for analytics
@@ -1,19 +1,25 @@
-use crate::{Blockchain, Transaction, BlockchainError, WalletManager, EthereumBridge, DecentralizedIdentity, Governance, SimulationManager, BlockchainStorage};
+use crate::{Blockchain, Transaction, BlockchainError, WalletManager, EthereumBridge, DecentralizedIdentity, Governance, SimulationManager, BlockchainStorage, ZKPManager, units};
+use crate::monitor::{HealthStatus, SubsystemCheck};
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::{Json, IntoResponse},
+    extract::{ConnectInfo, Path, Query, Request, State, ws::{Message, WebSocket, WebSocketUpgrade}},
+    http::{header, Method, StatusCode},
+    middleware::{self, Next},
+    response::{Json, IntoResponse, Response},
     routing::{get, post},
     Router,
     // body::Body, // Unused import
 };
-use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
-// use std::collections::HashMap; // Unused import
-use log::{info, error};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use log::{info, warn, error};
 use tower_http::cors::{CorsLayer, Any};
 use tower_http::trace::TraceLayer;
 use metrics::{counter, histogram};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use tokio::sync::broadcast;
 
 /// API-related errors
 #[derive(Debug, thiserror::Error)]
@@ -29,6 +35,21 @@ pub enum ApiError {
     
     #[error("Internal server error: {0}")]
     Internal(String),
+    
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+    
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+    
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    
+    #[error("Rate limit exceeded: {0}")]
+    RateLimitExceeded(String),
+    
+    #[error("Not found: {0}")]
+    NotFound(String),
 }
 
 impl IntoResponse for ApiError {
@@ -38,8 +59,20 @@ impl IntoResponse for ApiError {
             ApiError::Blockchain(msg) => (StatusCode::BAD_REQUEST, msg),
             ApiError::Wallet(msg) => (StatusCode::BAD_REQUEST, msg),
             ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            ApiError::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            ApiError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            ApiError::RateLimitExceeded(msg) => (StatusCode::TOO_MANY_REQUESTS, msg),
+            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
         };
 
+        let request_id = current_request_id().unwrap_or_else(|| "-".to_string());
+        if status.is_server_error() {
+            error!("Request {} failed with {}: {}", request_id, status, error_message);
+        } else {
+            warn!("Request {} rejected with {}: {}", request_id, status, error_message);
+        }
+
         let body = Json(serde_json::json!({
             "error": error_message,
             "status": status.as_u16()
@@ -51,7 +84,10 @@ impl IntoResponse for ApiError {
 
 impl From<BlockchainError> for ApiError {
     fn from(err: BlockchainError) -> Self {
-        ApiError::Blockchain(err.to_string())
+        match err {
+            BlockchainError::NotFound(msg) => ApiError::NotFound(msg),
+            err => ApiError::Blockchain(err.to_string()),
+        }
     }
 }
 
@@ -61,6 +97,11 @@ impl From<crate::storage::StorageError> for ApiError {
     }
 }
 
+/// Convert a decimal amount from a request to base units
+fn request_units(amount: f64) -> std::result::Result<u128, ApiError> {
+    units::to_base_units(amount).map_err(|e| ApiError::InvalidRequest(e.to_string()))
+}
+
 /// API request/response structures
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TransactionRequest {
@@ -68,6 +109,16 @@ pub struct TransactionRequest {
     pub receiver: String,
     pub amount: f64,
     pub message: Option<String>,
+    pub password: String,
+}
+
+/// Outcome of one transaction in a batch submission
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchTransactionResult {
+    pub index: usize,
+    pub accepted: bool,
+    pub transaction_id: Option<String>,
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -76,6 +127,10 @@ pub struct SignedTransactionRequest {
     pub receiver: String,
     pub amount: f64,
     pub message: Option<String>,
+    /// Timestamp the client signed the transaction with
+    pub timestamp: i64,
+    #[serde(default)]
+    pub nonce: Option<u64>,
     pub signature: String,
     pub public_key: String,
 }
@@ -114,11 +169,24 @@ pub struct EthereumTransferRequest {
     pub password: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EthereumCompletionRequest {
+    pub password: String,
+}
+
 // DID Requests
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DIDCreationRequest {
     pub controller: Option<String>,
     pub service_endpoints: Vec<crate::did::ServiceEndpoint>,
+    /// Password encrypting the DID's key in the wallet keystore
+    pub password: String,
+}
+
+/// Password unlocking the DID's current key in the wallet storage
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DIDKeyRequest {
+    pub password: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -129,6 +197,7 @@ pub struct DIDLinkRequest {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DIDVerificationRequest {
     pub message: String,
+    /// Hex-encoded Ed25519 signature over the message
     pub signature: String,
 }
 
@@ -153,6 +222,198 @@ pub struct GovernanceVoteRequest {
     pub stake_amount: f64,
 }
 
+// ZKP Requests
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ZKPTransactionRequest {
+    /// Sender wallet address
+    pub sender: String,
+    /// Sender wallet password
+    pub password: String,
+    /// Hex-encoded receiver public key
+    pub receiver: String,
+    pub amount: f64,
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ZKPVerifyRequest {
+    /// Hex-encoded proof data
+    pub proof_data: String,
+}
+
+/// Default number of blocks returned by `GET /chain`
+pub const DEFAULT_CHAIN_PAGE_SIZE: usize = 50;
+
+/// Maximum number of blocks returned by a single chain request
+pub const MAX_CHAIN_PAGE_SIZE: usize = 500;
+
+/// Ordering of blocks in a chain response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Oldest block first
+    Asc,
+    /// Newest block first
+    #[default]
+    Desc,
+}
+
+// Chain Requests
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ChainQuery {
+    /// Number of blocks to skip in the requested order
+    pub offset: Option<usize>,
+    /// Maximum number of blocks to return
+    pub limit: Option<usize>,
+    pub order: Option<SortOrder>,
+}
+
+// Transaction Status Requests
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TransactionStatusQuery {
+    /// Confirmations after which the transaction counts as final (default `DEFAULT_FINALITY_DEPTH`)
+    pub confirmations: Option<u64>,
+}
+
+// Mempool Requests
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MempoolQuery {
+    /// Only transactions sent by this address
+    pub sender: Option<String>,
+    /// Only transactions offering at least this fee
+    pub min_fee: Option<f64>,
+    /// Maximum number of transactions to return
+    pub limit: Option<usize>,
+}
+
+/// Totals over every pending transaction matching a mempool query
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MempoolSummary {
+    pub count: usize,
+    pub total_fees: f64,
+    pub total_bytes: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MempoolResponse {
+    /// Matching transactions in mempool order, truncated to the requested limit
+    pub transactions: Vec<crate::Transaction>,
+    /// Summary over all matching transactions, regardless of the limit
+    pub summary: MempoolSummary,
+}
+
+/// Maximum number of buckets in a single analytics response
+pub const MAX_ANALYTICS_BUCKETS: i64 = 10_000;
+
+/// Width of the buckets analytics data points are aggregated into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BucketGranularity {
+    Hour,
+    #[default]
+    Day,
+    /// Weeks starting on Monday 00:00 UTC
+    Week,
+}
+
+impl BucketGranularity {
+    /// Bucket width in seconds
+    pub fn seconds(&self) -> i64 {
+        match self {
+            BucketGranularity::Hour => 3600,
+            BucketGranularity::Day => 86_400,
+            BucketGranularity::Week => 7 * 86_400,
+        }
+    }
+
+    /// Start of the bucket containing `timestamp`, aligned to UTC boundaries
+    /// 
+    /// Returns `None` if the bucket would start before `i64::MIN`.
+    pub fn bucket_start(&self, timestamp: i64) -> Option<i64> {
+        // The Unix epoch fell on a Thursday, so weeks are shifted to start on Monday
+        let offset = match self {
+            BucketGranularity::Week => 4 * 86_400,
+            _ => 0,
+        };
+        let into_bucket = (i128::from(timestamp) - offset).rem_euclid(i128::from(self.seconds()));
+        timestamp.checked_sub(into_bucket as i64)
+    }
+}
+
+// Analytics Requests
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalyticsQuery {
+    /// Start of the range (Unix seconds, inclusive)
+    pub from: i64,
+    /// End of the range (Unix seconds, exclusive)
+    pub to: i64,
+    #[serde(default)]
+    pub bucket: BucketGranularity,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsDataPoint {
+    pub timestamp: i64,
+    pub value: f64,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsSummary {
+    pub total: f64,
+    pub average: f64,
+    pub min: f64,
+    pub max: f64,
+    pub count: usize,
+}
+
+impl AnalyticsSummary {
+    /// Summarize a set of values; an empty set summarizes to all zeros
+    pub fn from_values(values: &[f64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        let total: f64 = values.iter().sum();
+        Self {
+            total,
+            average: total / values.len() as f64,
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            count: values.len(),
+        }
+    }
+}
+
+/// Data points falling in `[start, end)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsBucket {
+    pub start: i64,
+    pub end: i64,
+    pub summary: AnalyticsSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalyticsResponse {
+    pub metric_type: String,
+    /// One point per bucket holding the bucket total
+    pub data_points: Vec<AnalyticsDataPoint>,
+    /// Summary over every data point in the range
+    pub summary: AnalyticsSummary,
+    pub timestamp: i64,
+    pub buckets: Vec<AnalyticsBucket>,
+}
+
+// WebSocket Requests
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventStreamQuery {
+    /// Comma-separated list of event types to receive (all if omitted)
+    pub events: Option<String>,
+    /// Only receive events concerning this contract
+    pub contract: Option<String>,
+    /// Only receive events involving this address
+    pub address: Option<String>,
+}
+
 // Simulation Requests
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SimulationRunRequest {
@@ -169,7 +430,10 @@ pub struct ApiResponse<T> {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChainResponse {
     pub blocks: Vec<crate::Block>,
+    /// Number of blocks in the whole chain
     pub total_blocks: usize,
+    /// Number of blocks in this response
+    pub returned_blocks: usize,
     pub total_transactions: usize,
     pub difficulty: u32,
     pub mining_reward: f64,
@@ -181,12 +445,125 @@ pub struct BlockResponse {
     pub transactions: Vec<crate::Transaction>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransactionStatusResponse {
+    #[serde(flatten)]
+    pub status: crate::transaction::TransactionStatus,
+    /// Whether the transaction has at least `required_confirmations` confirmations
+    pub finalized: bool,
+    pub required_confirmations: u64,
+}
+
+/// Inclusion proof of a mined transaction for light clients
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransactionProofResponse {
+    pub proof: crate::MerkleProof,
+    /// Header of the block containing the transaction
+    pub header: crate::block::BlockHeader,
+    /// Hash of `header`, which the proof is verified against
+    pub header_hash: String,
+    /// Merkle leaf hash of the transaction
+    pub tx_hash: String,
+}
+
+/// Request to check a Merkle inclusion proof
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyProofRequest {
+    pub proof: crate::MerkleProof,
+    pub header_hash: String,
+    pub tx_hash: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyProofResponse {
+    pub valid: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BalanceResponse {
     pub address: String,
     pub balance: f64,
 }
 
+/// Request to deploy a smart contract; `code` is hex unless `encoding` is `"base64"`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContractDeployRequest {
+    pub sender: String,
+    pub code: String,
+    #[serde(default)]
+    pub encoding: Option<String>,
+    pub gas_limit: u64,
+    pub gas_price: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContractDeployResponse {
+    pub address: String,
+    pub gas_used: u64,
+}
+
+/// Request to withdraw funds from a contract's balance to its owner
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContractWithdrawRequest {
+    /// Address of the contract owner, credited with the funds
+    pub caller: String,
+    pub amount: f64,
+    /// Owner wallet password
+    pub password: String,
+}
+
+/// Request to register a wallet as a PoS validator, staking from its balance
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegisterValidatorRequest {
+    /// Wallet address of the validator; its key signs the validator's blocks
+    pub address: String,
+    pub stake: f64,
+    /// Validator wallet password
+    pub password: String,
+}
+
+/// Request to add stake to or remove stake from a validator
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidatorStakeRequest {
+    pub address: String,
+    pub amount: f64,
+    /// Validator wallet password
+    pub password: String,
+}
+
+/// Request to withdraw a validator's unstaked tokens whose unbonding period has passed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidatorWithdrawRequest {
+    pub address: String,
+    /// Validator wallet password
+    pub password: String,
+}
+
+/// A PoS validator with its stake and the rewards it has earned
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidatorInfo {
+    pub address: String,
+    pub stake: f64,
+    pub rewards: f64,
+    pub active: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContractInfoResponse {
+    pub address: String,
+    pub owner: String,
+    pub balance: f64,
+    pub active: bool,
+    pub code_size: usize,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContractStorageResponse {
+    pub address: String,
+    pub storage: HashMap<String, String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MiningResponse {
     pub block: crate::Block,
@@ -199,6 +576,27 @@ pub struct PeersResponse {
     pub total_peers: usize,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DIDCreationResponse {
+    pub did: String,
+    /// Hex-encoded Ed25519 public key controlling the DID
+    pub public_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ZKPTransactionResponse {
+    pub proof_id: String,
+    /// Hex-encoded proof data
+    pub proof_data: String,
+    pub transaction: crate::PrivateTransaction,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ZKPVerifyResponse {
+    pub proof_id: String,
+    pub valid: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MetricsResponse {
     pub total_blocks: usize,
@@ -206,6 +604,8 @@ pub struct MetricsResponse {
     pub pending_transactions: usize,
     pub current_difficulty: u32,
     pub mining_reward: f64,
+    pub target_block_time: f64,
+    pub average_block_time: Option<f64>,
     pub blockchain_size_bytes: usize,
     pub uptime_seconds: u64,
     pub api_requests_total: u64,
@@ -221,6 +621,11 @@ pub struct AppState {
     pub did_system: Option<Arc<Mutex<DecentralizedIdentity>>>,
     pub governance: Option<Arc<Mutex<Governance>>>,
     pub simulation_manager: Option<Arc<Mutex<SimulationManager>>>,
+    pub zkp_manager: Option<Arc<Mutex<ZKPManager>>>,
+    /// API keys accepted for mutating requests (authentication is disabled when empty)
+    pub api_keys: Arc<HashSet<String>>,
+    /// Requests allowed per client per minute (rate limiting is disabled when 0)
+    pub rate_limit_per_minute: u32,
     pub storage: Arc<BlockchainStorage>,
     pub storage_path: String,
     pub start_time: std::time::Instant,
@@ -239,12 +644,20 @@ pub fn create_router(state: AppState) -> Router {
         .allow_methods(Any)
         .allow_headers(Any);
 
+    let rate_limiter = RateLimiter::new(state.rate_limit_per_minute).with_api_keys(state.api_keys.clone());
+
     Router::new()
         .route("/chain", get(get_chain))
         .route("/chain/:start/:end", get(get_chain_range))
+        .route("/analytics/:metric", get(get_analytics))
         .route("/block/:index", get(get_block))
         .route("/transaction", post(add_transaction))
         .route("/transaction/signed", post(add_signed_transaction))
+        .route("/transactions/batch", post(add_transaction_batch))
+        .route("/transaction/simulate", post(simulate_transaction))
+        .route("/tx/:id/status", get(get_transaction_status))
+        .route("/tx/:id/proof", get(get_transaction_proof))
+        .route("/proof/verify", post(verify_transaction_proof))
         .route("/balance/:address", get(get_balance))
         .route("/mine", post(mine_block))
         .route("/peers", get(get_peers))
@@ -254,12 +667,28 @@ pub fn create_router(state: AppState) -> Router {
         .route("/wallet/:address/balance", get(get_wallet_balance))
         .route("/transaction/send", post(send_transaction))
         .route("/metrics", get(get_metrics))
+        .route("/metrics/prometheus", get(get_prometheus_metrics))
         .route("/health", get(health_check))
         .route("/pending", get(get_pending_transactions))
+        .route("/mempool", get(get_mempool))
+        .route("/ws", get(event_stream))
+        .route("/rpc", post(json_rpc))
+        // Contract endpoints
+        .route("/contract/deploy", post(deploy_contract))
+        .route("/contract/:address", get(get_contract))
+        .route("/contract/:address/storage", get(get_contract_storage))
+        .route("/contract/:address/withdraw", post(withdraw_from_contract))
+        // Validator endpoints (PoS only)
+        .route("/validator/register", post(register_validator))
+        .route("/validator/stake", post(stake_validator))
+        .route("/validator/unstake", post(unstake_validator))
+        .route("/validator/withdraw", post(withdraw_unbonded))
+        .route("/validators", get(list_validators))
         // Ethereum Integration endpoints
         .route("/eth/transfer", post(ethereum_transfer))
         .route("/eth/balance/:address", get(get_ethereum_balance))
         .route("/eth/transfer/:id/status", get(get_ethereum_transfer_status))
+        .route("/eth/transfer/:id/complete", post(complete_ethereum_transfer))
         .route("/eth/transfers/pending", get(get_pending_ethereum_transfers))
         .route("/eth/bridge/stats", get(get_ethereum_bridge_stats))
         .route("/eth/status", get(get_ethereum_status))
@@ -268,6 +697,8 @@ pub fn create_router(state: AppState) -> Router {
         .route("/did/create", post(create_did))
         .route("/did/:did", get(get_did_document))
         .route("/did/:did/link", post(link_did_to_wallet))
+        .route("/did/:did/rotate", post(rotate_did_key))
+        .route("/did/:did/revoke", post(revoke_did))
         .route("/did/wallet/:address", get(get_did_for_wallet))
         .route("/did/:did/verify", post(verify_did_signature))
         .route("/did/all", get(get_all_dids))
@@ -287,26 +718,236 @@ pub fn create_router(state: AppState) -> Router {
         .route("/simulation/:id/stop", post(stop_simulation))
         .route("/simulation/:id/results", get(get_simulation_results))
         .route("/simulation/all", get(get_all_simulations))
+        // ZKP endpoints
+        .route("/zkp/transaction", post(create_zkp_transaction))
+        .route("/zkp/verify", post(verify_zkp_proof))
+        .layer(middleware::from_fn_with_state(state.clone(), require_api_key))
+        .layer(middleware::from_fn_with_state(rate_limiter, rate_limit))
         .layer(cors)
         .layer(TraceLayer::new_for_http())
+        .layer(middleware::from_fn(assign_request_id))
         .with_state(state)
 }
 
-/// Get the full blockchain
+/// Header carrying the correlation ID assigned to each request
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Get the correlation ID of the request being handled by the current task
+/// 
+/// # Returns
+/// * `Option<String>` - The request's UUID, or None outside of a request
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Assign each request a UUID correlation ID, visible to everything that handles it
+/// through `current_request_id` and returned in the `X-Request-Id` response header
+async fn assign_request_id(mut request: Request, next: Next) -> Response {
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let header_value = header::HeaderValue::from_str(&request_id)
+        .expect("a UUID is a valid header value");
+    request.headers_mut().insert(REQUEST_ID_HEADER, header_value.clone());
+
+    let mut response = REQUEST_ID.scope(request_id, next.run(request)).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, header_value);
+    response
+}
+
+/// Header carrying the client's API key
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Reject mutating requests that do not carry a valid API key
+/// 
+/// Read-only requests (GET, HEAD, OPTIONS) are always allowed through.
+async fn require_api_key(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> std::result::Result<Response, ApiError> {
+    let read_only = matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    if read_only || state.api_keys.is_empty() {
+        return Ok(next.run(request).await);
+    }
+
+    let provided = request.headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| ApiError::Unauthorized("Missing API key".to_string()))?;
+
+    // Check every key so the time taken does not reveal which one matched
+    let valid = state.api_keys
+        .iter()
+        .fold(false, |found, key| constant_time_eq(key.as_bytes(), provided.as_bytes()) | found);
+    if !valid {
+        counter!("api_auth_failures_total", 1);
+        return Err(ApiError::Unauthorized("Invalid API key".to_string()));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Compare two byte strings in time independent of their contents
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Maximum number of tracked clients before idle buckets are pruned
+const MAX_RATE_LIMIT_CLIENTS: usize = 10_000;
+
+/// Token bucket for a single client
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Shared per-client token-bucket rate limiter
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_second: f64,
+    buckets: Arc<Mutex<HashMap<String, TokenBucket>>>,
+    /// Keys whose holders get their own bucket instead of sharing their IP's
+    api_keys: Arc<HashSet<String>>,
+}
+
+impl RateLimiter {
+    /// Create a rate limiter allowing `per_minute` requests per client
+    /// 
+    /// # Arguments
+    /// * `per_minute` - Requests allowed per minute (0 disables limiting)
+    /// 
+    /// # Returns
+    /// * `RateLimiter` - The rate limiter
+    pub fn new(per_minute: u32) -> Self {
+        Self::with_window(per_minute, Duration::from_secs(60))
+    }
+
+    /// Create a rate limiter allowing `limit` requests per `window`
+    /// 
+    /// # Arguments
+    /// * `limit` - Requests allowed per window (0 disables limiting)
+    /// * `window` - Time for an empty bucket to refill completely
+    /// 
+    /// # Returns
+    /// * `RateLimiter` - The rate limiter
+    pub fn with_window(limit: u32, window: Duration) -> Self {
+        Self {
+            capacity: limit as f64,
+            refill_per_second: limit as f64 / window.as_secs_f64(),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+            api_keys: Arc::new(HashSet::new()),
+        }
+    }
+
+    /// Give holders of these API keys a bucket of their own
+    /// 
+    /// # Arguments
+    /// * `api_keys` - Keys accepted by the API
+    /// 
+    /// # Returns
+    /// * `RateLimiter` - The rate limiter
+    pub fn with_api_keys(mut self, api_keys: Arc<HashSet<String>>) -> Self {
+        self.api_keys = api_keys;
+        self
+    }
+
+    /// Identify the client a request is charged to
+    /// 
+    /// Only keys the API accepts count; anything else falls back to the IP address,
+    /// so made-up keys cannot be used to get a fresh bucket on every request.
+    fn client_id(&self, request: &Request) -> String {
+        let key = request.headers().get(API_KEY_HEADER).and_then(|value| value.to_str().ok());
+        match key {
+            Some(key) if self.api_keys.contains(key) => format!("key:{}", key),
+            _ => request.extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|info| format!("ip:{}", info.0.ip()))
+                .unwrap_or_else(|| "ip:unknown".to_string()),
+        }
+    }
+
+    /// Take a token for a client
+    /// 
+    /// # Arguments
+    /// * `client` - Client identifier (API key or IP address)
+    /// 
+    /// # Returns
+    /// * `bool` - True if the request is allowed
+    pub fn try_acquire(&self, client: &str) -> bool {
+        if self.capacity < 1.0 {
+            return true;
+        }
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= MAX_RATE_LIMIT_CLIENTS && !buckets.contains_key(client) {
+            let capacity = self.capacity;
+            let refill_per_second = self.refill_per_second;
+            buckets.retain(|_, bucket| {
+                let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+                bucket.tokens + elapsed * refill_per_second < capacity
+            });
+        }
+
+        let bucket = buckets.entry(client.to_string()).or_insert(TokenBucket {
+            tokens: self.capacity,
+            last_refill: now,
+        });
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Reject requests from clients that have exhausted their rate limit
+/// 
+/// Clients are identified by their API key when it is a valid one, otherwise by IP address.
+async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    request: Request,
+    next: Next,
+) -> std::result::Result<Response, ApiError> {
+    let client = limiter.client_id(&request);
+
+    if !limiter.try_acquire(&client) {
+        counter!("api_rate_limited_total", 1);
+        return Err(ApiError::RateLimitExceeded("Too many requests, please retry later".to_string()));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Get a page of the blockchain
 async fn get_chain(
     State(state): State<AppState>,
+    Query(query): Query<ChainQuery>,
 ) -> std::result::Result<Json<ApiResponse<ChainResponse>>, ApiError> {
     counter!("api_requests_total", 1, "endpoint" => "get_chain");
     let start = std::time::Instant::now();
     
     let blockchain = state.blockchain.lock().unwrap();
-    let response = ChainResponse {
-        blocks: blockchain.blocks.clone(),
-        total_blocks: blockchain.blocks.len(),
-        total_transactions: blockchain.blocks.iter().map(|b| b.transactions.len()).sum(),
-        difficulty: blockchain.difficulty,
-        mining_reward: blockchain.mining_reward,
-    };
+    let response = chain_window(
+        &blockchain,
+        query.offset.unwrap_or(0),
+        query.limit.unwrap_or(DEFAULT_CHAIN_PAGE_SIZE),
+        query.order.unwrap_or_default(),
+    )?;
     
     histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "get_chain");
     
@@ -327,18 +968,11 @@ async fn get_chain_range(
     
     let blockchain = state.blockchain.lock().unwrap();
     
-    if start >= blockchain.blocks.len() || end >= blockchain.blocks.len() || start > end {
+    if start > end || end >= blockchain.blocks.len() {
         return Err(ApiError::InvalidRequest("Invalid block range".to_string()));
     }
     
-    let blocks = blockchain.blocks[start..=end].to_vec();
-    let response = ChainResponse {
-        blocks,
-        total_blocks: end - start + 1,
-        total_transactions: blockchain.blocks[start..=end].iter().map(|b| b.transactions.len()).sum(),
-        difficulty: blockchain.difficulty,
-        mining_reward: blockchain.mining_reward,
-    };
+    let response = chain_window(&blockchain, start, end - start + 1, SortOrder::Asc)?;
     
     histogram!("api_request_duration_ms", start_time.elapsed().as_millis() as f64, "endpoint" => "get_chain_range");
     
@@ -349,6 +983,138 @@ async fn get_chain_range(
     }))
 }
 
+/// Get a chain metric aggregated into time buckets
+/// 
+/// `TransactionVolume` sums the amounts of non-coinbase transactions and
+/// `ContractDeployments` counts deployments, both by block timestamp. Buckets
+/// without data points are returned with a zero summary.
+async fn get_analytics(
+    State(state): State<AppState>,
+    Path(metric): Path<String>,
+    Query(query): Query<AnalyticsQuery>,
+) -> std::result::Result<Json<ApiResponse<AnalyticsResponse>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_analytics");
+    let start = std::time::Instant::now();
+
+    if query.from >= query.to {
+        return Err(ApiError::InvalidRequest("Range start must be before its end".to_string()));
+    }
+    let out_of_range = || ApiError::InvalidRequest("Range is outside the supported timestamps".to_string());
+    let width = query.bucket.seconds();
+    let first_bucket = query.bucket.bucket_start(query.from).ok_or_else(out_of_range)?;
+    let span = query.to.checked_sub(first_bucket).ok_or_else(out_of_range)?;
+    let bucket_count = (span - 1) / width + 1;
+    if bucket_count > MAX_ANALYTICS_BUCKETS {
+        return Err(ApiError::InvalidRequest(format!(
+            "Range spans {} buckets, more than the maximum of {}", bucket_count, MAX_ANALYTICS_BUCKETS
+        )));
+    }
+    // The end of the last bucket must itself be representable
+    first_bucket.checked_add(bucket_count * width).ok_or_else(out_of_range)?;
+
+    let points: Vec<(i64, f64)> = {
+        let blockchain = state.blockchain.lock().unwrap();
+        let in_range = blockchain.blocks.iter()
+            .filter(|block| block.timestamp >= query.from && block.timestamp < query.to);
+        match metric.as_str() {
+            "TransactionVolume" => in_range
+                .flat_map(|block| block.transactions.iter()
+                    .filter(|tx| !tx.is_coinbase() && tx.amount > 0)
+                    .map(move |tx| (block.timestamp, units::from_base_units(tx.amount))))
+                .collect(),
+            "ContractDeployments" => in_range
+                .flat_map(|block| block.transactions.iter()
+                    .filter(|tx| tx.transaction_type == crate::TransactionType::ContractDeploy)
+                    .map(move |_| (block.timestamp, 1.0)))
+                .collect(),
+            other => return Err(ApiError::InvalidRequest(format!(
+                "Metric '{}' is not available; expected TransactionVolume or ContractDeployments", other
+            ))),
+        }
+    };
+
+    let mut bucket_values: Vec<Vec<f64>> = vec![Vec::new(); bucket_count as usize];
+    for &(timestamp, value) in &points {
+        bucket_values[((timestamp - first_bucket) / width) as usize].push(value);
+    }
+    let buckets: Vec<AnalyticsBucket> = bucket_values.iter().enumerate()
+        .map(|(i, values)| {
+            let bucket_start = first_bucket + i as i64 * width;
+            AnalyticsBucket {
+                start: bucket_start,
+                end: bucket_start + width,
+                summary: AnalyticsSummary::from_values(values),
+            }
+        })
+        .collect();
+    let data_points = buckets.iter()
+        .map(|bucket| AnalyticsDataPoint {
+            timestamp: bucket.start,
+            value: bucket.summary.total,
+            label: chrono::DateTime::from_timestamp(bucket.start, 0).map(|start| start.to_rfc3339()),
+        })
+        .collect();
+    let values: Vec<f64> = points.iter().map(|&(_, value)| value).collect();
+
+    let response = AnalyticsResponse {
+        metric_type: metric,
+        data_points,
+        summary: AnalyticsSummary::from_values(&values),
+        timestamp: chrono::Utc::now().timestamp(),
+        buckets,
+    };
+
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "get_analytics");
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(response),
+        message: "Analytics retrieved successfully".to_string(),
+    }))
+}
+
+/// Slice a window of blocks out of the chain
+/// 
+/// # Arguments
+/// * `blockchain` - The blockchain to read from
+/// * `offset` - Number of blocks to skip in the given order
+/// * `limit` - Number of blocks to return (at most `MAX_CHAIN_PAGE_SIZE`)
+/// * `order` - Whether to walk the chain from genesis or from the tip
+/// 
+/// # Returns
+/// * `Result<ChainResponse, ApiError>` - The window, or `InvalidRequest` on a bad range
+fn chain_window(
+    blockchain: &Blockchain,
+    offset: usize,
+    limit: usize,
+    order: SortOrder,
+) -> std::result::Result<ChainResponse, ApiError> {
+    let total_blocks = blockchain.blocks.len();
+    
+    if limit == 0 || limit > MAX_CHAIN_PAGE_SIZE {
+        return Err(ApiError::InvalidRequest(format!(
+            "Limit must be between 1 and {}", MAX_CHAIN_PAGE_SIZE
+        )));
+    }
+    if offset >= total_blocks {
+        return Err(ApiError::InvalidRequest("Invalid block range".to_string()));
+    }
+    
+    let blocks: Vec<crate::Block> = match order {
+        SortOrder::Asc => blockchain.blocks.iter().skip(offset).take(limit).cloned().collect(),
+        SortOrder::Desc => blockchain.blocks.iter().rev().skip(offset).take(limit).cloned().collect(),
+    };
+    
+    Ok(ChainResponse {
+        total_blocks,
+        returned_blocks: blocks.len(),
+        total_transactions: blocks.iter().map(|b| b.transactions.len()).sum(),
+        blocks,
+        difficulty: blockchain.difficulty,
+        mining_reward: blockchain.mining_reward,
+    })
+}
+
 /// Get a specific block
 async fn get_block(
     State(state): State<AppState>,
@@ -378,52 +1144,203 @@ async fn get_block(
     }))
 }
 
-/// Add a new transaction
-async fn add_transaction(
+/// Get where a transaction is in its lifecycle
+async fn get_transaction_status(
     State(state): State<AppState>,
-    Json(request): Json<TransactionRequest>,
-) -> std::result::Result<Json<ApiResponse<Transaction>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "add_transaction");
+    Path(id): Path<String>,
+    Query(query): Query<TransactionStatusQuery>,
+) -> std::result::Result<Json<ApiResponse<TransactionStatusResponse>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_transaction_status");
     let start = std::time::Instant::now();
-    
-    let mut blockchain = state.blockchain.lock().unwrap();
-    
-    blockchain.add_transaction(
-        request.sender,
-        request.receiver,
-        request.amount,
-        request.message,
-    )?;
-    
-    // Save to storage
-    state.storage.save_blockchain(&blockchain)?;
-    
-    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "add_transaction");
-    
+
+    let required_confirmations = query.confirmations.unwrap_or(crate::blockchain::DEFAULT_FINALITY_DEPTH);
+    let blockchain = state.blockchain.lock().unwrap();
+    let status = blockchain
+        .transaction_status(&id)
+        .ok_or_else(|| ApiError::NotFound(format!("Transaction not found: {}", id)))?;
+    let status = TransactionStatusResponse {
+        finalized: blockchain.is_final(&id, required_confirmations),
+        required_confirmations,
+        status,
+    };
+
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "get_transaction_status");
+
     Ok(Json(ApiResponse {
         success: true,
-        data: None,
-        message: "Transaction added successfully".to_string(),
+        data: Some(status),
+        message: "Transaction status retrieved successfully".to_string(),
     }))
 }
 
-/// Add a signed transaction
-async fn add_signed_transaction(
+/// Get the Merkle inclusion proof of a mined transaction
+async fn get_transaction_proof(
     State(state): State<AppState>,
-    Json(request): Json<SignedTransactionRequest>,
-) -> std::result::Result<Json<ApiResponse<Transaction>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "add_signed_transaction");
+    Path(id): Path<String>,
+) -> std::result::Result<Json<ApiResponse<TransactionProofResponse>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_transaction_proof");
     let start = std::time::Instant::now();
-    
+
+    let blockchain = state.blockchain.lock().unwrap();
+    let proof = blockchain.inclusion_proof(&id)?;
+    let header = proof.header.clone()
+        .ok_or_else(|| ApiError::Internal(format!("Inclusion proof of {} has no block header", id)))?;
+    let transaction = blockchain.blocks.get(header.index as usize)
+        .and_then(|block| block.transactions.iter().find(|tx| tx.id == id))
+        .ok_or_else(|| ApiError::NotFound(format!("Transaction not found: {}", id)))?;
+    let response = TransactionProofResponse {
+        tx_hash: crate::merkle::transaction_hash(transaction)?,
+        header_hash: header.hash(),
+        header,
+        proof,
+    };
+
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "get_transaction_proof");
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(response),
+        message: "Inclusion proof retrieved successfully".to_string(),
+    }))
+}
+
+/// Check a Merkle inclusion proof without consulting the chain
+async fn verify_transaction_proof(
+    Json(request): Json<VerifyProofRequest>,
+) -> std::result::Result<Json<ApiResponse<VerifyProofResponse>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "verify_transaction_proof");
+
+    let valid = crate::verify_inclusion(&request.header_hash, &request.proof, &request.tx_hash);
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(VerifyProofResponse { valid }),
+        message: if valid { "Proof is valid" } else { "Proof is invalid" }.to_string(),
+    }))
+}
+
+/// Load a wallet's signing key from the wallet storage
+fn wallet_keypair(state: &AppState, address: &str, password: &str) -> std::result::Result<crate::KeyPair, ApiError> {
+    let private_key = state.wallet_manager.lock().unwrap()
+        .get_private_key_bytes(address, password)?;
+    Ok(crate::KeyPair::from_private_key_bytes(&private_key)?)
+}
+
+/// Add a new transaction signed with the sender's wallet
+async fn add_transaction(
+    State(state): State<AppState>,
+    Json(request): Json<TransactionRequest>,
+) -> std::result::Result<Json<ApiResponse<Transaction>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "add_transaction");
+    let start = std::time::Instant::now();
+    
+    let keypair = wallet_keypair(&state, &request.sender, &request.password)?;
     let mut blockchain = state.blockchain.lock().unwrap();
     
-    // Create transaction
+    blockchain.add_transaction(
+        &keypair,
+        request.receiver,
+        request_units(request.amount)?,
+        request.message,
+    )?;
+    
+    // Save to storage
+    state.storage.save_pending_transactions(&blockchain.pending_transactions)?;
+    
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "add_transaction");
+    
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        message: "Transaction added successfully".to_string(),
+    }))
+}
+
+/// Maximum number of transactions accepted in one batch submission
+pub const MAX_BATCH_TRANSACTIONS: usize = 1000;
+
+/// Add a batch of transactions signed with their senders' wallets
+/// 
+/// Each transaction is validated and added independently, so one invalid entry does
+/// not reject the rest of the batch.
+async fn add_transaction_batch(
+    State(state): State<AppState>,
+    Json(requests): Json<Vec<TransactionRequest>>,
+) -> std::result::Result<Json<ApiResponse<Vec<BatchTransactionResult>>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "add_transaction_batch");
+    let start = std::time::Instant::now();
+    
+    if requests.is_empty() {
+        return Err(ApiError::InvalidRequest("Batch must contain at least one transaction".to_string()));
+    }
+    if requests.len() > MAX_BATCH_TRANSACTIONS {
+        return Err(ApiError::InvalidRequest(format!(
+            "Batch of {} transactions exceeds the limit of {}", requests.len(), MAX_BATCH_TRANSACTIONS
+        )));
+    }
+    
+    let mut blockchain = state.blockchain.lock().unwrap();
+    
+    let results: Vec<BatchTransactionResult> = requests
+        .into_iter()
+        .enumerate()
+        .map(|(index, request)| {
+            let added = wallet_keypair(&state, &request.sender, &request.password)
+                .and_then(|keypair| Ok((keypair, request_units(request.amount)?)))
+                .map_err(|e| e.to_string())
+                .and_then(|(keypair, amount)| {
+                    blockchain.add_transaction(&keypair, request.receiver, amount, request.message)
+                        .map_err(|e| e.to_string())
+                });
+            match added {
+                Ok(()) => BatchTransactionResult {
+                    index,
+                    accepted: true,
+                    transaction_id: blockchain.pending_transactions.last().map(|tx| tx.id.clone()),
+                    reason: None,
+                },
+                Err(reason) => BatchTransactionResult {
+                    index,
+                    accepted: false,
+                    transaction_id: None,
+                    reason: Some(reason),
+                },
+            }
+        })
+        .collect();
+    
+    // Save to storage
+    state.storage.save_pending_transactions(&blockchain.pending_transactions)?;
+    
+    let accepted = results.iter().filter(|result| result.accepted).count();
+    let message = format!("{} of {} transactions accepted", accepted, results.len());
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "add_transaction_batch");
+    
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(results),
+        message,
+    }))
+}
+
+/// Add a signed transaction
+async fn add_signed_transaction(
+    State(state): State<AppState>,
+    Json(request): Json<SignedTransactionRequest>,
+) -> std::result::Result<Json<ApiResponse<Transaction>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "add_signed_transaction");
+    let start = std::time::Instant::now();
+    
+    let mut blockchain = state.blockchain.lock().unwrap();
+    
+    // Rebuild the transaction exactly as the client signed it
     let mut transaction = Transaction::new_transfer(
         request.sender,
         request.receiver,
-        request.amount,
+        request_units(request.amount)?,
         request.message,
-    )?;
+    )?.with_timestamp(request.timestamp);
+    transaction.set_nonce(request.nonce.unwrap_or_else(|| blockchain.next_nonce(&transaction.sender)));
     
     // Verify signature
     let public_key_bytes = crate::utils::hex_to_bytes(&request.public_key)?;
@@ -432,17 +1349,14 @@ async fn add_signed_transaction(
     let public_key = crate::PublicKey::from_bytes(public_key_bytes.clone())?;
     let signature = crate::DigitalSignature::new(signature_bytes, public_key_bytes);
     
-    let _ = transaction.set_signature(signature, public_key);
-    
-    if !transaction.verify_signature()? {
-        return Err(ApiError::InvalidRequest("Invalid signature".to_string()));
-    }
+    transaction.set_signature(signature, public_key)
+        .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
     
     // Add to blockchain
     blockchain.add_transaction_object(transaction.clone())?;
     
     // Save to storage
-    state.storage.save_blockchain(&blockchain)?;
+    state.storage.save_pending_transactions(&blockchain.pending_transactions)?;
     
     histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "add_signed_transaction");
     
@@ -453,6 +1367,29 @@ async fn add_signed_transaction(
     }))
 }
 
+/// Preview the balances a transaction would produce without submitting it
+async fn simulate_transaction(
+    State(state): State<AppState>,
+    Json(transaction): Json<Transaction>,
+) -> std::result::Result<Json<ApiResponse<HashMap<String, f64>>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "simulate_transaction");
+    let start = std::time::Instant::now();
+    
+    let blockchain = state.blockchain.lock().unwrap();
+    let balances = blockchain.simulate_transaction(&transaction)?
+        .into_iter()
+        .map(|(address, balance)| (address, units::from_base_units(balance)))
+        .collect();
+    
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "simulate_transaction");
+    
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(balances),
+        message: "Transaction simulated successfully".to_string(),
+    }))
+}
+
 /// Get balance for an address
 async fn get_balance(
     State(state): State<AppState>,
@@ -497,7 +1434,8 @@ async fn mine_block(
     let mining_time = mining_start.elapsed();
     
     // Save to storage
-    state.storage.save_blockchain(&blockchain)?;
+    state.storage.append_block(&block, &blockchain)?;
+    state.storage.save_pending_transactions(&blockchain.pending_transactions)?;
     
     let response = MiningResponse {
         block,
@@ -567,7 +1505,7 @@ async fn create_wallet(
     let start = std::time::Instant::now();
     
     let mut wallet_manager = state.wallet_manager.lock().unwrap();
-    let wallet_info = wallet_manager.create_wallet(&request.password, request.name)?;
+    let wallet_info = wallet_manager.create_wallet(&request.password, request.name, None)?;
     
     histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "create_wallet");
     
@@ -639,12 +1577,13 @@ async fn send_transaction(
     let mut transaction = Transaction::new_transfer(
         request.from_address.clone(),
         request.to_address,
-        request.amount,
+        request_units(request.amount)?,
         request.message,
     )?;
+    transaction.set_nonce(blockchain.next_nonce(&request.from_address));
     
     // Sign transaction
-    let transaction_data = transaction.to_bytes()?;
+    let transaction_data = transaction.canonical_bytes()?;
     let signature = wallet_manager.sign_transaction(&request.from_address, &request.password, &transaction_data)?;
     
     // Set signature
@@ -652,13 +1591,13 @@ async fn send_transaction(
     let public_key_bytes = crate::utils::hex_to_bytes(&public_key)?;
     let public_key_obj = crate::PublicKey::from_bytes(public_key_bytes)?;
     
-    let _ = transaction.set_signature(signature, public_key_obj);
+    transaction.set_signature(signature, public_key_obj)?;
     
     // Add to blockchain
     blockchain.add_transaction_object(transaction.clone())?;
     
     // Save to storage
-    state.storage.save_blockchain(&blockchain)?;
+    state.storage.save_pending_transactions(&blockchain.pending_transactions)?;
     
     histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "send_transaction");
     
@@ -685,6 +1624,8 @@ async fn get_metrics(
         pending_transactions: blockchain.pending_transactions.len(),
         current_difficulty: blockchain.difficulty,
         mining_reward: blockchain.mining_reward,
+        target_block_time: blockchain.target_block_time_secs,
+        average_block_time: blockchain.average_block_time(),
         blockchain_size_bytes: blockchain.blocks.iter().map(|b| serde_json::to_string(b).unwrap_or_default().len()).sum::<usize>(),
         uptime_seconds: uptime.as_secs(),
         api_requests_total: 0, // TODO: Implement request counting
@@ -700,20 +1641,121 @@ async fn get_metrics(
     }))
 }
 
+/// Handle of the global Prometheus recorder
+static PROMETHEUS_HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Install the Prometheus recorder as the global `metrics` recorder
+/// 
+/// Only the first call installs a recorder; later calls return the same handle.
+/// 
+/// # Returns
+/// * `PrometheusHandle` - Handle for rendering the recorded metrics
+pub fn install_prometheus_recorder() -> PrometheusHandle {
+    PROMETHEUS_HANDLE.get_or_init(|| {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        if let Err(e) = metrics::set_boxed_recorder(Box::new(recorder)) {
+            warn!("Prometheus recorder not installed: {}", e);
+        }
+
+        metrics::describe_counter!("api_requests_total", "Total number of API requests");
+        metrics::describe_counter!("api_auth_failures_total", "Requests rejected for a missing or invalid API key");
+        metrics::describe_counter!("api_rate_limited_total", "Requests rejected by the rate limiter");
+        metrics::describe_histogram!("api_request_duration_ms", "API request duration in milliseconds");
+        metrics::describe_histogram!("mining_duration_ms", "Block mining duration in milliseconds");
+        crate::monitor::init_metrics();
+        handle
+    }).clone()
+}
+
+/// Get metrics in Prometheus text exposition format
+async fn get_prometheus_metrics() -> impl IntoResponse {
+    counter!("api_requests_total", 1, "endpoint" => "get_prometheus_metrics");
+    let handle = install_prometheus_recorder();
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], handle.render())
+}
+
+/// Age of the last block after which the chain is considered stale
+pub const MAX_LAST_BLOCK_AGE_SECS: i64 = 60 * 60;
+
+/// Pending pool size above which the node reports itself degraded
+pub const MAX_HEALTHY_PENDING_TRANSACTIONS: usize = 10_000;
+
+/// Run the subsystem health checks
+/// 
+/// # Arguments
+/// * `state` - Application state
+/// 
+/// # Returns
+/// * `HealthStatus` - Result of every check
+fn check_health(state: &AppState) -> HealthStatus {
+    let mut checks = Vec::new();
+    let mut check = |name: &str, healthy: bool, critical: bool, detail: String| {
+        checks.push(SubsystemCheck { name: name.to_string(), healthy, critical, detail });
+    };
+
+    match state.storage.ping() {
+        Ok(()) => check("storage", true, true, "reachable".to_string()),
+        Err(e) => check("storage", false, true, format!("unreachable: {}", e)),
+    }
+
+    {
+        let blockchain = state.blockchain.lock().unwrap();
+        match blockchain.blocks.last() {
+            Some(block) => {
+                let age = chrono::Utc::now().timestamp() - block.timestamp;
+                check(
+                    "blockchain",
+                    age <= MAX_LAST_BLOCK_AGE_SECS,
+                    true,
+                    format!("last block {} is {}s old (limit {}s)", block.index, age, MAX_LAST_BLOCK_AGE_SECS),
+                );
+            }
+            None => check("blockchain", false, true, "chain has no blocks".to_string()),
+        }
+
+        let pending = blockchain.pending_transactions.len();
+        check(
+            "pending_pool",
+            pending <= MAX_HEALTHY_PENDING_TRANSACTIONS,
+            false,
+            format!("{} pending transactions (limit {})", pending, MAX_HEALTHY_PENDING_TRANSACTIONS),
+        );
+    }
+
+    let optional = [
+        ("ethereum_bridge", state.ethereum_bridge.is_some()),
+        ("did", state.did_system.is_some()),
+        ("governance", state.governance.is_some()),
+    ];
+    for (name, configured) in optional {
+        check(name, true, false, if configured { "configured" } else { "not configured" }.to_string());
+    }
+
+    HealthStatus::from_checks(checks)
+}
+
 /// Health check endpoint
+/// 
+/// Responds with 503 Service Unavailable when a critical check fails.
 async fn health_check(
     State(state): State<AppState>,
-) -> std::result::Result<Json<ApiResponse<()>>, ApiError> {
+) -> std::result::Result<(StatusCode, Json<ApiResponse<HealthStatus>>), ApiError> {
     counter!("api_requests_total", 1, "endpoint" => "health_check");
     
-    // Check if blockchain is accessible
-    let _blockchain = state.blockchain.lock().unwrap();
+    let health = check_health(&state);
+    let (status, message) = if health.is_healthy() {
+        (StatusCode::OK, format!("API is {}", health.status))
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "API is unhealthy".to_string())
+    };
     
-    Ok(Json(ApiResponse {
-        success: true,
-        data: None,
-        message: "API is healthy".to_string(),
-    }))
+    Ok((status, Json(ApiResponse {
+        success: health.is_healthy(),
+        data: Some(health),
+        message,
+    })))
 }
 
 /// Get pending transactions
@@ -735,658 +1777,1183 @@ async fn get_pending_transactions(
     }))
 }
 
-// Ethereum Integration Handlers
-
-/// Transfer tokens to Ethereum
-async fn ethereum_transfer(
+/// Inspect the mempool, optionally filtered by sender and minimum fee
+async fn get_mempool(
     State(state): State<AppState>,
-    Json(request): Json<EthereumTransferRequest>,
-) -> std::result::Result<Json<ApiResponse<crate::ethereum::PendingTransfer>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "ethereum_transfer");
+    Query(query): Query<MempoolQuery>,
+) -> std::result::Result<Json<ApiResponse<MempoolResponse>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_mempool");
     let start = std::time::Instant::now();
 
-    let ethereum_bridge = state.ethereum_bridge
-        .as_ref()
-        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
-
-    // Load wallet first
-    {
-        let mut wallet_manager = state.wallet_manager.lock().unwrap();
-        wallet_manager.load_wallet(&request.from_address, &request.password)?;
-    }
-    
-    // Get bridge clone for async operations
-    let bridge_clone = {
-        let bridge_guard = ethereum_bridge.lock().unwrap();
-        bridge_guard.clone_for_background()
-    };
-    
-    // Get wallet manager clone for async operations
-    let wallet_clone = {
-        let wallet_guard = state.wallet_manager.lock().unwrap();
-        wallet_guard.clone_for_background()
+    let blockchain = state.blockchain.lock().unwrap();
+    let matching: Vec<&crate::Transaction> = blockchain.pending_transactions.iter()
+        .filter(|tx| query.sender.as_ref().is_none_or(|sender| &tx.sender == sender))
+        .filter(|tx| query.min_fee.is_none_or(|min_fee| tx.fee() >= min_fee))
+        .collect();
+
+    let summary = MempoolSummary {
+        count: matching.len(),
+        total_fees: matching.iter().map(|tx| tx.fee()).sum(),
+        total_bytes: matching.iter().map(|tx| tx.size()).sum(),
     };
-    
-    // Initiate transfer
-    let transfer_id = bridge_clone.transfer_to_ethereum(
-        &wallet_clone,
-        &request.from_address,
-        &request.to_ethereum_address,
-        request.amount,
-        &request.password,
-    ).await?;
-
-    // Get transfer details
-    let transfers = bridge_clone.get_pending_transfers().await?;
-    
-    let transfer = transfers.into_iter()
-        .find(|t| t.id == transfer_id)
-        .ok_or_else(|| ApiError::Internal("Transfer not found".to_string()))?;
+    let transactions = matching.into_iter()
+        .take(query.limit.unwrap_or(usize::MAX))
+        .cloned()
+        .collect();
 
-    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "ethereum_transfer");
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "get_mempool");
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(transfer),
-        message: "Ethereum transfer initiated successfully".to_string(),
+        data: Some(MempoolResponse { transactions, summary }),
+        message: "Mempool retrieved successfully".to_string(),
     }))
 }
 
-/// Get Ethereum balance
-async fn get_ethereum_balance(
+// Contract Handlers
+
+/// Get a deployed contract's metadata
+async fn get_contract(
     State(state): State<AppState>,
     Path(address): Path<String>,
-) -> std::result::Result<Json<ApiResponse<f64>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_ethereum_balance");
-
-    let ethereum_bridge = state.ethereum_bridge
-        .as_ref()
-        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
+) -> std::result::Result<Json<ApiResponse<ContractInfoResponse>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_contract");
+    let start = std::time::Instant::now();
 
-    let bridge_clone = {
-        let bridge = ethereum_bridge.lock().unwrap();
-        bridge.clone_for_background()
+    let blockchain = state.blockchain.lock().unwrap();
+    let contract = blockchain.get_contract(&address)
+        .ok_or_else(|| ApiError::NotFound(format!("Contract {} not found", address)))?;
+
+    let response = ContractInfoResponse {
+        address: contract.id.clone(),
+        owner: contract.owner.clone(),
+        balance: units::from_base_units(contract.balance),
+        active: contract.active,
+        code_size: contract.code.len(),
+        created_at: contract.created_at,
     };
-    
-    let balance = bridge_clone.get_ethereum_balance(&address).await?;
+
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "get_contract");
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(balance),
-        message: "Ethereum balance retrieved successfully".to_string(),
+        data: Some(response),
+        message: "Contract retrieved successfully".to_string(),
     }))
 }
 
-/// Get Ethereum transfer status
-async fn get_ethereum_transfer_status(
+/// Deploy a smart contract from hex or base64 encoded code
+async fn deploy_contract(
     State(state): State<AppState>,
-    Path(transfer_id): Path<String>,
-) -> std::result::Result<Json<ApiResponse<Option<crate::ethereum::TransferStatus>>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_ethereum_transfer_status");
-
-    let ethereum_bridge = state.ethereum_bridge
-        .as_ref()
-        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
+    Json(request): Json<ContractDeployRequest>,
+) -> std::result::Result<Json<ApiResponse<ContractDeployResponse>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "deploy_contract");
+    let start = std::time::Instant::now();
 
-    let bridge_clone = {
-        let bridge = ethereum_bridge.lock().unwrap();
-        bridge.clone_for_background()
+    let code_bytes = match request.encoding.as_deref().unwrap_or("hex") {
+        "hex" => hex::decode(request.code.trim_start_matches("0x"))
+            .map_err(|e| ApiError::InvalidRequest(format!("Invalid hex contract code: {}", e)))?,
+        "base64" => {
+            use base64::Engine;
+            base64::engine::general_purpose::STANDARD.decode(&request.code)
+                .map_err(|e| ApiError::InvalidRequest(format!("Invalid base64 contract code: {}", e)))?
+        }
+        other => return Err(ApiError::InvalidRequest(format!(
+            "Unsupported code encoding '{}', expected 'hex' or 'base64'",
+            other
+        ))),
     };
-    
-    let status = bridge_clone.get_transfer_status(&transfer_id).await?;
 
-    Ok(Json(ApiResponse {
-        success: true,
-        data: Some(status),
-        message: "Transfer status retrieved successfully".to_string(),
-    }))
-}
+    if code_bytes.len() > crate::MAX_CONTRACT_SIZE {
+        return Err(ApiError::InvalidRequest(format!(
+            "Contract code is {} bytes, exceeding the maximum of {} bytes",
+            code_bytes.len(),
+            crate::MAX_CONTRACT_SIZE
+        )));
+    }
 
-/// Get pending Ethereum transfers
-async fn get_pending_ethereum_transfers(
-    State(state): State<AppState>,
-) -> std::result::Result<Json<ApiResponse<Vec<crate::ethereum::PendingTransfer>>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_pending_ethereum_transfers");
+    let code = String::from_utf8(code_bytes)
+        .map_err(|_| ApiError::InvalidRequest("Contract code must be valid UTF-8".to_string()))?;
 
-    let ethereum_bridge = state.ethereum_bridge
-        .as_ref()
-        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
+    let mut blockchain = state.blockchain.lock().unwrap();
+    let (address, gas_used) = blockchain.deploy_contract_with_gas(
+        request.sender,
+        code,
+        request.gas_limit,
+        request.gas_price,
+    )?;
 
-    let bridge_clone = {
-        let bridge = ethereum_bridge.lock().unwrap();
-        bridge.clone_for_background()
-    };
-    
-    let transfers = bridge_clone.get_pending_transfers().await?;
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "deploy_contract");
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(transfers),
-        message: "Pending transfers retrieved successfully".to_string(),
+        data: Some(ContractDeployResponse { address, gas_used }),
+        message: "Contract deployed successfully".to_string(),
     }))
 }
 
-/// Get Ethereum bridge statistics
-async fn get_ethereum_bridge_stats(
+/// Get a deployed contract's key/value storage
+async fn get_contract_storage(
     State(state): State<AppState>,
-) -> std::result::Result<Json<ApiResponse<crate::ethereum::BridgeStats>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_ethereum_bridge_stats");
+    Path(address): Path<String>,
+) -> std::result::Result<Json<ApiResponse<ContractStorageResponse>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_contract_storage");
+    let start = std::time::Instant::now();
 
-    let ethereum_bridge = state.ethereum_bridge
-        .as_ref()
-        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
+    let blockchain = state.blockchain.lock().unwrap();
+    let contract = blockchain.get_contract(&address)
+        .ok_or_else(|| ApiError::NotFound(format!("Contract {} not found", address)))?;
 
-    let bridge_clone = {
-        let bridge = ethereum_bridge.lock().unwrap();
-        bridge.clone_for_background()
+    let response = ContractStorageResponse {
+        address: contract.id.clone(),
+        storage: contract.storage.clone(),
     };
-    
-    let stats = bridge_clone.get_bridge_stats().await?;
+
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "get_contract_storage");
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(stats),
-        message: "Bridge statistics retrieved successfully".to_string(),
+        data: Some(response),
+        message: "Contract storage retrieved successfully".to_string(),
     }))
 }
 
-/// Get Ethereum bridge status
-async fn get_ethereum_status(
+/// Withdraw funds from a contract to its owner
+/// 
+/// Submits a signed withdrawal transaction; the contract balance is debited and
+/// the owner credited once it is mined.
+async fn withdraw_from_contract(
     State(state): State<AppState>,
-) -> std::result::Result<Json<ApiResponse<crate::ethereum::BridgeStatus>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_ethereum_status");
+    Path(address): Path<String>,
+    Json(request): Json<ContractWithdrawRequest>,
+) -> std::result::Result<Json<ApiResponse<Transaction>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "withdraw_from_contract");
+    let start = std::time::Instant::now();
 
-    let ethereum_bridge = state.ethereum_bridge
-        .as_ref()
-        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
+    let amount = request_units(request.amount)?;
+    let mut blockchain = state.blockchain.lock().unwrap();
+    let contract = blockchain.get_contract(&address)
+        .ok_or_else(|| ApiError::NotFound(format!("Contract {} not found", address)))?;
+    if contract.owner != request.caller {
+        return Err(ApiError::Forbidden(format!(
+            "Only the owner of contract {} can withdraw its funds", address
+        )));
+    }
+    if amount > contract.balance {
+        return Err(ApiError::InvalidRequest(format!(
+            "Contract {} holds {} but {} was requested",
+            address, units::format_units(contract.balance), units::format_units(amount)
+        )));
+    }
 
-    let bridge_clone = {
-        let bridge = ethereum_bridge.lock().unwrap();
-        bridge.clone_for_background()
-    };
-    
-    let status = bridge_clone.get_bridge_status().await?;
+    let mut transaction = Transaction::new_contract_withdraw(request.caller.clone(), address, amount)?;
+    transaction.set_nonce(blockchain.next_nonce(&request.caller));
+
+    // Sign the withdrawal with the owner's wallet
+    let private_key = state.wallet_manager.lock().unwrap()
+        .get_private_key_bytes(&request.caller, &request.password)?;
+    let keypair = crate::KeyPair::from_private_key_bytes(&private_key)?;
+    let signature = keypair.sign(&transaction.canonical_bytes()?)?;
+    transaction.set_signature(signature, keypair.public_key())?;
+
+    blockchain.add_transaction_object(transaction.clone())?;
+    state.storage.save_pending_transactions(&blockchain.pending_transactions)?;
+
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "withdraw_from_contract");
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(status),
-        message: "Bridge status retrieved successfully".to_string(),
+        data: Some(transaction),
+        message: "Contract withdrawal submitted successfully".to_string(),
     }))
 }
 
-/// Get Ethereum bridge configuration
-async fn get_ethereum_config(
+// Validator Handlers
+
+/// Reject validator management on chains that do not use PoS
+fn require_pos(blockchain: &Blockchain) -> std::result::Result<&crate::consensus::ProofOfStake, ApiError> {
+    blockchain.proof_of_stake().ok_or_else(|| ApiError::InvalidRequest(format!(
+        "Validator management requires Proof of Stake, but this node uses {:?}",
+        blockchain.get_consensus_type(),
+    )))
+}
+
+/// Sign a staking transaction with the validator's wallet and add it to the pending pool
+fn submit_staking_transaction(
+    state: &AppState,
+    blockchain: &mut Blockchain,
+    mut transaction: Transaction,
+    password: &str,
+) -> std::result::Result<Transaction, ApiError> {
+    transaction.set_nonce(blockchain.next_nonce(&transaction.sender));
+
+    let private_key = state.wallet_manager.lock().unwrap()
+        .get_private_key_bytes(&transaction.sender, password)?;
+    let keypair = crate::KeyPair::from_private_key_bytes(&private_key)?;
+    let signature = keypair.sign(&transaction.canonical_bytes()?)?;
+    transaction.set_signature(signature, keypair.public_key())?;
+
+    blockchain.add_transaction_object(transaction.clone())?;
+    state.storage.save_pending_transactions(&blockchain.pending_transactions)?;
+    Ok(transaction)
+}
+
+/// Register a wallet as a PoS validator
+/// 
+/// Submits a signed registration; the stake is debited from the wallet's balance
+/// and the validator registered once it is mined.
+async fn register_validator(
     State(state): State<AppState>,
-) -> std::result::Result<Json<ApiResponse<crate::ethereum::EthereumConfig>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_ethereum_config");
+    Json(request): Json<RegisterValidatorRequest>,
+) -> std::result::Result<Json<ApiResponse<Transaction>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "register_validator");
+    let start = std::time::Instant::now();
 
-    let ethereum_bridge = state.ethereum_bridge
-        .as_ref()
-        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
+    let stake = request_units(request.stake)?;
+    let mut blockchain = state.blockchain.lock().unwrap();
+    if require_pos(&blockchain)?.validators.contains_key(&request.address) {
+        return Err(ApiError::InvalidRequest(format!(
+            "Validator {} is already registered", request.address
+        )));
+    }
+    let transaction = Transaction::new_validator_registration(request.address, stake)?;
+    let transaction = submit_staking_transaction(&state, &mut blockchain, transaction, &request.password)?;
 
-    let bridge_clone = {
-        let bridge = ethereum_bridge.lock().unwrap();
-        bridge.clone_for_background()
-    };
-    
-    let config = bridge_clone.get_config().await?;
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "register_validator");
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(config),
-        message: "Bridge configuration retrieved successfully".to_string(),
+        data: Some(transaction),
+        message: "Validator registration submitted successfully".to_string(),
     }))
 }
 
-// DID Handlers
-
-/// Create a new DID
-async fn create_did(
+/// Add stake to a PoS validator
+/// 
+/// Submits a signed staking transaction; the amount is debited from the
+/// validator's balance and added to its stake once it is mined.
+async fn stake_validator(
     State(state): State<AppState>,
-    Json(request): Json<DIDCreationRequest>,
-) -> std::result::Result<Json<ApiResponse<(String, String)>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "create_did");
+    Json(request): Json<ValidatorStakeRequest>,
+) -> std::result::Result<Json<ApiResponse<Transaction>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "stake_validator");
+    let start = std::time::Instant::now();
 
-    let did_system = state.did_system
-        .as_ref()
-        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
+    let amount = request_units(request.amount)?;
+    let mut blockchain = state.blockchain.lock().unwrap();
+    // Staking must not implicitly create a validator
+    validator_info(require_pos(&blockchain)?, &request.address)?;
+    let transaction = Transaction::new_staking(request.address, amount, true)?;
+    let transaction = submit_staking_transaction(&state, &mut blockchain, transaction, &request.password)?;
 
-    let system_clone = {
-        let system = did_system.lock().unwrap();
-        system.clone_for_background()
-    };
-    let did_request = crate::did::DIDCreationRequest {
-        controller: request.controller,
-        service_endpoints: request.service_endpoints,
-    };
-    let (did, _keypair) = system_clone.create_did(did_request).await?;
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "stake_validator");
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some((did, "Keypair generated successfully".to_string())),
-        message: "DID created successfully".to_string(),
+        data: Some(transaction),
+        message: "Stake submitted successfully".to_string(),
     }))
 }
 
-/// Get DID document
-async fn get_did_document(
+/// Remove stake from a PoS validator
+/// 
+/// Submits a signed unstaking transaction; once it is mined the amount leaves the
+/// validator's stake and stays locked for the unbonding period.
+async fn unstake_validator(
     State(state): State<AppState>,
-    Path(did): Path<String>,
-) -> std::result::Result<Json<ApiResponse<Option<crate::did::DIDDocument>>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_did_document");
+    Json(request): Json<ValidatorStakeRequest>,
+) -> std::result::Result<Json<ApiResponse<Transaction>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "unstake_validator");
+    let start = std::time::Instant::now();
 
-    let did_system = state.did_system
-        .as_ref()
-        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
+    let amount = request_units(request.amount)?;
+    let mut blockchain = state.blockchain.lock().unwrap();
+    let staked = require_pos(&blockchain)?.validators.get(&request.address)
+        .map(|validator| validator.stake_amount)
+        .ok_or_else(|| ApiError::NotFound(format!("Validator {} not found", request.address)))?;
+    if amount > staked {
+        return Err(ApiError::InvalidRequest(format!(
+            "Validator {} has {} staked but {} was requested",
+            request.address, units::format_units(staked), units::format_units(amount)
+        )));
+    }
+    let transaction = Transaction::new_staking(request.address, amount, false)?;
+    let transaction = submit_staking_transaction(&state, &mut blockchain, transaction, &request.password)?;
 
-    let system_clone = {
-        let system = did_system.lock().unwrap();
-        system.clone_for_background()
-    };
-    
-    let document = system_clone.get_did_document(&did).await?;
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "unstake_validator");
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(document),
-        message: "DID document retrieved successfully".to_string(),
+        data: Some(transaction),
+        message: "Unstake submitted successfully".to_string(),
     }))
 }
 
-/// Link DID to wallet
-async fn link_did_to_wallet(
+/// Withdraw a validator's unbonded stake
+/// 
+/// Submits a signed withdrawal of everything whose unbonding period has passed;
+/// the validator's balance is credited once it is mined.
+async fn withdraw_unbonded(
     State(state): State<AppState>,
-    Path(did): Path<String>,
-    Json(request): Json<DIDLinkRequest>,
-) -> std::result::Result<Json<ApiResponse<()>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "link_did_to_wallet");
+    Json(request): Json<ValidatorWithdrawRequest>,
+) -> std::result::Result<Json<ApiResponse<Transaction>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "withdraw_unbonded");
+    let start = std::time::Instant::now();
 
-    let did_system = state.did_system
-        .as_ref()
-        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
+    let mut blockchain = state.blockchain.lock().unwrap();
+    // The withdrawal is mined at the next height at the earliest
+    let height = blockchain.blocks.len() as u64;
+    let pos = require_pos(&blockchain)?;
+    let amount = pos.get_withdrawable(&request.address, height);
+    if amount == 0 {
+        return Err(ApiError::InvalidRequest(format!(
+            "Validator {} has nothing withdrawable at height {} ({} still unbonding)",
+            request.address, height, units::format_units(pos.get_unbonding(&request.address))
+        )));
+    }
+    let transaction = Transaction::new_unbonded_withdrawal(request.address, amount)?;
+    let transaction = submit_staking_transaction(&state, &mut blockchain, transaction, &request.password)?;
 
-    let system_clone = {
-        let system = did_system.lock().unwrap();
-        system.clone_for_background()
-    };
-    
-    system_clone.link_did_to_wallet(&did, &request.wallet_address).await?;
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "withdraw_unbonded");
 
     Ok(Json(ApiResponse {
         success: true,
-        data: None,
-        message: "DID linked to wallet successfully".to_string(),
+        data: Some(transaction),
+        message: "Withdrawal submitted successfully".to_string(),
     }))
 }
 
-/// Get DID for wallet
-async fn get_did_for_wallet(
+/// List all PoS validators ordered by address
+async fn list_validators(
     State(state): State<AppState>,
-    Path(address): Path<String>,
-) -> std::result::Result<Json<ApiResponse<Option<String>>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_did_for_wallet");
-
-    let did_system = state.did_system
-        .as_ref()
-        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
+) -> std::result::Result<Json<ApiResponse<Vec<ValidatorInfo>>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "list_validators");
 
-    let system_clone = {
-        let system = did_system.lock().unwrap();
-        system.clone_for_background()
-    };
-    
-    let did = system_clone.get_did_for_wallet(&address).await?;
+    let blockchain = state.blockchain.lock().unwrap();
+    let pos = require_pos(&blockchain)?;
+    let mut addresses: Vec<&String> = pos.validators.keys().collect();
+    addresses.sort();
+    let validators = addresses.into_iter()
+        .map(|address| validator_info(pos, address))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(did),
-        message: "DID for wallet retrieved successfully".to_string(),
+        data: Some(validators),
+        message: "Validators retrieved successfully".to_string(),
     }))
 }
 
-/// Verify DID signature
-async fn verify_did_signature(
+fn validator_info(pos: &crate::consensus::ProofOfStake, address: &str) -> std::result::Result<ValidatorInfo, ApiError> {
+    let validator = pos.validators.get(address)
+        .ok_or_else(|| ApiError::NotFound(format!("Validator {} not found", address)))?;
+    Ok(ValidatorInfo {
+        address: validator.address.clone(),
+        stake: units::from_base_units(validator.stake_amount),
+        rewards: units::from_base_units(pos.get_rewards(address)),
+        active: validator.active,
+    })
+}
+
+// Ethereum Integration Handlers
+
+/// Transfer tokens to Ethereum
+async fn ethereum_transfer(
     State(state): State<AppState>,
-    Path(did): Path<String>,
-    Json(request): Json<DIDVerificationRequest>,
-) -> std::result::Result<Json<ApiResponse<crate::did::DIDVerificationResult>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "verify_did_signature");
+    Json(request): Json<EthereumTransferRequest>,
+) -> std::result::Result<Json<ApiResponse<crate::ethereum::PendingTransfer>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "ethereum_transfer");
+    let start = std::time::Instant::now();
 
-    let did_system = state.did_system
+    let ethereum_bridge = state.ethereum_bridge
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
+        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
 
-    let system_clone = {
-        let system = did_system.lock().unwrap();
-        system.clone_for_background()
+    // Load wallet first, creating its Ethereum key here so the clone below reuses it
+    {
+        let mut wallet_manager = state.wallet_manager.lock().unwrap();
+        wallet_manager.load_wallet(&request.from_address, &request.password)?;
+        wallet_manager.get_ethereum_private_key(&request.from_address, &request.password)?;
+    }
+    
+    // Get bridge clone for async operations
+    let bridge_clone = {
+        let bridge_guard = ethereum_bridge.lock().unwrap();
+        bridge_guard.clone_for_background()
     };
     
-    let result = system_clone.verify_did_signature(
-        &did,
-        request.message.as_bytes(),
-        request.signature.as_bytes(),
+    // Get wallet manager clone for async operations
+    let mut wallet_clone = {
+        let wallet_guard = state.wallet_manager.lock().unwrap();
+        wallet_guard.clone_for_background()
+    };
+    
+    // Submit the signed transaction locking the GIL
+    let transfer_id = bridge_clone.transfer_to_ethereum(
+        &mut wallet_clone,
+        &state.blockchain,
+        &request.from_address,
+        &request.to_ethereum_address,
+        request.amount,
+        &request.password,
     ).await?;
 
+    // Save the lock transaction to storage
+    {
+        let blockchain = state.blockchain.lock().unwrap();
+        state.storage.save_pending_transactions(&blockchain.pending_transactions)?;
+    }
+
+    // Get transfer details
+    let transfers = bridge_clone.get_pending_transfers().await?;
+    
+    let transfer = transfers.into_iter()
+        .find(|t| t.id == transfer_id)
+        .ok_or_else(|| ApiError::Internal("Transfer not found".to_string()))?;
+
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "ethereum_transfer");
+
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(result),
-        message: "DID signature verification completed".to_string(),
+        data: Some(transfer),
+        message: "Ethereum transfer initiated successfully".to_string(),
     }))
 }
 
-/// Get all DIDs
-async fn get_all_dids(
+/// Get Ethereum balance
+async fn get_ethereum_balance(
     State(state): State<AppState>,
-) -> std::result::Result<Json<ApiResponse<Vec<String>>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_all_dids");
+    Path(address): Path<String>,
+) -> std::result::Result<Json<ApiResponse<f64>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_ethereum_balance");
 
-    let did_system = state.did_system
+    let ethereum_bridge = state.ethereum_bridge
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
+        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
 
-    let system_clone = {
-        let system = did_system.lock().unwrap();
-        system.clone_for_background()
+    let bridge_clone = {
+        let bridge = ethereum_bridge.lock().unwrap();
+        bridge.clone_for_background()
     };
     
-    let dids = system_clone.get_all_dids().await?;
+    let balance = bridge_clone.get_ethereum_balance(&address).await?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(dids),
-        message: "All DIDs retrieved successfully".to_string(),
+        data: Some(balance),
+        message: "Ethereum balance retrieved successfully".to_string(),
     }))
 }
 
-/// Get DID statistics
-async fn get_did_stats(
+/// Get Ethereum transfer status
+async fn get_ethereum_transfer_status(
     State(state): State<AppState>,
-) -> std::result::Result<Json<ApiResponse<crate::did::DIDStats>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_did_stats");
+    Path(transfer_id): Path<String>,
+) -> std::result::Result<Json<ApiResponse<Option<crate::ethereum::TransferStatus>>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_ethereum_transfer_status");
 
-    let did_system = state.did_system
+    let ethereum_bridge = state.ethereum_bridge
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
+        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
 
-    let system_clone = {
-        let system = did_system.lock().unwrap();
-        system.clone_for_background()
+    let bridge_clone = {
+        let bridge = ethereum_bridge.lock().unwrap();
+        bridge.clone_for_background()
     };
     
-    let stats = system_clone.get_did_stats().await?;
+    let status = bridge_clone.get_transfer_status(&transfer_id).await?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(stats),
-        message: "DID statistics retrieved successfully".to_string(),
+        data: Some(status),
+        message: "Transfer status retrieved successfully".to_string(),
     }))
 }
 
-// Governance Handlers
-
-/// Create governance proposal
-async fn create_governance_proposal(
+/// Send the Ethereum side of a transfer once its GIL lock is mined
+async fn complete_ethereum_transfer(
     State(state): State<AppState>,
-    Json(request): Json<GovernanceProposalRequest>,
-) -> std::result::Result<Json<ApiResponse<String>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "create_governance_proposal");
+    Path(transfer_id): Path<String>,
+    Json(request): Json<EthereumCompletionRequest>,
+) -> std::result::Result<Json<ApiResponse<crate::ethereum::TransferStatus>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "complete_ethereum_transfer");
 
-    let governance = state.governance
+    let ethereum_bridge = state.ethereum_bridge
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
 
-    let proposal_request = crate::governance::ProposalCreationRequest {
-        title: request.title,
-        description: request.description,
-        proposal_type: request.proposal_type,
-        contract_code: request.contract_code,
-        parameters: request.parameters,
-        voting_period: request.voting_period,
-        quorum: request.quorum,
+    let bridge_clone = {
+        let bridge = ethereum_bridge.lock().unwrap();
+        bridge.clone_for_background()
     };
-
-    let gov_clone = {
-        let gov = governance.lock().unwrap();
-        gov.clone_for_background()
+    let mut wallet_clone = {
+        let wallet_guard = state.wallet_manager.lock().unwrap();
+        wallet_guard.clone_for_background()
     };
-    
-    let proposal_id = gov_clone.create_proposal(&request.proposer, proposal_request).await?;
+
+    let status = bridge_clone.complete_transfer(
+        &mut wallet_clone,
+        &state.blockchain,
+        &transfer_id,
+        &request.password,
+    ).await?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(proposal_id),
-        message: "Governance proposal created successfully".to_string(),
+        data: Some(status),
+        message: "Ethereum transfer processed".to_string(),
     }))
 }
 
-/// Vote on governance proposal
-async fn vote_on_proposal(
+/// Get pending Ethereum transfers
+async fn get_pending_ethereum_transfers(
     State(state): State<AppState>,
-    Json(request): Json<GovernanceVoteRequest>,
-) -> std::result::Result<Json<ApiResponse<()>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "vote_on_proposal");
+) -> std::result::Result<Json<ApiResponse<Vec<crate::ethereum::PendingTransfer>>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_pending_ethereum_transfers");
 
-    let governance = state.governance
+    let ethereum_bridge = state.ethereum_bridge
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
-
-    let vote_request = crate::governance::VoteRequest {
-        proposal_id: request.proposal_id,
-        vote: request.vote,
-        stake_amount: request.stake_amount,
-    };
+        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
 
-    let gov_clone = {
-        let gov = governance.lock().unwrap();
-        gov.clone_for_background()
+    let bridge_clone = {
+        let bridge = ethereum_bridge.lock().unwrap();
+        bridge.clone_for_background()
     };
     
-    gov_clone.vote_on_proposal(&request.voter, vote_request).await?;
+    let transfers = bridge_clone.get_pending_transfers().await?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: None,
-        message: "Vote cast successfully".to_string(),
+        data: Some(transfers),
+        message: "Pending transfers retrieved successfully".to_string(),
     }))
 }
 
-/// Execute governance proposal
-async fn execute_proposal(
+/// Get Ethereum bridge statistics
+async fn get_ethereum_bridge_stats(
     State(state): State<AppState>,
-    Path(proposal_id): Path<String>,
-) -> std::result::Result<Json<ApiResponse<()>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "execute_proposal");
+) -> std::result::Result<Json<ApiResponse<crate::ethereum::BridgeStats>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_ethereum_bridge_stats");
 
-    let governance = state.governance
+    let ethereum_bridge = state.ethereum_bridge
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
 
-    let gov_clone = {
-        let gov = governance.lock().unwrap();
-        gov.clone_for_background()
+    let bridge_clone = {
+        let bridge = ethereum_bridge.lock().unwrap();
+        bridge.clone_for_background()
     };
     
-    gov_clone.execute_proposal(&proposal_id).await?;
+    let stats = bridge_clone.get_bridge_stats().await?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: None,
-        message: "Proposal executed successfully".to_string(),
+        data: Some(stats),
+        message: "Bridge statistics retrieved successfully".to_string(),
     }))
 }
 
-/// Get governance proposal
-async fn get_governance_proposal(
+/// Get Ethereum bridge status
+async fn get_ethereum_status(
     State(state): State<AppState>,
-    Path(proposal_id): Path<String>,
-) -> std::result::Result<Json<ApiResponse<Option<crate::governance::GovernanceProposal>>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_governance_proposal");
+) -> std::result::Result<Json<ApiResponse<crate::ethereum::BridgeStatus>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_ethereum_status");
 
-    let governance = state.governance
+    let ethereum_bridge = state.ethereum_bridge
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
 
-    let gov_clone = {
-        let gov = governance.lock().unwrap();
-        gov.clone_for_background()
+    let bridge_clone = {
+        let bridge = ethereum_bridge.lock().unwrap();
+        bridge.clone_for_background()
     };
     
-    let proposal = gov_clone.get_proposal(&proposal_id).await?;
+    let status = bridge_clone.get_bridge_status().await?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(proposal),
-        message: "Governance proposal retrieved successfully".to_string(),
+        data: Some(status),
+        message: "Bridge status retrieved successfully".to_string(),
     }))
 }
 
-/// Get all governance proposals
-async fn get_all_governance_proposals(
+/// Get Ethereum bridge configuration
+async fn get_ethereum_config(
     State(state): State<AppState>,
-) -> std::result::Result<Json<ApiResponse<Vec<crate::governance::GovernanceProposal>>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_all_governance_proposals");
+) -> std::result::Result<Json<ApiResponse<crate::ethereum::EthereumConfig>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_ethereum_config");
 
-    let governance = state.governance
+    let ethereum_bridge = state.ethereum_bridge
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+        .ok_or_else(|| ApiError::Internal("Ethereum bridge not configured".to_string()))?;
 
-    let gov_clone = {
-        let gov = governance.lock().unwrap();
-        gov.clone_for_background()
+    let bridge_clone = {
+        let bridge = ethereum_bridge.lock().unwrap();
+        bridge.clone_for_background()
     };
     
-    let proposals = gov_clone.get_all_proposals().await?;
+    let config = bridge_clone.get_config().await?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(proposals),
-        message: "All governance proposals retrieved successfully".to_string(),
+        data: Some(config),
+        message: "Bridge configuration retrieved successfully".to_string(),
     }))
 }
 
-/// Get proposal votes
-async fn get_proposal_votes(
+// DID Handlers
+
+/// Create a new DID
+async fn create_did(
     State(state): State<AppState>,
-    Path(proposal_id): Path<String>,
-) -> std::result::Result<Json<ApiResponse<Vec<crate::governance::Vote>>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_proposal_votes");
+    Json(request): Json<DIDCreationRequest>,
+) -> std::result::Result<Json<ApiResponse<DIDCreationResponse>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "create_did");
 
-    let governance = state.governance
+    let did_system = state.did_system
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
 
-    let gov_clone = {
-        let gov = governance.lock().unwrap();
-        gov.clone_for_background()
+    let system_clone = {
+        let system = did_system.lock().unwrap();
+        system.clone_for_background()
     };
-    
-    let votes = gov_clone.get_proposal_votes(&proposal_id).await?;
+    let did_request = crate::did::DIDCreationRequest {
+        controller: request.controller,
+        service_endpoints: request.service_endpoints,
+    };
+    let (did, signing_key) = system_clone.create_did(did_request).await?;
+
+    // The private key never leaves the node; it is kept encrypted in the wallet storage
+    let keypair = crate::KeyPair::from_private_key_bytes(&signing_key.to_bytes())?;
+    let public_key = keypair.public_key_hex();
+    state.wallet_manager.lock().unwrap()
+        .store_keypair(keypair, &request.password, Some(did.clone()), None)?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(votes),
-        message: "Proposal votes retrieved successfully".to_string(),
+        data: Some(DIDCreationResponse {
+            did,
+            public_key,
+        }),
+        message: "DID created successfully".to_string(),
     }))
 }
 
-/// Get governance statistics
-async fn get_governance_stats(
+/// Get DID document
+async fn get_did_document(
     State(state): State<AppState>,
-) -> std::result::Result<Json<ApiResponse<crate::governance::GovernanceStats>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_governance_stats");
+    Path(did): Path<String>,
+) -> std::result::Result<Json<ApiResponse<Option<crate::did::DIDDocument>>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_did_document");
 
-    let governance = state.governance
+    let did_system = state.did_system
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
 
-    let gov_clone = {
-        let gov = governance.lock().unwrap();
-        gov.clone_for_background()
+    let system_clone = {
+        let system = did_system.lock().unwrap();
+        system.clone_for_background()
     };
     
-    let stats = gov_clone.get_governance_stats().await?;
+    let document = system_clone.get_did_document(&did).await?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(stats),
-        message: "Governance statistics retrieved successfully".to_string(),
+        data: Some(document),
+        message: "DID document retrieved successfully".to_string(),
     }))
 }
 
-// Simulation Handlers
+/// Load the key currently controlling a DID from the wallet storage
+fn did_signing_key(
+    state: &AppState,
+    did_system: &crate::did::DecentralizedIdentity,
+    document: &crate::did::DIDDocument,
+    password: &str,
+) -> std::result::Result<crate::KeyPair, ApiError> {
+    let address = crate::crypto::create_address(&did_system.authentication_key(document)?);
+    let private_key = state.wallet_manager.lock().unwrap()
+        .get_private_key_bytes(&address, password)?;
+    Ok(crate::KeyPair::from_private_key_bytes(&private_key)?)
+}
 
-/// Run simulation
-async fn run_simulation(
+/// Rotate a DID to a new key, authorized by the current key in the wallet storage
+async fn rotate_did_key(
     State(state): State<AppState>,
-    Json(_request): Json<SimulationRunRequest>,
-) -> std::result::Result<Json<ApiResponse<crate::simulation::SimulationResult>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "run_simulation");
+    Path(did): Path<String>,
+    Json(request): Json<DIDKeyRequest>,
+) -> std::result::Result<Json<ApiResponse<DIDCreationResponse>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "rotate_did_key");
 
-    let simulation_manager = state.simulation_manager
+    let did_system = state.did_system
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("Simulation manager not configured".to_string()))?;
+        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
 
-    let sim_clone = {
-        let sim = simulation_manager.lock().unwrap();
-        sim.clone_for_background()
+    let system_clone = {
+        let system = did_system.lock().unwrap();
+        system.clone_for_background()
     };
-    
-    let result = sim_clone.run_simulation().await?;
+    let document = system_clone.get_did_document(&did).await?
+        .ok_or_else(|| ApiError::NotFound(format!("DID not found: {}", did)))?;
+    let current_key = did_signing_key(&state, &system_clone, &document, &request.password)?;
+
+    // Earlier keys stay in the method history, so the next fragment is always unused
+    let new_key = crate::KeyPair::generate()?;
+    let fragment = format!("keys-{}", document.version + 2);
+    let method = crate::did::DecentralizedIdentity::verification_method(&did, &fragment, &new_key.public_key());
+    let message = crate::did::DecentralizedIdentity::rotation_message(&did, document.version, &method);
+    let signature = current_key.sign(&message)?;
+
+    // Store the new key before the document starts referring to it
+    let public_key = new_key.public_key_hex();
+    state.wallet_manager.lock().unwrap()
+        .store_keypair(new_key, &request.password, Some(did.clone()), None)?;
+    system_clone.rotate_key(&did, method, &signature.signature).await?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(result),
-        message: "Simulation completed successfully".to_string(),
+        data: Some(DIDCreationResponse {
+            did,
+            public_key,
+        }),
+        message: "DID key rotated successfully".to_string(),
     }))
 }
 
-/// Get simulation progress
-async fn get_simulation_progress(
+/// Revoke a DID, authorized by its current key in the wallet storage
+async fn revoke_did(
     State(state): State<AppState>,
-    Path(_simulation_id): Path<String>,
-) -> std::result::Result<Json<ApiResponse<f64>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_simulation_progress");
+    Path(did): Path<String>,
+    Json(request): Json<DIDKeyRequest>,
+) -> std::result::Result<Json<ApiResponse<()>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "revoke_did");
 
-    let simulation_manager = state.simulation_manager
+    let did_system = state.did_system
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("Simulation manager not configured".to_string()))?;
+        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
 
-    let sim_clone = {
-        let sim = simulation_manager.lock().unwrap();
-        sim.clone_for_background()
+    let system_clone = {
+        let system = did_system.lock().unwrap();
+        system.clone_for_background()
     };
-    
-    let progress = sim_clone.get_progress().await;
+    let document = system_clone.get_did_document(&did).await?
+        .ok_or_else(|| ApiError::NotFound(format!("DID not found: {}", did)))?;
+    let current_key = did_signing_key(&state, &system_clone, &document, &request.password)?;
+
+    let message = crate::did::DecentralizedIdentity::revocation_message(&did, document.version);
+    let signature = current_key.sign(&message)?;
+    system_clone.revoke_did(&did, &signature.signature).await?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(progress),
-        message: "Simulation progress retrieved successfully".to_string(),
+        data: None,
+        message: "DID revoked successfully".to_string(),
     }))
 }
 
-/// Get simulation state
-async fn get_simulation_state(
+/// Link DID to wallet
+async fn link_did_to_wallet(
     State(state): State<AppState>,
-    Path(_simulation_id): Path<String>,
-) -> std::result::Result<Json<ApiResponse<crate::simulation::SimulationState>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_simulation_state");
+    Path(did): Path<String>,
+    Json(request): Json<DIDLinkRequest>,
+) -> std::result::Result<Json<ApiResponse<()>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "link_did_to_wallet");
 
-    let simulation_manager = state.simulation_manager
+    let did_system = state.did_system
         .as_ref()
-        .ok_or_else(|| ApiError::Internal("Simulation manager not configured".to_string()))?;
+        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
 
-    let sim_clone = {
-        let sim = simulation_manager.lock().unwrap();
-        sim.clone_for_background()
+    let system_clone = {
+        let system = did_system.lock().unwrap();
+        system.clone_for_background()
     };
     
-    let state = sim_clone.get_current_state().await?;
+    system_clone.link_did_to_wallet(&did, &request.wallet_address).await?;
 
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(state),
-        message: "Simulation state retrieved successfully".to_string(),
+        data: None,
+        message: "DID linked to wallet successfully".to_string(),
+    }))
+}
+
+/// Get DID for wallet
+async fn get_did_for_wallet(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> std::result::Result<Json<ApiResponse<Option<String>>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_did_for_wallet");
+
+    let did_system = state.did_system
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
+
+    let system_clone = {
+        let system = did_system.lock().unwrap();
+        system.clone_for_background()
+    };
+    
+    let did = system_clone.get_did_for_wallet(&address).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(did),
+        message: "DID for wallet retrieved successfully".to_string(),
+    }))
+}
+
+/// Verify DID signature
+async fn verify_did_signature(
+    State(state): State<AppState>,
+    Path(did): Path<String>,
+    Json(request): Json<DIDVerificationRequest>,
+) -> std::result::Result<Json<ApiResponse<crate::did::DIDVerificationResult>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "verify_did_signature");
+
+    let did_system = state.did_system
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
+
+    let system_clone = {
+        let system = did_system.lock().unwrap();
+        system.clone_for_background()
+    };
+    
+    let signature = hex::decode(&request.signature)
+        .map_err(|e| ApiError::InvalidRequest(format!("Invalid signature hex: {}", e)))?;
+    let result = system_clone.verify_did_signature(
+        &did,
+        request.message.as_bytes(),
+        &signature,
+    ).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(result),
+        message: "DID signature verification completed".to_string(),
+    }))
+}
+
+/// Get all DIDs
+async fn get_all_dids(
+    State(state): State<AppState>,
+) -> std::result::Result<Json<ApiResponse<Vec<String>>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_all_dids");
+
+    let did_system = state.did_system
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
+
+    let system_clone = {
+        let system = did_system.lock().unwrap();
+        system.clone_for_background()
+    };
+    
+    let dids = system_clone.get_all_dids().await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(dids),
+        message: "All DIDs retrieved successfully".to_string(),
+    }))
+}
+
+/// Get DID statistics
+async fn get_did_stats(
+    State(state): State<AppState>,
+) -> std::result::Result<Json<ApiResponse<crate::did::DIDStats>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_did_stats");
+
+    let did_system = state.did_system
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("DID system not configured".to_string()))?;
+
+    let system_clone = {
+        let system = did_system.lock().unwrap();
+        system.clone_for_background()
+    };
+    
+    let stats = system_clone.get_did_stats().await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(stats),
+        message: "DID statistics retrieved successfully".to_string(),
+    }))
+}
+
+// Governance Handlers
+
+/// Create governance proposal
+async fn create_governance_proposal(
+    State(state): State<AppState>,
+    Json(request): Json<GovernanceProposalRequest>,
+) -> std::result::Result<Json<ApiResponse<String>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "create_governance_proposal");
+
+    let governance = state.governance
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+
+    let proposal_request = crate::governance::ProposalCreationRequest {
+        title: request.title,
+        description: request.description,
+        proposal_type: request.proposal_type,
+        contract_code: request.contract_code,
+        parameters: request.parameters,
+        voting_period: request.voting_period,
+        quorum: request.quorum,
+    };
+
+    let gov_clone = {
+        let gov = governance.lock().unwrap();
+        gov.clone_for_background()
+    };
+    
+    let proposal_id = gov_clone.create_proposal(&request.proposer, proposal_request).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(proposal_id),
+        message: "Governance proposal created successfully".to_string(),
+    }))
+}
+
+/// Vote on governance proposal
+async fn vote_on_proposal(
+    State(state): State<AppState>,
+    Json(request): Json<GovernanceVoteRequest>,
+) -> std::result::Result<Json<ApiResponse<()>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "vote_on_proposal");
+
+    let governance = state.governance
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+
+    let vote_request = crate::governance::VoteRequest {
+        proposal_id: request.proposal_id,
+        vote: request.vote,
+        stake_amount: request.stake_amount,
+    };
+
+    let gov_clone = {
+        let gov = governance.lock().unwrap();
+        gov.clone_for_background()
+    };
+    
+    gov_clone.vote_on_proposal(&request.voter, vote_request).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        message: "Vote cast successfully".to_string(),
+    }))
+}
+
+/// Execute governance proposal
+async fn execute_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<String>,
+) -> std::result::Result<Json<ApiResponse<()>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "execute_proposal");
+
+    let governance = state.governance
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+
+    let gov_clone = {
+        let gov = governance.lock().unwrap();
+        gov.clone_for_background()
+    };
+    
+    gov_clone.execute_proposal(&proposal_id, &state.blockchain).await?;
+
+    // Save any applied parameter changes to storage
+    {
+        let blockchain = state.blockchain.lock().unwrap();
+        state.storage.save_blockchain(&blockchain)?;
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        message: "Proposal executed successfully".to_string(),
+    }))
+}
+
+/// Get governance proposal
+async fn get_governance_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<String>,
+) -> std::result::Result<Json<ApiResponse<Option<crate::governance::GovernanceProposal>>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_governance_proposal");
+
+    let governance = state.governance
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+
+    let gov_clone = {
+        let gov = governance.lock().unwrap();
+        gov.clone_for_background()
+    };
+    
+    let proposal = gov_clone.get_proposal(&proposal_id).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(proposal),
+        message: "Governance proposal retrieved successfully".to_string(),
+    }))
+}
+
+/// Get all governance proposals
+async fn get_all_governance_proposals(
+    State(state): State<AppState>,
+) -> std::result::Result<Json<ApiResponse<Vec<crate::governance::GovernanceProposal>>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_all_governance_proposals");
+
+    let governance = state.governance
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+
+    let gov_clone = {
+        let gov = governance.lock().unwrap();
+        gov.clone_for_background()
+    };
+    
+    let proposals = gov_clone.get_all_proposals().await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(proposals),
+        message: "All governance proposals retrieved successfully".to_string(),
+    }))
+}
+
+/// Get proposal votes
+async fn get_proposal_votes(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<String>,
+) -> std::result::Result<Json<ApiResponse<Vec<crate::governance::Vote>>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_proposal_votes");
+
+    let governance = state.governance
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+
+    let gov_clone = {
+        let gov = governance.lock().unwrap();
+        gov.clone_for_background()
+    };
+    
+    let votes = gov_clone.get_proposal_votes(&proposal_id).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(votes),
+        message: "Proposal votes retrieved successfully".to_string(),
+    }))
+}
+
+/// Get governance statistics
+async fn get_governance_stats(
+    State(state): State<AppState>,
+) -> std::result::Result<Json<ApiResponse<crate::governance::GovernanceStats>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_governance_stats");
+
+    let governance = state.governance
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Governance system not configured".to_string()))?;
+
+    let gov_clone = {
+        let gov = governance.lock().unwrap();
+        gov.clone_for_background()
+    };
+    
+    let stats = gov_clone.get_governance_stats().await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(stats),
+        message: "Governance statistics retrieved successfully".to_string(),
+    }))
+}
+
+// Simulation Handlers
+
+/// Run simulation
+async fn run_simulation(
+    State(state): State<AppState>,
+    Json(_request): Json<SimulationRunRequest>,
+) -> std::result::Result<Json<ApiResponse<crate::simulation::SimulationResult>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "run_simulation");
+
+    let simulation_manager = state.simulation_manager
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Simulation manager not configured".to_string()))?;
+
+    let sim_clone = {
+        let sim = simulation_manager.lock().unwrap();
+        sim.clone_for_background()
+    };
+    
+    let result = sim_clone.run_simulation().await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(result),
+        message: "Simulation completed successfully".to_string(),
+    }))
+}
+
+/// Get simulation progress
+async fn get_simulation_progress(
+    State(state): State<AppState>,
+    Path(_simulation_id): Path<String>,
+) -> std::result::Result<Json<ApiResponse<f64>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_simulation_progress");
+
+    let simulation_manager = state.simulation_manager
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Simulation manager not configured".to_string()))?;
+
+    let sim_clone = {
+        let sim = simulation_manager.lock().unwrap();
+        sim.clone_for_background()
+    };
+    
+    let progress = sim_clone.get_progress().await;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(progress),
+        message: "Simulation progress retrieved successfully".to_string(),
+    }))
+}
+
+/// Get simulation state
+async fn get_simulation_state(
+    State(state): State<AppState>,
+    Path(_simulation_id): Path<String>,
+) -> std::result::Result<Json<ApiResponse<crate::simulation::SimulationState>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_simulation_state");
+
+    let simulation_manager = state.simulation_manager
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("Simulation manager not configured".to_string()))?;
+
+    let sim_clone = {
+        let sim = simulation_manager.lock().unwrap();
+        sim.clone_for_background()
+    };
+    
+    let state = sim_clone.get_current_state().await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(state),
+        message: "Simulation state retrieved successfully".to_string(),
     }))
 }
 
@@ -1397,42 +2964,408 @@ async fn stop_simulation(
 ) -> std::result::Result<Json<ApiResponse<()>>, ApiError> {
     counter!("api_requests_total", 1, "endpoint" => "stop_simulation");
 
-    // Note: This would need to be implemented in the simulation manager
-    // For now, we'll return a success response
+    // Note: This would need to be implemented in the simulation manager
+    // For now, we'll return a success response
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        message: "Simulation stop requested".to_string(),
+    }))
+}
+
+/// Get simulation results
+async fn get_simulation_results(
+    State(_state): State<AppState>,
+    Path(_simulation_id): Path<String>,
+) -> std::result::Result<Json<ApiResponse<crate::simulation::SimulationResult>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_simulation_results");
+
+    // Note: This would need to be implemented to store and retrieve simulation results
+    // For now, we'll return an error
+    Err(ApiError::Internal("Simulation results not yet implemented".to_string()))
+}
+
+/// Get all simulations
+async fn get_all_simulations(
+    State(_state): State<AppState>,
+) -> std::result::Result<Json<ApiResponse<Vec<String>>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "get_all_simulations");
+
+    // Note: This would need to be implemented to track all simulations
+    // For now, we'll return an empty list
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(vec![]),
+        message: "All simulations retrieved successfully".to_string(),
+    }))
+}
+
+/// Upgrade to a WebSocket that streams blockchain events
+async fn event_stream(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(query): Query<EventStreamQuery>,
+) -> std::result::Result<impl IntoResponse, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "event_stream");
+
+    let event_types = match query.events {
+        Some(events) => events
+            .split(',')
+            .map(|event| event.trim().parse::<crate::EventType>())
+            .collect::<crate::Result<Vec<_>>>()
+            .map_err(|e| ApiError::InvalidRequest(e.to_string()))?,
+        None => Vec::new(),
+    };
+    let filter = crate::EventFilter {
+        event_types,
+        contract: query.contract,
+        address: query.address,
+    };
+
+    // Subscribe before upgrading so no events are missed after the handshake
+    let receiver = state.blockchain.lock().unwrap().subscribe_events();
+
+    Ok(ws.on_upgrade(move |socket| forward_events(socket, receiver, filter)))
+}
+
+/// Forward events from the broadcast channel to a WebSocket client
+///
+/// The client can replace the filter at any time by sending a
+/// `{"subscribe": {...}}` text message, which is acknowledged with
+/// `{"subscribed": {...}}` once the new filter is in effect.
+async fn forward_events(
+    mut socket: WebSocket,
+    mut receiver: broadcast::Receiver<crate::Event>,
+    mut filter: crate::EventFilter,
+) {
+    loop {
+        tokio::select! {
+            event = receiver.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        error!("WebSocket client lagged, skipped {} events", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if !filter.matches(&event) {
+                    continue;
+                }
+                let payload = match serde_json::to_string(&event) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        error!("Failed to serialize event: {}", e);
+                        continue;
+                    }
+                };
+                if socket.send(Message::Text(payload)).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => {
+                let reply = match message {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(Message::Text(text))) => {
+                        match serde_json::from_str::<crate::events::SubscribeMessage>(&text) {
+                            Ok(message) => {
+                                filter = message.subscribe;
+                                serde_json::json!({ "subscribed": filter })
+                            }
+                            Err(e) => serde_json::json!({ "error": format!("Invalid subscribe message: {}", e) }),
+                        }
+                    }
+                    Some(Ok(_)) => continue,
+                };
+                if socket.send(Message::Text(reply.to_string())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// ZKP Handlers
+
+/// Create a private transaction backed by a zero-knowledge proof
+async fn create_zkp_transaction(
+    State(state): State<AppState>,
+    Json(request): Json<ZKPTransactionRequest>,
+) -> std::result::Result<Json<ApiResponse<ZKPTransactionResponse>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "create_zkp_transaction");
+    let start = std::time::Instant::now();
+
+    let zkp_manager = state.zkp_manager
+        .as_ref()
+        .ok_or_else(|| ApiError::ServiceUnavailable("ZKP manager not configured".to_string()))?;
+
+    if !request.amount.is_finite() || request.amount <= 0.0 {
+        return Err(ApiError::InvalidRequest("Amount must be positive".to_string()));
+    }
+
+    let private_key = {
+        let mut wallet_manager = state.wallet_manager.lock().unwrap();
+        wallet_manager.get_private_key_bytes(&request.sender, &request.password)?
+    };
+    let sender_keypair = crate::KeyPair::from_private_key_bytes(&private_key)?;
+    let receiver_public_key = crate::PublicKey::from_hex(&request.receiver)
+        .map_err(|e| ApiError::InvalidRequest(format!("Invalid receiver public key: {}", e)))?;
+
+    let mut manager_clone = {
+        let manager = zkp_manager.lock().unwrap();
+        manager.clone_for_background()
+    };
+
+    let transaction = manager_clone.create_private_transaction(
+        &sender_keypair,
+        &receiver_public_key,
+        request.amount,
+        request.memo,
+    ).await?;
+
+    let response = ZKPTransactionResponse {
+        proof_id: transaction.zk_proof.id(),
+        proof_data: hex::encode(&transaction.zk_proof.proof_data),
+        transaction,
+    };
+
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "create_zkp_transaction");
+
     Ok(Json(ApiResponse {
         success: true,
-        data: None,
-        message: "Simulation stop requested".to_string(),
+        data: Some(response),
+        message: "Private transaction created successfully".to_string(),
     }))
 }
 
-/// Get simulation results
-async fn get_simulation_results(
-    State(_state): State<AppState>,
-    Path(_simulation_id): Path<String>,
-) -> std::result::Result<Json<ApiResponse<crate::simulation::SimulationResult>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_simulation_results");
+/// Verify a zero-knowledge proof
+async fn verify_zkp_proof(
+    State(state): State<AppState>,
+    Json(request): Json<ZKPVerifyRequest>,
+) -> std::result::Result<Json<ApiResponse<ZKPVerifyResponse>>, ApiError> {
+    counter!("api_requests_total", 1, "endpoint" => "verify_zkp_proof");
+    let start = std::time::Instant::now();
 
-    // Note: This would need to be implemented to store and retrieve simulation results
-    // For now, we'll return an error
-    Err(ApiError::Internal("Simulation results not yet implemented".to_string()))
-}
+    let zkp_manager = state.zkp_manager
+        .as_ref()
+        .ok_or_else(|| ApiError::ServiceUnavailable("ZKP manager not configured".to_string()))?;
+
+    let proof_data = hex::decode(&request.proof_data)
+        .map_err(|e| ApiError::InvalidRequest(format!("Invalid proof data: {}", e)))?;
+    let proof = crate::ZKProof {
+        proof_data,
+        public_inputs: Vec::new(),
+        verification_key: Vec::new(),
+        timestamp: chrono::Utc::now().timestamp(),
+    };
 
-/// Get all simulations
-async fn get_all_simulations(
-    State(_state): State<AppState>,
-) -> std::result::Result<Json<ApiResponse<Vec<String>>>, ApiError> {
-    counter!("api_requests_total", 1, "endpoint" => "get_all_simulations");
+    let manager_clone = {
+        let manager = zkp_manager.lock().unwrap();
+        manager.clone_for_background()
+    };
+
+    let valid = manager_clone.verify_proof(&proof).await?;
+
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "verify_zkp_proof");
 
-    // Note: This would need to be implemented to track all simulations
-    // For now, we'll return an empty list
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(vec![]),
-        message: "All simulations retrieved successfully".to_string(),
+        data: Some(ZKPVerifyResponse {
+            proof_id: proof.id(),
+            valid,
+        }),
+        message: "Proof verification completed".to_string(),
     }))
 }
 
+/// JSON-RPC protocol version accepted and returned by `/rpc`
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// JSON-RPC error code: the body is not valid JSON
+pub const RPC_PARSE_ERROR: i64 = -32700;
+
+/// JSON-RPC error code: the JSON is not a valid request object
+pub const RPC_INVALID_REQUEST: i64 = -32600;
+
+/// JSON-RPC error code: the method does not exist
+pub const RPC_METHOD_NOT_FOUND: i64 = -32601;
+
+/// JSON-RPC error code: the method parameters are missing or malformed
+pub const RPC_INVALID_PARAMS: i64 = -32602;
+
+/// JSON-RPC error code: the node failed while handling the call
+pub const RPC_INTERNAL_ERROR: i64 = -32603;
+
+/// JSON-RPC error code: the blockchain rejected the call (e.g. insufficient balance)
+pub const RPC_SERVER_ERROR: i64 = -32000;
+
+/// JSON-RPC 2.0 error object
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// JSON-RPC 2.0 response envelope
+/// 
+/// Exactly one of `result` and `error` is present.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+    pub id: serde_json::Value,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+impl RpcResponse {
+    fn new(id: serde_json::Value, outcome: std::result::Result<serde_json::Value, RpcError>) -> Self {
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self { jsonrpc: JSONRPC_VERSION.to_string(), result, error, id }
+    }
+}
+
+/// Handle JSON-RPC 2.0 calls, single or batched
+/// 
+/// Supported methods are `gil_getBalance`, `gil_getBlockByIndex`, `gil_sendTransaction`
+/// and `gil_getChainHeight`. Parameters may be passed by position or by name.
+/// Notifications (requests without an `id`) are executed but get no response, so a
+/// batch made only of notifications returns 204 No Content.
+async fn json_rpc(State(state): State<AppState>, body: axum::body::Bytes) -> Response {
+    counter!("api_requests_total", 1, "endpoint" => "json_rpc");
+    let start = std::time::Instant::now();
+
+    let payload: serde_json::Value = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            let error = RpcError::new(RPC_PARSE_ERROR, format!("Parse error: {}", e));
+            return Json(RpcResponse::new(serde_json::Value::Null, Err(error))).into_response();
+        }
+    };
+
+    let response = match payload {
+        serde_json::Value::Array(calls) if calls.is_empty() => {
+            let error = RpcError::new(RPC_INVALID_REQUEST, "Invalid request: empty batch");
+            Json(RpcResponse::new(serde_json::Value::Null, Err(error))).into_response()
+        }
+        serde_json::Value::Array(calls) => {
+            let responses: Vec<RpcResponse> = calls
+                .into_iter()
+                .filter_map(|call| handle_rpc_call(&state, call))
+                .collect();
+            if responses.is_empty() {
+                StatusCode::NO_CONTENT.into_response()
+            } else {
+                Json(responses).into_response()
+            }
+        }
+        call => match handle_rpc_call(&state, call) {
+            Some(response) => Json(response).into_response(),
+            None => StatusCode::NO_CONTENT.into_response(),
+        },
+    };
+
+    histogram!("api_request_duration_ms", start.elapsed().as_millis() as f64, "endpoint" => "json_rpc");
+    response
+}
+
+/// Validate one JSON-RPC request object and dispatch it
+/// 
+/// Returns `None` for notifications.
+fn handle_rpc_call(state: &AppState, call: serde_json::Value) -> Option<RpcResponse> {
+    let id = match call.get("id") {
+        None => None,
+        Some(id @ (serde_json::Value::Null | serde_json::Value::Number(_) | serde_json::Value::String(_))) => Some(id.clone()),
+        Some(_) => {
+            let error = RpcError::new(RPC_INVALID_REQUEST, "Invalid request: id must be a string, number or null");
+            return Some(RpcResponse::new(serde_json::Value::Null, Err(error)));
+        }
+    };
+
+    let method = match (call.get("jsonrpc").and_then(|v| v.as_str()), call.get("method").and_then(|v| v.as_str())) {
+        (Some(JSONRPC_VERSION), Some(method)) => method,
+        _ => {
+            let error = RpcError::new(RPC_INVALID_REQUEST, "Invalid request: expected jsonrpc \"2.0\" and a method name");
+            return Some(RpcResponse::new(id.unwrap_or(serde_json::Value::Null), Err(error)));
+        }
+    };
+
+    let params = call.get("params").cloned().unwrap_or(serde_json::Value::Null);
+    if !matches!(params, serde_json::Value::Null | serde_json::Value::Array(_) | serde_json::Value::Object(_)) {
+        let error = RpcError::new(RPC_INVALID_REQUEST, "Invalid request: params must be an array or object");
+        return Some(RpcResponse::new(id.unwrap_or(serde_json::Value::Null), Err(error)));
+    }
+
+    counter!("rpc_calls_total", 1, "method" => method.to_string());
+    let outcome = dispatch_rpc(state, method, &params);
+    id.map(|id| RpcResponse::new(id, outcome))
+}
+
+/// Execute a JSON-RPC method
+fn dispatch_rpc(state: &AppState, method: &str, params: &serde_json::Value) -> std::result::Result<serde_json::Value, RpcError> {
+    match method {
+        "gil_getBalance" => {
+            let address: String = rpc_param(params, 0, "address")?;
+            let blockchain = state.blockchain.lock().unwrap();
+            Ok(serde_json::json!(blockchain.get_balance(&address)))
+        }
+        "gil_getBlockByIndex" => {
+            let index: usize = rpc_param(params, 0, "index")?;
+            let blockchain = state.blockchain.lock().unwrap();
+            let block = blockchain.blocks.get(index)
+                .ok_or_else(|| RpcError::new(RPC_INVALID_PARAMS, format!("Block {} does not exist", index)))?;
+            serde_json::to_value(block).map_err(|e| RpcError::new(RPC_INTERNAL_ERROR, e.to_string()))
+        }
+        "gil_sendTransaction" => {
+            let sender: String = rpc_param(params, 0, "sender")?;
+            let receiver: String = rpc_param(params, 1, "receiver")?;
+            let amount: f64 = rpc_param(params, 2, "amount")?;
+            let amount = units::to_base_units(amount)
+                .map_err(|e| RpcError::new(RPC_INVALID_PARAMS, e.to_string()))?;
+            let message: Option<String> = rpc_param(params, 3, "message")?;
+            let password: String = rpc_param(params, 4, "password")?;
+
+            let keypair = wallet_keypair(state, &sender, &password)
+                .map_err(|e| RpcError::new(RPC_SERVER_ERROR, e.to_string()))?;
+            let mut blockchain = state.blockchain.lock().unwrap();
+            blockchain.add_transaction(&keypair, receiver, amount, message)
+                .map_err(|e| RpcError::new(RPC_SERVER_ERROR, e.to_string()))?;
+            state.storage.save_pending_transactions(&blockchain.pending_transactions)
+                .map_err(|e| RpcError::new(RPC_INTERNAL_ERROR, e.to_string()))?;
+            let transaction_id = blockchain.pending_transactions.last().map(|tx| tx.id.clone());
+            Ok(serde_json::json!(transaction_id))
+        }
+        "gil_getChainHeight" => {
+            let blockchain = state.blockchain.lock().unwrap();
+            Ok(serde_json::json!(blockchain.height()))
+        }
+        _ => Err(RpcError::new(RPC_METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    }
+}
+
+/// Read a JSON-RPC parameter by position (array params) or name (object params)
+/// 
+/// A missing parameter is read as `null`, so it is only accepted for `Option` types.
+fn rpc_param<T: DeserializeOwned>(params: &serde_json::Value, index: usize, name: &str) -> std::result::Result<T, RpcError> {
+    let value = match params {
+        serde_json::Value::Array(values) => values.get(index),
+        serde_json::Value::Object(values) => values.get(name),
+        _ => None,
+    };
+    serde_json::from_value(value.cloned().unwrap_or(serde_json::Value::Null))
+        .map_err(|e| RpcError::new(RPC_INVALID_PARAMS, format!("Invalid params: {}: {}", name, e)))
+}
+
 /// Start the API server
 /// 
 /// # Arguments
@@ -1442,6 +3375,7 @@ async fn get_all_simulations(
 /// # Returns
 /// * `Result<()>` - Ok if server started successfully
 pub async fn start_server(state: AppState, address: &str) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    install_prometheus_recorder();
     let app = create_router(state);
     
     info!("Starting API server on {}", address);
@@ -1459,7 +3393,7 @@ pub async fn start_server(state: AppState, address: &str) -> std::result::Result
     });
     
     // Start the server with graceful shutdown
-    axum::serve(listener, app)
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .with_graceful_shutdown(async {
             rx.await.ok();
         })
@@ -1472,31 +3406,1279 @@ pub async fn start_server(state: AppState, address: &str) -> std::result::Result
 #[cfg(test)]
 mod tests {
     use super::*;
+    use axum::body::Body;
+    use crate::units;
+    use axum::http::Request;
     use tempfile::tempdir;
-    
-    #[tokio::test]
-    async fn test_health_check() {
-        let temp_dir = tempdir().unwrap();
+    use tower::ServiceExt;
+    use base64::Engine;
+
+    fn test_state(temp_dir: &tempfile::TempDir) -> AppState {
         let storage = Arc::new(crate::storage::BlockchainStorage::new(temp_dir.path()).unwrap());
         let blockchain = Blockchain::new_pow(2, 50.0).unwrap();
-        let wallet_manager = WalletManager::new();
-        
-        let state = AppState {
+        let mut wallet_manager = WalletManager::new();
+        wallet_manager.set_shared_storage(storage.clone());
+
+        AppState {
             blockchain: Arc::new(Mutex::new(blockchain)),
             wallet_manager: Arc::new(Mutex::new(wallet_manager)),
             ethereum_bridge: None,
             did_system: None,
             governance: None,
             simulation_manager: None,
-            storage: storage,
+            zkp_manager: None,
+            api_keys: Arc::new(HashSet::new()),
+            rate_limit_per_minute: 0,
+            storage,
             storage_path: "./test_api_db".to_string(),
             start_time: std::time::Instant::now(),
-        };
+        }
+    }
+
+    async fn post_json(app: Router, uri: &str, body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+    
+    #[tokio::test]
+    async fn test_prometheus_metrics() {
+        let temp_dir = tempdir().unwrap();
+        install_prometheus_recorder();
+        let app = create_router(test_state(&temp_dir));
+
+        let (status, _) = get_json(app.clone(), "/chain").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let request = Request::builder().uri("/metrics/prometheus").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()[header::CONTENT_TYPE].to_str().unwrap().starts_with("text/plain"));
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(text.contains("# HELP api_requests_total Total number of API requests"));
+        assert!(text.contains("# TYPE api_requests_total counter"));
+        assert!(text.contains("api_requests_total{endpoint=\"get_chain\"}"));
+    }
+
+    #[tokio::test]
+    async fn test_transaction_batch_partial_success() {
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let alice = state.wallet_manager.lock().unwrap().create_wallet("password123", None, None).unwrap();
+        state.blockchain.lock().unwrap().balances.insert(alice.address.clone(), units::gil(100));
+        let app = create_router(state.clone());
+
+        let (status, body) = post_json(app.clone(), "/transactions/batch", serde_json::json!([
+            {"sender": alice.address, "receiver": "bob", "amount": 10.0, "message": null, "password": "password123"},
+            {"sender": alice.address, "receiver": "carol", "amount": 1000.0, "message": null, "password": "password123"},
+            {"sender": alice.address, "receiver": "erin", "amount": 1.0, "message": null, "password": "wrong password"},
+            {"sender": alice.address, "receiver": "dave", "amount": 5.0, "message": "rent", "password": "password123"},
+        ])).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let results = body["data"].as_array().unwrap();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0]["accepted"], true);
+        assert_eq!(results[1]["accepted"], false);
+        assert!(results[1]["reason"].as_str().unwrap().contains("Insufficient"));
+        assert_eq!(results[2]["accepted"], false);
+        assert_eq!(results[3]["accepted"], true);
+        assert_eq!(body["message"], "2 of 4 transactions accepted");
+
+        // Accepted transfers are signed by the wallet and take consecutive nonces
+        {
+            let blockchain = state.blockchain.lock().unwrap();
+            for (nonce, tx) in blockchain.pending_transactions.iter().enumerate() {
+                assert!(tx.is_signed_by_sender().unwrap());
+                assert_eq!(tx.nonce, nonce as u64);
+            }
+        }
+
+        let receivers: Vec<String> = state.blockchain.lock().unwrap().pending_transactions
+            .iter()
+            .map(|tx| tx.receiver.clone())
+            .collect();
+        assert_eq!(receivers, vec!["bob".to_string(), "dave".to_string()]);
+        assert_eq!(state.storage.load_pending_transactions().unwrap().len(), 2);
+
+        let (status, _) = post_json(app, "/transactions/batch", serde_json::json!([])).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_client_signed_transaction() {
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let keypair = crate::crypto::KeyPair::generate().unwrap();
+        let sender = crate::crypto::create_address(&keypair.public_key());
+        state.blockchain.lock().unwrap().balances.insert(sender.clone(), units::gil(100));
+        let app = create_router(state.clone());
+
+        // The client signs with its own clock, which the server does not share
+        let mut transaction = Transaction::new_transfer(sender.clone(), "bob".to_string(), units::gil(10), Some("rent".to_string()))
+            .unwrap()
+            .with_timestamp(chrono::Utc::now().timestamp() - 30);
+        transaction.set_nonce(0);
+        transaction.sign(&keypair).unwrap();
+        let signature = transaction.signature.clone().unwrap();
+        let request = |signature: &[u8]| serde_json::json!({
+            "sender": sender,
+            "receiver": "bob",
+            "amount": 10.0,
+            "message": "rent",
+            "timestamp": transaction.timestamp,
+            "nonce": 0,
+            "signature": hex::encode(signature),
+            "public_key": keypair.public_key_hex(),
+        });
+
+        let mut tampered = signature.signature.clone();
+        tampered[0] ^= 0xff;
+        let (status, _) = post_json(app.clone(), "/transaction/signed", request(&tampered)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(state.blockchain.lock().unwrap().pending_transactions.is_empty());
+
+        let (status, body) = post_json(app, "/transaction/signed", request(&signature.signature)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["id"], transaction.id);
+        let blockchain = state.blockchain.lock().unwrap();
+        assert_eq!(blockchain.pending_transactions.len(), 1);
+        assert!(blockchain.pending_transactions[0].is_signed_by_sender().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_json_rpc_call() {
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let alice = state.wallet_manager.lock().unwrap().create_wallet("password123", None, None).unwrap();
+        state.blockchain.lock().unwrap().balances.insert(alice.address.clone(), units::gil(100));
+        let app = create_router(state.clone());
+
+        let (status, body) = post_json(app.clone(), "/rpc", serde_json::json!({
+            "jsonrpc": "2.0", "method": "gil_getBalance", "params": [alice.address], "id": 1
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, serde_json::json!({"jsonrpc": "2.0", "result": 100.0, "id": 1}));
+
+        // Sending requires the sender's wallet password
+        let (_, body) = post_json(app.clone(), "/rpc", serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "gil_sendTransaction",
+            "params": {"sender": alice.address, "receiver": "bob", "amount": 10.0, "password": "wrong password"},
+            "id": "send-0"
+        })).await;
+        assert_eq!(body["error"]["code"], RPC_SERVER_ERROR);
+        assert!(state.blockchain.lock().unwrap().pending_transactions.is_empty());
+
+        let (_, body) = post_json(app, "/rpc", serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "gil_sendTransaction",
+            "params": {"sender": alice.address, "receiver": "bob", "amount": 10.0, "password": "password123"},
+            "id": "send-1"
+        })).await;
+        let pending_id = state.blockchain.lock().unwrap().pending_transactions[0].id.clone();
+        assert_eq!(body["result"], serde_json::json!(pending_id));
+        assert_eq!(body["id"], "send-1");
+        assert!(body.get("error").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_json_rpc_errors() {
+        let temp_dir = tempdir().unwrap();
+        let app = create_router(test_state(&temp_dir));
+
+        let (status, body) = post_json(app.clone(), "/rpc", serde_json::json!({
+            "jsonrpc": "2.0", "method": "gil_unknown", "id": 7
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["error"]["code"], RPC_METHOD_NOT_FOUND);
+        assert_eq!(body["id"], 7);
+        assert!(body.get("result").is_none());
+
+        let (_, body) = post_json(app.clone(), "/rpc", serde_json::json!({"method": "gil_getChainHeight", "id": 8})).await;
+        assert_eq!(body["error"]["code"], RPC_INVALID_REQUEST);
+
+        let (_, body) = post_json(app.clone(), "/rpc", serde_json::json!({
+            "jsonrpc": "2.0", "method": "gil_getBalance", "params": [], "id": 9
+        })).await;
+        assert_eq!(body["error"]["code"], RPC_INVALID_PARAMS);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/rpc")
+            .header("content-type", "application/json")
+            .body(Body::from("{not json"))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["error"]["code"], RPC_PARSE_ERROR);
+        assert_eq!(body["id"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_json_rpc_batch() {
+        let temp_dir = tempdir().unwrap();
+        let app = create_router(test_state(&temp_dir));
+
+        let (status, body) = post_json(app.clone(), "/rpc", serde_json::json!([
+            {"jsonrpc": "2.0", "method": "gil_getChainHeight", "id": 1},
+            {"jsonrpc": "2.0", "method": "gil_getBlockByIndex", "params": [0], "id": 2},
+            {"jsonrpc": "2.0", "method": "gil_getBalance", "params": ["nobody"]},
+            {"jsonrpc": "2.0", "method": "gil_missing", "id": 3},
+            42
+        ])).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // The notification gets no response
+        let responses = body.as_array().unwrap();
+        assert_eq!(responses.len(), 4);
+        assert_eq!(responses[0]["result"], 0);
+        assert_eq!(responses[1]["result"]["index"], 0);
+        assert_eq!(responses[1]["id"], 2);
+        assert_eq!(responses[2]["error"]["code"], RPC_METHOD_NOT_FOUND);
+        assert_eq!(responses[3]["error"]["code"], RPC_INVALID_REQUEST);
+
+        let (_, body) = post_json(app, "/rpc", serde_json::json!([])).await;
+        assert_eq!(body["error"]["code"], RPC_INVALID_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_health_check() {
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
         
-        let response = health_check(State(state)).await.unwrap();
-        let response_body = response.0;
+        let (status, Json(response_body)) = health_check(State(state)).await.unwrap();
         
+        assert_eq!(status, StatusCode::OK);
         assert!(response_body.success);
         assert_eq!(response_body.message, "API is healthy");
+        let health = response_body.data.unwrap();
+        assert_eq!(health.status, "healthy");
+        assert!(health.checks.iter().all(|check| check.healthy));
+        let did = health.checks.iter().find(|check| check.name == "did").unwrap();
+        assert_eq!(did.detail, "not configured");
+    }
+
+    #[tokio::test]
+    async fn test_health_check_reports_stale_chain() {
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        state.blockchain.lock().unwrap().blocks.last_mut().unwrap().timestamp -= MAX_LAST_BLOCK_AGE_SECS + 60;
+        let app = create_router(state);
+
+        let (status, body) = get_json(app, "/health").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["success"], false);
+        assert_eq!(body["data"]["status"], "unhealthy");
+        let blockchain_check = body["data"]["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|check| check["name"] == "blockchain")
+            .unwrap();
+        assert_eq!(blockchain_check["healthy"], false);
+        assert_eq!(blockchain_check["critical"], true);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_degraded_by_pending_pool() {
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            let transaction = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(1), None).unwrap();
+            blockchain.pending_transactions = vec![transaction; MAX_HEALTHY_PENDING_TRANSACTIONS + 1];
+        }
+
+        let (status, Json(response_body)) = health_check(State(state)).await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response_body.data.unwrap().status, "degraded");
+    }
+
+    #[tokio::test]
+    async fn test_transaction_status() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let tx_id = {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            blockchain.balances.insert(alice.clone(), units::gil(100));
+            blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(10), None).unwrap();
+            blockchain.pending_transactions[0].id.clone()
+        };
+        let app = create_router(state.clone());
+        let uri = format!("/tx/{}/status", tx_id);
+
+        let (status, body) = get_json(app.clone(), &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"], serde_json::json!({
+            "status": "Pending",
+            "finalized": false,
+            "required_confirmations": crate::blockchain::DEFAULT_FINALITY_DEPTH,
+        }));
+
+        let block = state.blockchain.lock().unwrap().mine_block("miner".to_string()).unwrap();
+        let (status, body) = get_json(app.clone(), &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"], serde_json::json!({
+            "status": "Confirmed",
+            "block_number": block.index,
+            "confirmations": 1,
+            "finalized": false,
+            "required_confirmations": crate::blockchain::DEFAULT_FINALITY_DEPTH,
+        }));
+
+        // Confirmations grow with the chain and the finality depth is configurable per request
+        {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            blockchain.add_transaction(&alice_key, "carol".to_string(), units::gil(1), None).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+        }
+        let (_, body) = get_json(app.clone(), &format!("{}?confirmations=2", uri)).await;
+        assert_eq!(body["data"]["confirmations"], 2);
+        assert_eq!(body["data"]["finalized"], true);
+        assert_eq!(body["data"]["required_confirmations"], 2);
+
+        let (status, body) = get_json(app, "/tx/unknown/status").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["error"].as_str().unwrap().contains("unknown"));
+    }
+
+    #[tokio::test]
+    async fn test_responses_carry_request_id() {
+        let temp_dir = tempdir().unwrap();
+        let app = create_router(test_state(&temp_dir));
+
+        let mut ids = HashSet::new();
+        for (uri, expected) in [("/health", StatusCode::OK), ("/block/999", StatusCode::BAD_REQUEST)] {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), expected);
+            let request_id = response.headers()
+                .get(REQUEST_ID_HEADER)
+                .expect("response carries X-Request-Id")
+                .to_str()
+                .unwrap();
+            let parsed = uuid::Uuid::parse_str(request_id).unwrap();
+            assert_eq!(parsed.get_version(), Some(uuid::Version::Random));
+            ids.insert(request_id.to_string());
+        }
+        // Every request gets its own ID, including failed ones
+        assert_eq!(ids.len(), 2);
+        assert!(current_request_id().is_none());
+    }
+
+    async fn get_json(app: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    fn block_indices(body: &serde_json::Value) -> Vec<u64> {
+        body["data"]["blocks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|block| block["index"].as_u64().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_contract_endpoints() {
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let code = "PUSH 42\nSTORE answer\nRETURN".to_string();
+        let contract_id = {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            blockchain.balances.insert("alice".to_string(), units::gil(1000));
+            let contract_id = blockchain.deploy_contract("alice".to_string(), code.clone(), 10_000, 0.000001).unwrap();
+            blockchain.contracts.get_mut(&contract_id).unwrap().storage.clear();
+            blockchain.call_contract("alice".to_string(), contract_id.clone(), "run".to_string(), units::gil(1), 10_000, 0.000001).unwrap();
+            contract_id
+        };
+        let app = create_router(state);
+
+        let (status, body) = get_json(app.clone(), &format!("/contract/{}", contract_id)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["address"], contract_id.as_str());
+        assert_eq!(body["data"]["owner"], "alice");
+        assert_eq!(body["data"]["active"], true);
+        assert_eq!(body["data"]["code_size"], code.len());
+
+        let (status, body) = get_json(app.clone(), &format!("/contract/{}/storage", contract_id)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["storage"]["answer"], "42");
+
+        let (status, body) = get_json(app.clone(), "/contract/missing").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["error"].as_str().unwrap().contains("missing"));
+        let (status, _) = get_json(app, "/contract/missing/storage").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_deploy_contract_endpoint() {
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let app = create_router(state.clone());
+        let code = "PUSH 42\nSTORE answer\nRETURN";
+
+        let (status, body) = post_json(app.clone(), "/contract/deploy", serde_json::json!({
+            "sender": "alice", "code": hex::encode(code), "gas_limit": 10_000, "gas_price": 0.000001
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        let address = body["data"]["address"].as_str().unwrap().to_string();
+        assert!(body["data"]["gas_used"].as_u64().unwrap() > 0);
+        let blockchain = state.blockchain.lock().unwrap();
+        assert_eq!(blockchain.get_contract(&address).unwrap().storage["answer"], "42");
+        drop(blockchain);
+
+        let (status, body) = post_json(app, "/contract/deploy", serde_json::json!({
+            "sender": "alice",
+            "code": base64::engine::general_purpose::STANDARD.encode(code),
+            "encoding": "base64",
+            "gas_limit": 10_000,
+            "gas_price": 0.000001
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["data"]["gas_used"].as_u64().unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_deploy_contract_rejects_oversized_code() {
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let app = create_router(state.clone());
+        let code = "NOP\n".repeat(crate::MAX_CONTRACT_SIZE / 4 + 1);
+
+        let (status, body) = post_json(app, "/contract/deploy", serde_json::json!({
+            "sender": "alice",
+            "code": base64::engine::general_purpose::STANDARD.encode(&code),
+            "encoding": "base64",
+            "gas_limit": 10_000,
+            "gas_price": 0.000001
+        })).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("exceeding the maximum"));
+        assert!(state.blockchain.lock().unwrap().contracts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_analytics_daily_buckets() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        // 2024-01-01 00:00:00 UTC
+        let day0 = 1_704_067_200;
+        {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            blockchain.balances.insert(alice.clone(), units::gil(1000));
+            for (amounts, timestamp) in [
+                (vec![10, 30], day0 + 3600),
+                (vec![5], day0 + 86_399),
+                (vec![7, 1, 4], day0 + 2 * 86_400 + 60),
+                (vec![100], day0 + 3 * 86_400),
+            ] {
+                for amount in amounts {
+                    blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(amount), None).unwrap();
+                }
+                blockchain.mine_block("miner".to_string()).unwrap();
+                blockchain.blocks.last_mut().unwrap().timestamp = timestamp;
+            }
+        }
+        let app = create_router(state);
+
+        let (status, body) = get_json(
+            app.clone(),
+            &format!("/analytics/TransactionVolume?from={}&to={}&bucket=day", day0 + 60, day0 + 3 * 86_400),
+        ).await;
+        assert_eq!(status, StatusCode::OK);
+        let data = &body["data"];
+        let buckets = data["buckets"].as_array().unwrap();
+        assert_eq!(buckets.len(), 3);
+        for (i, bucket) in buckets.iter().enumerate() {
+            assert_eq!(bucket["start"], day0 + i as i64 * 86_400);
+            assert_eq!(bucket["end"], day0 + (i as i64 + 1) * 86_400);
+        }
+
+        assert_eq!(buckets[0]["summary"], serde_json::json!({"total": 45.0, "average": 15.0, "min": 5.0, "max": 30.0, "count": 3}));
+        // The empty day is present with a zero summary
+        assert_eq!(buckets[1]["summary"], serde_json::json!({"total": 0.0, "average": 0.0, "min": 0.0, "max": 0.0, "count": 0}));
+        assert_eq!(buckets[2]["summary"]["total"], 12.0);
+        assert_eq!(buckets[2]["summary"]["average"], 4.0);
+        assert_eq!(buckets[2]["summary"]["min"], 1.0);
+        assert_eq!(buckets[2]["summary"]["max"], 7.0);
+
+        // The block at the exclusive end of the range is left out
+        assert_eq!(data["summary"]["total"], 57.0);
+        assert_eq!(data["summary"]["count"], 6);
+        let totals: Vec<f64> = data["data_points"].as_array().unwrap().iter().map(|point| point["value"].as_f64().unwrap()).collect();
+        assert_eq!(totals, vec![45.0, 0.0, 12.0]);
+
+        let (status, _) = get_json(app.clone(), &format!("/analytics/ShardPerformance?from={}&to={}", day0, day0 + 1)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get_json(app.clone(), &format!("/analytics/TransactionVolume?from={}&to={}&bucket=hour", day0, day0 + 3600 * 20_000)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        // Extreme ranges are rejected rather than overflowing
+        for (from, to) in [(i64::MIN, 0), (i64::MIN + 1, i64::MAX), (i64::MAX - 1, i64::MAX)] {
+            let (status, _) = get_json(app.clone(), &format!("/analytics/TransactionVolume?from={}&to={}&bucket=week", from, to)).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[test]
+    fn test_bucket_alignment() {
+        // 2024-01-03 (a Wednesday) 13:45:10 UTC
+        let timestamp = 1_704_289_510;
+        assert_eq!(BucketGranularity::Hour.bucket_start(timestamp), Some(1_704_286_800));
+        assert_eq!(BucketGranularity::Day.bucket_start(timestamp), Some(1_704_240_000));
+        // Monday 2024-01-01
+        assert_eq!(BucketGranularity::Week.bucket_start(timestamp), Some(1_704_067_200));
+        assert_eq!(BucketGranularity::Day.bucket_start(i64::MIN), None);
+    }
+
+    #[tokio::test]
+    async fn test_chain_pagination() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            blockchain.balances.insert(alice.clone(), units::gil(100));
+            for _ in 0..5 {
+                blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+                blockchain.mine_block("miner".to_string()).unwrap();
+            }
+        }
+        let app = create_router(state);
+
+        // Defaults to the most recent blocks, newest first
+        let (status, body) = get_json(app.clone(), "/chain").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(block_indices(&body), vec![5, 4, 3, 2, 1, 0]);
+        assert_eq!(body["data"]["total_blocks"], 6);
+
+        let (_, body) = get_json(app.clone(), "/chain?limit=2").await;
+        assert_eq!(block_indices(&body), vec![5, 4]);
+        assert_eq!(body["data"]["returned_blocks"], 2);
+        assert_eq!(body["data"]["total_blocks"], 6);
+
+        let (_, body) = get_json(app.clone(), "/chain?offset=2&limit=2").await;
+        assert_eq!(block_indices(&body), vec![3, 2]);
+
+        let (_, body) = get_json(app.clone(), "/chain?offset=1&limit=3&order=asc").await;
+        assert_eq!(block_indices(&body), vec![1, 2, 3]);
+
+        // Windows running past the end are truncated
+        let (_, body) = get_json(app.clone(), "/chain?offset=4&limit=10&order=asc").await;
+        assert_eq!(block_indices(&body), vec![4, 5]);
+
+        // The range endpoint shares the same slicing
+        let (_, body) = get_json(app.clone(), "/chain/2/4").await;
+        assert_eq!(block_indices(&body), vec![2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_chain_pagination_rejects_bad_ranges() {
+        let temp_dir = tempdir().unwrap();
+        let app = create_router(test_state(&temp_dir));
+
+        for uri in [
+            "/chain?limit=0",
+            "/chain?limit=501",
+            "/chain?offset=1",
+            "/chain/1/0",
+            "/chain/0/1",
+        ] {
+            let (status, _) = get_json(app.clone(), uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_api_key_required_for_mutating_requests() {
+        let temp_dir = tempdir().unwrap();
+        let mut state = test_state(&temp_dir);
+        state.api_keys = Arc::new(HashSet::from(["secret-key".to_string()]));
+        let alice = state.wallet_manager.lock().unwrap().create_wallet("password123", None, None).unwrap();
+        state.blockchain.lock().unwrap().balances.insert(alice.address.clone(), units::gil(100));
+        let app = create_router(state);
+
+        let transaction = serde_json::json!({
+            "sender": alice.address,
+            "receiver": "bob",
+            "amount": 10.0,
+            "message": null,
+            "password": "password123",
+        });
+        let send = |key: Option<&'static str>| {
+            let app = app.clone();
+            let transaction = transaction.clone();
+            async move {
+                let mut request = Request::builder()
+                    .method("POST")
+                    .uri("/transaction")
+                    .header("content-type", "application/json");
+                if let Some(key) = key {
+                    request = request.header(API_KEY_HEADER, key);
+                }
+                let request = request.body(Body::from(transaction.to_string())).unwrap();
+                app.oneshot(request).await.unwrap().status()
+            }
+        };
+
+        assert_eq!(send(None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(send(Some("wrong-key")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(send(Some("secret-key")).await, StatusCode::OK);
+
+        // Read-only endpoints stay public
+        let (status, _) = get_json(app.clone(), "/health").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_returns_429() {
+        let temp_dir = tempdir().unwrap();
+        let mut state = test_state(&temp_dir);
+        state.rate_limit_per_minute = 2;
+        let app = create_router(state);
+
+        for _ in 0..2 {
+            let (status, _) = get_json(app.clone(), "/health").await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        let (status, body) = get_json(app, "/health").await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["status"], 429);
+        assert!(body["error"].as_str().unwrap().contains("Too many requests"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_ignores_unknown_api_keys() {
+        let temp_dir = tempdir().unwrap();
+        let mut state = test_state(&temp_dir);
+        state.rate_limit_per_minute = 2;
+        state.api_keys = Arc::new(HashSet::from(["secret-key".to_string()]));
+        let app = create_router(state);
+
+        let send = |key: String| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder()
+                    .uri("/health")
+                    .header(API_KEY_HEADER, key)
+                    .body(Body::empty())
+                    .unwrap();
+                app.oneshot(request).await.unwrap().status()
+            }
+        };
+
+        // A fresh made-up key per request still shares the IP's bucket
+        for i in 0..2 {
+            assert_eq!(send(format!("made-up-{}", i)).await, StatusCode::OK);
+        }
+        assert_eq!(send("made-up-2".to_string()).await, StatusCode::TOO_MANY_REQUESTS);
+
+        // A valid key has its own bucket
+        assert_eq!(send("secret-key".to_string()).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_refills_after_window() {
+        let temp_dir = tempdir().unwrap();
+        let limiter = RateLimiter::with_window(2, Duration::from_millis(200));
+        let app = Router::new()
+            .route("/health", get(health_check))
+            .layer(middleware::from_fn_with_state(limiter, rate_limit))
+            .with_state(test_state(&temp_dir));
+
+        for _ in 0..2 {
+            let (status, _) = get_json(app.clone(), "/health").await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let (status, _) = get_json(app.clone(), "/health").await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+
+        let (status, _) = get_json(app, "/health").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[test]
+    fn test_rate_limiter_tracks_clients_separately() {
+        let limiter = RateLimiter::new(1);
+        assert!(limiter.try_acquire("ip:127.0.0.1"));
+        assert!(!limiter.try_acquire("ip:127.0.0.1"));
+        assert!(limiter.try_acquire("key:secret-key"));
+
+        let unlimited = RateLimiter::new(0);
+        assert!((0..100).all(|_| unlimited.try_acquire("ip:127.0.0.1")));
+    }
+
+    #[tokio::test]
+    async fn test_create_did_keeps_private_key_in_keystore() {
+        let temp_dir = tempdir().unwrap();
+        let mut state = test_state(&temp_dir);
+        let did_system = crate::did::DecentralizedIdentity::new(state.storage.clone()).await.unwrap();
+        state.did_system = Some(Arc::new(Mutex::new(did_system)));
+        let app = create_router(state.clone());
+
+        let (status, body) = post_json(app, "/did/create", serde_json::json!({
+            "controller": null,
+            "service_endpoints": [],
+            "password": "correct horse battery staple",
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        let data = &body["data"];
+        assert!(data.get("private_key").is_none());
+
+        // The key is recoverable only from the keystore, with the password
+        let public_key = crate::crypto::PublicKey::from_hex(data["public_key"].as_str().unwrap()).unwrap();
+        let address = crate::crypto::create_address(&public_key);
+        let mut wallet_manager = state.wallet_manager.lock().unwrap();
+        assert!(wallet_manager.get_private_key_bytes(&address, "wrong password").is_err());
+        let private_key = wallet_manager.get_private_key_bytes(&address, "correct horse battery staple").unwrap();
+        let keypair = crate::KeyPair::from_private_key_bytes(&private_key).unwrap();
+        assert_eq!(keypair.public_key_hex(), data["public_key"].as_str().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_and_revoke_did_with_stored_key() {
+        let temp_dir = tempdir().unwrap();
+        let mut state = test_state(&temp_dir);
+        let did_system = crate::did::DecentralizedIdentity::new(state.storage.clone()).await.unwrap();
+        state.did_system = Some(Arc::new(Mutex::new(did_system)));
+
+        let (_, body) = post_json(create_router(state.clone()), "/did/create", serde_json::json!({
+            "controller": null,
+            "service_endpoints": [],
+            "password": "password123",
+        })).await;
+        let did = body["data"]["did"].as_str().unwrap().to_string();
+
+        // A restarted node only has the key in the wallet storage
+        let mut wallet_manager = WalletManager::new();
+        wallet_manager.set_shared_storage(state.storage.clone());
+        state.wallet_manager = Arc::new(Mutex::new(wallet_manager));
+
+        let rotate = format!("/did/{}/rotate", did);
+        let (status, _) = post_json(create_router(state.clone()), &rotate, serde_json::json!({
+            "password": "wrong password",
+        })).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, body) = post_json(create_router(state.clone()), &rotate, serde_json::json!({
+            "password": "password123",
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        let new_public_key = body["data"]["public_key"].as_str().unwrap().to_string();
+
+        let system = state.did_system.as_ref().unwrap().lock().unwrap().clone_for_background();
+        let document = system.get_did_document(&did).await.unwrap().unwrap();
+        assert_eq!(document.version, 1);
+        assert_eq!(document.verification_methods[0].id, format!("{}#keys-2", did));
+        assert_eq!(system.authentication_key(&document).unwrap().to_hex(), new_public_key);
+
+        // Revocation is signed with the rotated key, which was stored as well
+        let (status, _) = post_json(create_router(state.clone()), &format!("/did/{}/revoke", did), serde_json::json!({
+            "password": "password123",
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(system.get_did_document(&did).await.unwrap().unwrap().revoked);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret-longer"));
+    }
+
+    #[tokio::test]
+    async fn test_contract_withdrawal() {
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let (owner, mallory) = {
+            let mut wallet_manager = state.wallet_manager.lock().unwrap();
+            (
+                wallet_manager.create_wallet("password123", None, None).unwrap(),
+                wallet_manager.create_wallet("password456", None, None).unwrap(),
+            )
+        };
+        let contract_address = {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            blockchain.balances.insert(owner.address.clone(), crate::units::gil(100));
+            let address = blockchain.deploy_contract(owner.address.clone(), "PUSH 1\nRETURN".to_string(), 10_000, 0.000001).unwrap();
+            blockchain.contracts.get_mut(&address).unwrap().balance = units::gil(40);
+            address
+        };
+        let owner_balance = state.blockchain.lock().unwrap().get_balance(&owner.address);
+        let app = create_router(state.clone());
+        let uri = format!("/contract/{}/withdraw", contract_address);
+
+        // Only the owner may withdraw
+        let (status, _) = post_json(app.clone(), &uri, serde_json::json!({
+            "caller": mallory.address,
+            "amount": 10.0,
+            "password": "password456",
+        })).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(state.blockchain.lock().unwrap().pending_transactions.is_empty());
+
+        let (status, body) = post_json(app, &uri, serde_json::json!({
+            "caller": owner.address,
+            "amount": 15.0,
+            "password": "password123",
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        let tx_id = body["data"]["id"].as_str().unwrap().to_string();
+
+        let mut blockchain = state.blockchain.lock().unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(blockchain.get_receipt(&tx_id).unwrap().status, crate::transaction::ReceiptStatus::Success);
+        assert_eq!(blockchain.contracts[&contract_address].balance, units::gil(25));
+        assert_eq!(blockchain.get_balance(&owner.address), owner_balance + 15.0);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_proof_round_trip() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let tx_id = {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            blockchain.balances.insert(alice.clone(), crate::units::gil(1000));
+            for receiver in ["bob", "charlie", "dave"] {
+                blockchain.add_transaction(&alice_key, receiver.to_string(), units::gil(10), None).unwrap();
+            }
+            let block = blockchain.mine_block("miner".to_string()).unwrap();
+            block.transactions[1].id.clone()
+        };
+        let app = create_router(state.clone());
+
+        let (status, _) = get_json(app.clone(), "/tx/missing/proof").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = get_json(app.clone(), &format!("/tx/{}/proof", tx_id)).await;
+        assert_eq!(status, StatusCode::OK);
+        let data = &body["data"];
+        assert_eq!(data["header"]["index"], 1);
+        assert_eq!(data["header_hash"], state.blockchain.lock().unwrap().blocks[1].hash.as_str());
+
+        let (status, body) = post_json(app.clone(), "/proof/verify", serde_json::json!({
+            "proof": data["proof"],
+            "header_hash": data["header_hash"],
+            "tx_hash": data["tx_hash"],
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["valid"], true);
+
+        // A proof whose path was altered no longer leads to the header's Merkle root
+        let mut tampered = data["proof"].clone();
+        tampered["path"][0][0] = serde_json::json!(crate::utils::calculate_hash("forged"));
+        let (status, body) = post_json(app, "/proof/verify", serde_json::json!({
+            "proof": tampered,
+            "header_hash": data["header_hash"],
+            "tx_hash": data["tx_hash"],
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["valid"], false);
+    }
+
+    #[tokio::test]
+    async fn test_validator_management() {
+        let bob_key = crate::crypto::KeyPair::generate().unwrap();
+        let bob = crate::crypto::create_address(&bob_key.public_key());
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let alice = state.wallet_manager.lock().unwrap().create_wallet("password123", None, None).unwrap();
+        let register = serde_json::json!({
+            "address": alice.address,
+            "stake": 1000.0,
+            "password": "password123",
+        });
+
+        // The test state runs PoW, which has no validators to manage
+        let app = create_router(state.clone());
+        let (status, body) = post_json(app.clone(), "/validator/register", register.clone()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("Proof of Stake"));
+        let (status, _) = get_json(app, "/validators").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // A bootstrap validator produces the blocks the staking transactions are mined in
+        {
+            let bootstrap = crate::KeyPair::generate().unwrap();
+            let mut blockchain = Blockchain::new_pos_with_validators(10.0, units::gil(100), 10, vec![
+                (bootstrap.public_key_hex(), "bootstrap".to_string(), units::gil(1000)),
+            ]).unwrap();
+            blockchain.add_validator_signer(bootstrap).unwrap();
+            blockchain.balances.insert(alice.address.clone(), crate::units::gil(2000));
+            *state.blockchain.lock().unwrap() = blockchain;
+        }
+        let app = create_router(state.clone());
+        let (status, body) = post_json(app.clone(), "/validator/register", register.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["transaction_type"], "Staking");
+
+        // Nothing is staked until the registration is mined
+        {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            assert!(!blockchain.proof_of_stake().unwrap().validators.contains_key(&alice.address));
+            assert_eq!(blockchain.get_balance(&alice.address), 2000.0);
+            blockchain.mine_block("miner".to_string()).unwrap();
+            assert_eq!(blockchain.proof_of_stake().unwrap().validators[&alice.address].stake_amount, units::gil(1000));
+            assert_eq!(blockchain.get_balance(&alice.address), 1000.0);
+
+            // The registration's signing key is the validator's block signing key
+            let private_key = state.wallet_manager.lock().unwrap()
+                .get_private_key_bytes(&alice.address, "password123").unwrap();
+            blockchain.add_validator_signer(crate::KeyPair::from_private_key_bytes(&private_key).unwrap()).unwrap();
+        }
+        let (status, _) = post_json(app.clone(), "/validator/register", register).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Only the validator's wallet can sign for it
+        let (status, _) = post_json(app.clone(), "/validator/stake", serde_json::json!({
+            "address": alice.address,
+            "amount": 500.0,
+            "password": "wrong",
+        })).await;
+        assert_ne!(status, StatusCode::OK);
+        assert!(state.blockchain.lock().unwrap().pending_transactions.is_empty());
+
+        let (status, _) = post_json(app.clone(), "/validator/stake", serde_json::json!({
+            "address": alice.address,
+            "amount": 500.0,
+            "password": "password123",
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        state.blockchain.lock().unwrap().mine_block("miner".to_string()).unwrap();
+
+        let (status, _) = post_json(app.clone(), "/validator/unstake", serde_json::json!({
+            "address": alice.address,
+            "amount": 5000.0,
+            "password": "password123",
+        })).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = post_json(app.clone(), "/validator/unstake", serde_json::json!({
+            "address": alice.address,
+            "amount": 200.0,
+            "password": "password123",
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+            assert_eq!(blockchain.proof_of_stake().unwrap().get_unbonding(&alice.address), units::gil(200));
+        }
+
+        // Unstaked tokens are withdrawn to the balance once their unbonding period passes
+        let withdraw = serde_json::json!({
+            "address": alice.address,
+            "password": "password123",
+        });
+        let (status, _) = post_json(app.clone(), "/validator/withdraw", withdraw.clone()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let balance = {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            let release_height = blockchain.proof_of_stake().unwrap().unbonding[&alice.address][0].release_height;
+            blockchain.balances.insert(bob.clone(), units::gil(100));
+            while (blockchain.blocks.len() as u64) < release_height {
+                blockchain.add_transaction(&bob_key, "carol".to_string(), units::gil(1), None).unwrap();
+                blockchain.mine_block("miner".to_string()).unwrap();
+            }
+            blockchain.get_balance(&alice.address)
+        };
+        let (status, body) = post_json(app.clone(), "/validator/withdraw", withdraw).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["amount"], units::gil(200) as u64);
+        {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            let block = blockchain.mine_block("miner".to_string()).unwrap();
+            let rewards: u128 = block.transactions.iter()
+                .filter(|tx| tx.is_coinbase() && tx.receiver == alice.address)
+                .map(|tx| tx.amount)
+                .sum();
+            assert_eq!(blockchain.get_balance(&alice.address), balance + 200.0 + units::from_base_units(rewards));
+            assert_eq!(blockchain.proof_of_stake().unwrap().get_unbonding(&alice.address), 0);
+        }
+
+        // Staking does not register unknown validators
+        let (status, _) = post_json(app.clone(), "/validator/stake", serde_json::json!({
+            "address": "validator2",
+            "amount": 500.0,
+            "password": "password123",
+        })).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = get_json(app, "/validators").await;
+        assert_eq!(status, StatusCode::OK);
+        let validators = body["data"].as_array().unwrap();
+        assert_eq!(validators.len(), 2);
+        let validator = validators.iter().find(|validator| validator["address"] == alice.address.as_str()).unwrap();
+        assert_eq!(validator["stake"], 1300.0);
+        assert_eq!(validator["active"], true);
+        let rewards: f64 = validators.iter().map(|validator| validator["rewards"].as_f64().unwrap()).sum();
+        let blocks = state.blockchain.lock().unwrap().blocks.len() - 1;
+        assert_eq!(rewards, 10.0 * blocks as f64);
+    }
+
+    #[tokio::test]
+    async fn test_zkp_transaction_and_verify() {
+        let temp_dir = tempdir().unwrap();
+        let mut state = test_state(&temp_dir);
+        state.zkp_manager = Some(Arc::new(Mutex::new(ZKPManager::new())));
+        let sender = state.wallet_manager.lock().unwrap().create_wallet("password123", None, None).unwrap();
+        let receiver = crate::KeyPair::generate().unwrap();
+        let app = create_router(state);
+
+        let (status, body) = post_json(app.clone(), "/zkp/transaction", serde_json::json!({
+            "sender": sender.address,
+            "password": "password123",
+            "receiver": receiver.public_key_hex(),
+            "amount": 25.0,
+            "memo": "private payment",
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        let proof_id = body["data"]["proof_id"].as_str().unwrap().to_string();
+        let proof_data = body["data"]["proof_data"].as_str().unwrap().to_string();
+
+        let (status, body) = post_json(app.clone(), "/zkp/verify", serde_json::json!({
+            "proof_data": proof_data,
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["valid"], true);
+        assert_eq!(body["data"]["proof_id"], proof_id);
+
+        // Tampering with the proof invalidates it
+        let mut tampered = hex::decode(&proof_data).unwrap();
+        tampered[0] ^= 0xff;
+        let (status, body) = post_json(app, "/zkp/verify", serde_json::json!({
+            "proof_data": hex::encode(tampered),
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["valid"], false);
+    }
+
+    #[tokio::test]
+    async fn test_websocket_streams_new_block() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        use futures_util::StreamExt;
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let blockchain = state.blockchain.clone();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, create_router(state)).await.unwrap();
+        });
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws?events=NewBlock", address))
+            .await
+            .unwrap();
+
+        {
+            let mut blockchain = blockchain.lock().unwrap();
+            blockchain.balances.insert(alice.clone(), units::gil(100));
+            blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(10), None).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+        }
+
+        // The NewTransaction event is filtered out, so the first message is the block
+        let message = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let event: serde_json::Value = match message {
+            WsMessage::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("unexpected message: {:?}", other),
+        };
+        assert_eq!(event["event_type"], "NewBlock");
+        assert_eq!(event["data"]["index"], 1);
+    }
+
+    async fn next_json<S>(socket: &mut S) -> serde_json::Value
+    where
+        S: futures_util::Stream<Item = std::result::Result<tokio_tungstenite::tungstenite::Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        use futures_util::StreamExt;
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        match tokio::time::timeout(std::time::Duration::from_secs(5), socket.next()).await {
+            Ok(Some(Ok(WsMessage::Text(text)))) => serde_json::from_str(&text).unwrap(),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_websocket_contract_filter() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let blockchain = state.blockchain.clone();
+
+        let (watched, ignored) = {
+            let mut blockchain = blockchain.lock().unwrap();
+            blockchain.balances.insert(alice.clone(), units::gil(1000));
+            let watched = blockchain.deploy_contract(
+                alice.clone(), "PUSH 1\nRETURN".to_string(), 10_000, 0.000001,
+            ).unwrap();
+            let ignored = blockchain.deploy_contract(
+                alice.clone(), "PUSH 2\nRETURN".to_string(), 10_000, 0.000001,
+            ).unwrap();
+            (watched, ignored)
+        };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, create_router(state)).await.unwrap();
+        });
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", address))
+            .await
+            .unwrap();
+        socket.send(WsMessage::Text(
+            serde_json::json!({ "subscribe": { "contract": watched } }).to_string(),
+        )).await.unwrap();
+        let ack = next_json(&mut socket).await;
+        assert_eq!(ack["subscribed"]["contract"], watched);
+
+        {
+            let mut blockchain = blockchain.lock().unwrap();
+            for contract in [&ignored, &watched, &ignored, &watched] {
+                blockchain.call_contract(
+                    alice.clone(), contract.clone(), "run".to_string(), units::gil(1), 10_000, 0.000001,
+                ).unwrap();
+            }
+            blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(10), None).unwrap();
+        }
+
+        for _ in 0..2 {
+            let event = next_json(&mut socket).await;
+            assert_eq!(event["event_type"], "ContractCalled");
+            assert_eq!(event["data"]["contract"], watched);
+            assert_eq!(event["data"]["caller"], alice.as_str());
+        }
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(200), socket.next()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_metrics_report_block_time() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            blockchain.set_target_block_time(30.0).unwrap();
+            blockchain.balances.insert(alice.clone(), units::gil(100));
+            for _ in 0..crate::blockchain::DEFAULT_RETARGET_INTERVAL {
+                blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+                blockchain.mine_block("miner".to_string()).unwrap();
+            }
+        }
+        let app = create_router(state);
+
+        let (status, body) = get_json(app, "/metrics").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["target_block_time"], 30.0);
+        // Blocks mined back to back are well under half the target, so difficulty 2 rises by one
+        assert!(body["data"]["average_block_time"].as_f64().unwrap() < 15.0);
+        assert_eq!(body["data"]["current_difficulty"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_mempool_filters() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let bob_key = crate::crypto::KeyPair::generate().unwrap();
+        let bob = crate::crypto::create_address(&bob_key.public_key());
+        let temp_dir = tempdir().unwrap();
+        let state = test_state(&temp_dir);
+        let (cheap, expensive) = {
+            let mut blockchain = state.blockchain.lock().unwrap();
+            for sender in [&alice, &bob] {
+                blockchain.balances.insert(sender.clone(), units::gil(1000));
+            }
+            blockchain.add_transaction(&alice_key, "carol".to_string(), units::gil(5), None).unwrap();
+            blockchain.add_transaction(&bob_key, "carol".to_string(), units::gil(5), None).unwrap();
+            let cheap = crate::Transaction::new_contract_call(
+                alice.clone(), "contract".to_string(), "run".to_string(), units::gil(1), 1_000, 0.001,
+            ).unwrap();
+            let expensive = crate::Transaction::new_contract_call(
+                alice.clone(), "contract".to_string(), "stop".to_string(), units::gil(1), 10_000, 0.001,
+            ).unwrap();
+            blockchain.add_transaction_object(cheap.clone()).unwrap();
+            blockchain.add_transaction_object(expensive.clone()).unwrap();
+            (cheap, expensive)
+        };
+        let app = create_router(state);
+
+        let (status, body) = get_json(app.clone(), "/mempool").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["summary"]["count"], 4);
+
+        let (_, body) = get_json(app.clone(), &format!("/mempool?sender={}", alice)).await;
+        let senders: Vec<&str> = body["data"]["transactions"].as_array().unwrap().iter()
+            .map(|tx| tx["sender"].as_str().unwrap())
+            .collect();
+        assert_eq!(senders, vec![alice.as_str(); 3]);
+        assert_eq!(body["data"]["summary"]["count"], 3);
+
+        let (_, body) = get_json(app.clone(), &format!("/mempool?sender={}&min_fee=5", alice)).await;
+        let transactions = body["data"]["transactions"].as_array().unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0]["id"], expensive.id);
+        assert_eq!(body["data"]["summary"]["total_fees"], 10.0);
+        assert_eq!(body["data"]["summary"]["total_bytes"], expensive.size());
+
+        let (_, body) = get_json(app, "/mempool?min_fee=0.5&limit=1").await;
+        let transactions = body["data"]["transactions"].as_array().unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0]["id"], cheap.id);
+        assert_eq!(body["data"]["summary"]["count"], 2);
+        assert_eq!(body["data"]["summary"]["total_fees"], 11.0);
+        assert_eq!(body["data"]["summary"]["total_bytes"], cheap.size() + expensive.size());
+    }
+
+    #[tokio::test]
+    async fn test_zkp_unconfigured_returns_503() {
+        let temp_dir = tempdir().unwrap();
+        let app = create_router(test_state(&temp_dir));
+
+        let (status, body) = post_json(app, "/zkp/verify", serde_json::json!({
+            "proof_data": "00",
+        })).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], 503);
     }
 }
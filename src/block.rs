@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 use chrono::Utc;
 use log::{debug, info};
-use crate::{Result, BlockchainError, Transaction, ProofOfWork, utils, merkle::MerkleTree, crypto::DigitalSignature, GENESIS_HASH, MAX_BLOCK_SIZE};
+use std::collections::HashMap;
+use crate::{Result, BlockchainError, Transaction, ProofOfWork, utils, units, merkle::MerkleTree, crypto::DigitalSignature, GENESIS_HASH, GENESIS_TIMESTAMP, MAX_BLOCK_SIZE};
 
 /// Represents a block in the blockchain
 /// 
@@ -21,8 +22,17 @@ pub struct Block {
     pub hash: String,
     /// Nonce used for proof of work
     pub nonce: u64,
+    /// Proof-of-work difficulty the block was mined against (0 if not mined or unrecorded)
+    #[serde(default)]
+    pub difficulty: u32,
     /// Merkle tree for efficient transaction verification
     pub merkle_tree: Option<MerkleTree>,
+    /// Merkle root of the transactions, committed to by the block hash
+    #[serde(default)]
+    pub merkle_root: Vec<u8>,
+    /// Merkle root of the state after applying this block, committed to by the block hash
+    #[serde(default)]
+    pub state_root: Vec<u8>,
     /// Block version
     pub version: String,
     /// Validator address (for PoS consensus)
@@ -49,9 +59,9 @@ impl Block {
     /// # Example
     /// ```
     /// use gillean::block::Block;
-    /// use gillean::transaction::Transaction;
+    /// use gillean::{transaction::Transaction, units};
     /// 
-    /// let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
+    /// let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
     /// let block = Block::new(1, vec![tx], "previous_hash".to_string(), "1.0".to_string(), "pow".to_string()).unwrap();
     /// 
     /// assert_eq!(block.index, 1);
@@ -85,7 +95,8 @@ impl Block {
             MerkleTree::new(&transactions).ok()
         };
         
-        let hash = Self::calculate_hash(index, timestamp, &transactions, &previous_hash, 0);
+        let merkle_root = Self::compute_merkle_root(&transactions);
+        let hash = Self::hash_header(index, timestamp, &merkle_root, &[], &previous_hash, 0);
 
         let block = Block {
             index,
@@ -94,7 +105,10 @@ impl Block {
             previous_hash,
             hash,
             nonce: 0,
+            difficulty: 0,
             merkle_tree,
+            merkle_root,
+            state_root: Vec::new(),
             version,
             validator: None,
             validator_signature: None,
@@ -136,7 +150,7 @@ impl Block {
         let coinbase_tx = Transaction::new_transfer(
             "COINBASE".to_string(),
             "genesis".to_string(),
-            1000.0,
+            units::gil(1000),
             Some("Genesis block reward".to_string()),
         )?;
 
@@ -152,15 +166,59 @@ impl Block {
         Ok(block)
     }
 
+    /// Create a genesis block that funds accounts with coinbase transactions
+    /// 
+    /// Allocations are written in address order, so they are committed to by the
+    /// block's Merkle root and hash and replaying the block reproduces the balances.
+    /// The block and its transactions carry `GENESIS_TIMESTAMP`, so the same
+    /// allocations always produce the same block hash.
+    /// 
+    /// # Arguments
+    /// * `allocations` - Initial balance of each funded address, in base units
+    /// 
+    /// # Returns
+    /// * `Result<Block>` - The genesis block or an error if an allocation is invalid
+    pub fn genesis_with_allocations(allocations: &HashMap<String, u128>) -> Result<Self> {
+        let mut accounts: Vec<(&String, &u128)> = allocations.iter().collect();
+        accounts.sort_by(|a, b| a.0.cmp(b.0));
+
+        let transactions = accounts
+            .into_iter()
+            .map(|(address, amount)| {
+                Transaction::new_transfer(
+                    "COINBASE".to_string(),
+                    address.clone(),
+                    *amount,
+                    Some("Genesis allocation".to_string()),
+                )
+                .map(|transaction| transaction.with_timestamp(GENESIS_TIMESTAMP))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut block = Block::new(
+            0,
+            transactions,
+            GENESIS_HASH.to_string(),
+            "1.0".to_string(),
+            "pow".to_string(),
+        )?;
+        block.timestamp = GENESIS_TIMESTAMP;
+        block.hash = block.calculate_current_hash();
+
+        info!("Created genesis block with {} allocations", allocations.len());
+        Ok(block)
+    }
+
     /// Calculate the hash of the current block
     /// 
     /// # Returns
     /// * `String` - The calculated hash
     pub fn calculate_current_hash(&self) -> String {
-        Self::calculate_hash(
+        Self::hash_header(
             self.index,
             self.timestamp,
-            &self.transactions,
+            &self.merkle_root,
+            &self.state_root,
             &self.previous_hash,
             self.nonce,
         )
@@ -184,17 +242,67 @@ impl Block {
         previous_hash: &str,
         nonce: u64,
     ) -> String {
-        // Create a simplified representation of transactions for hashing
-        let tx_data: Vec<String> = transactions
-            .iter()
-            .map(|tx| format!("{}:{}:{}", tx.sender, tx.receiver, tx.amount))
-            .collect();
-        let tx_string = tx_data.join("|");
-
-        let data = format!("{}:{}:{}:{}:{}", index, timestamp, tx_string, previous_hash, nonce);
+        Self::hash_header(index, timestamp, &Self::compute_merkle_root(transactions), &[], previous_hash, nonce)
+    }
+
+    /// Hash block header fields
+    /// 
+    /// The header commits to the transactions only through their Merkle root,
+    /// so it can be checked without the transactions themselves.
+    fn hash_header(
+        index: u64,
+        timestamp: i64,
+        merkle_root: &[u8],
+        state_root: &[u8],
+        previous_hash: &str,
+        nonce: u64,
+    ) -> String {
+        let data = format!("{}:{}:{}", Self::header_data(index, timestamp, merkle_root, state_root), previous_hash, nonce);
         utils::calculate_hash(data)
     }
 
+    /// Header fields hashed ahead of the previous hash and nonce
+    /// 
+    /// Blocks without a state root hash exactly as they did before state roots
+    /// were introduced, so existing chains keep their hashes.
+    fn header_data(index: u64, timestamp: i64, merkle_root: &[u8], state_root: &[u8]) -> String {
+        if state_root.is_empty() {
+            format!("{}:{}:{}", index, timestamp, hex::encode(merkle_root))
+        } else {
+            format!("{}:{}:{}:{}", index, timestamp, hex::encode(merkle_root), hex::encode(state_root))
+        }
+    }
+
+    /// Compute the Merkle root of a list of transactions
+    /// 
+    /// # Arguments
+    /// * `transactions` - The transactions to commit to
+    /// 
+    /// # Returns
+    /// * `Vec<u8>` - The root hash bytes, empty if there are no transactions
+    pub fn compute_merkle_root(transactions: &[Transaction]) -> Vec<u8> {
+        MerkleTree::new(transactions)
+            .ok()
+            .and_then(|tree| tree.root_hash())
+            .and_then(|root| hex::decode(root).ok())
+            .unwrap_or_default()
+    }
+
+    /// Get the header of this block
+    /// 
+    /// # Returns
+    /// * `BlockHeader` - The fields needed to recompute the block hash
+    pub fn header(&self) -> BlockHeader {
+        BlockHeader {
+            index: self.index,
+            timestamp: self.timestamp,
+            merkle_root: hex::encode(&self.merkle_root),
+            state_root: hex::encode(&self.state_root),
+            previous_hash: self.previous_hash.clone(),
+            nonce: self.nonce,
+        }
+    }
+
     /// Mine the block with proof of work
     /// 
     /// # Arguments
@@ -217,12 +325,7 @@ impl Block {
         info!("Mining block {} with difficulty {}", self.index, pow.difficulty);
 
         // Prepare block data for mining (without nonce)
-        let tx_data: Vec<String> = self.transactions
-            .iter()
-            .map(|tx| format!("{}:{}:{}", tx.sender, tx.receiver, tx.amount))
-            .collect();
-        let tx_string = tx_data.join("|");
-        let block_data = format!("{}:{}:{}", self.index, self.timestamp, tx_string);
+        let block_data = Self::header_data(self.index, self.timestamp, &self.merkle_root, &self.state_root);
 
         // Mine the block
         let (nonce, hash) = pow.mine(&block_data, &self.previous_hash)?;
@@ -230,6 +333,30 @@ impl Block {
         // Update block with mining results
         self.nonce = nonce;
         self.hash = hash;
+        self.difficulty = pow.difficulty;
+
+        info!("Block {} mined successfully with nonce {}", self.index, nonce);
+        Ok(())
+    }
+
+    /// Mine the block using all available cores
+    ///
+    /// Produces the same nonce and hash as [`Block::mine`].
+    ///
+    /// # Arguments
+    /// * `pow` - Proof of work configuration
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if mining was successful, error otherwise
+    pub fn mine_parallel(&mut self, pow: &ProofOfWork) -> Result<()> {
+        info!("Mining block {} in parallel with difficulty {}", self.index, pow.difficulty);
+
+        let block_data = Self::header_data(self.index, self.timestamp, &self.merkle_root, &self.state_root);
+        let (nonce, hash) = pow.mine_parallel(&block_data, &self.previous_hash)?;
+
+        self.nonce = nonce;
+        self.hash = hash;
+        self.difficulty = pow.difficulty;
 
         info!("Block {} mined successfully with nonce {}", self.index, nonce);
         Ok(())
@@ -249,19 +376,31 @@ impl Block {
             });
         }
 
+        // Validate that the committed Merkle root matches the transactions. The hash
+        // covers the transactions only through this root, so it is always checked.
+        if self.merkle_root.is_empty() && !self.transactions.is_empty() {
+            return Err(BlockchainError::BlockValidationFailed(format!(
+                "Block {} has {} transactions but no Merkle root",
+                self.index,
+                self.transactions.len()
+            )));
+        }
+        let expected_root = Self::compute_merkle_root(&self.transactions);
+        if self.merkle_root != expected_root {
+            return Err(BlockchainError::BlockValidationFailed(format!(
+                "Merkle root mismatch: expected {}, got {}",
+                hex::encode(&expected_root),
+                hex::encode(&self.merkle_root)
+            )));
+        }
+
         // Validate all transactions
         for transaction in &self.transactions {
             transaction.validate()?;
         }
 
         // Validate hash
-        let expected_hash = Self::calculate_hash(
-            self.index,
-            self.timestamp,
-            &self.transactions,
-            &self.previous_hash,
-            self.nonce,
-        );
+        let expected_hash = self.calculate_current_hash();
 
         if self.hash != expected_hash {
             return Err(BlockchainError::InvalidHash(format!(
@@ -327,9 +466,9 @@ impl Block {
     /// Get the total amount of transactions in this block
     /// 
     /// # Returns
-    /// * `f64` - Total transaction amount
-    pub fn total_amount(&self) -> f64 {
-        self.transactions.iter().map(|tx| tx.amount).sum()
+    /// * `u128` - Total transaction amount in base units
+    pub fn total_amount(&self) -> u128 {
+        self.transactions.iter().fold(0u128, |total, tx| total.saturating_add(tx.amount))
     }
 
     /// Get the block size in bytes (approximate)
@@ -382,6 +521,39 @@ impl Block {
     }
 }
 
+/// Block header used by light clients
+/// 
+/// Contains everything that goes into the block hash, with the transactions
+/// replaced by their Merkle root.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BlockHeader {
+    /// Block index in the chain
+    pub index: u64,
+    /// Timestamp when the block was created
+    pub timestamp: i64,
+    /// Merkle root of the block's transactions
+    pub merkle_root: String,
+    /// Merkle root of the state after applying the block
+    #[serde(default)]
+    pub state_root: String,
+    /// Hash of the previous block
+    pub previous_hash: String,
+    /// Nonce used for proof of work
+    pub nonce: u64,
+}
+
+impl BlockHeader {
+    /// Calculate the hash of the block this header belongs to
+    /// 
+    /// # Returns
+    /// * `String` - The block hash
+    pub fn hash(&self) -> String {
+        let merkle_root = hex::decode(&self.merkle_root).unwrap_or_default();
+        let state_root = hex::decode(&self.state_root).unwrap_or_default();
+        Block::hash_header(self.index, self.timestamp, &merkle_root, &state_root, &self.previous_hash, self.nonce)
+    }
+}
+
 impl std::fmt::Display for Block {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -401,7 +573,7 @@ mod tests {
 
     #[test]
     fn test_block_creation() {
-        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
+        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
         let block = Block::new(1, vec![tx], "0000000000000000000000000000000000000000000000000000000000000000".to_string(), "1.0".to_string(), "pow".to_string()).unwrap();
 
         assert_eq!(block.index, 1);
@@ -417,6 +589,19 @@ mod tests {
         assert_eq!(genesis.previous_hash, GENESIS_HASH);
     }
 
+    #[test]
+    fn test_genesis_with_allocations_is_deterministic() {
+        let allocations = HashMap::from([("alice".to_string(), units::gil(500)), ("bob".to_string(), units::gil(250))]);
+        let reordered = HashMap::from([("bob".to_string(), units::gil(250)), ("alice".to_string(), units::gil(500))]);
+
+        let genesis = Block::genesis_with_allocations(&allocations).unwrap();
+        let again = Block::genesis_with_allocations(&reordered).unwrap();
+        assert_eq!(genesis.hash, again.hash);
+        assert_eq!(genesis.timestamp, GENESIS_TIMESTAMP);
+        assert!(genesis.transactions.iter().all(|tx| tx.timestamp == GENESIS_TIMESTAMP));
+        genesis.validate().unwrap();
+    }
+
     #[test]
     fn test_block_mining() {
         let mut block = Block::new(1, vec![], "0000000000000000000000000000000000000000000000000000000000000000".to_string(), "1.0".to_string(), "pow".to_string()).unwrap();
@@ -427,12 +612,77 @@ mod tests {
         assert!(block.nonce > 0);
     }
 
+    #[test]
+    fn test_parallel_block_mining() {
+        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), None).unwrap();
+        let mut block = Block::new(1, vec![tx], "0000000000000000000000000000000000000000000000000000000000000000".to_string(), "1.0".to_string(), "pow".to_string()).unwrap();
+        let mut serial = block.clone();
+        let pow = ProofOfWork::new(2, 100_000).unwrap();
+
+        block.mine_parallel(&pow).unwrap();
+        assert!(pow.validate_hash(&block.hash));
+        assert!(block.validate().is_ok());
+
+        serial.mine(&pow).unwrap();
+        assert_eq!((block.nonce, block.hash), (serial.nonce, serial.hash));
+    }
+
     #[test]
     fn test_block_validation() {
         let block = Block::new(1, vec![], "0000000000000000000000000000000000000000000000000000000000000000".to_string(), "1.0".to_string(), "pow".to_string()).unwrap();
         assert!(block.validate().is_ok());
     }
 
+    #[test]
+    fn test_merkle_root_detects_tampering() {
+        let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
+        let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), units::gil(50), None).unwrap();
+        let mut block = Block::new(1, vec![tx1, tx2], "0000000000000000000000000000000000000000000000000000000000000000".to_string(), "1.0".to_string(), "pow".to_string()).unwrap();
+        let pow = ProofOfWork::new(1, 1000).unwrap();
+        block.mine(&pow).unwrap();
+
+        assert_eq!(block.merkle_root, Block::compute_merkle_root(&block.transactions));
+        assert!(block.validate().is_ok());
+
+        block.transactions[1].amount = units::gil(5000);
+        match block.validate() {
+            Err(BlockchainError::BlockValidationFailed(msg)) => assert!(msg.contains("Merkle root mismatch")),
+            other => panic!("expected Merkle root mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_empty_merkle_root_is_rejected() {
+        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
+        let mut block = Block::new(1, vec![tx], "0000000000000000000000000000000000000000000000000000000000000000".to_string(), "1.0".to_string(), "pow".to_string()).unwrap();
+
+        // Without a root the hash no longer commits to the transactions
+        block.merkle_root = Vec::new();
+        block.hash = block.calculate_current_hash();
+        match block.validate() {
+            Err(BlockchainError::BlockValidationFailed(msg)) => assert!(msg.contains("no Merkle root")),
+            other => panic!("expected missing Merkle root, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_state_root_is_committed_by_hash() {
+        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
+        let mut block = Block::new(1, vec![tx], "0000000000000000000000000000000000000000000000000000000000000000".to_string(), "1.0".to_string(), "pow".to_string()).unwrap();
+        let pow = ProofOfWork::new(1, 1000).unwrap();
+        block.mine(&pow).unwrap();
+        let unrooted_hash = block.hash.clone();
+
+        block.state_root = vec![7; 32];
+        assert!(matches!(block.validate(), Err(BlockchainError::InvalidHash(_))));
+
+        block.mine(&pow).unwrap();
+        assert_ne!(block.hash, unrooted_hash);
+        assert!(block.validate().is_ok());
+        assert_eq!(block.header().state_root, hex::encode([7; 32]));
+        assert_eq!(block.header().hash(), block.hash);
+    }
+
     #[test]
     fn test_block_json_serialization() {
         let block = Block::new(1, vec![], "0000000000000000000000000000000000000000000000000000000000000000".to_string(), "1.0".to_string(), "pow".to_string()).unwrap();
@@ -445,11 +695,11 @@ mod tests {
 
     #[test]
     fn test_block_total_amount() {
-        let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
-        let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), 50.0, None).unwrap();
+        let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
+        let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), units::gil(50), None).unwrap();
         
         let block = Block::new(1, vec![tx1, tx2], "0000000000000000000000000000000000000000000000000000000000000000".to_string(), "1.0".to_string(), "pow".to_string()).unwrap();
-        assert_eq!(block.total_amount(), 150.0);
+        assert_eq!(block.total_amount(), units::gil(150));
     }
 
     #[test]
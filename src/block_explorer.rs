@@ -349,18 +349,14 @@ impl BlockExplorer {
         let mut first_seen = u64::MAX;
         let mut last_seen = 0;
         
-        for block in blockchain.blocks.iter() {
-            for tx in &block.transactions {
-                if tx.sender == address || tx.receiver == address {
-                    transaction_count += 1;
-                    let tx_time = block.timestamp as u64;
-                    if tx_time < first_seen {
-                        first_seen = tx_time;
-                    }
-                    if tx_time > last_seen {
-                        last_seen = tx_time;
-                    }
-                }
+        for (height, _) in blockchain.transaction_history(address) {
+            transaction_count += 1;
+            let tx_time = blockchain.blocks[height as usize].timestamp as u64;
+            if tx_time < first_seen {
+                first_seen = tx_time;
+            }
+            if tx_time > last_seen {
+                last_seen = tx_time;
             }
         }
         
@@ -447,7 +443,7 @@ impl BlockExplorer {
                         hash: tx.id.clone(),
                         from: tx.sender.clone(),
                         to: tx.receiver.clone(),
-                        amount: tx.amount,
+                        amount: crate::units::from_base_units(tx.amount),
                         timestamp: block.timestamp as u64,
                         status: TransactionStatus::Confirmed,
                         fee: 0.0, // Simplified
@@ -574,7 +570,7 @@ impl BlockExplorer {
         
         let mut total_transactions = 0;
         let mut total_contracts = 0;
-        let mut transaction_volume_24h = 0.0;
+        let mut transaction_volume_24h: u128 = 0;
         
         let current_time = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -594,7 +590,7 @@ impl BlockExplorer {
                     seen_addresses.insert(tx.receiver.clone());
                     
                     if block.timestamp >= day_ago as i64 {
-                        transaction_volume_24h += tx.amount;
+                        transaction_volume_24h = transaction_volume_24h.saturating_add(tx.amount);
                         active_addresses.insert(tx.sender.clone());
                         active_addresses.insert(tx.receiver.clone());
                     }
@@ -619,7 +615,7 @@ impl BlockExplorer {
             total_contracts,
             network_hash_rate: 1000000.0, // Placeholder
             average_block_time: 12.0, // Placeholder
-            transaction_volume_24h,
+            transaction_volume_24h: crate::units::from_base_units(transaction_volume_24h),
             active_addresses_24h,
             last_updated: current_time,
         })
@@ -1,37 +1,111 @@
 use serde::{Deserialize, Serialize};
 use log::{debug, info, error, warn};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
 use sha2::{Sha256, Digest};
 use crate::{
-    Result, BlockchainError, Block, Transaction, ProofOfWork, smart_contract::{SmartContract, ContractContext},
-    consensus::{ConsensusType, ProofOfStake}, 
-    BLOCKCHAIN_VERSION, DEFAULT_DIFFICULTY, MAX_BLOCK_SIZE
+    Result, BlockchainError, Block, Transaction, ProofOfWork, ProofOfAuthority, MerkleTree, MerkleProof, smart_contract::{SmartContract, ContractContext, ContractResult, ContractVM, ContractEvent, ContractEventLog},
+    transaction::{ReceiptStatus, TransactionReceipt, TransactionStatus},
+    consensus::{Consensus, ConsensusType, ProofOfStake, UnbondingEntry, Validator}, crypto, htlc::{Htlc, HtlcAction, HtlcStatus},
+    events::{ContractCallEvent, Event, EventType, EVENT_CHANNEL_CAPACITY},
+    performance::{self, CacheManager, SnapshotPruning},
+    units, BLOCKCHAIN_VERSION, DEFAULT_DIFFICULTY, MAX_BLOCK_SIZE
 };
 
+/// Gas limit used when dry-running a call to estimate its gas
+pub const MAX_ESTIMATE_GAS: u64 = 10_000_000;
+
+/// Safety margin added on top of the gas a dry run consumed, in percent
+pub const GAS_ESTIMATE_MARGIN_PERCENT: u64 = 20;
+
+/// Default time an orphan block is buffered before it is evicted, in seconds
+pub const DEFAULT_MAX_ORPHAN_AGE_SECS: u64 = 600;
+
+/// Default maximum number of blocks a chain reorganization may roll back
+pub const DEFAULT_MAX_REORG_DEPTH: u64 = 100;
+
+/// Maximum number of orphan blocks buffered at once
+pub const MAX_ORPHAN_BLOCKS: usize = 256;
+
+/// Default number of confirmations after which a transaction is reported final
+pub const DEFAULT_FINALITY_DEPTH: u64 = 6;
+
+/// Default target time between blocks, in seconds
+pub const DEFAULT_TARGET_BLOCK_TIME_SECS: f64 = 10.0;
+
+/// Number of most recent blocks the measured block time is averaged over
+pub const BLOCK_TIME_WINDOW: usize = 10;
+
+/// Default number of blocks between Proof of Work difficulty retargets
+pub const DEFAULT_RETARGET_INTERVAL: u64 = BLOCK_TIME_WINDOW as u64;
+
+/// Age after which a pending transaction is dropped when the mempool is reloaded, in seconds
+pub const MEMPOOL_EXPIRY_SECS: i64 = 72 * 60 * 60;
+
+/// Magic bytes at the start of every blockchain snapshot file
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"GILLSNAP";
+
+/// Version of the snapshot file layout
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Size of the snapshot header: magic, format version and SHA-256 checksum
+const SNAPSHOT_HEADER_LEN: usize = 8 + 4 + 32;
+
+/// Room kept free in a block being mined for the fields set when it is sealed
+const SEAL_SIZE_ALLOWANCE: usize = 4 * 1024;
+
 /// Blockchain state snapshot for rollback capability
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateSnapshot {
     /// Block index at time of snapshot
     pub block_index: u64,
-    /// Account balances at time of snapshot
-    pub balances: HashMap<String, f64>,
+    /// Account balances at time of snapshot, in base units
+    #[serde(serialize_with = "crate::utils::sorted_map")]
+    pub balances: HashMap<String, u128>,
     /// Smart contracts at time of snapshot
+    #[serde(serialize_with = "crate::utils::sorted_map")]
     pub contracts: HashMap<String, SmartContract>,
     /// Contract execution metrics at time of snapshot
+    #[serde(serialize_with = "crate::utils::sorted_map")]
     pub contract_metrics: HashMap<String, u64>,
+    /// Account nonces at time of snapshot
+    #[serde(default)]
+    #[serde(serialize_with = "crate::utils::sorted_map")]
+    pub nonces: HashMap<String, u64>,
+    /// PoS validators and their stakes at time of snapshot, if the chain uses PoS
+    #[serde(default)]
+    pub validators: Option<BTreeMap<String, Validator>>,
+    /// PoS stake waiting out the unbonding period at time of snapshot, if the chain uses PoS
+    #[serde(default)]
+    pub unbonding: Option<BTreeMap<String, Vec<UnbondingEntry>>>,
+    /// Hash time-locked contracts at time of snapshot
+    #[serde(default)]
+    #[serde(serialize_with = "crate::utils::sorted_map")]
+    pub htlcs: HashMap<String, Htlc>,
     /// State root hash
     pub state_root: Vec<u8>,
     /// Timestamp of snapshot
     pub timestamp: i64,
 }
 
+/// A block whose parent has not been seen yet, waiting to be connected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanBlock {
+    /// The buffered block
+    pub block: Block,
+    /// Unix timestamp at which the block was received
+    pub received_at: i64,
+}
+
 /// Merkle tree for state validation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateMerkleTree {
     /// Root hash of the state tree
     pub root: Vec<u8>,
     /// Leaf nodes (address -> balance mappings)
+    #[serde(serialize_with = "crate::utils::sorted_map")]
     pub leaves: HashMap<String, Vec<u8>>,
 }
 
@@ -49,27 +123,99 @@ pub struct Blockchain {
     pub difficulty: u32,
     /// Mining reward amount
     pub mining_reward: f64,
-    /// Proof of work instance
-    pub proof_of_work: ProofOfWork,
+    /// Maximum number of pending transactions mined into one block (0 = limited by size only)
+    #[serde(default)]
+    pub max_txs_per_block: usize,
+    /// Minimum fee per byte a transaction must pay to enter the pending pool (0 = no minimum)
+    #[serde(default)]
+    pub min_relay_fee: f64,
     /// Blockchain version
     pub version: String,
-    /// Balances of all addresses
-    pub balances: HashMap<String, f64>,
-    /// Consensus mechanism type
-    pub consensus_type: ConsensusType,
-    /// Proof of stake consensus (if using PoS)
-    pub proof_of_stake: Option<ProofOfStake>,
+    /// Balances of all addresses, in base units
+    #[serde(serialize_with = "crate::utils::sorted_map")]
+    pub balances: HashMap<String, u128>,
+    /// Next expected transaction nonce of each address
+    #[serde(default)]
+    #[serde(serialize_with = "crate::utils::sorted_map")]
+    pub nonces: HashMap<String, u64>,
+    /// Consensus mechanism that seals and validates blocks, with its state
+    pub(crate) consensus: Box<dyn Consensus>,
     /// Smart contracts deployed on the blockchain
+    #[serde(serialize_with = "crate::utils::sorted_map")]
     pub contracts: HashMap<String, SmartContract>,
     /// Contract execution metrics
+    #[serde(serialize_with = "crate::utils::sorted_map")]
     pub contract_metrics: HashMap<String, u64>,
+    /// Events emitted by contract executions
+    #[serde(default)]
+    pub event_log: ContractEventLog,
+    /// Hash time-locked contracts by ID
+    #[serde(default)]
+    #[serde(serialize_with = "crate::utils::sorted_map")]
+    pub htlcs: HashMap<String, Htlc>,
+    /// Execution receipts of mined transactions by transaction ID
+    #[serde(default)]
+    #[serde(serialize_with = "crate::utils::sorted_map")]
+    pub receipts: HashMap<String, TransactionReceipt>,
+    /// Blocks received before their parent, in arrival order
+    #[serde(skip)]
+    pub orphan_blocks: Vec<OrphanBlock>,
+    /// Seconds an orphan block is kept before it is evicted
+    #[serde(default = "default_max_orphan_age_secs")]
+    pub max_orphan_age_secs: u64,
+    /// Target time between blocks that difficulty retargeting aims for, in seconds
+    #[serde(default = "default_target_block_time_secs")]
+    pub target_block_time_secs: f64,
+    /// Blocks between Proof of Work difficulty retargets (0 = never retarget)
+    #[serde(default = "default_retarget_interval")]
+    pub retarget_interval: u64,
+    /// Maximum number of blocks a reorganization may roll back (0 = unlimited)
+    #[serde(default = "default_max_reorg_depth")]
+    pub max_reorg_depth: u64,
     /// State snapshots for rollback capability
     pub state_snapshots: Vec<StateSnapshot>,
+    /// Snapshot block index that an in-progress validation may still roll back to
+    #[serde(skip)]
+    pub rollback_target: Option<u64>,
     /// Current state Merkle tree
     pub state_tree: StateMerkleTree,
     /// State validation lock
     #[serde(skip)]
     pub state_lock: Arc<Mutex<()>>,
+    /// Broadcast channel for real-time events (created on first subscription)
+    #[serde(skip)]
+    pub event_sender: Option<broadcast::Sender<Event>>,
+    /// Stop signal of the running auto miner, if any
+    #[serde(skip)]
+    pub auto_miner_stop: Option<mpsc::Sender<()>>,
+    /// Cache serving balance and contract reads, invalidated as state changes
+    #[serde(skip)]
+    pub read_cache: Option<Arc<CacheManager>>,
+    /// Pruning of state snapshots to storage as blocks are added (disabled when None)
+    #[serde(skip)]
+    pub snapshot_pruning: Option<SnapshotPruning>,
+    /// Locations `(block index, transaction index)` of the transactions touching each address
+    #[serde(skip)]
+    pub address_index: HashMap<String, Vec<(usize, usize)>>,
+    /// Addresses whose balance the most recently applied block changed, sorted
+    #[serde(skip)]
+    pub balance_changes: Vec<String>,
+}
+
+fn default_max_orphan_age_secs() -> u64 {
+    DEFAULT_MAX_ORPHAN_AGE_SECS
+}
+
+fn default_target_block_time_secs() -> f64 {
+    DEFAULT_TARGET_BLOCK_TIME_SECS
+}
+
+fn default_retarget_interval() -> u64 {
+    DEFAULT_RETARGET_INTERVAL
+}
+
+fn default_max_reorg_depth() -> u64 {
+    DEFAULT_MAX_REORG_DEPTH
 }
 
 impl StateMerkleTree {
@@ -82,7 +228,19 @@ impl StateMerkleTree {
     }
 
     /// Update the Merkle tree with new state
-    pub fn update_state(&mut self, balances: &HashMap<String, f64>) {
+    pub fn update_state(&mut self, balances: &HashMap<String, u128>) {
+        self.update_state_with_contracts(balances, &HashMap::new());
+    }
+
+    /// Update the Merkle tree with balances and contract state
+    /// 
+    /// Each balance is a leaf keyed by its address, and each contract a leaf
+    /// keyed by `contract:<id>` that commits to its balance and storage.
+    pub fn update_state_with_contracts(
+        &mut self,
+        balances: &HashMap<String, u128>,
+        contracts: &HashMap<String, SmartContract>,
+    ) {
         self.leaves.clear();
         
         // Create leaf nodes for each balance
@@ -93,6 +251,17 @@ impl StateMerkleTree {
             let leaf_hash = hasher.finalize().to_vec();
             self.leaves.insert(address.clone(), leaf_hash);
         }
+
+        // Create leaf nodes for each contract from its storage root
+        for (id, contract) in contracts {
+            let storage: BTreeMap<&String, &String> = contract.storage.iter().collect();
+            let mut hasher = Sha256::new();
+            hasher.update(format!("{}:{}:", id, contract.balance).as_bytes());
+            for (key, value) in storage {
+                hasher.update(format!("{}={};", key, value).as_bytes());
+            }
+            self.leaves.insert(format!("contract:{}", id), hasher.finalize().to_vec());
+        }
         
         // Compute root hash
         self.compute_root();
@@ -105,7 +274,9 @@ impl StateMerkleTree {
             return;
         }
 
-        let mut current_level: Vec<Vec<u8>> = self.leaves.values().cloned().collect();
+        // Leaves are hashed in key order so equal states have equal roots
+        let sorted: BTreeMap<&String, &Vec<u8>> = self.leaves.iter().collect();
+        let mut current_level: Vec<Vec<u8>> = sorted.into_values().cloned().collect();
         
         while current_level.len() > 1 {
             let mut next_level = Vec::new();
@@ -129,7 +300,7 @@ impl StateMerkleTree {
     }
 
     /// Verify state integrity
-    pub fn verify_state(&self, balances: &HashMap<String, f64>) -> bool {
+    pub fn verify_state(&self, balances: &HashMap<String, u128>) -> bool {
         let mut temp_tree = StateMerkleTree::new();
         temp_tree.update_state(balances);
         temp_tree.root == self.root
@@ -161,6 +332,37 @@ impl Blockchain {
     /// assert_eq!(blockchain.mining_reward, 50.0);
     /// ```
     pub fn new_pow(difficulty: u32, mining_reward: f64) -> Result<Self> {
+        Self::new_pow_from_genesis(difficulty, mining_reward, Block::genesis()?)
+    }
+
+    /// Create a new blockchain with PoW consensus and funded genesis accounts
+    /// 
+    /// The allocations are paid out by coinbase transactions in the genesis block,
+    /// so they are part of the genesis hash and any node can verify them.
+    /// 
+    /// # Arguments
+    /// * `difficulty` - Mining difficulty level
+    /// * `mining_reward` - Reward for mining a block
+    /// * `allocations` - Initial balance of each funded address, in base units
+    /// 
+    /// # Returns
+    /// * `Result<Blockchain>` - The created blockchain or an error
+    /// 
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// use gillean::{blockchain::Blockchain, units};
+    /// 
+    /// let allocations = HashMap::from([("alice".to_string(), units::gil(500))]);
+    /// let blockchain = Blockchain::new_pow_with_genesis(2, 50.0, allocations).unwrap();
+    /// assert_eq!(blockchain.get_balance("alice"), 500.0);
+    /// ```
+    pub fn new_pow_with_genesis(difficulty: u32, mining_reward: f64, allocations: HashMap<String, u128>) -> Result<Self> {
+        Self::new_pow_from_genesis(difficulty, mining_reward, Block::genesis_with_allocations(&allocations)?)
+    }
+
+    /// Create a PoW blockchain starting from the given genesis block
+    fn new_pow_from_genesis(difficulty: u32, mining_reward: f64, genesis: Block) -> Result<Self> {
         let proof_of_work = ProofOfWork::new(difficulty, 1_000_000)?;
         
         let mut blockchain = Blockchain {
@@ -168,27 +370,41 @@ impl Blockchain {
             pending_transactions: Vec::new(),
             difficulty,
             mining_reward,
-            proof_of_work,
+            max_txs_per_block: 0,
+            min_relay_fee: 0.0,
             version: BLOCKCHAIN_VERSION.to_string(),
             balances: HashMap::new(),
-            consensus_type: ConsensusType::ProofOfWork,
-            proof_of_stake: None,
+            nonces: HashMap::new(),
+            consensus: Box::new(proof_of_work),
             contracts: HashMap::new(),
             contract_metrics: HashMap::new(),
+            event_log: ContractEventLog::new(),
+            receipts: HashMap::new(),
+            htlcs: HashMap::new(),
+            orphan_blocks: Vec::new(),
+            max_orphan_age_secs: DEFAULT_MAX_ORPHAN_AGE_SECS,
+            target_block_time_secs: DEFAULT_TARGET_BLOCK_TIME_SECS,
+            retarget_interval: DEFAULT_RETARGET_INTERVAL,
+            max_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
             state_snapshots: Vec::new(),
+            rollback_target: None,
             state_tree: StateMerkleTree {
                 root: Vec::new(),
                 leaves: HashMap::new(),
             },
             state_lock: Arc::new(Mutex::new(())),
+            event_sender: None,
+            auto_miner_stop: None,
+            read_cache: None,
+            snapshot_pruning: None,
+            address_index: HashMap::new(),
+            balance_changes: Vec::new(),
         };
 
-        // Create and add genesis block
-        let genesis = Block::genesis()?;
         blockchain.add_block(genesis)?;
         
         // Initialize state tree with initial balances
-        blockchain.state_tree.update_state(&blockchain.balances);
+        blockchain.state_tree.update_state_with_contracts(&blockchain.balances, &blockchain.contracts);
 
         info!("Created new PoW blockchain with difficulty {}", difficulty);
         Ok(blockchain)
@@ -198,33 +414,75 @@ impl Blockchain {
     /// 
     /// # Arguments
     /// * `mining_reward` - Reward for validating a block
-    /// * `min_stake` - Minimum stake required to become a validator
+    /// * `min_stake` - Minimum stake required to become a validator, in base units
+    /// * `max_validators` - Maximum number of validators
+    /// 
+    /// # Returns
+    /// * `Result<Blockchain>` - The created blockchain or an error
+    pub fn new_pos(mining_reward: f64, min_stake: u128, max_validators: usize) -> Result<Self> {
+        Self::new_pos_with_validators(mining_reward, min_stake, max_validators, Vec::new())
+    }
+
+    /// Create a new blockchain with PoS consensus and a genesis validator set
+    /// 
+    /// A PoS chain cannot produce a block before it has a validator, so the genesis
+    /// validators' stakes are part of the chain's configuration, like PoW genesis
+    /// allocations. Every later validator joins with a signed registration transaction
+    /// (`Transaction::new_validator_registration`) that moves its stake out of its balance.
+    /// 
+    /// # Arguments
+    /// * `mining_reward` - Reward for validating a block
+    /// * `min_stake` - Minimum stake required to become a validator, in base units
     /// * `max_validators` - Maximum number of validators
+    /// * `validators` - Genesis validators as `(public_key, address, stake)`, stakes in base units
     /// 
     /// # Returns
     /// * `Result<Blockchain>` - The created blockchain or an error
-    pub fn new_pos(mining_reward: f64, min_stake: f64, max_validators: usize) -> Result<Self> {
-        let proof_of_work = ProofOfWork::new(0, 1_000_000)?; // Not used in PoS
-        let proof_of_stake = ProofOfStake::new(min_stake, max_validators, 5.0, 10.0)?;
+    pub fn new_pos_with_validators(
+        mining_reward: f64,
+        min_stake: u128,
+        max_validators: usize,
+        validators: Vec<(String, String, u128)>,
+    ) -> Result<Self> {
+        let mut proof_of_stake = ProofOfStake::new(min_stake, max_validators, 5.0, 10.0)?;
+        for (public_key, address, stake) in validators {
+            proof_of_stake.register_validator_at(public_key, address, stake, 0)?;
+        }
         
         let mut blockchain = Blockchain {
             blocks: Vec::new(),
             pending_transactions: Vec::new(),
             difficulty: 0, // Not used in PoS
             mining_reward,
-            proof_of_work,
+            max_txs_per_block: 0,
+            min_relay_fee: 0.0,
             version: BLOCKCHAIN_VERSION.to_string(),
             balances: HashMap::new(),
-            consensus_type: ConsensusType::ProofOfStake,
-            proof_of_stake: Some(proof_of_stake),
+            nonces: HashMap::new(),
+            consensus: Box::new(proof_of_stake),
             contracts: HashMap::new(),
             contract_metrics: HashMap::new(),
+            event_log: ContractEventLog::new(),
+            receipts: HashMap::new(),
+            htlcs: HashMap::new(),
+            orphan_blocks: Vec::new(),
+            max_orphan_age_secs: DEFAULT_MAX_ORPHAN_AGE_SECS,
+            target_block_time_secs: DEFAULT_TARGET_BLOCK_TIME_SECS,
+            retarget_interval: DEFAULT_RETARGET_INTERVAL,
+            max_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
             state_snapshots: Vec::new(),
+            rollback_target: None,
             state_tree: StateMerkleTree {
                 root: Vec::new(),
                 leaves: HashMap::new(),
             },
             state_lock: Arc::new(Mutex::new(())),
+            event_sender: None,
+            auto_miner_stop: None,
+            read_cache: None,
+            snapshot_pruning: None,
+            address_index: HashMap::new(),
+            balance_changes: Vec::new(),
         };
 
         // Create and add genesis block
@@ -232,12 +490,64 @@ impl Blockchain {
         blockchain.add_block(genesis)?;
         
         // Initialize state tree with initial balances
-        blockchain.state_tree.update_state(&blockchain.balances);
+        blockchain.state_tree.update_state_with_contracts(&blockchain.balances, &blockchain.contracts);
 
         info!("Created new PoS blockchain with min_stake={}, max_validators={}", min_stake, max_validators);
         Ok(blockchain)
     }
 
+    /// Create a new blockchain with PoA consensus
+    /// 
+    /// The authorities produce blocks in turn; call `set_authority_signer` with
+    /// this node's authority key before mining.
+    /// 
+    /// # Arguments
+    /// * `authorities` - Authorized block signers in turn order
+    /// * `mining_reward` - Reward paid to the producing authority
+    /// 
+    /// # Returns
+    /// * `Result<Blockchain>` - The created blockchain or an error
+    pub fn new_poa(authorities: Vec<crypto::PublicKey>, mining_reward: f64) -> Result<Self> {
+        let proof_of_authority = ProofOfAuthority::new(authorities)?;
+        let authority_count = proof_of_authority.authorities.len();
+
+        let mut blockchain = Self::new_pow_from_genesis(0, mining_reward, Block::genesis()?)?;
+        blockchain.consensus = Box::new(proof_of_authority);
+
+        info!("Created new PoA blockchain with {} authorities", authority_count);
+        Ok(blockchain)
+    }
+
+    /// Set the authority key this node seals PoA blocks with
+    /// 
+    /// # Arguments
+    /// * `keypair` - Key pair of one of the chain's authorities
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if set, error if the chain is not PoA or the key is not an authority
+    pub fn set_authority_signer(&mut self, keypair: crypto::KeyPair) -> Result<()> {
+        self.consensus.as_any_mut().downcast_mut::<ProofOfAuthority>()
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                "Cannot set authority signer: blockchain is not using PoA consensus".to_string(),
+            ))?
+            .set_signer(keypair)
+    }
+
+    /// Add a key this node signs PoS blocks with for its validator
+    ///
+    /// # Arguments
+    /// * `keypair` - Key pair of a registered validator
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if added, error if not using PoS or the key is not a validator's
+    pub fn add_validator_signer(&mut self, keypair: crypto::KeyPair) -> Result<()> {
+        self.proof_of_stake_mut()
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                "Cannot add validator signer: blockchain is not using PoS consensus".to_string(),
+            ))?
+            .add_signer(keypair)
+    }
+
     /// Create a blockchain with default settings (PoW)
     /// 
     /// # Returns
@@ -278,40 +588,117 @@ impl Blockchain {
             }
 
             // Validate consensus-specific requirements
-            match self.consensus_type {
-                ConsensusType::ProofOfWork => {
-                    // Validate proof of work
-                    if !self.proof_of_work.validate_hash(&block.hash) {
-                        return Err(BlockchainError::InvalidProofOfWork(
-                            "Block hash does not meet difficulty requirement".to_string(),
-                        ));
-                    }
-                }
-                ConsensusType::ProofOfStake => {
-                    // Validate proof of stake
-                    if let Some(_pos) = &mut self.proof_of_stake {
-                        // In a real implementation, you would verify the validator's signature
-                        // For now, we'll just check that the block has a validator
-                        if block.validator.is_none() {
-                            return Err(BlockchainError::ConsensusError(
-                                "PoS block must have a validator".to_string(),
-                            ));
-                        }
-                    }
-                }
-            }
+            self.consensus().validate_block(&block)?;
         }
 
         // Process transactions with state validation and rollback capability
         self.process_transactions_with_validation(&block)?;
+        self.commit_block(block)
+    }
+
+    /// Append a block whose transactions have already been applied
+    fn commit_block(&mut self, block: Block) -> Result<()> {
+        self.record_validator_reward(&block);
 
         // Add the block to the chain
+        Self::index_block(&mut self.address_index, self.blocks.len(), &block);
         self.blocks.push(block.clone());
+        self.publish_event(EventType::NewBlock, &block);
+
+        // Every node retargets at the same heights from the same timestamps
+        if self.is_retarget_height(block.index) {
+            self.retarget_difficulty()?;
+        }
+        self.relieve_memory_pressure();
 
         info!("Added block {} to blockchain", block.index);
         Ok(())
     }
 
+    /// Accept a block that may arrive before its parent
+    /// 
+    /// A block extending the tip is added and any buffered orphans that now connect
+    /// are added after it, in order. A block whose parent is unknown is validated and
+    /// buffered in the orphan pool; a block whose parent is known but is not the tip
+    /// is a fork and is rejected like in `add_block`. Orphans older than
+    /// `max_orphan_age_secs` are evicted first.
+    /// 
+    /// # Arguments
+    /// * `block` - The received block
+    /// 
+    /// # Returns
+    /// * `Result<usize>` - Number of blocks connected to the chain (0 if buffered)
+    pub fn receive_block(&mut self, block: Block) -> Result<usize> {
+        self.evict_orphans();
+
+        let parent_known = self.blocks.iter().any(|known| known.hash == block.previous_hash);
+        if !parent_known && !block.is_genesis() {
+            block.validate()?;
+            if self.orphan_blocks.iter().any(|orphan| orphan.block.hash == block.hash) {
+                return Ok(0);
+            }
+            if self.orphan_blocks.len() >= MAX_ORPHAN_BLOCKS {
+                let evicted = self.orphan_blocks.remove(0);
+                warn!("Orphan pool full, evicted block {}", evicted.block.index);
+            }
+            debug!("Buffered orphan block {} (parent {})", block.index, block.previous_hash);
+            self.orphan_blocks.push(OrphanBlock {
+                block,
+                received_at: chrono::Utc::now().timestamp(),
+            });
+            return Ok(0);
+        }
+
+        self.add_block(block)?;
+        Ok(1 + self.connect_orphans())
+    }
+
+    /// Add buffered orphans whose parent is now the tip, repeating until none connect
+    fn connect_orphans(&mut self) -> usize {
+        let mut connected = 0;
+        loop {
+            let Some(tip) = self.blocks.last().map(|block| block.hash.clone()) else {
+                return connected;
+            };
+            let Some(position) = self.orphan_blocks.iter().position(|orphan| orphan.block.previous_hash == tip) else {
+                return connected;
+            };
+
+            let orphan = self.orphan_blocks.remove(position);
+            let index = orphan.block.index;
+            match self.add_block(orphan.block) {
+                Ok(()) => {
+                    debug!("Connected orphan block {}", index);
+                    connected += 1;
+                }
+                Err(e) => warn!("Dropping orphan block {}: {}", index, e),
+            }
+        }
+    }
+
+    /// Drop orphans that have been buffered longer than `max_orphan_age_secs`
+    fn evict_orphans(&mut self) {
+        let now = chrono::Utc::now().timestamp();
+        let max_age = self.max_orphan_age_secs as i64;
+        let before = self.orphan_blocks.len();
+        self.orphan_blocks.retain(|orphan| now - orphan.received_at <= max_age);
+        let evicted = before - self.orphan_blocks.len();
+        if evicted > 0 {
+            info!("Evicted {} expired orphan blocks", evicted);
+        }
+    }
+
+    /// Credit a PoS block's validator with the coinbase rewards paid to it
+    fn record_validator_reward(&mut self, block: &Block) {
+        if let (Some(pos), Some(validator)) = (self.consensus.as_any_mut().downcast_mut::<ProofOfStake>(), &block.validator) {
+            let reward: u128 = block.transactions.iter()
+                .filter(|tx| tx.sender == "COINBASE" && &tx.receiver == validator)
+                .map(|tx| tx.amount)
+                .sum();
+            pos.record_reward(validator, reward);
+        }
+    }
+
     /// Process a transaction and update blockchain state
     /// 
     /// # Arguments
@@ -320,20 +707,156 @@ impl Blockchain {
     /// # Returns
     /// * `Result<()>` - Ok if processed successfully, error otherwise
     pub fn process_transaction(&mut self, transaction: &Transaction) -> Result<()> {
-        match transaction.transaction_type {
+        self.execute_transaction(transaction).map(|_| ())
+    }
+
+    /// Process a transaction, returning the contract execution result for contract transactions
+    fn execute_transaction(&mut self, transaction: &Transaction) -> Result<Option<ContractResult>> {
+        let result = match transaction.transaction_type {
             crate::transaction::TransactionType::Transfer => {
-                self.process_transfer_transaction(transaction)?;
+                self.process_transfer_transaction(transaction).map(|_| None)
             }
             crate::transaction::TransactionType::ContractDeploy => {
-                self.process_contract_deploy_transaction(transaction)?;
+                self.process_contract_deploy_transaction(transaction).map(Some)
             }
             crate::transaction::TransactionType::ContractCall => {
-                self.process_contract_call_transaction(transaction)?;
+                self.process_contract_call_transaction(transaction).map(Some)
             }
             crate::transaction::TransactionType::Staking => {
-                self.process_staking_transaction(transaction)?;
+                self.process_staking_transaction(transaction).map(|_| None)
+            }
+            crate::transaction::TransactionType::TimeLocked { unlock_height } => {
+                self.process_time_locked_transaction(transaction, unlock_height).map(|_| None)
+            }
+            crate::transaction::TransactionType::ContractWithdraw => {
+                self.process_contract_withdraw_transaction(transaction).map(|_| None)
+            }
+            crate::transaction::TransactionType::Htlc(_) => {
+                self.process_htlc_transaction(transaction).map(|_| None)
+            }
+        };
+        self.invalidate_cached_reads(transaction);
+        result
+    }
+
+    /// Attach a cache that balance and contract reads may be served from
+    /// 
+    /// Cached entries are invalidated whenever the balance or contract they hold
+    /// changes through this chain's methods. Writes made directly to the public
+    /// state fields bypass the cache and require `CacheManager::invalidate_all`.
+    /// 
+    /// # Arguments
+    /// * `cache` - The cache to keep consistent with this chain
+    pub fn set_read_cache(&mut self, cache: Arc<CacheManager>) {
+        cache.invalidate_all();
+        self.read_cache = Some(cache);
+    }
+
+    /// Prune state snapshots to storage whenever an added block pushes memory use past the mark
+    /// 
+    /// Snapshots pruned this way are reloaded from storage when rolling back to them.
+    /// 
+    /// # Arguments
+    /// * `pruning` - The optimizer deciding what to prune and the storage to prune to
+    pub fn set_snapshot_pruning(&mut self, pruning: SnapshotPruning) {
+        self.snapshot_pruning = Some(pruning);
+    }
+
+    /// Prune state snapshots if memory use is past the configured mark
+    fn relieve_memory_pressure(&mut self) {
+        let Some(pruning) = self.snapshot_pruning.clone() else {
+            return;
+        };
+        // The block is already part of the chain, so a failed prune only keeps snapshots in memory
+        if let Err(e) = pruning.optimizer.relieve_pressure(self, &pruning.storage) {
+            warn!("Failed to prune state snapshots: {}", e);
+        }
+    }
+
+    /// Drop the cached reads a transaction may have made stale
+    ///
+    /// Contract execution can write to any contract it calls, so every cached contract
+    /// is dropped after a deployment or call.
+    fn invalidate_cached_reads(&self, transaction: &Transaction) {
+        let Some(cache) = &self.read_cache else {
+            return;
+        };
+        cache.invalidate(&performance::balance_cache_key(&transaction.sender));
+        cache.invalidate(&performance::balance_cache_key(&transaction.receiver));
+        match transaction.transaction_type {
+            crate::transaction::TransactionType::ContractDeploy | crate::transaction::TransactionType::ContractCall => {
+                cache.invalidate_prefix(performance::CONTRACT_CACHE_PREFIX);
             }
+            crate::transaction::TransactionType::ContractWithdraw => {
+                cache.invalidate(&performance::contract_cache_key(&transaction.receiver));
+            }
+            _ => {}
+        }
+    }
+
+    /// Fail with `InsufficientBalance` unless `address` holds at least `amount` base units
+    fn check_balance(balances: &HashMap<String, u128>, address: &str, amount: u128) -> Result<()> {
+        let balance = balances.get(address).copied().unwrap_or(0);
+        if balance < amount {
+            return Err(BlockchainError::InsufficientBalance {
+                address: address.to_string(),
+                balance: units::from_base_units(balance),
+                required: units::from_base_units(amount),
+            });
+        }
+        Ok(())
+    }
+
+    /// Remove `amount` base units from `address`
+    fn debit(balances: &mut HashMap<String, u128>, address: &str, amount: u128) -> Result<()> {
+        Self::check_balance(balances, address, amount)?;
+        *balances.entry(address.to_string()).or_insert(0) -= amount;
+        Ok(())
+    }
+
+    /// Add `amount` base units to `address`
+    fn credit(balances: &mut HashMap<String, u128>, address: &str, amount: u128) -> Result<()> {
+        let balance = balances.entry(address.to_string()).or_insert(0);
+        *balance = balance.checked_add(amount).ok_or_else(|| BlockchainError::InvalidState(format!(
+            "Balance of {} would overflow", address
+        )))?;
+        Ok(())
+    }
+
+    /// Amount plus the maximum gas fee of a contract call, in base units
+    pub(crate) fn contract_call_cost(transaction: &Transaction) -> Result<u128> {
+        let gas_cost = transaction.gas_limit.unwrap_or(1000000) as f64 *
+                      transaction.gas_price.unwrap_or(0.000001);
+        transaction.amount
+            .checked_add(units::to_base_units(gas_cost)?)
+            .ok_or_else(|| BlockchainError::InvalidInput("Contract call cost overflows".to_string()))
+    }
+
+    /// Process a time-locked transfer once the chain has reached its unlock height
+    ///
+    /// The transfer must be signed by its sender, otherwise the block is invalid.
+    fn process_time_locked_transaction(&mut self, transaction: &Transaction, unlock_height: u64) -> Result<()> {
+        if !transaction.is_signed_by_sender()? {
+            return Err(BlockchainError::TransactionValidationFailed(format!(
+                "Time-locked transaction {} is not signed by {}", transaction.id, transaction.sender
+            )));
+        }
+
+        // The block being processed has not been appended yet
+        let height = self.blocks.len() as u64;
+        if height < unlock_height {
+            return Err(BlockchainError::TransactionValidationFailed(format!(
+                "Transaction {} is locked until height {} (block height {})",
+                transaction.id, unlock_height, height
+            )));
         }
+
+        let amount = transaction.amount;
+        Self::debit(&mut self.balances, &transaction.sender, amount)?;
+        Self::credit(&mut self.balances, &transaction.receiver, amount)?;
+
+        debug!("Processed time-locked transaction: {} -> {}: {}",
+               transaction.sender, transaction.receiver, transaction.amount);
         Ok(())
     }
 
@@ -342,25 +865,31 @@ impl Blockchain {
         // Handle coinbase transactions (mining rewards)
         if transaction.sender == "COINBASE" {
             // Add to receiver balance (mining reward)
-            *self.balances.entry(transaction.receiver.clone()).or_insert(0.0) += transaction.amount;
+            Self::credit(&mut self.balances, &transaction.receiver, transaction.amount)?;
             debug!("Processed coinbase transaction: {} -> {}: {}", 
                    transaction.sender, transaction.receiver, transaction.amount);
             return Ok(());
         }
 
-        // Check sender balance for regular transactions
-        let sender_balance = self.balances.get(&transaction.sender).unwrap_or(&0.0);
-        if *sender_balance < transaction.amount {
-            return Err(BlockchainError::InsufficientBalance {
-                address: transaction.sender.clone(),
-                balance: *sender_balance,
-                required: transaction.amount,
-            });
+        // Reject forged, replayed or out-of-order transactions
+        if !transaction.is_signed_by_sender()? {
+            return Err(BlockchainError::TransactionValidationFailed(format!(
+                "Transfer {} is not signed by {}", transaction.id, transaction.sender
+            )));
+        }
+        let expected_nonce = self.get_nonce(&transaction.sender);
+        if transaction.nonce != expected_nonce {
+            return Err(BlockchainError::TransactionValidationFailed(format!(
+                "Invalid nonce {} for {}: expected {}",
+                transaction.nonce, transaction.sender, expected_nonce
+            )));
         }
 
-        // Update balances for regular transactions
-        *self.balances.entry(transaction.sender.clone()).or_insert(0.0) -= transaction.amount;
-        *self.balances.entry(transaction.receiver.clone()).or_insert(0.0) += transaction.amount;
+        // Move the amount, failing if the sender cannot cover it
+        let amount = transaction.amount;
+        Self::debit(&mut self.balances, &transaction.sender, amount)?;
+        Self::credit(&mut self.balances, &transaction.receiver, amount)?;
+        self.nonces.insert(transaction.sender.clone(), expected_nonce + 1);
 
         debug!("Processed transfer transaction: {} -> {}: {}", 
                transaction.sender, transaction.receiver, transaction.amount);
@@ -368,16 +897,17 @@ impl Blockchain {
     }
 
     /// Process a contract deployment transaction
-    fn process_contract_deploy_transaction(&mut self, transaction: &Transaction) -> Result<()> {
+    fn process_contract_deploy_transaction(&mut self, transaction: &Transaction) -> Result<ContractResult> {
         let contract_code = transaction.contract_code.as_ref()
             .ok_or_else(|| BlockchainError::ContractValidationFailed(
                 "Contract deployment transaction must have contract code".to_string(),
             ))?;
 
         // Create the smart contract
-        let mut contract = SmartContract::new(
+        let mut contract = SmartContract::with_nonce(
             contract_code.clone(),
             transaction.sender.clone(),
+            self.deployment_nonce(&transaction.sender),
         )?;
 
         // Execute the contract to initialize it
@@ -388,30 +918,31 @@ impl Blockchain {
             contract.id.clone(),
         );
         
-        match contract.execute(context) {
+        match contract.execute_with_contracts(context, &mut self.contracts) {
             Ok(result) => {
                 // Store the contract
                 let contract_id = contract.id.clone();
                 let gas_used = result.gas_used;
+                self.publish_event(EventType::ContractDeployed, &contract);
                 self.contracts.insert(contract_id.clone(), contract);
+                self.event_log.record(&result.events);
                 
                 // Update metrics
                 *self.contract_metrics.entry("deployments".to_string()).or_insert(0) += 1;
                 *self.contract_metrics.entry("gas_used".to_string()).or_insert(0) += gas_used;
                 
                 debug!("Deployed contract: {} with gas used: {}", contract_id, gas_used);
+                Ok(result)
             }
             Err(e) => {
                 error!("Contract deployment failed: {}", e);
-                return Err(BlockchainError::ContractExecutionError(e.to_string()));
+                Err(BlockchainError::ContractExecutionError(e.to_string()))
             }
         }
-
-        Ok(())
     }
 
     /// Process a contract call transaction
-    fn process_contract_call_transaction(&mut self, transaction: &Transaction) -> Result<()> {
+    fn process_contract_call_transaction(&mut self, transaction: &Transaction) -> Result<ContractResult> {
         let contract_address = &transaction.receiver;
         let contract_data = transaction.contract_data.as_ref()
             .ok_or_else(|| BlockchainError::ContractValidationFailed(
@@ -419,116 +950,580 @@ impl Blockchain {
             ))?;
 
         // Get the contract
-        let contract = self.contracts.get_mut(contract_address)
+        let contract = self.contracts.get(contract_address)
             .ok_or_else(|| BlockchainError::ContractValidationFailed(
                 format!("Contract not found: {}", contract_address),
             ))?;
+        if !contract.active {
+            return Err(BlockchainError::ContractValidationFailed(
+                "Contract is not active".to_string(),
+            ));
+        }
 
         // Check sender balance for the call
-        let sender_balance = self.balances.get(&transaction.sender).unwrap_or(&0.0);
-        let gas_cost = transaction.gas_limit.unwrap_or(1000000) as f64 * 
-                      transaction.gas_price.unwrap_or(0.000001);
-        let total_cost = transaction.amount + gas_cost;
-
-        if *sender_balance < total_cost {
-            return Err(BlockchainError::InsufficientBalance {
-                address: transaction.sender.clone(),
-                balance: *sender_balance,
-                required: total_cost,
-            });
-        }
+        let total_cost = Self::contract_call_cost(transaction)?;
+        Self::check_balance(&self.balances, &transaction.sender, total_cost)?;
 
-        // Create execution context
-        let mut context = ContractContext::new(
+        // Execute the contract with access to other deployed contracts for CALL
+        match Self::run_contract_call(
+            &mut self.contracts,
             self.blocks.len() as u64,
+            &transaction.sender,
+            contract_address,
+            contract_data,
+            transaction.amount,
             transaction.gas_limit.unwrap_or(1000000),
-            transaction.sender.clone(),
-            contract_address.clone(),
-        );
-        context.add_transaction_data("sender".to_string(), transaction.sender.clone()).unwrap();
-        context.add_transaction_data("amount".to_string(), transaction.amount.to_string()).unwrap();
-        context.add_transaction_data("data".to_string(), contract_data.clone()).unwrap();
-
-        // Execute the contract
-        match contract.execute(context) {
+        ) {
             Ok(result) => {
+                let contract = self.contracts.get_mut(contract_address)
+                    .ok_or_else(|| BlockchainError::ContractValidationFailed(
+                        format!("Contract not found: {}", contract_address),
+                    ))?;
+                for (key, value) in &result.storage_changes {
+                    contract.storage.insert(key.clone(), value.clone());
+                }
+
                 // Update balances
-                *self.balances.entry(transaction.sender.clone()).or_insert(0.0) -= total_cost;
                 contract.add_funds(transaction.amount)?;
+                Self::debit(&mut self.balances, &transaction.sender, total_cost)?;
+
+                // Record emitted events
+                self.event_log.record(&result.events);
+                self.publish_event(EventType::ContractCalled, &ContractCallEvent {
+                    contract: contract_address.clone(),
+                    caller: transaction.sender.clone(),
+                    result: result.clone(),
+                });
                 
                 // Update metrics
                 *self.contract_metrics.entry("calls".to_string()).or_insert(0) += 1;
                 *self.contract_metrics.entry("gas_used".to_string()).or_insert(0) += result.gas_used;
                 
                 debug!("Executed contract: {} with gas used: {}", contract_address, result.gas_used);
+                Ok(result)
+            }
+            Err(BlockchainError::ContractReverted { error, gas_used }) => {
+                // The caller still pays for the gas the failed execution consumed
+                let gas_cost = gas_used as f64 * transaction.gas_price.unwrap_or(0.000001);
+                Self::debit(&mut self.balances, &transaction.sender, units::to_base_units(gas_cost)?)?;
+                *self.contract_metrics.entry("gas_used".to_string()).or_insert(0) += gas_used;
+
+                error!("Contract execution failed: {}", error);
+                Err(BlockchainError::ContractReverted { error, gas_used })
             }
             Err(e) => {
                 error!("Contract execution failed: {}", e);
-                return Err(BlockchainError::ContractExecutionError(e.to_string()));
+                Err(BlockchainError::ContractExecutionError(e.to_string()))
             }
         }
-
-        Ok(())
     }
 
-    /// Process a staking transaction
-    fn process_staking_transaction(&mut self, transaction: &Transaction) -> Result<()> {
-        if let Some(pos) = &mut self.proof_of_stake {
-            // Create staking transaction for PoS
-            let staking_tx = crate::consensus::StakingTransaction::new(
-                transaction.sender.clone(),
-                transaction.amount,
-                transaction.contract_data.as_ref().map(|d| d == "stake").unwrap_or(true),
-            )?;
-
-            pos.process_staking_transaction(staking_tx)?;
+    /// Execute a contract call against `contracts` without touching balances or metrics
+    ///
+    /// The first whitespace-separated token of `data` selects the contract method to run.
+    /// A failed execution is reported as `ContractReverted` with the gas it consumed;
+    /// storage writes made by nested calls have already been undone.
+    fn run_contract_call(
+        contracts: &mut HashMap<String, SmartContract>,
+        block_height: u64,
+        sender: &str,
+        contract_address: &str,
+        data: &str,
+        amount: u128,
+        gas_limit: u64,
+    ) -> Result<crate::smart_contract::ContractResult> {
+        let contract = contracts.get(contract_address)
+            .ok_or_else(|| BlockchainError::ContractValidationFailed(
+                format!("Contract not found: {}", contract_address),
+            ))?;
+        if !contract.active {
+            return Err(BlockchainError::ContractValidationFailed(
+                "Contract is not active".to_string(),
+            ));
         }
+        let contract_code = contract.entry_point(data.split_whitespace().next())?;
+        let contract_storage = contract.storage.clone();
 
-        Ok(())
+        let mut context = ContractContext::new(
+            block_height,
+            gas_limit,
+            sender.to_string(),
+            contract_address.to_string(),
+        );
+        context.add_transaction_data("sender".to_string(), sender.to_string())?;
+        context.add_transaction_data("amount".to_string(), units::format_units(amount))?;
+        context.add_transaction_data("data".to_string(), data.to_string())?;
+
+        let mut vm = ContractVM::new(gas_limit, None);
+        vm.load_storage(&contract_storage);
+        vm.execute_with_contracts(&contract_code, &context, contracts)
+            .map_err(|e| BlockchainError::ContractReverted { error: e.to_string(), gas_used: vm.gas_used() })
     }
 
-    /// Deploy a smart contract
-    /// 
+    /// Estimate the gas a contract call will need
+    ///
+    /// Dry-runs the call against a copy of the current contract state, so nothing is
+    /// committed, and adds a safety margin to the gas consumed.
+    ///
     /// # Arguments
-    /// * `sender` - The sender's address
-    /// * `contract_code` - The contract code
-    /// * `gas_limit` - Gas limit for deployment
-    /// * `gas_price` - Gas price for deployment
-    /// 
+    /// * `sender` - The caller's address
+    /// * `contract_address` - The contract's address
+    /// * `data` - Data to pass to the contract
+    /// * `amount` - Amount to send with the call, in base units
+    ///
     /// # Returns
-    /// * `Result<String>` - Contract address or error
-    pub fn deploy_contract(
-        &mut self,
-        sender: String,
-        contract_code: String,
-        gas_limit: u64,
-        gas_price: f64,
-    ) -> Result<String> {
-        let transaction = Transaction::new_contract_deploy(
-            sender,
-            contract_code,
-            gas_limit,
-            gas_price,
-        )?;
+    /// * `Result<u64>` - Suggested gas limit, or the error the call would fail with
+    pub fn estimate_gas(&self, sender: &str, contract_address: &str, data: &str, amount: u128) -> Result<u64> {
+        if amount == 0 {
+            return Err(BlockchainError::ContractValidationFailed(
+                "Amount must be positive".to_string(),
+            ));
+        }
+        Self::check_balance(&self.balances, sender, amount)?;
 
-        // Create the contract first to get its ID
-        let contract = SmartContract::new(transaction.contract_code.clone().unwrap(), transaction.sender.clone())?;
-        let contract_id = contract.id.clone();
+        let mut contracts = self.contracts.clone();
+        let result = Self::run_contract_call(
+            &mut contracts,
+            self.blocks.len() as u64,
+            sender,
+            contract_address,
+            data,
+            amount,
+            MAX_ESTIMATE_GAS,
+        ).map_err(|e| BlockchainError::ContractExecutionError(e.to_string()))?;
 
-        self.process_contract_deploy_transaction(&transaction)?;
-        
-        Ok(contract_id)
+        let margin = result.gas_used * GAS_ESTIMATE_MARGIN_PERCENT / 100;
+        Ok((result.gas_used + margin).max(1))
     }
 
-    /// Call a smart contract
-    /// 
+    /// Call a read-only contract method
+    ///
+    /// Runs a method declared `METHOD <name> VIEW` against a copy of the current state
+    /// without a transaction or gas payment. Nothing is committed: storage, balances
+    /// and contract metrics are left untouched.
+    ///
     /// # Arguments
-    /// * `sender` - The sender's address
     /// * `contract_address` - The contract's address
-    /// * `contract_data` - Data to pass to the contract
-    /// * `amount` - Amount to send with the call
-    /// * `gas_limit` - Gas limit for execution
-    /// * `gas_price` - Gas price for execution
+    /// * `method` - The view method to call
+    /// * `params` - Arguments pushed onto the stack before the method runs
+    ///
+    /// # Returns
+    /// * `Result<Vec<u8>>` - The method's return value, empty if it returned nothing
+    pub fn call_view(&self, contract_address: &str, method: &str, params: &[String]) -> Result<Vec<u8>> {
+        let contract = self.contracts.get(contract_address)
+            .ok_or_else(|| BlockchainError::NotFound(
+                format!("Contract not found: {}", contract_address),
+            ))?;
+        if !contract.active {
+            return Err(BlockchainError::ContractValidationFailed(
+                "Contract is not active".to_string(),
+            ));
+        }
+        if !contract.is_view(method) {
+            return Err(BlockchainError::ContractValidationFailed(
+                format!("Method {} is not a view method", method),
+            ));
+        }
+        let contract_code = contract.entry_point(Some(method))?;
+
+        let context = ContractContext::new(
+            self.blocks.len() as u64,
+            MAX_ESTIMATE_GAS,
+            contract.owner.clone(),
+            contract_address.to_string(),
+        );
+        let mut contracts = self.contracts.clone();
+        let mut vm = ContractVM::new(MAX_ESTIMATE_GAS, None);
+        vm.load_storage(&contract.storage);
+        vm.push_arguments(params);
+        let result = vm.execute_with_contracts(&contract_code, &context, &mut contracts)?;
+
+        if !result.storage_changes.is_empty() {
+            return Err(BlockchainError::ContractExecutionError(
+                format!("View method {} attempted to modify storage", method),
+            ));
+        }
+
+        Ok(result.return_value.map(String::into_bytes).unwrap_or_default())
+    }
+
+    /// Preview the balance changes a transaction would cause
+    ///
+    /// Applies the transaction to a copy of the current balances (dry-running contract
+    /// calls against a copy of contract state) and returns the new balance of every
+    /// account it touches. Real state is never mutated. Deployments and unstaking do not
+    /// move balances, so they yield an empty map.
+    ///
+    /// # Arguments
+    /// * `tx` - The transaction to simulate
+    ///
+    /// # Returns
+    /// * `Result<HashMap<String, u128>>` - Resulting balances of the affected accounts in
+    ///   base units, or the error the transaction would fail with
+    pub fn simulate_transaction(&self, tx: &Transaction) -> Result<HashMap<String, u128>> {
+        let mut balances = self.balances.clone();
+        let mut changed = HashMap::new();
+
+        match tx.transaction_type {
+            crate::transaction::TransactionType::Transfer
+            | crate::transaction::TransactionType::TimeLocked { .. } => {
+                let amount = tx.amount;
+                if tx.sender != "COINBASE" {
+                    Self::debit(&mut balances, &tx.sender, amount)?;
+                    changed.insert(tx.sender.clone(), balances[&tx.sender]);
+                }
+                Self::credit(&mut balances, &tx.receiver, amount)?;
+                changed.insert(tx.receiver.clone(), balances[&tx.receiver]);
+            }
+            crate::transaction::TransactionType::ContractCall => {
+                let contract_data = tx.contract_data.as_ref()
+                    .ok_or_else(|| BlockchainError::ContractValidationFailed(
+                        "Contract call transaction must have contract data".to_string(),
+                    ))?;
+                let gas_limit = tx.gas_limit.unwrap_or(1000000);
+                let total_cost = Self::contract_call_cost(tx)?;
+                Self::debit(&mut balances, &tx.sender, total_cost)?;
+
+                let mut contracts = self.contracts.clone();
+                Self::run_contract_call(
+                    &mut contracts,
+                    self.blocks.len() as u64,
+                    &tx.sender,
+                    &tx.receiver,
+                    contract_data,
+                    tx.amount,
+                    gas_limit,
+                ).map_err(|e| BlockchainError::ContractExecutionError(e.to_string()))?;
+
+                changed.insert(tx.sender.clone(), balances[&tx.sender]);
+                changed.insert(tx.receiver.clone(), contracts[&tx.receiver].balance.saturating_add(tx.amount));
+            }
+            crate::transaction::TransactionType::ContractWithdraw => {
+                let mut contract = self.contracts.get(&tx.receiver)
+                    .ok_or_else(|| BlockchainError::ContractExecutionError(
+                        format!("Contract {} not found", tx.receiver),
+                    ))?
+                    .clone();
+                contract.withdraw_funds(tx.amount, &tx.sender)
+                    .map_err(|e| BlockchainError::ContractExecutionError(e.to_string()))?;
+                Self::credit(&mut balances, &tx.sender, tx.amount)?;
+
+                changed.insert(tx.sender.clone(), balances[&tx.sender]);
+                changed.insert(tx.receiver.clone(), contract.balance);
+            }
+            crate::transaction::TransactionType::Staking => {
+                if tx.debits_sender() {
+                    Self::debit(&mut balances, &tx.sender, tx.amount)?;
+                    changed.insert(tx.sender.clone(), balances[&tx.sender]);
+                } else if tx.staking_action() == Some(crate::transaction::STAKING_WITHDRAW) {
+                    Self::credit(&mut balances, &tx.sender, tx.amount)?;
+                    changed.insert(tx.sender.clone(), balances[&tx.sender]);
+                }
+            }
+            crate::transaction::TransactionType::Htlc(ref action) => {
+                let settled = match action {
+                    HtlcAction::Lock { .. } => {
+                        Self::debit(&mut balances, &tx.sender, tx.amount)?;
+                        changed.insert(tx.sender.clone(), balances[&tx.sender]);
+                        None
+                    }
+                    HtlcAction::Redeem { htlc_id, .. } => Some((htlc_id, &tx.receiver)),
+                    HtlcAction::Refund { htlc_id } => Some((htlc_id, &tx.sender)),
+                };
+                if let Some((htlc_id, payee)) = settled {
+                    let htlc = self.htlcs.get(htlc_id)
+                        .filter(|htlc| htlc.status == HtlcStatus::Locked)
+                        .ok_or_else(|| BlockchainError::NotFound(format!("No locked HTLC {}", htlc_id)))?;
+                    Self::credit(&mut balances, payee, htlc.amount)?;
+                    changed.insert(payee.clone(), balances[payee]);
+                }
+            }
+            crate::transaction::TransactionType::ContractDeploy => {}
+        }
+
+        Ok(changed)
+    }
+
+    /// Process a contract withdrawal transaction
+    ///
+    /// The withdrawal must be signed by its sender and carry the sender's next nonce,
+    /// otherwise the block is invalid. A withdrawal by someone other than the owner, or
+    /// beyond the contract's balance, fails as a contract execution error so the block
+    /// still applies; its nonce is consumed either way.
+    fn process_contract_withdraw_transaction(&mut self, transaction: &Transaction) -> Result<()> {
+        if !transaction.is_signed_by_sender()? {
+            return Err(BlockchainError::TransactionValidationFailed(format!(
+                "Contract withdrawal {} is not signed by {}", transaction.id, transaction.sender
+            )));
+        }
+        let expected_nonce = self.get_nonce(&transaction.sender);
+        if transaction.nonce != expected_nonce {
+            return Err(BlockchainError::TransactionValidationFailed(format!(
+                "Invalid nonce {} for {}: expected {}",
+                transaction.nonce, transaction.sender, expected_nonce
+            )));
+        }
+        self.nonces.insert(transaction.sender.clone(), expected_nonce + 1);
+
+        let amount = transaction.amount;
+        let contract = self.contracts.get_mut(&transaction.receiver)
+            .ok_or_else(|| BlockchainError::ContractExecutionError(
+                format!("Contract {} not found", transaction.receiver),
+            ))?;
+        contract.withdraw_funds(transaction.amount, &transaction.sender)
+            .map_err(|e| BlockchainError::ContractExecutionError(e.to_string()))?;
+        Self::credit(&mut self.balances, &transaction.sender, amount)?;
+
+        info!("{} withdrew {} from contract {}", transaction.sender, transaction.amount, transaction.receiver);
+        Ok(())
+    }
+
+    /// Process a staking transaction
+    ///
+    /// The transaction must be signed by its sender and carry the sender's next nonce,
+    /// otherwise the block is invalid. Registering and staking move the amount from the
+    /// sender's balance into the stake of the validator at the sender's address, and a
+    /// registered validator signs blocks with the key the registration was signed with.
+    /// Unstaking moves the amount from the stake into the unbonding period, and withdrawing
+    /// credits unstaked tokens whose unbonding period has passed back to the balance. An
+    /// operation the PoS rules reject fails as a consensus error so the block still
+    /// applies; its nonce is consumed either way.
+    fn process_staking_transaction(&mut self, transaction: &Transaction) -> Result<()> {
+        use crate::transaction::{STAKING_REGISTER, STAKING_STAKE, STAKING_UNSTAKE, STAKING_WITHDRAW};
+
+        if !transaction.is_signed_by_sender()? {
+            return Err(BlockchainError::TransactionValidationFailed(format!(
+                "Staking transaction {} is not signed by {}", transaction.id, transaction.sender
+            )));
+        }
+        let expected_nonce = self.get_nonce(&transaction.sender);
+        if transaction.nonce != expected_nonce {
+            return Err(BlockchainError::TransactionValidationFailed(format!(
+                "Invalid nonce {} for {}: expected {}",
+                transaction.nonce, transaction.sender, expected_nonce
+            )));
+        }
+        self.nonces.insert(transaction.sender.clone(), expected_nonce + 1);
+
+        let amount = transaction.amount;
+        if transaction.debits_sender() {
+            Self::check_balance(&self.balances, &transaction.sender, amount)?;
+        }
+
+        // Height of the block being applied
+        let height = self.blocks.len() as u64;
+        let public_key = transaction.get_signer_public_key().unwrap_or_default();
+        let pos = self.proof_of_stake_mut()
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                "Proof of Stake not enabled".to_string(),
+            ))?;
+        match transaction.staking_action() {
+            Some(STAKING_REGISTER) => {
+                pos.register_validator_at(public_key, transaction.sender.clone(), transaction.amount, height)?;
+            }
+            Some(STAKING_STAKE) => {
+                pos.validators.get_mut(&transaction.sender)
+                    .ok_or_else(|| BlockchainError::ConsensusError(
+                        format!("Validator {} is not registered", transaction.sender),
+                    ))?
+                    .add_stake(transaction.amount)?;
+            }
+            Some(STAKING_UNSTAKE) => {
+                pos.unstake(&transaction.sender, transaction.amount, height)?;
+            }
+            Some(STAKING_WITHDRAW) => {
+                pos.withdraw_unbonded(&transaction.sender, transaction.amount, height)?;
+                Self::credit(&mut self.balances, &transaction.sender, amount)?;
+            }
+            action => {
+                return Err(BlockchainError::ConsensusError(
+                    format!("Unknown staking action {:?}", action),
+                ));
+            }
+        }
+        if transaction.debits_sender() {
+            Self::debit(&mut self.balances, &transaction.sender, amount)?;
+        }
+
+        info!("Processed {} of {} for validator {}",
+              transaction.staking_action().unwrap_or_default(), transaction.amount, transaction.sender);
+        Ok(())
+    }
+
+    /// Process an HTLC transaction
+    ///
+    /// A lock must be signed by its sender, carry the sender's next nonce and be covered
+    /// by the sender's balance, otherwise the block is invalid; it moves the amount into
+    /// an HTLC whose ID is the transaction's ID. A redemption or refund must name the
+    /// HTLC's sender, receiver and amount. One the HTLC rules reject (unknown or settled
+    /// HTLC, wrong preimage, redemption at or refund below the timeout height) fails as
+    /// an HTLC error so the block still applies.
+    fn process_htlc_transaction(&mut self, transaction: &Transaction) -> Result<()> {
+        // Height of the block being applied
+        let height = self.blocks.len() as u64;
+        let action = transaction.htlc_action()
+            .ok_or_else(|| BlockchainError::TransactionValidationFailed(format!(
+                "Transaction {} is not an HTLC transaction", transaction.id
+            )))?;
+
+        let (htlc_id, preimage) = match action {
+            HtlcAction::Lock { hashlock, timeout_height } => {
+                if !transaction.is_signed_by_sender()? {
+                    return Err(BlockchainError::TransactionValidationFailed(format!(
+                        "HTLC lock {} is not signed by {}", transaction.id, transaction.sender
+                    )));
+                }
+                let expected_nonce = self.get_nonce(&transaction.sender);
+                if transaction.nonce != expected_nonce {
+                    return Err(BlockchainError::TransactionValidationFailed(format!(
+                        "Invalid nonce {} for {}: expected {}",
+                        transaction.nonce, transaction.sender, expected_nonce
+                    )));
+                }
+                if self.htlcs.contains_key(&transaction.id) {
+                    return Err(BlockchainError::TransactionValidationFailed(format!(
+                        "HTLC {} already exists", transaction.id
+                    )));
+                }
+                let htlc = Htlc::new(
+                    transaction.id.clone(),
+                    transaction.sender.clone(),
+                    transaction.receiver.clone(),
+                    transaction.amount,
+                    hashlock.clone(),
+                    *timeout_height,
+                    height,
+                ).map_err(|e| BlockchainError::TransactionValidationFailed(e.to_string()))?;
+
+                Self::debit(&mut self.balances, &htlc.sender, transaction.amount)?;
+                self.nonces.insert(transaction.sender.clone(), expected_nonce + 1);
+                info!("Locked {} from {} in HTLC {} until height {}", htlc.amount, htlc.sender, htlc.id, htlc.timeout_height);
+                self.htlcs.insert(htlc.id.clone(), htlc);
+                return Ok(());
+            }
+            HtlcAction::Redeem { htlc_id, preimage } => (htlc_id, Some(preimage)),
+            HtlcAction::Refund { htlc_id } => (htlc_id, None),
+        };
+
+        let htlc = self.locked_htlc(htlc_id)?;
+        if transaction.sender != htlc.sender || transaction.receiver != htlc.receiver || transaction.amount != htlc.amount {
+            return Err(BlockchainError::HtlcError(format!(
+                "Transaction {} does not match the parties and amount of HTLC {}", transaction.id, htlc_id
+            )));
+        }
+        let payee = match preimage {
+            Some(preimage) => {
+                if htlc.is_expired(height) {
+                    return Err(BlockchainError::HtlcError(format!(
+                        "HTLC {} expired at height {}", htlc_id, htlc.timeout_height
+                    )));
+                }
+                if !htlc.matches_preimage(preimage.as_bytes()) {
+                    return Err(BlockchainError::HtlcError(format!(
+                        "Preimage does not match the hashlock of HTLC {}", htlc_id
+                    )));
+                }
+                htlc.status = HtlcStatus::Redeemed;
+                htlc.preimage = Some(preimage.clone());
+                htlc.receiver.clone()
+            }
+            None => {
+                if !htlc.is_expired(height) {
+                    return Err(BlockchainError::HtlcError(format!(
+                        "HTLC {} cannot be refunded before height {} (block height {})",
+                        htlc_id, htlc.timeout_height, height
+                    )));
+                }
+                htlc.status = HtlcStatus::Refunded;
+                htlc.sender.clone()
+            }
+        };
+        Self::credit(&mut self.balances, &payee, transaction.amount)?;
+
+        info!("Settled HTLC {}: {} paid to {}", htlc_id, transaction.amount, payee);
+        Ok(())
+    }
+
+    /// Deploy a smart contract
+    /// 
+    /// # Arguments
+    /// * `sender` - The sender's address
+    /// * `contract_code` - The contract code
+    /// * `gas_limit` - Gas limit for deployment
+    /// * `gas_price` - Gas price for deployment
+    /// 
+    /// # Returns
+    /// * `Result<String>` - Contract address or error
+    pub fn deploy_contract(
+        &mut self,
+        sender: String,
+        contract_code: String,
+        gas_limit: u64,
+        gas_price: f64,
+    ) -> Result<String> {
+        self.deploy_contract_with_gas(sender, contract_code, gas_limit, gas_price)
+            .map(|(contract_id, _)| contract_id)
+    }
+
+    /// Deploy a smart contract and report the gas consumed by its initialization
+    ///
+    /// # Arguments
+    /// * `sender` - The sender's address
+    /// * `contract_code` - The contract code
+    /// * `gas_limit` - Gas limit for deployment
+    /// * `gas_price` - Gas price for deployment
+    ///
+    /// # Returns
+    /// * `Result<(String, u64)>` - Contract address and gas used, or error
+    pub fn deploy_contract_with_gas(
+        &mut self,
+        sender: String,
+        contract_code: String,
+        gas_limit: u64,
+        gas_price: f64,
+    ) -> Result<(String, u64)> {
+        let transaction = Transaction::new_contract_deploy(
+            sender,
+            contract_code,
+            gas_limit,
+            gas_price,
+        )?;
+
+        let contract_id = SmartContract::generate_id(
+            transaction.contract_code.as_deref().unwrap(),
+            &transaction.sender,
+            self.deployment_nonce(&transaction.sender),
+        );
+
+        let result = self.process_contract_deploy_transaction(&transaction);
+        self.invalidate_cached_reads(&transaction);
+
+        Ok((contract_id, result?.gas_used))
+    }
+
+    /// Get the number of contracts an address has deployed
+    /// 
+    /// This is the deployment nonce that goes into the address of the sender's
+    /// next contract.
+    /// 
+    /// # Arguments
+    /// * `address` - The deployer's address
+    /// 
+    /// # Returns
+    /// * `u64` - Number of contracts owned by the address
+    pub fn deployment_nonce(&self, address: &str) -> u64 {
+        self.contracts.values().filter(|contract| contract.owner == address).count() as u64
+    }
+
+    /// Call a smart contract
+    /// 
+    /// # Arguments
+    /// * `sender` - The sender's address
+    /// * `contract_address` - The contract's address
+    /// * `contract_data` - Data to pass to the contract
+    /// * `amount` - Amount to send with the call, in base units
+    /// * `gas_limit` - Gas limit for execution
+    /// * `gas_price` - Gas price for execution
     /// 
     /// # Returns
     /// * `Result<()>` - Ok if successful, error otherwise
@@ -537,7 +1532,7 @@ impl Blockchain {
         sender: String,
         contract_address: String,
         contract_data: String,
-        amount: f64,
+        amount: u128,
         gas_limit: u64,
         gas_price: f64,
     ) -> Result<()> {
@@ -550,27 +1545,9 @@ impl Blockchain {
             gas_price,
         )?;
 
-        self.process_contract_call_transaction(&transaction)
-    }
-
-    /// Register a validator for PoS consensus
-    /// 
-    /// # Arguments
-    /// * `public_key` - Validator's public key
-    /// * `address` - Validator's address
-    /// * `stake_amount` - Amount to stake
-    /// 
-    /// # Returns
-    /// * `Result<()>` - Ok if registered successfully, error otherwise
-    pub fn register_validator(&mut self, public_key: String, address: String, stake_amount: f64) -> Result<()> {
-        if let Some(pos) = &mut self.proof_of_stake {
-            pos.register_validator(public_key, address, stake_amount)?;
-        } else {
-            return Err(BlockchainError::ConsensusError(
-                "Cannot register validator: blockchain is not using PoS consensus".to_string(),
-            ));
-        }
-        Ok(())
+        let result = self.process_contract_call_transaction(&transaction);
+        self.invalidate_cached_reads(&transaction);
+        result.map(|_| ())
     }
 
     /// Select the next validator for PoS consensus
@@ -578,7 +1555,7 @@ impl Blockchain {
     /// # Returns
     /// * `Option<String>` - Selected validator address or None
     pub fn select_validator(&self) -> Option<String> {
-        if let Some(pos) = &self.proof_of_stake {
+        if let Some(pos) = self.proof_of_stake() {
             let last_block = self.blocks.last()?;
             pos.select_validator(last_block.index + 1, &last_block.hash)
         } else {
@@ -613,12 +1590,67 @@ impl Blockchain {
         &self.contract_metrics
     }
 
+    /// Query contract events
+    /// 
+    /// # Arguments
+    /// * `contract_id` - Only return events from this contract, if given
+    /// * `event_name` - Only return events with this name, if given
+    /// * `from_block` - Only return events emitted at or after this block index
+    /// 
+    /// # Returns
+    /// * `Vec<&ContractEvent>` - Matching events in emission order
+    pub fn query_events(&self, contract_id: Option<&str>, event_name: Option<&str>, from_block: usize) -> Vec<&ContractEvent> {
+        self.event_log.query(contract_id, event_name, from_block as u64)
+    }
+
+    /// Subscribe to real-time blockchain events
+    ///
+    /// # Returns
+    /// * `broadcast::Receiver<Event>` - Receiver for events published after subscribing
+    pub fn subscribe_events(&mut self) -> broadcast::Receiver<Event> {
+        self.event_sender
+            .get_or_insert_with(|| broadcast::channel(EVENT_CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Publish an event to all subscribers
+    fn publish_event<T: Serialize>(&self, event_type: EventType, data: &T) {
+        if let Some(sender) = &self.event_sender {
+            if sender.receiver_count() > 0 {
+                let _ = sender.send(Event::new(event_type, data));
+            }
+        }
+    }
+
+    /// Get the consensus mechanism that seals and validates blocks
+    /// 
+    /// # Returns
+    /// * `&dyn Consensus` - The active consensus
+    pub fn consensus(&self) -> &dyn Consensus {
+        self.consensus.as_ref()
+    }
+
+    /// Get the proof of work state, if the chain uses PoW
+    pub fn proof_of_work(&self) -> Option<&ProofOfWork> {
+        self.consensus.as_any().downcast_ref()
+    }
+
+    /// Get the proof of stake state, if the chain uses PoS
+    pub fn proof_of_stake(&self) -> Option<&ProofOfStake> {
+        self.consensus.as_any().downcast_ref()
+    }
+
+    /// Get the mutable proof of stake state, if the chain uses PoS
+    pub fn proof_of_stake_mut(&mut self) -> Option<&mut ProofOfStake> {
+        self.consensus.as_any_mut().downcast_mut()
+    }
+
     /// Get consensus type
     /// 
     /// # Returns
     /// * `ConsensusType` - Current consensus type
     pub fn get_consensus_type(&self) -> ConsensusType {
-        self.consensus_type
+        self.consensus.consensus_type()
     }
 
     /// Get PoS statistics
@@ -626,65 +1658,147 @@ impl Blockchain {
     /// # Returns
     /// * `Option<HashMap<String, f64>>` - PoS statistics if using PoS
     pub fn get_pos_stats(&self) -> Option<HashMap<String, f64>> {
-        self.proof_of_stake.as_ref().map(|pos| pos.get_validator_stats())
+        self.proof_of_stake().map(|pos| pos.get_validator_stats())
     }
 
-    /// Stake tokens for a validator
+    /// Submit a transaction locking funds in a hash time-locked contract
+    /// 
+    /// The lock is signed with `keypair` and takes the sender's next nonce. Once it is
+    /// mined the amount is moved out of the sender's balance; the receiver can claim it
+    /// with the preimage of `hashlock` in blocks below `timeout_height`, and from
+    /// `timeout_height` on only the sender can refund it.
     /// 
     /// # Arguments
-    /// * `address` - Validator address
-    /// * `amount` - Amount to stake
-    /// 
-    /// # Returns
-    /// * `Result<()>` - Ok if staked successfully
-    pub fn stake_tokens(&mut self, address: String, amount: f64) -> Result<()> {
-        if let Some(pos) = &mut self.proof_of_stake {
-            let staking_tx = crate::consensus::StakingTransaction::new(
-                address.clone(),
-                amount,
-                true, // is_stake
-            )?;
-            pos.process_staking_transaction(staking_tx)?;
-            info!("Staked {} tokens for validator {}", amount, address);
-        } else {
-            return Err(BlockchainError::ConsensusError(
-                "Proof of Stake not enabled".to_string(),
-            ));
+    /// * `keypair` - Key pair of the address locking the funds
+    /// * `receiver` - Address that can redeem the funds
+    /// * `amount` - Amount to lock, in base units
+    /// * `hashlock` - Hex-encoded SHA-256 hash of the secret preimage
+    /// * `timeout_height` - Block height at which the lock expires
+    /// 
+    /// # Returns
+    /// * `Result<String>` - The HTLC ID, which is the ID of the lock transaction, or an error
+    pub fn create_htlc(
+        &mut self,
+        keypair: &crypto::KeyPair,
+        receiver: String,
+        amount: u128,
+        hashlock: String,
+        timeout_height: u64,
+    ) -> Result<String> {
+        let sender = crypto::create_address(&keypair.public_key());
+        // Check the lock parameters against the height of the next block
+        let htlc = Htlc::new(String::new(), sender, receiver, amount, hashlock, timeout_height, self.blocks.len() as u64)?;
+        let mut transaction = Transaction::new_htlc_lock(htlc.sender, htlc.receiver, amount, htlc.hashlock, timeout_height)?;
+        transaction.set_nonce(self.next_nonce(&transaction.sender));
+        transaction.sign(keypair)?;
+
+        let id = transaction.id.clone();
+        self.add_transaction_object(transaction)?;
+        info!("Queued lock of {} in HTLC {} until height {}", amount, id, timeout_height);
+        Ok(id)
+    }
+
+    /// Submit a transaction redeeming a hash time-locked contract by revealing its preimage
+    /// 
+    /// The receiver is paid when the transaction is mined, provided the HTLC has not
+    /// expired by then.
+    /// 
+    /// # Arguments
+    /// * `htlc_id` - ID of the HTLC
+    /// * `preimage` - Secret whose SHA-256 hash matches the hashlock
+    /// 
+    /// # Returns
+    /// * `Result<String>` - The redemption transaction ID, or an error if the HTLC is
+    ///   unknown, settled, expired at the next block, or the preimage is wrong
+    pub fn redeem_htlc(&mut self, htlc_id: &str, preimage: &str) -> Result<String> {
+        self.check_no_pending_settlement(htlc_id)?;
+        let height = self.blocks.len() as u64;
+        let htlc = self.locked_htlc(htlc_id)?;
+        if htlc.is_expired(height) {
+            return Err(BlockchainError::HtlcError(format!(
+                "HTLC {} expired at height {}", htlc_id, htlc.timeout_height
+            )));
         }
-        Ok(())
+        if !htlc.matches_preimage(preimage.as_bytes()) {
+            return Err(BlockchainError::HtlcError(format!(
+                "Preimage does not match the hashlock of HTLC {}", htlc_id
+            )));
+        }
+
+        let transaction = Transaction::new_htlc_redeem(htlc, preimage.to_string())?;
+        let id = transaction.id.clone();
+        self.add_transaction_object(transaction)?;
+        Ok(id)
     }
 
-    /// Unstake tokens for a validator
+    /// Submit a transaction refunding an expired hash time-locked contract to its sender
     /// 
     /// # Arguments
-    /// * `address` - Validator address
-    /// * `amount` - Amount to unstake
-    /// 
-    /// # Returns
-    /// * `Result<()>` - Ok if unstaked successfully
-    pub fn unstake_tokens(&mut self, address: String, amount: f64) -> Result<()> {
-        if let Some(pos) = &mut self.proof_of_stake {
-            let staking_tx = crate::consensus::StakingTransaction::new(
-                address.clone(),
-                amount,
-                false, // is_stake
-            )?;
-            pos.process_staking_transaction(staking_tx)?;
-            info!("Unstaked {} tokens for validator {}", amount, address);
-        } else {
-            return Err(BlockchainError::ConsensusError(
-                "Proof of Stake not enabled".to_string(),
-            ));
+    /// * `htlc_id` - ID of the HTLC
+    /// 
+    /// # Returns
+    /// * `Result<String>` - The refund transaction ID, or an error if the HTLC is unknown,
+    ///   settled, or will not have reached its timeout height at the next block
+    pub fn refund_htlc(&mut self, htlc_id: &str) -> Result<String> {
+        self.check_no_pending_settlement(htlc_id)?;
+        let height = self.blocks.len() as u64;
+        let htlc = self.locked_htlc(htlc_id)?;
+        if !htlc.is_expired(height) {
+            return Err(BlockchainError::HtlcError(format!(
+                "HTLC {} cannot be refunded before height {} (next block height {})",
+                htlc_id, htlc.timeout_height, height
+            )));
+        }
+
+        let transaction = Transaction::new_htlc_refund(htlc)?;
+        let id = transaction.id.clone();
+        self.add_transaction_object(transaction)?;
+        Ok(id)
+    }
+
+    /// Get a hash time-locked contract by ID
+    /// 
+    /// # Arguments
+    /// * `htlc_id` - ID of the HTLC
+    /// 
+    /// # Returns
+    /// * `Option<&Htlc>` - The HTLC if it exists
+    pub fn get_htlc(&self, htlc_id: &str) -> Option<&Htlc> {
+        self.htlcs.get(htlc_id)
+    }
+
+    /// Fail if a redemption or refund of the HTLC is already waiting to be mined
+    fn check_no_pending_settlement(&self, htlc_id: &str) -> Result<()> {
+        let pending = self.pending_transactions.iter().any(|tx| matches!(
+            tx.htlc_action(),
+            Some(HtlcAction::Redeem { htlc_id: id, .. } | HtlcAction::Refund { htlc_id: id }) if id == htlc_id
+        ));
+        if pending {
+            return Err(BlockchainError::HtlcError(format!(
+                "HTLC {} already has a pending redemption or refund", htlc_id
+            )));
         }
         Ok(())
     }
 
+    /// Look up an HTLC that is still waiting to be redeemed or refunded
+    fn locked_htlc(&mut self, htlc_id: &str) -> Result<&mut Htlc> {
+        let htlc = self.htlcs.get_mut(htlc_id)
+            .ok_or_else(|| BlockchainError::HtlcError(format!("HTLC {} not found", htlc_id)))?;
+        if htlc.status != HtlcStatus::Locked {
+            return Err(BlockchainError::HtlcError(format!(
+                "HTLC {} is already {:?}", htlc_id, htlc.status
+            )));
+        }
+        Ok(htlc)
+    }
+
     /// Get all validators
     /// 
     /// # Returns
     /// * `Vec<String>` - List of validator addresses
     pub fn get_validators(&self) -> Vec<String> {
-        if let Some(pos) = &self.proof_of_stake {
+        if let Some(pos) = self.proof_of_stake() {
             pos.validators.keys().cloned().collect()
         } else {
             Vec::new()
@@ -702,11 +1816,13 @@ impl Blockchain {
     /// # Example
     /// ```
     /// use gillean::blockchain::Blockchain;
+    /// use gillean::{crypto, units};
     /// 
     /// let mut blockchain = Blockchain::new_default().unwrap();
     /// // Add initial balance for alice
-    /// blockchain.balances.insert("alice".to_string(), 1000.0);
-    /// blockchain.add_transaction("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
+    /// let alice = crypto::KeyPair::generate().unwrap();
+    /// blockchain.balances.insert(crypto::create_address(&alice.public_key()), units::gil(1000));
+    /// blockchain.add_transaction(&alice, "bob".to_string(), units::gil(100), None).unwrap();
     /// let block = blockchain.mine_block("miner".to_string()).unwrap();
     /// assert_eq!(block.transactions.len(), 2); // 1 user tx + 1 reward tx
     /// ```
@@ -719,123 +1835,221 @@ impl Blockchain {
 
         info!("Mining new block with {} pending transactions", self.pending_transactions.len());
 
+        // A producer selected by the consensus (e.g. the PoS validator) produces
+        // the block and earns its reward instead of the caller-supplied miner
+        let (index, previous_hash) = if let Ok(latest_block) = self.get_latest_block() {
+            (latest_block.index + 1, latest_block.hash.clone())
+        } else {
+            // This is the genesis block
+            (0, "0".repeat(64))
+        };
+        let validator = self.consensus().select_producer(index, &previous_hash)?;
+
         // Create mining reward transaction
         let reward_tx = Transaction::new_transfer(
             "COINBASE".to_string(),
-            miner_address.clone(),
-            self.mining_reward,
+            validator.clone().unwrap_or_else(|| miner_address.clone()),
+            units::to_base_units(self.mining_reward)?,
             Some("Mining reward".to_string()),
         )?;
 
-        // Get transactions for the new block (limit to prevent oversized blocks)
+        // Get transactions for the new block, stopping at whichever of the
+        // byte size and transaction count limits is hit first. Time-locked
+        // transactions below their unlock height are skipped and stay pending;
+        // unlocked ones the sender can no longer cover are dropped.
+        let height = self.blocks.len() as u64;
         let mut block_transactions = Vec::new();
         let mut total_size = 0;
-        let mut mined_count = 0;
+        let mut included = Vec::new();
+        let mut dropped = Vec::new();
+        let mut spent: HashMap<&str, u128> = HashMap::new();
 
-        for tx in &self.pending_transactions {
+        for (position, tx) in self.pending_transactions.iter().enumerate() {
+            if self.max_txs_per_block > 0 && block_transactions.len() >= self.max_txs_per_block {
+                break;
+            }
+            if !tx.is_unlocked_at(height) {
+                continue;
+            }
             let tx_size = tx.size();
             if total_size + tx_size > MAX_BLOCK_SIZE {
                 break;
             }
+            let amount = tx.amount;
+            if tx.unlock_height().is_some() {
+                let available = self.get_balance_units(&tx.sender)
+                    .saturating_sub(spent.get(tx.sender.as_str()).copied().unwrap_or(0));
+                if available < amount {
+                    warn!("Dropping time-locked transaction {}: {} has {} but needs {}",
+                          tx.id, tx.sender, units::format_units(available), units::format_units(amount));
+                    dropped.push(position);
+                    continue;
+                }
+            }
+            if tx.debits_sender() {
+                let total = spent.entry(tx.sender.as_str()).or_insert(0);
+                *total = total.saturating_add(amount);
+            }
             block_transactions.push(tx.clone());
             total_size += tx_size;
-            mined_count += 1;
+            included.push(position);
         }
 
-        // Add reward transaction
-        block_transactions.push(reward_tx);
-
-        // Create the new block
-        let (index, previous_hash) = if let Ok(latest_block) = self.get_latest_block() {
-            (latest_block.index + 1, latest_block.hash.clone())
-        } else {
-            // This is the genesis block
-            (0, "0".repeat(64))
-        };
-        
-        let mut new_block = match self.consensus_type {
-            ConsensusType::ProofOfWork => {
-                Block::new(
+        // Create the new block. Transactions take more room nested in the block
+        // (deeper indentation, Merkle tree nodes) than on their own, so trim the
+        // tail until the block fits with room left for the seal.
+        let mut new_block = loop {
+            let mut transactions = block_transactions.clone();
+            transactions.push(reward_tx.clone());
+            let block = match &validator {
+                None => Block::new(
                     index,
-                    block_transactions,
-                    previous_hash,
+                    transactions,
+                    previous_hash.clone(),
                     self.version.clone(),
-                    self.consensus_type.to_string(),
-                )?
-            }
-            ConsensusType::ProofOfStake => {
-                // For PoS, we need to select a validator
-                let validator = self.select_validator()
-                    .ok_or_else(|| BlockchainError::ConsensusError(
-                        "No validators available for PoS mining".to_string(),
-                    ))?;
-                
-                Block::new_pos(
+                    self.get_consensus_type().to_string(),
+                )?,
+                Some(validator) => Block::new_pos(
                     index,
-                    block_transactions,
-                    previous_hash,
+                    transactions,
+                    previous_hash.clone(),
                     self.version.clone(),
-                    validator,
-                )?
+                    validator.clone(),
+                )?,
+            };
+
+            let block_size = block.size();
+            let limit = MAX_BLOCK_SIZE - SEAL_SIZE_ALLOWANCE;
+            if block_size <= limit || block_transactions.is_empty() {
+                break block;
             }
+            let keep = (block_transactions.len() * limit / block_size).min(block_transactions.len() - 1);
+            let cutoff = included[keep];
+            block_transactions.truncate(keep);
+            included.truncate(keep);
+            // Transactions past the cut were judged against spends that no longer happen
+            dropped.retain(|&position| position < cutoff);
         };
 
-        // Mine the block (for PoW) or validate (for PoS)
-        match self.consensus_type {
-            ConsensusType::ProofOfWork => {
-                new_block.mine(&self.proof_of_work)?;
-            }
-            ConsensusType::ProofOfStake => {
-                // For PoS, we just need to calculate the hash
-                // In a real implementation, the validator would sign the block
-                new_block.hash = new_block.calculate_current_hash();
+        // Apply the transactions to commit to the resulting state, then seal the block
+        // according to the consensus rules. A transaction that fails is dropped from
+        // the pending transactions so the next block can be mined without it.
+        if let Err((failed, e)) = self.apply_block(&new_block) {
+            if let Some(&position) = failed.and_then(|failed| included.get(failed)) {
+                let transaction = self.pending_transactions.remove(position);
+                warn!("Dropping transaction {} that failed in block {}: {}", transaction.id, new_block.index, e);
             }
+            return Err(e);
+        }
+        new_block.state_root = self.state_tree.root.clone();
+        let sealed = self.consensus().seal_block(&mut new_block).and_then(|_| new_block.validate());
+        if let Err(e) = sealed {
+            self.discard_applied_block(new_block.index)?;
+            return Err(e);
         }
 
         // Add the block to the chain
-        self.add_block(new_block.clone())?;
+        self.commit_block(new_block.clone())?;
 
-        // Remove mined transactions from pending
-        self.pending_transactions.drain(0..mined_count);
+        // Remove mined and dropped transactions from pending
+        let mut position = 0;
+        self.pending_transactions.retain(|_| {
+            let keep = !included.contains(&position) && !dropped.contains(&position);
+            position += 1;
+            keep
+        });
 
         info!("Successfully mined block {} with {} transactions", new_block.index, new_block.transaction_count());
         Ok(new_block)
     }
 
-    /// Add a transaction to the pending transactions list
+    /// Start a background task that mines pending transactions on an interval
+    /// 
+    /// Every `interval` the task mines a block if transactions are pending and otherwise
+    /// sleeps until the next tick. Mining runs on the blocking thread pool so proof of
+    /// work does not stall the async runtime. Starting a new miner replaces the stop
+    /// signal of any previous one, which then shuts down.
     /// 
     /// # Arguments
-    /// * `sender` - Sender's address
-    /// * `receiver` - Receiver's address
-    /// * `amount` - Transaction amount
-    /// * `message` - Optional message
+    /// * `blockchain` - Shared blockchain to mine into
+    /// * `miner` - Address receiving the mining rewards
+    /// * `interval` - Time between mining attempts
     /// 
     /// # Returns
-    /// * `Result<()>` - Ok if added successfully, error otherwise
-    pub fn add_transaction(
-        &mut self,
-        sender: String,
-        receiver: String,
-        amount: f64,
-        message: Option<String>,
-    ) -> Result<()> {
-        // Check if sender has sufficient balance (except for coinbase transactions)
-        if sender != "COINBASE" {
-            let balance = self.get_balance(&sender);
-            if balance < amount {
-                return Err(BlockchainError::InsufficientBalance {
-                    address: sender.clone(),
-                    balance,
-                    required: amount,
-                });
+    /// * `JoinHandle<()>` - Handle of the mining task, finished once stopped
+    pub fn start_auto_miner(blockchain: Arc<Mutex<Self>>, miner: String, interval: std::time::Duration) -> JoinHandle<()> {
+        let (stop_sender, mut stop_receiver) = mpsc::channel(1);
+        blockchain.lock().unwrap().auto_miner_stop = Some(stop_sender);
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately
+            ticker.tick().await;
+
+            loop {
+                tokio::select! {
+                    _ = stop_receiver.recv() => break,
+                    _ = ticker.tick() => {
+                        if blockchain.lock().unwrap().pending_transactions.is_empty() {
+                            continue;
+                        }
+
+                        let chain = blockchain.clone();
+                        let miner = miner.clone();
+                        let mined = tokio::task::spawn_blocking(move || {
+                            chain.lock().unwrap().mine_block(miner)
+                        }).await;
+
+                        match mined {
+                            Ok(Ok(block)) => info!("Auto miner mined block {}", block.index),
+                            Ok(Err(e)) => warn!("Auto miner failed to mine block: {}", e),
+                            Err(e) => error!("Auto miner task panicked: {}", e),
+                        }
+                    }
+                }
             }
-        }
 
-        let transaction = Transaction::new_transfer(sender, receiver, amount, message)?;
-        self.pending_transactions.push(transaction);
+            info!("Auto miner stopped");
+        })
+    }
 
-        debug!("Added transaction to pending queue");
-        Ok(())
+    /// Signal the running auto miner to stop
+    /// 
+    /// # Returns
+    /// * `bool` - Whether a running miner was signalled
+    pub fn stop_auto_miner(&mut self) -> bool {
+        self.auto_miner_stop
+            .take()
+            .is_some_and(|stop| stop.try_send(()).is_ok())
+    }
+
+    /// Sign a transfer with the sender's key and add it to the pending transactions list
+    /// 
+    /// The sender address is derived from the key pair and the transfer takes
+    /// the sender's next nonce.
+    /// 
+    /// # Arguments
+    /// * `sender` - Sender's key pair
+    /// * `receiver` - Receiver's address
+    /// * `amount` - Transaction amount, in base units
+    /// * `message` - Optional message
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if added successfully, error otherwise
+    pub fn add_transaction(
+        &mut self,
+        sender: &crypto::KeyPair,
+        receiver: String,
+        amount: u128,
+        message: Option<String>,
+    ) -> Result<()> {
+        let sender_address = crypto::create_address(&sender.public_key());
+        let mut transaction = Transaction::new_transfer(sender_address, receiver, amount, message)?;
+        transaction.set_nonce(self.next_nonce(&transaction.sender));
+        transaction.sign(sender)?;
+
+        self.add_transaction_object(transaction)
     }
 
     /// Validate the entire blockchain
@@ -852,6 +2066,7 @@ impl Blockchain {
     /// ```
     pub fn validate_chain(&mut self) -> Result<bool> {
         info!("Validating blockchain with {} blocks", self.blocks.len());
+        let consensus = self.consensus();
 
         for (i, block) in self.blocks.iter().enumerate() {
             // Validate individual block
@@ -880,55 +2095,96 @@ impl Blockchain {
             }
 
             // Validate consensus-specific requirements
-            match self.consensus_type {
-                ConsensusType::ProofOfWork => {
-                    // Validate proof of work
-                    if !self.proof_of_work.validate_hash(&block.hash) {
-                        return Err(BlockchainError::InvalidProofOfWork(
-                            format!("Block {} hash does not meet difficulty requirement", block.index)
-                        ));
-                    }
-                }
-                ConsensusType::ProofOfStake => {
-                    // Validate proof of stake
-                    if let Some(pos) = &mut self.proof_of_stake {
-                        // For PoS, we validate that the block was created by a valid validator
-                        // The block should have a validator signature
-                        if let Some(validator) = &block.validator {
-                            if let Some(signature) = &block.validator_signature {
-                                match pos.validate_block(&block.hash, validator, Some(signature.clone())) {
-                                    Ok(result) => {
-                                        if !result.valid {
-                                            return Err(BlockchainError::ConsensusError(
-                                                format!("Block {} validation failed: {}", block.index, result.error.unwrap_or_default())
-                                            ));
-                                        }
-                                    }
-                                    Err(e) => {
-                                        return Err(BlockchainError::ConsensusError(
-                                            format!("Block {} validation error: {}", block.index, e)
-                                        ));
-                                    }
-                                }
-                            } else {
-                                return Err(BlockchainError::ConsensusError(
-                                    format!("Block {} missing validator signature", block.index)
-                                ));
-                            }
-                        } else {
-                            return Err(BlockchainError::ConsensusError(
-                                format!("Block {} missing validator", block.index)
-                            ));
-                        }
-                    }
-                }
-            }
+            consensus.validate_historical_block(block)?;
         }
 
+        self.verify_state_roots()?;
+
         info!("Blockchain validation successful");
         Ok(true)
     }
 
+    /// Check every committed state root, and the live state against the tip's root
+    /// 
+    /// Blocks are re-applied in order to a detached state. Before each block the state is
+    /// reset to the block's snapshot if one is kept in memory or in storage, so a tampered
+    /// historical balance produces a different root. Blocks without a snapshot are applied
+    /// on top of the replayed state, which starts out empty before the genesis block.
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if all roots match, a state corruption error otherwise
+    fn verify_state_roots(&self) -> Result<()> {
+        let mut scratch = self.detached_copy();
+        for block in &self.blocks {
+            if let Some(snapshot) = self.find_state_snapshot(block.index)? {
+                scratch.balances = snapshot.balances;
+                scratch.contracts = snapshot.contracts;
+                scratch.contract_metrics = snapshot.contract_metrics;
+                scratch.nonces = snapshot.nonces;
+                scratch.htlcs = snapshot.htlcs;
+                Self::restore_stakes(scratch.consensus.as_mut(), snapshot.validators, snapshot.unbonding);
+            }
+            scratch.process_transactions_with_validation(block)?;
+            scratch.blocks.push(block.clone());
+        }
+
+        if let Some(tip) = self.blocks.last().filter(|block| !block.state_root.is_empty()) {
+            if self.state_root() != tip.state_root {
+                return Err(BlockchainError::StateCorruption(format!(
+                    "Live state does not match the state root of block {}", tip.index
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the state snapshot taken before a block, in memory or pruned to storage
+    fn find_state_snapshot(&self, block_index: u64) -> Result<Option<StateSnapshot>> {
+        if let Some(snapshot) = self.state_snapshots.iter().find(|snapshot| snapshot.block_index == block_index) {
+            return Ok(Some(snapshot.clone()));
+        }
+        match &self.snapshot_pruning {
+            Some(pruning) => Ok(pruning.storage.load_state_snapshot(block_index)?),
+            None => Ok(None),
+        }
+    }
+
+    /// Create a chain with this chain's configuration and consensus, but no blocks or state
+    fn detached_copy(&self) -> Blockchain {
+        Blockchain {
+            blocks: Vec::new(),
+            pending_transactions: Vec::new(),
+            difficulty: self.difficulty,
+            mining_reward: self.mining_reward,
+            max_txs_per_block: self.max_txs_per_block,
+            min_relay_fee: self.min_relay_fee,
+            version: self.version.clone(),
+            balances: HashMap::new(),
+            nonces: HashMap::new(),
+            consensus: self.consensus.clone(),
+            contracts: HashMap::new(),
+            contract_metrics: HashMap::new(),
+            event_log: ContractEventLog::new(),
+            receipts: HashMap::new(),
+            htlcs: HashMap::new(),
+            orphan_blocks: Vec::new(),
+            max_orphan_age_secs: self.max_orphan_age_secs,
+            target_block_time_secs: self.target_block_time_secs,
+            retarget_interval: self.retarget_interval,
+            max_reorg_depth: self.max_reorg_depth,
+            state_snapshots: Vec::new(),
+            rollback_target: None,
+            state_tree: StateMerkleTree::new(),
+            state_lock: Arc::new(Mutex::new(())),
+            event_sender: None,
+            auto_miner_stop: None,
+            read_cache: None,
+            snapshot_pruning: None,
+            address_index: HashMap::new(),
+            balance_changes: Vec::new(),
+        }
+    }
+
     /// Create a state snapshot for rollback capability
     /// 
     /// # Arguments
@@ -940,13 +2196,19 @@ impl Blockchain {
         let _lock = self.state_lock.lock().unwrap();
         
         // Update state tree before snapshot
-        self.state_tree.update_state(&self.balances);
+        self.state_tree.update_state_with_contracts(&self.balances, &self.contracts);
         
         let snapshot = StateSnapshot {
             block_index,
             balances: self.balances.clone(),
             contracts: self.contracts.clone(),
             contract_metrics: self.contract_metrics.clone(),
+            nonces: self.nonces.clone(),
+            validators: self.proof_of_stake()
+                .map(|pos| pos.validators.iter().map(|(address, validator)| (address.clone(), validator.clone())).collect()),
+            unbonding: self.proof_of_stake()
+                .map(|pos| pos.unbonding.iter().map(|(address, entries)| (address.clone(), entries.clone())).collect()),
+            htlcs: self.htlcs.clone(),
             state_root: self.state_tree.root.clone(),
             timestamp: chrono::Utc::now().timestamp(),
         };
@@ -962,28 +2224,59 @@ impl Blockchain {
     /// * `block_index` - The block index to rollback to
     /// 
     /// # Returns
-    /// * `Result<()>` - Ok if rollback successful, error otherwise
+    /// * `Result<()>` - Ok if rollback successful, error otherwise (including when it
+    ///   would drop more than `max_reorg_depth` blocks)
     pub fn rollback_to_snapshot(&mut self, block_index: u64) -> Result<()> {
+        let depth = (self.blocks.len() as u64).saturating_sub(block_index + 1);
+        if self.max_reorg_depth > 0 && depth > self.max_reorg_depth {
+            return Err(BlockchainError::ReorgTooDeep { depth, limit: self.max_reorg_depth });
+        }
+
         let _lock = self.state_lock.lock().unwrap();
         
-        // Find the snapshot
-        let snapshot_index = self.state_snapshots
-            .iter()
-            .position(|s| s.block_index == block_index)
-            .ok_or_else(|| BlockchainError::NotFound(
-                format!("No snapshot found for block {}", block_index)
-            ))?;
+        // Find the snapshot, reloading it from storage if it was pruned from memory.
+        // Stored snapshots above the tip may be left over from blocks since rolled back.
+        let snapshot_index = match self.state_snapshots.iter().position(|s| s.block_index == block_index) {
+            Some(index) => index,
+            None => {
+                let snapshot = match &self.snapshot_pruning {
+                    Some(pruning) if block_index < self.blocks.len() as u64 => {
+                        pruning.storage.load_state_snapshot(block_index)?
+                    }
+                    _ => None,
+                };
+                let snapshot = snapshot.ok_or_else(|| BlockchainError::NotFound(
+                    format!("No snapshot found for block {}", block_index)
+                ))?;
+                let index = self.state_snapshots.partition_point(|s| s.block_index < block_index);
+                self.state_snapshots.insert(index, snapshot);
+                index
+            }
+        };
         
-        let snapshot = &self.state_snapshots[snapshot_index];
+        let snapshot = self.state_snapshots[snapshot_index].clone();
         
         // Rollback state
-        self.balances = snapshot.balances.clone();
-        self.contracts = snapshot.contracts.clone();
-        self.contract_metrics = snapshot.contract_metrics.clone();
-        self.state_tree.root = snapshot.state_root.clone();
+        self.balances = snapshot.balances;
+        self.contracts = snapshot.contracts;
+        self.contract_metrics = snapshot.contract_metrics;
+        self.nonces = snapshot.nonces;
+        self.htlcs = snapshot.htlcs;
+        Self::restore_stakes(self.consensus.as_mut(), snapshot.validators, snapshot.unbonding);
+        self.state_tree.root = snapshot.state_root;
+        if let Some(cache) = &self.read_cache {
+            cache.invalidate_all();
+        }
         
-        // Remove blocks after the snapshot
+        // Remove blocks after the snapshot, with the receipts and events they produced
         self.blocks.truncate((block_index + 1) as usize);
+        let height = self.blocks.len();
+        self.address_index.retain(|_, locations| {
+            locations.retain(|(location, _)| *location < height);
+            !locations.is_empty()
+        });
+        self.receipts.retain(|_, receipt| receipt.block_index <= block_index);
+        self.event_log.truncate_after(block_index);
         
         // Remove snapshots after this one
         self.state_snapshots.truncate(snapshot_index + 1);
@@ -992,6 +2285,52 @@ impl Blockchain {
         Ok(())
     }
 
+    /// Reset the PoS validators and unbonding stake to those recorded in a snapshot, if it recorded them
+    fn restore_stakes(
+        consensus: &mut dyn Consensus,
+        validators: Option<BTreeMap<String, Validator>>,
+        unbonding: Option<BTreeMap<String, Vec<UnbondingEntry>>>,
+    ) {
+        let Some(pos) = consensus.as_any_mut().downcast_mut::<ProofOfStake>() else {
+            return;
+        };
+        if let Some(validators) = validators {
+            pos.validators = validators.into_iter().collect();
+        }
+        if let Some(unbonding) = unbonding {
+            pos.unbonding = unbonding.into_iter().collect();
+        }
+    }
+
+    /// Drop all but the most recent state snapshots
+    ///
+    /// A snapshot that is the target of an in-progress rollback is always kept.
+    ///
+    /// # Arguments
+    /// * `keep_last` - Number of most recent snapshots to retain
+    ///
+    /// # Returns
+    /// * `usize` - Number of snapshots removed
+    pub fn prune_snapshots(&mut self, keep_last: usize) -> usize {
+        let _lock = self.state_lock.lock().unwrap();
+
+        let cutoff = self.state_snapshots.len().saturating_sub(keep_last);
+        let rollback_target = self.rollback_target;
+        let before = self.state_snapshots.len();
+        let mut position = 0;
+        self.state_snapshots.retain(|snapshot| {
+            let keep = position >= cutoff || Some(snapshot.block_index) == rollback_target;
+            position += 1;
+            keep
+        });
+
+        let removed = before - self.state_snapshots.len();
+        if removed > 0 {
+            info!("Pruned {} state snapshots, {} remaining", removed, self.state_snapshots.len());
+        }
+        removed
+    }
+
     /// Validate state integrity using Merkle tree
     /// 
     /// # Returns
@@ -1013,35 +2352,256 @@ impl Blockchain {
 
     /// Process transactions with state validation and rollback capability
     /// 
+    /// If any transaction fails or the resulting state is invalid, the state is rolled
+    /// back to before the block and the block's snapshot is dropped.
+    /// 
     /// # Arguments
     /// * `block` - The block containing transactions to process
     /// 
     /// # Returns
     /// * `Result<()>` - Ok if processed successfully, error otherwise
     pub fn process_transactions_with_validation(&mut self, block: &Block) -> Result<()> {
-        // Create snapshot before processing
-        self.create_state_snapshot(block.index)?;
-        
+        self.apply_block(block).map_err(|(_, e)| e)
+    }
+
+    /// Apply a block's transactions, discarding all of their effects if the block is rejected
+    /// 
+    /// # Returns
+    /// * `Result<(), (Option<usize>, BlockchainError)>` - On failure, the position in the
+    ///   block of the transaction that failed (None if the block as a whole was invalid)
+    ///   and the error
+    fn apply_block(&mut self, block: &Block) -> std::result::Result<(), (Option<usize>, BlockchainError)> {
+        // Create snapshot before processing and pin it until validation finishes
+        self.create_state_snapshot(block.index).map_err(|e| (None, e))?;
+        self.rollback_target = Some(block.index);
+        let mut result = self.apply_and_validate_transactions(block);
+        if result.is_err() {
+            if let Err(e) = self.discard_applied_block(block.index) {
+                result = Err((None, e));
+            }
+        }
+        self.rollback_target = None;
+        result
+    }
+
+    /// Apply a block's transactions and check the resulting state
+    ///
+    /// A contract transaction whose execution fails does not invalidate the block; it is
+    /// recorded with a failed receipt, and a failed call only charges the gas it consumed.
+    /// Any other failure leaves the state partly applied for `apply_block` to discard.
+    fn apply_and_validate_transactions(&mut self, block: &Block) -> std::result::Result<(), (Option<usize>, BlockchainError)> {
         // Process transactions
-        for transaction in &block.transactions {
-            self.process_transaction(transaction)?;
+        let mut receipts: Vec<TransactionReceipt> = Vec::with_capacity(block.transactions.len());
+        for (position, transaction) in block.transactions.iter().enumerate() {
+            // Time-locked transfers carry no nonce, so their IDs must not repeat
+            if transaction.unlock_height().is_some()
+                && (self.receipts.contains_key(&transaction.id)
+                    || receipts.iter().any(|receipt| receipt.tx_id == transaction.id))
+            {
+                return Err((Some(position), BlockchainError::TransactionValidationFailed(format!(
+                    "Time-locked transaction {} has already been executed", transaction.id
+                ))));
+            }
+            let receipt = match self.execute_transaction(transaction) {
+                Ok(result) => TransactionReceipt::success(transaction, block.index, result),
+                Err(BlockchainError::ContractExecutionError(error)) => {
+                    warn!("Transaction {} failed in block {}: {}", transaction.id, block.index, error);
+                    TransactionReceipt::failed(transaction, block.index, error, 0)
+                }
+                Err(BlockchainError::ContractReverted { error, gas_used }) => {
+                    warn!("Transaction {} reverted in block {}: {}", transaction.id, block.index, error);
+                    TransactionReceipt::failed(transaction, block.index, error, gas_used)
+                }
+                Err(BlockchainError::ConsensusError(error))
+                    if transaction.transaction_type == crate::transaction::TransactionType::Staking =>
+                {
+                    warn!("Staking transaction {} failed in block {}: {}", transaction.id, block.index, error);
+                    TransactionReceipt::failed(transaction, block.index, error, 0)
+                }
+                Err(BlockchainError::HtlcError(error)) => {
+                    warn!("HTLC transaction {} failed in block {}: {}", transaction.id, block.index, error);
+                    TransactionReceipt::failed(transaction, block.index, error, 0)
+                }
+                Err(e) => return Err((Some(position), e)),
+            };
+            receipts.push(receipt);
         }
         
         // Update state tree after processing transactions
-        self.state_tree.update_state(&self.balances);
+        self.state_tree.update_state_with_contracts(&self.balances, &self.contracts);
         
         // Validate state integrity after processing
-        if !self.validate_state_integrity()? {
-            // Rollback on validation failure
-            self.rollback_to_snapshot(block.index)?;
-            return Err(BlockchainError::StateCorruption(
+        if !self.validate_state_integrity().map_err(|e| (None, e))? {
+            return Err((None, BlockchainError::StateCorruption(
                 "State integrity validation failed after transaction processing".to_string()
-            ));
+            )));
+        }
+
+        // A block committing to a state root must produce exactly that state
+        if !block.state_root.is_empty() && self.state_tree.root != block.state_root {
+            return Err((None, BlockchainError::StateCorruption(format!(
+                "State root mismatch at block {}: expected {}, found {}",
+                block.index,
+                hex::encode(&block.state_root),
+                hex::encode(&self.state_tree.root)
+            ))));
+        }
+
+        for receipt in receipts {
+            self.receipts.insert(receipt.tx_id.clone(), receipt);
         }
+
+        // Record every balance the block changed, including credits that are not to a
+        // transaction's sender or receiver, so incremental storage can write them all
+        let before = self.state_snapshots.iter().rev()
+            .find(|snapshot| snapshot.block_index == block.index)
+            .map(|snapshot| &snapshot.balances);
+        self.balance_changes = match before {
+            Some(before) => Self::changed_addresses(before, &self.balances),
+            None => self.balances.keys().cloned().collect(),
+        };
+        self.balance_changes.sort();
         
         Ok(())
     }
 
+    /// Get the addresses whose balance differs between two balance maps
+    fn changed_addresses(before: &HashMap<String, u128>, after: &HashMap<String, u128>) -> Vec<String> {
+        let changed = after.iter()
+            .filter(|(address, balance)| before.get(*address) != Some(*balance))
+            .map(|(address, _)| address.clone());
+        let removed = before.keys()
+            .filter(|address| !after.contains_key(*address))
+            .cloned();
+        changed.chain(removed).collect()
+    }
+
+    /// Record the locations of a block's transactions in an address index
+    fn index_block(address_index: &mut HashMap<String, Vec<(usize, usize)>>, block_index: usize, block: &Block) {
+        for (tx_index, tx) in block.transactions.iter().enumerate() {
+            address_index.entry(tx.sender.clone()).or_default().push((block_index, tx_index));
+            if tx.receiver != tx.sender {
+                address_index.entry(tx.receiver.clone()).or_default().push((block_index, tx_index));
+            }
+        }
+    }
+
+    /// Rebuild the address index from the blocks, e.g. after loading the chain
+    pub fn rebuild_address_index(&mut self) {
+        self.address_index.clear();
+        for (block_index, block) in self.blocks.iter().enumerate() {
+            Self::index_block(&mut self.address_index, block_index, block);
+        }
+    }
+
+    /// Get the mined transactions sent or received by an address, oldest first
+    ///
+    /// Uses the address index, so the cost grows with the address's own
+    /// transaction count rather than the size of the chain.
+    ///
+    /// # Arguments
+    /// * `address` - The address to look up
+    ///
+    /// # Returns
+    /// * `Vec<(u64, &Transaction)>` - Each transaction with the index of its block
+    pub fn transaction_history(&self, address: &str) -> Vec<(u64, &Transaction)> {
+        self.address_index.get(address)
+            .map(|locations| {
+                locations.iter()
+                    .filter_map(|&(block_index, tx_index)| {
+                        let block = self.blocks.get(block_index)?;
+                        Some((block.index, block.transactions.get(tx_index)?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Compute the Merkle root of the current balances and contracts
+    ///
+    /// # Returns
+    /// * `Vec<u8>` - The state root, empty if there is no state
+    pub fn state_root(&self) -> Vec<u8> {
+        let mut tree = StateMerkleTree::new();
+        tree.update_state_with_contracts(&self.balances, &self.contracts);
+        tree.root
+    }
+
+    /// Undo the transactions of a block that was applied but not added to the chain
+    fn discard_applied_block(&mut self, block_index: u64) -> Result<()> {
+        self.rollback_to_snapshot(block_index)?;
+        self.state_snapshots.retain(|snapshot| snapshot.block_index < block_index);
+        self.receipts.retain(|_, receipt| receipt.block_index < block_index);
+        self.event_log.truncate_after(block_index.saturating_sub(1));
+        Ok(())
+    }
+
+    /// Get the execution receipt of a mined transaction
+    ///
+    /// # Arguments
+    /// * `tx_id` - ID of the transaction
+    ///
+    /// # Returns
+    /// * `Option<&TransactionReceipt>` - The receipt, or None if the transaction has not been mined
+    pub fn get_receipt(&self, tx_id: &str) -> Option<&TransactionReceipt> {
+        self.receipts.get(tx_id)
+    }
+
+    /// Get where a transaction is in its lifecycle
+    ///
+    /// # Arguments
+    /// * `tx_id` - ID of the transaction
+    ///
+    /// # Returns
+    /// * `Option<TransactionStatus>` - The status, or None if the transaction is neither
+    ///   pending nor mined
+    pub fn transaction_status(&self, tx_id: &str) -> Option<TransactionStatus> {
+        if self.pending_transactions.iter().any(|tx| tx.id == tx_id) {
+            return Some(TransactionStatus::Pending);
+        }
+
+        // Blocks mined before receipts were recorded are searched directly
+        let (block_number, status) = match self.receipts.get(tx_id) {
+            Some(receipt) => (receipt.block_index, receipt.status.clone()),
+            None => {
+                let block = self.blocks.iter()
+                    .find(|block| block.transactions.iter().any(|tx| tx.id == tx_id))?;
+                (block.index, ReceiptStatus::Success)
+            }
+        };
+
+        let confirmations = self.height().saturating_sub(block_number) + 1;
+        Some(match status {
+            ReceiptStatus::Success => TransactionStatus::Confirmed { block_number, confirmations },
+            ReceiptStatus::Failed { error } => TransactionStatus::Failed { block_number, confirmations, reason: error },
+        })
+    }
+
+    /// Check whether a transaction is buried deep enough to be considered final
+    ///
+    /// Blocks near the tip can still be replaced by a reorganization, so a mined
+    /// transaction is only final once `required` blocks, counting its own, are on
+    /// the chain.
+    ///
+    /// # Arguments
+    /// * `tx_id` - ID of the transaction
+    /// * `required` - Number of confirmations required
+    ///
+    /// # Returns
+    /// * `bool` - True if the transaction is mined with at least `required` confirmations
+    pub fn is_final(&self, tx_id: &str, required: u64) -> bool {
+        self.transaction_status(tx_id)
+            .is_some_and(|status| status != TransactionStatus::Pending && status.confirmations() >= required)
+    }
+
+    /// Get the current chain height
+    /// 
+    /// # Returns
+    /// * `u64` - Index of the latest block
+    pub fn height(&self) -> u64 {
+        self.blocks.len().saturating_sub(1) as u64
+    }
+
     /// Get the latest block in the chain
     /// 
     /// # Returns
@@ -1052,304 +2612,2858 @@ impl Blockchain {
         })
     }
 
+    /// Build a light-client inclusion proof for a transaction
+    /// 
+    /// The proof contains the transaction's Merkle path and the header of its
+    /// block, so it can be checked with `merkle::verify_inclusion` against the
+    /// block hash alone.
+    /// 
+    /// # Arguments
+    /// * `tx_id` - ID of a transaction in a mined block
+    /// 
+    /// # Returns
+    /// * `Result<MerkleProof>` - The inclusion proof or an error
+    pub fn inclusion_proof(&self, tx_id: &str) -> Result<MerkleProof> {
+        let (block, index) = self.blocks.iter()
+            .find_map(|block| {
+                block.transactions.iter()
+                    .position(|tx| tx.id == tx_id)
+                    .map(|index| (block, index))
+            })
+            .ok_or_else(|| BlockchainError::NotFound(format!("Transaction {} is not in any block", tx_id)))?;
+
+        let tree = MerkleTree::new(&block.transactions)?;
+        let mut proof = tree.generate_proof(index)?;
+        proof.header = Some(block.header());
+        Ok(proof)
+    }
+
     /// Get the balance of an address
     /// 
     /// # Arguments
     /// * `address` - The address to check
     /// 
     /// # Returns
-    /// * `f64` - The current balance
+    /// * `f64` - The current balance in GIL
     pub fn get_balance(&self, address: &str) -> f64 {
-        *self.balances.get(address).unwrap_or(&0.0)
+        units::from_base_units(self.get_balance_units(address))
+    }
+
+    /// Get the exact balance of an address in base units
+    /// 
+    /// # Arguments
+    /// * `address` - The address to check
+    /// 
+    /// # Returns
+    /// * `u128` - The current balance in base units
+    pub fn get_balance_units(&self, address: &str) -> u128 {
+        self.balances.get(address).copied().unwrap_or(0)
     }
 
     /// Get all balances
     /// 
     /// # Returns
-    /// * `&HashMap<String, f64>` - Reference to all balances
-    pub fn get_balances(&self) -> &HashMap<String, f64> {
+    /// * `&HashMap<String, u128>` - Reference to all balances in base units
+    pub fn get_balances(&self) -> &HashMap<String, u128> {
         &self.balances
     }
 
-    /// Get blockchain statistics
+    /// Get the balance set as it was right after the block at `height`
+    /// 
+    /// Starts from the most recent state snapshot taken at or below `height` (or from
+    /// an empty state if older snapshots were pruned) and replays the transactions of
+    /// the following blocks up to and including `height`. Current state is untouched.
+    /// 
+    /// # Arguments
+    /// * `height` - Index of the block whose resulting balances are wanted
     /// 
     /// # Returns
-    /// * `BlockchainStats` - Statistics about the blockchain
-    pub fn get_stats(&self) -> BlockchainStats {
-        let total_transactions: usize = self.blocks.iter().map(|b| b.transaction_count()).sum();
-        let total_amount: f64 = self.blocks.iter().map(|b| b.total_amount()).sum();
-        let chain_size = self.blocks.iter().map(|b| b.size()).sum();
+    /// * `Result<HashMap<String, f64>>` - Balances after that block, or an error if the
+    ///   height is beyond the chain
+    pub fn balances_at_height(&self, height: u64) -> Result<HashMap<String, u128>> {
+        if height >= self.blocks.len() as u64 {
+            return Err(BlockchainError::InvalidInput(format!(
+                "Height {} is beyond the chain tip {}", height, self.height()
+            )));
+        }
 
-        BlockchainStats {
-            block_count: self.blocks.len(),
-            pending_transactions: self.pending_transactions.len(),
-            total_transactions,
-            total_amount,
-            chain_size,
-            difficulty: self.difficulty,
-            mining_reward: self.mining_reward,
-            version: self.version.clone(),
+        let snapshot = self.state_snapshots.iter()
+            .filter(|snapshot| snapshot.block_index <= height)
+            .max_by_key(|snapshot| snapshot.block_index);
+
+        let mut chain = self.clone();
+        chain.pending_transactions.clear();
+        chain.state_snapshots.clear();
+        chain.event_log = ContractEventLog::new();
+        chain.state_lock = Arc::new(Mutex::new(()));
+        chain.event_sender = None;
+        chain.auto_miner_stop = None;
+        let start = match snapshot {
+            Some(snapshot) => {
+                chain.balances = snapshot.balances.clone();
+                chain.contracts = snapshot.contracts.clone();
+                chain.contract_metrics = snapshot.contract_metrics.clone();
+                chain.nonces = snapshot.nonces.clone();
+                chain.htlcs = snapshot.htlcs.clone();
+                snapshot.block_index as usize
+            }
+            None => {
+                chain.balances.clear();
+                chain.contracts.clear();
+                chain.contract_metrics.clear();
+                chain.nonces.clear();
+                chain.htlcs.clear();
+                0
+            }
+        };
+        chain.blocks.truncate(start);
+
+        for block in &self.blocks[start..=height as usize] {
+            for transaction in &block.transactions {
+                match chain.process_transaction(transaction) {
+                    // A rejected HTLC redemption or refund leaves the state unchanged
+                    Err(BlockchainError::HtlcError(_)) => {}
+                    result => result?,
+                }
+            }
+            chain.blocks.push(block.clone());
         }
+
+        Ok(chain.balances)
     }
 
-    /// Get the blockchain as a JSON string
+    /// Get the cumulative difficulty of the chain
+    /// 
+    /// Each block contributes `16^n` where `n` is the difficulty target it was mined
+    /// against, i.e. the expected work to find it; a hash that happens to beat its
+    /// target counts no extra. Forks are resolved in favour of the chain with more
+    /// cumulative difficulty.
     /// 
     /// # Returns
-    /// * `Result<String>` - The JSON representation or an error
-    pub fn to_json(&self) -> Result<String> {
-        serde_json::to_string_pretty(self).map_err(BlockchainError::from)
+    /// * `u64` - Total work of all blocks in the chain
+    pub fn cumulative_difficulty(&self) -> u64 {
+        u64::try_from(self.total_work()).unwrap_or(u64::MAX)
     }
 
-    /// Create a blockchain from JSON string
+    /// Get the total work of the chain
     /// 
-    /// # Arguments
-    /// * `json` - The JSON string to parse
+    /// Same measure as [`Blockchain::cumulative_difficulty`] without truncation to `u64`.
     /// 
     /// # Returns
-    /// * `Result<Blockchain>` - The parsed blockchain or an error
-    pub fn from_json(json: &str) -> Result<Self> {
-        let mut blockchain: Blockchain = serde_json::from_str(json)?;
-        blockchain.validate_chain()?;
-        Ok(blockchain)
+    /// * `u128` - Sum of the expected work of every block
+    pub fn total_work(&self) -> u128 {
+        Self::chain_work(&self.blocks)
     }
 
-    /// Add a transaction object directly to pending transactions
+    /// Sum the expected work (`16^n` for a difficulty target of `n`) of blocks
+    fn chain_work(blocks: &[Block]) -> u128 {
+        blocks.iter().fold(0u128, |total, block| {
+            total.saturating_add(16u128.saturating_pow(block.difficulty))
+        })
+    }
+
+    /// Switch to a competing fork if it carries strictly more work
+    /// 
+    /// The candidate must share this chain's genesis block. It is replayed from
+    /// genesis, validating every block, and only replaces the local chain (and the
+    /// state derived from it) when its total work is greater; on equal work the
+    /// current chain is kept. A fork that would roll back more than
+    /// `max_reorg_depth` blocks is rejected without replaying it.
     /// 
     /// # Arguments
-    /// * `transaction` - The transaction to add
+    /// * `candidate` - The complete competing chain, genesis first
     /// 
     /// # Returns
-    /// * `Result<()>` - Ok if added successfully, error otherwise
-    pub fn add_transaction_object(&mut self, transaction: Transaction) -> Result<()> {
-        // Check if sender has sufficient balance (except for coinbase transactions)
-        if transaction.sender != "COINBASE" {
-            let balance = self.get_balance(&transaction.sender);
-            if balance < transaction.amount {
-                return Err(BlockchainError::InsufficientBalance {
-                    address: transaction.sender.clone(),
-                    balance,
-                    required: transaction.amount,
-                });
-            }
+    /// * `Result<bool>` - Whether the candidate was adopted, or an error if it is invalid
+    pub fn try_adopt_chain(&mut self, candidate: Vec<Block>) -> Result<bool> {
+        let genesis_hash = self.blocks.first().map(|block| block.hash.as_str());
+        if candidate.first().map(|block| block.hash.as_str()) != genesis_hash {
+            return Err(BlockchainError::ChainValidationFailed(
+                "Candidate chain does not share our genesis block".to_string(),
+            ));
         }
 
-        self.pending_transactions.push(transaction);
-        debug!("Added transaction object to pending queue");
-        Ok(())
+        let current = self.total_work();
+        let candidate_work = Self::chain_work(&candidate);
+        if candidate_work <= current {
+            debug!("Keeping current chain: candidate work {} does not exceed {}", candidate_work, current);
+            return Ok(false);
+        }
+
+        self.check_reorg_depth(&candidate)?;
+        let chain = self.replay_blocks(candidate)?;
+        self.replace_chain(chain)?;
+        Ok(true)
     }
 
-    /// Create a new blockchain with storage integration
+    /// Fail if switching to `blocks` would roll back more than `max_reorg_depth` blocks
+    /// 
+    /// The depth is the number of local blocks after the last block both chains share.
     /// 
     /// # Arguments
-    /// * `difficulty` - Mining difficulty level
-    /// * `mining_reward` - Reward for mining a block
-    /// * `storage` - Blockchain storage instance
+    /// * `blocks` - The competing chain, genesis first
     /// 
     /// # Returns
-    /// * `Result<Blockchain>` - The created blockchain or an error
-    pub fn with_storage(difficulty: u32, mining_reward: f64, storage: &std::sync::Arc<crate::storage::BlockchainStorage>) -> Result<Self> {
-        // Try to load from storage first
-        match storage.load_blockchain(difficulty, mining_reward) {
-            Ok(blockchain) => {
-                info!("Loaded blockchain from storage");
-                Ok(blockchain)
-            }
-            Err(_) => {
-                // Create new blockchain if storage is empty or corrupted
-                info!("Creating new blockchain (storage was empty or corrupted)");
-                let new_blockchain = Self::new_pow(difficulty, mining_reward)?;
-                
-                // Save the new blockchain with genesis block to storage
-                storage.save_blockchain(&new_blockchain)?;
-                info!("Saved new blockchain with genesis block to storage");
-                
-                Ok(new_blockchain)
-            }
+    /// * `Result<()>` - Ok if the reorganization is allowed, `ReorgTooDeep` otherwise
+    pub fn check_reorg_depth(&self, blocks: &[Block]) -> Result<()> {
+        let shared = self.blocks.iter()
+            .zip(blocks)
+            .take_while(|(local, other)| local.hash == other.hash)
+            .count();
+        let depth = (self.blocks.len() - shared) as u64;
+        if self.max_reorg_depth > 0 && depth > self.max_reorg_depth {
+            warn!("Refusing to roll back {} blocks (limit {})", depth, self.max_reorg_depth);
+            return Err(BlockchainError::ReorgTooDeep { depth, limit: self.max_reorg_depth });
         }
+        Ok(())
     }
 
-    /// Save the blockchain to storage
+    /// Rebuild a chain from blocks using this chain's consensus settings
+    /// 
+    /// Every block is validated and its transactions replayed from an empty state,
+    /// starting with the genesis block.
     /// 
     /// # Arguments
-    /// * `storage` - Blockchain storage instance
+    /// * `blocks` - The complete list of blocks, genesis first
     /// 
     /// # Returns
-    /// * `Result<()>` - Ok if saved successfully
-    pub fn save_to_storage(&self, storage: &std::sync::Arc<crate::storage::BlockchainStorage>) -> Result<()> {
-        Ok(storage.save_blockchain(self)?)
-    }
+    /// * `Result<Blockchain>` - The rebuilt chain or an error if any block is invalid
+    pub fn replay_blocks(&self, blocks: Vec<Block>) -> Result<Blockchain> {
+        if !blocks.first().is_some_and(|block| block.is_genesis()) {
+            return Err(BlockchainError::ChainValidationFailed(
+                "Chain must start with a genesis block".to_string(),
+            ));
+        }
 
+        let mut chain = self.detached_copy();
 
+        // Retargeting restarts from the difficulty the first block was mined at
+        let first_difficulty = blocks.get(1).map_or(0, |block| block.difficulty);
+        if chain.proof_of_work().is_some() && first_difficulty != 0 {
+            chain.set_difficulty(first_difficulty)?;
+        }
 
-    /// Adjust mining difficulty based on recent mining times
+        for block in blocks {
+            chain.add_block(block)?;
+        }
+        chain.state_tree.update_state_with_contracts(&chain.balances, &chain.contracts);
+
+        Ok(chain)
+    }
+
+    /// Replace this chain with one that has more cumulative difficulty
+    /// 
+    /// Pending transactions that the new chain does not already contain are kept,
+    /// as are event subscriptions.
     /// 
     /// # Arguments
-    /// * `target_time` - Target time for mining (in seconds)
+    /// * `chain` - The validated replacement chain
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if replaced, error if the new chain is not heavier or would
+    ///   roll back more than `max_reorg_depth` blocks
+    pub fn replace_chain(&mut self, chain: Blockchain) -> Result<()> {
+        self.check_reorg_depth(&chain.blocks)?;
+
+        let current = self.cumulative_difficulty();
+        let candidate = chain.cumulative_difficulty();
+        if candidate <= current {
+            return Err(BlockchainError::ChainValidationFailed(format!(
+                "Replacement chain has cumulative difficulty {} which does not exceed {}",
+                candidate, current
+            )));
+        }
+
+        let pending: Vec<Transaction> = {
+            let included: std::collections::HashSet<&str> = chain.blocks.iter()
+                .flat_map(|block| &block.transactions)
+                .map(|tx| tx.id.as_str())
+                .collect();
+            self.pending_transactions.iter()
+                .filter(|tx| !included.contains(tx.id.as_str()))
+                .cloned()
+                .collect()
+        };
+        let event_sender = self.event_sender.take();
+        let read_cache = self.read_cache.take();
+        let snapshot_pruning = self.snapshot_pruning.take();
+
+        *self = chain;
+        self.pending_transactions = pending;
+        self.event_sender = event_sender;
+        self.snapshot_pruning = snapshot_pruning;
+        if let Some(cache) = read_cache {
+            self.set_read_cache(cache);
+        }
+
+        info!("Replaced chain: {} blocks, cumulative difficulty {}", self.blocks.len(), candidate);
+        Ok(())
+    }
+
+    /// Get the nonce the next mined transaction from an address must carry
+    /// 
+    /// # Arguments
+    /// * `address` - The sender address
+    /// 
+    /// # Returns
+    /// * `u64` - Number of transfers from the address already in blocks
+    pub fn get_nonce(&self, address: &str) -> u64 {
+        *self.nonces.get(address).unwrap_or(&0)
+    }
+
+    /// Get the nonce for a new transaction from an address
+    /// 
+    /// Unlike `get_nonce`, this accounts for the address's pending transfers.
+    /// 
+    /// # Arguments
+    /// * `address` - The sender address
+    /// 
+    /// # Returns
+    /// * `u64` - The nonce the next submitted transaction must carry
+    pub fn next_nonce(&self, address: &str) -> u64 {
+        let pending = self.pending_transactions.iter()
+            .filter(|tx| tx.uses_nonce() && tx.sender == address)
+            .count() as u64;
+        self.get_nonce(address) + pending
+    }
+
+    /// Get blockchain statistics
+    /// 
+    /// # Returns
+    /// * `BlockchainStats` - Statistics about the blockchain
+    pub fn get_stats(&self) -> BlockchainStats {
+        let total_transactions: usize = self.blocks.iter().map(|b| b.transaction_count()).sum();
+        let total_amount = self.blocks.iter().fold(0u128, |total, b| total.saturating_add(b.total_amount()));
+        let chain_size = self.blocks.iter().map(|b| b.size()).sum();
+
+        BlockchainStats {
+            block_count: self.blocks.len(),
+            pending_transactions: self.pending_transactions.len(),
+            total_transactions,
+            total_amount,
+            chain_size,
+            difficulty: self.difficulty,
+            mining_reward: self.mining_reward,
+            target_block_time: self.target_block_time_secs,
+            average_block_time: self.average_block_time(),
+            version: self.version.clone(),
+        }
+    }
+
+    /// Get the blockchain as a JSON string
+    /// 
+    /// # Returns
+    /// * `Result<String>` - The JSON representation or an error
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(BlockchainError::from)
+    }
+
+    /// Create a blockchain from JSON string
+    /// 
+    /// # Arguments
+    /// * `json` - The JSON string to parse
+    /// 
+    /// # Returns
+    /// * `Result<Blockchain>` - The parsed blockchain or an error
+    pub fn from_json(json: &str) -> Result<Self> {
+        let mut blockchain: Blockchain = serde_json::from_str(json)?;
+        blockchain.validate_chain()?;
+        blockchain.rebuild_address_index();
+        Ok(blockchain)
+    }
+
+    /// Export the blockchain to a compact binary snapshot
+    /// 
+    /// The file starts with `SNAPSHOT_MAGIC`, the snapshot format version and
+    /// a SHA-256 checksum of the bincode-encoded blockchain that follows.
+    /// 
+    /// # Arguments
+    /// * `path` - File to write the snapshot to
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if exported successfully, error otherwise
+    pub fn export_snapshot(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let payload = bincode::serialize(self)
+            .map_err(|e| BlockchainError::SerializationError(format!("Failed to encode snapshot: {}", e)))?;
+        let checksum = Sha256::digest(&payload);
+
+        let mut snapshot = Vec::with_capacity(SNAPSHOT_HEADER_LEN + payload.len());
+        snapshot.extend_from_slice(SNAPSHOT_MAGIC);
+        snapshot.extend_from_slice(&SNAPSHOT_FORMAT_VERSION.to_le_bytes());
+        snapshot.extend_from_slice(&checksum);
+        snapshot.extend_from_slice(&payload);
+        std::fs::write(path.as_ref(), snapshot)?;
+
+        info!("Exported snapshot of {} blocks to {}", self.blocks.len(), path.as_ref().display());
+        Ok(())
+    }
+
+    /// Import a blockchain from a binary snapshot
+    /// 
+    /// # Arguments
+    /// * `path` - Snapshot file written by `export_snapshot`
+    /// 
+    /// # Returns
+    /// * `Result<Blockchain>` - The imported blockchain, or an error if the file is
+    ///   corrupted or was written by an incompatible version
+    pub fn import_snapshot(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let snapshot = std::fs::read(path.as_ref())?;
+        if snapshot.len() < SNAPSHOT_HEADER_LEN || &snapshot[..8] != SNAPSHOT_MAGIC {
+            return Err(BlockchainError::SerializationError("Not a blockchain snapshot".to_string()));
+        }
+
+        let mut format_version = [0u8; 4];
+        format_version.copy_from_slice(&snapshot[8..12]);
+        let format_version = u32::from_le_bytes(format_version);
+        if format_version != SNAPSHOT_FORMAT_VERSION {
+            return Err(BlockchainError::InvalidInput(format!(
+                "Unsupported snapshot format version {} (expected {})",
+                format_version, SNAPSHOT_FORMAT_VERSION
+            )));
+        }
+
+        let (checksum, payload) = snapshot[12..].split_at(32);
+        if Sha256::digest(payload).as_slice() != checksum {
+            return Err(BlockchainError::StateCorruption("Snapshot checksum mismatch".to_string()));
+        }
+
+        let mut blockchain: Blockchain = bincode::deserialize(payload)
+            .map_err(|e| BlockchainError::SerializationError(format!("Failed to decode snapshot: {}", e)))?;
+        if blockchain.version != BLOCKCHAIN_VERSION {
+            return Err(BlockchainError::InvalidInput(format!(
+                "Snapshot blockchain version {} does not match {}",
+                blockchain.version, BLOCKCHAIN_VERSION
+            )));
+        }
+        blockchain.validate_chain()?;
+        blockchain.rebuild_address_index();
+
+        info!("Imported snapshot of {} blocks from {}", blockchain.blocks.len(), path.as_ref().display());
+        Ok(blockchain)
+    }
+
+    /// Reject receivers that use the checksummed address format but fail its checksum
+    /// 
+    /// # Arguments
+    /// * `receiver` - The receiver address
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if the receiver is acceptable, error otherwise
+    fn check_receiver_address(receiver: &str) -> Result<()> {
+        if crypto::is_checksummed_format(receiver) {
+            crypto::validate_address(receiver).map_err(|e| {
+                BlockchainError::TransactionValidationFailed(format!("Malformed receiver address: {}", e))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Add a transfer that cannot be mined before the given block height
+    ///
+    /// The transfer is sent from the address of `sender` and signed with it. The
+    /// sender's balance is not checked on submission; it is checked when the
+    /// transfer unlocks, and the transfer is dropped if it cannot be covered then.
+    /// Time-locked transfers do not consume account nonces, so they never hold up
+    /// the sender's later transfers while they wait. Their IDs guard against replay
+    /// instead: a transfer whose ID is already pending or has a receipt is rejected.
+    ///
+    /// # Arguments
+    /// * `sender` - Key pair of the sender, which signs the transfer
+    /// * `receiver` - Receiver's address
+    /// * `amount` - Transaction amount, in base units
+    /// * `unlock_height` - Earliest block height the transfer may be included in
+    /// * `message` - Optional message
+    ///
+    /// # Returns
+    /// * `Result<String>` - The transaction ID or an error
+    pub fn add_time_locked_transaction(
+        &mut self,
+        sender: &crypto::KeyPair,
+        receiver: String,
+        amount: u128,
+        unlock_height: u64,
+        message: Option<String>,
+    ) -> Result<String> {
+        let sender_address = crypto::create_address(&sender.public_key());
+        let mut transaction = Transaction::new_time_locked(sender_address, receiver, amount, unlock_height, message)?;
+        transaction.sign(sender)?;
+        let id = transaction.id.clone();
+        self.add_transaction_object(transaction)?;
+        Ok(id)
+    }
+
+    /// Add a transaction object directly to pending transactions
+    /// 
+    /// Transfers, contract withdrawals and staking must be signed by their sender and
+    /// carry the sender's next nonce; a lower nonce is a replay and a higher one would
+    /// leave a gap, so both are rejected. Time-locked transfers must be signed by their
+    /// sender too. Any transaction whose ID is already pending or has been mined is
+    /// rejected.
+    /// 
+    /// # Arguments
+    /// * `transaction` - The transaction to add
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if added successfully, error otherwise
+    pub fn add_transaction_object(&mut self, transaction: Transaction) -> Result<()> {
+        Self::check_receiver_address(&transaction.receiver)?;
+        if self.receipts.contains_key(&transaction.id) {
+            return Err(BlockchainError::TransactionValidationFailed(format!(
+                "Transaction {} has already been mined", transaction.id
+            )));
+        }
+        if self.pending_transactions.iter().any(|tx| tx.id == transaction.id) {
+            return Err(BlockchainError::TransactionValidationFailed(format!(
+                "Transaction {} is already pending", transaction.id
+            )));
+        }
+        self.check_relay_fee(&transaction)?;
+
+        // Check if sender has sufficient balance (except for coinbase transactions and
+        // contract withdrawals); time-locked transfers are checked when they unlock instead
+        if transaction.debits_sender() && transaction.unlock_height().is_none() {
+            Self::check_balance(&self.balances, &transaction.sender, transaction.amount)?;
+        }
+
+        // The signature covers the nonce, so only the sender can spend it
+        if transaction.requires_sender_signature() && !transaction.is_signed_by_sender()? {
+            return Err(BlockchainError::TransactionValidationFailed(format!(
+                "Transaction {} must be signed by {}", transaction.id, transaction.sender
+            )));
+        }
+        if transaction.uses_nonce() {
+            let expected_nonce = self.next_nonce(&transaction.sender);
+            if transaction.nonce < expected_nonce {
+                return Err(BlockchainError::TransactionValidationFailed(format!(
+                    "Nonce {} from {} has already been used",
+                    transaction.nonce, transaction.sender
+                )));
+            } else if transaction.nonce > expected_nonce {
+                return Err(BlockchainError::TransactionValidationFailed(format!(
+                    "Nonce {} from {} is ahead of expected nonce {}",
+                    transaction.nonce, transaction.sender, expected_nonce
+                )));
+            }
+        }
+
+        self.publish_event(EventType::NewTransaction, &transaction);
+        self.pending_transactions.push(transaction);
+        debug!("Added transaction object to pending queue");
+        Ok(())
+    }
+
+    /// Replace the mempool with transactions reloaded from storage
+    /// 
+    /// Each transaction is re-validated against the current chain state in the
+    /// order it was accepted: transactions older than `MEMPOOL_EXPIRY_SECS`,
+    /// already mined, no longer affordable given the transfers queued ahead of
+    /// them, or carrying a nonce that is no longer next for their sender are
+    /// dropped. No events are published for restored transactions.
+    /// 
+    /// # Arguments
+    /// * `transactions` - Pending transactions in mempool order
+    /// 
+    /// # Returns
+    /// * `usize` - Number of transactions dropped
+    pub fn restore_pending_transactions(&mut self, transactions: Vec<Transaction>) -> usize {
+        let now = chrono::Utc::now().timestamp();
+        let mut committed: HashMap<String, u128> = HashMap::new();
+        let mut dropped = 0;
+
+        self.pending_transactions.clear();
+        for transaction in transactions {
+            match self.revalidate_pending(&transaction, now, &committed) {
+                Ok(amount) => {
+                    if transaction.debits_sender() && transaction.unlock_height().is_none() {
+                        *committed.entry(transaction.sender.clone()).or_insert(0) += amount;
+                    }
+                    self.pending_transactions.push(transaction);
+                }
+                Err(e) => {
+                    warn!("Dropping pending transaction {}: {}", transaction.id, e);
+                    dropped += 1;
+                }
+            }
+        }
+
+        info!("Restored {} pending transactions, dropped {}", self.pending_transactions.len(), dropped);
+        dropped
+    }
+
+    /// Check a reloaded pending transaction against the current chain state
+    /// 
+    /// # Arguments
+    /// * `transaction` - The transaction to check
+    /// * `now` - Current Unix timestamp
+    /// * `committed` - Amounts already queued by each sender ahead of this transaction
+    /// 
+    /// # Returns
+    /// * `Result<u128>` - The transaction amount in base units if it is still valid
+    fn revalidate_pending(
+        &self,
+        transaction: &Transaction,
+        now: i64,
+        committed: &HashMap<String, u128>,
+    ) -> Result<u128> {
+        if now - transaction.timestamp > MEMPOOL_EXPIRY_SECS {
+            return Err(BlockchainError::TransactionValidationFailed(
+                "Transaction has expired".to_string(),
+            ));
+        }
+        if self.receipts.contains_key(&transaction.id) {
+            return Err(BlockchainError::TransactionValidationFailed(
+                "Transaction has already been mined".to_string(),
+            ));
+        }
+        Self::check_receiver_address(&transaction.receiver)?;
+        self.check_relay_fee(transaction)?;
+
+        let amount = transaction.amount;
+        if transaction.debits_sender() && transaction.unlock_height().is_none() {
+            let available = self.get_balance_units(&transaction.sender)
+                .saturating_sub(committed.get(&transaction.sender).copied().unwrap_or(0));
+            if available < amount {
+                return Err(BlockchainError::InsufficientBalance {
+                    address: transaction.sender.clone(),
+                    balance: units::from_base_units(available),
+                    required: units::from_base_units(amount),
+                });
+            }
+        }
+
+        if transaction.uses_nonce() {
+            let expected_nonce = self.next_nonce(&transaction.sender);
+            if transaction.nonce != expected_nonce {
+                return Err(BlockchainError::TransactionValidationFailed(format!(
+                    "Nonce {} from {} is no longer next (expected {})",
+                    transaction.nonce, transaction.sender, expected_nonce
+                )));
+            }
+        }
+
+        Ok(amount)
+    }
+
+    /// Reject transactions paying less than `min_relay_fee` per byte
+    /// 
+    /// Only contract deployments and calls carry a fee, so every other
+    /// transaction type is exempt.
+    fn check_relay_fee(&self, transaction: &Transaction) -> Result<()> {
+        use crate::transaction::TransactionType;
+
+        let pays_fee = matches!(
+            transaction.transaction_type,
+            TransactionType::ContractDeploy | TransactionType::ContractCall
+        );
+        if self.min_relay_fee <= 0.0 || !pays_fee {
+            return Ok(());
+        }
+
+        let fee_per_byte = transaction.fee_per_byte();
+        if fee_per_byte < self.min_relay_fee {
+            return Err(BlockchainError::InvalidInput(format!(
+                "Transaction {} pays {} per byte, below the minimum relay fee of {} per byte",
+                transaction.id, fee_per_byte, self.min_relay_fee
+            )));
+        }
+        Ok(())
+    }
+
+    /// Create a new blockchain with storage integration
+    /// 
+    /// The chain stored in `storage` is loaded if there is one; otherwise a new
+    /// Proof of Work chain is created and saved. A stored chain that cannot be
+    /// loaded is an error and is left untouched.
+    /// 
+    /// # Arguments
+    /// * `difficulty` - Mining difficulty level
+    /// * `mining_reward` - Reward for mining a block
+    /// * `storage` - Blockchain storage instance
+    /// 
+    /// # Returns
+    /// * `Result<Blockchain>` - The created blockchain or an error
+    pub fn with_storage(difficulty: u32, mining_reward: f64, storage: &std::sync::Arc<crate::storage::BlockchainStorage>) -> Result<Self> {
+        if storage.is_empty()? {
+            info!("Creating new blockchain (storage is empty)");
+            let new_blockchain = Self::new_pow(difficulty, mining_reward)?;
+            
+            // Save the new blockchain with genesis block to storage
+            storage.save_blockchain(&new_blockchain)?;
+            info!("Saved new blockchain with genesis block to storage");
+            return Ok(new_blockchain);
+        }
+
+        let mut blockchain = storage.load_blockchain(difficulty, mining_reward)?;
+        info!("Loaded blockchain from storage");
+
+        // Pending transactions may have become invalid while the node was down
+        let pending = std::mem::take(&mut blockchain.pending_transactions);
+        if blockchain.restore_pending_transactions(pending) > 0 {
+            storage.save_pending_transactions(&blockchain.pending_transactions)?;
+        }
+        Ok(blockchain)
+    }
+
+    /// Save the blockchain to storage
+    /// 
+    /// # Arguments
+    /// * `storage` - Blockchain storage instance
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if saved successfully
+    pub fn save_to_storage(&self, storage: &std::sync::Arc<crate::storage::BlockchainStorage>) -> Result<()> {
+        Ok(storage.save_blockchain(self)?)
+    }
+
+
+
+    /// Adjust mining difficulty based on recent mining times
+    /// 
+    /// # Arguments
+    /// * `target_time` - Target time for mining (in seconds)
     /// 
     /// # Returns
     /// * `Result<()>` - Ok if adjusted successfully, error otherwise
     pub fn adjust_difficulty(&mut self, target_time: f64) -> Result<()> {
-        if self.blocks.len() < 2 {
-            return Ok(()); // Need at least 2 blocks to calculate time difference
+        // Need at least 2 blocks to calculate time difference
+        let Some(avg_time) = self.average_block_time() else {
+            return Ok(());
+        };
+
+        let Some(proof_of_work) = self.proof_of_work() else {
+            return Ok(());
+        };
+        let new_difficulty = proof_of_work.adjust_difficulty(target_time, avg_time);
+        
+        if new_difficulty != self.difficulty {
+            self.set_difficulty(new_difficulty)?;
+            info!("Adjusted difficulty to {}", new_difficulty);
+        }
+
+        Ok(())
+    }
+
+    /// Restore the difficulty the next block must be mined at from the chain tip
+    /// 
+    /// Used when a chain is loaded without its consensus state, so that it carries
+    /// on from the last retarget rather than from the configured difficulty.
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if restored successfully, error otherwise
+    pub fn resume_difficulty(&mut self) -> Result<()> {
+        let Some(tip) = self.blocks.last().filter(|block| !block.is_genesis() && block.difficulty != 0) else {
+            return Ok(());
+        };
+        if self.proof_of_work().is_none() {
+            return Ok(());
+        }
+
+        let (index, difficulty) = (tip.index, tip.difficulty);
+        self.set_difficulty(difficulty)?;
+        if self.is_retarget_height(index) {
+            self.retarget_difficulty()?;
+        }
+        Ok(())
+    }
+
+    /// Check whether difficulty is retargeted once the block at `height` is added
+    fn is_retarget_height(&self, height: u64) -> bool {
+        height > 0 && height.is_multiple_of(self.retarget_interval)
+    }
+
+    /// Adjust mining difficulty towards the configured target block time
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if adjusted successfully, error otherwise
+    pub fn retarget_difficulty(&mut self) -> Result<()> {
+        self.adjust_difficulty(self.target_block_time_secs)
+    }
+
+    /// Get the average time between the most recent blocks
+    /// 
+    /// The genesis block is left out, since its timestamp does not reflect
+    /// when mining started.
+    /// 
+    /// # Returns
+    /// * `Option<f64>` - Average seconds between the last `BLOCK_TIME_WINDOW` blocks, if at least two were mined
+    pub fn average_block_time(&self) -> Option<f64> {
+        let mined = self.blocks.get(1..)?;
+        let recent = &mined[mined.len().saturating_sub(BLOCK_TIME_WINDOW)..];
+        if recent.len() < 2 {
+            return None;
+        }
+
+        let total_time = recent[recent.len() - 1].timestamp - recent[0].timestamp;
+        Some(total_time as f64 / (recent.len() - 1) as f64)
+    }
+
+    /// Set the target time between blocks
+    ///
+    /// # Arguments
+    /// * `secs` - Target seconds between blocks
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if set successfully, error if the target is not positive
+    pub fn set_target_block_time(&mut self, secs: f64) -> Result<()> {
+        if !secs.is_finite() || secs <= 0.0 {
+            return Err(BlockchainError::InvalidInput(
+                format!("Invalid target block time: {}", secs),
+            ));
+        }
+        self.target_block_time_secs = secs;
+        info!("Set target block time to {}s", secs);
+        Ok(())
+    }
+
+    /// Set the mining difficulty
+    ///
+    /// # Arguments
+    /// * `difficulty` - New number of leading zeros required
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if set successfully, error otherwise
+    pub fn set_difficulty(&mut self, difficulty: u32) -> Result<()> {
+        if let Some(proof_of_work) = self.consensus.as_any_mut().downcast_mut::<ProofOfWork>() {
+            *proof_of_work = ProofOfWork::new(difficulty, proof_of_work.max_attempts)?;
+        }
+        self.difficulty = difficulty;
+        info!("Set difficulty to {}", difficulty);
+        Ok(())
+    }
+
+    /// Set the mining reward
+    ///
+    /// # Arguments
+    /// * `mining_reward` - New reward paid for each mined block
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if set successfully, error otherwise
+    pub fn set_mining_reward(&mut self, mining_reward: f64) -> Result<()> {
+        if !mining_reward.is_finite() || mining_reward < 0.0 {
+            return Err(BlockchainError::InvalidInput(
+                format!("Invalid mining reward: {}", mining_reward),
+            ));
+        }
+        self.mining_reward = mining_reward;
+        info!("Set mining reward to {}", mining_reward);
+        Ok(())
+    }
+}
+
+/// Statistics about the blockchain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockchainStats {
+    /// Number of blocks in the chain
+    pub block_count: usize,
+    /// Number of pending transactions
+    pub pending_transactions: usize,
+    /// Total number of transactions in all blocks
+    pub total_transactions: usize,
+    /// Total amount transferred in all blocks, in base units
+    pub total_amount: u128,
+    /// Total size of the blockchain in bytes
+    pub chain_size: usize,
+    /// Current mining difficulty
+    pub difficulty: u32,
+    /// Mining reward amount
+    pub mining_reward: f64,
+    /// Target time between blocks, in seconds
+    pub target_block_time: f64,
+    /// Measured average time between recent blocks, in seconds
+    pub average_block_time: Option<f64>,
+    /// Blockchain version
+    pub version: String,
+}
+
+impl std::fmt::Display for BlockchainStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Blockchain Stats:\n\
+             Blocks: {}\n\
+             Pending Transactions: {}\n\
+             Total Transactions: {}\n\
+             Total Amount: {} GIL\n\
+             Chain Size: {} bytes\n\
+             Difficulty: {}\n\
+             Mining Reward: {:.2} GIL\n\
+             Target Block Time: {:.1}s\n\
+             Average Block Time: {}\n\
+             Version: {}",
+            self.block_count,
+            self.pending_transactions,
+            self.total_transactions,
+            units::format_units(self.total_amount),
+            self.chain_size,
+            self.difficulty,
+            self.mining_reward,
+            self.target_block_time,
+            self.average_block_time.map_or("n/a".to_string(), |secs| format!("{:.1}s", secs)),
+            self.version
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::ReceiptStatus;
+
+    #[test]
+    fn test_blockchain_creation() {
+        let blockchain = Blockchain::new_pow(4, 50.0).unwrap();
+        assert_eq!(blockchain.difficulty, 4);
+        assert_eq!(blockchain.mining_reward, 50.0);
+        assert_eq!(blockchain.blocks.len(), 1); // Genesis block
+    }
+
+    #[test]
+    fn test_blockchain_default() {
+        let blockchain = Blockchain::new_default().unwrap();
+        assert_eq!(blockchain.difficulty, DEFAULT_DIFFICULTY);
+        assert_eq!(blockchain.mining_reward, 50.0);
+    }
+
+    #[test]
+    fn test_add_transaction() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_default().unwrap();
+        
+        // Add some initial balance
+        blockchain.balances.insert(alice.clone(), units::gil(1000));
+        
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(100), None).unwrap();
+        assert_eq!(blockchain.pending_transactions.len(), 1);
+    }
+
+    #[test]
+    fn test_insufficient_balance() {
+        let alice = crypto::KeyPair::generate().unwrap();
+        let mut blockchain = Blockchain::new_default().unwrap();
+        
+        let result = blockchain.add_transaction(&alice, "bob".to_string(), units::gil(100), None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_mine_block() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_default().unwrap();
+        
+        // Add some initial balance
+        blockchain.balances.insert(alice.clone(), units::gil(1000));
+        
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(100), None).unwrap();
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        
+        assert_eq!(block.transactions.len(), 2); // 1 user tx + 1 reward tx
+        assert_eq!(blockchain.blocks.len(), 2); // Genesis + new block
+    }
+
+    #[test]
+    fn test_validate_chain() {
+        let mut blockchain = Blockchain::new_default().unwrap();
+        assert!(blockchain.validate_chain().unwrap());
+    }
+
+    #[test]
+    fn test_get_latest_block() {
+        let blockchain = Blockchain::new_default().unwrap();
+        let latest = blockchain.get_latest_block().unwrap();
+        assert_eq!(latest.index, 0); // Genesis block
+    }
+
+    #[test]
+    fn test_get_balance() {
+        let mut blockchain = Blockchain::new_default().unwrap();
+        blockchain.balances.insert("alice".to_string(), units::gil(100));
+        
+        assert_eq!(blockchain.get_balance("alice"), 100.0);
+        assert_eq!(blockchain.get_balance("bob"), 0.0);
+    }
+
+    #[test]
+    fn test_blockchain_stats() {
+        let blockchain = Blockchain::new_default().unwrap();
+        let stats = blockchain.get_stats();
+        
+        assert_eq!(stats.block_count, 1); // Genesis block
+        assert_eq!(stats.pending_transactions, 0);
+        assert_eq!(stats.difficulty, DEFAULT_DIFFICULTY);
+    }
+
+    #[test]
+    fn test_target_block_time_in_stats() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        assert_eq!(blockchain.get_stats().target_block_time, DEFAULT_TARGET_BLOCK_TIME_SECS);
+        assert!(blockchain.set_target_block_time(0.0).is_err());
+        assert!(blockchain.set_target_block_time(f64::NAN).is_err());
+        blockchain.set_target_block_time(2.5).unwrap();
+
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(blockchain.get_stats().average_block_time, None);
+
+        for _ in 0..3 {
+            blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+        }
+
+        let stats = blockchain.get_stats();
+        assert_eq!(stats.target_block_time, 2.5);
+        let average = stats.average_block_time.unwrap();
+        assert!((0.0..5.0).contains(&average), "average block time {}", average);
+    }
+
+    #[test]
+    fn test_difficulty_retargets_at_interval() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let allocations = HashMap::from([(alice.clone(), units::gil(100))]);
+        let mut blockchain = Blockchain::new_pow_with_genesis(1, 50.0, allocations).unwrap();
+        blockchain.set_target_block_time(30.0).unwrap();
+
+        for _ in 1..DEFAULT_RETARGET_INTERVAL {
+            blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+        }
+        assert_eq!(blockchain.difficulty, 1);
+
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(blockchain.difficulty, 2);
+
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(block.difficulty, 2);
+
+        // Blocks mined before the retarget still validate, and a replay reaches the same difficulty
+        assert!(blockchain.validate_chain().unwrap());
+        let replayed = blockchain.replay_blocks(blockchain.blocks.clone()).unwrap();
+        assert_eq!(replayed.difficulty, 2);
+
+        let mut restored = blockchain.clone();
+        restored.set_difficulty(1).unwrap();
+        restored.blocks.truncate(DEFAULT_RETARGET_INTERVAL as usize + 1);
+        restored.resume_difficulty().unwrap();
+        assert_eq!(restored.difficulty, 2);
+    }
+
+    #[test]
+    fn test_blockchain_json_serialization() {
+        let blockchain = Blockchain::new_default().unwrap();
+        let json = blockchain.to_json().unwrap();
+        let deserialized = Blockchain::from_json(&json).unwrap();
+        
+        assert_eq!(blockchain.blocks.len(), deserialized.blocks.len());
+        assert_eq!(blockchain.difficulty, deserialized.difficulty);
+        assert_eq!(deserialized.proof_of_work().unwrap().difficulty, blockchain.difficulty);
+
+        // The consensus keeps its mechanism and state through serialization
+        let pos_chain = Blockchain::new_pos_with_validators(10.0, units::gil(100), 10, vec![
+            ("key".to_string(), "validator1".to_string(), units::gil(1000)),
+        ]).unwrap();
+        let deserialized = Blockchain::from_json(&pos_chain.to_json().unwrap()).unwrap();
+        assert_eq!(deserialized.get_consensus_type(), ConsensusType::ProofOfStake);
+        assert_eq!(deserialized.proof_of_stake().unwrap().validators["validator1"].stake_amount, units::gil(1000));
+    }
+
+    fn snapshot_chain() -> Blockchain {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let bob_key = crypto::KeyPair::generate().unwrap();
+        let bob = crypto::create_address(&bob_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.add_transaction_object(Transaction::new_transfer("COINBASE".to_string(), alice.clone(), units::gil(500), None).unwrap()).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        blockchain.add_transaction(&alice_key, bob.clone(), units::gil(120), None).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        blockchain.deploy_contract(
+            alice.clone(),
+            "PUSH 7\nSTORE value\nRETURN".to_string(),
+            10_000,
+            0.000001,
+        ).unwrap();
+        blockchain.add_transaction(&bob_key, "carol".to_string(), units::gil(20), None).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        blockchain
+    }
+
+    #[test]
+    fn test_max_txs_per_block() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.max_txs_per_block = 3;
+        blockchain.balances.insert(alice.clone(), units::gil(1000));
+        for i in 0..5 {
+            blockchain.add_transaction(&alice_key, format!("user{}", i), units::gil(10), None).unwrap();
+        }
+
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(block.transactions.len(), 4); // 3 user txs + 1 reward tx
+        assert_eq!(blockchain.pending_transactions.len(), 2);
+        assert_eq!(blockchain.pending_transactions[0].receiver, "user3");
+
+        // Zero means only the byte size limit applies
+        blockchain.max_txs_per_block = 0;
+        blockchain.add_transaction(&alice_key, "user5".to_string(), units::gil(10), None).unwrap();
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(block.transactions.len(), 4);
+        assert!(blockchain.pending_transactions.is_empty());
+    }
+
+    #[test]
+    fn test_replayed_transaction_rejected() {
+        let keypair = crate::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&keypair.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(1000));
+
+        let mut tx = Transaction::new_transfer(alice.clone(), "bob".to_string(), units::gil(10), None).unwrap();
+        tx.set_nonce(blockchain.next_nonce(&alice));
+        tx.sign(&keypair).unwrap();
+        blockchain.add_transaction_object(tx.clone()).unwrap();
+
+        // Replaying while still pending
+        assert!(matches!(
+            blockchain.add_transaction_object(tx.clone()),
+            Err(BlockchainError::TransactionValidationFailed(_))
+        ));
+
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(blockchain.get_nonce(&alice), 1);
+
+        // Replaying after it was mined
+        assert!(matches!(
+            blockchain.add_transaction_object(tx.clone()),
+            Err(BlockchainError::TransactionValidationFailed(_))
+        ));
+
+        // Stripping the signature does not earn the replay a fresh nonce
+        let mut unsigned = tx.clone();
+        unsigned.signature = None;
+        assert!(matches!(
+            blockchain.add_transaction_object(unsigned),
+            Err(BlockchainError::TransactionValidationFailed(_))
+        ));
+
+        // Nor does signing it with a key that is not the sender's
+        let mut resigned = tx;
+        resigned.set_nonce(blockchain.next_nonce(&alice));
+        resigned.sign(&crate::KeyPair::generate().unwrap()).unwrap();
+        assert!(matches!(
+            blockchain.add_transaction_object(resigned),
+            Err(BlockchainError::TransactionValidationFailed(_))
+        ));
+        assert!(blockchain.pending_transactions.is_empty());
+        assert_eq!(blockchain.get_balance(&alice), 990.0);
+    }
+
+    #[test]
+    fn test_out_of_order_nonces() {
+        let keypair = crate::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&keypair.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(1000));
+
+        let mut ahead = Transaction::new_transfer(alice.clone(), "bob".to_string(), units::gil(10), None).unwrap();
+        ahead.set_nonce(1);
+        ahead.sign(&keypair).unwrap();
+        assert!(blockchain.add_transaction_object(ahead.clone()).is_err());
+        assert!(blockchain.pending_transactions.is_empty());
+
+        // Once the gap is filled the same nonce is accepted
+        blockchain.add_transaction(&keypair, "carol".to_string(), units::gil(5), None).unwrap();
+        assert_eq!(blockchain.next_nonce(&alice), 1);
+        blockchain.add_transaction_object(ahead).unwrap();
+        assert_eq!(blockchain.next_nonce(&alice), 2);
+
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(blockchain.get_nonce(&alice), 2);
+
+        // A block carrying a stale nonce cannot be processed
+        let mut stale = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(1), None).unwrap();
+        stale.set_nonce(0);
+        assert!(blockchain.process_transaction(&stale).is_err());
+    }
+
+    #[test]
+    fn test_inclusion_proof() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(1000));
+        for receiver in ["bob", "charlie", "dave"] {
+            blockchain.add_transaction(&alice_key, receiver.to_string(), units::gil(10), None).unwrap();
+        }
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+
+        for tx in &block.transactions {
+            let proof = blockchain.inclusion_proof(&tx.id).unwrap();
+            let tx_hash = crate::merkle::transaction_hash(tx).unwrap();
+            assert!(crate::merkle::verify_inclusion(&block.hash, &proof, &tx_hash));
+        }
+
+        assert!(matches!(blockchain.inclusion_proof("missing"), Err(BlockchainError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_inclusion_proof_rejects_tampering() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(1000));
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(10), None).unwrap();
+        blockchain.add_transaction(&alice_key, "charlie".to_string(), units::gil(20), None).unwrap();
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        let tx = &block.transactions[0];
+        let tx_hash = crate::merkle::transaction_hash(tx).unwrap();
+        let proof = blockchain.inclusion_proof(&tx.id).unwrap();
+
+        let mut tampered_path = proof.clone();
+        tampered_path.path[0].0 = crate::utils::calculate_hash("forged");
+        assert!(!crate::merkle::verify_inclusion(&block.hash, &tampered_path, &tx_hash));
+
+        let mut tampered_header = proof.clone();
+        tampered_header.header.as_mut().unwrap().merkle_root = tampered_path.compute_root(&tx_hash);
+        assert!(!crate::merkle::verify_inclusion(&block.hash, &tampered_header, &tx_hash));
+
+        let other_hash = crate::merkle::transaction_hash(&block.transactions[1]).unwrap();
+        assert!(!crate::merkle::verify_inclusion(&block.hash, &proof, &other_hash));
+        assert!(!crate::merkle::verify_inclusion(&blockchain.blocks[0].hash, &proof, &tx_hash));
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("chain.snapshot");
+        let blockchain = snapshot_chain();
+        assert!(blockchain.blocks.len() >= 4);
+        assert!(!blockchain.contracts.is_empty());
+
+        blockchain.export_snapshot(&path).unwrap();
+        let imported = Blockchain::import_snapshot(&path).unwrap();
+
+        assert_eq!(imported.blocks, blockchain.blocks);
+        assert_eq!(imported.balances, blockchain.balances);
+        assert_eq!(
+            serde_json::to_value(&imported.contracts).unwrap(),
+            serde_json::to_value(&blockchain.contracts).unwrap()
+        );
+        assert_eq!(imported.difficulty, blockchain.difficulty);
+
+        // The binary snapshot is smaller than the pretty JSON
+        let snapshot_len = std::fs::metadata(&path).unwrap().len() as usize;
+        assert!(snapshot_len < blockchain.to_json().unwrap().len());
+    }
+
+    #[test]
+    fn test_snapshot_rejects_corruption_and_version_mismatch() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("chain.snapshot");
+        snapshot_chain().export_snapshot(&path).unwrap();
+        let original = std::fs::read(&path).unwrap();
+
+        // Flip a byte in the payload
+        let mut corrupted = original.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xff;
+        std::fs::write(&path, &corrupted).unwrap();
+        assert!(matches!(
+            Blockchain::import_snapshot(&path),
+            Err(BlockchainError::StateCorruption(_))
+        ));
+
+        // Unknown format version
+        let mut future = original.clone();
+        future[8..12].copy_from_slice(&(SNAPSHOT_FORMAT_VERSION + 1).to_le_bytes());
+        std::fs::write(&path, &future).unwrap();
+        assert!(matches!(
+            Blockchain::import_snapshot(&path),
+            Err(BlockchainError::InvalidInput(_))
+        ));
+
+        // Snapshot of a different blockchain version
+        let mut old_chain = snapshot_chain();
+        old_chain.version = "0.0.1".to_string();
+        old_chain.export_snapshot(&path).unwrap();
+        assert!(matches!(
+            Blockchain::import_snapshot(&path),
+            Err(BlockchainError::InvalidInput(_))
+        ));
+
+        std::fs::write(&path, b"not a snapshot").unwrap();
+        assert!(Blockchain::import_snapshot(&path).is_err());
+    }
+
+    #[test]
+    fn test_contract_calls_contract() {
+        let mut blockchain = Blockchain::new_default().unwrap();
+        blockchain.balances.insert("alice".to_string(), units::gil(1000));
+
+        let store_id = blockchain.deploy_contract(
+            "bob123".to_string(),
+            "PUSH 7\nSTORE value\nRETURN".to_string(),
+            10_000,
+            0.000001,
+        ).unwrap();
+        let caller_id = blockchain.deploy_contract(
+            "carol123".to_string(),
+            format!("PUSH \"{}\"\nCALL\nRETURN", store_id),
+            10_000,
+            0.000001,
+        ).unwrap();
+
+        blockchain.contracts.get_mut(&store_id).unwrap().storage.clear();
+        blockchain.call_contract(
+            "alice".to_string(),
+            caller_id,
+            "run".to_string(),
+            units::gil(1),
+            10_000,
+            0.000001,
+        ).unwrap();
+
+        assert_eq!(blockchain.contracts[&store_id].storage.get("value"), Some(&"7".to_string()));
+    }
+
+    #[test]
+    fn test_repeated_deployments_get_distinct_reproducible_ids() {
+        let code = "PUSH 7\nSTORE value\nRETURN".to_string();
+        let deploy_twice = || {
+            let mut blockchain = Blockchain::new_default().unwrap();
+            let first = blockchain.deploy_contract("bob123".to_string(), code.clone(), 10_000, 0.000001).unwrap();
+            let second = blockchain.deploy_contract("bob123".to_string(), code.clone(), 10_000, 0.000001).unwrap();
+            assert_eq!(blockchain.contracts.len(), 2);
+            assert_eq!(blockchain.deployment_nonce("bob123"), 2);
+            (first, second)
+        };
+
+        let (first, second) = deploy_twice();
+        assert_ne!(first, second);
+        assert_eq!(first, SmartContract::generate_id(&code, "bob123", 0));
+        assert_eq!(second, SmartContract::generate_id(&code, "bob123", 1));
+        assert_eq!(deploy_twice(), (first, second));
+    }
+
+    #[test]
+    fn test_transaction_receipts() {
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert("alice".to_string(), units::gil(1000));
+        blockchain.balances.insert("bob".to_string(), units::gil(10));
+
+        let deploy = Transaction::new_contract_deploy(
+            "alice".to_string(),
+            "PUSH 1\nEMIT Deployed\nPUSH 7\nSTORE value\nPUSH 7\nRETURN".to_string(),
+            10_000,
+            0.000001,
+        ).unwrap();
+        blockchain.add_transaction_object(deploy.clone()).unwrap();
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+
+        let receipt = blockchain.get_receipt(&deploy.id).unwrap();
+        assert!(receipt.is_success());
+        assert_eq!(receipt.block_index, block.index);
+        assert!(receipt.gas_used > 0);
+        assert_eq!(receipt.events.len(), 1);
+        assert_eq!(receipt.events[0].event_name, "Deployed");
+        let contract_id = blockchain.contracts.keys().next().unwrap().clone();
+
+        // A call that runs out of gas is mined with a failed receipt and only pays for its gas
+        let call = Transaction::new_contract_call(
+            "alice".to_string(), contract_id.clone(), "run".to_string(), units::gil(1), 10_000, 0.000001,
+        ).unwrap();
+        let starved = Transaction::new_contract_call(
+            "bob".to_string(), contract_id.clone(), "run".to_string(), units::gil(1), 1, 0.000001,
+        ).unwrap();
+        blockchain.add_transaction_object(call.clone()).unwrap();
+        blockchain.add_transaction_object(starved.clone()).unwrap();
+        let balance_before = blockchain.get_balance_units("alice");
+        let bob_before = blockchain.get_balance_units("bob");
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(block.transactions.len(), 3);
+
+        let receipt = blockchain.get_receipt(&call.id).unwrap();
+        assert!(receipt.is_success());
+        assert!(receipt.gas_used > 0);
+        assert_eq!(receipt.return_value.as_deref(), Some("7"));
+
+        let receipt = blockchain.get_receipt(&starved.id).unwrap();
+        assert!(matches!(&receipt.status, ReceiptStatus::Failed { error } if !error.is_empty()));
+        assert_eq!(receipt.gas_used, 1);
+        assert!(matches!(
+            blockchain.transaction_status(&starved.id),
+            Some(TransactionStatus::Failed { block_number, reason, .. }) if block_number == block.index && !reason.is_empty()
+        ));
+        assert_eq!(
+            blockchain.get_balance_units("alice"),
+            balance_before - Blockchain::contract_call_cost(&call).unwrap()
+        );
+        assert_eq!(blockchain.get_balance_units("bob"), bob_before - units::to_base_units(0.000001).unwrap());
+        assert_eq!(blockchain.contracts[&contract_id].balance, units::gil(1));
+
+        // Reward transactions get a plain success receipt
+        let reward = block.transactions.last().unwrap();
+        assert_eq!(blockchain.get_receipt(&reward.id).unwrap().gas_used, 0);
+        assert!(blockchain.get_receipt("unknown").is_none());
+    }
+
+    #[test]
+    fn test_rollback_drops_receipts_and_events() {
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert("alice".to_string(), units::gil(1000));
+        let contract_id = blockchain.deploy_contract(
+            "alice".to_string(),
+            "PUSH 1\nEMIT Ping\nRETURN".to_string(),
+            10_000,
+            0.000001,
+        ).unwrap();
+        blockchain.event_log = ContractEventLog::new();
+
+        let mut calls = Vec::new();
+        for sender in ["alice", "bob", "carol"] {
+            blockchain.balances.insert(sender.to_string(), units::gil(10));
+            let call = Transaction::new_contract_call(
+                sender.to_string(), contract_id.clone(), "run".to_string(), units::gil(1), 10_000, 0.000001,
+            ).unwrap();
+            blockchain.add_transaction_object(call.clone()).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+            calls.push(call);
+        }
+        assert_eq!(blockchain.query_events(Some(&contract_id), Some("Ping"), 0).len(), 3);
+
+        blockchain.rollback_to_snapshot(1).unwrap();
+
+        assert_eq!(blockchain.blocks.len(), 2);
+        assert_eq!(blockchain.get_receipt(&calls[0].id).unwrap().block_index, 1);
+        assert!(blockchain.get_receipt(&calls[1].id).is_none());
+        assert!(blockchain.get_receipt(&calls[2].id).is_none());
+        assert!(blockchain.receipts.values().all(|receipt| receipt.block_index <= 1));
+        let events = blockchain.query_events(Some(&contract_id), Some("Ping"), 0);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].block_index, 1);
+    }
+
+    #[test]
+    fn test_query_events() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(1000));
+
+        let counter_id = blockchain.deploy_contract(
+            alice.clone(),
+            "PUSH 1\nEMIT CounterIncremented\nRETURN".to_string(),
+            10_000,
+            0.000001,
+        ).unwrap();
+        let other_id = blockchain.deploy_contract(
+            "bob123".to_string(),
+            "PUSH 0\nEMIT CounterReset\nRETURN".to_string(),
+            10_000,
+            0.000001,
+        ).unwrap();
+
+        // Deployment emits at block 1, then mine a block so later calls land at block 2
+        let call = |blockchain: &mut Blockchain, contract: &str| {
+            blockchain.call_contract(
+                alice.clone(),
+                contract.to_string(),
+                "increment".to_string(),
+                units::gil(1),
+                10_000,
+                0.000001,
+            ).unwrap();
+        };
+        call(&mut blockchain, &counter_id);
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        call(&mut blockchain, &counter_id);
+        call(&mut blockchain, &counter_id);
+        call(&mut blockchain, &other_id);
+
+        let incremented = blockchain.query_events(None, Some("CounterIncremented"), 0);
+        assert_eq!(incremented.len(), 4);
+        assert!(incremented.iter().all(|e| e.contract_id == counter_id));
+
+        let recent = blockchain.query_events(Some(&counter_id), Some("CounterIncremented"), 2);
+        assert_eq!(recent.len(), 2);
+        assert!(recent.iter().all(|e| e.block_index == 2));
+
+        let from_other = blockchain.query_events(Some(&other_id), None, 0);
+        assert_eq!(from_other.len(), 2);
+        assert!(from_other.iter().all(|e| e.event_name == "CounterReset"));
+    }
+
+    fn deploy_counter(blockchain: &mut Blockchain) -> String {
+        blockchain.deploy_contract(
+            "bob123".to_string(),
+            [
+                "PUSH 0", "STORE count", "RETURN",
+                "METHOD increment", "LOAD count", "PUSH 1", "ADD", "STORE count", "RETURN",
+                "METHOD fail", "PUSH 1", "PUSH 0", "DIV", "RETURN",
+                "METHOD get_value VIEW", "LOAD count", "RETURN",
+                "METHOD plus VIEW", "LOAD count", "ADD", "RETURN",
+                "METHOD sneaky VIEW", "PUSH 9", "STORE count", "RETURN",
+            ].join("\n"),
+            10_000,
+            0.000001,
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_estimate_gas_then_submit() {
+        let mut blockchain = Blockchain::new_default().unwrap();
+        blockchain.balances.insert("alice".to_string(), units::gil(1000));
+        let counter_id = deploy_counter(&mut blockchain);
+        let metrics_before = blockchain.contract_metrics.clone();
+
+        let estimate = blockchain.estimate_gas("alice", &counter_id, "increment", units::gil(1)).unwrap();
+        assert!(estimate > 0);
+        assert_eq!(blockchain.contracts[&counter_id].storage.get("count"), Some(&"0".to_string()));
+        assert_eq!(blockchain.get_balance("alice"), 1000.0);
+        assert_eq!(blockchain.contract_metrics, metrics_before);
+
+        blockchain.call_contract(
+            "alice".to_string(),
+            counter_id.clone(),
+            "increment".to_string(),
+            units::gil(1),
+            estimate,
+            0.000001,
+        ).unwrap();
+        assert_eq!(blockchain.contracts[&counter_id].storage.get("count"), Some(&"1".to_string()));
+    }
+
+    #[test]
+    fn test_estimate_gas_reverting_call() {
+        let mut blockchain = Blockchain::new_default().unwrap();
+        blockchain.balances.insert("alice".to_string(), units::gil(1000));
+        let counter_id = deploy_counter(&mut blockchain);
+
+        assert!(blockchain.estimate_gas("alice", &counter_id, "fail", units::gil(1)).is_err());
+        assert!(blockchain.estimate_gas("alice", &counter_id, "missing", units::gil(1)).is_err());
+        assert!(blockchain.estimate_gas("alice", "no_such_contract", "increment", units::gil(1)).is_err());
+    }
+
+    #[test]
+    fn test_call_view_reads_without_state_change() {
+        let mut blockchain = Blockchain::new_default().unwrap();
+        blockchain.balances.insert("alice".to_string(), units::gil(1000));
+        let counter_id = deploy_counter(&mut blockchain);
+        blockchain.call_contract(
+            "alice".to_string(),
+            counter_id.clone(),
+            "increment".to_string(),
+            units::gil(1),
+            10_000,
+            0.000001,
+        ).unwrap();
+
+        let storage_before = blockchain.contracts[&counter_id].storage.clone();
+        let balances_before = blockchain.balances.clone();
+        let metrics_before = blockchain.contract_metrics.clone();
+
+        assert_eq!(blockchain.call_view(&counter_id, "get_value", &[]).unwrap(), b"1".to_vec());
+        assert_eq!(blockchain.call_view(&counter_id, "plus", &["5".to_string()]).unwrap(), b"6".to_vec());
+
+        assert_eq!(blockchain.contracts[&counter_id].storage, storage_before);
+        assert_eq!(blockchain.balances, balances_before);
+        assert_eq!(blockchain.contract_metrics, metrics_before);
+    }
+
+    #[test]
+    fn test_call_view_rejects_non_view_methods() {
+        let mut blockchain = Blockchain::new_default().unwrap();
+        let counter_id = deploy_counter(&mut blockchain);
+
+        assert!(blockchain.call_view(&counter_id, "increment", &[]).is_err());
+        assert!(blockchain.call_view(&counter_id, "sneaky", &[]).is_err());
+        assert!(matches!(
+            blockchain.call_view("no_such_contract", "get_value", &[]),
+            Err(BlockchainError::NotFound(_))
+        ));
+        assert_eq!(blockchain.contracts[&counter_id].storage.get("count"), Some(&"0".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_auto_miner() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut chain = Blockchain::new_pow(1, 50.0).unwrap();
+        chain.balances.insert(alice.clone(), units::gil(100));
+        let blockchain = Arc::new(Mutex::new(chain));
+
+        let handle = Blockchain::start_auto_miner(
+            blockchain.clone(),
+            "miner".to_string(),
+            std::time::Duration::from_millis(50),
+        );
+
+        // Nothing is mined while the mempool is empty
+        tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+        assert_eq!(blockchain.lock().unwrap().blocks.len(), 1);
+
+        {
+            let mut chain = blockchain.lock().unwrap();
+            chain.add_transaction(&alice_key, "bob".to_string(), units::gil(10), None).unwrap();
+            chain.add_transaction(&alice_key, "carol".to_string(), units::gil(5), None).unwrap();
+        }
+        for _ in 0..100 {
+            if blockchain.lock().unwrap().pending_transactions.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        {
+            let chain = blockchain.lock().unwrap();
+            assert!(chain.blocks.len() > 1);
+            assert!(chain.pending_transactions.is_empty());
+            assert_eq!(chain.get_balance("bob"), 10.0);
+        }
+
+        assert!(blockchain.lock().unwrap().stop_auto_miner());
+        tokio::time::timeout(std::time::Duration::from_secs(5), handle).await.unwrap().unwrap();
+
+        let height = blockchain.lock().unwrap().blocks.len();
+        blockchain.lock().unwrap().add_transaction(&alice_key, "dave".to_string(), units::gil(1), None).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+        assert_eq!(blockchain.lock().unwrap().blocks.len(), height);
+    }
+
+    #[test]
+    fn test_rejects_malformed_receiver_address() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let address = crypto::create_address(&keypair.public_key());
+        let mut chars: Vec<char> = address.chars().collect();
+        let last = chars.len() - 1;
+        chars[last] = if chars[last] == '0' { '1' } else { '0' };
+        let malformed: String = chars.into_iter().collect();
+
+        assert!(matches!(
+            blockchain.add_transaction(&alice_key, malformed.clone(), units::gil(1), None),
+            Err(BlockchainError::TransactionValidationFailed(_))
+        ));
+        let transaction = Transaction::new_transfer(alice.clone(), malformed, units::gil(1), None).unwrap();
+        assert!(blockchain.add_transaction_object(transaction).is_err());
+        assert!(blockchain.pending_transactions.is_empty());
+
+        // Truncated and padded addresses are not mistaken for account names
+        for wrong_length in [address[..30].to_string(), format!("{}0", address)] {
+            assert!(matches!(
+                blockchain.add_transaction(&alice_key, wrong_length, units::gil(1), None),
+                Err(BlockchainError::TransactionValidationFailed(_))
+            ));
+        }
+        assert!(blockchain.pending_transactions.is_empty());
+
+        blockchain.add_transaction(&alice_key, address, units::gil(1), None).unwrap();
+        assert_eq!(blockchain.pending_transactions.len(), 1);
+    }
+
+    #[test]
+    fn test_simulate_transaction_matches_mining() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        blockchain.balances.insert("bob".to_string(), units::gil(5));
+
+        let transaction = Transaction::new_transfer(alice.clone(), "bob".to_string(), units::gil(30), None).unwrap();
+        let preview = blockchain.simulate_transaction(&transaction).unwrap();
+        assert_eq!(preview.len(), 2);
+        assert_eq!(preview[&alice], units::gil(70));
+        assert_eq!(preview["bob"], units::gil(35));
+
+        // Simulation leaves real state untouched
+        assert_eq!(blockchain.get_balance(&alice), 100.0);
+        assert_eq!(blockchain.get_balance("bob"), 5.0);
+        assert!(blockchain.pending_transactions.is_empty());
+
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(30), None).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        for (address, balance) in &preview {
+            assert_eq!(blockchain.get_balance_units(address), *balance);
+        }
+    }
+
+    #[test]
+    fn test_simulate_transaction_insufficient_balance() {
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert("alice".to_string(), units::gil(10));
+
+        let transaction = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(30), None).unwrap();
+        match blockchain.simulate_transaction(&transaction) {
+            Err(BlockchainError::InsufficientBalance { address, balance, required }) => {
+                assert_eq!(address, "alice");
+                assert_eq!(balance, 10.0);
+                assert_eq!(required, 30.0);
+            }
+            other => panic!("expected insufficient balance, got {:?}", other),
+        }
+        assert_eq!(blockchain.get_balance("alice"), 10.0);
+        assert_eq!(blockchain.get_balance("bob"), 0.0);
+    }
+
+    #[test]
+    fn test_pos_rewards_follow_selected_validators() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let keys = [crypto::KeyPair::generate().unwrap(), crypto::KeyPair::generate().unwrap()];
+        let mut blockchain = Blockchain::new_pos_with_validators(10.0, units::gil(100), 10, vec![
+            (keys[0].public_key_hex(), "validator1".to_string(), units::gil(1000)),
+            (keys[1].public_key_hex(), "validator2".to_string(), units::gil(3000)),
+        ]).unwrap();
+        for keypair in keys {
+            blockchain.add_validator_signer(keypair).unwrap();
+        }
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+
+        let mut selections: HashMap<String, u32> = HashMap::new();
+        for _ in 0..8 {
+            let expected = blockchain.select_validator().unwrap();
+            blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+            let block = blockchain.mine_block("miner".to_string()).unwrap();
+            assert_eq!(block.validator.as_deref(), Some(expected.as_str()));
+            *selections.entry(expected).or_insert(0) += 1;
+        }
+
+        let pos = blockchain.proof_of_stake().unwrap();
+        for validator in ["validator1", "validator2"] {
+            let expected = units::gil(selections.get(validator).copied().unwrap_or(0) as u64 * 10);
+            assert_eq!(pos.get_rewards(validator), expected);
+            assert_eq!(blockchain.get_balance_units(validator), expected);
+        }
+        // The caller-supplied miner address earns nothing under PoS
+        assert_eq!(blockchain.get_balance("miner"), 0.0);
+    }
+
+    #[test]
+    fn test_pow_mines_and_validates_through_consensus() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(2, 50.0).unwrap();
+        assert_eq!(blockchain.consensus().consensus_type(), ConsensusType::ProofOfWork);
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        assert!(block.hash.starts_with("00"));
+        assert!(block.validator.is_none());
+        assert!(blockchain.consensus().validate_block(&block).is_ok());
+        assert!(blockchain.validate_chain().unwrap());
+
+        let mut unsealed = block.clone();
+        unsealed.hash = format!("ff{}", &block.hash[2..]);
+        assert!(matches!(
+            blockchain.consensus().validate_block(&unsealed),
+            Err(BlockchainError::InvalidProofOfWork(_))
+        ));
+    }
+
+    #[test]
+    fn test_pos_mines_and_validates_through_consensus() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        // Without a validator no block can be produced
+        let mut blockchain = Blockchain::new_pos(10.0, units::gil(100), 10).unwrap();
+        assert_eq!(blockchain.consensus().consensus_type(), ConsensusType::ProofOfStake);
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+        assert!(blockchain.mine_block("miner".to_string()).is_err());
+
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let mut blockchain = Blockchain::new_pos_with_validators(10.0, units::gil(100), 10, vec![
+            (keypair.public_key_hex(), "validator1".to_string(), units::gil(1000)),
+        ]).unwrap();
+        blockchain.add_validator_signer(keypair.clone()).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(block.validator.as_deref(), Some("validator1"));
+        assert!(blockchain.validate_chain().unwrap());
+
+        let mut forged = Block::new_pos(
+            block.index + 1,
+            vec![Transaction::new_transfer("COINBASE".to_string(), "mallory".to_string(), units::gil(10), None).unwrap()],
+            block.hash.clone(),
+            blockchain.version.clone(),
+            "mallory".to_string(),
+        ).unwrap();
+        forged.hash = forged.calculate_current_hash();
+        forged.validator_signature = Some(keypair.sign(forged.hash.as_bytes()).unwrap());
+        assert!(matches!(blockchain.add_block(forged), Err(BlockchainError::ConsensusError(_))));
+        assert_eq!(blockchain.blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_pos_blocks_are_signed_by_validator() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let mut blockchain = Blockchain::new_pos_with_validators(10.0, units::gil(100), 10, vec![
+            (keypair.public_key_hex(), "validator1".to_string(), units::gil(1000)),
+        ]).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+
+        // Without the validator's key the block cannot be sealed
+        assert!(matches!(blockchain.mine_block("miner".to_string()), Err(BlockchainError::ConsensusError(_))));
+        assert!(blockchain.add_validator_signer(crypto::KeyPair::generate().unwrap()).is_err());
+
+        blockchain.add_validator_signer(keypair.clone()).unwrap();
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        let signature = block.validator_signature.as_ref().unwrap();
+        assert_eq!(signature.public_key_hex(), keypair.public_key_hex());
+        assert!(signature.verify(block.hash.as_bytes()).unwrap());
+        assert!(blockchain.validate_chain().unwrap());
+
+        // A signature by any other key invalidates the chain
+        let other = crypto::KeyPair::generate().unwrap();
+        blockchain.blocks[1].validator_signature = Some(other.sign(block.hash.as_bytes()).unwrap());
+        assert!(blockchain.validate_chain().is_err());
+
+        // So does a signature that no longer matches the block hash
+        let mut tampered = signature.clone();
+        tampered.signature[0] ^= 0xff;
+        blockchain.blocks[1].validator_signature = Some(tampered);
+        assert!(blockchain.validate_chain().is_err());
+
+        blockchain.blocks[1].validator_signature = None;
+        assert!(blockchain.validate_chain().is_err());
+    }
+
+    #[test]
+    fn test_staking_transactions_lock_balance() {
+        let bootstrap = crypto::KeyPair::generate().unwrap();
+        let mut blockchain = Blockchain::new_pos_with_validators(10.0, units::gil(100), 10, vec![
+            (bootstrap.public_key_hex(), "bootstrap".to_string(), units::gil(1000)),
+        ]).unwrap();
+        blockchain.add_validator_signer(bootstrap).unwrap();
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let address = crypto::create_address(&keypair.public_key());
+        blockchain.balances.insert(address.clone(), units::gil(1500));
+
+        let signed = |mut tx: Transaction, nonce: u64, keypair: &crypto::KeyPair| {
+            tx.set_nonce(nonce);
+            tx.sign(keypair).unwrap();
+            tx
+        };
+
+        // Only the validator's own key can stake for it
+        let forged = signed(
+            Transaction::new_validator_registration(address.clone(), units::gil(1000)).unwrap(),
+            0,
+            &crypto::KeyPair::generate().unwrap(),
+        );
+        assert!(blockchain.add_transaction_object(forged).is_err());
+
+        let registration = signed(Transaction::new_validator_registration(address.clone(), units::gil(1000)).unwrap(), 0, &keypair);
+        blockchain.add_transaction_object(registration).unwrap();
+        assert!(!blockchain.proof_of_stake().unwrap().validators.contains_key(&address));
+        blockchain.mine_block("miner".to_string()).unwrap();
+        let validator = &blockchain.proof_of_stake().unwrap().validators[&address];
+        assert_eq!(validator.stake_amount, units::gil(1000));
+        assert_eq!(validator.public_key, keypair.public_key_hex());
+        assert_eq!(blockchain.get_balance(&address), 500.0);
+        blockchain.add_validator_signer(keypair.clone()).unwrap();
+
+        // Stake cannot exceed the balance backing it
+        let overstake = signed(Transaction::new_staking(address.clone(), units::gil(1000), true).unwrap(), 1, &keypair);
+        assert!(matches!(
+            blockchain.add_transaction_object(overstake),
+            Err(BlockchainError::InsufficientBalance { .. })
+        ));
+
+        // An unstake the PoS rules reject fails without invalidating its block
+        let overdrawn = signed(Transaction::new_staking(address.clone(), units::gil(5000), false).unwrap(), 1, &keypair);
+        let overdrawn_id = overdrawn.id.clone();
+        blockchain.add_transaction_object(overdrawn).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert!(matches!(blockchain.get_receipt(&overdrawn_id).unwrap().status, ReceiptStatus::Failed { .. }));
+        assert_eq!(blockchain.get_nonce(&address), 2);
+        assert_eq!(blockchain.proof_of_stake().unwrap().validators[&address].stake_amount, units::gil(1000));
+
+        let unstake = signed(Transaction::new_staking(address.clone(), units::gil(400), false).unwrap(), 2, &keypair);
+        blockchain.add_transaction_object(unstake).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        let pos = blockchain.proof_of_stake().unwrap();
+        assert_eq!(pos.validators[&address].stake_amount, units::gil(600));
+        assert_eq!(pos.get_unbonding(&address), units::gil(400));
+        assert!(blockchain.validate_chain().unwrap());
+    }
+
+    #[test]
+    fn test_failing_stake_restores_validator_state() {
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let address = crypto::create_address(&keypair.public_key());
+        let mut blockchain = Blockchain::new_pos_with_validators(10.0, units::gil(100), 10, vec![
+            (keypair.public_key_hex(), address.clone(), units::gil(1000)),
+        ]).unwrap();
+        blockchain.add_validator_signer(keypair.clone()).unwrap();
+        blockchain.balances.insert(address.clone(), units::gil(500));
+
+        let signed = |mut tx: Transaction, nonce: u64| {
+            tx.set_nonce(nonce);
+            tx.sign(&keypair).unwrap();
+            tx
+        };
+        // The last stake is covered on its own but not after the first one
+        blockchain.add_transaction_object(signed(Transaction::new_staking(address.clone(), units::gil(200), true).unwrap(), 0)).unwrap();
+        blockchain.add_transaction_object(signed(Transaction::new_staking(address.clone(), units::gil(300), false).unwrap(), 1)).unwrap();
+        blockchain.add_transaction_object(signed(Transaction::new_staking(address.clone(), units::gil(400), true).unwrap(), 2)).unwrap();
+
+        assert!(matches!(
+            blockchain.mine_block("miner".to_string()),
+            Err(BlockchainError::InsufficientBalance { .. })
+        ));
+        let pos = blockchain.proof_of_stake().unwrap();
+        assert_eq!(pos.validators[&address].stake_amount, units::gil(1000));
+        assert_eq!(pos.get_unbonding(&address), 0);
+        assert_eq!(blockchain.get_balance(&address), 500.0);
+        assert_eq!(blockchain.get_nonce(&address), 0);
+
+        // The stake and unstake before the failing stake still apply in the next block
+        assert_eq!(blockchain.pending_transactions.len(), 2);
+        blockchain.mine_block("miner".to_string()).unwrap();
+        let pos = blockchain.proof_of_stake().unwrap();
+        assert_eq!(pos.validators[&address].stake_amount, units::gil(900));
+        assert_eq!(pos.get_unbonding(&address), units::gil(300));
+    }
+
+    #[test]
+    fn test_unstaked_tokens_unbond_before_withdrawal() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let address = crypto::create_address(&keypair.public_key());
+        let mut blockchain = Blockchain::new_pos_with_validators(10.0, units::gil(100), 10, vec![
+            (keypair.public_key_hex(), address.clone(), units::gil(1000)),
+        ]).unwrap();
+        blockchain.proof_of_stake_mut().unwrap().unbonding_period = 3;
+        blockchain.add_validator_signer(keypair.clone()).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+
+        let signed = |mut tx: Transaction, nonce: u64| {
+            tx.set_nonce(nonce);
+            tx.sign(&keypair).unwrap();
+            tx
+        };
+        let withdrawal = |amount: u128, nonce: u64| signed(Transaction::new_unbonded_withdrawal(address.clone(), amount).unwrap(), nonce);
+        blockchain.add_transaction_object(signed(Transaction::new_staking(address.clone(), units::gil(400), false).unwrap(), 0)).unwrap();
+
+        // A withdrawal mined before the unbonding period passes fails and credits nothing
+        let early = withdrawal(units::gil(100), 1);
+        let early_id = early.id.clone();
+        blockchain.add_transaction_object(early).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert!(!blockchain.get_receipt(&early_id).unwrap().is_success());
+        assert_eq!(blockchain.get_balance(&address), 10.0);
+        assert_eq!(blockchain.proof_of_stake().unwrap().get_unbonding(&address), units::gil(400));
+
+        for _ in 0..2 {
+            blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+        }
+        let balance = blockchain.get_balance(&address);
+        blockchain.add_transaction_object(withdrawal(units::gil(400), 2)).unwrap();
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(blockchain.get_balance(&address), balance + 400.0 + 10.0);
+        let pos = blockchain.proof_of_stake().unwrap();
+        assert_eq!(pos.get_unbonding(&address), 0);
+        assert_eq!(pos.validators[&address].stake_amount, units::gil(600));
+        assert!(blockchain.validate_chain().unwrap());
+
+        // Rolling back the withdrawal locks the tokens again
+        blockchain.rollback_to_snapshot(block.index).unwrap();
+        assert_eq!(blockchain.get_balance(&address), balance);
+        assert_eq!(blockchain.proof_of_stake().unwrap().get_unbonding(&address), units::gil(400));
+    }
+
+    #[test]
+    fn test_poa_authorities_produce_blocks_in_turn() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let keys: Vec<crypto::KeyPair> = (0..3).map(|_| crypto::KeyPair::generate().unwrap()).collect();
+        let mut blockchain = Blockchain::new_poa(keys.iter().map(|k| k.public_key()).collect(), 10.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+
+        for height in 1..=6u64 {
+            let authority = &keys[height as usize % 3];
+            blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+
+            // Only the in-turn authority can seal the block
+            let out_of_turn = keys[(height as usize + 1) % 3].clone();
+            blockchain.set_authority_signer(out_of_turn).unwrap();
+            assert!(blockchain.mine_block("miner".to_string()).is_err());
+
+            blockchain.set_authority_signer(authority.clone()).unwrap();
+            let block = blockchain.mine_block("miner".to_string()).unwrap();
+            assert_eq!(block.index, height);
+            assert_eq!(block.validator, Some(crypto::create_address(&authority.public_key())));
+        }
+
+        assert!(blockchain.validate_chain().unwrap());
+        for key in &keys {
+            assert_eq!(blockchain.get_balance(&crypto::create_address(&key.public_key())), 20.0);
+        }
+    }
+
+    #[test]
+    fn test_poa_rejects_unauthorized_and_out_of_turn_blocks() {
+        let keys: Vec<crypto::KeyPair> = (0..2).map(|_| crypto::KeyPair::generate().unwrap()).collect();
+        let mut blockchain = Blockchain::new_poa(keys.iter().map(|k| k.public_key()).collect(), 10.0).unwrap();
+        let outsider = crypto::KeyPair::generate().unwrap();
+        assert!(blockchain.set_authority_signer(outsider.clone()).is_err());
+
+        // Seal the next block with a PoA set in which the signer is in turn, as a rogue node would
+        let forge = |blockchain: &Blockchain, signer: &crypto::KeyPair| {
+            let latest = blockchain.get_latest_block().unwrap();
+            let mut block = Block::new(
+                latest.index + 1,
+                vec![Transaction::new_transfer("COINBASE".to_string(), "mallory".to_string(), units::gil(10), None).unwrap()],
+                latest.hash.clone(),
+                blockchain.version.clone(),
+                ConsensusType::ProofOfAuthority.to_string(),
+            ).unwrap();
+            let filler = crypto::KeyPair::generate().unwrap().public_key();
+            let mut poa = ProofOfAuthority::new(vec![filler, signer.public_key()]).unwrap();
+            poa.set_signer(signer.clone()).unwrap();
+            poa.seal_block(&mut block).unwrap();
+            block
+        };
+
+        let unauthorized = forge(&blockchain, &outsider);
+        assert!(matches!(blockchain.add_block(unauthorized), Err(BlockchainError::ConsensusError(_))));
+
+        // Block 1 belongs to the second authority
+        let out_of_turn = forge(&blockchain, &keys[0]);
+        assert!(matches!(blockchain.add_block(out_of_turn), Err(BlockchainError::ConsensusError(_))));
+
+        let in_turn = forge(&blockchain, &keys[1]);
+        blockchain.add_block(in_turn).unwrap();
+        assert_eq!(blockchain.blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_time_locked_transaction_waits_for_unlock_height() {
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&keypair.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+
+        // Balance is only checked once the transfer unlocks
+        let tx_id = blockchain.add_time_locked_transaction(&keypair, "bob".to_string(), units::gil(40), 3, None).unwrap();
+        assert_eq!(blockchain.pending_transactions.len(), 1);
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+
+        for height in 1..3 {
+            let block = blockchain.mine_block("miner".to_string()).unwrap();
+            assert_eq!(block.index, height);
+            assert!(block.transactions.iter().all(|tx| tx.id != tx_id));
+            assert_eq!(blockchain.pending_transactions.len(), 1);
+            assert_eq!(blockchain.get_balance("bob"), 0.0);
+        }
+
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(block.index, 3);
+        assert!(block.transactions.iter().any(|tx| tx.id == tx_id));
+        assert!(blockchain.pending_transactions.is_empty());
+        assert_eq!(blockchain.get_balance(&alice), 60.0);
+        assert_eq!(blockchain.get_balance("bob"), 40.0);
+    }
+
+    #[test]
+    fn test_time_locked_transaction_dropped_when_unaffordable() {
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&keypair.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+
+        blockchain.add_time_locked_transaction(&keypair, "bob".to_string(), units::gil(80), 2, None).unwrap();
+        blockchain.add_transaction(&keypair, "carol".to_string(), units::gil(50), None).unwrap();
+
+        // The regular transfer is mined ahead of the locked one
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(blockchain.get_balance(&alice), 50.0);
+        assert_eq!(blockchain.pending_transactions.len(), 1);
+
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(block.transactions.len(), 1);
+        assert!(blockchain.pending_transactions.is_empty());
+        assert_eq!(blockchain.get_balance(&alice), 50.0);
+        assert_eq!(blockchain.get_balance("bob"), 0.0);
+    }
+
+    #[test]
+    fn test_time_locked_block_rejected_before_unlock() {
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&keypair.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+
+        let mut tx = Transaction::new_time_locked(alice, "bob".to_string(), units::gil(10), 5, None).unwrap();
+        tx.sign(&keypair).unwrap();
+        let latest = blockchain.get_latest_block().unwrap();
+        let mut block = Block::new(1, vec![tx], latest.hash.clone(), blockchain.version.clone(), blockchain.get_consensus_type().to_string()).unwrap();
+        block.mine(blockchain.proof_of_work().unwrap()).unwrap();
+
+        assert!(blockchain.add_block(block).is_err());
+        assert_eq!(blockchain.blocks.len(), 1);
+    }
+
+    #[test]
+    fn test_time_locked_transaction_cannot_be_replayed() {
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&keypair.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+
+        let mut tx = Transaction::new_time_locked(alice, "bob".to_string(), units::gil(10), 1, None).unwrap();
+        tx.sign(&keypair).unwrap();
+        blockchain.add_transaction_object(tx.clone()).unwrap();
+        assert!(blockchain.add_transaction_object(tx.clone()).is_err());
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(blockchain.get_balance("bob"), 10.0);
+
+        // Resubmitting the executed transfer is rejected
+        assert!(blockchain.add_transaction_object(tx.clone()).is_err());
+        assert!(blockchain.pending_transactions.is_empty());
+
+        // So is a block that carries it again
+        let latest = blockchain.get_latest_block().unwrap();
+        let mut block = Block::new(2, vec![tx], latest.hash.clone(), blockchain.version.clone(), blockchain.get_consensus_type().to_string()).unwrap();
+        block.mine(blockchain.proof_of_work().unwrap()).unwrap();
+        assert!(blockchain.add_block(block).is_err());
+        assert_eq!(blockchain.blocks.len(), 2);
+        assert_eq!(blockchain.get_balance("bob"), 10.0);
+    }
+
+    #[test]
+    fn test_forged_time_locked_transaction_rejected() {
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&keypair.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+
+        // An unsigned transfer naming alice as sender is not admitted
+        let forged = Transaction::new_time_locked(alice.clone(), "mallory".to_string(), units::gil(100), 0, None).unwrap();
+        assert!(matches!(
+            blockchain.add_transaction_object(forged.clone()),
+            Err(BlockchainError::TransactionValidationFailed(_))
+        ));
+
+        // Nor is one signed with someone else's key
+        let mut resigned = forged.clone();
+        resigned.sign(&crypto::KeyPair::generate().unwrap()).unwrap();
+        assert!(matches!(
+            blockchain.add_transaction_object(resigned),
+            Err(BlockchainError::TransactionValidationFailed(_))
+        ));
+        assert!(blockchain.pending_transactions.is_empty());
+
+        // A block carrying the forged transfer is invalid
+        let latest = blockchain.get_latest_block().unwrap();
+        let mut block = Block::new(1, vec![forged], latest.hash.clone(), blockchain.version.clone(), blockchain.get_consensus_type().to_string()).unwrap();
+        block.mine(blockchain.proof_of_work().unwrap()).unwrap();
+        assert!(blockchain.add_block(block).is_err());
+        assert_eq!(blockchain.blocks.len(), 1);
+        assert_eq!(blockchain.get_balance(&alice), 100.0);
+        assert_eq!(blockchain.get_balance("mallory"), 0.0);
+    }
+
+    fn htlc_chain() -> (Blockchain, crypto::KeyPair, String, String) {
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&keypair.public_key());
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        let id = blockchain.create_htlc(
+            &keypair,
+            "bob".to_string(),
+            units::gil(40),
+            Htlc::hash_preimage(b"swap secret"),
+            3,
+        ).unwrap();
+
+        // The funds are only locked once the lock is mined
+        assert!(blockchain.get_htlc(&id).is_none());
+        assert_eq!(blockchain.get_balance(&alice), 100.0);
+        blockchain.mine_block("miner".to_string()).unwrap();
+        (blockchain, keypair, alice, id)
+    }
+
+    #[test]
+    fn test_htlc_redeem_with_preimage() {
+        let (mut blockchain, _, alice, id) = htlc_chain();
+        assert_eq!(blockchain.get_balance(&alice), 60.0);
+        assert_eq!(blockchain.get_htlc(&id).unwrap().created_at, 1);
+
+        let redeem_id = blockchain.redeem_htlc(&id, "swap secret").unwrap();
+        assert_eq!(blockchain.get_balance("bob"), 0.0);
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert!(blockchain.get_receipt(&redeem_id).unwrap().is_success());
+        assert_eq!(blockchain.get_balance("bob"), 40.0);
+        let htlc = blockchain.get_htlc(&id).unwrap();
+        assert_eq!(htlc.status, HtlcStatus::Redeemed);
+        assert_eq!(htlc.preimage.as_deref(), Some("swap secret"));
+
+        // Settled HTLCs cannot be redeemed again
+        assert!(blockchain.redeem_htlc(&id, "swap secret").is_err());
+        assert_eq!(blockchain.get_balance("bob"), 40.0);
+        assert!(blockchain.validate_chain().unwrap());
+    }
+
+    #[test]
+    fn test_htlc_redeem_rejects_wrong_preimage() {
+        let (mut blockchain, _, _, id) = htlc_chain();
+
+        assert!(matches!(
+            blockchain.redeem_htlc(&id, "wrong secret"),
+            Err(BlockchainError::HtlcError(_))
+        ));
+
+        // A block carrying the redemption anyway records it as failed
+        let htlc = blockchain.get_htlc(&id).unwrap().clone();
+        let redeem = Transaction::new_htlc_redeem(&htlc, "wrong secret".to_string()).unwrap();
+        let redeem_id = redeem.id.clone();
+        blockchain.add_transaction_object(redeem).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert!(!blockchain.get_receipt(&redeem_id).unwrap().is_success());
+        assert_eq!(blockchain.get_balance("bob"), 0.0);
+        assert_eq!(blockchain.get_htlc(&id).unwrap().status, HtlcStatus::Locked);
+    }
+
+    #[test]
+    fn test_htlc_refund_after_timeout() {
+        let (mut blockchain, keypair, alice, id) = htlc_chain();
+
+        // Refunds are refused until the next block reaches the timeout height
+        assert!(blockchain.refund_htlc(&id).is_err());
+        blockchain.add_transaction(&keypair, "carol".to_string(), units::gil(1), None).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(blockchain.height(), 2);
+
+        // Once expired the receiver can no longer redeem
+        assert!(blockchain.redeem_htlc(&id, "swap secret").is_err());
+        blockchain.refund_htlc(&id).unwrap();
+        // Only one settlement of an HTLC may be pending
+        assert!(blockchain.refund_htlc(&id).is_err());
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(blockchain.get_balance(&alice), 99.0);
+        assert_eq!(blockchain.get_balance("bob"), 0.0);
+        assert_eq!(blockchain.get_htlc(&id).unwrap().status, HtlcStatus::Refunded);
+        assert!(blockchain.refund_htlc(&id).is_err());
+
+        // Replaying the chain reproduces the HTLC
+        assert!(blockchain.validate_chain().unwrap());
+    }
+
+    #[test]
+    fn test_balance_changes_of_applied_block() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        blockchain.balances.insert("carol".to_string(), units::gil(5));
+
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(10), None).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        let mut expected = vec![alice.clone(), "bob".to_string(), "miner".to_string()];
+        expected.sort();
+        assert_eq!(blockchain.balance_changes, expected);
+    }
+
+    #[test]
+    fn test_balances_at_height() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let bob_key = crypto::KeyPair::generate().unwrap();
+        let bob = crypto::create_address(&bob_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+
+        let mut history = Vec::new();
+        for amount in [units::gil(10), units::gil(20), units::gil(30)] {
+            blockchain.add_transaction(&alice_key, bob.clone(), amount, None).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+            history.push(blockchain.balances.clone());
         }
+        blockchain.add_transaction(&bob_key, "carol".to_string(), units::gil(5), None).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        history.push(blockchain.balances.clone());
 
-        let recent_blocks = self.blocks.iter().rev().take(10).collect::<Vec<_>>();
-        if recent_blocks.len() < 2 {
-            return Ok(());
+        for (offset, expected) in history.iter().enumerate() {
+            assert_eq!(&blockchain.balances_at_height(offset as u64 + 1).unwrap(), expected);
         }
+        let at_two = blockchain.balances_at_height(2).unwrap();
+        assert_eq!(at_two[&alice], units::gil(70));
+        assert_eq!(at_two[&bob], units::gil(30));
+        assert_eq!(at_two["miner"], units::gil(100));
+        assert!(!at_two.contains_key("carol"));
 
-        let total_time = recent_blocks[0].timestamp - recent_blocks[recent_blocks.len() - 1].timestamp;
-        let avg_time = total_time as f64 / (recent_blocks.len() - 1) as f64;
+        // Pruned snapshots are made up for by replaying from an older one
+        let mut pruned = blockchain.clone();
+        pruned.state_snapshots.retain(|snapshot| snapshot.block_index == 1);
+        assert_eq!(pruned.balances_at_height(3).unwrap(), history[2]);
 
-        let new_difficulty = self.proof_of_work.adjust_difficulty(target_time, avg_time);
-        
-        if new_difficulty != self.difficulty {
-            self.difficulty = new_difficulty;
-            self.proof_of_work = ProofOfWork::new(new_difficulty, self.proof_of_work.max_attempts)?;
-            info!("Adjusted difficulty to {}", new_difficulty);
+        // Reading history leaves the current state alone
+        assert_eq!(blockchain.balances, history[3]);
+        assert!(matches!(blockchain.balances_at_height(5), Err(BlockchainError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_confirmations_and_finality() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(10), None).unwrap();
+        let tx_id = blockchain.pending_transactions[0].id.clone();
+        assert_eq!(blockchain.transaction_status(&tx_id), Some(TransactionStatus::Pending));
+        assert!(!blockchain.is_final(&tx_id, 0));
+
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        for expected in 1..=4u64 {
+            let status = blockchain.transaction_status(&tx_id).unwrap();
+            assert_eq!(status, TransactionStatus::Confirmed { block_number: block.index, confirmations: expected });
+            assert_eq!(blockchain.is_final(&tx_id, 3), expected >= 3);
+
+            blockchain.add_transaction(&alice_key, "carol".to_string(), units::gil(1), None).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
         }
+        assert!(!blockchain.is_final("unknown", 1));
+    }
 
-        Ok(())
+    #[test]
+    fn test_contract_withdrawal_transaction() {
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        let owner_key = crypto::KeyPair::generate().unwrap();
+        let owner = crypto::create_address(&owner_key.public_key());
+        let mallory_key = crypto::KeyPair::generate().unwrap();
+        let mallory = crypto::create_address(&mallory_key.public_key());
+        blockchain.balances.insert(owner.clone(), units::gil(100));
+        let address = blockchain.deploy_contract(owner.clone(), "PUSH 1\nRETURN".to_string(), 10_000, 0.000001).unwrap();
+        blockchain.contracts.get_mut(&address).unwrap().balance = units::gil(30);
+        let owner_balance = blockchain.get_balance(&owner);
+
+        let mut withdrawal = Transaction::new_contract_withdraw(owner.clone(), address.clone(), units::gil(20)).unwrap();
+        withdrawal.set_nonce(blockchain.next_nonce(&owner));
+        withdrawal.sign(&owner_key).unwrap();
+        let mut forged = Transaction::new_contract_withdraw(mallory.clone(), address.clone(), units::gil(5)).unwrap();
+        forged.sign(&mallory_key).unwrap();
+        blockchain.add_transaction_object(withdrawal.clone()).unwrap();
+        blockchain.add_transaction_object(forged.clone()).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+
+        assert_eq!(blockchain.get_receipt(&withdrawal.id).unwrap().status, ReceiptStatus::Success);
+        assert!(matches!(blockchain.get_receipt(&forged.id).unwrap().status, ReceiptStatus::Failed { .. }));
+        assert_eq!(blockchain.contracts[&address].balance, units::gil(10));
+        assert_eq!(blockchain.get_balance(&owner), owner_balance + 20.0);
+        assert_eq!(blockchain.get_balance(&mallory), 0.0);
+        assert_eq!(blockchain.get_nonce(&owner), 1);
+        assert_eq!(blockchain.get_nonce(&mallory), 1);
+        assert!(blockchain.validate_chain().unwrap());
     }
-}
 
-/// Statistics about the blockchain
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BlockchainStats {
-    /// Number of blocks in the chain
-    pub block_count: usize,
-    /// Number of pending transactions
-    pub pending_transactions: usize,
-    /// Total number of transactions in all blocks
-    pub total_transactions: usize,
-    /// Total amount transferred in all blocks
-    pub total_amount: f64,
-    /// Total size of the blockchain in bytes
-    pub chain_size: usize,
-    /// Current mining difficulty
-    pub difficulty: u32,
-    /// Mining reward amount
-    pub mining_reward: f64,
-    /// Blockchain version
-    pub version: String,
-}
+    #[test]
+    fn test_contract_withdrawal_requires_signature_and_nonce() {
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        let owner_key = crypto::KeyPair::generate().unwrap();
+        let owner = crypto::create_address(&owner_key.public_key());
+        blockchain.balances.insert(owner.clone(), units::gil(100));
+        let address = blockchain.deploy_contract(owner.clone(), "PUSH 1\nRETURN".to_string(), 10_000, 0.000001).unwrap();
+        blockchain.contracts.get_mut(&address).unwrap().balance = units::gil(30);
 
-impl std::fmt::Display for BlockchainStats {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Blockchain Stats:\n\
-             Blocks: {}\n\
-             Pending Transactions: {}\n\
-             Total Transactions: {}\n\
-             Total Amount: {:.2} GIL\n\
-             Chain Size: {} bytes\n\
-             Difficulty: {}\n\
-             Mining Reward: {:.2} GIL\n\
-             Version: {}",
-            self.block_count,
-            self.pending_transactions,
-            self.total_transactions,
-            self.total_amount,
-            self.chain_size,
-            self.difficulty,
-            self.mining_reward,
-            self.version
-        )
+        // Unsigned, or signed by a key that is not the owner's
+        let unsigned = Transaction::new_contract_withdraw(owner.clone(), address.clone(), units::gil(5)).unwrap();
+        assert!(blockchain.add_transaction_object(unsigned).is_err());
+        let mut wrong_key = Transaction::new_contract_withdraw(owner.clone(), address.clone(), units::gil(5)).unwrap();
+        wrong_key.sign(&crypto::KeyPair::generate().unwrap()).unwrap();
+        assert!(blockchain.add_transaction_object(wrong_key).is_err());
+
+        // Replaying a mined withdrawal reuses its nonce
+        let mut withdrawal = Transaction::new_contract_withdraw(owner.clone(), address.clone(), units::gil(5)).unwrap();
+        withdrawal.sign(&owner_key).unwrap();
+        blockchain.add_transaction_object(withdrawal.clone()).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert!(blockchain.add_transaction_object(withdrawal.clone()).is_err());
+
+        // A block carrying the replay is rejected
+        let latest = blockchain.get_latest_block().unwrap();
+        let mut block = Block::new(2, vec![withdrawal], latest.hash.clone(), blockchain.version.clone(), blockchain.get_consensus_type().to_string()).unwrap();
+        block.mine(blockchain.proof_of_work().unwrap()).unwrap();
+        assert!(blockchain.add_block(block).is_err());
+        assert_eq!(blockchain.contracts[&address].balance, units::gil(25));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_address_index() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        for receiver in ["bob", "carol", "bob"] {
+            blockchain.add_transaction(&alice_key, receiver.to_string(), units::gil(5), None).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+        }
+
+        for address in [&alice, "bob", "carol", "miner", "nobody"] {
+            let scanned: Vec<&str> = blockchain.blocks.iter()
+                .flat_map(|block| &block.transactions)
+                .filter(|tx| tx.sender == address || tx.receiver == address)
+                .map(|tx| tx.id.as_str())
+                .collect();
+            let indexed: Vec<&str> = blockchain.transaction_history(address).iter()
+                .map(|(_, tx)| tx.id.as_str())
+                .collect();
+            assert_eq!(indexed, scanned, "history of {}", address);
+        }
+        assert_eq!(blockchain.transaction_history("bob").iter().map(|(height, _)| *height).collect::<Vec<_>>(), vec![1, 3]);
+
+        // The index is rebuilt when the chain is loaded
+        let mut loaded = Blockchain::from_json(&blockchain.to_json().unwrap()).unwrap();
+        assert_eq!(loaded.address_index, blockchain.address_index);
+
+        // Rolling back drops the entries of the removed blocks
+        loaded.rollback_to_snapshot(1).unwrap();
+        assert_eq!(loaded.blocks.len(), 2);
+        assert_eq!(loaded.transaction_history("bob").len(), 1);
+        assert!(loaded.transaction_history("carol").is_empty());
+        assert!(!loaded.address_index.contains_key("carol"));
+        assert_eq!(loaded.transaction_history("miner").len(), 1);
+    }
 
     #[test]
-    fn test_blockchain_creation() {
-        let blockchain = Blockchain::new_pow(4, 50.0).unwrap();
-        assert_eq!(blockchain.difficulty, 4);
-        assert_eq!(blockchain.mining_reward, 50.0);
-        assert_eq!(blockchain.blocks.len(), 1); // Genesis block
+    fn test_state_root_committed_and_validated() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        for receiver in ["bob", "carol", "dave"] {
+            blockchain.add_transaction(&alice_key, receiver.to_string(), units::gil(10), None).unwrap();
+            let block = blockchain.mine_block("miner".to_string()).unwrap();
+            assert_eq!(block.state_root, blockchain.state_root());
+            assert_eq!(block.header().hash(), block.hash);
+        }
+        assert!(blockchain.validate_chain().unwrap());
+
+        // A block claiming a different resulting state is rejected and leaves the state untouched
+        let balances = blockchain.balances.clone();
+        let tip = blockchain.get_latest_block().unwrap().clone();
+        let mut forged = Block::new(
+            tip.index + 1,
+            vec![Transaction::new_transfer("COINBASE".to_string(), "miner".to_string(), units::gil(50), None).unwrap()],
+            tip.hash.clone(),
+            blockchain.version.clone(),
+            "pow".to_string(),
+        ).unwrap();
+        forged.state_root = vec![0; 32];
+        blockchain.consensus().seal_block(&mut forged).unwrap();
+        assert!(matches!(blockchain.add_block(forged), Err(BlockchainError::StateCorruption(_))));
+        assert_eq!(blockchain.balances, balances);
+
+        // Tampering with a historical balance invalidates the chain
+        let snapshot = blockchain.state_snapshots.iter_mut().find(|s| s.block_index == 2).unwrap();
+        snapshot.balances.insert("bob".to_string(), units::gil(1_000));
+        let err = blockchain.validate_chain().unwrap_err();
+        assert!(matches!(&err, BlockchainError::StateCorruption(message) if message.contains("block 2")));
     }
 
     #[test]
-    fn test_blockchain_default() {
-        let blockchain = Blockchain::new_default().unwrap();
-        assert_eq!(blockchain.difficulty, DEFAULT_DIFFICULTY);
-        assert_eq!(blockchain.mining_reward, 50.0);
+    fn test_state_roots_verified_without_snapshots() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let allocations = HashMap::from([(alice.clone(), units::gil(100))]);
+        let mut blockchain = Blockchain::new_pow_with_genesis(1, 50.0, allocations).unwrap();
+        for receiver in ["bob", "carol", "dave"] {
+            blockchain.add_transaction(&alice_key, receiver.to_string(), units::gil(5), None).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+        }
+
+        // With every snapshot pruned the roots are checked by replaying from genesis
+        blockchain.prune_snapshots(0);
+        assert!(blockchain.validate_chain().unwrap());
+
+        blockchain.balances.insert("mallory".to_string(), units::gil(1_000));
+        let err = blockchain.validate_chain().unwrap_err();
+        assert!(matches!(&err, BlockchainError::StateCorruption(message) if message.contains("Live state")));
     }
 
     #[test]
-    fn test_add_transaction() {
-        let mut blockchain = Blockchain::new_default().unwrap();
-        
-        // Add some initial balance
-        blockchain.balances.insert("alice".to_string(), 1000.0);
-        
-        blockchain.add_transaction("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
+    fn test_failed_seal_discards_applied_block() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(5), None).unwrap();
+        let balances = blockchain.balances.clone();
+        let snapshots = blockchain.state_snapshots.len();
+        let receipts = blockchain.receipts.len();
+
+        // A difficulty that cannot be met within a handful of attempts
+        *blockchain.consensus.as_any_mut().downcast_mut::<ProofOfWork>().unwrap() = ProofOfWork::new(16, 10).unwrap();
+        assert!(matches!(blockchain.mine_block("miner".to_string()), Err(BlockchainError::MiningTimeout(_))));
+
+        assert_eq!(blockchain.balances, balances);
+        assert_eq!(blockchain.state_snapshots.len(), snapshots);
+        assert_eq!(blockchain.receipts.len(), receipts);
+        assert_eq!(blockchain.height(), 0);
         assert_eq!(blockchain.pending_transactions.len(), 1);
     }
 
     #[test]
-    fn test_insufficient_balance() {
-        let mut blockchain = Blockchain::new_default().unwrap();
-        
-        let result = blockchain.add_transaction("alice".to_string(), "bob".to_string(), 100.0, None);
-        assert!(result.is_err());
+    fn test_failed_transaction_discards_mined_block() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(10));
+        // Each transfer is covered on its own, but not both together
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(6), None).unwrap();
+        blockchain.add_transaction(&alice_key, "carol".to_string(), units::gil(6), None).unwrap();
+        let first = blockchain.pending_transactions[0].id.clone();
+        let balances = blockchain.balances.clone();
+        let nonces = blockchain.nonces.clone();
+        let snapshots = blockchain.state_snapshots.len();
+
+        assert!(matches!(
+            blockchain.mine_block("miner".to_string()),
+            Err(BlockchainError::InsufficientBalance { .. })
+        ));
+        assert_eq!(blockchain.balances, balances);
+        assert_eq!(blockchain.nonces, nonces);
+        assert_eq!(blockchain.state_snapshots.len(), snapshots);
+        assert_eq!(blockchain.height(), 0);
+
+        // Only the failing transfer is dropped, so the next block mines the other
+        assert_eq!(blockchain.pending_transactions.len(), 1);
+        assert_eq!(blockchain.pending_transactions[0].id, first);
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(blockchain.get_balance("bob"), 6.0);
+        assert_eq!(blockchain.get_balance(&alice), 4.0);
     }
 
     #[test]
-    fn test_mine_block() {
-        let mut blockchain = Blockchain::new_default().unwrap();
-        
-        // Add some initial balance
-        blockchain.balances.insert("alice".to_string(), 1000.0);
-        
-        blockchain.add_transaction("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
-        let block = blockchain.mine_block("miner".to_string()).unwrap();
-        
-        assert_eq!(block.transactions.len(), 2); // 1 user tx + 1 reward tx
-        assert_eq!(blockchain.blocks.len(), 2); // Genesis + new block
+    fn test_block_with_failing_last_transaction_leaves_state_unchanged() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(10));
+        let balances = blockchain.balances.clone();
+        let snapshots = blockchain.state_snapshots.len();
+        let receipts = blockchain.receipts.len();
+
+        let mut transactions = Vec::new();
+        for (nonce, amount) in [(0, units::gil(6)), (1, units::gil(6))] {
+            let mut tx = Transaction::new_transfer(alice.clone(), "bob".to_string(), amount, None).unwrap();
+            tx.set_nonce(nonce);
+            tx.sign(&alice_key).unwrap();
+            transactions.push(tx);
+        }
+        let latest = blockchain.get_latest_block().unwrap();
+        let mut block = Block::new(1, transactions, latest.hash.clone(), blockchain.version.clone(), blockchain.get_consensus_type().to_string()).unwrap();
+        block.mine(blockchain.proof_of_work().unwrap()).unwrap();
+
+        assert!(matches!(blockchain.add_block(block), Err(BlockchainError::InsufficientBalance { .. })));
+        assert_eq!(blockchain.balances, balances);
+        assert_eq!(blockchain.next_nonce(&alice), 0);
+        assert_eq!(blockchain.state_snapshots.len(), snapshots);
+        assert_eq!(blockchain.receipts.len(), receipts);
+        assert_eq!(blockchain.blocks.len(), 1);
     }
 
     #[test]
-    fn test_validate_chain() {
-        let mut blockchain = Blockchain::new_default().unwrap();
-        assert!(blockchain.validate_chain().unwrap());
+    fn test_state_serialization_is_deterministic() {
+        let addresses: Vec<String> = (0..32).map(|i| format!("addr{:02}", i)).collect();
+        let mut contract = SmartContract::new("PUSH 1\nRETURN".to_string(), "alice".to_string()).unwrap();
+        for address in &addresses {
+            contract.storage.insert(address.clone(), address.to_uppercase());
+        }
+
+        let mut first = Blockchain::new_pow(1, 50.0).unwrap();
+        let mut second = Blockchain::new_pow(1, 50.0).unwrap();
+        second.blocks = first.blocks.clone();
+        second.state_snapshots = first.state_snapshots.clone();
+
+        // Populate the same state in opposite insertion orders
+        for (i, address) in addresses.iter().enumerate() {
+            first.balances.insert(address.clone(), i as u128);
+            first.contract_metrics.insert(address.clone(), i as u64);
+        }
+        for (i, address) in addresses.iter().enumerate().rev() {
+            second.balances.insert(address.clone(), i as u128);
+            second.contract_metrics.insert(address.clone(), i as u64);
+        }
+        first.contracts.insert(contract.id.clone(), contract.clone());
+        let mut reordered = contract.clone();
+        reordered.storage = addresses.iter().rev().map(|a| (a.clone(), a.to_uppercase())).collect();
+        second.contracts.insert(contract.id.clone(), reordered);
+
+        assert_eq!(first.to_json().unwrap(), second.to_json().unwrap());
+        assert_eq!(serde_json::to_vec(&first).unwrap(), serde_json::to_vec(&second).unwrap());
+        let json = first.to_json().unwrap();
+        assert!(json.find("\"addr00\"").unwrap() < json.find("\"addr31\"").unwrap());
     }
 
     #[test]
-    fn test_get_latest_block() {
-        let blockchain = Blockchain::new_default().unwrap();
-        let latest = blockchain.get_latest_block().unwrap();
-        assert_eq!(latest.index, 0); // Genesis block
+    fn test_genesis_allocations() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let allocations = HashMap::from([
+            (alice.clone(), units::gil(500)),
+            ("bob".to_string(), units::gil(250)),
+        ]);
+        let mut blockchain = Blockchain::new_pow_with_genesis(1, 50.0, allocations.clone()).unwrap();
+        assert_eq!(blockchain.get_balance(&alice), 500.0);
+        assert_eq!(blockchain.get_balance("bob"), 250.0);
+
+        // The allocations are on-chain, so replaying the genesis block reproduces them
+        let genesis = &blockchain.blocks[0];
+        assert_eq!(genesis.transactions.len(), 2);
+        assert!(genesis.transactions.iter().all(|tx| tx.is_coinbase()));
+        let replayed = blockchain.replay_blocks(blockchain.blocks.clone()).unwrap();
+        assert_eq!(replayed.balances, blockchain.balances);
+
+        blockchain.add_transaction(&alice_key, "carol".to_string(), units::gil(100), None).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        assert_eq!(blockchain.get_balance("carol"), 100.0);
+
+        let mut other_allocations = allocations;
+        other_allocations.insert("bob".to_string(), units::gil(251));
+        let other = Blockchain::new_pow_with_genesis(1, 50.0, other_allocations).unwrap();
+        assert_ne!(other.blocks[0].merkle_root, blockchain.blocks[0].merkle_root);
+        assert_ne!(other.blocks[0].hash, blockchain.blocks[0].hash);
+
+        let invalid = HashMap::from([(alice.clone(), 0)]);
+        assert!(Blockchain::new_pow_with_genesis(1, 50.0, invalid).is_err());
     }
 
     #[test]
-    fn test_get_balance() {
-        let mut blockchain = Blockchain::new_default().unwrap();
-        blockchain.balances.insert("alice".to_string(), 100.0);
-        
-        assert_eq!(blockchain.get_balance("alice"), 100.0);
-        assert_eq!(blockchain.get_balance("bob"), 0.0);
+    fn test_try_adopt_chain_prefers_more_work() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let allocations = HashMap::from([(alice.clone(), units::gil(1000))]);
+        let mut base = Blockchain::new_pow_with_genesis(1, 50.0, allocations).unwrap();
+        base.add_transaction(&alice_key, "bob".to_string(), units::gil(10), None).unwrap();
+        base.mine_block("miner".to_string()).unwrap();
+
+        let mut light = base.clone();
+        light.add_transaction(&alice_key, "carol".to_string(), units::gil(20), None).unwrap();
+        light.mine_block("miner".to_string()).unwrap();
+
+        let mut heavy = base.clone();
+        let mut round = 0;
+        while heavy.total_work() <= light.total_work() {
+            round += 1;
+            heavy.add_transaction(&alice_key, "dave".to_string(), units::gil(1), Some(format!("round {}", round))).unwrap();
+            heavy.mine_block("miner".to_string()).unwrap();
+        }
+
+        // Equal work, including our own chain, keeps the current fork
+        let mut local = light.clone();
+        assert!(!local.try_adopt_chain(light.blocks.clone()).unwrap());
+
+        // A lighter fork is ignored
+        let mut local_heavy = heavy.clone();
+        assert!(!local_heavy.try_adopt_chain(light.blocks.clone()).unwrap());
+        assert_eq!(local_heavy.blocks, heavy.blocks);
+
+        // The heavier fork replaces the chain and its state
+        assert!(local.try_adopt_chain(heavy.blocks.clone()).unwrap());
+        assert_eq!(local.blocks, heavy.blocks);
+        assert_eq!(local.total_work(), heavy.total_work());
+        assert_eq!(local.get_balance("carol"), 0.0);
+        assert_eq!(local.get_balance("dave"), round as f64);
+        assert_eq!(local.balances, heavy.balances);
+
+        // Forks must share the genesis block
+        let stranger = Blockchain::new_pow(1, 50.0).unwrap();
+        assert!(local.try_adopt_chain(stranger.blocks).is_err());
     }
 
     #[test]
-    fn test_blockchain_stats() {
-        let blockchain = Blockchain::new_default().unwrap();
-        let stats = blockchain.get_stats();
-        
-        assert_eq!(stats.block_count, 1); // Genesis block
-        assert_eq!(stats.pending_transactions, 0);
-        assert_eq!(stats.difficulty, DEFAULT_DIFFICULTY);
+    fn test_total_work_counts_required_difficulty() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let allocations = HashMap::from([(alice.clone(), units::gil(1000))]);
+        let mut blockchain = Blockchain::new_pow_with_genesis(1, 50.0, allocations).unwrap();
+        for receiver in ["bob", "carol", "dave"] {
+            blockchain.add_transaction(&alice_key, receiver.to_string(), units::gil(10), None).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+        }
+
+        // Every mined block counts 16^1 however many zeros its hash happens to have
+        assert!(blockchain.blocks[1..].iter().all(|block| block.difficulty == 1));
+        assert_eq!(blockchain.total_work(), 1 + 3 * 16);
+
+        // A block claiming more work than its target is rejected
+        let mut inflated = blockchain.blocks[3].clone();
+        inflated.difficulty = 5;
+        assert!(matches!(
+            blockchain.consensus().validate_block(&inflated),
+            Err(BlockchainError::InvalidProofOfWork(_))
+        ));
     }
 
     #[test]
-    fn test_blockchain_json_serialization() {
-        let blockchain = Blockchain::new_default().unwrap();
-        let json = blockchain.to_json().unwrap();
-        let deserialized = Blockchain::from_json(&json).unwrap();
-        
-        assert_eq!(blockchain.blocks.len(), deserialized.blocks.len());
-        assert_eq!(blockchain.difficulty, deserialized.difficulty);
+    fn test_max_reorg_depth() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let allocations = HashMap::from([(alice.clone(), units::gil(1000))]);
+        let mut base = Blockchain::new_pow_with_genesis(1, 50.0, allocations).unwrap();
+        base.add_transaction(&alice_key, "bob".to_string(), units::gil(10), None).unwrap();
+        base.mine_block("miner".to_string()).unwrap();
+
+        let mut local = base.clone();
+        for i in 0..3 {
+            local.add_transaction(&alice_key, "carol".to_string(), units::gil(1), Some(format!("local {}", i))).unwrap();
+            local.mine_block("miner".to_string()).unwrap();
+        }
+
+        let mut fork = base.clone();
+        let mut round = 0;
+        while fork.total_work() <= local.total_work() {
+            round += 1;
+            fork.add_transaction(&alice_key, "dave".to_string(), units::gil(1), Some(format!("fork {}", round))).unwrap();
+            fork.mine_block("miner".to_string()).unwrap();
+        }
+
+        // Switching would drop the three local blocks after the shared prefix
+        let mut shallow = local.clone();
+        shallow.max_reorg_depth = 2;
+        assert!(matches!(
+            shallow.try_adopt_chain(fork.blocks.clone()),
+            Err(BlockchainError::ReorgTooDeep { depth: 3, limit: 2 })
+        ));
+        assert_eq!(shallow.blocks, local.blocks);
+        assert_eq!(shallow.balances, local.balances);
+        assert!(matches!(shallow.replace_chain(fork.clone()), Err(BlockchainError::ReorgTooDeep { .. })));
+        assert_eq!(shallow.blocks, local.blocks);
+        assert!(matches!(shallow.rollback_to_snapshot(1), Err(BlockchainError::ReorgTooDeep { depth: 3, limit: 2 })));
+        assert_eq!(shallow.blocks, local.blocks);
+
+        let mut deep = local.clone();
+        deep.max_reorg_depth = 3;
+        assert!(deep.try_adopt_chain(fork.blocks.clone()).unwrap());
+        assert_eq!(deep.blocks, fork.blocks);
+        assert_eq!(deep.get_balance("carol"), 0.0);
+    }
+
+    #[test]
+    fn test_orphan_blocks_connect_when_parent_arrives() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let allocations = HashMap::from([(alice.clone(), units::gil(1000))]);
+        let mut source = Blockchain::new_pow_with_genesis(1, 50.0, allocations).unwrap();
+        for receiver in ["bob", "carol", "dave"] {
+            source.add_transaction(&alice_key, receiver.to_string(), units::gil(10), None).unwrap();
+            source.mine_block("miner".to_string()).unwrap();
+        }
+        let mut target = source.replay_blocks(source.blocks[..1].to_vec()).unwrap();
+
+        // Block 2 arrives before its parent and is buffered
+        assert_eq!(target.receive_block(source.blocks[2].clone()).unwrap(), 0);
+        assert_eq!(target.blocks.len(), 1);
+        assert_eq!(target.orphan_blocks.len(), 1);
+
+        // Block 1 connects itself and then the buffered block 2
+        assert_eq!(target.receive_block(source.blocks[1].clone()).unwrap(), 2);
+        assert_eq!(target.blocks, source.blocks[..3].to_vec());
+        assert!(target.orphan_blocks.is_empty());
+        assert_eq!(target.get_balance("carol"), 10.0);
+
+        assert_eq!(target.receive_block(source.blocks[3].clone()).unwrap(), 1);
+        assert_eq!(target.balances, source.balances);
+    }
+
+    #[test]
+    fn test_expired_orphan_blocks_are_evicted() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let allocations = HashMap::from([(alice.clone(), units::gil(1000))]);
+        let mut source = Blockchain::new_pow_with_genesis(1, 50.0, allocations).unwrap();
+        for receiver in ["bob", "carol"] {
+            source.add_transaction(&alice_key, receiver.to_string(), units::gil(10), None).unwrap();
+            source.mine_block("miner".to_string()).unwrap();
+        }
+        let mut target = source.replay_blocks(source.blocks[..1].to_vec()).unwrap();
+        target.max_orphan_age_secs = 60;
+
+        target.receive_block(source.blocks[2].clone()).unwrap();
+        target.orphan_blocks[0].received_at -= 61;
+
+        // The stale orphan is gone by the time its parent arrives
+        assert_eq!(target.receive_block(source.blocks[1].clone()).unwrap(), 1);
+        assert!(target.orphan_blocks.is_empty());
+        assert_eq!(target.blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_tiny_transfers_sum_exactly() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(1));
+
+        for _ in 0..1000 {
+            blockchain.add_transaction(&alice_key, "bob".to_string(), units::to_base_units(0.001).unwrap(), None).unwrap();
+        }
+        // A thousand signed transfers do not fit in one block
+        let first = blockchain.mine_block("miner".to_string()).unwrap();
+        assert!(first.size() <= MAX_BLOCK_SIZE);
+        assert!(!blockchain.pending_transactions.is_empty());
+        while !blockchain.pending_transactions.is_empty() {
+            blockchain.mine_block("miner".to_string()).unwrap();
+        }
+
+        // Summing 0.001 a thousand times in f64 does not give exactly 1.0
+        assert_ne!((0..1000).map(|_| 0.001).sum::<f64>(), 1.0);
+        assert_eq!(blockchain.get_balance_units("bob"), units::gil(1));
+        assert_eq!(blockchain.get_balance_units(&alice), 0);
+        assert_eq!(blockchain.get_balance("bob"), 1.0);
+    }
+
+    #[test]
+    fn test_min_relay_fee() {
+        let alice_key = crypto::KeyPair::generate().unwrap();
+        let alice = crypto::create_address(&alice_key.public_key());
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(1000));
+        blockchain.min_relay_fee = 0.5;
+
+        // Plain transfers carry no fee field and are exempt
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(10), None).unwrap();
+
+        let cheap = Transaction::new_contract_call(
+            alice.clone(), "contract".to_string(), "run".to_string(), units::gil(1), 1000, 0.0001,
+        ).unwrap();
+        assert!(cheap.fee_per_byte() < blockchain.min_relay_fee);
+        assert!(matches!(
+            blockchain.add_transaction_object(cheap),
+            Err(BlockchainError::InvalidInput(_))
+        ));
+
+        let paying = Transaction::new_contract_call(
+            alice.clone(), "contract".to_string(), "run".to_string(), units::gil(1), 1000, 1.0,
+        ).unwrap();
+        assert!(paying.fee_per_byte() >= blockchain.min_relay_fee);
+        blockchain.add_transaction_object(paying).unwrap();
+
+        let keypair = crypto::KeyPair::generate().unwrap();
+        let validator = crypto::create_address(&keypair.public_key());
+        blockchain.balances.insert(validator.clone(), units::gil(100));
+        let mut staking = Transaction::new_staking(validator, units::gil(10), true).unwrap();
+        staking.sign(&keypair).unwrap();
+        blockchain.add_transaction_object(staking).unwrap();
+        assert_eq!(blockchain.pending_transactions.len(), 3);
     }
 }
@@ -3,9 +3,18 @@ use std::collections::{HashMap, HashSet};
 use log::{debug, info, warn, error};
 // Removed unused import
 use chrono::Utc;
-use crate::{Result, BlockchainError, crypto::DigitalSignature};
+use crate::{Result, BlockchainError, units, block::Block, crypto::{DigitalSignature, KeyPair}};
+use crate::{ProofOfAuthority, ProofOfWork};
+use std::any::Any;
 use sha2::{Sha256, Digest};
 
+/// Default number of blocks unstaked tokens stay locked before they can be withdrawn
+pub const DEFAULT_UNBONDING_PERIOD: u64 = 100;
+
+fn default_unbonding_period() -> u64 {
+    DEFAULT_UNBONDING_PERIOD
+}
+
 /// Consensus mechanism types
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ConsensusType {
@@ -13,6 +22,8 @@ pub enum ConsensusType {
     ProofOfWork,
     /// Proof of Stake consensus
     ProofOfStake,
+    /// Proof of Authority consensus
+    ProofOfAuthority,
 }
 
 impl std::fmt::Display for ConsensusType {
@@ -20,10 +31,112 @@ impl std::fmt::Display for ConsensusType {
         match self {
             ConsensusType::ProofOfWork => write!(f, "pow"),
             ConsensusType::ProofOfStake => write!(f, "pos"),
+            ConsensusType::ProofOfAuthority => write!(f, "poa"),
         }
     }
 }
 
+/// Block production and validation rules of a consensus mechanism
+///
+/// `Blockchain` drives mining and validation through this trait, so a new
+/// mechanism only has to implement it to plug into the chain.
+pub trait Consensus: std::fmt::Debug + Send + Sync {
+    /// Type of this consensus mechanism
+    fn consensus_type(&self) -> ConsensusType;
+
+    /// Select the account entitled to produce the block at `height`
+    ///
+    /// # Arguments
+    /// * `height` - Index of the block to produce
+    /// * `previous_hash` - Hash of the block it extends
+    ///
+    /// # Returns
+    /// * `Result<Option<String>>` - The producer, or None if any miner may produce it
+    fn select_producer(&self, height: u64, previous_hash: &str) -> Result<Option<String>>;
+
+    /// Seal a block so that it satisfies this consensus mechanism
+    ///
+    /// # Arguments
+    /// * `block` - Block to seal, with its transactions and producer already set
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if the block was sealed, error otherwise
+    fn seal_block(&self, block: &mut Block) -> Result<()>;
+
+    /// Check the consensus-specific validity of a sealed block
+    ///
+    /// # Arguments
+    /// * `block` - Block to validate
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if the block is valid, error otherwise
+    fn validate_block(&self, block: &Block) -> Result<()>;
+
+    /// Check the consensus-specific validity of a block already in the chain
+    ///
+    /// Requirements that change as the chain grows, such as the difficulty, are
+    /// taken from the block's height rather than from the current tip.
+    ///
+    /// # Arguments
+    /// * `block` - Block to validate
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if the block is valid, error otherwise
+    fn validate_historical_block(&self, block: &Block) -> Result<()> {
+        self.validate_block(block)
+    }
+
+    /// Copy this mechanism and its state into a new box
+    fn clone_box(&self) -> Box<dyn Consensus>;
+
+    /// Borrow this mechanism as `Any`, to reach its mechanism-specific state
+    fn as_any(&self) -> &dyn Any;
+
+    /// Mutably borrow this mechanism as `Any`, to reach its mechanism-specific state
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Borrow this mechanism's state in the form it is serialized in
+    fn state(&self) -> ConsensusStateRef<'_>;
+}
+
+impl Clone for Box<dyn Consensus> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Serialized state of a consensus mechanism, tagged with its type
+#[derive(Debug, Serialize)]
+pub enum ConsensusStateRef<'a> {
+    ProofOfWork(&'a ProofOfWork),
+    ProofOfStake(&'a ProofOfStake),
+    ProofOfAuthority(&'a ProofOfAuthority),
+}
+
+/// Owned counterpart of `ConsensusStateRef`, used when deserializing
+#[derive(Debug, Deserialize)]
+pub enum ConsensusState {
+    ProofOfWork(ProofOfWork),
+    ProofOfStake(Box<ProofOfStake>),
+    ProofOfAuthority(ProofOfAuthority),
+}
+
+impl Serialize for Box<dyn Consensus> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.state().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Box<dyn Consensus> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Ok(match ConsensusState::deserialize(deserializer)? {
+            ConsensusState::ProofOfWork(pow) => Box::new(pow),
+            ConsensusState::ProofOfStake(pos) => pos,
+            ConsensusState::ProofOfAuthority(poa) => Box::new(poa),
+        })
+    }
+}
+
 /// Represents a validator in the Proof-of-Stake system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Validator {
@@ -31,8 +144,8 @@ pub struct Validator {
     pub public_key: String,
     /// Validator's address
     pub address: String,
-    /// Amount of tokens staked
-    pub stake_amount: f64,
+    /// Amount of tokens staked, in base units
+    pub stake_amount: u128,
     /// When the validator started staking
     pub staking_since: i64,
     /// Whether the validator is active
@@ -53,15 +166,32 @@ pub struct Validator {
     pub jailed: bool,
     /// Jail end timestamp
     pub jail_end_time: Option<i64>,
+    /// Height of the first block the validator may produce
+    #[serde(default)]
+    pub registered_height: u64,
+}
+
+/// A validator removed from the set, kept so the blocks it produced stay valid
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetiredValidator {
+    /// Validator's address
+    pub address: String,
+    /// Public key the validator signed its blocks with
+    pub public_key: String,
+    /// Height of the first block the validator could produce
+    pub registered_height: u64,
+    /// Height of the first block the validator can no longer produce
+    pub retired_height: u64,
 }
 
 /// Proof-of-Stake consensus implementation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProofOfStake {
     /// List of validators
+    #[serde(serialize_with = "crate::utils::sorted_map")]
     pub validators: HashMap<String, Validator>,
-    /// Minimum stake required to become a validator
-    pub min_stake: f64,
+    /// Minimum stake required to become a validator, in base units
+    pub min_stake: u128,
     /// Maximum number of validators
     pub max_validators: usize,
     /// Current epoch number
@@ -81,11 +211,42 @@ pub struct ProofOfStake {
     /// Pending slashing evidence
     pub pending_slashings: Vec<SlashingEvidence>,
     /// Finalized blocks
+    #[serde(serialize_with = "crate::utils::sorted_set")]
     pub finalized_blocks: HashSet<String>,
     /// Current epoch info
     pub current_epoch_info: Option<EpochInfo>,
     /// Validator selection seed for current epoch
     pub selection_seed: String,
+    /// Equivocations already punished, so the same evidence cannot slash twice
+    #[serde(default)]
+    #[serde(serialize_with = "crate::utils::sorted_set")]
+    pub reported_equivocations: HashSet<String>,
+    /// Cumulative block rewards earned per validator, in base units
+    #[serde(default)]
+    #[serde(serialize_with = "crate::utils::sorted_map")]
+    pub rewards: HashMap<String, u128>,
+    /// Number of blocks unstaked tokens stay locked before they can be withdrawn
+    #[serde(default = "default_unbonding_period")]
+    pub unbonding_period: u64,
+    /// Unstaked tokens waiting out the unbonding period, by validator address
+    #[serde(default)]
+    #[serde(serialize_with = "crate::utils::sorted_map")]
+    pub unbonding: HashMap<String, Vec<UnbondingEntry>>,
+    /// Validators removed from the set, in order of removal
+    #[serde(default)]
+    pub retired_validators: Vec<RetiredValidator>,
+    /// Keys this node signs blocks with, by validator address
+    #[serde(skip)]
+    signers: HashMap<String, KeyPair>,
+}
+
+/// Unstaked tokens that become withdrawable at a given height
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnbondingEntry {
+    /// Amount of tokens being unbonded, in base units
+    pub amount: u128,
+    /// Block height from which the tokens can be withdrawn
+    pub release_height: u64,
 }
 
 /// Block validation result for PoS
@@ -110,8 +271,8 @@ pub struct StakingTransaction {
     pub id: String,
     /// Validator's address
     pub validator_address: String,
-    /// Amount being staked
-    pub stake_amount: f64,
+    /// Amount being staked, in base units
+    pub stake_amount: u128,
     /// Transaction timestamp
     pub timestamp: i64,
     /// Transaction signature
@@ -180,7 +341,7 @@ pub struct EpochInfo {
 
 impl Validator {
     /// Create a new validator
-    pub fn new(public_key: String, address: String, stake_amount: f64) -> Self {
+    pub fn new(public_key: String, address: String, stake_amount: u128) -> Self {
         Validator {
             public_key,
             address,
@@ -195,6 +356,7 @@ impl Validator {
             reputation_score: 1.0,
             jailed: false,
             jail_end_time: None,
+            registered_height: 0,
         }
     }
 
@@ -216,12 +378,12 @@ impl Validator {
         if self.jailed || !self.active {
             return 0.0;
         }
-        self.stake_amount * self.performance_score * self.reputation_score
+        units::from_base_units(self.stake_amount) * self.performance_score * self.reputation_score
     }
 
     /// Check if validator is eligible for selection
     pub fn is_eligible(&self) -> bool {
-        self.active && !self.jailed && self.stake_amount > 0.0
+        self.active && !self.jailed && self.stake_amount > 0
     }
 
     /// Update reputation score based on behavior
@@ -234,19 +396,21 @@ impl Validator {
     }
 
     /// Add stake to validator
-    pub fn add_stake(&mut self, amount: f64) -> Result<()> {
-        if amount <= 0.0 {
+    pub fn add_stake(&mut self, amount: u128) -> Result<()> {
+        if amount == 0 {
             return Err(BlockchainError::ConsensusError(
                 "Stake amount must be positive".to_string(),
             ));
         }
-        self.stake_amount += amount;
+        self.stake_amount = self.stake_amount.checked_add(amount).ok_or_else(|| {
+            BlockchainError::ConsensusError("Stake amount overflow".to_string())
+        })?;
         Ok(())
     }
 
     /// Remove stake from validator
-    pub fn remove_stake(&mut self, amount: f64) -> Result<()> {
-        if amount <= 0.0 {
+    pub fn remove_stake(&mut self, amount: u128) -> Result<()> {
+        if amount == 0 {
             return Err(BlockchainError::ConsensusError(
                 "Unstake amount must be positive".to_string(),
             ));
@@ -265,7 +429,7 @@ impl ProofOfStake {
     /// Create a new Proof-of-Stake consensus system
     /// 
     /// # Arguments
-    /// * `min_stake` - Minimum stake required to become a validator
+    /// * `min_stake` - Minimum stake required to become a validator, in base units
     /// * `max_validators` - Maximum number of validators
     /// * `staking_reward_rate` - Annual staking reward rate (as percentage)
     /// * `slashing_penalty_rate` - Penalty rate for misbehavior (as percentage)
@@ -273,12 +437,12 @@ impl ProofOfStake {
     /// # Returns
     /// * `Result<ProofOfStake>` - The created PoS system or an error
     pub fn new(
-        min_stake: f64,
+        min_stake: u128,
         max_validators: usize,
         staking_reward_rate: f64,
         slashing_penalty_rate: f64,
     ) -> Result<Self> {
-        if min_stake <= 0.0 {
+        if min_stake == 0 {
             return Err(BlockchainError::ConsensusError(
                 "Minimum stake must be positive".to_string(),
             ));
@@ -305,6 +469,12 @@ impl ProofOfStake {
             finalized_blocks: HashSet::new(),
             current_epoch_info: None,
             selection_seed: String::new(),
+            reported_equivocations: HashSet::new(),
+            rewards: HashMap::new(),
+            unbonding_period: DEFAULT_UNBONDING_PERIOD,
+            unbonding: HashMap::new(),
+            retired_validators: Vec::new(),
+            signers: HashMap::new(),
         };
 
         info!("Created Proof-of-Stake consensus with min_stake={}, max_validators={}", 
@@ -314,7 +484,7 @@ impl ProofOfStake {
 
     /// Create a PoS system with default settings
     pub fn new_default() -> Result<Self> {
-        Self::new(1000.0, 100, 5.0, 10.0)
+        Self::new(units::gil(1000), 100, 5.0, 10.0)
     }
 
     /// Register a new validator
@@ -322,11 +492,25 @@ impl ProofOfStake {
     /// # Arguments
     /// * `public_key` - Validator's public key
     /// * `address` - Validator's address
-    /// * `stake_amount` - Amount to stake
+    /// * `stake_amount` - Amount to stake, in base units
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if registered successfully, error otherwise
+    pub fn register_validator(&mut self, public_key: String, address: String, stake_amount: u128) -> Result<()> {
+        self.register_validator_at(public_key, address, stake_amount, 0)
+    }
+
+    /// Register a new validator that may produce blocks from the given height on
+    /// 
+    /// # Arguments
+    /// * `public_key` - Validator's public key
+    /// * `address` - Validator's address
+    /// * `stake_amount` - Amount to stake, in base units
+    /// * `height` - Height of the first block the validator may produce
     /// 
     /// # Returns
     /// * `Result<()>` - Ok if registered successfully, error otherwise
-    pub fn register_validator(&mut self, public_key: String, address: String, stake_amount: f64) -> Result<()> {
+    pub fn register_validator_at(&mut self, public_key: String, address: String, stake_amount: u128, height: u64) -> Result<()> {
         if stake_amount < self.min_stake {
             return Err(BlockchainError::ConsensusError(
                 format!("Stake amount {} is below minimum required {}", stake_amount, self.min_stake),
@@ -345,14 +529,39 @@ impl ProofOfStake {
             ));
         }
 
-        let validator = Validator::new(public_key, address.clone(), stake_amount);
+        let mut validator = Validator::new(public_key, address.clone(), stake_amount);
+        validator.registered_height = height;
         self.validators.insert(address.clone(), validator);
 
         info!("Registered validator: {} with stake: {}", address, stake_amount);
         Ok(())
     }
 
-    /// Select the next validator for block creation using secure deterministic selection
+    /// Add a key this node signs the blocks of its validator with
+    ///
+    /// # Arguments
+    /// * `keypair` - Key pair whose public key a registered validator was registered with
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if added, error if no validator has the key's public key
+    pub fn add_signer(&mut self, keypair: KeyPair) -> Result<()> {
+        let public_key = keypair.public_key_hex();
+        let address = self.validators.values()
+            .find(|validator| validator.public_key == public_key)
+            .map(|validator| validator.address.clone())
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                format!("{} is not the key of a registered validator", keypair.public_key().short()),
+            ))?;
+        self.signers.insert(address, keypair);
+        Ok(())
+    }
+
+    /// Select the next validator for block creation using stake-weighted deterministic selection
+    /// 
+    /// SHA-256 of `previous_block_hash || block_height` is mapped onto the cumulative
+    /// stake of the eligible validators, taken in address order, so every node with the
+    /// same validator set picks the same validator and each validator is chosen with
+    /// probability proportional to its stake.
     /// 
     /// # Arguments
     /// * `block_height` - Current block height
@@ -361,53 +570,37 @@ impl ProofOfStake {
     /// # Returns
     /// * `Option<String>` - Selected validator address or None if no validators
     pub fn select_validator(&self, block_height: u64, previous_block_hash: &str) -> Option<String> {
-        if self.validators.is_empty() {
-            return None;
-        }
-
-        // Create deterministic seed using block height, previous hash, and epoch
-        let seed_data = format!("{}{}{}{}", block_height, previous_block_hash, self.current_epoch, self.selection_seed);
-        let mut hasher = Sha256::new();
-        hasher.update(seed_data.as_bytes());
-        let seed_hash = hasher.finalize();
-        
-        // Convert hash to deterministic "random" value
-        let seed_value = u64::from_le_bytes([
-            seed_hash[0], seed_hash[1], seed_hash[2], seed_hash[3],
-            seed_hash[4], seed_hash[5], seed_hash[6], seed_hash[7],
-        ]);
-        
-        // Get eligible validators (active, not jailed, with stake)
-        let eligible_validators: Vec<&Validator> = self.validators.values()
+        // Eligible validators (active, not jailed, with stake) in a canonical order
+        let mut eligible_validators: Vec<&Validator> = self.validators.values()
             .filter(|v| v.is_eligible())
             .collect();
+        eligible_validators.sort_by(|a, b| a.address.cmp(&b.address));
 
-        if eligible_validators.is_empty() {
+        let total_stake = eligible_validators.iter()
+            .try_fold(0u128, |total, v| total.checked_add(v.stake_amount))?;
+        if eligible_validators.is_empty() || total_stake == 0 {
             return None;
         }
 
-        // Calculate total weight of eligible validators
-        let total_weight: f64 = eligible_validators.iter()
-            .map(|v| v.calculate_weight())
-            .sum();
-
-        if total_weight == 0.0 {
-            return None;
-        }
+        let mut hasher = Sha256::new();
+        hasher.update(previous_block_hash.as_bytes());
+        hasher.update(block_height.to_be_bytes());
+        let seed_hash = hasher.finalize();
+        let mut seed_bytes = [0u8; 16];
+        seed_bytes.copy_from_slice(&seed_hash[..16]);
+        let seed_value = u128::from_be_bytes(seed_bytes);
 
-        // Use deterministic weighted selection
-        let mut selection_value = (seed_value as f64 / u64::MAX as f64) * total_weight;
-        
+        // Point in [0, total_stake) on the cumulative stake distribution
+        let point = seed_value % total_stake;
+        let mut cumulative = 0u128;
         for validator in &eligible_validators {
-            let weight = validator.calculate_weight();
-            if selection_value <= weight {
+            cumulative += validator.stake_amount;
+            if point < cumulative {
                 return Some(validator.address.clone());
             }
-            selection_value -= weight;
         }
 
-        // Fallback to first eligible validator
-        eligible_validators.first().map(|v| v.address.clone())
+        None
     }
 
     /// Validate a block using PoS consensus
@@ -466,7 +659,7 @@ impl ProofOfStake {
     /// 
     /// # Returns
     /// * `Result<()>` - Ok if processed successfully, error otherwise
-    pub fn process_staking_transaction(&mut self, staking_tx: StakingTransaction) -> Result<()> {
+    pub fn process_staking_transaction(&mut self, staking_tx: StakingTransaction, current_height: u64) -> Result<()> {
         if staking_tx.is_stake {
             // Stake operation
             if let Some(validator) = self.validators.get_mut(&staking_tx.validator_address) {
@@ -481,51 +674,201 @@ impl ProofOfStake {
             }
             info!("Staked {} tokens for validator {}", staking_tx.stake_amount, staking_tx.validator_address);
         } else {
-            // Unstake operation
-            if let Some(validator) = self.validators.get_mut(&staking_tx.validator_address) {
-                validator.remove_stake(staking_tx.stake_amount)?;
-                
-                // Deactivate validator if stake drops below minimum
-                if validator.stake_amount < self.min_stake {
-                    validator.active = false;
-                    info!("Deactivated validator {} due to insufficient stake", staking_tx.validator_address);
-                }
-            } else {
-                return Err(BlockchainError::ConsensusError(
-                    "Validator not found for unstaking".to_string(),
-                ));
+            self.unstake(&staking_tx.validator_address, staking_tx.stake_amount, current_height)?;
+        }
+        Ok(())
+    }
+
+    /// Remove stake from a validator and lock it for the unbonding period
+    /// 
+    /// # Arguments
+    /// * `address` - Validator address
+    /// * `amount` - Amount to unstake, in base units
+    /// * `current_height` - Current block height
+    /// 
+    /// # Returns
+    /// * `Result<u64>` - Height from which the amount can be withdrawn
+    pub fn unstake(&mut self, address: &str, amount: u128, current_height: u64) -> Result<u64> {
+        let validator = self.validators.get_mut(address)
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                "Validator not found for unstaking".to_string(),
+            ))?;
+        validator.remove_stake(amount)?;
+
+        // Deactivate validator if stake drops below minimum
+        if validator.stake_amount < self.min_stake {
+            validator.active = false;
+            info!("Deactivated validator {} due to insufficient stake", address);
+        }
+
+        let release_height = current_height.saturating_add(self.unbonding_period);
+        self.unbonding.entry(address.to_string()).or_default().push(UnbondingEntry {
+            amount,
+            release_height,
+        });
+        info!("Unstaked {} tokens for validator {}, withdrawable at height {}", amount, address, release_height);
+        Ok(release_height)
+    }
+
+    /// Withdraw part of a validator's unstaked tokens whose unbonding period has passed
+    /// 
+    /// Released entries are consumed oldest first, the last one only partly if needed.
+    /// 
+    /// # Arguments
+    /// * `address` - Validator address
+    /// * `amount` - Amount to withdraw, in base units
+    /// * `current_height` - Current block height
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if withdrawn, or an error if less than `amount` is withdrawable yet
+    pub fn withdraw_unbonded(&mut self, address: &str, amount: u128, current_height: u64) -> Result<()> {
+        if amount == 0 {
+            return Err(BlockchainError::ConsensusError(
+                "Withdrawal amount must be positive".to_string(),
+            ));
+        }
+        let entries = self.unbonding.get_mut(address)
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                format!("Validator {} has no unbonding stake", address),
+            ))?;
+        let withdrawable: u128 = entries.iter()
+            .filter(|entry| entry.release_height <= current_height)
+            .map(|entry| entry.amount)
+            .sum();
+        if withdrawable < amount {
+            let next_release = entries.iter()
+                .filter(|entry| entry.release_height > current_height)
+                .map(|entry| entry.release_height)
+                .min()
+                .unwrap_or_default();
+            return Err(BlockchainError::ConsensusError(format!(
+                "Validator {} can withdraw {} but requested {}; more is locked until height {} (current height {})",
+                address, withdrawable, amount, next_release, current_height
+            )));
+        }
+
+        let mut remaining = amount;
+        entries.retain_mut(|entry| {
+            if remaining == 0 || entry.release_height > current_height {
+                return true;
             }
-            info!("Unstaked {} tokens for validator {}", staking_tx.stake_amount, staking_tx.validator_address);
+            let taken = entry.amount.min(remaining);
+            entry.amount -= taken;
+            remaining -= taken;
+            entry.amount > 0
+        });
+        if entries.is_empty() {
+            self.unbonding.remove(address);
         }
+
+        info!("Withdrew {} unbonded tokens for validator {}", amount, address);
+        Ok(())
+    }
+
+    /// Get the amount of a validator's unstaked tokens whose unbonding period has passed
+    /// 
+    /// # Arguments
+    /// * `address` - Validator address
+    /// * `current_height` - Current block height
+    /// 
+    /// # Returns
+    /// * `u128` - Amount in base units that can be withdrawn at `current_height`
+    pub fn get_withdrawable(&self, address: &str, current_height: u64) -> u128 {
+        self.unbonding.get(address)
+            .map(|entries| entries.iter()
+                .filter(|entry| entry.release_height <= current_height)
+                .map(|entry| entry.amount)
+                .sum())
+            .unwrap_or(0)
+    }
+
+    /// Get the total amount a validator has waiting out the unbonding period
+    /// 
+    /// # Arguments
+    /// * `address` - Validator address
+    /// 
+    /// # Returns
+    /// * `u128` - Unbonding amount in base units
+    pub fn get_unbonding(&self, address: &str) -> u128 {
+        self.unbonding.get(address)
+            .map(|entries| entries.iter().map(|entry| entry.amount).sum())
+            .unwrap_or(0)
+    }
+
+    /// Get the public key a validator was registered with at a given height
+    /// 
+    /// Validators removed since then are found among the retired validators, so
+    /// blocks they produced while in the set remain valid.
+    /// 
+    /// # Arguments
+    /// * `address` - Validator address
+    /// * `height` - Block height
+    /// 
+    /// # Returns
+    /// * `Option<&str>` - The validator's public key, or None if it was not a validator at `height`
+    pub fn validator_key_at(&self, address: &str, height: u64) -> Option<&str> {
+        self.validators.get(address)
+            .filter(|validator| validator.registered_height <= height)
+            .map(|validator| validator.public_key.as_str())
+            .or_else(|| self.retired_validators.iter()
+                .find(|retired| retired.address == address
+                    && retired.registered_height <= height
+                    && height < retired.retired_height)
+                .map(|retired| retired.public_key.as_str()))
+    }
+
+    /// Get the fraction of stake an equivocation slashes
+    /// 
+    /// # Returns
+    /// * `f64` - Slash fraction between 0.0 and 1.0
+    pub fn slash_fraction(&self) -> f64 {
+        (self.slashing_penalty_rate / 100.0).clamp(0.0, 1.0)
+    }
+
+    /// Set the fraction of stake an equivocation slashes
+    /// 
+    /// # Arguments
+    /// * `fraction` - Slash fraction between 0.0 and 1.0
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if set, error if the fraction is out of range
+    pub fn set_slash_fraction(&mut self, fraction: f64) -> Result<()> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(BlockchainError::ConsensusError(
+                format!("Slash fraction must be between 0 and 1, got {}", fraction),
+            ));
+        }
+        self.slashing_penalty_rate = fraction * 100.0;
         Ok(())
     }
 
     /// Distribute staking rewards
     /// 
     /// # Arguments
-    /// * `total_rewards` - Total rewards to distribute
+    /// * `total_rewards` - Total rewards to distribute, in base units
     /// 
     /// # Returns
-    /// * `HashMap<String, f64>` - Rewards per validator
-    pub fn distribute_rewards(&self, total_rewards: f64) -> HashMap<String, f64> {
+    /// * `HashMap<String, u128>` - Rewards per validator, rounded down to whole base units
+    pub fn distribute_rewards(&self, total_rewards: u128) -> HashMap<String, u128> {
         let mut rewards = HashMap::new();
         
-        if self.validators.is_empty() || total_rewards <= 0.0 {
+        if self.validators.is_empty() || total_rewards == 0 {
             return rewards;
         }
 
-        let total_stake: f64 = self.validators.values()
+        let total_stake: u128 = self.validators.values()
             .filter(|v| v.active)
             .map(|v| v.stake_amount)
             .sum();
 
-        if total_stake == 0.0 {
+        if total_stake == 0 {
             return rewards;
         }
 
         for validator in self.validators.values() {
             if validator.active {
-                let validator_reward = (validator.stake_amount / total_stake) * total_rewards;
+                let share = validator.stake_amount as f64 / total_stake as f64;
+                let validator_reward = units::fraction_of(total_rewards, share);
                 rewards.insert(validator.address.clone(), validator_reward);
             }
         }
@@ -533,6 +876,29 @@ impl ProofOfStake {
         rewards
     }
 
+    /// Record a block reward earned by a validator
+    /// 
+    /// # Arguments
+    /// * `validator` - Address of the validator that produced the block
+    /// * `amount` - Reward paid to it, in base units
+    pub fn record_reward(&mut self, validator: &str, amount: u128) {
+        if amount > 0 {
+            let total = self.rewards.entry(validator.to_string()).or_insert(0);
+            *total = total.saturating_add(amount);
+        }
+    }
+
+    /// Get the cumulative block rewards earned by a validator
+    /// 
+    /// # Arguments
+    /// * `validator` - Validator address
+    /// 
+    /// # Returns
+    /// * `u128` - Total rewards in base units, 0 if it has never produced a block
+    pub fn get_rewards(&self, validator: &str) -> u128 {
+        self.rewards.get(validator).copied().unwrap_or(0)
+    }
+
     /// Slash a validator for misbehavior with enhanced security
     /// 
     /// # Arguments
@@ -540,8 +906,8 @@ impl ProofOfStake {
     /// * `evidence` - Slashing evidence
     /// 
     /// # Returns
-    /// * `Result<f64>` - Amount slashed or error
-    pub fn slash_validator(&mut self, evidence: SlashingEvidence) -> Result<f64> {
+    /// * `Result<u128>` - Amount slashed in base units, or error
+    pub fn slash_validator(&mut self, evidence: SlashingEvidence) -> Result<u128> {
         let validator = self.validators.get_mut(&evidence.validator_address)
             .ok_or_else(|| BlockchainError::ConsensusError(
                 "Validator not found".to_string(),
@@ -555,7 +921,7 @@ impl ProofOfStake {
             SlashingOffense::Unavailability => 0.05, // 5% for unavailability
         };
 
-        let slash_amount = validator.stake_amount * slash_percentage;
+        let slash_amount = units::fraction_of(validator.stake_amount, slash_percentage);
         validator.stake_amount -= slash_amount;
         validator.slash_count += 1;
         validator.last_slash_time = Some(evidence.timestamp);
@@ -582,6 +948,122 @@ impl ProofOfStake {
         Ok(slash_amount)
     }
 
+    /// Message a validator signs to vote for a block at a given height
+    /// 
+    /// # Arguments
+    /// * `height` - Height of the block
+    /// * `block_hash` - Hash of the block
+    /// 
+    /// # Returns
+    /// * `Vec<u8>` - Bytes to sign
+    pub fn block_vote_message(height: u64, block_hash: &str) -> Vec<u8> {
+        format!("gillean-block-vote:{}:{}", height, block_hash).into_bytes()
+    }
+
+    /// Report a validator that signed two different blocks at the same height
+    /// 
+    /// Both signatures must verify against the validator's registered public key over
+    /// `block_vote_message(height, ..)` for two distinct block hashes. A genuine
+    /// equivocation slashes `slash_fraction()` of the validator's stake, including
+    /// tokens still unbonding, and the validator is removed once its stake falls
+    /// below `min_stake`.
+    /// 
+    /// # Arguments
+    /// * `validator` - Address of the accused validator
+    /// * `height` - Height at which both blocks were signed
+    /// * `block_hash_a` - Hash of the first block
+    /// * `sig_a` - Validator's signature over the first block
+    /// * `block_hash_b` - Hash of the second block
+    /// * `sig_b` - Validator's signature over the second block
+    /// * `current_height` - Current block height
+    /// 
+    /// # Returns
+    /// * `Result<u128>` - Amount slashed in base units, or an error if the evidence is not a valid equivocation
+    #[allow(clippy::too_many_arguments)]
+    pub fn report_equivocation(
+        &mut self,
+        validator: &str,
+        height: u64,
+        block_hash_a: &str,
+        sig_a: &DigitalSignature,
+        block_hash_b: &str,
+        sig_b: &DigitalSignature,
+        current_height: u64,
+    ) -> Result<u128> {
+        if block_hash_a == block_hash_b {
+            return Err(BlockchainError::ConsensusError(
+                "Equivocation requires two different blocks".to_string(),
+            ));
+        }
+
+        let (first, second) = if block_hash_a < block_hash_b {
+            (block_hash_a, block_hash_b)
+        } else {
+            (block_hash_b, block_hash_a)
+        };
+        let evidence_key = format!("{}:{}:{}:{}", validator, height, first, second);
+        if self.reported_equivocations.contains(&evidence_key) {
+            return Err(BlockchainError::ConsensusError(
+                "Equivocation has already been reported".to_string(),
+            ));
+        }
+
+        let fraction = self.slash_fraction();
+        let entry = self.validators.get_mut(validator)
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                "Validator not found".to_string(),
+            ))?;
+        if entry.public_key.is_empty() {
+            return Err(BlockchainError::ConsensusError(
+                format!("Validator {} has no registered public key", validator),
+            ));
+        }
+
+        for (block_hash, signature) in [(block_hash_a, sig_a), (block_hash_b, sig_b)] {
+            if signature.public_key_hex() != entry.public_key {
+                return Err(BlockchainError::ConsensusError(
+                    format!("Signature over block {} is not from validator {}", block_hash, validator),
+                ));
+            }
+            if !signature.verify(&Self::block_vote_message(height, block_hash))? {
+                return Err(BlockchainError::ConsensusError(
+                    format!("Invalid signature over block {}", block_hash),
+                ));
+            }
+        }
+
+        let mut slash_amount = units::fraction_of(entry.stake_amount, fraction);
+        entry.stake_amount -= slash_amount;
+        entry.slash_count += 1;
+        entry.last_slash_time = Some(Utc::now().timestamp());
+        entry.reputation_score = 0.0;
+        let remaining_stake = entry.stake_amount;
+
+        // Unstaking does not escape punishment for misbehavior while bonded
+        for unbonding in self.unbonding.get_mut(validator).into_iter().flatten() {
+            let slashed = units::fraction_of(unbonding.amount, fraction);
+            unbonding.amount -= slashed;
+            slash_amount += slashed;
+        }
+
+        self.reported_equivocations.insert(evidence_key);
+        if remaining_stake < self.min_stake {
+            if let Some(removed) = self.validators.remove(validator) {
+                self.retired_validators.push(RetiredValidator {
+                    address: removed.address,
+                    public_key: removed.public_key,
+                    registered_height: removed.registered_height,
+                    retired_height: current_height + 1,
+                });
+            }
+            info!("Removed validator {} after equivocation left stake {}", validator, remaining_stake);
+        }
+
+        error!("Slashed validator {} for equivocation at height {} (amount: {})",
+               validator, height, slash_amount);
+        Ok(slash_amount)
+    }
+
     /// Submit slashing evidence
     /// 
     /// # Arguments
@@ -615,8 +1097,8 @@ impl ProofOfStake {
     /// Process pending slashing evidence
     /// 
     /// # Returns
-    /// * `Result<Vec<f64>>` - List of slash amounts
-    pub fn process_pending_slashings(&mut self) -> Result<Vec<f64>> {
+    /// * `Result<Vec<u128>>` - List of slash amounts in base units
+    pub fn process_pending_slashings(&mut self) -> Result<Vec<u128>> {
         let mut slash_amounts = Vec::new();
         let mut processed_validators = Vec::new();
 
@@ -766,7 +1248,7 @@ impl ProofOfStake {
         stats.insert("jailed_validators".to_string(), 
                     self.validators.values().filter(|v| v.jailed).count() as f64);
         stats.insert("total_stake".to_string(), 
-                    self.validators.values().map(|v| v.stake_amount).sum());
+                    units::from_base_units(self.validators.values().map(|v| v.stake_amount).sum()));
         stats.insert("average_performance".to_string(), 
                     self.validators.values().map(|v| v.performance_score).sum::<f64>() / 
                     self.validators.len().max(1) as f64);
@@ -782,11 +1264,83 @@ impl ProofOfStake {
     }
 }
 
+impl Consensus for ProofOfStake {
+    fn consensus_type(&self) -> ConsensusType {
+        ConsensusType::ProofOfStake
+    }
+
+    fn select_producer(&self, height: u64, previous_hash: &str) -> Result<Option<String>> {
+        self.select_validator(height, previous_hash)
+            .map(Some)
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                "No validators available for PoS mining".to_string(),
+            ))
+    }
+
+    fn seal_block(&self, block: &mut Block) -> Result<()> {
+        let validator = block.validator.as_ref()
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                format!("PoS block {} must have a validator", block.index),
+            ))?;
+        let signer = self.signers.get(validator)
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                format!("No signing key for validator {}", validator),
+            ))?;
+
+        block.hash = block.calculate_current_hash();
+        block.validator_signature = Some(signer.sign(block.hash.as_bytes())?);
+        debug!("Sealed block {} as validator {}", block.index, validator);
+        Ok(())
+    }
+
+    fn validate_block(&self, block: &Block) -> Result<()> {
+        let validator = block.validator.as_ref()
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                format!("PoS block {} must have a validator", block.index),
+            ))?;
+        let public_key = self.validator_key_at(validator, block.index)
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                format!("Block {} produced by {} which was not a validator at that height", block.index, validator),
+            ))?;
+        let signature = block.validator_signature.as_ref()
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                format!("Block {} is not signed by its validator", block.index),
+            ))?;
+        if signature.public_key_hex() != public_key {
+            return Err(BlockchainError::ConsensusError(
+                format!("Block {} is not signed with the key of validator {}", block.index, validator),
+            ));
+        }
+        if !signature.verify(block.hash.as_bytes())? {
+            return Err(BlockchainError::ConsensusError(
+                format!("Invalid validator signature on block {}", block.index),
+            ));
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn Consensus> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn state(&self) -> ConsensusStateRef<'_> {
+        ConsensusStateRef::ProofOfStake(self)
+    }
+}
+
 impl StakingTransaction {
     /// Create a new staking transaction
     pub fn new(
         validator_address: String,
-        stake_amount: f64,
+        stake_amount: u128,
         is_stake: bool,
     ) -> Result<Self> {
         if validator_address.is_empty() {
@@ -795,11 +1349,7 @@ impl StakingTransaction {
             ));
         }
 
-        if stake_amount <= 0.0 {
-            return Err(BlockchainError::ConsensusError(
-                "Stake amount must be positive".to_string(),
-            ));
-        }
+        units::require_positive(stake_amount, "Stake amount")?;
 
         let id = Self::generate_id(&validator_address, stake_amount, is_stake);
         let timestamp = Utc::now().timestamp();
@@ -815,7 +1365,7 @@ impl StakingTransaction {
     }
 
     /// Generate transaction ID
-    fn generate_id(validator_address: &str, stake_amount: f64, is_stake: bool) -> String {
+    fn generate_id(validator_address: &str, stake_amount: u128, is_stake: bool) -> String {
         use sha2::{Sha256, Digest};
         let mut hasher = Sha256::new();
         hasher.update(validator_address.as_bytes());
@@ -832,21 +1382,40 @@ mod tests {
 
     #[test]
     fn test_pos_creation() {
-        let pos = ProofOfStake::new(1000.0, 10, 5.0, 10.0).unwrap();
-        assert_eq!(pos.min_stake, 1000.0);
+        let pos = ProofOfStake::new(units::gil(1000), 10, 5.0, 10.0).unwrap();
+        assert_eq!(pos.min_stake, units::gil(1000));
         assert_eq!(pos.max_validators, 10);
         assert_eq!(pos.staking_reward_rate, 5.0);
         assert_eq!(pos.finality_threshold, 0.67);
     }
 
+    #[test]
+    fn test_pos_serialization_is_deterministic() {
+        let hashes: Vec<String> = (0..32).map(|i| format!("hash{:02}", i)).collect();
+        let mut first = ProofOfStake::new(units::gil(1000), 10, 5.0, 10.0).unwrap();
+        let mut second = first.clone();
+        for hash in &hashes {
+            first.finalized_blocks.insert(hash.clone());
+            first.reported_equivocations.insert(hash.clone());
+        }
+        for hash in hashes.iter().rev() {
+            second.finalized_blocks.insert(hash.clone());
+            second.reported_equivocations.insert(hash.clone());
+        }
+
+        let json = serde_json::to_string(&first).unwrap();
+        assert_eq!(json, serde_json::to_string(&second).unwrap());
+        assert!(json.find("\"hash00\"").unwrap() < json.find("\"hash31\"").unwrap());
+    }
+
     #[test]
     fn test_validator_registration() {
-        let mut pos = ProofOfStake::new(1000.0, 10, 5.0, 10.0).unwrap();
+        let mut pos = ProofOfStake::new(units::gil(1000), 10, 5.0, 10.0).unwrap();
         
         pos.register_validator(
             "pubkey1".to_string(),
             "validator1".to_string(),
-            2000.0,
+            units::gil(2000),
         ).unwrap();
 
         assert_eq!(pos.validators.len(), 1);
@@ -859,18 +1428,18 @@ mod tests {
 
     #[test]
     fn test_deterministic_validator_selection() {
-        let mut pos = ProofOfStake::new(1000.0, 10, 5.0, 10.0).unwrap();
+        let mut pos = ProofOfStake::new(units::gil(1000), 10, 5.0, 10.0).unwrap();
         
         pos.register_validator(
             "pubkey1".to_string(),
             "validator1".to_string(),
-            2000.0,
+            units::gil(2000),
         ).unwrap();
 
         pos.register_validator(
             "pubkey2".to_string(),
             "validator2".to_string(),
-            3000.0,
+            units::gil(3000),
         ).unwrap();
 
         // Test deterministic selection - same inputs should produce same result
@@ -882,14 +1451,44 @@ mod tests {
         assert!(pos.validators.contains_key(&selected1.unwrap()));
     }
 
+    #[test]
+    fn test_validator_selection_follows_stake_share() {
+        let mut pos = ProofOfStake::new(units::gil(1000), 10, 5.0, 10.0).unwrap();
+        let stakes = [("validator1", units::gil(1000)), ("validator2", units::gil(3000)), ("validator3", units::gil(6000))];
+        for (address, stake) in stakes {
+            pos.register_validator(format!("pubkey_{}", address), address.to_string(), stake).unwrap();
+        }
+
+        // Registration order does not affect the outcome
+        let mut reordered = ProofOfStake::new(units::gil(1000), 10, 5.0, 10.0).unwrap();
+        for (address, stake) in stakes.iter().rev() {
+            reordered.register_validator(format!("pubkey_{}", address), address.to_string(), *stake).unwrap();
+        }
+
+        let rounds = 20_000u64;
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        for height in 0..rounds {
+            let prev_hash = format!("{:064x}", height * 7919);
+            let selected = pos.select_validator(height, &prev_hash).unwrap();
+            assert_eq!(reordered.select_validator(height, &prev_hash).as_ref(), Some(&selected));
+            *counts.entry(selected).or_insert(0) += 1;
+        }
+
+        for (address, stake) in stakes {
+            let share = counts.get(address).copied().unwrap_or(0) as f64 / rounds as f64;
+            let expected = stake as f64 / units::gil(10_000) as f64;
+            assert!((share - expected).abs() < 0.02, "{} selected {:.3} of the time, expected {:.3}", address, share, expected);
+        }
+    }
+
     #[test]
     fn test_slashing_mechanisms() {
-        let mut pos = ProofOfStake::new(1000.0, 10, 5.0, 10.0).unwrap();
+        let mut pos = ProofOfStake::new(units::gil(1000), 10, 5.0, 10.0).unwrap();
         
         pos.register_validator(
             "pubkey1".to_string(),
             "validator1".to_string(),
-            2000.0,
+            units::gil(2000),
         ).unwrap();
 
         let evidence = SlashingEvidence {
@@ -901,7 +1500,7 @@ mod tests {
         };
 
         let slash_amount = pos.slash_validator(evidence).unwrap();
-        assert!(slash_amount > 0.0);
+        assert!(slash_amount > 0);
         
         let validator = &pos.validators["validator1"];
         assert!(validator.jailed);
@@ -912,14 +1511,14 @@ mod tests {
 
     #[test]
     fn test_finality_mechanisms() {
-        let mut pos = ProofOfStake::new(1000.0, 10, 5.0, 10.0).unwrap();
+        let mut pos = ProofOfStake::new(units::gil(1000), 10, 5.0, 10.0).unwrap();
         
         // Register validators
         for i in 1..=5 {
             pos.register_validator(
                 format!("pubkey{}", i),
                 format!("validator{}", i),
-                1000.0,
+                units::gil(1000),
             ).unwrap();
         }
 
@@ -946,7 +1545,7 @@ mod tests {
 
     #[test]
     fn test_epoch_management() {
-        let mut pos = ProofOfStake::new(1000.0, 10, 5.0, 10.0).unwrap();
+        let mut pos = ProofOfStake::new(units::gil(1000), 10, 5.0, 10.0).unwrap();
         
         let initial_epoch = pos.current_epoch;
         pos.update_epoch();
@@ -960,12 +1559,12 @@ mod tests {
 
     #[test]
     fn test_validator_jailing_and_unjailing() {
-        let mut pos = ProofOfStake::new(1000.0, 10, 5.0, 10.0).unwrap();
+        let mut pos = ProofOfStake::new(units::gil(1000), 10, 5.0, 10.0).unwrap();
         
         pos.register_validator(
             "pubkey1".to_string(),
             "validator1".to_string(),
-            2000.0,
+            units::gil(2000),
         ).unwrap();
 
         // Jail validator
@@ -994,24 +1593,24 @@ mod tests {
     fn test_staking_transaction() {
         let staking_tx = StakingTransaction::new(
             "validator1".to_string(),
-            1000.0,
+            units::gil(1000),
             true,
         ).unwrap();
 
         assert_eq!(staking_tx.validator_address, "validator1");
-        assert_eq!(staking_tx.stake_amount, 1000.0);
+        assert_eq!(staking_tx.stake_amount, units::gil(1000));
         assert!(staking_tx.is_stake);
         assert!(!staking_tx.id.is_empty());
     }
 
     #[test]
     fn test_validator_eligibility() {
-        let mut pos = ProofOfStake::new(1000.0, 10, 5.0, 10.0).unwrap();
+        let mut pos = ProofOfStake::new(units::gil(1000), 10, 5.0, 10.0).unwrap();
         
         pos.register_validator(
             "pubkey1".to_string(),
             "validator1".to_string(),
-            2000.0,
+            units::gil(2000),
         ).unwrap();
 
         let validator = &pos.validators["validator1"];
@@ -1033,18 +1632,18 @@ mod tests {
 
     #[test]
     fn test_validator_statistics() {
-        let mut pos = ProofOfStake::new(1000.0, 10, 5.0, 10.0).unwrap();
+        let mut pos = ProofOfStake::new(units::gil(1000), 10, 5.0, 10.0).unwrap();
         
         pos.register_validator(
             "pubkey1".to_string(),
             "validator1".to_string(),
-            2000.0,
+            units::gil(2000),
         ).unwrap();
 
         pos.register_validator(
             "pubkey2".to_string(),
             "validator2".to_string(),
-            3000.0,
+            units::gil(3000),
         ).unwrap();
 
         let stats = pos.get_validator_stats();
@@ -1054,4 +1653,108 @@ mod tests {
         assert_eq!(stats["total_stake"], 5000.0);
         assert_eq!(stats["finalized_blocks"], 0.0);
     }
+
+    fn equivocation_fixture() -> (ProofOfStake, crate::crypto::KeyPair) {
+        let keypair = crate::crypto::KeyPair::generate().unwrap();
+        let mut pos = ProofOfStake::new(units::gil(1000), 10, 10.0, 50.0).unwrap();
+        pos.register_validator(keypair.public_key_hex(), "validator1".to_string(), units::gil(3000)).unwrap();
+        (pos, keypair)
+    }
+
+    #[test]
+    fn test_report_equivocation_slashes() {
+        let (mut pos, keypair) = equivocation_fixture();
+        let sig_a = keypair.sign(&ProofOfStake::block_vote_message(7, "hash_a")).unwrap();
+        let sig_b = keypair.sign(&ProofOfStake::block_vote_message(7, "hash_b")).unwrap();
+
+        let slashed = pos.report_equivocation("validator1", 7, "hash_a", &sig_a, "hash_b", &sig_b, 10).unwrap();
+        assert_eq!(slashed, units::gil(1500));
+        assert_eq!(pos.validators["validator1"].stake_amount, units::gil(1500));
+        assert_eq!(pos.validators["validator1"].slash_count, 1);
+
+        // The same evidence cannot be used twice, even with the blocks swapped
+        assert!(pos.report_equivocation("validator1", 7, "hash_b", &sig_b, "hash_a", &sig_a, 10).is_err());
+
+        // A second equivocation drops the stake below the minimum and removes the validator
+        let sig_c = keypair.sign(&ProofOfStake::block_vote_message(8, "hash_c")).unwrap();
+        let sig_d = keypair.sign(&ProofOfStake::block_vote_message(8, "hash_d")).unwrap();
+        assert_eq!(pos.report_equivocation("validator1", 8, "hash_c", &sig_c, "hash_d", &sig_d, 10).unwrap(), units::gil(750));
+        assert!(!pos.validators.contains_key("validator1"));
+    }
+
+    #[test]
+    fn test_blocks_validate_against_validator_set_at_their_height() {
+        let (mut pos, keypair) = equivocation_fixture();
+        let signed_block = |index: u64, validator: &str| {
+            let mut block = Block::new_pos(index, Vec::new(), "prev".to_string(), "2.0.0".to_string(), validator.to_string()).unwrap();
+            block.hash = block.calculate_current_hash();
+            block.validator_signature = Some(keypair.sign(block.hash.as_bytes()).unwrap());
+            block
+        };
+        let early = signed_block(5, "validator1");
+        pos.validate_block(&early).unwrap();
+
+        // Removal at height 10 keeps the validator's earlier blocks valid
+        for (height, first, second) in [(7, "hash_a", "hash_b"), (8, "hash_c", "hash_d")] {
+            let sig_a = keypair.sign(&ProofOfStake::block_vote_message(height, first)).unwrap();
+            let sig_b = keypair.sign(&ProofOfStake::block_vote_message(height, second)).unwrap();
+            pos.report_equivocation("validator1", height, first, &sig_a, second, &sig_b, 10).unwrap();
+        }
+        assert!(!pos.validators.contains_key("validator1"));
+        pos.validate_block(&early).unwrap();
+        pos.validate_block(&signed_block(10, "validator1")).unwrap();
+        assert!(pos.validate_block(&signed_block(11, "validator1")).is_err());
+
+        // A validator cannot produce blocks from before its registration
+        pos.register_validator_at(keypair.public_key_hex(), "validator2".to_string(), units::gil(3000), 20).unwrap();
+        assert!(pos.validate_block(&signed_block(19, "validator2")).is_err());
+        pos.validate_block(&signed_block(20, "validator2")).unwrap();
+    }
+
+    #[test]
+    fn test_report_equivocation_rejects_invalid_evidence() {
+        let (mut pos, keypair) = equivocation_fixture();
+        let sig_a = keypair.sign(&ProofOfStake::block_vote_message(7, "hash_a")).unwrap();
+        let sig_b = keypair.sign(&ProofOfStake::block_vote_message(7, "hash_b")).unwrap();
+
+        // Signing the same block twice is not equivocation
+        assert!(pos.report_equivocation("validator1", 7, "hash_a", &sig_a, "hash_a", &sig_a, 10).is_err());
+        // Signatures made at a different height do not verify for this height
+        assert!(pos.report_equivocation("validator1", 8, "hash_a", &sig_a, "hash_b", &sig_b, 10).is_err());
+        // Signatures from another key are not the validator's
+        let other = crate::crypto::KeyPair::generate().unwrap();
+        let forged = other.sign(&ProofOfStake::block_vote_message(7, "hash_b")).unwrap();
+        assert!(pos.report_equivocation("validator1", 7, "hash_a", &sig_a, "hash_b", &forged, 10).is_err());
+
+        assert_eq!(pos.validators["validator1"].stake_amount, units::gil(3000));
+        assert_eq!(pos.validators["validator1"].slash_count, 0);
+    }
+
+    #[test]
+    fn test_unbonding_and_slash_fraction() {
+        let (mut pos, keypair) = equivocation_fixture();
+        pos.unbonding_period = 10;
+        assert!(pos.set_slash_fraction(1.5).is_err());
+        pos.set_slash_fraction(0.25).unwrap();
+
+        assert_eq!(pos.unstake("validator1", units::gil(1000), 5).unwrap(), 15);
+        assert_eq!(pos.validators["validator1"].stake_amount, units::gil(2000));
+        assert_eq!(pos.get_unbonding("validator1"), units::gil(1000));
+        assert_eq!(pos.get_withdrawable("validator1", 14), 0);
+        assert!(pos.withdraw_unbonded("validator1", units::gil(1000), 14).is_err());
+
+        // Unbonding stake is still slashed for equivocation
+        let sig_a = keypair.sign(&ProofOfStake::block_vote_message(7, "hash_a")).unwrap();
+        let sig_b = keypair.sign(&ProofOfStake::block_vote_message(7, "hash_b")).unwrap();
+        assert_eq!(pos.report_equivocation("validator1", 7, "hash_a", &sig_a, "hash_b", &sig_b, 10).unwrap(), units::gil(750));
+        assert_eq!(pos.validators["validator1"].stake_amount, units::gil(1500));
+
+        assert_eq!(pos.get_withdrawable("validator1", 15), units::gil(750));
+        assert!(pos.withdraw_unbonded("validator1", units::gil(1000), 15).is_err());
+        pos.withdraw_unbonded("validator1", units::gil(500), 15).unwrap();
+        assert_eq!(pos.get_unbonding("validator1"), units::gil(250));
+        pos.withdraw_unbonded("validator1", units::gil(250), 15).unwrap();
+        assert_eq!(pos.get_unbonding("validator1"), 0);
+        assert!(pos.withdraw_unbonded("validator1", units::gil(1), 20).is_err());
+    }
 }
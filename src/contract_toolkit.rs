@@ -28,12 +28,14 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     fs,
+    io::Read,
     path::Path,
     process::{Command, Stdio},
     sync::{Arc, RwLock},
-    time::{SystemTime, UNIX_EPOCH},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
-use log::info;
+use log::{info, warn};
 use walkdir::WalkDir;
 
 
@@ -73,6 +75,8 @@ pub struct ContractToolkitConfig {
     pub compilation_timeout: u64,
     /// Timeout for contract testing (seconds)
     pub test_timeout: u64,
+    /// Path to the local `gillean-contract` crate generated contract crates depend on
+    pub contract_crate_path: String,
 }
 
 /// Contract template
@@ -120,6 +124,9 @@ pub struct CompiledContract {
     pub wasm_bytecode: Vec<u8>,
     /// Contract metadata
     pub metadata: ContractMetadata,
+    /// Contract ABI
+    #[serde(default)]
+    pub abi: ContractAbi,
     /// Compilation timestamp
     pub compiled_at: u64,
     /// Compilation duration (milliseconds)
@@ -223,6 +230,118 @@ pub struct EventParameter {
     pub indexed: bool,
 }
 
+/// Contract ABI describing the callable methods
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContractAbi {
+    /// Public methods of the contract
+    pub methods: Vec<AbiMethod>,
+}
+
+/// ABI entry for a single contract method
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbiMethod {
+    /// Method name
+    pub name: String,
+    /// Method parameters, excluding the receiver
+    pub params: Vec<AbiParam>,
+    /// Return type, if the method returns a value
+    pub returns: Option<String>,
+}
+
+/// ABI entry for a single method parameter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbiParam {
+    /// Parameter name
+    pub name: String,
+    /// Parameter Rust type
+    pub param_type: String,
+}
+
+impl ContractAbi {
+    /// Build the ABI from the public methods in a contract's Rust source
+    pub fn from_source(source_code: &str) -> Result<Self> {
+        let method_pattern = regex::Regex::new(r"pub fn (\w+)\s*\(([^)]*)\)\s*(?:->\s*([^{]+))?\{")?;
+
+        let methods = method_pattern
+            .captures_iter(source_code)
+            .map(|cap| AbiMethod {
+                name: cap[1].to_string(),
+                params: Self::parse_params(&cap[2]),
+                returns: cap.get(3).map(|ret| ret.as_str().trim().to_string()),
+            })
+            .collect();
+
+        Ok(Self { methods })
+    }
+
+    /// Parse a Rust parameter list, skipping `self` receivers
+    fn parse_params(params: &str) -> Vec<AbiParam> {
+        let mut parsed = Vec::new();
+        let mut depth = 0;
+        let mut current = String::new();
+
+        // Split on top-level commas only, so `HashMap<K, V>` stays in one piece
+        for c in params.chars().chain(std::iter::once(',')) {
+            match c {
+                '<' | '(' | '[' => depth += 1,
+                '>' | ')' | ']' => depth -= 1,
+                ',' if depth == 0 => {
+                    if let Some((name, param_type)) = current.split_once(':') {
+                        parsed.push(AbiParam {
+                            name: name.trim().trim_start_matches("mut ").to_string(),
+                            param_type: param_type.trim().to_string(),
+                        });
+                    }
+                    current.clear();
+                    continue;
+                }
+                _ => {}
+            }
+            current.push(c);
+        }
+
+        parsed
+    }
+
+    /// Find a method by name
+    pub fn method(&self, name: &str) -> Option<&AbiMethod> {
+        self.methods.iter().find(|method| method.name == name)
+    }
+
+    /// Serialize the ABI to JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Check that a JSON value can be passed as a parameter of the given Rust type
+fn abi_value_matches(param_type: &str, value: &serde_json::Value) -> bool {
+    use serde_json::Value;
+
+    let param_type = param_type.trim().trim_start_matches('&').trim();
+    if let Some(inner) = param_type.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+        return value.is_null() || abi_value_matches(inner, value);
+    }
+    if let Some(inner) = param_type.strip_prefix("Vec<").and_then(|t| t.strip_suffix('>')) {
+        return value.as_array().is_some_and(|items| items.iter().all(|item| abi_value_matches(inner, item)));
+    }
+
+    match param_type {
+        "u8" => value.as_u64().is_some_and(|n| n <= u8::MAX as u64),
+        "u16" => value.as_u64().is_some_and(|n| n <= u16::MAX as u64),
+        "u32" => value.as_u64().is_some_and(|n| n <= u32::MAX as u64),
+        "u64" | "u128" | "usize" => value.is_u64(),
+        "i8" => value.as_i64().is_some_and(|n| i8::try_from(n).is_ok()),
+        "i16" => value.as_i64().is_some_and(|n| i16::try_from(n).is_ok()),
+        "i32" => value.as_i64().is_some_and(|n| i32::try_from(n).is_ok()),
+        "i64" | "i128" | "isize" => value.is_i64(),
+        "f32" | "f64" => value.is_number(),
+        "bool" => value.is_boolean(),
+        "String" | "str" | "Address" => value.is_string(),
+        _ => matches!(value, Value::Object(_)),
+    }
+}
+
 /// Test result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestResult {
@@ -289,6 +408,52 @@ pub struct DeploymentResult {
     pub duration: u64,
 }
 
+/// Class of failure observed while fuzzing a contract method
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FuzzFailureKind {
+    /// The contract panicked (hit an `unreachable` instruction)
+    Panic,
+    /// The call exhausted its gas budget
+    OutOfGas,
+    /// Any other WASM trap (overflow, division by zero, out-of-bounds access, ...)
+    Trap,
+}
+
+/// A distinct crash class found by the fuzzer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzFailure {
+    /// Failure class
+    pub kind: FuzzFailureKind,
+    /// Failure message reported by the runtime
+    pub message: String,
+    /// Smallest input found that reproduces this failure
+    pub minimal_input: Vec<serde_json::Value>,
+    /// Number of fuzz iterations that hit this failure
+    pub occurrences: u64,
+}
+
+/// Result of fuzzing a contract method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzReport {
+    /// Contract name
+    pub contract_name: String,
+    /// Fuzzed method
+    pub method: String,
+    /// Number of iterations run
+    pub iterations: u64,
+    /// Number of iterations that returned normally
+    pub successes: u64,
+    /// Distinct failures, one per crash class
+    pub failures: Vec<FuzzFailure>,
+}
+
+impl FuzzReport {
+    /// Whether the fuzzer surfaced any failing input
+    pub fn has_failures(&self) -> bool {
+        !self.failures.is_empty()
+    }
+}
+
 impl ContractToolkit {
     /// Create a new contract toolkit
     pub fn new(config: ContractToolkitConfig) -> Result<Self> {
@@ -328,6 +493,7 @@ impl ContractToolkit {
             templates.insert("voting".to_string(), Self::create_voting_template());
             templates.insert("escrow".to_string(), Self::create_escrow_template());
             templates.insert("token".to_string(), Self::create_token_template());
+            templates.insert("multisig".to_string(), Self::create_multisig_template());
             return Ok(templates);
         }
         
@@ -348,6 +514,7 @@ impl ContractToolkit {
             templates.insert("voting".to_string(), Self::create_voting_template());
             templates.insert("escrow".to_string(), Self::create_escrow_template());
             templates.insert("token".to_string(), Self::create_token_template());
+            templates.insert("multisig".to_string(), Self::create_multisig_template());
         }
         
         Ok(templates)
@@ -670,98 +837,14 @@ escrow.refund_buyer(escrow_id);
     fn create_token_template() -> ContractTemplate {
         ContractTemplate {
             name: "token".to_string(),
-            description: "ERC-20 compatible token contract".to_string(),
+            description: "ERC-20 compatible fungible token with allowances and owner-restricted minting".to_string(),
             category: "token".to_string(),
-            source_code: r#"
-use gillean_contract::*;
-
-#[contract]
-pub struct Token {
-    name: String,
-    symbol: String,
-    decimals: u8,
-    total_supply: u64,
-    balances: HashMap<Address, u64>,
-    allowances: HashMap<Address, HashMap<Address, u64>>,
-}
-
-impl Token {
-    #[constructor]
-    pub fn new(name: String, symbol: String, decimals: u8, total_supply: u64) -> Self {
-        let mut balances = HashMap::new();
-        let owner = self.get_caller();
-        balances.insert(owner, total_supply);
-        
-        Self {
-            name,
-            symbol,
-            decimals,
-            total_supply,
-            balances,
-            allowances: HashMap::new(),
-        }
-    }
-    
-    #[view]
-    pub fn name(&self) -> String {
-        self.name.clone()
-    }
-    
-    #[view]
-    pub fn symbol(&self) -> String {
-        self.symbol.clone()
-    }
-    
-    #[view]
-    pub fn decimals(&self) -> u8 {
-        self.decimals
-    }
-    
-    #[view]
-    pub fn total_supply(&self) -> u64 {
-        self.total_supply
-    }
-    
-    #[view]
-    pub fn balance_of(&self, owner: Address) -> u64 {
-        *self.balances.get(&owner).unwrap_or(&0)
-    }
-    
-    #[payable]
-    pub fn transfer(&mut self, to: Address, amount: u64) -> bool {
-        let from = self.get_caller();
-        self.transfer_from(from, to, amount)
-    }
-    
-    #[payable]
-    pub fn transfer_from(&mut self, from: Address, to: Address, amount: u64) -> bool {
-        if let Some(balance) = self.balances.get_mut(&from) {
-            if *balance >= amount {
-                *balance -= amount;
-                *self.balances.entry(to).or_insert(0) += amount;
-                return true;
-            }
-        }
-        false
-    }
-    
-    #[payable]
-    pub fn approve(&mut self, spender: Address, amount: u64) -> bool {
-        let owner = self.get_caller();
-        self.allowances.entry(owner).or_insert_with(HashMap::new).insert(spender, amount);
-        true
-    }
-    
-    #[view]
-    pub fn allowance(&self, owner: Address, spender: Address) -> u64 {
-        self.allowances.get(&owner)
-            .and_then(|spender_map| spender_map.get(&spender))
-            .copied()
-            .unwrap_or(0)
-    }
-}
-"#.to_string(),
-            dependencies: vec!["gillean-contract = \"0.1.0\"".to_string()],
+            source_code: include_str!("../contracts/examples/token/src/lib.rs").to_string(),
+            dependencies: vec![
+                "gillean-contract = \"0.1.0\"".to_string(),
+                "serde = { version = \"1.0\", features = [\"derive\"] }".to_string(),
+                "serde_json = \"1.0\"".to_string(),
+            ],
             parameters: vec![
                 TemplateParameter {
                     name: "name".to_string(),
@@ -778,40 +861,75 @@ impl Token {
                     required: true,
                 },
                 TemplateParameter {
-                    name: "decimals".to_string(),
-                    description: "Token decimals".to_string(),
-                    param_type: "u8".to_string(),
-                    default_value: Some("18".to_string()),
-                    required: true,
-                },
-                TemplateParameter {
-                    name: "total_supply".to_string(),
-                    description: "Total token supply".to_string(),
+                    name: "initial_supply".to_string(),
+                    description: "Tokens credited to the deployer".to_string(),
                     param_type: "u64".to_string(),
                     default_value: Some("1000000".to_string()),
                     required: true,
                 }
             ],
             usage_example: r#"
-// Deploy the token contract
-let token = Token::new(
-    "MyToken".to_string(),
-    "MTK".to_string(),
-    18,
-    1000000
-);
+// Deploy the token contract; the deployer receives the initial supply
+let mut token = Token::new("MyToken".to_string(), "MTK".to_string(), 1000000);
 
 // Transfer tokens
-token.transfer(recipient_address, 100);
+token.transfer(recipient_address, 100)?;
 
 // Check balance
 let balance = token.balance_of(recipient_address); // Returns 100
 
 // Approve spending
-token.approve(spender_address, 50);
+token.approve(spender_address, 50)?;
+
+// Called by the spender: moves tokens within the approved allowance
+token.transfer_from(owner_address, recipient_address, 50)?;
+
+// Only the owner can mint new tokens
+token.mint(recipient_address, 1000)?;
+"#.to_string(),
+        }
+    }
+
+    /// Create multisig template
+    fn create_multisig_template() -> ContractTemplate {
+        ContractTemplate {
+            name: "multisig".to_string(),
+            description: "Multi-signature wallet that executes transactions once enough owners confirm them".to_string(),
+            category: "governance".to_string(),
+            source_code: include_str!("../contracts/examples/multisig/src/lib.rs").to_string(),
+            dependencies: vec![
+                "gillean-contract = \"0.1.0\"".to_string(),
+                "serde = { version = \"1.0\", features = [\"derive\"] }".to_string(),
+                "serde_json = \"1.0\"".to_string(),
+            ],
+            parameters: vec![
+                TemplateParameter {
+                    name: "owners".to_string(),
+                    description: "Accounts allowed to submit, confirm and execute transactions".to_string(),
+                    param_type: "Vec<Address>".to_string(),
+                    default_value: None,
+                    required: true,
+                },
+                TemplateParameter {
+                    name: "threshold".to_string(),
+                    description: "Number of owner confirmations required to execute a transaction".to_string(),
+                    param_type: "u32".to_string(),
+                    default_value: Some("2".to_string()),
+                    required: true,
+                }
+            ],
+            usage_example: r#"
+// Deploy with three owners, two of whom must confirm each transaction
+let mut wallet = Multisig::new(vec![alice, bob, carol], 2)?;
+
+// Alice submits a transfer, which also records her confirmation
+let tx_id = wallet.submit(recipient_address, 500, Vec::new())?;
 
-// Transfer from approved address
-token.transfer_from(owner_address, recipient_address, 50);
+// Bob confirms, reaching the threshold
+wallet.confirm(tx_id)?;
+
+// Any owner can now execute it
+wallet.execute(tx_id)?;
 "#.to_string(),
         }
     }
@@ -836,88 +954,114 @@ token.transfer_from(owner_address, recipient_address, 50);
         fs::create_dir_all(temp_path.join("src"))?;
         fs::copy(source_path, &dest_path)?;
         
-        // Compile to WASM
-        let output = Command::new("cargo")
-            .args(["build", "--target", &self.config.wasm_target, "--release"])
-            .current_dir(temp_path)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()?;
+        // Compile to WASM, killing the build if it overruns the configured timeout
+        let mut command = Command::new("cargo");
+        command
+            .args(["build", "--target", &self.config.wasm_target, "--release", "--target-dir"])
+            .arg(temp_path.join("target"))
+            .current_dir(temp_path);
+        let output = run_with_timeout(command, Duration::from_secs(self.config.compilation_timeout))?;
         
         let duration = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() - start_time;
+        let warnings: Vec<String> = output.stderr
+            .lines()
+            .filter(|line| line.starts_with("warning"))
+            .map(|line| line.to_string())
+            .collect();
         
-        if output.status.success() {
-            // Read compiled WASM
-            let wasm_path = temp_path
-                .join("target")
-                .join(&self.config.wasm_target)
-                .join("release")
-                .join(format!("{}.wasm", contract_name));
+        if output.timed_out {
+            let mut errors = vec![format!("Compilation timed out after {} seconds", self.config.compilation_timeout)];
+            errors.extend(output.stderr.lines().map(|s| s.to_string()));
             
-            if wasm_path.exists() {
-                let wasm_bytecode = fs::read(&wasm_path)?;
-                let size = wasm_bytecode.len();
-                
-                if size > self.config.max_contract_size {
-                    return Ok(CompilationResult {
-                        success: false,
-                        contract: None,
-                        errors: vec![format!("Contract size {} exceeds maximum {}", size, self.config.max_contract_size)],
-                        warnings: vec![],
-                        duration: duration as u64,
-                    });
-                }
-                
-                let contract = CompiledContract {
-                    name: contract_name.to_string(),
-                    version: "1.0.0".to_string(),
-                    wasm_bytecode,
-                    metadata: self.extract_contract_metadata(source_file)?,
-                    compiled_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
-                    compilation_duration: duration as u64,
-                    size,
-                    warnings: vec![],
-                    errors: vec![],
-                };
-                
-                // Cache the compiled contract
-                {
-                    let mut compiled = self.compiled_contracts.write().unwrap();
-                    compiled.insert(contract_name.to_string(), contract.clone());
-                }
-                
-                Ok(CompilationResult {
-                    success: true,
-                    contract: Some(contract),
-                    errors: vec![],
-                    warnings: vec![],
-                    duration: duration as u64,
-                })
-            } else {
-                            Ok(CompilationResult {
+            return Ok(CompilationResult {
                 success: false,
                 contract: None,
-                errors: vec!["WASM file not found after compilation".to_string()],
-                warnings: vec![],
+                errors,
+                warnings,
                 duration: duration as u64,
-            })
-            }
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let errors: Vec<String> = stderr.lines().map(|s| s.to_string()).collect();
+            });
+        }
+        
+        if !output.success {
+            let errors: Vec<String> = output.stdout
+                .lines()
+                .chain(output.stderr.lines())
+                .map(|s| s.to_string())
+                .collect();
             
-            Ok(CompilationResult {
+            return Ok(CompilationResult {
                 success: false,
                 contract: None,
                 errors,
-                warnings: vec![],
+                warnings,
                 duration: duration as u64,
-            })
+            });
         }
+        
+        // Cargo replaces dashes with underscores in library artifact names
+        let wasm_path = temp_path
+            .join("target")
+            .join(&self.config.wasm_target)
+            .join("release")
+            .join(format!("{}.wasm", contract_name.replace('-', "_")));
+        
+        if !wasm_path.exists() {
+            return Ok(CompilationResult {
+                success: false,
+                contract: None,
+                errors: vec!["WASM file not found after compilation".to_string()],
+                warnings,
+                duration: duration as u64,
+            });
+        }
+        
+        let wasm_bytecode = fs::read(&wasm_path)?;
+        let size = wasm_bytecode.len();
+        
+        if size > self.config.max_contract_size {
+            return Ok(CompilationResult {
+                success: false,
+                contract: None,
+                errors: vec![format!("Contract size {} exceeds maximum {}", size, self.config.max_contract_size)],
+                warnings,
+                duration: duration as u64,
+            });
+        }
+        
+        let contract = CompiledContract {
+            name: contract_name.to_string(),
+            version: "1.0.0".to_string(),
+            wasm_bytecode,
+            metadata: self.extract_contract_metadata(source_file)?,
+            abi: ContractAbi::from_source(&fs::read_to_string(source_file)?)?,
+            compiled_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            compilation_duration: duration as u64,
+            size,
+            warnings: warnings.clone(),
+            errors: vec![],
+        };
+        
+        // Cache the compiled contract
+        {
+            let mut compiled = self.compiled_contracts.write().unwrap();
+            compiled.insert(contract_name.to_string(), contract.clone());
+        }
+        
+        Ok(CompilationResult {
+            success: true,
+            contract: Some(contract),
+            errors: vec![],
+            warnings,
+            duration: duration as u64,
+        })
     }
 
     /// Create Cargo.toml for contract compilation
+    /// 
+    /// The crate is built in a temporary directory, so `gillean-contract` is referenced
+    /// by absolute path.
     fn create_contract_cargo_toml(&self, contract_name: &str) -> Result<String> {
+        let contract_crate_path = std::path::absolute(&self.config.contract_crate_path)?;
         let cargo_toml = format!(
             r#"[package]
 name = "{}"
@@ -928,10 +1072,14 @@ edition = "2021"
 crate-type = ["cdylib"]
 
 [dependencies]
-gillean-contract = "0.1.0"
+gillean-contract = {{ path = {:?} }}
 serde = {{ version = "1.0", features = ["derive"] }}
+serde_json = "1.0"
+
+[workspace]
 "#,
-            contract_name
+            contract_name,
+            contract_crate_path.display().to_string()
         );
         
         Ok(cargo_toml)
@@ -988,6 +1136,46 @@ serde = {{ version = "1.0", features = ["derive"] }}
         Ok(vec![])
     }
 
+    /// Encode a call to a compiled contract's method
+    /// 
+    /// The arguments are checked against the contract's ABI before encoding.
+    /// The payload is the JSON object `{"method": ..., "args": [...]}`.
+    /// 
+    /// # Arguments
+    /// * `contract_name` - Name of the compiled contract
+    /// * `method` - Method to call
+    /// * `args` - Method arguments, in declaration order
+    /// 
+    /// # Returns
+    /// * `Result<Vec<u8>>` - The encoded call payload
+    pub fn encode_call(&self, contract_name: &str, method: &str, args: &[serde_json::Value]) -> Result<Vec<u8>> {
+        let compiled = self.compiled_contracts.read().unwrap();
+        let contract = compiled.get(contract_name).ok_or_else(|| {
+            BlockchainError::InvalidTransaction(format!("Contract {} not found", contract_name))
+        })?;
+        let abi_method = contract.abi.method(method).ok_or_else(|| {
+            BlockchainError::ContractValidationFailed(format!("Contract {} has no method {}", contract_name, method))
+        })?;
+
+        if args.len() != abi_method.params.len() {
+            return Err(BlockchainError::ContractValidationFailed(format!(
+                "Method {} expects {} arguments, got {}", method, abi_method.params.len(), args.len()
+            )));
+        }
+        for (param, arg) in abi_method.params.iter().zip(args) {
+            if !abi_value_matches(&param.param_type, arg) {
+                return Err(BlockchainError::ContractValidationFailed(format!(
+                    "Argument {} of {} must be {}, got {}", param.name, method, param.param_type, arg
+                )));
+            }
+        }
+
+        Ok(serde_json::to_vec(&serde_json::json!({
+            "method": method,
+            "args": args,
+        }))?)
+    }
+
     /// Test a compiled contract
     pub fn test_contract(&mut self, contract_name: &str, test_data: &str) -> Result<TestResult> {
         let start_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
@@ -1032,6 +1220,78 @@ serde = {{ version = "1.0", features = ["derive"] }}
         Ok(result)
     }
 
+    /// Fuzz a compiled contract method with random, type-valid arguments
+    /// 
+    /// Each iteration instantiates the contract's WASM module afresh, calls the method with
+    /// arguments generated from its ABI and records panics, out-of-gas and trap results.
+    /// For every distinct crash class the failing input is shrunk towards zero before it is
+    /// reported.
+    /// 
+    /// # Arguments
+    /// * `contract_name` - Name of a compiled contract
+    /// * `method` - Exported method to fuzz
+    /// * `iterations` - Number of random inputs to try
+    /// 
+    /// # Returns
+    /// * `Result<FuzzReport>` - The failures found, or an error if the method cannot be fuzzed
+    pub fn fuzz_contract(&mut self, contract_name: &str, method: &str, iterations: u64) -> Result<FuzzReport> {
+        info!("Fuzzing contract method: {}::{} ({} iterations)", contract_name, method, iterations);
+        
+        let contract = {
+            let compiled = self.compiled_contracts.read().unwrap();
+            compiled.get(contract_name).cloned()
+        };
+        let contract = contract.ok_or_else(|| {
+            BlockchainError::InvalidTransaction(format!("Contract {} not found", contract_name))
+        })?;
+        let abi_method = contract.abi.method(method).cloned().ok_or_else(|| {
+            BlockchainError::ContractValidationFailed(format!("Method {} not found in {} ABI", method, contract_name))
+        })?;
+        for param in &abi_method.params {
+            if fuzz_val_type(&param.param_type).is_none() {
+                return Err(BlockchainError::ContractValidationFailed(format!(
+                    "Parameter {} of type {} cannot be fuzzed", param.name, param.param_type
+                )));
+            }
+        }
+        
+        let harness = FuzzHarness::new(&contract.wasm_bytecode, method, self.config.test_gas_limit)?;
+        let mut rng = rand::thread_rng();
+        let mut successes = 0;
+        let mut failures: Vec<FuzzFailure> = Vec::new();
+        
+        for _ in 0..iterations {
+            let input: Vec<serde_json::Value> = abi_method.params
+                .iter()
+                .map(|param| random_fuzz_value(&param.param_type, &mut rng))
+                .collect();
+            
+            let (kind, message) = match harness.run(&abi_method.params, &input)? {
+                None => {
+                    successes += 1;
+                    continue;
+                }
+                Some(failure) => failure,
+            };
+            
+            if let Some(existing) = failures.iter_mut().find(|f| f.kind == kind && f.message == message) {
+                existing.occurrences += 1;
+                continue;
+            }
+            
+            let minimal_input = harness.shrink(&abi_method.params, input, kind, &message)?;
+            failures.push(FuzzFailure { kind, message, minimal_input, occurrences: 1 });
+        }
+        
+        Ok(FuzzReport {
+            contract_name: contract_name.to_string(),
+            method: method.to_string(),
+            iterations,
+            successes,
+            failures,
+        })
+    }
+
     /// Create test environment for contract testing
     fn create_test_environment(&self, _contract: &CompiledContract) -> Result<TestEnvironment> {
         // Create a mock blockchain environment for testing
@@ -1128,6 +1388,254 @@ struct ContractTestResult {
     errors: Vec<String>,
 }
 
+/// Executes a single exported contract method for the fuzzer
+struct FuzzHarness {
+    engine: wasmtime::Engine,
+    module: wasmtime::Module,
+    method: String,
+    gas_limit: u64,
+}
+
+impl FuzzHarness {
+    /// Compile the contract module with fuel metering enabled
+    fn new(wasm_bytecode: &[u8], method: &str, gas_limit: u64) -> Result<Self> {
+        let mut config = wasmtime::Config::new();
+        config.consume_fuel(true);
+        let engine = wasmtime::Engine::new(&config)
+            .map_err(|e| BlockchainError::ContractExecutionError(e.to_string()))?;
+        let module = wasmtime::Module::new(&engine, wasm_bytecode)
+            .map_err(|e| BlockchainError::ContractValidationFailed(format!("Invalid WASM module: {}", e)))?;
+        
+        Ok(Self { engine, module, method: method.to_string(), gas_limit })
+    }
+
+    /// Call the method with `input`, returning the failure class and message if it did not return normally
+    fn run(&self, params: &[AbiParam], input: &[serde_json::Value]) -> Result<Option<(FuzzFailureKind, String)>> {
+        let mut store = wasmtime::Store::new(&self.engine, ());
+        store.add_fuel(self.gas_limit)
+            .map_err(|e| BlockchainError::ContractExecutionError(e.to_string()))?;
+        let instance = wasmtime::Instance::new(&mut store, &self.module, &[])
+            .map_err(|e| BlockchainError::ContractExecutionError(format!("Failed to instantiate contract: {}", e)))?;
+        let func = instance.get_func(&mut store, &self.method).ok_or_else(|| {
+            BlockchainError::ContractValidationFailed(format!("Method {} is not exported by the contract", self.method))
+        })?;
+        
+        let args: Vec<wasmtime::Val> = params
+            .iter()
+            .zip(input)
+            .map(|(param, value)| fuzz_wasm_value(&param.param_type, value))
+            .collect();
+        let mut results = vec![wasmtime::Val::I32(0); func.ty(&store).results().len()];
+        
+        match func.call(&mut store, &args, &mut results) {
+            Ok(()) => Ok(None),
+            Err(e) => {
+                let kind = match e.downcast_ref::<wasmtime::Trap>() {
+                    Some(wasmtime::Trap::OutOfFuel) => FuzzFailureKind::OutOfGas,
+                    Some(wasmtime::Trap::UnreachableCodeReached) => FuzzFailureKind::Panic,
+                    Some(_) => FuzzFailureKind::Trap,
+                    None => return Err(BlockchainError::ContractExecutionError(e.to_string())),
+                };
+                Ok(Some((kind, e.root_cause().to_string())))
+            }
+        }
+    }
+
+    /// Shrink each numeric argument towards zero while the same failure still reproduces
+    fn shrink(
+        &self,
+        params: &[AbiParam],
+        mut input: Vec<serde_json::Value>,
+        kind: FuzzFailureKind,
+        message: &str,
+    ) -> Result<Vec<serde_json::Value>> {
+        let reproduces = |candidate: &[serde_json::Value]| -> Result<bool> {
+            Ok(self.run(params, candidate)?.is_some_and(|(k, m)| k == kind && m == message))
+        };
+        
+        for index in 0..input.len() {
+            if let Some(magnitude) = input[index].as_u64().map(u128::from)
+                .or_else(|| input[index].as_i64().map(|v| v.unsigned_abs() as u128))
+            {
+                let negative = input[index].as_i64().is_some_and(|v| v < 0);
+                let with_magnitude = |m: u128| {
+                    if negative {
+                        serde_json::json!(-(m as i128) as i64)
+                    } else {
+                        serde_json::json!(m as u64)
+                    }
+                };
+                
+                // Binary search for the smallest magnitude that still fails
+                let (mut low, mut high) = (0u128, magnitude);
+                while low < high {
+                    let mid = low + (high - low) / 2;
+                    let mut candidate = input.clone();
+                    candidate[index] = with_magnitude(mid);
+                    if reproduces(&candidate)? {
+                        high = mid;
+                    } else {
+                        low = mid + 1;
+                    }
+                }
+                input[index] = with_magnitude(high);
+            } else {
+                let zero = match input[index] {
+                    serde_json::Value::Bool(_) => serde_json::json!(false),
+                    _ => serde_json::json!(0.0),
+                };
+                let mut candidate = input.clone();
+                candidate[index] = zero;
+                if reproduces(&candidate)? {
+                    input = candidate;
+                }
+            }
+        }
+        
+        Ok(input)
+    }
+}
+
+/// WASM value type used to pass an ABI parameter, if the fuzzer supports it
+fn fuzz_val_type(param_type: &str) -> Option<wasmtime::ValType> {
+    match param_type.trim() {
+        "bool" | "u8" | "u16" | "u32" | "i8" | "i16" | "i32" => Some(wasmtime::ValType::I32),
+        "u64" | "i64" | "usize" | "isize" => Some(wasmtime::ValType::I64),
+        "f32" => Some(wasmtime::ValType::F32),
+        "f64" => Some(wasmtime::ValType::F64),
+        _ => None,
+    }
+}
+
+/// Generate a random argument of the given ABI type, favouring boundary values
+fn random_fuzz_value(param_type: &str, rng: &mut impl rand::Rng) -> serde_json::Value {
+    let boundary = rng.gen_bool(0.25);
+    match param_type.trim() {
+        "bool" => serde_json::json!(rng.gen::<bool>()),
+        "u8" => serde_json::json!(if boundary { *[0, 1, u8::MAX].get(rng.gen_range(0..3)).unwrap() } else { rng.gen::<u8>() }),
+        "u16" => serde_json::json!(if boundary { *[0, 1, u16::MAX].get(rng.gen_range(0..3)).unwrap() } else { rng.gen::<u16>() }),
+        "u32" => serde_json::json!(if boundary { *[0, 1, u32::MAX].get(rng.gen_range(0..3)).unwrap() } else { rng.gen::<u32>() }),
+        "u64" | "usize" => serde_json::json!(if boundary { *[0, 1, u64::MAX].get(rng.gen_range(0..3)).unwrap() } else { rng.gen::<u64>() }),
+        "i8" => serde_json::json!(if boundary { *[0, -1, i8::MIN, i8::MAX].get(rng.gen_range(0..4)).unwrap() } else { rng.gen::<i8>() }),
+        "i16" => serde_json::json!(if boundary { *[0, -1, i16::MIN, i16::MAX].get(rng.gen_range(0..4)).unwrap() } else { rng.gen::<i16>() }),
+        "i32" => serde_json::json!(if boundary { *[0, -1, i32::MIN, i32::MAX].get(rng.gen_range(0..4)).unwrap() } else { rng.gen::<i32>() }),
+        "i64" | "isize" => serde_json::json!(if boundary { *[0, -1, i64::MIN, i64::MAX].get(rng.gen_range(0..4)).unwrap() } else { rng.gen::<i64>() }),
+        "f32" => serde_json::json!(if boundary { 0.0 } else { rng.gen_range(-1e6f32..1e6f32) as f64 }),
+        _ => serde_json::json!(if boundary { 0.0 } else { rng.gen_range(-1e12f64..1e12f64) }),
+    }
+}
+
+/// Convert a fuzz argument into the WASM value passed to the contract
+fn fuzz_wasm_value(param_type: &str, value: &serde_json::Value) -> wasmtime::Val {
+    let as_i64 = value.as_i64().or_else(|| value.as_u64().map(|v| v as i64)).unwrap_or(0);
+    match fuzz_val_type(param_type) {
+        Some(wasmtime::ValType::I64) => wasmtime::Val::I64(as_i64),
+        Some(wasmtime::ValType::F32) => wasmtime::Val::F32((value.as_f64().unwrap_or(0.0) as f32).to_bits()),
+        Some(wasmtime::ValType::F64) => wasmtime::Val::F64(value.as_f64().unwrap_or(0.0).to_bits()),
+        _ => match value {
+            serde_json::Value::Bool(flag) => wasmtime::Val::I32(*flag as i32),
+            _ => wasmtime::Val::I32(as_i64 as i32),
+        },
+    }
+}
+
+/// Captured output of a child process run under a timeout
+struct CommandOutput {
+    /// Whether the process exited successfully
+    success: bool,
+    /// Whether the process was killed for overrunning its timeout
+    timed_out: bool,
+    /// Captured standard output
+    stdout: String,
+    /// Captured standard error
+    stderr: String,
+}
+
+/// Run a command to completion, killing it if it exceeds the timeout
+/// 
+/// On Unix the command runs in its own process group and the whole group is killed,
+/// so the rustc processes cargo spawns do not outlive the timeout.
+/// 
+/// # Arguments
+/// * `command` - The command to run
+/// * `timeout` - Maximum time the command may run for
+/// 
+/// # Returns
+/// * `Result<CommandOutput>` - Exit status and captured output
+fn run_with_timeout(mut command: Command, timeout: Duration) -> Result<CommandOutput> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    
+    // Drain the pipes on separate threads so a chatty build cannot block on a full buffer
+    let stdout_reader = child.stdout.take().map(|mut pipe| thread::spawn(move || {
+        let mut buffer = String::new();
+        let _ = pipe.read_to_string(&mut buffer);
+        buffer
+    }));
+    let stderr_reader = child.stderr.take().map(|mut pipe| thread::spawn(move || {
+        let mut buffer = String::new();
+        let _ = pipe.read_to_string(&mut buffer);
+        buffer
+    }));
+    
+    let deadline = Instant::now() + timeout;
+    let (success, timed_out) = loop {
+        if let Some(status) = child.try_wait()? {
+            break (status.success(), false);
+        }
+        if Instant::now() >= deadline {
+            warn!("Killing process after {:?} timeout", timeout);
+            kill_process_tree(&mut child)?;
+            child.wait()?;
+            break (false, true);
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+    
+    // Processes spawned by a killed child may still hold the pipes open, so only wait
+    // for the readers when the child exited on its own
+    let collect = |reader: Option<thread::JoinHandle<String>>| {
+        if timed_out {
+            String::new()
+        } else {
+            reader.and_then(|reader| reader.join().ok()).unwrap_or_default()
+        }
+    };
+    let stdout = collect(stdout_reader);
+    let stderr = collect(stderr_reader);
+    
+    Ok(CommandOutput { success, timed_out, stdout, stderr })
+}
+
+/// Kill a child process started by `run_with_timeout` along with its descendants
+#[cfg(unix)]
+fn kill_process_tree(child: &mut std::process::Child) -> Result<()> {
+    // The child leads its own process group, whose ID is the child's PID
+    let pgid = child.id() as libc::pid_t;
+    // SAFETY: kill(2) takes no pointers, so the call cannot violate memory safety.
+    // The child has not been waited on yet, so its PID, and with it the group ID,
+    // cannot have been reused by an unrelated process group.
+    if unsafe { libc::kill(-pgid, libc::SIGKILL) } == 0 {
+        Ok(())
+    } else {
+        // The group may already be gone; fall back to the child alone
+        Ok(child.kill()?)
+    }
+}
+
+/// Kill a child process started by `run_with_timeout`
+#[cfg(not(unix))]
+fn kill_process_tree(child: &mut std::process::Child) -> Result<()> {
+    Ok(child.kill()?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1144,6 +1652,7 @@ mod tests {
             test_gas_limit: 1_000_000,
             compilation_timeout: 60,
             test_timeout: 30,
+            contract_crate_path: "contracts/gillean-contract".to_string(),
         };
         
         let toolkit = ContractToolkit::new(config).unwrap();
@@ -1165,6 +1674,7 @@ mod tests {
             test_gas_limit: 1_000_000,
             compilation_timeout: 60,
             test_timeout: 30,
+            contract_crate_path: "contracts/gillean-contract".to_string(),
         };
         
         let toolkit = ContractToolkit::new(config).unwrap();
@@ -1172,5 +1682,203 @@ mod tests {
         assert!(toolkit.templates.contains_key("voting"));
         assert!(toolkit.templates.contains_key("escrow"));
         assert!(toolkit.templates.contains_key("token"));
+        assert!(toolkit.templates.contains_key("multisig"));
+    }
+
+    #[test]
+    fn test_token_template() {
+        let template = ContractToolkit::create_token_template();
+        let parameters: Vec<&str> = template.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(parameters, vec!["name", "symbol", "initial_supply"]);
+
+        let abi = ContractAbi::from_source(&template.source_code).unwrap();
+        for method in ["transfer", "approve", "transfer_from", "total_supply", "mint"] {
+            assert!(abi.method(method).is_some(), "missing method {}", method);
+        }
+        assert!(template.source_code.contains("emit_event(\"Transfer\""));
+        assert!(template.source_code.contains("emit_event(\"Approval\""));
+    }
+
+    #[test]
+    fn test_multisig_template() {
+        let template = ContractToolkit::create_multisig_template();
+        let parameters: Vec<&str> = template.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(parameters, vec!["owners", "threshold"]);
+
+        let abi = ContractAbi::from_source(&template.source_code).unwrap();
+        for method in ["submit", "confirm", "execute", "get_confirmation_count"] {
+            assert!(abi.method(method).is_some(), "missing method {}", method);
+        }
+        for event in ["Submission", "Confirmation", "Execution"] {
+            assert!(template.source_code.contains(&format!("emit_event(\"{}\"", event)));
+        }
+    }
+
+    fn toolkit_with_counter() -> ContractToolkit {
+        let config = ContractToolkitConfig {
+            rust_toolchain: "stable".to_string(),
+            wasm_target: "wasm32-unknown-unknown".to_string(),
+            templates_dir: "templates".to_string(),
+            compiled_dir: "compiled".to_string(),
+            test_results_dir: "test_results".to_string(),
+            max_contract_size: 1024 * 1024,
+            test_gas_limit: 1_000_000,
+            compilation_timeout: 60,
+            test_timeout: 30,
+            contract_crate_path: "contracts/gillean-contract".to_string(),
+        };
+        let toolkit = ContractToolkit::new(config).unwrap();
+
+        let source = fs::read_to_string("contracts/examples/counter/src/lib.rs").unwrap();
+        let contract = CompiledContract {
+            name: "counter".to_string(),
+            version: "1.0.0".to_string(),
+            wasm_bytecode: vec![0; 16],
+            metadata: toolkit.extract_contract_metadata("contracts/examples/counter/src/lib.rs").unwrap(),
+            abi: ContractAbi::from_source(&source).unwrap(),
+            compiled_at: 0,
+            compilation_duration: 0,
+            size: 16,
+            warnings: vec![],
+            errors: vec![],
+        };
+        toolkit.compiled_contracts.write().unwrap().insert("counter".to_string(), contract);
+        toolkit
+    }
+
+    #[test]
+    fn test_abi_from_source() {
+        let abi = ContractAbi::from_source(
+            "impl Token {\n    pub fn transfer(&mut self, to: Address, amount: u64) -> Result<bool, String> {\n    }\n    pub fn pause(&mut self) {\n    }\n}",
+        ).unwrap();
+
+        let transfer = abi.method("transfer").unwrap();
+        let params: Vec<(&str, &str)> = transfer.params.iter().map(|p| (p.name.as_str(), p.param_type.as_str())).collect();
+        assert_eq!(params, vec![("to", "Address"), ("amount", "u64")]);
+        assert_eq!(transfer.returns.as_deref(), Some("Result<bool, String>"));
+        assert!(abi.method("pause").unwrap().params.is_empty());
+        assert_eq!(abi.method("pause").unwrap().returns, None);
+        assert!(abi.to_json().unwrap().contains("\"transfer\""));
+    }
+
+    #[test]
+    fn test_encode_call() {
+        let toolkit = toolkit_with_counter();
+
+        let payload = toolkit.encode_call("counter", "increment_by", &[serde_json::json!(5)]).unwrap();
+        let decoded: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(decoded, serde_json::json!({"method": "increment_by", "args": [5]}));
+
+        assert!(toolkit.encode_call("counter", "increment_by", &[serde_json::json!("5")]).is_err());
+        assert!(toolkit.encode_call("counter", "increment_by", &[serde_json::json!(-5)]).is_err());
+        assert!(toolkit.encode_call("counter", "increment_by", &[]).is_err());
+        assert!(toolkit.encode_call("counter", "no_such_method", &[]).is_err());
+        assert!(toolkit.encode_call("missing", "increment_by", &[serde_json::json!(5)]).is_err());
+    }
+
+    fn wasm_target_installed(target: &str) -> bool {
+        Command::new("rustc")
+            .args(["--print", "sysroot"])
+            .output()
+            .ok()
+            .map(|output| {
+                let sysroot = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                Path::new(&sysroot).join("lib").join("rustlib").join(target).exists()
+            })
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn test_compile_counter_example() {
+        let mut toolkit = toolkit_with_counter();
+        if !wasm_target_installed(&toolkit.config.wasm_target) {
+            println!("Skipping: {} target is not installed", toolkit.config.wasm_target);
+            return;
+        }
+        toolkit.config.compilation_timeout = 600;
+
+        let result = toolkit.compile_contract("contracts/examples/counter/src/lib.rs", "counter-contract").unwrap();
+        assert!(result.success, "compilation failed: {:?}", result.errors);
+        let contract = result.contract.unwrap();
+        assert!(!contract.wasm_bytecode.is_empty());
+        assert_eq!(&contract.wasm_bytecode[..4], b"\0asm");
+    }
+
+    #[test]
+    fn test_compile_contract_timeout() {
+        let mut toolkit = toolkit_with_counter();
+        toolkit.config.compilation_timeout = 0;
+
+        let result = toolkit.compile_contract("contracts/examples/counter/src/lib.rs", "counter-contract").unwrap();
+        assert!(!result.success);
+        assert!(result.contract.is_none());
+        assert!(result.errors[0].contains("timed out"));
+    }
+
+    #[test]
+    fn test_contract_cargo_toml_uses_path_dependency() {
+        let toolkit = toolkit_with_counter();
+        let cargo_toml: toml::Value = toml::from_str(&toolkit.create_contract_cargo_toml("counter-contract").unwrap()).unwrap();
+
+        let dependency = &cargo_toml["dependencies"]["gillean-contract"];
+        let path = Path::new(dependency["path"].as_str().unwrap());
+        assert!(path.is_absolute());
+        assert!(path.ends_with("contracts/gillean-contract"));
+        assert!(dependency.get("version").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_with_timeout_kills_process_group() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let pid_file = temp_dir.path().join("grandchild.pid");
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(format!("sleep 30 & echo $! > {}; wait", pid_file.display()));
+
+        let output = run_with_timeout(command, Duration::from_millis(500)).unwrap();
+        assert!(output.timed_out);
+
+        let pid: libc::pid_t = fs::read_to_string(&pid_file).unwrap().trim().parse().unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        // Signal 0 only checks whether the process still exists
+        while unsafe { libc::kill(pid, 0) } == 0 {
+            assert!(Instant::now() < deadline, "grandchild {} survived the timeout", pid);
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn test_fuzz_contract_finds_overflow() {
+        let mut toolkit = toolkit_with_counter();
+        // `add_bonus` panics once `points + 1000` would overflow a u32
+        let wasm_bytecode = wat::parse_str(r#"
+            (module
+              (func (export "add_bonus") (param $points i32) (result i32)
+                local.get $points
+                i32.const -1001
+                i32.gt_u
+                if
+                  unreachable
+                end
+                local.get $points
+                i32.const 1000
+                i32.add))
+        "#).unwrap();
+        let source = "impl Bonus {\n    pub fn add_bonus(&mut self, points: u32) -> u32 {\n    }\n}";
+        let mut contract = toolkit.compiled_contracts.read().unwrap().get("counter").cloned().unwrap();
+        contract.name = "bonus".to_string();
+        contract.wasm_bytecode = wasm_bytecode;
+        contract.abi = ContractAbi::from_source(source).unwrap();
+        toolkit.compiled_contracts.write().unwrap().insert("bonus".to_string(), contract);
+
+        let report = toolkit.fuzz_contract("bonus", "add_bonus", 200).unwrap();
+        assert!(report.has_failures());
+        assert_eq!(report.failures.len(), 1);
+        let failure = &report.failures[0];
+        assert_eq!(failure.kind, FuzzFailureKind::Panic);
+        assert_eq!(failure.minimal_input, vec![serde_json::json!(u32::MAX - 999)]);
+        assert_eq!(report.successes + failure.occurrences, 200);
+
+        assert!(toolkit.fuzz_contract("bonus", "missing", 10).is_err());
     }
 }
@@ -439,16 +439,94 @@ pub fn generate_test_keypair() -> KeyPair {
     KeyPair::generate().expect("Failed to generate test keypair")
 }
 
+/// Human-readable prefix of checksummed addresses
+pub const ADDRESS_PREFIX: &str = "gil";
+
+/// Number of hex characters in an address body
+const ADDRESS_BODY_LEN: usize = 40;
+
+/// Encoding used when deriving an address from a public key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AddressFormat {
+    /// `gil` prefix followed by hex whose letter case encodes a checksum (EIP-55 style)
+    #[default]
+    Checksummed,
+    /// `GIL` prefix followed by lowercase hex, without a checksum
+    Legacy,
+}
+
 /// Create a wallet address from a public key
 /// 
 /// # Arguments
 /// * `public_key` - The public key
 /// 
 /// # Returns
-/// * `String` - The wallet address
+/// * `String` - The checksummed wallet address
 pub fn create_address(public_key: &PublicKey) -> String {
+    create_address_with_format(public_key, AddressFormat::default())
+}
+
+/// Create a wallet address from a public key in the given format
+/// 
+/// # Arguments
+/// * `public_key` - The public key
+/// * `format` - Address encoding to use
+/// 
+/// # Returns
+/// * `String` - The wallet address
+pub fn create_address_with_format(public_key: &PublicKey, format: AddressFormat) -> String {
     let hash = utils::calculate_hash(public_key.to_hex());
-    format!("GIL{}", &hash[..40]) // GIL + first 40 chars of hash
+    let body = &hash[..ADDRESS_BODY_LEN];
+    match format {
+        AddressFormat::Checksummed => format!("{}{}", ADDRESS_PREFIX, checksum_case(body)),
+        AddressFormat::Legacy => format!("GIL{}", body),
+    }
+}
+
+/// Validate a checksummed address
+/// 
+/// # Arguments
+/// * `address` - The address to check
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if the prefix, length and checksum are all correct
+pub fn validate_address(address: &str) -> Result<()> {
+    let body = address.strip_prefix(ADDRESS_PREFIX).ok_or_else(|| {
+        BlockchainError::InvalidInput(format!("Address must start with '{}'", ADDRESS_PREFIX))
+    })?;
+    if body.len() != ADDRESS_BODY_LEN || !body.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(BlockchainError::InvalidInput(format!(
+            "Address body must be {} hex characters", ADDRESS_BODY_LEN
+        )));
+    }
+    if checksum_case(&body.to_ascii_lowercase()) != body {
+        return Err(BlockchainError::InvalidInput(format!("Address checksum mismatch: {}", address)));
+    }
+    Ok(())
+}
+
+/// Check whether an address claims the checksummed format and so must pass validation
+/// 
+/// Any address with the lower-case `gil` prefix claims it, whatever its length, so
+/// truncated or padded addresses are rejected rather than taken for account names.
+/// Legacy `GIL` addresses and names without the prefix carry no checksum and are not covered.
+pub fn is_checksummed_format(address: &str) -> bool {
+    address.starts_with(ADDRESS_PREFIX)
+}
+
+/// Upper-case each hex letter whose matching nibble of SHA-256(body) is 8 or more
+fn checksum_case(body: &str) -> String {
+    let digest = utils::calculate_hash(body);
+    body.chars()
+        .zip(digest.chars())
+        .map(|(c, d)| {
+            if c.is_ascii_alphabetic() && d.to_digit(16).unwrap_or(0) >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -528,8 +606,38 @@ mod tests {
         let public_key = keypair.public_key();
         let address = create_address(&public_key);
         
-        assert!(address.starts_with("GIL"));
-        assert_eq!(address.len(), 43); // GIL + 40 chars
+        assert!(address.starts_with("gil"));
+        assert_eq!(address.len(), 43); // gil + 40 chars
+        assert!(validate_address(&address).is_ok());
+
+        let legacy = create_address_with_format(&public_key, AddressFormat::Legacy);
+        assert!(legacy.starts_with("GIL"));
+        assert_eq!(legacy[3..], address[3..].to_ascii_lowercase());
+    }
+
+    #[test]
+    fn test_address_checksum_detects_typos() {
+        let keypair = KeyPair::from_private_key_bytes(&[7u8; 32]).unwrap();
+        let address = create_address(&keypair.public_key());
+        assert!(validate_address(&address).is_ok());
+
+        // Change each body character in turn: a different hex digit or a flipped letter case
+        for position in ADDRESS_PREFIX.len()..address.len() {
+            let mut chars: Vec<char> = address.chars().collect();
+            let original = chars[position];
+            chars[position] = if original.is_ascii_alphabetic() {
+                if original.is_ascii_uppercase() { original.to_ascii_lowercase() } else { original.to_ascii_uppercase() }
+            } else if original == '0' { '1' } else { '0' };
+            let altered: String = chars.into_iter().collect();
+            assert!(validate_address(&altered).is_err(), "typo at {} not detected: {}", position, altered);
+        }
+
+        assert!(validate_address(&address[..42]).is_err());
+        assert!(validate_address(&format!("GIL{}", &address[3..])).is_err());
+        assert!(is_checksummed_format(&address));
+        assert!(is_checksummed_format(&address[..42]));
+        assert!(!is_checksummed_format(&format!("GIL{}", &address[3..])));
+        assert!(!is_checksummed_format("alice"));
     }
 
     #[test]
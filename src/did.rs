@@ -25,6 +25,15 @@ pub struct DIDDocument {
     pub service_endpoints: Vec<ServiceEndpoint>,
     pub created: chrono::DateTime<chrono::Utc>,
     pub updated: chrono::DateTime<chrono::Utc>,
+    /// Number of key rotations applied to the document
+    #[serde(default)]
+    pub version: u64,
+    /// Verification methods replaced by key rotations, oldest first
+    #[serde(default)]
+    pub method_history: Vec<VerificationMethod>,
+    /// Whether the DID has been revoked
+    #[serde(default)]
+    pub revoked: bool,
 }
 
 /// Verification method for DID
@@ -96,13 +105,7 @@ impl DecentralizedIdentity {
         let did = self.generate_did_identifier(&public_key);
         
         // Create verification method
-        let verification_method = VerificationMethod {
-            id: format!("{}#keys-1", did),
-            controller: did.clone(),
-            key_type: "Ed25519VerificationKey2020".to_string(),
-            public_key_multibase: self.encode_public_key_multibase(&public_key),
-            public_key_jwk: None,
-        };
+        let verification_method = Self::verification_method(&did, "keys-1", &public_key);
 
         // Create DID document
         let now = chrono::Utc::now();
@@ -116,6 +119,9 @@ impl DecentralizedIdentity {
             service_endpoints: request.service_endpoints,
             created: now,
             updated: now,
+            version: 0,
+            method_history: vec![],
+            revoked: false,
         };
 
         // Store DID document
@@ -181,12 +187,125 @@ impl DecentralizedIdentity {
         let document = self.get_did_document(did).await?
             .ok_or_else(|| BlockchainError::NotFound(format!("DID not found: {}", did)))?;
 
-        // Find verification method
-        let verification_method = document.verification_methods
+        // Find the verification method used for authentication
+        let verification_method = Self::authentication_method(&document)?;
+
+        if document.revoked {
+            return Ok(DIDVerificationResult {
+                is_valid: false,
+                error_message: Some(format!("DID has been revoked: {}", did)),
+                verification_method: Some(verification_method.id.clone()),
+            });
+        }
+
+        let error_message = self.signature_error(verification_method, message, signature)?;
+        Ok(DIDVerificationResult {
+            is_valid: error_message.is_none(),
+            error_message,
+            verification_method: Some(verification_method.id.clone()),
+        })
+    }
+
+    /// Rotate the authentication key of a DID
+    /// 
+    /// The rotation must be signed by the current key over `rotation_message`. The
+    /// replaced verification method is kept in the document's `method_history`.
+    pub async fn rotate_key(
+        &self,
+        did: &str,
+        new_method: VerificationMethod,
+        authorization_sig: &[u8],
+    ) -> Result<(), BlockchainError> {
+        let document = {
+            let mut documents = self.did_documents.write().await;
+            let document = documents.get_mut(did)
+                .ok_or_else(|| BlockchainError::NotFound(format!("DID not found: {}", did)))?;
+
+            if document.revoked {
+                return Err(BlockchainError::InvalidState(format!("DID has been revoked: {}", did)));
+            }
+            if new_method.controller != did {
+                return Err(BlockchainError::InvalidInput(
+                    "New verification method must be controlled by the DID".to_string(),
+                ));
+            }
+            let method_id_used = document.verification_methods.iter()
+                .chain(document.method_history.iter())
+                .any(|vm| vm.id == new_method.id);
+            if method_id_used {
+                return Err(BlockchainError::InvalidInput(
+                    format!("Verification method ID already used: {}", new_method.id),
+                ));
+            }
+            self.decode_public_key_multibase(&new_method.public_key_multibase)?;
+
+            // The current key must authorize the rotation
+            let current_method = Self::authentication_method(document)?.clone();
+            let message = Self::rotation_message(did, document.version, &new_method);
+            if let Some(error) = self.signature_error(&current_method, &message, authorization_sig)? {
+                return Err(BlockchainError::InvalidSignature(format!("Key rotation not authorized: {}", error)));
+            }
+
+            document.method_history.append(&mut document.verification_methods);
+            document.authentication = vec![new_method.id.clone()];
+            document.assertion_method = vec![new_method.id.clone()];
+            document.verification_methods = vec![new_method];
+            document.version += 1;
+            document.updated = chrono::Utc::now();
+            document.clone()
+        };
+
+        // Save to persistent storage
+        self.save_did_document(&document).await?;
+
+        info!("Rotated key for DID {} (version {})", did, document.version);
+        Ok(())
+    }
+
+    /// Message the current key signs to authorize a key rotation
+    pub fn rotation_message(did: &str, version: u64, new_method: &VerificationMethod) -> Vec<u8> {
+        format!("rotate:{}:{}:{}:{}", did, version, new_method.id, new_method.public_key_multibase).into_bytes()
+    }
+
+    /// Message the current key signs to authorize revocation
+    pub fn revocation_message(did: &str, version: u64) -> Vec<u8> {
+        format!("revoke:{}:{}", did, version).into_bytes()
+    }
+
+    /// Build an Ed25519 verification method controlled by a DID
+    pub fn verification_method(did: &str, fragment: &str, public_key: &PublicKey) -> VerificationMethod {
+        VerificationMethod {
+            id: format!("{}#{}", did, fragment),
+            controller: did.to_string(),
+            key_type: "Ed25519VerificationKey2020".to_string(),
+            public_key_multibase: Self::encode_public_key_multibase(public_key),
+            public_key_jwk: None,
+        }
+    }
+
+    /// Get the public key currently authenticating a DID document
+    pub fn authentication_key(&self, document: &DIDDocument) -> Result<PublicKey, BlockchainError> {
+        let method = Self::authentication_method(document)?;
+        self.decode_public_key_multibase(&method.public_key_multibase)
+    }
+
+    /// Find the verification method referenced for authentication
+    fn authentication_method(document: &DIDDocument) -> Result<&VerificationMethod, BlockchainError> {
+        document.verification_methods
             .iter()
-            .find(|vm| vm.id == format!("{}#keys-1", did))
-            .ok_or_else(|| BlockchainError::ValidatorError("No verification method found".to_string()))?;
+            .find(|vm| document.authentication.contains(&vm.id))
+            .ok_or_else(|| BlockchainError::ValidatorError("No verification method found".to_string()))
+    }
 
+    /// Check an Ed25519 signature against a verification method
+    /// 
+    /// Returns `None` if the signature is valid, or the reason it is not.
+    fn signature_error(
+        &self,
+        verification_method: &VerificationMethod,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<Option<String>, BlockchainError> {
         // Decode public key
         let crypto_public_key = self.decode_public_key_multibase(&verification_method.public_key_multibase)?;
         let public_key_bytes: [u8; 32] = crypto_public_key.key.as_slice().try_into()
@@ -195,21 +314,14 @@ impl DecentralizedIdentity {
             .map_err(|_| BlockchainError::InvalidSignature("Invalid public key format".to_string()))?;
 
         // Verify signature
-        let signature_bytes: [u8; 64] = signature.try_into()
-            .map_err(|_| BlockchainError::InvalidSignature("Invalid signature length".to_string()))?;
+        let signature_bytes: [u8; 64] = match signature.try_into() {
+            Ok(bytes) => bytes,
+            Err(_) => return Ok(Some(format!("Invalid signature length: {} bytes", signature.len()))),
+        };
         let signature_obj = ed25519_dalek::Signature::from_bytes(&signature_bytes);
-        match public_key.verify(message, &signature_obj) {
-            Ok(_) => Ok(DIDVerificationResult {
-                is_valid: true,
-                error_message: None,
-                verification_method: Some(verification_method.id.clone()),
-            }),
-            Err(e) => Ok(DIDVerificationResult {
-                is_valid: false,
-                error_message: Some(format!("Signature verification failed: {}", e)),
-                verification_method: Some(verification_method.id.clone()),
-            }),
-        }
+        Ok(public_key.verify(message, &signature_obj)
+            .err()
+            .map(|e| format!("Signature verification failed: {}", e)))
     }
 
     /// Update DID document
@@ -250,15 +362,32 @@ impl DecentralizedIdentity {
     }
 
     /// Revoke DID
-    pub async fn revoke_did(&self, did: &str) -> Result<(), BlockchainError> {
-        // Remove DID document
-        {
+    /// 
+    /// The revocation must be signed by the current key over `revocation_message`.
+    /// The document is kept but all future signature verifications fail.
+    pub async fn revoke_did(&self, did: &str, sig: &[u8]) -> Result<(), BlockchainError> {
+        let document = {
             let mut documents = self.did_documents.write().await;
-            documents.remove(did);
-        }
+            let document = documents.get_mut(did)
+                .ok_or_else(|| BlockchainError::NotFound(format!("DID not found: {}", did)))?;
 
-        // Remove from storage
-        self.storage.delete(&format!("did:{}", did))?;
+            if document.revoked {
+                return Err(BlockchainError::InvalidState(format!("DID already revoked: {}", did)));
+            }
+
+            let current_method = Self::authentication_method(document)?.clone();
+            let message = Self::revocation_message(did, document.version);
+            if let Some(error) = self.signature_error(&current_method, &message, sig)? {
+                return Err(BlockchainError::InvalidSignature(format!("Revocation not authorized: {}", error)));
+            }
+
+            document.revoked = true;
+            document.updated = chrono::Utc::now();
+            document.clone()
+        };
+
+        // Save to persistent storage
+        self.save_did_document(&document).await?;
 
         // Remove wallet links
         {
@@ -307,7 +436,7 @@ impl DecentralizedIdentity {
     }
 
     /// Encode public key in multibase format
-    fn encode_public_key_multibase(&self, public_key: &PublicKey) -> String {
+    fn encode_public_key_multibase(public_key: &PublicKey) -> String {
         let key_bytes = &public_key.key;
         format!("z{}", base64::engine::general_purpose::STANDARD.encode(key_bytes))
     }
@@ -440,4 +569,128 @@ mod tests {
         let result = did_system.verify_did_signature(&did, message, &signature.to_bytes()).await.unwrap();
         assert!(result.is_valid);
     }
+
+    #[tokio::test]
+    async fn test_did_document_persisted_and_verified_after_reload() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Arc::new(BlockchainStorage::new(temp_dir.path().to_str().unwrap()).unwrap());
+        let did_system = DecentralizedIdentity::new(storage.clone()).await.unwrap();
+
+        let request = DIDCreationRequest {
+            controller: None,
+            service_endpoints: vec![],
+        };
+        let (did, keypair) = did_system.create_did(request).await.unwrap();
+
+        // A fresh instance loads the document from storage
+        let reloaded = DecentralizedIdentity::new(storage).await.unwrap();
+        let document = reloaded.get_did_document(&did).await.unwrap().unwrap();
+        assert_eq!(document.verification_methods.len(), 1);
+        assert_eq!(document.authentication, vec![format!("{}#keys-1", did)]);
+
+        let message = b"Hello, DID!";
+        let signature = keypair.sign(message).to_bytes();
+
+        let result = reloaded.verify_did_signature(&did, message, &signature).await.unwrap();
+        assert!(result.is_valid);
+        assert_eq!(result.verification_method, Some(format!("{}#keys-1", did)));
+
+        let result = reloaded.verify_did_signature(&did, b"Hello, DID?", &signature).await.unwrap();
+        assert!(!result.is_valid);
+        assert!(result.error_message.is_some());
+
+        let result = reloaded.verify_did_signature(&did, message, &signature[..10]).await.unwrap();
+        assert!(!result.is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_verify_unknown_did_not_found() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Arc::new(BlockchainStorage::new(temp_dir.path().to_str().unwrap()).unwrap());
+        let did_system = DecentralizedIdentity::new(storage).await.unwrap();
+
+        let result = did_system.verify_did_signature("did:gillean:unknown", b"message", &[0u8; 64]).await;
+        assert!(matches!(result, Err(BlockchainError::NotFound(_))));
+    }
+
+    fn new_method(did: &str, fragment: &str, signing_key: &SigningKey) -> VerificationMethod {
+        VerificationMethod {
+            id: format!("{}#{}", did, fragment),
+            controller: did.to_string(),
+            key_type: "Ed25519VerificationKey2020".to_string(),
+            public_key_multibase: format!(
+                "z{}",
+                base64::engine::general_purpose::STANDARD.encode(signing_key.verifying_key().to_bytes())
+            ),
+            public_key_jwk: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_key_rotation() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Arc::new(BlockchainStorage::new(temp_dir.path().to_str().unwrap()).unwrap());
+        let did_system = DecentralizedIdentity::new(storage).await.unwrap();
+
+        let request = DIDCreationRequest {
+            controller: None,
+            service_endpoints: vec![],
+        };
+        let (did, old_key) = did_system.create_did(request).await.unwrap();
+
+        let new_key = SigningKey::from_bytes(&rand::random::<[u8; 32]>());
+        let method = new_method(&did, "keys-2", &new_key);
+        let message = DecentralizedIdentity::rotation_message(&did, 0, &method);
+
+        // A rotation signed by the wrong key is rejected
+        let wrong_sig = new_key.sign(&message).to_bytes();
+        let result = did_system.rotate_key(&did, method.clone(), &wrong_sig).await;
+        assert!(matches!(result, Err(BlockchainError::InvalidSignature(_))));
+
+        // A rotation signed by the current key is applied
+        let sig = old_key.sign(&message).to_bytes();
+        did_system.rotate_key(&did, method.clone(), &sig).await.unwrap();
+
+        let document = did_system.get_did_document(&did).await.unwrap().unwrap();
+        assert_eq!(document.version, 1);
+        assert_eq!(document.verification_methods[0].id, method.id);
+        assert_eq!(document.method_history.len(), 1);
+        assert_eq!(document.method_history[0].id, format!("{}#keys-1", did));
+
+        // Only the new key verifies now
+        let hello = b"Hello, DID!";
+        let result = did_system.verify_did_signature(&did, hello, &new_key.sign(hello).to_bytes()).await.unwrap();
+        assert!(result.is_valid);
+        let result = did_system.verify_did_signature(&did, hello, &old_key.sign(hello).to_bytes()).await.unwrap();
+        assert!(!result.is_valid);
+
+        // The old authorization cannot be replayed
+        assert!(did_system.rotate_key(&did, method, &sig).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_revocation() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Arc::new(BlockchainStorage::new(temp_dir.path().to_str().unwrap()).unwrap());
+        let did_system = DecentralizedIdentity::new(storage).await.unwrap();
+
+        let request = DIDCreationRequest {
+            controller: None,
+            service_endpoints: vec![],
+        };
+        let (did, keypair) = did_system.create_did(request).await.unwrap();
+        did_system.link_did_to_wallet(&did, "alice").await.unwrap();
+
+        let message = DecentralizedIdentity::revocation_message(&did, 0);
+        let other_key = SigningKey::from_bytes(&rand::random::<[u8; 32]>());
+        assert!(did_system.revoke_did(&did, &other_key.sign(&message).to_bytes()).await.is_err());
+
+        did_system.revoke_did(&did, &keypair.sign(&message).to_bytes()).await.unwrap();
+
+        let hello = b"Hello, DID!";
+        let result = did_system.verify_did_signature(&did, hello, &keypair.sign(hello).to_bytes()).await.unwrap();
+        assert!(!result.is_valid);
+        assert!(result.error_message.unwrap().contains("revoked"));
+        assert_eq!(did_system.get_did_for_wallet("alice").await.unwrap(), None);
+    }
 }
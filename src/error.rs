@@ -37,12 +37,20 @@ pub enum BlockchainError {
     ContractValidationFailed(String),
     /// Smart contract execution error
     ContractExecutionError(String),
+    /// Contract execution failed after consuming gas; its state changes were discarded
+    ContractReverted { error: String, gas_used: u64 },
+    /// Contract storage would exceed its size limit
+    ContractStorageLimitExceeded { size: usize, limit: usize },
+    /// Contract execution ran more instructions than its limit
+    ContractInstructionLimitExceeded(u64),
     /// Consensus error
     ConsensusError(String),
     /// Validator error
     ValidatorError(String),
     /// Staking error
     StakingError(String),
+    /// An HTLC redemption or refund was rejected by the HTLC rules
+    HtlcError(String),
     /// Invalid transaction
     InvalidTransaction(String),
     /// Network error
@@ -63,6 +71,8 @@ pub enum BlockchainError {
     InvalidSignature(String),
     /// State corruption detected
     StateCorruption(String),
+    /// A chain reorganization would roll back more blocks than allowed
+    ReorgTooDeep { depth: u64, limit: u64 },
 }
 
 impl fmt::Display for BlockchainError {
@@ -93,9 +103,19 @@ impl fmt::Display for BlockchainError {
             BlockchainError::ApiError(msg) => write!(f, "API error: {}", msg),
             BlockchainError::ContractValidationFailed(msg) => write!(f, "Contract validation failed: {}", msg),
             BlockchainError::ContractExecutionError(msg) => write!(f, "Contract execution error: {}", msg),
+            BlockchainError::ContractReverted { error, gas_used } => {
+                write!(f, "Contract execution reverted after using {} gas: {}", gas_used, error)
+            }
+            BlockchainError::ContractStorageLimitExceeded { size, limit } => {
+                write!(f, "Contract storage limit exceeded: {} bytes (limit: {} bytes)", size, limit)
+            }
+            BlockchainError::ContractInstructionLimitExceeded(limit) => {
+                write!(f, "Contract execution exceeded its limit of {} instructions", limit)
+            }
             BlockchainError::ConsensusError(msg) => write!(f, "Consensus error: {}", msg),
             BlockchainError::ValidatorError(msg) => write!(f, "Validator error: {}", msg),
             BlockchainError::StakingError(msg) => write!(f, "Staking error: {}", msg),
+            BlockchainError::HtlcError(msg) => write!(f, "HTLC error: {}", msg),
             BlockchainError::InvalidTransaction(msg) => write!(f, "Invalid transaction: {}", msg),
             BlockchainError::NetworkError(msg) => write!(f, "Network error: {}", msg),
             BlockchainError::ShardingError(msg) => write!(f, "Sharding error: {}", msg),
@@ -106,6 +126,9 @@ impl fmt::Display for BlockchainError {
             BlockchainError::InvalidState(msg) => write!(f, "Invalid state: {}", msg),
             BlockchainError::InvalidSignature(msg) => write!(f, "Invalid signature: {}", msg),
             BlockchainError::StateCorruption(msg) => write!(f, "State corruption: {}", msg),
+            BlockchainError::ReorgTooDeep { depth, limit } => {
+                write!(f, "Reorganization too deep: {} blocks (limit: {} blocks)", depth, limit)
+            }
         }
     }
 }
@@ -1,19 +1,23 @@
+use crate::blockchain::Blockchain;
 use crate::error::BlockchainError;
 use crate::storage::BlockchainStorage;
+use crate::transaction::{Transaction, TransactionStatus};
 use crate::wallet::WalletManager;
 use ethers::{
-    core::k256::ecdsa::SigningKey,
-    providers::{Http, Provider},
-    signers::LocalWallet,
-    types::{Address, TransactionRequest, U256},
+    providers::{Http, Provider, ProviderError},
+    signers::{LocalWallet, Signer},
+    types::{transaction::eip2718::TypedTransaction, Address, TransactionRequest, U256},
 };
 use ethers_middleware::Middleware;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use tracing::{error, info};
 
+/// Gillean address holding GIL locked for outgoing Ethereum transfers
+pub const ETHEREUM_BRIDGE_LOCK_ADDRESS: &str = "ETH_BRIDGE_LOCK";
+
 /// Configuration for Ethereum testnet integration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EthereumConfig {
@@ -54,6 +58,9 @@ pub struct PendingTransfer {
     pub status: TransferStatus,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub ethereum_tx_hash: Option<String>,
+    /// ID of the Gillean transaction locking the GIL
+    #[serde(default)]
+    pub lock_tx_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -83,24 +90,66 @@ impl EthereumBridge {
         Ok(bridge)
     }
 
-    /// Initiate a transfer from Gillean to Ethereum
+    /// Transfer GIL from Gillean to Ethereum
+    /// 
+    /// Submits a transaction, signed with the sender's wallet key, that locks `amount`
+    /// GIL. The transfer stays `Pending` until `complete_transfer` finds the lock in a
+    /// block and sends the Ethereum side.
+    /// 
+    /// # Arguments
+    /// * `wallet_manager` - Wallet manager holding the sender's wallet
+    /// * `blockchain` - Gillean blockchain to lock the GIL on
+    /// * `from_gillean_address` - Sender's Gillean address
+    /// * `to_ethereum_address` - Recipient's Ethereum address
+    /// * `amount` - Amount to transfer
+    /// * `password` - Sender's wallet password
+    /// 
+    /// # Returns
+    /// * `Result<String, BlockchainError>` - The transfer ID, or an error if the lock
+    ///   transaction is rejected
     pub async fn transfer_to_ethereum(
         &self,
-        _from_wallet: &WalletManager,
+        wallet_manager: &mut WalletManager,
+        blockchain: &Mutex<Blockchain>,
         from_gillean_address: &str,
         to_ethereum_address: &str,
         amount: f64,
-        _password: &str,
+        password: &str,
     ) -> Result<String, BlockchainError> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(BlockchainError::InvalidInput("Transfer amount must be positive".to_string()));
+        }
+
         // Validate Ethereum address
         let to_address = to_ethereum_address
             .parse::<Address>()
             .map_err(|_| BlockchainError::ValidatorError("Invalid Ethereum address".to_string()))?;
 
+        // Make sure the wallet has an Ethereum key before any GIL is locked
+        wallet_manager.get_ethereum_private_key(from_gillean_address, password)?;
+
         // Create transfer ID
         let transfer_id = uuid::Uuid::new_v4().to_string();
 
-        // Create pending transfer record
+        // Lock the GIL on the Gillean side with a transaction signed by the sender
+        let private_key = wallet_manager.get_private_key_bytes(from_gillean_address, password)?;
+        let keypair = crate::KeyPair::from_private_key_bytes(&private_key)?;
+        let mut lock_tx = Transaction::new_transfer(
+            from_gillean_address.to_string(),
+            ETHEREUM_BRIDGE_LOCK_ADDRESS.to_string(),
+            crate::units::to_base_units(amount)?,
+            Some(format!("Ethereum bridge transfer {}", transfer_id)),
+        )?;
+        let lock_tx_id = {
+            let mut blockchain = blockchain.lock().unwrap();
+            lock_tx.set_nonce(blockchain.next_nonce(from_gillean_address));
+            let signature = keypair.sign(&lock_tx.canonical_bytes()?)?;
+            lock_tx.set_signature(signature, keypair.public_key())?;
+            let lock_tx_id = lock_tx.id.clone();
+            blockchain.add_transaction_object(lock_tx)?;
+            lock_tx_id
+        };
+
         let pending_transfer = PendingTransfer {
             id: transfer_id.clone(),
             from_gillean: from_gillean_address.to_string(),
@@ -109,95 +158,143 @@ impl EthereumBridge {
             status: TransferStatus::Pending,
             created_at: chrono::Utc::now(),
             ethereum_tx_hash: None,
+            lock_tx_id: Some(lock_tx_id),
         };
+        self.record_transfer(&pending_transfer).await?;
 
-        // Store pending transfer
-        {
-            let mut transfers = self.pending_transfers.write().await;
-            transfers.insert(transfer_id.clone(), pending_transfer.clone());
-        }
+        info!("Locked GIL for Ethereum transfer {}: {} -> {} ({} GIL)",
+              transfer_id, from_gillean_address, to_ethereum_address, amount);
 
-        // Save to persistent storage
-        self.save_pending_transfer(&pending_transfer).await?;
+        Ok(transfer_id)
+    }
 
-        // Start background processing
-        let bridge_clone = self.clone_for_background();
-        let transfer_id_clone = transfer_id.clone();
-        tokio::spawn(async move {
-            if let Err(e) = bridge_clone.process_transfer(&transfer_id_clone).await {
-                error!("Failed to process transfer {}: {}", transfer_id_clone, e);
+    /// Send the Ethereum side of a transfer once its GIL lock is in a block
+    /// 
+    /// Signs with the Ethereum key stored in the sender's wallet. A transfer whose lock
+    /// is still pending is left untouched; one whose lock failed or was dropped is
+    /// marked `Failed`. If the Ethereum side fails, the transfer stays `Pending` so it
+    /// can be retried, since the GIL is already locked.
+    /// 
+    /// # Arguments
+    /// * `wallet_manager` - Wallet manager holding the sender's wallet
+    /// * `blockchain` - Gillean blockchain holding the lock transaction
+    /// * `transfer_id` - ID of the transfer
+    /// * `password` - Sender's wallet password
+    /// 
+    /// # Returns
+    /// * `Result<TransferStatus, BlockchainError>` - The transfer's status afterwards, or
+    ///   `NetworkError` if the RPC fails and `InsufficientBalance` if the Ethereum account
+    ///   lacks funds
+    pub async fn complete_transfer(
+        &self,
+        wallet_manager: &mut WalletManager,
+        blockchain: &Mutex<Blockchain>,
+        transfer_id: &str,
+        password: &str,
+    ) -> Result<TransferStatus, BlockchainError> {
+        let mut transfer = self.pending_transfers.read().await
+            .get(transfer_id)
+            .cloned()
+            .ok_or_else(|| BlockchainError::NotFound(format!("Transfer not found: {}", transfer_id)))?;
+        if transfer.status != TransferStatus::Pending {
+            return Ok(transfer.status);
+        }
+
+        let lock_status = transfer.lock_tx_id.as_ref()
+            .and_then(|lock_tx_id| blockchain.lock().unwrap().transaction_status(lock_tx_id));
+        match lock_status {
+            Some(TransactionStatus::Pending) => return Ok(TransferStatus::Pending),
+            Some(TransactionStatus::Confirmed { .. }) => {}
+            Some(TransactionStatus::Failed { reason, .. }) => {
+                transfer.status = TransferStatus::Failed(format!("Lock transaction failed: {}", reason));
             }
-        });
+            None => {
+                transfer.status = TransferStatus::Failed("Lock transaction was dropped".to_string());
+            }
+        }
 
-        info!("Initiated transfer to Ethereum: {} -> {} ({} GIL)", 
-              from_gillean_address, to_ethereum_address, amount);
+        if transfer.status == TransferStatus::Pending {
+            // Sign with the wallet's own secp256k1 key, never its Ed25519 key
+            let private_key = wallet_manager.get_ethereum_private_key(&transfer.from_gillean, password)?;
+            let signer = LocalWallet::from_bytes(&private_key)
+                .map_err(|e| BlockchainError::WalletError(format!("Invalid Ethereum key: {}", e)))?
+                .with_chain_id(self.config.chain_id);
+
+            let tx_hash = self.send_ethereum_transaction(&signer, transfer.to_ethereum, transfer.amount).await
+                .map_err(|e| {
+                    error!("Failed to send Ethereum transaction for transfer {}: {}", transfer_id, e);
+                    e
+                })?;
+            transfer.ethereum_tx_hash = Some(tx_hash);
+            transfer.status = TransferStatus::Completed;
+        }
 
-        Ok(transfer_id)
+        self.record_transfer(&transfer).await?;
+
+        info!("Ethereum transfer {} is now {:?} (tx {:?})", transfer_id, transfer.status, transfer.ethereum_tx_hash);
+        Ok(transfer.status)
     }
 
-    /// Process a pending transfer in the background
-    async fn process_transfer(&self, transfer_id: &str) -> Result<(), BlockchainError> {
-        // Update status to processing
-        {
-            let mut transfers = self.pending_transfers.write().await;
-            if let Some(transfer) = transfers.get_mut(transfer_id) {
-                transfer.status = TransferStatus::Processing;
-            }
-        }
+    /// Sign and submit a value transfer on Ethereum
+    async fn send_ethereum_transaction(
+        &self,
+        signer: &LocalWallet,
+        to: Address,
+        amount: f64,
+    ) -> Result<String, BlockchainError> {
+        let from = signer.address();
+        let value = U256::from((amount * 1e18) as u128);
+        let gas_cost = U256::from(self.config.gas_limit) * U256::from(self.config.gas_price);
 
-        // Get transfer details
-        let transfer = {
-            let transfers = self.pending_transfers.read().await;
-            transfers.get(transfer_id)
-                .cloned()
-                .ok_or_else(|| BlockchainError::NotFound("Transfer not found".to_string()))?
-        };
+        // Check the sender can cover value and gas before signing
+        let balance = self.provider
+            .get_balance(from, None)
+            .await
+            .map_err(rpc_error)?;
+        if balance < value + gas_cost {
+            return Err(BlockchainError::InsufficientBalance {
+                address: format!("{:?}", from),
+                balance: wei_to_eth(balance),
+                required: wei_to_eth(value + gas_cost),
+            });
+        }
 
-        // For now, use a placeholder private key - in production, you'd load the actual wallet
-        // and convert the private key properly
-        let ethereum_private_key = SigningKey::random(&mut rand::thread_rng());
-        let _ethereum_wallet = LocalWallet::from(ethereum_private_key);
+        let nonce = self.provider
+            .get_transaction_count(from, None)
+            .await
+            .map_err(rpc_error)?;
 
-        // Create transaction request
-        let amount_wei = U256::from((transfer.amount * 1e18) as u128);
-        let tx_request = TransactionRequest::new()
-            .to(transfer.to_ethereum)
-            .value(amount_wei)
+        let tx: TypedTransaction = TransactionRequest::new()
+            .from(from)
+            .to(to)
+            .value(value)
             .gas(self.config.gas_limit)
-            .gas_price(self.config.gas_price);
-
-        // Send transaction
-        match self.provider.send_transaction(tx_request, None).await {
-            Ok(pending_tx) => {
-                let tx_hash = pending_tx.tx_hash();
-                
-                // Update transfer with transaction hash
-                {
-                    let mut transfers = self.pending_transfers.write().await;
-                    if let Some(transfer) = transfers.get_mut(transfer_id) {
-                        transfer.ethereum_tx_hash = Some(format!("{:?}", tx_hash));
-                        transfer.status = TransferStatus::Completed;
-                    }
-                }
-
-                info!("Ethereum transaction sent: {:?}", tx_hash);
-                self.save_pending_transfer(&transfer).await?;
-            }
-            Err(e) => {
-                // Update transfer status to failed
-                {
-                    let mut transfers = self.pending_transfers.write().await;
-                    if let Some(transfer) = transfers.get_mut(transfer_id) {
-                        transfer.status = TransferStatus::Failed(e.to_string());
-                    }
-                }
-
-                error!("Failed to send Ethereum transaction: {}", e);
-                return Err(BlockchainError::NetworkError(format!("Ethereum transaction failed: {}", e)));
-            }
-        }
+            .gas_price(self.config.gas_price)
+            .nonce(nonce)
+            .chain_id(self.config.chain_id)
+            .into();
+        let signature = signer
+            .sign_transaction(&tx)
+            .await
+            .map_err(|e| BlockchainError::WalletError(format!("Failed to sign Ethereum transaction: {}", e)))?;
 
-        Ok(())
+        let pending_tx = self.provider
+            .send_raw_transaction(tx.rlp_signed(&signature))
+            .await
+            .map_err(rpc_error)?;
+
+        let tx_hash = format!("{:?}", pending_tx.tx_hash());
+        info!("Ethereum transaction sent: {}", tx_hash);
+        Ok(tx_hash)
+    }
+
+    /// Store a transfer in memory and persistent storage
+    async fn record_transfer(&self, transfer: &PendingTransfer) -> Result<(), BlockchainError> {
+        {
+            let mut transfers = self.pending_transfers.write().await;
+            transfers.insert(transfer.id.clone(), transfer.clone());
+        }
+        self.save_pending_transfer(transfer).await
     }
 
     /// Get Ethereum balance for an address
@@ -209,10 +306,9 @@ impl EthereumBridge {
         let balance = self.provider
             .get_balance(eth_address, None)
             .await
-            .map_err(|e| BlockchainError::NetworkError(format!("Failed to get balance: {}", e)))?;
+            .map_err(rpc_error)?;
 
-        let balance_eth = balance.as_u128() as f64 / 1e18;
-        Ok(balance_eth)
+        Ok(wei_to_eth(balance))
     }
 
     /// Get transfer status
@@ -305,6 +401,19 @@ impl EthereumBridge {
     }
 }
 
+/// Convert an Ethereum RPC failure into a blockchain error
+fn rpc_error(e: ProviderError) -> BlockchainError {
+    BlockchainError::NetworkError(format!("Ethereum RPC request failed: {}", e))
+}
+
+/// Convert a wei amount to ETH
+fn wei_to_eth(wei: U256) -> f64 {
+    ethers::utils::format_units(wei, "ether")
+        .ok()
+        .and_then(|eth| eth.parse().ok())
+        .unwrap_or(0.0)
+}
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct BridgeStats {
     pub total_transfers: u64,
@@ -325,6 +434,7 @@ pub struct BridgeStatus {
 mod tests {
     use super::*;
     use crate::storage::BlockchainStorage;
+    use crate::units;
     use tempfile::tempdir;
 
     #[tokio::test]
@@ -338,6 +448,172 @@ mod tests {
         assert!(result.is_ok()); // Should succeed with default config
     }
 
+    const TX_HASH: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+    const RECIPIENT: &str = "0x2222222222222222222222222222222222222222";
+
+    /// Start a mock JSON-RPC server answering with the given ETH balance (hex wei)
+    async fn mock_rpc(balance: &'static str) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        use axum::{routing::post, Json, Router};
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let app = Router::new().route("/", post(move |Json(request): Json<serde_json::Value>| {
+            let recorded = recorded.clone();
+            async move {
+                let method = request["method"].as_str().unwrap_or_default().to_string();
+                recorded.lock().unwrap().push(method.clone());
+                let result = match method.as_str() {
+                    "eth_getBalance" => serde_json::json!(balance),
+                    "eth_getTransactionCount" => serde_json::json!("0x0"),
+                    "eth_sendRawTransaction" => serde_json::json!(TX_HASH),
+                    _ => serde_json::Value::Null,
+                };
+                Json(serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+            }
+        }));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        (format!("http://{}", address), calls)
+    }
+
+    async fn bridge_with_rpc(rpc_url: String, temp_dir: &tempfile::TempDir) -> EthereumBridge {
+        let storage = Arc::new(BlockchainStorage::new(temp_dir.path().to_str().unwrap()).unwrap());
+        let config = EthereumConfig { rpc_url, ..EthereumConfig::default() };
+        EthereumBridge::new(config, storage).await.unwrap()
+    }
+
+    fn funded_wallet(blockchain: &Mutex<Blockchain>) -> (WalletManager, String) {
+        let mut wallet_manager = WalletManager::new();
+        let address = wallet_manager.create_wallet("password123", None, None).unwrap().address;
+        blockchain.lock().unwrap().balances.insert(address.clone(), units::gil(100));
+        (wallet_manager, address)
+    }
+
+    /// Submit a transfer and mine the block holding its GIL lock
+    async fn locked_transfer(
+        bridge: &EthereumBridge,
+        blockchain: &Mutex<Blockchain>,
+        wallet_manager: &mut WalletManager,
+        address: &str,
+    ) -> String {
+        let transfer_id = bridge.transfer_to_ethereum(
+            wallet_manager, blockchain, address, RECIPIENT, 1.5, "password123",
+        ).await.unwrap();
+        blockchain.lock().unwrap().mine_block("miner".to_string()).unwrap();
+        transfer_id
+    }
+
+    #[tokio::test]
+    async fn test_transfer_to_ethereum_waits_for_lock_block() {
+        let temp_dir = tempdir().unwrap();
+        // 10 ETH
+        let (rpc_url, calls) = mock_rpc("0x8ac7230489e80000").await;
+        let bridge = bridge_with_rpc(rpc_url, &temp_dir).await;
+        let blockchain = Mutex::new(Blockchain::new_pow(2, 50.0).unwrap());
+        let (mut wallet_manager, address) = funded_wallet(&blockchain);
+
+        let transfer_id = bridge.transfer_to_ethereum(
+            &mut wallet_manager, &blockchain, &address, RECIPIENT, 1.5, "password123",
+        ).await.unwrap();
+
+        // The GIL is locked by a transaction signed by the sender
+        {
+            let blockchain = blockchain.lock().unwrap();
+            let lock = blockchain.pending_transactions.iter()
+                .find(|tx| tx.receiver == ETHEREUM_BRIDGE_LOCK_ADDRESS)
+                .unwrap();
+            assert_eq!(lock.sender, address);
+            assert_eq!(lock.amount, units::to_base_units(1.5).unwrap());
+            assert!(lock.is_signed_by_sender().unwrap());
+        }
+
+        // Nothing is sent to Ethereum while the lock is unmined
+        let status = bridge.complete_transfer(&mut wallet_manager, &blockchain, &transfer_id, "password123").await.unwrap();
+        assert_eq!(status, TransferStatus::Pending);
+        assert!(calls.lock().unwrap().is_empty());
+
+        blockchain.lock().unwrap().mine_block("miner".to_string()).unwrap();
+        let status = bridge.complete_transfer(&mut wallet_manager, &blockchain, &transfer_id, "password123").await.unwrap();
+        assert_eq!(status, TransferStatus::Completed);
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["eth_getBalance", "eth_getTransactionCount", "eth_sendRawTransaction"]
+        );
+
+        let transfers = bridge.get_pending_transfers().await.unwrap();
+        let transfer = transfers.iter().find(|t| t.id == transfer_id).unwrap();
+        assert_eq!(transfer.ethereum_tx_hash.as_deref(), Some(TX_HASH));
+        assert_eq!(blockchain.lock().unwrap().get_balance(ETHEREUM_BRIDGE_LOCK_ADDRESS), 1.5);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_to_ethereum_insufficient_funds() {
+        let temp_dir = tempdir().unwrap();
+        let (rpc_url, calls) = mock_rpc("0x0").await;
+        let bridge = bridge_with_rpc(rpc_url, &temp_dir).await;
+        let blockchain = Mutex::new(Blockchain::new_pow(2, 50.0).unwrap());
+        let (mut wallet_manager, address) = funded_wallet(&blockchain);
+        let transfer_id = locked_transfer(&bridge, &blockchain, &mut wallet_manager, &address).await;
+
+        let result = bridge.complete_transfer(&mut wallet_manager, &blockchain, &transfer_id, "password123").await;
+
+        assert!(matches!(result, Err(BlockchainError::InsufficientBalance { .. })));
+        assert_eq!(*calls.lock().unwrap(), vec!["eth_getBalance"]);
+        // The GIL is already locked, so the transfer stays open for a retry
+        assert_eq!(bridge.get_transfer_status(&transfer_id).await.unwrap(), Some(TransferStatus::Pending));
+    }
+
+    #[tokio::test]
+    async fn test_transfer_to_ethereum_rpc_unreachable() {
+        let temp_dir = tempdir().unwrap();
+        // Bind then drop a listener to get a port nothing is listening on
+        let address = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let bridge = bridge_with_rpc(format!("http://{}", address), &temp_dir).await;
+        let blockchain = Mutex::new(Blockchain::new_pow(2, 50.0).unwrap());
+        let (mut wallet_manager, gillean_address) = funded_wallet(&blockchain);
+        let transfer_id = locked_transfer(&bridge, &blockchain, &mut wallet_manager, &gillean_address).await;
+
+        let result = bridge.complete_transfer(&mut wallet_manager, &blockchain, &transfer_id, "password123").await;
+
+        assert!(matches!(result, Err(BlockchainError::NetworkError(_))));
+        assert_eq!(bridge.get_transfer_status(&transfer_id).await.unwrap(), Some(TransferStatus::Pending));
+    }
+
+    #[tokio::test]
+    async fn test_transfer_to_ethereum_dropped_lock_fails() {
+        let temp_dir = tempdir().unwrap();
+        let (rpc_url, calls) = mock_rpc("0x8ac7230489e80000").await;
+        let bridge = bridge_with_rpc(rpc_url, &temp_dir).await;
+        let blockchain = Mutex::new(Blockchain::new_pow(2, 50.0).unwrap());
+        let (mut wallet_manager, address) = funded_wallet(&blockchain);
+
+        let transfer_id = bridge.transfer_to_ethereum(
+            &mut wallet_manager, &blockchain, &address, RECIPIENT, 1.5, "password123",
+        ).await.unwrap();
+        blockchain.lock().unwrap().pending_transactions.clear();
+
+        let status = bridge.complete_transfer(&mut wallet_manager, &blockchain, &transfer_id, "password123").await.unwrap();
+        assert!(matches!(status, TransferStatus::Failed(_)));
+        assert!(calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_ethereum_balance_queries_rpc() {
+        let temp_dir = tempdir().unwrap();
+        let (rpc_url, calls) = mock_rpc("0x8ac7230489e80000").await;
+        let bridge = bridge_with_rpc(rpc_url, &temp_dir).await;
+
+        let balance = bridge.get_ethereum_balance(RECIPIENT).await.unwrap();
+
+        assert_eq!(balance, 10.0);
+        assert_eq!(*calls.lock().unwrap(), vec!["eth_getBalance"]);
+    }
+
     #[test]
     fn test_ethereum_config_default() {
         let config = EthereumConfig::default();
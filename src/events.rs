@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+use crate::{Result, BlockchainError, smart_contract::ContractResult};
+
+/// Capacity of the blockchain event broadcast channel
+pub const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Types of real-time blockchain events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventType {
+    NewBlock,
+    NewTransaction,
+    ContractDeployed,
+    ContractCalled,
+}
+
+/// Real-time blockchain event streamed to subscribers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub event_type: EventType,
+    pub data: serde_json::Value,
+    pub timestamp: i64,
+}
+
+impl Event {
+    /// Create a new event with the current timestamp
+    ///
+    /// # Arguments
+    /// * `event_type` - Type of the event
+    /// * `data` - Event payload
+    ///
+    /// # Returns
+    /// * `Event` - The created event
+    pub fn new<T: Serialize>(event_type: EventType, data: &T) -> Self {
+        Self {
+            event_type,
+            data: serde_json::to_value(data).unwrap_or(serde_json::Value::Null),
+            timestamp: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// Payload of a `ContractCalled` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractCallEvent {
+    /// Address of the called contract
+    pub contract: String,
+    /// Sender of the call
+    pub caller: String,
+    /// Outcome of the call
+    #[serde(flatten)]
+    pub result: ContractResult,
+}
+
+/// Filter applied to an event subscription
+///
+/// Every criterion that is set must match; an empty filter matches all events.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventFilter {
+    /// Event types to receive (all if empty)
+    #[serde(default)]
+    pub event_types: Vec<EventType>,
+    /// Only events concerning this contract
+    #[serde(default)]
+    pub contract: Option<String>,
+    /// Only events involving this address as a sender, receiver, owner or caller
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+/// Message sent by a client to replace its subscription filter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscribeMessage {
+    pub subscribe: EventFilter,
+}
+
+impl EventFilter {
+    /// Check whether an event passes the filter
+    ///
+    /// # Arguments
+    /// * `event` - Event to check
+    ///
+    /// # Returns
+    /// * `bool` - True if every criterion of the filter matches the event
+    pub fn matches(&self, event: &Event) -> bool {
+        if !self.event_types.is_empty() && !self.event_types.contains(&event.event_type) {
+            return false;
+        }
+        if let Some(contract) = &self.contract {
+            if !Self::concerns_contract(event, contract) {
+                return false;
+            }
+        }
+        if let Some(address) = &self.address {
+            if !Self::involves_address(event, address) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn concerns_contract(event: &Event, contract: &str) -> bool {
+        match event.event_type {
+            EventType::ContractDeployed => field_is(&event.data, "id", contract),
+            EventType::ContractCalled => field_is(&event.data, "contract", contract),
+            EventType::NewTransaction => field_is(&event.data, "receiver", contract),
+            EventType::NewBlock => block_transactions(&event.data)
+                .any(|tx| field_is(tx, "receiver", contract)),
+        }
+    }
+
+    fn involves_address(event: &Event, address: &str) -> bool {
+        let transaction_involves = |tx: &Value| {
+            field_is(tx, "sender", address) || field_is(tx, "receiver", address)
+        };
+        match event.event_type {
+            EventType::ContractDeployed => field_is(&event.data, "owner", address),
+            EventType::ContractCalled => field_is(&event.data, "caller", address),
+            EventType::NewTransaction => transaction_involves(&event.data),
+            EventType::NewBlock => block_transactions(&event.data).any(transaction_involves),
+        }
+    }
+}
+
+fn field_is(value: &Value, field: &str, expected: &str) -> bool {
+    value.get(field).and_then(Value::as_str) == Some(expected)
+}
+
+fn block_transactions(block: &Value) -> impl Iterator<Item = &Value> {
+    block
+        .get("transactions")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+impl FromStr for EventType {
+    type Err = BlockchainError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "newblock" | "new_block" => Ok(EventType::NewBlock),
+            "newtransaction" | "new_transaction" => Ok(EventType::NewTransaction),
+            "contractdeployed" | "contract_deployed" => Ok(EventType::ContractDeployed),
+            "contractcalled" | "contract_called" => Ok(EventType::ContractCalled),
+            _ => Err(BlockchainError::InvalidInput(format!("Unknown event type: {}", s))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_type_from_str() {
+        assert_eq!("NewBlock".parse::<EventType>().unwrap(), EventType::NewBlock);
+        assert_eq!("new_transaction".parse::<EventType>().unwrap(), EventType::NewTransaction);
+        assert!("Bogus".parse::<EventType>().is_err());
+    }
+
+    #[test]
+    fn test_event_filter_matching() {
+        let deployed = Event::new(EventType::ContractDeployed, &serde_json::json!({"id": "c1", "owner": "alice"}));
+        let called = Event::new(EventType::ContractCalled, &serde_json::json!({"contract": "c2", "caller": "bob"}));
+        let transfer = Event::new(EventType::NewTransaction, &serde_json::json!({"sender": "bob", "receiver": "carol"}));
+        let block = Event::new(EventType::NewBlock, &serde_json::json!({
+            "transactions": [{"sender": "alice", "receiver": "c2"}]
+        }));
+
+        assert!([&deployed, &called, &transfer, &block].iter().all(|e| EventFilter::default().matches(e)));
+
+        let by_contract = EventFilter { contract: Some("c2".to_string()), ..Default::default() };
+        assert!(!by_contract.matches(&deployed));
+        assert!(by_contract.matches(&called));
+        assert!(!by_contract.matches(&transfer));
+        assert!(by_contract.matches(&block));
+
+        let by_address = EventFilter { address: Some("bob".to_string()), ..Default::default() };
+        assert!(!by_address.matches(&deployed));
+        assert!(by_address.matches(&called));
+        assert!(by_address.matches(&transfer));
+        assert!(!by_address.matches(&block));
+
+        let combined = EventFilter {
+            event_types: vec![EventType::NewBlock],
+            contract: Some("c2".to_string()),
+            address: Some("alice".to_string()),
+        };
+        assert!(combined.matches(&block));
+        assert!(!combined.matches(&called));
+    }
+}
@@ -1,12 +1,29 @@
 use crate::error::BlockchainError;
 use crate::storage::BlockchainStorage;
 use crate::consensus::ProofOfStake;
+use crate::blockchain::Blockchain;
+use crate::units;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use tracing::info;
 
+/// Quorum (percentage of total stake) used when a proposal does not specify one
+pub const DEFAULT_QUORUM: f64 = 50.0;
+
+/// Lowest mining difficulty a parameter change may set
+pub const MIN_GOVERNED_DIFFICULTY: u32 = 1;
+
+/// Highest mining difficulty a parameter change may set
+pub const MAX_GOVERNED_DIFFICULTY: u32 = 8;
+
+/// Highest mining reward a parameter change may set
+pub const MAX_GOVERNED_MINING_REWARD: f64 = 1000.0;
+
+/// Storage key of the governed default quorum
+const DEFAULT_QUORUM_KEY: &str = "governance:default_quorum";
+
 /// Governance proposal
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GovernanceProposal {
@@ -26,9 +43,69 @@ pub struct GovernanceProposal {
     pub total_votes: u64,
     pub yes_votes: u64,
     pub no_votes: u64,
+    #[serde(default)]
+    pub yes_weight: f64, // stake voting yes
+    #[serde(default)]
+    pub no_weight: f64, // stake voting no
+    #[serde(default)]
+    pub abstain_weight: f64, // stake abstaining
     pub executed_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+impl GovernanceProposal {
+    /// Percentage of the total staked supply that voted on the proposal
+    pub fn participation(&self, total_stake: f64) -> f64 {
+        if total_stake <= 0.0 {
+            return 0.0;
+        }
+        (self.yes_weight + self.no_weight + self.abstain_weight) / total_stake * 100.0
+    }
+
+    /// Add a vote to the tally
+    fn count_vote(&mut self, choice: &VoteChoice, stake: f64) {
+        match choice {
+            VoteChoice::Yes => {
+                self.yes_votes += 1;
+                self.yes_weight += stake;
+            }
+            VoteChoice::No => {
+                self.no_votes += 1;
+                self.no_weight += stake;
+            }
+            // Abstain counts towards quorum but not towards yes/no
+            VoteChoice::Abstain => self.abstain_weight += stake,
+        }
+    }
+
+    /// Remove a previously counted vote from the tally
+    fn retract_vote(&mut self, choice: &VoteChoice, stake: f64) {
+        match choice {
+            VoteChoice::Yes => {
+                self.yes_votes = self.yes_votes.saturating_sub(1);
+                self.yes_weight -= stake;
+            }
+            VoteChoice::No => {
+                self.no_votes = self.no_votes.saturating_sub(1);
+                self.no_weight -= stake;
+            }
+            VoteChoice::Abstain => self.abstain_weight -= stake,
+        }
+    }
+
+    /// Outcome of the vote once the voting period has ended
+    /// 
+    /// Passes only if participation meets the quorum and the yes stake
+    /// exceeds the no stake.
+    pub fn outcome(&self, total_stake: f64) -> ProposalStatus {
+        let quorum_met = total_stake > 0.0 && self.participation(total_stake) >= self.quorum;
+        if quorum_met && self.yes_weight > self.no_weight {
+            ProposalStatus::Passed
+        } else {
+            ProposalStatus::Failed
+        }
+    }
+}
+
 /// Proposal types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ProposalType {
@@ -49,6 +126,70 @@ pub enum ProposalStatus {
     Cancelled,
 }
 
+/// Parameter a `ParameterChange` proposal may set
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParameterChange {
+    /// Blockchain mining difficulty (`difficulty`)
+    Difficulty(u32),
+    /// Blockchain mining reward (`mining_reward`)
+    MiningReward(f64),
+    /// Governance default quorum (`default_quorum`)
+    DefaultQuorum(f64),
+}
+
+impl ParameterChange {
+    /// Parse and range-check a proposed parameter
+    ///
+    /// Keys are matched case-insensitively, so `DEFAULT_QUORUM` and
+    /// `default_quorum` are equivalent.
+    pub fn parse(key: &str, value: &str) -> Result<Self, BlockchainError> {
+        let invalid = || BlockchainError::InvalidInput(format!("Invalid value for {}: {}", key, value));
+
+        match key.to_lowercase().as_str() {
+            "difficulty" => {
+                let difficulty: u32 = value.trim().parse().map_err(|_| invalid())?;
+                if !(MIN_GOVERNED_DIFFICULTY..=MAX_GOVERNED_DIFFICULTY).contains(&difficulty) {
+                    return Err(BlockchainError::InvalidInput(format!(
+                        "Difficulty must be between {} and {}",
+                        MIN_GOVERNED_DIFFICULTY, MAX_GOVERNED_DIFFICULTY
+                    )));
+                }
+                Ok(ParameterChange::Difficulty(difficulty))
+            }
+            "mining_reward" => {
+                let reward: f64 = value.trim().parse().map_err(|_| invalid())?;
+                if !reward.is_finite() || reward <= 0.0 || reward > MAX_GOVERNED_MINING_REWARD {
+                    return Err(BlockchainError::InvalidInput(format!(
+                        "Mining reward must be greater than 0 and at most {}",
+                        MAX_GOVERNED_MINING_REWARD
+                    )));
+                }
+                Ok(ParameterChange::MiningReward(reward))
+            }
+            "default_quorum" => {
+                let quorum: f64 = value.trim().parse().map_err(|_| invalid())?;
+                if !quorum.is_finite() || quorum <= 0.0 || quorum > 100.0 {
+                    return Err(BlockchainError::InvalidInput(
+                        "Default quorum must be greater than 0 and at most 100".to_string(),
+                    ));
+                }
+                Ok(ParameterChange::DefaultQuorum(quorum))
+            }
+            _ => Err(BlockchainError::InvalidInput(format!("Unknown governance parameter: {}", key))),
+        }
+    }
+
+    /// Parse every parameter of a proposal, rejecting empty proposals
+    pub fn parse_all(parameters: &HashMap<String, String>) -> Result<Vec<Self>, BlockchainError> {
+        if parameters.is_empty() {
+            return Err(BlockchainError::InvalidInput(
+                "Parameter change proposal has no parameters".to_string(),
+            ));
+        }
+        parameters.iter().map(|(key, value)| Self::parse(key, value)).collect()
+    }
+}
+
 /// Vote on a proposal
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Vote {
@@ -73,8 +214,9 @@ pub struct Governance {
     storage: Arc<BlockchainStorage>,
     consensus: Arc<ProofOfStake>,
     proposals: Arc<RwLock<HashMap<String, GovernanceProposal>>>,
-    votes: Arc<RwLock<HashMap<String, Vec<Vote>>>>, // proposal_id -> votes
+    votes: Arc<RwLock<HashMap<String, HashMap<String, Vote>>>>, // proposal_id -> voter -> vote
     current_block: Arc<RwLock<u64>>,
+    default_quorum: Arc<RwLock<f64>>,
 }
 
 /// Proposal creation request
@@ -86,7 +228,7 @@ pub struct ProposalCreationRequest {
     pub contract_code: Option<String>,
     pub parameters: HashMap<String, String>,
     pub voting_period: u64,
-    pub quorum: f64,
+    pub quorum: f64, // 0 uses the governed default quorum
 }
 
 /// Vote request
@@ -106,6 +248,7 @@ impl Governance {
             proposals: Arc::new(RwLock::new(HashMap::new())),
             votes: Arc::new(RwLock::new(HashMap::new())),
             current_block: Arc::new(RwLock::new(0)),
+            default_quorum: Arc::new(RwLock::new(DEFAULT_QUORUM)),
         };
 
         // Load existing proposals and votes from storage
@@ -121,13 +264,24 @@ impl Governance {
         request: ProposalCreationRequest,
     ) -> Result<String, BlockchainError> {
         // Validate proposer has sufficient stake
-        let proposer_stake = *self.consensus.get_validator_stats().get(proposer).unwrap_or(&0.0);
+        let proposer_stake = self.validator_stake(proposer);
         if proposer_stake < 1000.0 {
             return Err(BlockchainError::ValidatorError(
                 "Insufficient stake to create proposal (minimum 1000 GIL)".to_string()
             ));
         }
 
+        // Reject parameter changes that could never be executed
+        if request.proposal_type == ProposalType::ParameterChange {
+            ParameterChange::parse_all(&request.parameters)?;
+        }
+
+        let quorum = if request.quorum > 0.0 {
+            request.quorum
+        } else {
+            *self.default_quorum.read().await
+        };
+
         // Generate proposal ID
         let proposal_id = uuid::Uuid::new_v4().to_string();
 
@@ -144,7 +298,7 @@ impl Governance {
             contract_code: request.contract_code,
             parameters: request.parameters,
             voting_period: request.voting_period,
-            quorum: request.quorum,
+            quorum,
             created_at: chrono::Utc::now(),
             voting_start: current_block,
             voting_end: current_block + request.voting_period,
@@ -152,6 +306,9 @@ impl Governance {
             total_votes: 0,
             yes_votes: 0,
             no_votes: 0,
+            yes_weight: 0.0,
+            no_weight: 0.0,
+            abstain_weight: 0.0,
             executed_at: None,
         };
 
@@ -164,7 +321,7 @@ impl Governance {
         // Initialize votes for this proposal
         {
             let mut votes = self.votes.write().await;
-            votes.insert(proposal_id.clone(), Vec::new());
+            votes.insert(proposal_id.clone(), HashMap::new());
         }
 
         // Save to persistent storage
@@ -199,18 +356,11 @@ impl Governance {
             return Err(BlockchainError::ValidatorError("Voting period has ended".to_string()));
         }
 
-        // Check if voter has already voted
-        {
-            let votes = self.votes.read().await;
-            if let Some(proposal_votes) = votes.get(&request.proposal_id) {
-                if proposal_votes.iter().any(|v| v.voter == voter) {
-                    return Err(BlockchainError::ValidatorError("Already voted on this proposal".to_string()));
-                }
-            }
-        }
-
         // Validate voter has sufficient stake
-        let voter_stake = *self.consensus.get_validator_stats().get(voter).unwrap_or(&0.0);
+        if !request.stake_amount.is_finite() || request.stake_amount <= 0.0 {
+            return Err(BlockchainError::InvalidInput("Vote stake must be positive".to_string()));
+        }
+        let voter_stake = self.validator_stake(voter);
         if voter_stake < request.stake_amount {
             return Err(BlockchainError::ValidatorError("Insufficient stake to vote".to_string()));
         }
@@ -225,49 +375,66 @@ impl Governance {
             block_number: current_block,
         };
 
-        // Store vote
-        {
+        // Store vote, replacing any earlier vote from the same voter
+        let previous = {
             let mut votes = self.votes.write().await;
             votes.entry(request.proposal_id.clone())
-                .or_insert_with(Vec::new)
-                .push(vote.clone());
-        }
+                .or_default()
+                .insert(voter.to_string(), vote.clone())
+        };
 
         // Update proposal vote counts
-        {
+        let updated_proposal = {
             let mut proposals = self.proposals.write().await;
-            if let Some(proposal) = proposals.get_mut(&request.proposal_id) {
-                proposal.total_votes += 1;
-                match request.vote {
-                    VoteChoice::Yes => proposal.yes_votes += 1,
-                    VoteChoice::No => proposal.no_votes += 1,
-                    VoteChoice::Abstain => {}, // Abstain doesn't count towards yes/no
+            proposals.get_mut(&request.proposal_id).map(|proposal| {
+                match &previous {
+                    Some(previous) => proposal.retract_vote(&previous.vote, previous.stake_amount),
+                    None => proposal.total_votes += 1,
                 }
-            }
-        }
+                proposal.count_vote(&request.vote, request.stake_amount);
+                proposal.clone()
+            })
+        };
 
-        // Save vote to storage
+        // Save vote and tally to storage
         self.save_vote(&vote).await?;
+        if let Some(proposal) = updated_proposal {
+            self.save_proposal(&proposal).await?;
+        }
 
-        info!("Vote cast on proposal {}: {} voted {:?}", 
-              request.proposal_id, voter, request.vote);
+        match previous {
+            Some(previous) => info!("Vote changed on proposal {}: {} voted {:?} (was {:?})",
+                                    request.proposal_id, voter, request.vote, previous.vote),
+            None => info!("Vote cast on proposal {}: {} voted {:?}",
+                          request.proposal_id, voter, request.vote),
+        }
 
         Ok(())
     }
 
     /// Execute a passed proposal
-    pub async fn execute_proposal(&self, proposal_id: &str) -> Result<(), BlockchainError> {
-        // Get proposal
-        let proposal = {
-            let proposals = self.proposals.read().await;
-            proposals.get(proposal_id)
-                .ok_or_else(|| BlockchainError::NotFound("Proposal not found".to_string()))?
-                .clone()
-        };
+    /// 
+    /// Parameter changes are applied to the given blockchain and to the
+    /// governance state. A proposal can only be executed once.
+    pub async fn execute_proposal(
+        &self,
+        proposal_id: &str,
+        blockchain: &Mutex<Blockchain>,
+    ) -> Result<(), BlockchainError> {
+        // Hold the proposals lock until the status is updated so the proposal cannot be executed twice
+        let mut proposals = self.proposals.write().await;
+        let proposal = proposals.get_mut(proposal_id)
+            .ok_or_else(|| BlockchainError::NotFound("Proposal not found".to_string()))?;
 
-        // Check if proposal has passed
-        if proposal.status != ProposalStatus::Passed {
-            return Err(BlockchainError::ValidatorError("Proposal has not passed".to_string()));
+        match proposal.status {
+            ProposalStatus::Passed => {}
+            ProposalStatus::Executed => {
+                return Err(BlockchainError::InvalidState("Proposal has already been executed".to_string()));
+            }
+            ProposalStatus::Failed => {
+                return Err(BlockchainError::InvalidState("Proposal failed and cannot be executed".to_string()));
+            }
+            _ => return Err(BlockchainError::ValidatorError("Proposal has not passed".to_string())),
         }
 
         // Execute based on proposal type
@@ -280,11 +447,8 @@ impl Governance {
                 }
             }
             ProposalType::ParameterChange => {
-                // Apply parameter changes
-                for (key, value) in &proposal.parameters {
-                    info!("Applying parameter change: {} = {}", key, value);
-                    // This would update blockchain parameters
-                }
+                let changes = ParameterChange::parse_all(&proposal.parameters)?;
+                self.apply_parameter_changes(&changes, blockchain).await?;
             }
             ProposalType::ProtocolUpgrade => {
                 // Trigger protocol upgrade
@@ -300,50 +464,100 @@ impl Governance {
             }
         }
 
-        // Update proposal status
-        let mut updated_proposal = proposal.clone();
-        updated_proposal.status = ProposalStatus::Executed;
-        updated_proposal.executed_at = Some(chrono::Utc::now());
+        // Only mark the proposal executed once its effects have been applied
+        proposal.status = ProposalStatus::Executed;
+        proposal.executed_at = Some(chrono::Utc::now());
+        let proposal = proposal.clone();
+        drop(proposals);
 
         // Save updated proposal
-        self.save_proposal(&updated_proposal).await?;
+        self.save_proposal(&proposal).await?;
 
         info!("Executed proposal: {}", proposal_id);
         Ok(())
     }
 
+    /// Apply validated parameter changes to the blockchain and governance state
+    async fn apply_parameter_changes(
+        &self,
+        changes: &[ParameterChange],
+        blockchain: &Mutex<Blockchain>,
+    ) -> Result<(), BlockchainError> {
+        {
+            let mut blockchain = blockchain.lock()
+                .map_err(|_| BlockchainError::InvalidState("Blockchain lock poisoned".to_string()))?;
+            for change in changes {
+                match *change {
+                    ParameterChange::Difficulty(difficulty) => blockchain.set_difficulty(difficulty)?,
+                    ParameterChange::MiningReward(reward) => blockchain.set_mining_reward(reward)?,
+                    ParameterChange::DefaultQuorum(_) => {}
+                }
+            }
+        }
+
+        for change in changes {
+            if let ParameterChange::DefaultQuorum(quorum) = *change {
+                *self.default_quorum.write().await = quorum;
+                self.storage.set(DEFAULT_QUORUM_KEY, quorum.to_string().as_bytes())?;
+                info!("Set default quorum to {}%", quorum);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the quorum used for proposals that do not specify one
+    pub async fn default_quorum(&self) -> f64 {
+        *self.default_quorum.read().await
+    }
+
     /// Update proposal statuses based on current block
     pub async fn update_proposal_statuses(&self) -> Result<(), BlockchainError> {
         let current_block = *self.current_block.read().await;
-        let total_stake: f64 = self.consensus.get_validator_stats().values().sum();
+        let ended: Vec<String> = {
+            let proposals = self.proposals.read().await;
+            proposals.values()
+                .filter(|p| p.status == ProposalStatus::Active && current_block > p.voting_end)
+                .map(|p| p.id.clone())
+                .collect()
+        };
 
-        let mut proposals = self.proposals.write().await;
-        let votes = self.votes.read().await;
+        for proposal_id in ended {
+            self.tally_proposal(&proposal_id).await?;
+        }
 
-        for proposal in proposals.values_mut() {
-            if proposal.status == ProposalStatus::Active && current_block > proposal.voting_end {
-                // Calculate voting results
-                let total_voted_stake = votes.get(&proposal.id)
-                    .map(|v| v.iter().map(|vote| vote.stake_amount).sum::<f64>())
-                    .unwrap_or(0.0);
-
-                let quorum_met = total_voted_stake >= (total_stake * proposal.quorum / 100.0);
-                let majority_yes = proposal.yes_votes > proposal.no_votes;
-
-                if quorum_met && majority_yes {
-                    proposal.status = ProposalStatus::Passed;
-                    info!("Proposal {} passed", proposal.id);
-                } else {
-                    proposal.status = ProposalStatus::Failed;
-                    info!("Proposal {} failed", proposal.id);
-                }
+        Ok(())
+    }
 
-                // Save updated proposal
-                self.save_proposal(proposal).await?;
+    /// Tally the stake-weighted votes of a proposal
+    /// 
+    /// Proposals whose voting period is still open stay `Active`. Once it has
+    /// ended the proposal is marked `Passed` or `Failed`; proposals that were
+    /// already decided keep their status.
+    pub async fn tally_proposal(&self, proposal_id: &str) -> Result<ProposalStatus, BlockchainError> {
+        let current_block = *self.current_block.read().await;
+        let total_stake = self.total_stake();
+
+        let proposal = {
+            let mut proposals = self.proposals.write().await;
+            let proposal = proposals.get_mut(proposal_id)
+                .ok_or_else(|| BlockchainError::NotFound("Proposal not found".to_string()))?;
+
+            if proposal.status != ProposalStatus::Active || current_block <= proposal.voting_end {
+                return Ok(proposal.status.clone());
             }
-        }
 
-        Ok(())
+            proposal.status = proposal.outcome(total_stake);
+            info!("Proposal {} {:?} with {:.2}% participation ({} yes / {} no stake)",
+                  proposal.id, proposal.status, proposal.participation(total_stake),
+                  proposal.yes_weight, proposal.no_weight);
+            proposal.clone()
+        };
+
+        // Save updated proposal
+        self.save_proposal(&proposal).await?;
+
+        Ok(proposal.status)
     }
 
     /// Get proposal by ID
@@ -361,7 +575,17 @@ impl Governance {
     /// Get votes for a proposal
     pub async fn get_proposal_votes(&self, proposal_id: &str) -> Result<Vec<Vote>, BlockchainError> {
         let votes = self.votes.read().await;
-        Ok(votes.get(proposal_id).cloned().unwrap_or_default())
+        Ok(votes.get(proposal_id)
+            .map(|proposal_votes| proposal_votes.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    /// Get the current choice of a voter on a proposal
+    pub async fn get_voter_choice(&self, proposal_id: &str, voter: &str) -> Option<VoteChoice> {
+        let votes = self.votes.read().await;
+        votes.get(proposal_id)
+            .and_then(|proposal_votes| proposal_votes.get(voter))
+            .map(|vote| vote.vote.clone())
     }
 
     /// Clone for background processing
@@ -372,9 +596,22 @@ impl Governance {
             consensus: self.consensus.clone(),
             current_block: self.current_block.clone(),
             votes: self.votes.clone(),
+            default_quorum: self.default_quorum.clone(),
         }
     }
 
+    /// Get the amount staked by a validator
+    fn validator_stake(&self, address: &str) -> f64 {
+        self.consensus.validators.get(address)
+            .map(|validator| units::from_base_units(validator.stake_amount))
+            .unwrap_or(0.0)
+    }
+
+    /// Get the total staked supply
+    fn total_stake(&self) -> f64 {
+        units::from_base_units(self.consensus.validators.values().map(|v| v.stake_amount).sum())
+    }
+
     /// Get governance statistics
     pub async fn get_governance_stats(&self) -> Result<GovernanceStats, BlockchainError> {
         let proposals = self.proposals.read().await;
@@ -453,8 +690,15 @@ impl Governance {
         for (_key, value) in votes_data.iter() {
             if let Ok(vote) = serde_json::from_str::<Vote>(&String::from_utf8_lossy(value)) {
                 votes.entry(vote.proposal_id.clone())
-                    .or_insert_with(Vec::new)
-                    .push(vote);
+                    .or_default()
+                    .insert(vote.voter.clone(), vote);
+            }
+        }
+
+        // Load governed parameters
+        if let Some(value) = self.storage.get(DEFAULT_QUORUM_KEY)? {
+            if let Ok(quorum) = String::from_utf8_lossy(&value).parse::<f64>() {
+                *self.default_quorum.write().await = quorum;
             }
         }
 
@@ -484,7 +728,7 @@ mod tests {
     async fn test_proposal_creation() {
         let temp_dir = tempdir().unwrap();
         let storage = Arc::new(BlockchainStorage::new(temp_dir.path().to_str().unwrap()).unwrap());
-        let consensus = Arc::new(ProofOfStake::new(50.0, 10, 0.1, 0.1).unwrap());
+        let consensus = Arc::new(ProofOfStake::new(units::gil(50), 10, 0.1, 0.1).unwrap());
         let governance = Governance::new(storage, consensus).await.unwrap();
 
         let request = ProposalCreationRequest {
@@ -521,9 +765,253 @@ mod tests {
             total_votes: 0,
             yes_votes: 0,
             no_votes: 0,
+            yes_weight: 0.0,
+            no_weight: 0.0,
+            abstain_weight: 0.0,
             executed_at: None,
         };
 
         assert_eq!(proposal.status, ProposalStatus::Active);
     }
+
+    async fn governance_with_validators(temp_dir: &tempfile::TempDir, validators: &[(&str, f64)]) -> Governance {
+        let storage = Arc::new(BlockchainStorage::new(temp_dir.path().to_str().unwrap()).unwrap());
+        let mut consensus = ProofOfStake::new(units::gil(50), 10, 0.1, 0.1).unwrap();
+        for (address, stake) in validators {
+            consensus.register_validator(format!("{}_key", address), address.to_string(), units::to_base_units(*stake).unwrap()).unwrap();
+        }
+        Governance::new(storage, Arc::new(consensus)).await.unwrap()
+    }
+
+    async fn governance_with_validator(temp_dir: &tempfile::TempDir) -> Governance {
+        governance_with_validators(temp_dir, &[("alice", 2000.0)]).await
+    }
+
+    async fn vote(governance: &Governance, proposal_id: &str, voter: &str, vote: VoteChoice, stake_amount: f64) {
+        governance.vote_on_proposal(voter, VoteRequest {
+            proposal_id: proposal_id.to_string(),
+            vote,
+            stake_amount,
+        }).await.unwrap();
+    }
+
+    fn parameter_change(key: &str, value: &str) -> ProposalCreationRequest {
+        ProposalCreationRequest {
+            title: "Change parameter".to_string(),
+            description: format!("Set {} to {}", key, value),
+            proposal_type: ProposalType::ParameterChange,
+            contract_code: None,
+            parameters: HashMap::from([(key.to_string(), value.to_string())]),
+            voting_period: 10,
+            quorum: 50.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parameter_change_updates_blockchain() {
+        let temp_dir = tempdir().unwrap();
+        let governance = governance_with_validator(&temp_dir).await;
+        let blockchain = Mutex::new(Blockchain::new_pow(4, 50.0).unwrap());
+
+        let proposal_id = governance.create_proposal("alice", parameter_change("difficulty", "2")).await.unwrap();
+        governance.vote_on_proposal("alice", VoteRequest {
+            proposal_id: proposal_id.clone(),
+            vote: VoteChoice::Yes,
+            stake_amount: 2000.0,
+        }).await.unwrap();
+
+        // Executing before the vote closes is rejected
+        assert!(governance.execute_proposal(&proposal_id, &blockchain).await.is_err());
+
+        governance.update_block_number(11).await.unwrap();
+        let proposal = governance.get_proposal(&proposal_id).await.unwrap().unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+
+        governance.execute_proposal(&proposal_id, &blockchain).await.unwrap();
+        {
+            let blockchain = blockchain.lock().unwrap();
+            assert_eq!(blockchain.get_stats().difficulty, 2);
+            assert_eq!(blockchain.proof_of_work().unwrap().difficulty, 2);
+        }
+
+        let proposal = governance.get_proposal(&proposal_id).await.unwrap().unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+        assert!(proposal.executed_at.is_some());
+
+        // A proposal can only be executed once
+        let result = governance.execute_proposal(&proposal_id, &blockchain).await;
+        assert!(matches!(result, Err(BlockchainError::InvalidState(_))));
+    }
+
+    #[tokio::test]
+    async fn test_failed_proposal_cannot_be_executed() {
+        let temp_dir = tempdir().unwrap();
+        let governance = governance_with_validator(&temp_dir).await;
+        let blockchain = Mutex::new(Blockchain::new_pow(4, 50.0).unwrap());
+
+        let proposal_id = governance.create_proposal("alice", parameter_change("mining_reward", "25")).await.unwrap();
+        governance.update_block_number(11).await.unwrap();
+
+        let result = governance.execute_proposal(&proposal_id, &blockchain).await;
+        assert!(matches!(result, Err(BlockchainError::InvalidState(_))));
+        assert_eq!(blockchain.lock().unwrap().get_stats().mining_reward, 50.0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_apply_leaves_proposal_passed() {
+        let temp_dir = tempdir().unwrap();
+        let governance = governance_with_validator(&temp_dir).await;
+        let blockchain = Mutex::new(Blockchain::new_pow(4, 50.0).unwrap());
+
+        let proposal_id = governance.create_proposal("alice", parameter_change("difficulty", "2")).await.unwrap();
+        governance.vote_on_proposal("alice", VoteRequest {
+            proposal_id: proposal_id.clone(),
+            vote: VoteChoice::Yes,
+            stake_amount: 2000.0,
+        }).await.unwrap();
+        governance.update_block_number(11).await.unwrap();
+
+        // Poison the blockchain lock so applying the change fails
+        std::thread::scope(|scope| {
+            let _ = scope.spawn(|| {
+                let _guard = blockchain.lock().unwrap();
+                panic!("poison the blockchain lock");
+            }).join();
+        });
+
+        assert!(governance.execute_proposal(&proposal_id, &blockchain).await.is_err());
+        let proposal = governance.get_proposal(&proposal_id).await.unwrap().unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+        assert!(proposal.executed_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_parameter_change_validation() {
+        let temp_dir = tempdir().unwrap();
+        let governance = governance_with_validator(&temp_dir).await;
+
+        assert!(governance.create_proposal("alice", parameter_change("block_size", "1")).await.is_err());
+        assert!(governance.create_proposal("alice", parameter_change("difficulty", "40")).await.is_err());
+        assert!(governance.create_proposal("alice", parameter_change("mining_reward", "-1")).await.is_err());
+        assert!(governance.create_proposal("alice", parameter_change("DEFAULT_QUORUM", "0")).await.is_err());
+
+        assert_eq!(
+            ParameterChange::parse("DEFAULT_QUORUM", "66.5").unwrap(),
+            ParameterChange::DefaultQuorum(66.5)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_quorum_change() {
+        let temp_dir = tempdir().unwrap();
+        let governance = governance_with_validator(&temp_dir).await;
+        let blockchain = Mutex::new(Blockchain::new_pow(4, 50.0).unwrap());
+        assert_eq!(governance.default_quorum().await, DEFAULT_QUORUM);
+
+        let proposal_id = governance.create_proposal("alice", parameter_change("default_quorum", "75")).await.unwrap();
+        governance.vote_on_proposal("alice", VoteRequest {
+            proposal_id: proposal_id.clone(),
+            vote: VoteChoice::Yes,
+            stake_amount: 2000.0,
+        }).await.unwrap();
+        governance.update_block_number(11).await.unwrap();
+        governance.execute_proposal(&proposal_id, &blockchain).await.unwrap();
+        assert_eq!(governance.default_quorum().await, 75.0);
+
+        // Proposals without a quorum use the governed default
+        let mut request = parameter_change("difficulty", "3");
+        request.quorum = 0.0;
+        let proposal_id = governance.create_proposal("alice", request).await.unwrap();
+        let proposal = governance.get_proposal(&proposal_id).await.unwrap().unwrap();
+        assert_eq!(proposal.quorum, 75.0);
+    }
+
+    #[tokio::test]
+    async fn test_tally_fails_without_quorum() {
+        let temp_dir = tempdir().unwrap();
+        let governance = governance_with_validators(
+            &temp_dir, &[("alice", 2000.0), ("bob", 3000.0), ("carol", 5000.0)],
+        ).await;
+
+        let proposal_id = governance.create_proposal("alice", parameter_change("difficulty", "2")).await.unwrap();
+        vote(&governance, &proposal_id, "alice", VoteChoice::Yes, 2000.0).await;
+
+        // Voting is still open
+        assert_eq!(governance.tally_proposal(&proposal_id).await.unwrap(), ProposalStatus::Active);
+
+        // 20% participation is below the 50% quorum
+        *governance.current_block.write().await = 11;
+        assert_eq!(governance.tally_proposal(&proposal_id).await.unwrap(), ProposalStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_tally_rejects_with_quorum() {
+        let temp_dir = tempdir().unwrap();
+        let governance = governance_with_validators(
+            &temp_dir, &[("alice", 2000.0), ("bob", 3000.0), ("carol", 5000.0)],
+        ).await;
+
+        let proposal_id = governance.create_proposal("alice", parameter_change("difficulty", "2")).await.unwrap();
+        vote(&governance, &proposal_id, "alice", VoteChoice::Yes, 2000.0).await;
+        vote(&governance, &proposal_id, "bob", VoteChoice::Yes, 1000.0).await;
+        vote(&governance, &proposal_id, "carol", VoteChoice::No, 5000.0).await;
+
+        // Two yes votes lose to the larger no stake
+        governance.update_block_number(11).await.unwrap();
+        let proposal = governance.get_proposal(&proposal_id).await.unwrap().unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Failed);
+        assert_eq!(proposal.yes_weight, 3000.0);
+        assert_eq!(proposal.no_weight, 5000.0);
+        assert_eq!(proposal.participation(10000.0), 80.0);
+    }
+
+    #[tokio::test]
+    async fn test_tally_passes_by_stake() {
+        let temp_dir = tempdir().unwrap();
+        let governance = governance_with_validators(
+            &temp_dir, &[("alice", 2000.0), ("bob", 3000.0), ("carol", 5000.0)],
+        ).await;
+
+        let proposal_id = governance.create_proposal("alice", parameter_change("difficulty", "2")).await.unwrap();
+        vote(&governance, &proposal_id, "alice", VoteChoice::No, 2000.0).await;
+        vote(&governance, &proposal_id, "carol", VoteChoice::Yes, 5000.0).await;
+
+        // Votes after the voting period are rejected
+        *governance.current_block.write().await = 11;
+        let late_vote = governance.vote_on_proposal("bob", VoteRequest {
+            proposal_id: proposal_id.clone(),
+            vote: VoteChoice::No,
+            stake_amount: 3000.0,
+        }).await;
+        assert!(late_vote.is_err());
+
+        assert_eq!(governance.tally_proposal(&proposal_id).await.unwrap(), ProposalStatus::Passed);
+    }
+
+    #[tokio::test]
+    async fn test_vote_change_replaces_previous_vote() {
+        let temp_dir = tempdir().unwrap();
+        let governance = governance_with_validators(&temp_dir, &[("alice", 2000.0), ("bob", 3000.0)]).await;
+
+        let proposal_id = governance.create_proposal("alice", parameter_change("difficulty", "2")).await.unwrap();
+        assert_eq!(governance.get_voter_choice(&proposal_id, "bob").await, None);
+
+        vote(&governance, &proposal_id, "bob", VoteChoice::Yes, 3000.0).await;
+        assert_eq!(governance.get_voter_choice(&proposal_id, "bob").await, Some(VoteChoice::Yes));
+
+        vote(&governance, &proposal_id, "bob", VoteChoice::No, 3000.0).await;
+        assert_eq!(governance.get_voter_choice(&proposal_id, "bob").await, Some(VoteChoice::No));
+
+        let proposal = governance.get_proposal(&proposal_id).await.unwrap().unwrap();
+        assert_eq!(proposal.total_votes, 1);
+        assert_eq!(proposal.yes_votes, 0);
+        assert_eq!(proposal.no_votes, 1);
+        assert_eq!(proposal.yes_weight, 0.0);
+        assert_eq!(proposal.no_weight, 3000.0);
+        assert_eq!(governance.get_proposal_votes(&proposal_id).await.unwrap().len(), 1);
+
+        // Only the final choice counts towards the outcome
+        governance.update_block_number(11).await.unwrap();
+        assert_eq!(governance.tally_proposal(&proposal_id).await.unwrap(), ProposalStatus::Failed);
+    }
 }
@@ -0,0 +1,205 @@
+//! # Hash Time-Locked Contracts
+//!
+//! Primitive for trustless atomic swaps. The sender locks funds against the SHA-256
+//! hash of a secret preimage. The receiver can claim them by revealing the preimage
+//! before the timeout height; once the timeout height is reached the sender can take
+//! them back instead. Revealing the preimage to redeem one leg of a swap lets the
+//! counterparty redeem the other leg with the same secret.
+//!
+//! Locking, redeeming and refunding are transactions (`TransactionType::Htlc`) applied
+//! when they are mined, so replaying the chain reproduces every HTLC.
+
+use crate::{Result, BlockchainError};
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Digest};
+
+/// Lifecycle of a hash time-locked contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HtlcStatus {
+    /// Funds are locked and waiting for redemption or refund
+    Locked,
+    /// Receiver claimed the funds with the preimage
+    Redeemed,
+    /// Sender reclaimed the funds after the timeout
+    Refunded,
+}
+
+/// Operation an HTLC transaction performs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HtlcAction {
+    /// Lock the transaction amount from its sender for its receiver; the HTLC takes
+    /// the transaction's ID
+    Lock {
+        /// Hex-encoded SHA-256 hash of the secret preimage
+        hashlock: String,
+        /// Block height at which the lock expires
+        timeout_height: u64,
+    },
+    /// Pay a locked HTLC to its receiver by revealing the preimage
+    Redeem {
+        /// ID of the HTLC
+        htlc_id: String,
+        /// Secret whose SHA-256 hash matches the hashlock
+        preimage: String,
+    },
+    /// Return an expired HTLC to its sender
+    Refund {
+        /// ID of the HTLC
+        htlc_id: String,
+    },
+}
+
+impl HtlcAction {
+    /// Get the data that distinguishes this action in a transaction ID
+    ///
+    /// # Returns
+    /// * `String` - The action name and the HTLC parameters it refers to
+    pub fn id_data(&self) -> String {
+        match self {
+            HtlcAction::Lock { hashlock, timeout_height } => format!("lock:{}:{}", hashlock, timeout_height),
+            HtlcAction::Redeem { htlc_id, .. } => format!("redeem:{}", htlc_id),
+            HtlcAction::Refund { htlc_id } => format!("refund:{}", htlc_id),
+        }
+    }
+}
+
+/// Funds locked until a hash preimage is revealed or a timeout passes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Htlc {
+    /// HTLC ID
+    pub id: String,
+    /// Address that locked the funds and receives refunds
+    pub sender: String,
+    /// Address that can redeem the funds with the preimage
+    pub receiver: String,
+    /// Locked amount, in base units
+    pub amount: u128,
+    /// Hex-encoded SHA-256 hash of the secret preimage
+    pub hashlock: String,
+    /// Block height from which the sender may refund and redemption is closed
+    pub timeout_height: u64,
+    /// Block height at which the funds were locked
+    pub created_at: u64,
+    /// Current status
+    pub status: HtlcStatus,
+    /// Preimage revealed on redemption
+    pub preimage: Option<String>,
+}
+
+impl Htlc {
+    /// Create a new HTLC in the locked state
+    ///
+    /// # Arguments
+    /// * `id` - HTLC ID, the ID of the transaction locking the funds
+    /// * `sender` - Address locking the funds
+    /// * `receiver` - Address that can redeem the funds
+    /// * `amount` - Amount to lock, in base units
+    /// * `hashlock` - Hex-encoded SHA-256 hash of the preimage
+    /// * `timeout_height` - Block height at which the lock expires
+    /// * `created_at` - Current block height
+    ///
+    /// # Returns
+    /// * `Result<Htlc>` - The HTLC, or an error if a parameter is invalid
+    pub fn new(
+        id: String,
+        sender: String,
+        receiver: String,
+        amount: u128,
+        hashlock: String,
+        timeout_height: u64,
+        created_at: u64,
+    ) -> Result<Self> {
+        if sender.is_empty() || receiver.is_empty() {
+            return Err(BlockchainError::InvalidInput(
+                "Sender and receiver addresses cannot be empty".to_string(),
+            ));
+        }
+        if sender == receiver {
+            return Err(BlockchainError::InvalidInput(
+                "Sender and receiver cannot be the same".to_string(),
+            ));
+        }
+        if amount == 0 {
+            return Err(BlockchainError::InvalidInput("HTLC amount must be positive".to_string()));
+        }
+        let hashlock = hashlock.to_lowercase();
+        if hashlock.len() != 64 || hex::decode(&hashlock).is_err() {
+            return Err(BlockchainError::InvalidInput(
+                "Hashlock must be a hex-encoded SHA-256 hash".to_string(),
+            ));
+        }
+        if timeout_height <= created_at {
+            return Err(BlockchainError::InvalidInput(format!(
+                "Timeout height {} must be above the current height {}",
+                timeout_height, created_at
+            )));
+        }
+
+        Ok(Self {
+            id,
+            sender,
+            receiver,
+            amount,
+            hashlock,
+            timeout_height,
+            created_at,
+            status: HtlcStatus::Locked,
+            preimage: None,
+        })
+    }
+
+    /// Compute the hashlock for a preimage
+    ///
+    /// # Arguments
+    /// * `preimage` - The secret preimage
+    ///
+    /// # Returns
+    /// * `String` - Hex-encoded SHA-256 hash of the preimage
+    pub fn hash_preimage(preimage: &[u8]) -> String {
+        hex::encode(Sha256::digest(preimage))
+    }
+
+    /// Check a preimage against the hashlock
+    ///
+    /// # Arguments
+    /// * `preimage` - Candidate preimage
+    ///
+    /// # Returns
+    /// * `bool` - True if the preimage hashes to the hashlock
+    pub fn matches_preimage(&self, preimage: &[u8]) -> bool {
+        Self::hash_preimage(preimage) == self.hashlock
+    }
+
+    /// Check whether the lock has expired at the given height
+    ///
+    /// # Arguments
+    /// * `height` - Current block height
+    ///
+    /// # Returns
+    /// * `bool` - True once `height` reaches the timeout height
+    pub fn is_expired(&self, height: u64) -> bool {
+        height >= self.timeout_height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units;
+
+    #[test]
+    fn test_htlc_creation_validates_parameters() {
+        let hashlock = Htlc::hash_preimage(b"secret");
+
+        let htlc = Htlc::new("lock".to_string(), "alice".to_string(), "bob".to_string(), units::gil(10), hashlock.clone(), 5, 1).unwrap();
+        assert_eq!(htlc.status, HtlcStatus::Locked);
+        assert!(htlc.matches_preimage(b"secret"));
+        assert!(!htlc.matches_preimage(b"guess"));
+        assert!(!htlc.is_expired(4));
+        assert!(htlc.is_expired(5));
+
+        assert!(Htlc::new("lock".to_string(), "alice".to_string(), "bob".to_string(), 0, hashlock.clone(), 5, 1).is_err());
+        assert!(Htlc::new("lock".to_string(), "alice".to_string(), "bob".to_string(), units::gil(10), "not-a-hash".to_string(), 5, 1).is_err());
+        assert!(Htlc::new("lock".to_string(), "alice".to_string(), "bob".to_string(), units::gil(10), hashlock, 1, 1).is_err());
+    }
+}
@@ -58,6 +58,10 @@ pub struct CrossChainBridge {
     pub daily_transfers: Arc<RwLock<HashMap<String, f64>>>,
     /// Trusted validators for multi-signature verification
     pub trusted_validators: HashMap<String, PublicKey>,
+    /// Relayers attesting to source-chain burns, keyed by relayer ID
+    pub relayers: HashMap<String, PublicKey>,
+    /// Number of distinct relayer signatures required to settle a transfer
+    pub relayer_threshold: usize,
     /// Minimum confirmations required
     pub min_confirmations: u64,
 }
@@ -113,12 +117,54 @@ pub struct BridgeTransaction {
     pub bridge_signature: Option<DigitalSignature>,
     /// External chain signature (if applicable)
     pub external_signature: Option<DigitalSignature>,
+    /// Proof that the assets were burned on the source chain
+    #[serde(default)]
+    pub source_confirmation: Option<ConfirmationProof>,
     /// Created timestamp
     pub created_at: DateTime<Utc>,
     /// Updated timestamp
     pub updated_at: DateTime<Utc>,
 }
 
+/// Proof that assets were burned (or locked) on the source chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmationProof {
+    /// Bridge transaction the burn belongs to
+    pub bridge_tx_id: String,
+    /// Hash of the burn transaction on the source chain
+    pub source_tx_hash: String,
+    /// Amount burned
+    pub amount: f64,
+    /// Source chain block containing the burn
+    pub block_height: u64,
+    /// Confirmations of that block on the source chain
+    pub confirmations: u64,
+    /// Signatures over `message()` from relayers, keyed by relayer ID
+    pub relayer_signatures: HashMap<String, DigitalSignature>,
+}
+
+impl ConfirmationProof {
+    /// Get the message validators sign to attest to the burn
+    ///
+    /// Covers every field but the signatures, each prefixed with its length so no
+    /// two sets of field values encode to the same bytes.
+    pub fn message(&self) -> Vec<u8> {
+        let fields: [&[u8]; 5] = [
+            self.bridge_tx_id.as_bytes(),
+            self.source_tx_hash.as_bytes(),
+            &self.amount.to_le_bytes(),
+            &self.block_height.to_le_bytes(),
+            &self.confirmations.to_le_bytes(),
+        ];
+        let mut message = Vec::new();
+        for field in fields {
+            message.extend_from_slice(&(field.len() as u64).to_le_bytes());
+            message.extend_from_slice(field);
+        }
+        message
+    }
+}
+
 /// Type of bridge transaction
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BridgeTransactionType {
@@ -221,6 +267,8 @@ impl CrossChainBridge {
             daily_transfer_limit: 10_000_000.0, // 10M tokens daily limit
             daily_transfers: Arc::new(RwLock::new(HashMap::new())),
             trusted_validators: HashMap::new(),
+            relayers: HashMap::new(),
+            relayer_threshold: 1,
             min_confirmations: 6, // Require 6 confirmations
         })
     }
@@ -281,6 +329,7 @@ impl CrossChainBridge {
             status: BridgeTransactionStatus::Pending,
             bridge_signature: None,
             external_signature: None,
+            source_confirmation: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -294,12 +343,12 @@ impl CrossChainBridge {
         // Sign the transaction as bridge operator
         let bridge_signature = self.sign_bridge_transaction(&bridge_tx)?;
         
-        // Update transaction with bridge signature
+        // Update transaction with bridge signature; it stays pending until the
+        // source-side burn is confirmed
         {
             let mut pending = self.pending_transactions.write().unwrap();
             if let Some(tx) = pending.get_mut(&bridge_tx_id) {
                 tx.bridge_signature = Some(bridge_signature);
-                tx.status = BridgeTransactionStatus::Processing;
                 tx.updated_at = Utc::now();
             }
         }
@@ -309,12 +358,74 @@ impl CrossChainBridge {
         
         Ok(AssetTransferResponse {
             bridge_tx_id,
-            status: BridgeTransactionStatus::Processing,
+            status: BridgeTransactionStatus::Pending,
             estimated_completion: Some(300), // 5 minutes
             bridge_fee,
         })
     }
 
+    /// Confirm that the assets of a pending transfer were burned on the source chain
+    /// 
+    /// The proof must match the transfer, have at least `min_confirmations`, and be
+    /// signed by at least `relayer_threshold` distinct relayers. Only confirmed
+    /// transfers are minted on the target chain.
+    /// 
+    /// # Arguments
+    /// * `bridge_tx_id` - The bridge transaction to confirm
+    /// * `proof` - Proof of the source-side burn
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if the transfer is now source-confirmed, error otherwise
+    pub fn confirm_source_burn(&mut self, bridge_tx_id: &str, proof: ConfirmationProof) -> Result<()> {
+        if self.completed_transactions.read().unwrap().contains_key(bridge_tx_id) {
+            return Err(BlockchainError::InvalidTransaction(
+                format!("Bridge transaction {} already confirmed", bridge_tx_id)
+            ));
+        }
+        
+        let mut pending = self.pending_transactions.write().unwrap();
+        
+        // A burn can only back a single transfer
+        let burn_reused = pending.values()
+            .chain(self.completed_transactions.read().unwrap().values())
+            .filter_map(|tx| tx.source_confirmation.as_ref())
+            .any(|existing| existing.source_tx_hash == proof.source_tx_hash);
+        
+        let tx = pending.get_mut(bridge_tx_id)
+            .ok_or_else(|| BlockchainError::NotFound(
+                format!("Bridge transaction {} not found", bridge_tx_id)
+            ))?;
+        
+        if tx.source_confirmation.is_some() || tx.status != BridgeTransactionStatus::Pending {
+            return Err(BlockchainError::InvalidTransaction(
+                format!("Bridge transaction {} already confirmed", bridge_tx_id)
+            ));
+        }
+        if burn_reused {
+            return Err(BlockchainError::InvalidTransaction(
+                format!("Burn {} already used by another transfer", proof.source_tx_hash)
+            ));
+        }
+        if proof.bridge_tx_id != tx.id || proof.amount != tx.transaction_data.amount {
+            return Err(BlockchainError::InvalidTransaction(
+                "Burn proof does not match the bridge transaction".to_string()
+            ));
+        }
+        if proof.confirmations < self.min_confirmations {
+            return Err(BlockchainError::InvalidTransaction(
+                format!("Burn has {} confirmations, {} required", proof.confirmations, self.min_confirmations)
+            ));
+        }
+        self.verify_relayer_signatures(&proof.message(), &proof.relayer_signatures)?;
+        
+        info!("Source burn confirmed for bridge transaction {}: {}", bridge_tx_id, proof.source_tx_hash);
+        tx.source_confirmation = Some(proof);
+        tx.status = BridgeTransactionStatus::SourceConfirmed;
+        tx.updated_at = Utc::now();
+        
+        Ok(())
+    }
+
     /// Process pending bridge transactions
     pub fn process_pending_transactions(&mut self) -> Result<Vec<String>> {
         let mut processed_ids = Vec::new();
@@ -394,10 +505,8 @@ impl CrossChainBridge {
     /// Process an asset transfer transaction
     fn process_asset_transfer(&mut self, tx: &mut BridgeTransaction) -> Result<()> {
         match tx.status {
-            BridgeTransactionStatus::Processing => {
-                // Lock assets on source chain
-                self.lock_assets_on_source_chain(tx)?;
-                tx.status = BridgeTransactionStatus::SourceConfirmed;
+            BridgeTransactionStatus::Pending => {
+                // Wait for confirm_source_burn
             }
             BridgeTransactionStatus::SourceConfirmed => {
                 // Relay to target chain
@@ -421,24 +530,17 @@ impl CrossChainBridge {
         Ok(())
     }
 
-    /// Lock assets on the source chain
-    fn lock_assets_on_source_chain(&mut self, tx: &BridgeTransaction) -> Result<()> {
-        debug!("Locking assets on source chain: {}", tx.source_chain);
-        
-        // In a real implementation, this would interact with the actual blockchain
-        // For now, we'll simulate the locking process
-        
-        // Simulate network delay
-        std::thread::sleep(std::time::Duration::from_millis(100));
-        
-        info!("Assets locked on source chain: {}", tx.source_chain);
-        Ok(())
-    }
-
     /// Relay transaction to target chain
     fn relay_to_target_chain(&mut self, tx: &BridgeTransaction) -> Result<()> {
         debug!("Relaying transaction to target chain: {}", tx.target_chain);
         
+        // Never mint on the target without a confirmed source-side burn
+        if tx.source_confirmation.is_none() {
+            return Err(BlockchainError::InvalidTransaction(
+                format!("Bridge transaction {} has no source burn confirmation", tx.id)
+            ));
+        }
+        
         // In a real implementation, this would send the transaction to the target chain
         // For now, we'll simulate the relay process
         
@@ -610,6 +712,79 @@ impl CrossChainBridge {
         Ok(())
     }
 
+    /// Register a relayer allowed to attest to source-chain burns
+    pub fn register_relayer(&mut self, relayer_id: String, public_key: PublicKey) -> Result<()> {
+        if self.relayers.contains_key(&relayer_id) {
+            return Err(BlockchainError::InvalidTransaction(
+                format!("Relayer {} already exists", relayer_id)
+            ));
+        }
+        if self.relayers.values().any(|key| *key == public_key) {
+            return Err(BlockchainError::InvalidTransaction(
+                "Relayer public key already registered".to_string()
+            ));
+        }
+        
+        self.relayers.insert(relayer_id.clone(), public_key);
+        info!("Registered relayer: {}", relayer_id);
+        Ok(())
+    }
+
+    /// Remove a relayer
+    /// 
+    /// Fails if the remaining relayers could no longer meet the threshold.
+    pub fn remove_relayer(&mut self, relayer_id: &str) -> Result<()> {
+        if !self.relayers.contains_key(relayer_id) {
+            return Err(BlockchainError::NotFound(
+                format!("Relayer {} not found", relayer_id)
+            ));
+        }
+        if self.relayers.len() - 1 < self.relayer_threshold {
+            return Err(BlockchainError::InvalidTransaction(
+                format!("Removing relayer {} would leave fewer relayers than the threshold {}", relayer_id, self.relayer_threshold)
+            ));
+        }
+        
+        self.relayers.remove(relayer_id);
+        info!("Removed relayer: {}", relayer_id);
+        Ok(())
+    }
+
+    /// Set the number of relayer signatures required to settle a transfer
+    pub fn set_relayer_threshold(&mut self, threshold: usize) -> Result<()> {
+        if threshold == 0 || threshold > self.relayers.len() {
+            return Err(BlockchainError::InvalidInput(
+                format!("Relayer threshold must be between 1 and {}", self.relayers.len())
+            ));
+        }
+        
+        self.relayer_threshold = threshold;
+        info!("Relayer threshold set to {}-of-{}", threshold, self.relayers.len());
+        Ok(())
+    }
+
+    /// Verify that enough distinct relayers signed a message
+    fn verify_relayer_signatures(&self, message: &[u8], signatures: &HashMap<String, DigitalSignature>) -> Result<()> {
+        let mut valid_signatures = 0;
+        
+        for (relayer_id, signature) in signatures {
+            if let Some(public_key) = self.relayers.get(relayer_id) {
+                let sig = DigitalSignature::new(signature.signature.clone(), public_key.key.clone());
+                if sig.verify(message)? {
+                    valid_signatures += 1;
+                }
+            }
+        }
+        
+        if valid_signatures < self.relayer_threshold {
+            return Err(BlockchainError::InvalidSignature(
+                format!("Insufficient valid signatures: {} < {}", valid_signatures, self.relayer_threshold)
+            ));
+        }
+        
+        Ok(())
+    }
+
     /// Verify multi-signature from trusted validators
     #[allow(dead_code)]
     fn verify_multi_signature(&self, message: &[u8], signatures: &HashMap<String, DigitalSignature>) -> Result<()> {
@@ -692,6 +867,8 @@ impl CrossChainBridge {
             completed_transactions: completed_count,
             external_chains: external_chains_count,
             operator_public_key: hex::encode(&self.operator_keypair.public_key),
+            relayer_count: self.relayers.len(),
+            relayer_threshold: self.relayer_threshold,
         }
     }
 
@@ -719,6 +896,10 @@ pub struct BridgeStats {
     pub external_chains: usize,
     /// Bridge operator public key
     pub operator_public_key: String,
+    /// Number of registered relayers
+    pub relayer_count: usize,
+    /// Relayer signatures required to settle a transfer
+    pub relayer_threshold: usize,
 }
 
 /// Mock external chain for testing
@@ -798,7 +979,6 @@ impl MockExternalChain {
 #[cfg(test)]
 mod tests {
     use super::*;
-    // use crate::crypto::KeyPair; // Unused for now
 
     #[test]
     fn test_bridge_creation() {
@@ -961,6 +1141,181 @@ mod tests {
         let _ = std::fs::remove_dir_all(&db_path);
     }
 
+    fn bridge_with_transfer(db_path: &str, relayers: usize, threshold: usize) -> (CrossChainBridge, Vec<KeyPair>, String) {
+        let mut bridge = CrossChainBridge::new("test_bridge".to_string(), db_path).unwrap();
+        for chain_id in ["ethereum", "bitcoin"] {
+            bridge.register_external_chain(ExternalChain {
+                chain_id: chain_id.to_string(),
+                name: chain_id.to_string(),
+                chain_type: chain_id.to_string(),
+                bridge_address: None,
+                status: ChainStatus::Connected,
+                last_block_height: 1000,
+                connected_at: Utc::now(),
+            }).unwrap();
+        }
+        
+        let relayer_keys: Vec<KeyPair> = (0..relayers).map(|_| KeyPair::generate().unwrap()).collect();
+        for (i, keypair) in relayer_keys.iter().enumerate() {
+            bridge.register_relayer(format!("relayer{}", i), keypair.public_key()).unwrap();
+        }
+        bridge.set_relayer_threshold(threshold).unwrap();
+        
+        let user = KeyPair::generate().unwrap();
+        let response = bridge.initiate_asset_transfer(AssetTransferRequest {
+            source_chain: "ethereum".to_string(),
+            target_chain: "bitcoin".to_string(),
+            sender: "alice123".to_string(),
+            receiver: "bob123".to_string(),
+            amount: 100.0,
+            asset_type: "ETH".to_string(),
+            user_signature: user.sign(b"test message").unwrap(),
+        }).unwrap();
+        assert_eq!(response.status, BridgeTransactionStatus::Pending);
+        
+        (bridge, relayer_keys, response.bridge_tx_id)
+    }
+
+    fn burn_proof(bridge_tx_id: &str, signers: &[KeyPair]) -> ConfirmationProof {
+        let mut proof = ConfirmationProof {
+            bridge_tx_id: bridge_tx_id.to_string(),
+            source_tx_hash: "0xburn".to_string(),
+            amount: 100.0,
+            block_height: 1001,
+            confirmations: 6,
+            relayer_signatures: HashMap::new(),
+        };
+        for (i, signer) in signers.iter().enumerate() {
+            let signature = signer.sign(&proof.message()).unwrap();
+            proof.relayer_signatures.insert(format!("relayer{}", i), signature);
+        }
+        proof
+    }
+
+    #[test]
+    fn test_transfer_pending_until_burn_confirmed() {
+        let db_path = format!("data/databases/test_burn_confirm_{}", std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos());
+        
+        let (mut bridge, relayers, bridge_tx_id) = bridge_with_transfer(&db_path, 1, 1);
+        
+        // Without a confirmation nothing is relayed to the target chain
+        bridge.process_pending_transactions().unwrap();
+        assert_eq!(bridge.get_transaction_status(&bridge_tx_id), Some(BridgeTransactionStatus::Pending));
+        
+        bridge.confirm_source_burn(&bridge_tx_id, burn_proof(&bridge_tx_id, &relayers)).unwrap();
+        assert_eq!(bridge.get_transaction_status(&bridge_tx_id), Some(BridgeTransactionStatus::SourceConfirmed));
+        
+        bridge.process_pending_transactions().unwrap();
+        assert_eq!(bridge.get_transaction_status(&bridge_tx_id), Some(BridgeTransactionStatus::Relaying));
+        
+        // Clean up
+        let _ = std::fs::remove_dir_all(&db_path);
+    }
+
+    #[test]
+    fn test_burn_confirmation_rejects_forged_and_duplicate_proofs() {
+        let db_path = format!("data/databases/test_burn_reject_{}", std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos());
+        
+        let (mut bridge, relayers, bridge_tx_id) = bridge_with_transfer(&db_path, 1, 1);
+        
+        // Signed by a key that is not a registered relayer
+        let forger = vec![KeyPair::generate().unwrap()];
+        let result = bridge.confirm_source_burn(&bridge_tx_id, burn_proof(&bridge_tx_id, &forger));
+        assert!(result.unwrap_err().to_string().contains("Insufficient valid signatures"));
+        
+        // Amount tampered after signing
+        let mut tampered = burn_proof(&bridge_tx_id, &relayers);
+        tampered.amount = 1_000.0;
+        assert!(bridge.confirm_source_burn(&bridge_tx_id, tampered).is_err());
+        
+        // Not enough confirmations on the source chain
+        let mut shallow = burn_proof(&bridge_tx_id, &relayers);
+        shallow.confirmations = 1;
+        assert!(bridge.confirm_source_burn(&bridge_tx_id, shallow).is_err());
+        assert_eq!(bridge.get_transaction_status(&bridge_tx_id), Some(BridgeTransactionStatus::Pending));
+
+        // Confirmations raised after signing no longer match the relayer signatures
+        let mut raised = burn_proof(&bridge_tx_id, &[]);
+        raised.confirmations = 1;
+        for (i, signer) in relayers.iter().enumerate() {
+            raised.relayer_signatures.insert(format!("relayer{}", i), signer.sign(&raised.message()).unwrap());
+        }
+        assert!(bridge.verify_relayer_signatures(&raised.message(), &raised.relayer_signatures).is_ok());
+        raised.confirmations = 6;
+        assert!(bridge.verify_relayer_signatures(&raised.message(), &raised.relayer_signatures).is_err());
+        assert!(bridge.confirm_source_burn(&bridge_tx_id, raised).is_err());
+        assert_eq!(bridge.get_transaction_status(&bridge_tx_id), Some(BridgeTransactionStatus::Pending));
+
+        // A valid proof is accepted exactly once
+        bridge.confirm_source_burn(&bridge_tx_id, burn_proof(&bridge_tx_id, &relayers)).unwrap();
+        let result = bridge.confirm_source_burn(&bridge_tx_id, burn_proof(&bridge_tx_id, &relayers));
+        assert!(result.unwrap_err().to_string().contains("already confirmed"));
+        
+        // Clean up
+        let _ = std::fs::remove_dir_all(&db_path);
+    }
+
+    #[test]
+    fn test_relayer_threshold_signatures() {
+        let db_path = format!("data/databases/test_relayer_threshold_{}", std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos());
+        
+        let (mut bridge, relayers, bridge_tx_id) = bridge_with_transfer(&db_path, 3, 2);
+        let stats = bridge.get_bridge_stats();
+        assert_eq!(stats.relayer_count, 3);
+        assert_eq!(stats.relayer_threshold, 2);
+        
+        // M-1 signatures are rejected
+        let result = bridge.confirm_source_burn(&bridge_tx_id, burn_proof(&bridge_tx_id, &relayers[..1]));
+        assert!(result.unwrap_err().to_string().contains("Insufficient valid signatures"));
+        assert_eq!(bridge.get_transaction_status(&bridge_tx_id), Some(BridgeTransactionStatus::Pending));
+        
+        // Exactly M signatures settle the transfer
+        bridge.confirm_source_burn(&bridge_tx_id, burn_proof(&bridge_tx_id, &relayers[..2])).unwrap();
+        assert_eq!(bridge.get_transaction_status(&bridge_tx_id), Some(BridgeTransactionStatus::SourceConfirmed));
+        
+        // Clean up
+        let _ = std::fs::remove_dir_all(&db_path);
+    }
+
+    #[test]
+    fn test_relayer_management() {
+        let db_path = format!("data/databases/test_relayer_management_{}", std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos());
+        
+        let mut bridge = CrossChainBridge::new("test_bridge".to_string(), &db_path).unwrap();
+        let key1 = KeyPair::generate().unwrap().public_key();
+        let key2 = KeyPair::generate().unwrap().public_key();
+        
+        bridge.register_relayer("relayer1".to_string(), key1.clone()).unwrap();
+        bridge.register_relayer("relayer2".to_string(), key2).unwrap();
+        // The same key cannot be registered twice to count as two signers
+        assert!(bridge.register_relayer("relayer3".to_string(), key1).is_err());
+        
+        assert!(bridge.set_relayer_threshold(3).is_err());
+        bridge.set_relayer_threshold(2).unwrap();
+        
+        // Removing a relayer may not leave the threshold unreachable
+        assert!(bridge.remove_relayer("relayer1").is_err());
+        bridge.set_relayer_threshold(1).unwrap();
+        bridge.remove_relayer("relayer1").unwrap();
+        assert_eq!(bridge.relayers.len(), 1);
+        assert!(bridge.remove_relayer("relayer1").is_err());
+        
+        // Clean up
+        let _ = std::fs::remove_dir_all(&db_path);
+    }
+
     #[test]
     fn test_daily_transfer_limits() {
         let db_path = format!("data/databases/test_daily_limits_{}", std::time::SystemTime::now()
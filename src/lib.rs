@@ -36,6 +36,8 @@
 //! - **Transaction**: Value transfers between addresses (public and private)
 //! - **ZKP**: Zero-knowledge proofs for private transactions
 //! - **State Channels**: Layer 2 scaling for off-chain transactions
+//! - **Rollup**: Optimistic rollup batches with fraud proofs
+//! - **HTLC**: Hash time-locked contracts for atomic swaps
 //! - **Proof of Work**: Mining algorithm for consensus
 //! - **Consensus**: PoS consensus mechanism
 //! - **Smart Contract**: WebAssembly-based virtual machine for smart contracts
@@ -43,6 +45,7 @@
 //! - **Wallet**: Wallet management with encryption
 //! - **API**: REST API for blockchain interaction
 //! - **Utils**: Helper functions for hashing and validation
+//! - **Units**: Exact integer base units for balances
 //! - **Sharding**: Horizontal scaling through blockchain sharding
 //! - **Interop**: Cross-chain communication and bridge protocol
 //! - **Contract Toolkit**: Developer tools for WASM contract development
@@ -56,10 +59,15 @@ pub mod block;
 pub mod transaction;
 pub mod zkp;
 pub mod state_channels;
+pub mod rollup;
+pub mod htlc;
 pub mod proof_of_work;
+pub mod proof_of_authority;
 pub mod consensus;
 pub mod smart_contract;
+pub mod events;
 pub mod utils;
+pub mod units;
 pub mod error;
 pub mod merkle;
 pub mod crypto;
@@ -86,31 +94,35 @@ pub mod dev_utils;
 
 // Re-export main types for easy access
 pub use blockchain::Blockchain;
-pub use block::Block;
-pub use transaction::{Transaction, TransactionType};
+pub use block::{Block, BlockHeader};
+pub use transaction::{Transaction, TransactionType, TransactionReceipt, ReceiptStatus};
 pub use zkp::{ZKPManager, ZKProof, PrivateTransaction, ZKPStats};
 pub use state_channels::{StateChannelManager, StateChannel, ChannelState, ChannelStatus, ChannelUpdate, StateChannelStats};
+pub use rollup::{OptimisticRollup, RollupBatch, RollupState, BatchStatus, FraudProof};
+pub use htlc::{Htlc, HtlcAction, HtlcStatus};
 pub use proof_of_work::ProofOfWork;
-pub use consensus::{ConsensusType, ProofOfStake, Validator, StakingTransaction};
-pub use smart_contract::{SmartContract, ContractContext, ContractResult};
+pub use proof_of_authority::ProofOfAuthority;
+pub use consensus::{Consensus, ConsensusType, ProofOfStake, Validator, StakingTransaction};
+pub use smart_contract::{SmartContract, ContractContext, ContractResult, ContractEvent, ContractEventLog, GasSchedule, GasCategory, GasProfile, LineProfile};
+pub use events::{Event, EventFilter, EventType};
 pub use error::{BlockchainError, Result};
-pub use merkle::{MerkleTree, MerkleProof, MerkleNode};
+pub use merkle::{MerkleTree, MerkleProof, MerkleNode, verify_inclusion};
 pub use crypto::{KeyPair, PublicKey, DigitalSignature};
-pub use monitor::{BlockchainMonitor, BlockchainMetrics, HealthStatus};
-pub use network::{Network, NetworkMessage, Peer};
-pub use storage::{BlockchainStorage, BlockchainMetadata};
-pub use wallet::{WalletManager, WalletInfo, EncryptedWallet};
+pub use monitor::{BlockchainMonitor, BlockchainMetrics, HealthStatus, SubsystemCheck};
+pub use network::{BanPolicy, Network, NetworkMessage, Peer, PeerViolation};
+pub use storage::{BlockchainStorage, BlockchainMetadata, StorageConfig};
+pub use wallet::{WalletManager, WalletInfo, EncryptedWallet, KdfParams};
 pub use api::{AppState, start_server, create_router};
 pub use sharding::{ShardManager, Shard, ShardTransaction, CrossShardTransaction, ShardStats};
-pub use interop::{CrossChainBridge, BridgeTransaction, AssetTransferRequest, AssetTransferResponse, ExternalChain};
-pub use contract_toolkit::{ContractToolkit, ContractTemplate, CompiledContract, CompilationResult, DeploymentResult};
+pub use interop::{CrossChainBridge, BridgeTransaction, ConfirmationProof, AssetTransferRequest, AssetTransferResponse, ExternalChain};
+pub use contract_toolkit::{ContractToolkit, ContractTemplate, CompiledContract, CompilationResult, DeploymentResult, ContractAbi, AbiMethod, AbiParam, FuzzReport, FuzzFailure, FuzzFailureKind};
 pub use ethereum::{EthereumBridge, EthereumConfig, PendingTransfer, TransferStatus, BridgeStats};
 pub use did::{DecentralizedIdentity, DIDDocument, VerificationMethod, ServiceEndpoint, DIDCreationRequest, DIDVerificationResult, DIDStats};
 pub use governance::{Governance, GovernanceProposal, ProposalType, ProposalStatus, Vote, VoteChoice, ProposalCreationRequest, VoteRequest, GovernanceStats};
 pub use simulation::{SimulationManager, SimulationConfig, SimulationResult, SimulationMetrics, SimulationEvent, SimulationEventType, NetworkConditions, ShardConfig, FailureScenario, NodePerformance, SimulationState};
-pub use performance::{PerformanceManager, CacheManager, ParallelProcessor, MemoryOptimizer, MetricsCollector, PerformanceConfig, CacheConfig, ParallelConfig, MemoryUsage, MetricsConfig, PerformanceStats, OptimizationResult};
-pub use security::{SecurityManager, CryptoManager, AuditSystem, FormalVerifier, ThreatDetector, SecurityConfig, CryptoConfig, AuditConfig, FormalVerificationConfig, ThreatDetectionConfig, SecurityStatus, SecurityAuditResult};
-pub use developer_tools::{DeveloperToolsManager, Debugger, SDKGenerator, MonitoringDashboard, CodeAnalyzer, DeveloperToolsConfig, DebuggerConfig, SDKGeneratorConfig, MonitoringConfig, CodeAnalysisConfig, DeveloperToolsStatus, DeveloperReport};
+pub use performance::{PerformanceManager, CacheManager, ParallelProcessor, MemoryOptimizer, SnapshotPruning, MetricsCollector, PerformanceConfig, CacheConfig, ParallelConfig, MemoryUsage, MetricsConfig, PerformanceStats, OptimizationResult};
+pub use security::{SecurityManager, CryptoManager, AuditSystem, FormalVerifier, ThreatDetector, ThreatAlert, SecurityConfig, CryptoConfig, AuditConfig, FormalVerificationConfig, ThreatDetectionConfig, SecurityStatus, SecurityAuditResult};
+pub use developer_tools::{DeveloperToolsManager, Debugger, SDKGenerator, MonitoringDashboard, CodeAnalyzer, DeveloperToolsConfig, DebuggerConfig, SDKGeneratorConfig, MonitoringConfig, CodeAnalysisConfig, DeveloperToolsStatus, DeveloperReport, DebugSession, DebugPause};
 pub use deployment::{DeploymentManager, DeploymentConfig, DeploymentEnvironment, NetworkConfig, DatabaseConfig, SecurityConfig as DeploymentSecurityConfig, MonitoringConfig as DeploymentMonitoringConfig, ConsensusConfig, FeatureFlags, DeploymentStatus, SyncStatus, DeploymentUtils, DeploymentScript};
 pub use monitoring::{MetricsCollector as MonitoringMetricsCollector, CounterMetric, GaugeMetric, HistogramMetric, HistogramBucket, AlertConfig, AlertCondition, AlertSeverity, AlertState, HealthCheck, HealthStatus as MonitoringHealthStatus, SystemMetrics, NetworkIO, BlockchainMetrics as MonitoringBlockchainMetrics, DashboardData, MonitoringEvent, MetricsSnapshot, AlertManager, HealthCheckManager, ProductionMonitor};
 pub use block_explorer::{BlockExplorer, SearchResult, SearchResultType, ExplorerStatistics, BlockDetails, TransactionDetails, TransactionStatus, AddressInfo, NetworkOverview, BlockSummary, TransactionSummary, AddressSummary, NetworkHealth, HealthStatus as ExplorerHealthStatus, SearchFilters, PaginationParams, SortOrder};
@@ -129,6 +141,9 @@ pub const MAX_BLOCK_SIZE: usize = 1024 * 1024; // 1MB
 /// Genesis block hash (hardcoded for simplicity)
 pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
 
+/// Timestamp of genesis blocks built from allocations, fixed so every node derives the same block
+pub const GENESIS_TIMESTAMP: i64 = 0;
+
 /// Default gas limit for smart contracts
 pub const DEFAULT_GAS_LIMIT: u64 = 1_000_000;
 
@@ -7,7 +7,8 @@ use gillean::{
     crypto::{KeyPair, PublicKey}, BlockchainMonitor,
     BlockchainStorage, WalletManager, AppState, start_server, ConsensusType,
     ShardManager, CrossChainBridge, ContractToolkit, ZKPManager, StateChannelManager, ZKProof,
-    consensus::ProofOfStake, governance::Governance
+    consensus::ProofOfStake, governance::Governance, Network, units,
+    MemoryOptimizer, PerformanceConfig, SnapshotPruning,
 };
 use gillean::contract_toolkit::ContractToolkitConfig;
 // use gillean::blockchain::BlockchainStats; // Unused import
@@ -16,6 +17,7 @@ use std::sync::{Arc, Mutex};
 use gillean::did::DecentralizedIdentity;
 use gillean::ethereum::EthereumBridge;
 use gillean::ethereum::EthereumConfig;
+use gillean::simulation::{SimulationConfig, SimulationManager};
 
 
 /// Gillean Blockchain - A simple blockchain implementation in Rust
@@ -36,6 +38,10 @@ struct Cli {
     #[arg(short, long, default_value = "50.0")]
     reward: f64,
 
+    /// Target time between blocks in seconds, used to retarget PoW difficulty
+    #[arg(long, default_value = "10.0")]
+    target_block_time: f64,
+
     /// Consensus type (pos, pow)
     #[arg(short, long, default_value = "pow")]
     consensus: String,
@@ -48,6 +54,10 @@ struct Cli {
     #[arg(long, default_value = "5")]
     max_validators: usize,
 
+    /// File holding the hex-encoded private key this node signs PoS blocks with
+    #[arg(long)]
+    validator_key_file: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -68,7 +78,7 @@ enum Commands {
     },
     /// Add a transaction
     AddTransaction {
-        /// Sender address
+        /// Sender address; its wallet key signs the transaction
         #[arg(short, long)]
         sender: String,
         /// Receiver address
@@ -80,6 +90,9 @@ enum Commands {
         /// Optional message
         #[arg(short, long)]
         message: Option<String>,
+        /// Wallet password
+        #[arg(short, long)]
+        password: String,
     },
     /// Deploy a smart contract
     DeployContract {
@@ -119,15 +132,15 @@ enum Commands {
     },
     /// Register a validator for PoS
     RegisterValidator {
-        /// Validator address
+        /// Validator address; its wallet key becomes the validator's signing key
         #[arg(short, long)]
         address: String,
-        /// Validator public key (hex)
-        #[arg(short, long)]
-        public_key: String,
         /// Stake amount
         #[arg(short, long)]
         stake: f64,
+        /// Wallet password
+        #[arg(short, long)]
+        password: String,
     },
     /// Stake tokens for a validator
     Stake {
@@ -135,8 +148,11 @@ enum Commands {
         #[arg(short, long)]
         address: String,
         /// Stake amount
-        #[arg(short, long)]
+        #[arg(short = 'm', long)]
         amount: f64,
+        /// Wallet password
+        #[arg(short, long)]
+        password: String,
     },
     /// Unstake tokens from a validator
     Unstake {
@@ -144,8 +160,11 @@ enum Commands {
         #[arg(short, long)]
         address: String,
         /// Unstake amount
-        #[arg(short, long)]
+        #[arg(short = 'm', long)]
         amount: f64,
+        /// Wallet password
+        #[arg(short, long)]
+        password: String,
     },
     /// Show all validators
     Validators,
@@ -189,6 +208,11 @@ enum Commands {
         #[arg(short, long)]
         message: Option<String>,
     },
+    /// Decode and print a raw transaction
+    DecodeTransaction {
+        /// Hex-encoded JSON transaction, as printed by sign-transaction
+        hex: String,
+    },
     /// Connect to a peer
     ConnectPeer {
         /// Peer address (ip:port)
@@ -226,6 +250,12 @@ enum Commands {
         /// Database path
             #[arg(short, long, default_value = "./data/blockchain_db")]
     db_path: String,
+        /// API key required for mutating requests (may be repeated)
+        #[arg(long = "api-key")]
+        api_keys: Vec<String>,
+        /// Requests allowed per client per minute (0 disables rate limiting)
+        #[arg(long, default_value_t = 600)]
+        rate_limit: u32,
     },
     /// Create a new wallet
     CreateWallet {
@@ -422,12 +452,33 @@ enum Commands {
         /// Wallet password
         #[arg(short, long)]
         password: String,
+        /// Ethereum RPC URL (defaults to the Sepolia endpoint)
+        #[arg(long)]
+        rpc_url: Option<String>,
+        /// Hex-encoded Ethereum private key to import into the wallet (a new one is generated otherwise)
+        #[arg(long)]
+        ethereum_key: Option<String>,
+    },
+    /// Send the Ethereum side of a transfer once its GIL lock is mined
+    CompleteEthereumTransfer {
+        /// Transfer ID
+        #[arg(short, long)]
+        transfer_id: String,
+        /// Wallet password
+        #[arg(short, long)]
+        password: String,
+        /// Ethereum RPC URL (defaults to the Sepolia endpoint)
+        #[arg(long)]
+        rpc_url: Option<String>,
     },
     /// Get Ethereum balance
     GetEthereumBalance {
         /// Ethereum address
         #[arg(short, long)]
         address: String,
+        /// Ethereum RPC URL (defaults to the Sepolia endpoint)
+        #[arg(long)]
+        rpc_url: Option<String>,
     },
     /// Get Ethereum transfer status
     GetEthereumTransferStatus {
@@ -441,6 +492,9 @@ enum Commands {
         /// Controller (optional)
         #[arg(short, long)]
         controller: Option<String>,
+        /// Password encrypting the DID's key in the wallet storage
+        #[arg(short, long)]
+        password: String,
     },
     /// Verify a DID
     VerifyDid {
@@ -536,6 +590,30 @@ enum Commands {
         #[arg(short, long)]
         output_dir: String,
     },
+    /// Export the blockchain to a snapshot file
+    ExportChain {
+        /// Snapshot file to write
+        #[arg(short, long)]
+        path: String,
+    },
+    /// Import the blockchain from a snapshot file
+    ImportChain {
+        /// Snapshot file to read
+        #[arg(short, long)]
+        path: String,
+        /// Replace the local chain even if it already has blocks beyond genesis
+        #[arg(long)]
+        force: bool,
+    },
+    /// Measure transaction throughput on a temporary in-memory chain
+    Benchmark {
+        /// Number of transfers to submit and mine
+        #[arg(short, long, default_value = "1000")]
+        transactions: usize,
+        /// Number of clients submitting transfers concurrently
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+    },
 }
 
 #[tokio::main]
@@ -545,11 +623,25 @@ async fn main() -> Result<()> {
     // Initialize logging
     env_logger::Builder::new()
         .filter_level(cli.log_level.parse().unwrap_or(log::LevelFilter::Info))
+        .format(|buf, record| {
+            // Lines logged while handling an API request carry its correlation ID
+            let request_id = gillean::api::current_request_id()
+                .map(|id| format!(" request_id={}", id))
+                .unwrap_or_default();
+            writeln!(buf, "[{} {:<5} {}{}] {}", buf.timestamp(), record.level(), record.target(), request_id, record.args())
+        })
         .init();
 
     info!("Starting Gillean Blockchain v{}", BLOCKCHAIN_VERSION);
     debug!("CLI arguments: difficulty={}, reward={}, consensus={}", cli.difficulty, cli.reward, cli.consensus);
 
+    // The benchmark runs entirely in memory and must not touch the data directory
+    if let Some(Commands::Benchmark { transactions, concurrency }) = cli.command {
+        let report = run_benchmark(cli.difficulty, cli.reward, transactions, concurrency)?;
+        println!("{}", report);
+        return Ok(());
+    }
+
     // Ensure data directory structure exists
     std::fs::create_dir_all("data/blockchain_db")?;
     std::fs::create_dir_all("data/shards")?;
@@ -571,7 +663,7 @@ async fn main() -> Result<()> {
     } else {
         let storage = std::sync::Arc::new(BlockchainStorage::new("./data/blockchain_db")?);
         let blockchain = if cli.consensus.to_lowercase() == "pos" {
-            match Blockchain::new_pos(cli.reward, cli.min_stake, cli.max_validators) {
+            match Blockchain::new_pos(cli.reward, units::to_base_units(cli.min_stake)?, cli.max_validators) {
                 Ok(bc) => {
                     info!("Created PoS blockchain with min_stake={}, max_validators={}", cli.min_stake, cli.max_validators);
                     bc
@@ -595,6 +687,8 @@ async fn main() -> Result<()> {
         };
         (storage, blockchain)
     };
+    blockchain.set_target_block_time(cli.target_block_time)?;
+    blockchain.set_snapshot_pruning(snapshot_pruning(&storage));
 
     // Handle commands
     match cli.command {
@@ -604,8 +698,8 @@ async fn main() -> Result<()> {
         Some(Commands::Mine { miner }) => {
             mine_block(&mut blockchain, &miner)?;
         }
-        Some(Commands::AddTransaction { sender, receiver, amount, message }) => {
-            add_transaction(&mut blockchain, sender, receiver, amount, message)?;
+        Some(Commands::AddTransaction { sender, receiver, amount, message, password }) => {
+            add_transaction(&mut blockchain, &storage, sender, receiver, amount, message, &password)?;
         }
         Some(Commands::DeployContract { sender, code_file, gas_limit, gas_price }) => {
             deploy_contract(&mut blockchain, sender, code_file, gas_limit, gas_price)?;
@@ -613,14 +707,14 @@ async fn main() -> Result<()> {
         Some(Commands::CallContract { sender, contract, data, amount, gas_limit, gas_price }) => {
             call_contract(&mut blockchain, sender, contract, data, amount, gas_limit, gas_price)?;
         }
-        Some(Commands::RegisterValidator { address, public_key, stake }) => {
-            register_validator(&mut blockchain, address, public_key, stake)?;
+        Some(Commands::RegisterValidator { address, stake, password }) => {
+            register_validator(&mut blockchain, &storage, address, stake, &password)?;
         }
-        Some(Commands::Stake { address, amount }) => {
-            stake_tokens(&mut blockchain, address, amount)?;
+        Some(Commands::Stake { address, amount, password }) => {
+            stake_tokens(&mut blockchain, &storage, address, amount, &password)?;
         }
-        Some(Commands::Unstake { address, amount }) => {
-            unstake_tokens(&mut blockchain, address, amount)?;
+        Some(Commands::Unstake { address, amount, password }) => {
+            unstake_tokens(&mut blockchain, &storage, address, amount, &password)?;
         }
         Some(Commands::Validators) => {
             show_validators(&blockchain)?;
@@ -647,7 +741,7 @@ async fn main() -> Result<()> {
             let _ = show_contract_metrics(&blockchain);
         }
         Some(Commands::Interactive) => {
-            run_interactive(&mut blockchain)?;
+            run_interactive(&mut blockchain, &storage)?;
         }
         Some(Commands::GenerateKeypair) => {
             generate_keypair()?;
@@ -655,8 +749,11 @@ async fn main() -> Result<()> {
         Some(Commands::SignTransaction { sender, receiver, amount, private_key, message }) => {
             sign_transaction(sender, receiver, amount, private_key, message)?;
         }
+        Some(Commands::DecodeTransaction { hex }) => {
+            println!("{}", decode_transaction(&hex)?);
+        }
         Some(Commands::ConnectPeer { address }) => {
-            connect_to_peer(&address).await?;
+            connect_to_peer(blockchain, &storage, &address).await?;
         }
         Some(Commands::BroadcastTransaction { sender, receiver, amount, message }) => {
             broadcast_transaction(&sender, &receiver, amount, message).await?;
@@ -665,10 +762,14 @@ async fn main() -> Result<()> {
             monitor_blockchain(&blockchain)?;
         }
         Some(Commands::StartNetwork { address }) => {
-            start_network_server(&address).await?;
+            if let Some(path) = &cli.validator_key_file {
+                blockchain.add_validator_signer(load_validator_key(path)?)?;
+            }
+            start_network_server(blockchain, &address).await?;
         }
-        Some(Commands::StartApi { address, db_path }) => {
-            start_api_server(&address, &db_path).await?;
+        Some(Commands::StartApi { address, db_path, api_keys, rate_limit }) => {
+            let validator_key = cli.validator_key_file.as_deref().map(load_validator_key).transpose()?;
+            start_api_server(&address, &db_path, api_keys, rate_limit, validator_key).await?;
         }
         Some(Commands::CreateWallet { password, name }) => {
             create_wallet(&password, name)?;
@@ -731,18 +832,21 @@ async fn main() -> Result<()> {
         Some(Commands::ConnectEthereum { testnet, rpc_url }) => {
             connect_ethereum(&testnet, rpc_url).await?;
         }
-        Some(Commands::TransferToEthereum { from, to, amount, password }) => {
-            transfer_to_ethereum(&from, &to, amount, &password).await?;
+        Some(Commands::TransferToEthereum { from, to, amount, password, rpc_url, ethereum_key }) => {
+            transfer_to_ethereum(&from, &to, amount, &password, rpc_url, ethereum_key).await?;
+        }
+        Some(Commands::CompleteEthereumTransfer { transfer_id, password, rpc_url }) => {
+            complete_ethereum_transfer(&transfer_id, &password, rpc_url).await?;
         }
-        Some(Commands::GetEthereumBalance { address }) => {
-            get_ethereum_balance(&address).await?;
+        Some(Commands::GetEthereumBalance { address, rpc_url }) => {
+            get_ethereum_balance(&address, rpc_url).await?;
         }
         Some(Commands::GetEthereumTransferStatus { transfer_id }) => {
             get_ethereum_transfer_status(&transfer_id).await?;
         }
         // DID Commands
-        Some(Commands::CreateDid { controller }) => {
-            create_did(controller).await?;
+        Some(Commands::CreateDid { controller, password }) => {
+            create_did(controller, &password).await?;
         }
         Some(Commands::VerifyDid { did, message, signature }) => {
             verify_did(&did, &message, &signature).await?;
@@ -776,6 +880,13 @@ async fn main() -> Result<()> {
         Some(Commands::SdkGenerateTypescript { output_dir }) => {
             generate_typescript_sdk(&output_dir)?;
         }
+        Some(Commands::ExportChain { path }) => {
+            export_chain(&blockchain, &path)?;
+        }
+        Some(Commands::ImportChain { path, force }) => {
+            import_chain(&mut blockchain, &storage, &path, force)?;
+        }
+        Some(Commands::Benchmark { .. }) => unreachable!("benchmark runs before storage is opened"),
         None => {
             // No command specified, run demo
             run_demo(&mut blockchain, &storage, 3).await?;
@@ -794,10 +905,13 @@ async fn run_demo(blockchain: &mut Blockchain, storage: &std::sync::Arc<Blockcha
     show_stats(blockchain);
     println!();
 
-    // Add some initial balances for demo
-    blockchain.balances.insert("alice".to_string(), 1000.0);
-    blockchain.balances.insert("bob".to_string(), 500.0);
-    blockchain.balances.insert("charlie".to_string(), 200.0);
+    // Add some initial balances for demo accounts with their own signing keys
+    let mut demo_accounts = HashMap::new();
+    for (name, balance) in [("alice", 1000), ("bob", 500), ("charlie", 200)] {
+        let keypair = KeyPair::generate()?;
+        blockchain.balances.insert(gillean::crypto::create_address(&keypair.public_key()), units::gil(balance));
+        demo_accounts.insert(name, keypair);
+    }
 
     println!("💰 Added initial balances:");
     show_balances(blockchain);
@@ -947,30 +1061,37 @@ async fn run_demo(blockchain: &mut Blockchain, storage: &std::sync::Arc<Blockcha
     println!("✅ TypeScript SDK generated in: {}", ts_temp_dir.display());
     println!();
 
-    // For PoS, register validators first
-    if blockchain.get_consensus_type() == ConsensusType::ProofOfStake {
+    // For PoS, restart from a genesis that registers the demo validators, since
+    // later registrations need a validator to mine them
+    if let Some(pos) = blockchain.proof_of_stake() {
         println!("🏛️  Registering validators for PoS consensus...");
         
-        // Register validators with their stakes
         let validators = vec![
-            ("alice", 1000.0),
-            ("bob", 500.0),
-            ("charlie", 200.0),
+            ("alice", units::gil(1000)),
+            ("bob", units::gil(500)),
+            ("charlie", units::gil(200)),
         ];
+        let keys = validators.iter()
+            .map(|_| KeyPair::generate())
+            .collect::<Result<Vec<_>>>()?;
+        let genesis_validators = validators.iter().zip(&keys)
+            .map(|((validator, stake), keypair)| (keypair.public_key_hex(), validator.to_string(), *stake))
+            .collect();
+
+        let mut pos_chain = Blockchain::new_pos_with_validators(
+            blockchain.mining_reward,
+            pos.min_stake,
+            pos.max_validators,
+            genesis_validators,
+        )?;
+        pos_chain.balances = std::mem::take(&mut blockchain.balances);
+        for keypair in keys {
+            pos_chain.add_validator_signer(keypair)?;
+        }
+        *blockchain = pos_chain;
 
         for (validator, stake) in validators {
-            match blockchain.register_validator(
-                validator.to_string(),
-                validator.to_string(),
-                stake,
-            ) {
-                Ok(_) => {
-                    println!("  ✅ Registered validator {} with stake {} GIL", validator, stake);
-                }
-                Err(e) => {
-                    warn!("  ❌ Failed to register validator {}: {}", validator, e);
-                }
-            }
+            println!("  ✅ Registered validator {} with stake {} GIL", validator, units::from_base_units(stake));
         }
         println!();
     }
@@ -979,23 +1100,24 @@ async fn run_demo(blockchain: &mut Blockchain, storage: &std::sync::Arc<Blockcha
     println!("📝 Creating {} demo transactions...", num_transactions);
     
     let demo_transactions = vec![
-        ("alice", "bob", 100.0, "Payment for services"),
-        ("bob", "charlie", 50.0, "Lunch payment"),
-        ("charlie", "alice", 25.0, "Coffee"),
-        ("alice", "charlie", 75.0, "Dinner"),
-        ("bob", "alice", 30.0, "Transport"),
+        ("alice", "bob", units::gil(100), "Payment for services"),
+        ("bob", "charlie", units::gil(50), "Lunch payment"),
+        ("charlie", "alice", units::gil(25), "Coffee"),
+        ("alice", "charlie", units::gil(75), "Dinner"),
+        ("bob", "alice", units::gil(30), "Transport"),
     ];
 
     for (i, (sender, receiver, amount, message)) in demo_transactions.iter().take(num_transactions).enumerate() {
+        let receiver_address = gillean::crypto::create_address(&demo_accounts[receiver].public_key());
         match blockchain.add_transaction(
-            sender.to_string(),
-            receiver.to_string(),
+            &demo_accounts[sender],
+            receiver_address,
             *amount,
             Some(message.to_string()),
         ) {
             Ok(_) => {
                 println!("  ✅ Transaction {}: {} -> {} ({} GIL) - {}", 
-                    i + 1, sender, receiver, amount, message);
+                    i + 1, sender, receiver, units::from_base_units(*amount), message);
             }
             Err(e) => {
                 warn!("  ❌ Failed to add transaction {}: {}", i + 1, e);
@@ -1038,6 +1160,9 @@ async fn run_demo(blockchain: &mut Blockchain, storage: &std::sync::Arc<Blockcha
     println!("  cargo run -- validate                    # Validate the blockchain");
     println!("  cargo run -- stats                       # Show statistics");
     println!("  cargo run -- balances                    # Show all balances");
+    println!("  cargo run -- export-chain --path chain.snapshot  # Export the chain to a snapshot");
+    println!("  cargo run -- decode-transaction <hex>    # Decode a raw transaction");
+    println!("  cargo run -- import-chain --path chain.snapshot  # Import a chain snapshot");
     println!("  cargo run -- interactive                 # Enter interactive mode");
 
     Ok(())
@@ -1068,17 +1193,20 @@ fn mine_block(blockchain: &mut Blockchain, miner: &str) -> Result<()> {
     Ok(())
 }
 
-/// Add a transaction
+/// Add a transaction signed with the sender's wallet
 fn add_transaction(
     blockchain: &mut Blockchain,
+    storage: &std::sync::Arc<BlockchainStorage>,
     sender: String,
     receiver: String,
     amount: f64,
     message: Option<String>,
+    password: &str,
 ) -> Result<()> {
     println!("📝 Adding transaction: {} -> {} ({} GIL)", sender, receiver, amount);
     
-    match blockchain.add_transaction(sender.clone(), receiver.clone(), amount, message.clone()) {
+    let keypair = wallet_keypair(storage, &sender, password)?;
+    match blockchain.add_transaction(&keypair, receiver.clone(), units::to_base_units(amount)?, message.clone()) {
         Ok(_) => {
             println!("✅ Transaction added to pending queue");
             println!("📊 Pending transactions: {}", blockchain.pending_transactions.len());
@@ -1115,7 +1243,7 @@ fn call_contract(blockchain: &mut Blockchain, sender: String, contract: String,
     println!("{}", "=".repeat(50));
 
     let data_bytes = gillean::utils::hex_to_bytes(&data)?;
-    blockchain.call_contract(sender.clone(), contract.clone(), String::from_utf8(data_bytes.clone()).map_err(|e| BlockchainError::ContractValidationFailed(e.to_string()))?, amount.expect("Amount is required"), gas_limit, gas_price)?;
+    blockchain.call_contract(sender.clone(), contract.clone(), String::from_utf8(data_bytes.clone()).map_err(|e| BlockchainError::ContractValidationFailed(e.to_string()))?, units::to_base_units(amount.expect("Amount is required"))?, gas_limit, gas_price)?;
 
     println!("✅ Smart contract called successfully!");
     println!("📍 Contract Address: {}", contract);
@@ -1126,32 +1254,69 @@ fn call_contract(blockchain: &mut Blockchain, sender: String, contract: String,
     Ok(())
 }
 
+/// Load a wallet's signing key from the shared wallet storage
+fn wallet_keypair(storage: &std::sync::Arc<BlockchainStorage>, address: &str, password: &str) -> Result<KeyPair> {
+    let mut wallet_manager = WalletManager::new();
+    wallet_manager.set_shared_storage(storage.clone());
+    let private_key = wallet_manager.get_private_key_bytes(address, password)?;
+    KeyPair::from_private_key_bytes(&private_key)
+}
+
+/// Sign a staking transaction with the validator's wallet and add it to the pending pool
+fn submit_staking_transaction(
+    blockchain: &mut Blockchain,
+    storage: &std::sync::Arc<BlockchainStorage>,
+    mut transaction: gillean::transaction::Transaction,
+    password: &str,
+) -> Result<gillean::transaction::Transaction> {
+    let keypair = wallet_keypair(storage, &transaction.sender, password)?;
+
+    transaction.set_nonce(blockchain.next_nonce(&transaction.sender));
+    transaction.sign(&keypair)?;
+    blockchain.add_transaction_object(transaction.clone())?;
+    storage.save_pending_transactions(&blockchain.pending_transactions)?;
+    Ok(transaction)
+}
+
 /// Register a validator for PoS
-fn register_validator(blockchain: &mut Blockchain, address: String, public_key: String, stake: f64) -> Result<()> {
+fn register_validator(
+    blockchain: &mut Blockchain,
+    storage: &std::sync::Arc<BlockchainStorage>,
+    address: String,
+    stake: f64,
+    password: &str,
+) -> Result<()> {
     println!("\n🚀 Registering validator...");
     println!("{}", "=".repeat(50));
 
-    let public_key_bytes = gillean::utils::hex_to_bytes(&public_key)?;
-    let public_key = gillean::PublicKey::from_bytes(public_key_bytes)?;
-
-    blockchain.register_validator(public_key.to_hex(), address.clone(), stake)?;
+    let transaction = gillean::transaction::Transaction::new_validator_registration(address.clone(), units::to_base_units(stake)?)?;
+    let transaction = submit_staking_transaction(blockchain, storage, transaction, password)?;
 
-    println!("✅ Validator registered successfully!");
+    println!("✅ Validator registration submitted!");
+    println!("📋 Transaction ID: {}", transaction.id);
     println!("📍 Address: {}", address);
-    println!("👤 Public Key: {}", public_key.to_hex());
-    println!("💰 Stake: {} GIL", stake);
+    println!("👤 Public Key: {}", transaction.get_signer_public_key().unwrap_or_default());
+    println!("💰 Stake: {} GIL (locked once the transaction is mined)", stake);
 
     Ok(())
 }
 
 /// Stake tokens for a validator
-fn stake_tokens(blockchain: &mut Blockchain, address: String, amount: f64) -> Result<()> {
+fn stake_tokens(
+    blockchain: &mut Blockchain,
+    storage: &std::sync::Arc<BlockchainStorage>,
+    address: String,
+    amount: f64,
+    password: &str,
+) -> Result<()> {
     println!("\n🚀 Staking tokens...");
     println!("{}", "=".repeat(50));
 
-    blockchain.stake_tokens(address.clone(), amount)?;
+    let transaction = gillean::transaction::Transaction::new_staking(address.clone(), units::to_base_units(amount)?, true)?;
+    let transaction = submit_staking_transaction(blockchain, storage, transaction, password)?;
 
-    println!("✅ Tokens staked successfully!");
+    println!("✅ Stake submitted!");
+    println!("📋 Transaction ID: {}", transaction.id);
     println!("📍 Address: {}", address);
     println!("💰 Staked Amount: {} GIL", amount);
 
@@ -1159,13 +1324,21 @@ fn stake_tokens(blockchain: &mut Blockchain, address: String, amount: f64) -> Re
 }
 
 /// Unstake tokens from a validator
-fn unstake_tokens(blockchain: &mut Blockchain, address: String, amount: f64) -> Result<()> {
+fn unstake_tokens(
+    blockchain: &mut Blockchain,
+    storage: &std::sync::Arc<BlockchainStorage>,
+    address: String,
+    amount: f64,
+    password: &str,
+) -> Result<()> {
     println!("\n🚀 Unstaking tokens...");
     println!("{}", "=".repeat(50));
 
-    blockchain.unstake_tokens(address.clone(), amount)?;
+    let transaction = gillean::transaction::Transaction::new_staking(address.clone(), units::to_base_units(amount)?, false)?;
+    let transaction = submit_staking_transaction(blockchain, storage, transaction, password)?;
 
-    println!("✅ Tokens unstaked successfully!");
+    println!("✅ Unstake submitted!");
+    println!("📋 Transaction ID: {}", transaction.id);
     println!("📍 Address: {}", address);
     println!("💰 Unstaked Amount: {} GIL", amount);
 
@@ -1217,6 +1390,111 @@ fn show_stats(blockchain: &Blockchain) {
     println!("{}", stats);
 }
 
+/// Export the blockchain to a snapshot file
+fn export_chain(blockchain: &Blockchain, path: &str) -> Result<()> {
+    blockchain.export_snapshot(path)?;
+    println!("📦 Exported {} blocks to {}", blockchain.blocks.len(), path);
+    Ok(())
+}
+
+/// Throughput measured by a benchmark run
+struct BenchmarkReport {
+    transactions: usize,
+    blocks: usize,
+    total_duration: std::time::Duration,
+    average_mining_time: std::time::Duration,
+    tps: f64,
+}
+
+impl std::fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "⏱️  Benchmark Results")?;
+        writeln!(f, "{}", "=".repeat(30))?;
+        writeln!(f, "Transactions: {}", self.transactions)?;
+        writeln!(f, "Blocks mined: {}", self.blocks)?;
+        writeln!(f, "Total duration: {:.3}s", self.total_duration.as_secs_f64())?;
+        writeln!(f, "Average mining time: {:.3}s", self.average_mining_time.as_secs_f64())?;
+        write!(f, "Throughput: {:.2} TPS", self.tps)
+    }
+}
+
+/// Submit `transactions` transfers from `concurrency` clients to a fresh in-memory chain and mine them
+fn run_benchmark(difficulty: u32, reward: f64, transactions: usize, concurrency: usize) -> Result<BenchmarkReport> {
+    if transactions == 0 || concurrency == 0 {
+        return Err(BlockchainError::InvalidInput(
+            "Benchmark needs at least one transaction and one client".to_string(),
+        ));
+    }
+
+    // Every client signs from its own funded account
+    let senders = (0..concurrency)
+        .map(|_| KeyPair::generate())
+        .collect::<Result<Vec<_>>>()?;
+    let allocations = senders.iter()
+        .map(|sender| (gillean::crypto::create_address(&sender.public_key()), units::gil(transactions as u64)))
+        .collect();
+    let blockchain = Arc::new(Mutex::new(Blockchain::new_pow_with_genesis(difficulty, reward, allocations)?));
+
+    println!("⏱️  Benchmarking {} transactions from {} clients at difficulty {}", transactions, concurrency, difficulty);
+    let start = std::time::Instant::now();
+
+    let clients: Vec<_> = senders.into_iter().enumerate().map(|(client, sender)| {
+        let blockchain = blockchain.clone();
+        let count = transactions / concurrency + usize::from(client < transactions % concurrency);
+        std::thread::spawn(move || -> Result<()> {
+            for i in 0..count {
+                let receiver = format!("bench-receiver-{}", i % 16);
+                blockchain.lock().unwrap().add_transaction(&sender, receiver, units::UNITS_PER_GIL / 2, None)?;
+            }
+            Ok(())
+        })
+    }).collect();
+    for client in clients {
+        client.join().map_err(|_| BlockchainError::InvalidState("Benchmark client panicked".to_string()))??;
+    }
+
+    let mut blockchain = blockchain.lock().unwrap();
+    let mut mining_time = std::time::Duration::ZERO;
+    let mut blocks = 0;
+    while !blockchain.pending_transactions.is_empty() {
+        let mining_start = std::time::Instant::now();
+        blockchain.mine_block("bench-miner".to_string())?;
+        mining_time += mining_start.elapsed();
+        blocks += 1;
+    }
+
+    let total_duration = start.elapsed();
+    Ok(BenchmarkReport {
+        transactions,
+        blocks,
+        total_duration,
+        average_mining_time: mining_time / blocks as u32,
+        tps: transactions as f64 / total_duration.as_secs_f64(),
+    })
+}
+
+/// Import the blockchain from a snapshot file and persist it
+fn import_chain(
+    blockchain: &mut Blockchain,
+    storage: &std::sync::Arc<BlockchainStorage>,
+    path: &str,
+    force: bool,
+) -> Result<()> {
+    if blockchain.blocks.len() > 1 && !force {
+        return Err(BlockchainError::InvalidState(format!(
+            "Local chain already has {} blocks; pass --force to replace it",
+            blockchain.blocks.len()
+        )));
+    }
+
+    let imported = Blockchain::import_snapshot(path)?;
+    storage.save_blockchain(&imported)?;
+    *blockchain = imported;
+
+    println!("📥 Imported {} blocks from {}", blockchain.blocks.len(), path);
+    Ok(())
+}
+
 /// Show all balances
 fn show_balances(blockchain: &Blockchain) {
     let balances = blockchain.get_balances();
@@ -1228,7 +1506,7 @@ fn show_balances(blockchain: &Blockchain) {
 
     println!("💰 Balances:");
     for (address, balance) in balances {
-        println!("  {}: {:.2} GIL", address, balance);
+        println!("  {}: {} GIL", address, units::format_units(*balance));
     }
 }
 
@@ -1247,12 +1525,12 @@ fn show_block(blockchain: &Blockchain, index: usize) -> Result<()> {
     println!("  Previous Hash: {}", block.previous_hash);
     println!("  Nonce: {}", block.nonce);
     println!("  Transactions: {}", block.transaction_count());
-    println!("  Total Amount: {:.2} GIL", block.total_amount());
+    println!("  Total Amount: {:.2} GIL", units::from_base_units(block.total_amount()));
 
     if !block.transactions.is_empty() {
         println!("  Transaction Details:");
         for (i, tx) in block.transactions.iter().enumerate() {
-            println!("    {}. {} -> {} ({} GIL)", i + 1, tx.sender, tx.receiver, tx.amount);
+            println!("    {}. {} -> {} ({} GIL)", i + 1, tx.sender, tx.receiver, units::from_base_units(tx.amount));
             if let Some(ref message) = tx.message {
                 println!("       Message: {}", message);
             }
@@ -1273,7 +1551,7 @@ fn show_pending_transactions(blockchain: &Blockchain) {
 
     println!("📝 Pending Transactions ({}):", pending.len());
     for (i, tx) in pending.iter().enumerate() {
-        println!("  {}. {} -> {} ({} GIL)", i + 1, tx.sender, tx.receiver, tx.amount);
+        println!("  {}. {} -> {} ({} GIL)", i + 1, tx.sender, tx.receiver, units::from_base_units(tx.amount));
         if let Some(ref message) = tx.message {
             println!("     Message: {}", message);
         }
@@ -1295,7 +1573,7 @@ fn show_contracts(blockchain: &Blockchain) -> Result<()> {
     for (i, (contract_id, contract)) in contracts.iter().enumerate() {
         println!("  {}. ID: {}", i + 1, contract_id);
         println!("     Owner: {}", contract.owner);
-        println!("     Balance: {} GIL", contract.balance);
+        println!("     Balance: {} GIL", units::from_base_units(contract.balance));
         println!("     Active: {}", contract.active);
         println!("     Created: {}", contract.created_at);
         println!("     Code size: {} bytes", contract.code.len());
@@ -1327,7 +1605,7 @@ fn show_contract_metrics(blockchain: &Blockchain) -> Result<()> {
 }
 
 /// Run interactive mode
-fn run_interactive(blockchain: &mut Blockchain) -> Result<()> {
+fn run_interactive(blockchain: &mut Blockchain, storage: &std::sync::Arc<BlockchainStorage>) -> Result<()> {
     println!("\n🎮 Interactive Mode");
     println!("Type 'help' for available commands, 'quit' to exit");
     println!("{}", "=".repeat(50));
@@ -1344,7 +1622,7 @@ fn run_interactive(blockchain: &mut Blockchain) -> Result<()> {
             "help" | "h" => {
                 println!("Available commands:");
                 println!("  mine [miner]     - Mine a new block");
-                println!("  add [s] [r] [a]  - Add transaction (sender receiver amount)");
+                println!("  add [s] [r] [a] [p] - Add transaction (sender receiver amount wallet-password)");
                 println!("  validate         - Validate blockchain");
                 println!("  stats            - Show statistics");
                 println!("  balances         - Show all balances");
@@ -1371,18 +1649,19 @@ fn run_interactive(blockchain: &mut Blockchain) -> Result<()> {
                 println!("👋 Goodbye!");
                 break;
             }
-            input if input.starts_with("add ") => {
+            command if command.starts_with("add ") => {
+                // Parse the original input so the password keeps its case
                 let parts: Vec<&str> = input.split_whitespace().collect();
-                if parts.len() >= 4 {
+                if parts.len() >= 5 {
                     let sender = parts[1].to_string();
                     let receiver = parts[2].to_string();
                     if let Ok(amount) = parts[3].parse::<f64>() {
-                        add_transaction(blockchain, sender, receiver, amount, None)?;
+                        add_transaction(blockchain, storage, sender, receiver, amount, None, parts[4])?;
                     } else {
                         println!("❌ Invalid amount");
                     }
                 } else {
-                    println!("❌ Usage: add [sender] [receiver] [amount]");
+                    println!("❌ Usage: add [sender] [receiver] [amount] [password]");
                 }
             }
             input if input.starts_with("block ") => {
@@ -1444,7 +1723,7 @@ fn sign_transaction(sender: String, receiver: String, amount: f64, private_key_h
     println!("{}", "=".repeat(50));
 
     // Create transaction
-            let mut transaction = gillean::transaction::Transaction::new_transfer(sender.clone(), receiver.clone(), amount, message)?;
+            let mut transaction = gillean::transaction::Transaction::new_transfer(sender.clone(), receiver.clone(), units::to_base_units(amount)?, message)?;
     
     // Create keypair from private key
     let private_key_bytes = gillean::utils::hex_to_bytes(&private_key_hex)?;
@@ -1461,20 +1740,59 @@ fn sign_transaction(sender: String, receiver: String, amount: f64, private_key_h
     println!("💰 Amount: {} GIL", amount);
     println!("🔐 Signature: {}", transaction.get_signer_public_key().unwrap());
     println!("✅ Signature valid: {}", transaction.verify_signature()?);
+    println!("📦 Raw: {}", hex::encode(transaction.to_json()?));
 
     Ok(())
 }
 
-/// Connect to a peer
-async fn connect_to_peer(address: &str) -> Result<()> {
+/// Decode a hex-encoded JSON transaction into a readable report
+fn decode_transaction(raw: &str) -> Result<String> {
+    let bytes = hex::decode(raw.trim())
+        .map_err(|e| BlockchainError::InvalidInput(format!("Transaction is not valid hex: {}", e)))?;
+    let transaction: gillean::Transaction = serde_json::from_slice(&bytes)
+        .map_err(|e| BlockchainError::InvalidInput(format!("Bytes are not a transaction: {}", e)))?;
+
+    let structure = match transaction.validate() {
+        Ok(()) => "valid".to_string(),
+        Err(e) => format!("invalid ({})", e),
+    };
+    let signature = match transaction.verify_signature() {
+        Ok(true) => "valid".to_string(),
+        Ok(false) if !transaction.is_signed() => "missing".to_string(),
+        Ok(false) => "invalid".to_string(),
+        Err(e) => format!("invalid ({})", e),
+    };
+
+    let mut report = String::new();
+    report.push_str(&format!("📋 Transaction ID: {}\n", transaction.id));
+    report.push_str(&format!("🏷️  Type: {:?}\n", transaction.transaction_type));
+    report.push_str(&format!("👤 Sender: {}\n", transaction.sender));
+    report.push_str(&format!("👥 Receiver: {}\n", transaction.receiver));
+    report.push_str(&format!("💰 Amount: {} GIL\n", units::from_base_units(transaction.amount)));
+    report.push_str(&format!("🔢 Nonce: {}\n", transaction.nonce));
+    report.push_str(&format!("⛽ Fee: {} GIL\n", transaction.fee()));
+    report.push_str(&format!("🧱 Structure: {}\n", structure));
+    report.push_str(&format!("🔐 Signature: {}", signature));
+    Ok(report)
+}
+
+/// Connect to a peer and sync the local chain from it
+async fn connect_to_peer(blockchain: Blockchain, storage: &std::sync::Arc<BlockchainStorage>, address: &str) -> Result<()> {
     println!("\n🌐 Connecting to peer...");
     println!("{}", "=".repeat(50));
 
-    // This would require a network instance
-    // For now, just show a placeholder
     println!("🔗 Attempting to connect to: {}", address);
-    println!("⚠️  Network functionality requires a running network instance");
-    println!("💡 Use 'start-network' command to start a network server first");
+    let local_height = blockchain.blocks.len();
+    let blockchain = Arc::new(tokio::sync::Mutex::new(blockchain));
+    let monitor = Arc::new(tokio::sync::Mutex::new(BlockchainMonitor::new()));
+    let mut network = Network::new("127.0.0.1:0".to_string(), Arc::clone(&blockchain), monitor)?;
+    network.connect_to_peer(address).await?;
+
+    let blockchain = blockchain.lock().await;
+    storage.save_blockchain(&blockchain)?;
+
+    println!("✅ Connected to peer");
+    println!("📦 Chain height: {} (was {})", blockchain.blocks.len(), local_height);
 
     Ok(())
 }
@@ -1485,7 +1803,7 @@ async fn broadcast_transaction(sender: &str, receiver: &str, amount: f64, messag
     println!("{}", "=".repeat(50));
 
     // Create transaction
-            let transaction = gillean::transaction::Transaction::new_transfer(sender.to_string(), receiver.to_string(), amount, message)?;
+            let transaction = gillean::transaction::Transaction::new_transfer(sender.to_string(), receiver.to_string(), units::to_base_units(amount)?, message)?;
     
     println!("✅ Transaction created!");
     println!("📋 Transaction ID: {}", transaction.id);
@@ -1531,27 +1849,56 @@ fn monitor_blockchain(blockchain: &Blockchain) -> Result<()> {
     Ok(())
 }
 
-/// Start network server
-async fn start_network_server(address: &str) -> Result<()> {
+/// Start network server and serve the local chain to peers until interrupted
+async fn start_network_server(blockchain: Blockchain, address: &str) -> Result<()> {
     println!("\n🌐 Starting network server...");
     println!("{}", "=".repeat(50));
 
-    println!("🔗 Server will start on: {}", address);
-    println!("⚠️  Network server functionality is implemented but requires additional setup");
-    println!("💡 This would start a P2P network server for blockchain synchronization");
+    let height = blockchain.blocks.len();
+    let blockchain = Arc::new(tokio::sync::Mutex::new(blockchain));
+    let monitor = Arc::new(tokio::sync::Mutex::new(BlockchainMonitor::new()));
+    let mut network = Network::new(address.to_string(), blockchain, monitor)?;
+    network.start().await?;
+
+    println!("🔗 Listening on: {}", network.local_address());
+    println!("📦 Serving chain of {} blocks", height);
+    println!("💡 Press Ctrl+C to stop");
 
-    // In a real implementation, this would:
-    // 1. Create a blockchain instance
-    // 2. Create a monitor instance
-    // 3. Create a network instance
-    // 4. Start the network server
-    // 5. Keep it running
+    tokio::signal::ctrl_c().await?;
+    network.stop().await?;
+    println!("🛑 Network server stopped");
 
     Ok(())
 }
 
+/// Prune state snapshots to `storage` under the default memory high-water mark
+fn snapshot_pruning(storage: &Arc<BlockchainStorage>) -> SnapshotPruning {
+    let optimizer = MemoryOptimizer::from_config(&PerformanceConfig::default());
+    SnapshotPruning::new(Arc::new(optimizer), storage.clone())
+}
+
+/// Load the key a validator node signs PoS blocks with
+/// 
+/// # Arguments
+/// * `path` - File holding the hex-encoded 32 byte private key
+/// 
+/// # Returns
+/// * `Result<KeyPair>` - The validator's key pair or an error
+fn load_validator_key(path: &str) -> Result<KeyPair> {
+    let private_key_hex = std::fs::read_to_string(path)
+        .map_err(|e| BlockchainError::InvalidInput(format!("Cannot read validator key file {}: {}", path, e)))?;
+    let private_key_bytes = gillean::utils::hex_to_bytes(private_key_hex.trim())?;
+    KeyPair::from_private_key_bytes(&private_key_bytes)
+}
+
 /// Start REST API server
-async fn start_api_server(address: &str, db_path: &str) -> Result<()> {
+async fn start_api_server(
+    address: &str,
+    db_path: &str,
+    api_keys: Vec<String>,
+    rate_limit_per_minute: u32,
+    validator_key: Option<KeyPair>,
+) -> Result<()> {
     println!("\n🚀 Starting REST API server...");
     println!("{}", "=".repeat(50));
 
@@ -1559,7 +1906,7 @@ async fn start_api_server(address: &str, db_path: &str) -> Result<()> {
     let storage = std::sync::Arc::new(BlockchainStorage::new(db_path)?);
     
     // Load blockchain from storage, or create new one if empty
-    let blockchain = match Blockchain::with_storage(4, 50.0, &storage) {
+    let mut blockchain = match Blockchain::with_storage(4, 50.0, &storage) {
         Ok(bc) => {
             if bc.blocks.is_empty() {
                 info!("Storage is empty, creating new blockchain with genesis block");
@@ -1581,12 +1928,17 @@ async fn start_api_server(address: &str, db_path: &str) -> Result<()> {
         }
     };
     
+    blockchain.set_snapshot_pruning(snapshot_pruning(&storage));
+    if let Some(keypair) = validator_key {
+        blockchain.add_validator_signer(keypair)?;
+    }
+    
     // Initialize wallet manager with shared storage
     let mut wallet_manager = WalletManager::new();
-    wallet_manager.set_storage_path(db_path.to_string());
+    wallet_manager.set_shared_storage(storage.clone());
     
     // Initialize consensus and governance systems
-    let consensus = Arc::new(ProofOfStake::new(1000.0, 100, 5.0, 10.0)?);
+    let consensus = Arc::new(ProofOfStake::new(units::gil(1000), 100, 5.0, 10.0)?);
     let governance = Arc::new(Mutex::new(Governance::new(storage.clone(), consensus).await?));
     
     // Initialize DID system
@@ -1596,6 +1948,10 @@ async fn start_api_server(address: &str, db_path: &str) -> Result<()> {
     let ethereum_config = EthereumConfig::default();
     let ethereum_bridge = Arc::new(Mutex::new(EthereumBridge::new(ethereum_config, storage.clone()).await?));
     
+    if api_keys.is_empty() {
+        warn!("No API keys configured, mutating endpoints are unauthenticated");
+    }
+    
     // Create application state
     let state = AppState {
         blockchain: std::sync::Arc::new(std::sync::Mutex::new(blockchain)),
@@ -1604,6 +1960,9 @@ async fn start_api_server(address: &str, db_path: &str) -> Result<()> {
         did_system: Some(did_system),
         governance: Some(governance),
         simulation_manager: None, // TODO: Initialize when needed
+        zkp_manager: Some(Arc::new(Mutex::new(ZKPManager::new()))),
+        api_keys: Arc::new(api_keys.into_iter().collect()),
+        rate_limit_per_minute,
         storage: storage.clone(),
         storage_path: db_path.to_string(),
         start_time: std::time::Instant::now(),
@@ -1611,11 +1970,13 @@ async fn start_api_server(address: &str, db_path: &str) -> Result<()> {
 
     println!("🔗 API server starting on: {}", address);
     println!("📊 Available endpoints:");
-    println!("  GET  /chain                    - Get full blockchain");
+    println!("  GET  /chain                    - Get blocks (?offset=&limit=&order=)");
     println!("  GET  /chain/:start/:end        - Get block range");
     println!("  GET  /block/:index             - Get specific block");
     println!("  POST /transaction              - Add transaction");
     println!("  POST /transaction/signed       - Add signed transaction");
+    println!("  POST /transactions/batch       - Add a batch of transactions");
+    println!("  POST /transaction/simulate     - Preview balance changes of a transaction");
     println!("  GET  /balance/:address         - Get balance");
     println!("  POST /mine                     - Mine block");
     println!("  GET  /peers                    - List peers");
@@ -1625,7 +1986,14 @@ async fn start_api_server(address: &str, db_path: &str) -> Result<()> {
     println!("  GET  /wallet/:address/balance  - Get wallet balance");
     println!("  POST /transaction/send         - Send transaction");
     println!("  GET  /metrics                  - Get metrics");
+    println!("  GET  /metrics/prometheus       - Get metrics in Prometheus format");
     println!("  GET  /health                   - Health check");
+    println!("  GET  /mempool                  - Inspect pending transactions (?sender=&min_fee=&limit=)");
+    println!("  GET  /ws                       - Stream real-time events");
+    println!("  POST /rpc                      - JSON-RPC 2.0 endpoint");
+    println!("  POST /contract/deploy          - Deploy a contract (hex or base64 code)");
+    println!("  GET  /contract/:address        - Get contract metadata");
+    println!("  GET  /contract/:address/storage - Get contract storage");
     println!();
     println!("💡 Try: curl http://{}/health", address);
 
@@ -1648,7 +2016,7 @@ fn create_wallet(password: &str, name: Option<String>) -> Result<()> {
     // Initialize wallet manager
     let mut wallet_manager = WalletManager::with_storage("./data/blockchain_db".to_string());
 
-    let wallet_info = wallet_manager.create_wallet(password, name)?;
+    let wallet_info = wallet_manager.create_wallet(password, name, None)?;
 
     println!("✅ Wallet created successfully!");
     println!("📋 Wallet ID: {}", wallet_info.id);
@@ -1701,12 +2069,13 @@ fn send_transaction(from: &str, to: &str, amount: f64, password: &str, message:
     let mut transaction = gillean::transaction::Transaction::new_transfer(
         from.to_string(),
         to.to_string(),
-        amount,
+        units::to_base_units(amount)?,
         message.clone(),
     )?;
+    transaction.set_nonce(blockchain.next_nonce(from));
 
     // Sign transaction
-    let transaction_data = transaction.to_bytes()?;
+    let transaction_data = transaction.canonical_bytes()?;
     let signature = wallet_manager.sign_transaction(from, password, &transaction_data)?;
 
     // Set signature
@@ -1844,6 +2213,7 @@ fn compile_contract(source_file: &str, contract_name: &str) -> Result<()> {
         test_gas_limit: 1_000_000,
         compilation_timeout: 60,
         test_timeout: 30,
+        contract_crate_path: "contracts/gillean-contract".to_string(),
     };
 
     let mut toolkit = ContractToolkit::new(config)?;
@@ -1886,6 +2256,7 @@ fn test_contract(contract_name: &str, test_data: Option<String>) -> Result<()> {
         test_gas_limit: 1_000_000,
         compilation_timeout: 60,
         test_timeout: 30,
+        contract_crate_path: "contracts/gillean-contract".to_string(),
     };
 
     let mut toolkit = ContractToolkit::new(config)?;
@@ -1927,6 +2298,7 @@ fn deploy_wasm_contract(contract_name: &str, private_key: &str) -> Result<()> {
         test_gas_limit: 1_000_000,
         compilation_timeout: 60,
         test_timeout: 30,
+        contract_crate_path: "contracts/gillean-contract".to_string(),
     };
 
     let mut toolkit = ContractToolkit::new(config)?;
@@ -2000,6 +2372,7 @@ fn show_bridge_status() -> Result<()> {
     println!("✅ Completed transactions: {}", stats.completed_transactions);
     println!("🔗 External chains: {}", stats.external_chains);
     println!("🔑 Operator public key: {}", stats.operator_public_key);
+    println!("🛰️  Relayers: {} ({} signatures required)", stats.relayer_count, stats.relayer_threshold);
     println!();
     
     // Show external chains
@@ -2032,6 +2405,7 @@ fn show_contract_templates() -> Result<()> {
         test_gas_limit: 1_000_000,
         compilation_timeout: 60,
         test_timeout: 30,
+        contract_crate_path: "contracts/gillean-contract".to_string(),
     };
 
     let toolkit = ContractToolkit::new(config)?;
@@ -2386,8 +2760,24 @@ async fn connect_ethereum(testnet: &str, rpc_url: Option<String>) -> Result<()>
     Ok(())
 }
 
+/// Create an Ethereum config, overriding the RPC URL if given
+fn ethereum_config(rpc_url: Option<String>) -> EthereumConfig {
+    let mut config = EthereumConfig::default();
+    if let Some(rpc_url) = rpc_url {
+        config.rpc_url = rpc_url;
+    }
+    config
+}
+
 /// Transfer tokens to Ethereum
-async fn transfer_to_ethereum(from: &str, to: &str, amount: f64, _password: &str) -> Result<()> {
+async fn transfer_to_ethereum(
+    from: &str,
+    to: &str,
+    amount: f64,
+    password: &str,
+    rpc_url: Option<String>,
+    ethereum_key: Option<String>,
+) -> Result<()> {
     println!("\n🌉 Transferring to Ethereum");
     println!("{}", "=".repeat(50));
 
@@ -2395,22 +2785,63 @@ async fn transfer_to_ethereum(from: &str, to: &str, amount: f64, _password: &str
     println!("📥 To Ethereum: {}", to);
     println!("💰 Amount: {} GIL", amount);
 
-    // In a real implementation, this would use the EthereumBridge
-    println!("⏳ Processing transfer...");
-    println!("✅ Transfer initiated successfully!");
-    println!("🆔 Transfer ID: {}", uuid::Uuid::new_v4());
+    // Initialize storage
+    let storage = std::sync::Arc::new(BlockchainStorage::new("./data/blockchain_db")?);
+    let blockchain = Mutex::new(Blockchain::with_storage(4, 50.0, &storage)?);
+    let mut wallet_manager = WalletManager::new();
+    wallet_manager.set_shared_storage(storage.clone());
+    if let Some(key) = ethereum_key {
+        wallet_manager.import_ethereum_key(from, password, &gillean::utils::hex_to_bytes(key.trim_start_matches("0x"))?)?;
+    }
+    let bridge = EthereumBridge::new(ethereum_config(rpc_url), storage.clone()).await?;
+
+    println!("⏳ Locking GIL...");
+    let transfer_id = bridge.transfer_to_ethereum(&mut wallet_manager, &blockchain, from, to, amount, password).await?;
+    storage.save_blockchain(&blockchain.lock().unwrap())?;
+
+    println!("✅ Lock transaction submitted!");
+    println!("🆔 Transfer ID: {}", transfer_id);
+    println!("⏳ Run complete-ethereum-transfer once the lock is mined");
+
+    Ok(())
+}
+
+/// Send the Ethereum side of a transfer once its GIL lock is mined
+async fn complete_ethereum_transfer(transfer_id: &str, password: &str, rpc_url: Option<String>) -> Result<()> {
+    println!("\n🌉 Completing Ethereum Transfer");
+    println!("{}", "=".repeat(50));
+
+    let storage = std::sync::Arc::new(BlockchainStorage::new("./data/blockchain_db")?);
+    let blockchain = Mutex::new(Blockchain::with_storage(4, 50.0, &storage)?);
+    let mut wallet_manager = WalletManager::new();
+    wallet_manager.set_shared_storage(storage.clone());
+    let bridge = EthereumBridge::new(ethereum_config(rpc_url), storage).await?;
+
+    let status = bridge.complete_transfer(&mut wallet_manager, &blockchain, transfer_id, password).await?;
+    let transfer = bridge.get_pending_transfers().await?
+        .into_iter()
+        .find(|t| t.id == transfer_id);
+
+    println!("🆔 Transfer ID: {}", transfer_id);
+    println!("📊 Status: {:?}", status);
+    if let Some(tx_hash) = transfer.and_then(|t| t.ethereum_tx_hash) {
+        println!("🔗 Ethereum Tx: {}", tx_hash);
+    }
 
     Ok(())
 }
 
 /// Get Ethereum balance
-async fn get_ethereum_balance(address: &str) -> Result<()> {
+async fn get_ethereum_balance(address: &str, rpc_url: Option<String>) -> Result<()> {
     println!("\n💰 Ethereum Balance");
     println!("{}", "=".repeat(50));
 
+    let storage = std::sync::Arc::new(BlockchainStorage::new("./data/blockchain_db")?);
+    let bridge = EthereumBridge::new(ethereum_config(rpc_url), storage).await?;
+    let balance = bridge.get_ethereum_balance(address).await?;
+
     println!("📍 Address: {}", address);
-    println!("💰 Balance: 0.0 ETH (simulated)");
-    println!("⚠️  Note: This is a simulated response");
+    println!("💰 Balance: {} ETH", balance);
 
     Ok(())
 }
@@ -2430,17 +2861,30 @@ async fn get_ethereum_transfer_status(transfer_id: &str) -> Result<()> {
 // DID Handlers
 
 /// Create a new DID
-async fn create_did(controller: Option<String>) -> Result<()> {
+async fn create_did(controller: Option<String>, password: &str) -> Result<()> {
     println!("\n🆔 Creating Decentralized Identity");
     println!("{}", "=".repeat(50));
 
-    let did = format!("did:gillean:{}", hex::encode([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]));
+    let storage = std::sync::Arc::new(BlockchainStorage::new("./data/blockchain_db")?);
+    let did_system = DecentralizedIdentity::new(storage.clone()).await?;
+    let request = gillean::did::DIDCreationRequest {
+        controller: controller.clone(),
+        service_endpoints: vec![],
+    };
+    let (did, signing_key) = did_system.create_did(request).await?;
+
+    // The key is only kept encrypted in the wallet storage, never printed
+    let mut wallet_manager = WalletManager::new();
+    wallet_manager.set_shared_storage(storage);
+    let keypair = KeyPair::from_private_key_bytes(&signing_key.to_bytes())?;
+    let wallet_info = wallet_manager.store_keypair(keypair, password, Some(did.clone()), None)?;
     
     println!("🆔 DID: {}", did);
     if let Some(ctrl) = controller {
         println!("👤 Controller: {}", ctrl);
     }
-    println!("🔑 Keypair generated successfully");
+    println!("🔑 Public key: {}", wallet_info.public_key);
+    println!("👛 Key wallet: {}", wallet_info.address);
     println!("✅ DID created successfully!");
 
     Ok(())
@@ -2454,7 +2898,19 @@ async fn verify_did(did: &str, message: &str, signature: &str) -> Result<()> {
     println!("🆔 DID: {}", did);
     println!("📝 Message: {}", message);
     println!("✍️  Signature: {}", signature);
-    println!("✅ Signature verification: VALID (simulated)");
+
+    let signature_bytes = gillean::utils::hex_to_bytes(signature)?;
+    let did_system = open_did_system().await?;
+    let result = did_system.verify_did_signature(did, message.as_bytes(), &signature_bytes).await?;
+
+    if result.is_valid {
+        println!("✅ Signature verification: VALID");
+    } else {
+        println!("❌ Signature verification: INVALID");
+        if let Some(error) = result.error_message {
+            println!("   {}", error);
+        }
+    }
 
     Ok(())
 }
@@ -2464,6 +2920,9 @@ async fn link_did(did: &str, wallet_address: &str) -> Result<()> {
     println!("\n🔗 Linking DID to Wallet");
     println!("{}", "=".repeat(50));
 
+    let did_system = open_did_system().await?;
+    did_system.link_did_to_wallet(did, wallet_address).await?;
+
     println!("🆔 DID: {}", did);
     println!("👛 Wallet: {}", wallet_address);
     println!("✅ DID linked to wallet successfully!");
@@ -2476,12 +2935,23 @@ async fn get_did_for_wallet(wallet_address: &str) -> Result<()> {
     println!("\n🔍 Getting DID for Wallet");
     println!("{}", "=".repeat(50));
 
+    let did_system = open_did_system().await?;
+
     println!("👛 Wallet: {}", wallet_address);
-    println!("🆔 DID: did:gillean:1234567890abcdef (simulated)");
+    match did_system.get_did_for_wallet(wallet_address).await? {
+        Some(did) => println!("🆔 DID: {}", did),
+        None => println!("❌ No DID linked to this wallet"),
+    }
 
     Ok(())
 }
 
+/// Open the DID system backed by the default database
+async fn open_did_system() -> Result<DecentralizedIdentity> {
+    let storage = std::sync::Arc::new(BlockchainStorage::new("./data/blockchain_db")?);
+    DecentralizedIdentity::new(storage).await
+}
+
 // Governance Handlers
 
 /// Create a governance proposal
@@ -2533,7 +3003,23 @@ async fn execute_proposal(proposal_id: &str) -> Result<()> {
     println!("\n⚡ Executing Governance Proposal");
     println!("{}", "=".repeat(50));
 
+    let storage = Arc::new(BlockchainStorage::new("./data/blockchain_db")?);
+    let blockchain = Mutex::new(Blockchain::with_storage(4, 50.0, &storage)?);
+    let consensus = Arc::new(ProofOfStake::new(units::gil(1000), 100, 5.0, 10.0)?);
+    let governance = Governance::new(storage.clone(), consensus).await?;
+
     println!("🆔 Proposal ID: {}", proposal_id);
+    governance.execute_proposal(proposal_id, &blockchain).await?;
+
+    let stats = {
+        let blockchain = blockchain.lock().unwrap();
+        storage.save_blockchain(&blockchain)?;
+        blockchain.get_stats()
+    };
+
+    println!("⛏️  Difficulty: {}", stats.difficulty);
+    println!("💰 Mining Reward: {} GIL", stats.mining_reward);
+    println!("📊 Default Quorum: {}%", governance.default_quorum().await);
     println!("✅ Proposal executed successfully!");
 
     Ok(())
@@ -2578,13 +3064,26 @@ async fn run_simulation(config_file: &str) -> Result<()> {
 
     println!("📁 Config File: {}", config_file);
     println!("🎯 Loading simulation configuration...");
-    println!("🚀 Starting simulation...");
-    println!("⏳ Running for 100 blocks...");
-    println!("📊 Generating transactions...");
-    println!("🔒 Processing ZKP transactions...");
-    println!("🌉 Simulating cross-chain transfers...");
-    println!("🗳️  Running governance scenarios...");
-    println!("✅ Simulation completed successfully!");
+    let config = SimulationConfig::from_toml_file(config_file)?;
+
+    // Simulations run against an in-memory blockchain and throwaway storage, never the node's database
+    let storage_dir = tempfile::tempdir()?;
+    let storage = Arc::new(BlockchainStorage::new(storage_dir.path())?);
+    let blockchain = Arc::new(Mutex::new(Blockchain::new_pow(2, 50.0)?));
+
+    println!("🚀 Starting simulation (seed {})...", config.seed);
+    println!("⏳ Running for {} blocks...", config.duration_blocks);
+    let simulation = SimulationManager::new(storage, blockchain, config).await?;
+    let result = simulation.run_simulation().await?;
+
+    let metrics = &result.metrics;
+    println!("✅ Simulation completed in {:.2} seconds", result.duration_seconds);
+    println!("📦 Blocks Mined: {}", metrics.total_blocks);
+    println!("💸 Transactions: {} ({} failed)", metrics.total_transactions, metrics.failed_transactions);
+    println!("⏱️  Average Block Time: {:.2} s", metrics.average_block_time);
+    println!("📈 Throughput: {:.2} tx/s", metrics.average_transaction_throughput);
+
+    std::fs::write("simulation_results.json", serde_json::to_string_pretty(&result)?)?;
     println!("📈 Results saved to simulation_results.json");
 
     Ok(())
@@ -2651,16 +3150,136 @@ mod tests {
 
     #[test]
     fn test_demo_creation() {
+        let alice = KeyPair::generate().unwrap();
         let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
-        blockchain.balances.insert("alice".to_string(), 1000.0);
+        blockchain.balances.insert(gillean::crypto::create_address(&alice.public_key()), units::gil(1000));
         
         // Test adding a transaction
         let result = blockchain.add_transaction(
-            "alice".to_string(),
+            &alice,
             "bob".to_string(),
-            100.0,
+            units::gil(100),
             None,
         );
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_decode_transaction() {
+        let keypair = KeyPair::generate().unwrap();
+        let mut transaction = gillean::Transaction::new_transfer(
+            "alice".to_string(), "bob".to_string(), units::to_base_units(12.5).unwrap(), None,
+        ).unwrap();
+        transaction.set_nonce(3);
+        transaction.sign(&keypair).unwrap();
+        let raw = hex::encode(transaction.to_json().unwrap());
+
+        let report = decode_transaction(&raw).unwrap();
+        assert!(report.contains(&format!("Transaction ID: {}", transaction.id)));
+        assert!(report.contains("Type: Transfer"));
+        assert!(report.contains("Sender: alice"));
+        assert!(report.contains("Receiver: bob"));
+        assert!(report.contains("Amount: 12.5 GIL"));
+        assert!(report.contains("Nonce: 3"));
+        assert!(report.contains("Fee: 0 GIL"));
+        assert!(report.contains("Structure: valid"));
+        assert!(report.contains("Signature: valid"));
+
+        // Tampering after signing invalidates the signature
+        transaction.set_nonce(4);
+        let report = decode_transaction(&hex::encode(transaction.to_json().unwrap())).unwrap();
+        assert!(report.contains("Signature: invalid"));
+
+        assert!(matches!(decode_transaction("zz"), Err(BlockchainError::InvalidInput(_))));
+        assert!(matches!(decode_transaction("7b7d"), Err(BlockchainError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_benchmark_reports_throughput() {
+        let cli = Cli::try_parse_from(["gillean", "benchmark", "--transactions", "25", "--concurrency", "3"]).unwrap();
+        assert!(matches!(cli.command, Some(Commands::Benchmark { transactions: 25, concurrency: 3 })));
+
+        let report = run_benchmark(1, 50.0, 25, 3).unwrap();
+        assert_eq!(report.transactions, 25);
+        assert!(report.blocks >= 1);
+        assert!(report.tps > 0.0);
+        assert!(report.to_string().contains("TPS"));
+
+        assert!(matches!(run_benchmark(1, 50.0, 0, 3), Err(BlockchainError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_export_import_chain_round_trip() {
+        let alice_key = KeyPair::generate().unwrap();
+        let alice = gillean::crypto::create_address(&alice_key.public_key());
+        let carol_key = KeyPair::generate().unwrap();
+        let carol = gillean::crypto::create_address(&carol_key.public_key());
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = dir.path().join("chain.snapshot");
+        let snapshot = snapshot.to_str().unwrap();
+
+        let mut source = Blockchain::new_pow(1, 50.0).unwrap();
+        source.balances.insert(alice.clone(), units::gil(1000));
+        source.add_transaction(&alice_key, "bob".to_string(), units::gil(100), None).unwrap();
+        source.mine_block("miner".to_string()).unwrap();
+        export_chain(&source, snapshot).unwrap();
+
+        let storage = std::sync::Arc::new(BlockchainStorage::new(dir.path().join("db")).unwrap());
+        let mut target = Blockchain::new_pow(1, 50.0).unwrap();
+        import_chain(&mut target, &storage, snapshot, false).unwrap();
+        assert_eq!(target.blocks, source.blocks);
+        assert_eq!(target.balances, source.balances);
+
+        let persisted = storage.load_blockchain(1, 50.0).unwrap();
+        assert_eq!(persisted.blocks, source.blocks);
+
+        // A chain with blocks beyond genesis is only replaced with --force
+        let mut other = Blockchain::new_pow(1, 50.0).unwrap();
+        other.balances.insert(carol.clone(), units::gil(10));
+        for _ in 0..2 {
+            other.add_transaction(&carol_key, "dave".to_string(), units::gil(1), None).unwrap();
+            other.mine_block("miner".to_string()).unwrap();
+        }
+        other.export_snapshot(snapshot).unwrap();
+        assert!(matches!(
+            import_chain(&mut target, &storage, snapshot, false),
+            Err(BlockchainError::InvalidState(_))
+        ));
+        assert_eq!(target.blocks, source.blocks);
+
+        import_chain(&mut target, &storage, snapshot, true).unwrap();
+        assert_eq!(target.blocks, other.blocks);
+        assert_eq!(storage.load_blockchain(1, 50.0).unwrap().blocks, other.blocks);
+    }
+
+    #[test]
+    fn test_load_validator_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("validator.key");
+        let path = path.to_str().unwrap();
+        let keypair = KeyPair::generate().unwrap();
+        std::fs::write(path, format!("{}\n", keypair.private_key_hex())).unwrap();
+
+        let cli = Cli::try_parse_from(["gillean", "--validator-key-file", path, "start-network"]).unwrap();
+        assert_eq!(cli.validator_key_file.as_deref(), Some(path));
+
+        let loaded = load_validator_key(path).unwrap();
+        assert_eq!(loaded.public_key_hex(), keypair.public_key_hex());
+
+        // The loaded key signs the blocks of the validator registered with it
+        let mut blockchain = Blockchain::new_pos(50.0, units::gil(100), 5).unwrap();
+        assert!(blockchain.add_validator_signer(load_validator_key(path).unwrap()).is_err());
+        let mut blockchain = Blockchain::new_pos_with_validators(50.0, units::gil(100), 5, vec![
+            (keypair.public_key_hex(), "alice".to_string(), units::gil(1000)),
+        ]).unwrap();
+        blockchain.add_validator_signer(loaded).unwrap();
+        let sender = KeyPair::generate().unwrap();
+        blockchain.balances.insert(gillean::crypto::create_address(&sender.public_key()), units::gil(10));
+        blockchain.add_transaction(&sender, "bob".to_string(), units::gil(1), None).unwrap();
+        assert_eq!(blockchain.mine_block("miner".to_string()).unwrap().validator.as_deref(), Some("alice"));
+
+        std::fs::write(path, "not hex").unwrap();
+        assert!(load_validator_key(path).is_err());
+        assert!(load_validator_key(dir.path().join("missing").to_str().unwrap()).is_err());
+    }
 }
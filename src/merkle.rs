@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use log::debug;
-use crate::{Result, BlockchainError, Transaction, utils};
+use crate::{Result, BlockchainError, Transaction, utils, block::BlockHeader};
 
 /// Represents a node in the Merkle tree
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -41,10 +41,10 @@ impl MerkleTree {
     /// # Example
     /// ```
     /// use gillean::merkle::MerkleTree;
-    /// use gillean::transaction::Transaction;
+    /// use gillean::{transaction::Transaction, units};
     /// 
-    /// let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
-/// let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), 50.0, None).unwrap();
+    /// let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
+    /// let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), units::gil(50), None).unwrap();
     /// let tree = MerkleTree::new(&[tx1, tx2]).unwrap();
     /// 
     /// assert!(tree.root.is_some());
@@ -93,9 +93,9 @@ impl MerkleTree {
     /// # Example
     /// ```
     /// use gillean::merkle::MerkleTree;
-    /// use gillean::transaction::Transaction;
+    /// use gillean::{transaction::Transaction, units};
     /// 
-    /// let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
+    /// let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
     /// let tree = MerkleTree::new(&[tx.clone()]).unwrap();
     /// let proof = tree.generate_proof(0).unwrap();
     /// 
@@ -119,20 +119,8 @@ impl MerkleTree {
             ));
         }
 
-        // For simplified implementation, just check if transaction hash matches any leaf
-        let tx_json = transaction.to_json()?;
-        let tx_hash = utils::calculate_hash(tx_json);
-        
-        // This is a simplified verification - in a real implementation,
-        // we would use the proof path to verify inclusion
-        if proof.path.is_empty() && self.leaf_count == 1 {
-            // Single transaction case
-            return Ok(self.root_hash().unwrap() == tx_hash);
-        }
-        
-        // For multiple transactions, return true for valid indices
-        // This is a placeholder for the full implementation
-        Ok(index < self.leaf_count)
+        let tx_hash = transaction_hash(transaction)?;
+        Ok(self.root_hash() == Some(proof.compute_root(&tx_hash)))
     }
 
     /// Generate a Merkle proof for a transaction at the given index
@@ -155,10 +143,28 @@ impl MerkleTree {
             ));
         }
 
-        // For now, return an empty proof for single transactions
-        // This is a simplified implementation
-        let proof = MerkleProof { path: Vec::new() };
-        Ok(proof)
+        // Every level is padded to an even width, so the bits of the index
+        // select the child at each level from the root down
+        let mut path = Vec::with_capacity(self.height);
+        let mut node = self.root.as_ref().unwrap();
+        for level in (0..self.height).rev() {
+            let (Some(left), Some(right)) = (&node.left, &node.right) else {
+                return Err(BlockchainError::BlockValidationFailed(
+                    "Merkle tree is malformed".to_string(),
+                ));
+            };
+
+            if (index >> level) & 1 == 1 {
+                path.push((left.hash.clone(), false));
+                node = right;
+            } else {
+                path.push((right.hash.clone(), true));
+                node = left;
+            }
+        }
+        path.reverse();
+
+        Ok(MerkleProof { path, header: None })
     }
 
     /// Get the size of the tree in bytes (approximate)
@@ -182,16 +188,37 @@ impl MerkleTree {
 /// 
 /// Contains the path of hashes from a leaf to the root,
 /// along with information about whether each step is a left or right sibling.
+/// Proofs issued for light clients also carry the header of the containing block.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MerkleProof {
     /// Path of (hash, is_right_sibling) pairs
     pub path: Vec<(String, bool)>,
+    /// Header of the block containing the transaction
+    #[serde(default)]
+    pub header: Option<BlockHeader>,
 }
 
 impl MerkleProof {
     /// Create an empty Merkle proof
     pub fn new() -> Self {
-        MerkleProof { path: Vec::new() }
+        MerkleProof { path: Vec::new(), header: None }
+    }
+
+    /// Compute the Merkle root implied by this proof for a leaf
+    /// 
+    /// # Arguments
+    /// * `leaf_hash` - Hash of the transaction at the leaf
+    /// 
+    /// # Returns
+    /// * `String` - The root hash reached by following the path
+    pub fn compute_root(&self, leaf_hash: &str) -> String {
+        self.path.iter().fold(leaf_hash.to_string(), |hash, (sibling, is_right)| {
+            if *is_right {
+                utils::calculate_hash_concat(&[&hash, sibling])
+            } else {
+                utils::calculate_hash_concat(&[sibling, &hash])
+            }
+        })
     }
 
     /// Get the size of the proof in bytes
@@ -217,13 +244,44 @@ impl Default for MerkleProof {
     }
 }
 
+/// Verify that a transaction is included in a block knowing only its header hash
+/// 
+/// The proof must carry the block header. The transaction's Merkle path has to
+/// lead to the header's Merkle root, and the header has to hash to
+/// `block_header_hash`.
+/// 
+/// # Arguments
+/// * `block_header_hash` - Hash of the block the transaction claims to be in
+/// * `proof` - Inclusion proof from `Blockchain::inclusion_proof`
+/// * `tx_hash` - Hash of the transaction, see `transaction_hash`
+/// 
+/// # Returns
+/// * `bool` - True if the transaction is included in the block
+pub fn verify_inclusion(block_header_hash: &str, proof: &MerkleProof, tx_hash: &str) -> bool {
+    let Some(header) = &proof.header else {
+        return false;
+    };
+
+    proof.compute_root(tx_hash) == header.merkle_root && header.hash() == block_header_hash
+}
+
+/// Hash a transaction as a Merkle leaf
+/// 
+/// # Arguments
+/// * `transaction` - The transaction to hash
+/// 
+/// # Returns
+/// * `Result<String>` - The leaf hash or an error
+pub fn transaction_hash(transaction: &Transaction) -> Result<String> {
+    Ok(utils::calculate_hash(transaction.to_json()?))
+}
+
 /// Create leaf nodes from transactions
 fn create_leaves(transactions: &[Transaction]) -> Result<Vec<MerkleNode>> {
     let mut leaves = Vec::new();
 
     for transaction in transactions {
-        let tx_json = transaction.to_json()?;
-        let hash = utils::calculate_hash(tx_json);
+        let hash = transaction_hash(transaction)?;
         
         leaves.push(MerkleNode {
             hash,
@@ -281,11 +339,12 @@ fn calculate_height(leaf_count: usize) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::units;
 
     #[test]
     fn test_merkle_tree_creation() {
-        let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
-        let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), 50.0, None).unwrap();
+        let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
+        let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), units::gil(50), None).unwrap();
         
         let tree = MerkleTree::new(&[tx1, tx2]).unwrap();
         
@@ -307,7 +366,7 @@ mod tests {
 
     #[test]
     fn test_single_transaction_tree() {
-        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
+        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
         let tree = MerkleTree::new(std::slice::from_ref(&tx)).unwrap();
         
         assert!(tree.root.is_some());
@@ -320,9 +379,9 @@ mod tests {
 
     #[test]
     fn test_multiple_transactions_tree() {
-        let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
-        let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), 50.0, None).unwrap();
-        let tx3 = Transaction::new_transfer("charlie".to_string(), "alice".to_string(), 25.0, None).unwrap();
+        let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
+        let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), units::gil(50), None).unwrap();
+        let tx3 = Transaction::new_transfer("charlie".to_string(), "alice".to_string(), units::gil(25), None).unwrap();
         
         let tree = MerkleTree::new(&[tx1.clone(), tx2.clone(), tx3.clone()]).unwrap();
         
@@ -338,8 +397,8 @@ mod tests {
 
     #[test]
     fn test_invalid_proof() {
-        let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
-        let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), 50.0, None).unwrap();
+        let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
+        let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), units::gil(50), None).unwrap();
         
         let tree = MerkleTree::new(&[tx1.clone(), tx2]).unwrap();
         let proof = tree.generate_proof(0).unwrap();
@@ -348,6 +407,24 @@ mod tests {
         assert!(tree.verify_transaction(&tx1, &proof, 10).is_err());
     }
 
+    #[test]
+    fn test_proof_paths() {
+        let transactions: Vec<Transaction> = (0..5)
+            .map(|i| Transaction::new_transfer("alice".to_string(), format!("user{}", i), units::gil(10), None).unwrap())
+            .collect();
+        let tree = MerkleTree::new(&transactions).unwrap();
+
+        for (i, tx) in transactions.iter().enumerate() {
+            let proof = tree.generate_proof(i).unwrap();
+            assert_eq!(proof.path.len(), tree.height);
+            assert!(tree.verify_transaction(tx, &proof, i).unwrap());
+        }
+
+        // A proof for one transaction does not verify another
+        let proof = tree.generate_proof(0).unwrap();
+        assert!(!tree.verify_transaction(&transactions[1], &proof, 1).unwrap());
+    }
+
     #[test]
     fn test_merkle_proof_size() {
         let proof = MerkleProof::new();
@@ -355,6 +432,7 @@ mod tests {
         
         let proof = MerkleProof {
             path: vec![("hash1".to_string(), false), ("hash2".to_string(), true)],
+            header: None,
         };
         assert!(proof.size() > 0);
     }
@@ -414,6 +414,7 @@ impl BlockchainMonitor {
         HealthStatus {
             status: status.to_string(),
             issues,
+            checks: Vec::new(),
             timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
         }
     }
@@ -432,10 +433,65 @@ pub struct HealthStatus {
     pub status: String,
     /// List of health issues
     pub issues: Vec<String>,
+    /// Results of the individual subsystem checks
+    #[serde(default)]
+    pub checks: Vec<SubsystemCheck>,
     /// Timestamp of the health check
     pub timestamp: u64,
 }
 
+impl HealthStatus {
+    /// Build a health status from subsystem check results
+    /// 
+    /// The status is "unhealthy" if a critical check failed, "degraded" if only
+    /// non-critical checks failed and "healthy" otherwise.
+    /// 
+    /// # Arguments
+    /// * `checks` - The subsystem check results
+    /// 
+    /// # Returns
+    /// * `HealthStatus` - The combined health status
+    pub fn from_checks(checks: Vec<SubsystemCheck>) -> Self {
+        let issues: Vec<String> = checks
+            .iter()
+            .filter(|check| !check.healthy)
+            .map(|check| format!("{}: {}", check.name, check.detail))
+            .collect();
+        let status = if checks.iter().any(|check| check.critical && !check.healthy) {
+            "unhealthy"
+        } else if !issues.is_empty() {
+            "degraded"
+        } else {
+            "healthy"
+        };
+
+        HealthStatus {
+            status: status.to_string(),
+            issues,
+            checks,
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+        }
+    }
+
+    /// Check whether no critical subsystem check failed
+    pub fn is_healthy(&self) -> bool {
+        self.status != "unhealthy"
+    }
+}
+
+/// Result of checking a single subsystem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubsystemCheck {
+    /// Subsystem name
+    pub name: String,
+    /// Whether the check passed
+    pub healthy: bool,
+    /// Whether a failure makes the node unhealthy
+    pub critical: bool,
+    /// Details about the result
+    pub detail: String,
+}
+
 /// Initialize metrics for the application
 pub fn init_metrics() {
     // Initialize Prometheus metrics
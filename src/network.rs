@@ -1,16 +1,32 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use std::time::{Duration, Instant};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::{TcpListener as TokioTcpListener, TcpStream as TokioTcpStream};
+use tokio::net::tcp::OwnedWriteHalf;
 use tokio::sync::mpsc;
 use tokio::time::timeout;
 use log::{info, debug, warn, error};
 use crate::{Result, BlockchainError, Blockchain, Block, Transaction, BlockchainMonitor};
 
+/// Maximum number of blocks returned for a single `GetBlocks` request
+pub const SYNC_BATCH_SIZE: u64 = 50;
+
+/// Timeout for a request/response exchange with a peer
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default maximum size in bytes of a single message frame accepted from a peer
+pub const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// Size in bytes of the big-endian length prefix in front of every message
+pub const FRAME_HEADER_SIZE: usize = 4;
+
+/// Maximum number of transaction IDs remembered for gossip deduplication
+pub const MAX_SEEN_TRANSACTIONS: usize = 100_000;
+
 /// Network message types for P2P communication
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum NetworkMessage {
@@ -30,6 +46,24 @@ pub enum NetworkMessage {
     PeerDiscovery,
     /// Peer discovery response
     PeerList(Vec<String>),
+    /// Request for the peer's chain height and cumulative difficulty
+    GetChainInfo,
+    /// Chain height and cumulative difficulty of the responding node
+    ChainInfo {
+        /// Number of blocks in the chain
+        height: u64,
+        /// Cumulative difficulty of the chain
+        cumulative_difficulty: u64,
+    },
+    /// Request for the blocks with indices in `from..to`
+    GetBlocks {
+        /// Index of the first block
+        from: u64,
+        /// Index after the last block
+        to: u64,
+    },
+    /// Blocks returned for a `GetBlocks` request, at most `SYNC_BATCH_SIZE`
+    Blocks(Vec<Block>),
 }
 
 /// Network peer information
@@ -43,6 +77,126 @@ pub struct Peer {
     pub connected: bool,
     /// Peer version
     pub version: String,
+    /// Reputation score, lowered by protocol violations
+    #[serde(default)]
+    pub score: i32,
+}
+
+/// Protocol violations that lower a peer's score
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerViolation {
+    /// Sent a block that fails validation
+    InvalidBlock,
+    /// Sent a transaction with a signature that does not verify
+    InvalidSignature,
+    /// Sent a message frame larger than the configured maximum frame size
+    OversizedMessage,
+}
+
+impl PeerViolation {
+    /// Score deducted for this violation
+    pub fn penalty(&self) -> i32 {
+        match self {
+            PeerViolation::InvalidBlock => 25,
+            PeerViolation::InvalidSignature => 20,
+            PeerViolation::OversizedMessage => 10,
+        }
+    }
+}
+
+/// When misbehaving peers are banned and for how long
+#[derive(Debug, Clone, Copy)]
+pub struct BanPolicy {
+    /// A peer is banned once its score drops to or below this value
+    pub threshold: i32,
+    /// How long a ban lasts
+    pub duration: Duration,
+}
+
+impl Default for BanPolicy {
+    fn default() -> Self {
+        Self {
+            threshold: -100,
+            duration: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// Why a message frame could not be read
+#[derive(Debug)]
+enum FrameError {
+    /// The length prefix announced a payload above the maximum frame size
+    Oversized(usize),
+    /// The connection failed or closed in the middle of a frame
+    Io(std::io::Error),
+}
+
+/// Scores and bans of peer hosts
+/// 
+/// Tracked per host rather than per address because inbound connections
+/// arrive from ephemeral ports. `Peer::score` mirrors the score of its host.
+#[derive(Debug, Default)]
+struct PeerReputation {
+    policy: BanPolicy,
+    scores: HashMap<String, i32>,
+    banned: HashMap<String, Instant>,
+}
+
+impl PeerReputation {
+    /// Check whether a host is banned, forgetting bans that have expired
+    fn is_banned(&mut self, host: &str) -> bool {
+        match self.banned.get(host) {
+            Some(until) if *until > Instant::now() => true,
+            Some(_) => {
+                self.banned.remove(host);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Ban a host for the configured duration
+    fn ban(&mut self, host: &str) {
+        self.scores.remove(host);
+        self.banned.insert(host.to_string(), Instant::now() + self.policy.duration);
+    }
+}
+
+/// Recently accepted transaction IDs, used to stop gossip loops
+/// 
+/// Only the newest `capacity` IDs are kept; the oldest is forgotten first.
+#[derive(Debug)]
+struct SeenTransactions {
+    capacity: usize,
+    ids: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl SeenTransactions {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ids: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.ids.contains(id)
+    }
+
+    /// Remember an ID, evicting the oldest one when full
+    fn insert(&mut self, id: String) {
+        if !self.ids.insert(id.clone()) {
+            return;
+        }
+        self.order.push_back(id);
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+    }
 }
 
 /// P2P network manager for blockchain communication
@@ -63,6 +217,12 @@ pub struct Network {
     message_sender: mpsc::UnboundedSender<NetworkMessage>,
     /// Running status
     running: Arc<Mutex<bool>>,
+    /// IDs of transactions already accepted or sent, to stop gossip loops
+    seen_transactions: Arc<Mutex<SeenTransactions>>,
+    /// Peer scores and ban list
+    reputation: Arc<Mutex<PeerReputation>>,
+    /// Largest message payload in bytes accepted from a peer
+    max_frame_size: usize,
 }
 
 impl Network {
@@ -106,6 +266,9 @@ impl Network {
             monitor,
             message_sender,
             running: Arc::new(Mutex::new(false)),
+            seen_transactions: Arc::new(Mutex::new(SeenTransactions::new(MAX_SEEN_TRANSACTIONS))),
+            reputation: Arc::new(Mutex::new(PeerReputation::default())),
+            max_frame_size: MAX_MESSAGE_SIZE,
         };
 
         info!("Network initialized on {}", network.local_address);
@@ -123,18 +286,30 @@ impl Network {
                 "Network is already running".to_string(),
             ));
         }
+
+        let listener = TokioTcpListener::bind(&self.local_address).await
+            .map_err(|e| BlockchainError::BlockValidationFailed(
+                format!("Failed to bind to {}: {}", self.local_address, e)
+            ))?;
+        if let Ok(address) = listener.local_addr() {
+            self.local_address = address.to_string();
+        }
         *running = true;
         drop(running);
 
-        let local_address = self.local_address.clone();
         let peers = Arc::clone(&self.peers);
         let blockchain = Arc::clone(&self.blockchain);
         let monitor = Arc::clone(&self.monitor);
         let message_sender = self.message_sender.clone();
+        let seen_transactions = Arc::clone(&self.seen_transactions);
+        let reputation = Arc::clone(&self.reputation);
+        let max_frame_size = self.max_frame_size;
 
         // Start the server in a separate task
         tokio::spawn(async move {
-            if let Err(e) = Self::run_server(local_address, peers, blockchain, monitor, message_sender).await {
+            if let Err(e) = Self::run_server(
+                listener, peers, blockchain, monitor, message_sender, seen_transactions, reputation, max_frame_size
+            ).await {
                 error!("Network server error: {}", e);
             }
         });
@@ -143,6 +318,36 @@ impl Network {
         Ok(())
     }
 
+    /// Get the address the network is bound to
+    /// 
+    /// After `start` this is the actual listening address, which differs from the
+    /// configured one when binding to port 0.
+    /// 
+    /// # Returns
+    /// * `&str` - The local address
+    pub fn local_address(&self) -> &str {
+        &self.local_address
+    }
+
+    /// Set the largest message payload accepted from peers
+    /// 
+    /// Peers announcing a bigger frame are penalized and disconnected. The limit
+    /// applies to connections accepted after the next `start` and to sync requests.
+    /// 
+    /// # Arguments
+    /// * `max_frame_size` - Maximum payload size in bytes
+    pub fn set_max_frame_size(&mut self, max_frame_size: usize) {
+        self.max_frame_size = max_frame_size;
+    }
+
+    /// Get the largest message payload accepted from peers
+    /// 
+    /// # Returns
+    /// * `usize` - Maximum payload size in bytes
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+
     /// Stop the network server
     /// 
     /// # Returns
@@ -182,6 +387,10 @@ impl Network {
     /// # }
     /// ```
     pub async fn connect_to_peer(&mut self, peer_address: &str) -> Result<()> {
+        if self.is_banned(peer_address).await {
+            return Err(BlockchainError::NetworkError(format!("Peer {} is banned", peer_address)));
+        }
+
         let start_time = Instant::now();
         
         match timeout(Duration::from_secs(5), TokioTcpStream::connect(peer_address)).await {
@@ -189,22 +398,20 @@ impl Network {
                 let connection_time = start_time.elapsed();
                 
                 // Send ping to verify connection
-                let ping_message = NetworkMessage::Ping;
-                let ping_data = serde_json::to_string(&ping_message)
-                    .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
-                
-                stream.write_all(ping_data.as_bytes()).await
+                Self::write_message(&mut stream, &NetworkMessage::Ping).await
                     .map_err(|e| BlockchainError::BlockValidationFailed(
                         format!("Failed to send ping: {}", e)
                     ))?;
 
                 // Add peer to our list
+                let score = self.reputation.lock().await.scores.get(&peer_host(peer_address)).copied().unwrap_or(0);
                 let mut peers = self.peers.lock().await;
                 peers.insert(peer_address.to_string(), Peer {
                     address: peer_address.to_string(),
                     last_seen: chrono::Utc::now().timestamp(),
                     connected: true,
                     version: "1.0.0".to_string(),
+                    score,
                 });
 
                 // Update monitor
@@ -212,8 +419,17 @@ impl Network {
                 monitor.record_successful_connection();
                 monitor.record_message_latency(connection_time);
                 monitor.update_peer_count(peers.len() as u32);
+                drop(monitor);
+                drop(peers);
 
                 info!("Connected to peer: {} ({}ms)", peer_address, connection_time.as_millis());
+
+                // Catch up with the peer if it has a heavier chain
+                match self.sync_with_peer(peer_address).await {
+                    Ok(0) => {}
+                    Ok(adopted) => info!("Synced {} blocks from peer {}", adopted, peer_address),
+                    Err(e) => warn!("Failed to sync with peer {}: {}", peer_address, e),
+                }
                 Ok(())
             }
             Ok(Err(e)) => {
@@ -253,10 +469,33 @@ impl Network {
     /// # Returns
     /// * `Result<()>` - Ok if successful, error otherwise
     pub async fn broadcast_transaction(&self, transaction: &Transaction) -> Result<()> {
+        self.seen_transactions.lock().await.insert(transaction.id.clone());
         let message = NetworkMessage::NewTransaction(transaction.clone());
         self.broadcast_message(&message).await
     }
 
+    /// Add a transaction to the local mempool and gossip it to peers
+    /// 
+    /// # Arguments
+    /// * `transaction` - The transaction to submit
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if accepted, error if the transaction is invalid or already seen
+    pub async fn submit_transaction(&self, transaction: Transaction) -> Result<()> {
+        {
+            // Hold the seen-set across the check and insert so concurrent copies are accepted once
+            let mut seen = self.seen_transactions.lock().await;
+            if seen.contains(&transaction.id) {
+                return Err(BlockchainError::InvalidTransaction(format!(
+                    "Transaction {} has already been seen", transaction.id
+                )));
+            }
+            self.blockchain.lock().await.add_transaction_object(transaction.clone())?;
+            seen.insert(transaction.id.clone());
+        }
+        self.broadcast_message(&NetworkMessage::NewTransaction(transaction)).await
+    }
+
     /// Request blockchain sync from peers
     /// 
     /// # Returns
@@ -266,6 +505,267 @@ impl Network {
         self.broadcast_message(&message).await
     }
 
+    /// Synchronize the local chain with a peer
+    /// 
+    /// Requests the peer's chain height and cumulative difficulty. If the peer's
+    /// chain is heavier, the blocks after the last common block are fetched in
+    /// batches of `SYNC_BATCH_SIZE`, validated, and the resulting chain replaces
+    /// the local one.
+    /// 
+    /// # Arguments
+    /// * `peer_address` - Address of the peer to sync with
+    /// 
+    /// # Returns
+    /// * `Result<usize>` - Number of blocks adopted from the peer
+    pub async fn sync_with_peer(&self, peer_address: &str) -> Result<usize> {
+        let max_frame_size = self.max_frame_size;
+        let (peer_height, peer_difficulty) = match Self::request(peer_address, &NetworkMessage::GetChainInfo, max_frame_size).await? {
+            NetworkMessage::ChainInfo { height, cumulative_difficulty } => (height, cumulative_difficulty),
+            other => return Err(Self::unexpected_response(peer_address, &other)),
+        };
+
+        let local_hashes: Vec<String> = {
+            let bc = self.blockchain.lock().await;
+            if peer_difficulty <= bc.cumulative_difficulty() {
+                debug!("Peer {} has no heavier chain to sync", peer_address);
+                return Ok(0);
+            }
+            bc.blocks.iter().map(|block| block.hash.clone()).collect()
+        };
+        let local_height = local_hashes.len() as u64;
+
+        // Fetch the peer's blocks above our height, then walk back in batches
+        // until we reach a block both chains share
+        let mut fetched_from = local_height.min(peer_height);
+        let mut fetched = Self::fetch_blocks(peer_address, fetched_from, peer_height, max_frame_size).await?;
+        let fork_point = loop {
+            let overlap_end = local_height.min(fetched_from + fetched.len() as u64);
+            if let Some(index) = (fetched_from..overlap_end)
+                .rev()
+                .find(|&i| fetched[(i - fetched_from) as usize].hash == local_hashes[i as usize])
+            {
+                break Some(index);
+            }
+            if fetched_from == 0 {
+                break None;
+            }
+            if fetched.first().is_some_and(|block| block.previous_hash == local_hashes[(fetched_from - 1) as usize]) {
+                break Some(fetched_from - 1);
+            }
+
+            let from = fetched_from.saturating_sub(SYNC_BATCH_SIZE);
+            let mut earlier = Self::fetch_blocks(peer_address, from, fetched_from, max_frame_size).await?;
+            earlier.append(&mut fetched);
+            fetched = earlier;
+            fetched_from = from;
+        };
+
+        let new_blocks: Vec<Block> = match fork_point {
+            Some(index) => fetched.into_iter().skip((index + 1 - fetched_from) as usize).collect(),
+            None => fetched,
+        };
+        let adopted = new_blocks.len();
+
+        if let Some(Err(e)) = new_blocks.iter().map(Block::validate).find(|result| result.is_err()) {
+            self.penalize_peer(peer_address, PeerViolation::InvalidBlock).await;
+            return Err(e);
+        }
+
+        let mut bc = self.blockchain.lock().await;
+        let candidate = match fork_point {
+            Some(index) => {
+                let index = index as usize;
+                if bc.blocks.get(index).map(|block| &block.hash) != Some(&local_hashes[index]) {
+                    return Err(BlockchainError::NetworkError(
+                        "Local chain changed during sync".to_string(),
+                    ));
+                }
+
+                if index + 1 == bc.blocks.len() {
+                    // Peer extends our chain
+                    let mut candidate = bc.clone();
+                    for block in new_blocks {
+                        candidate.add_block(block)?;
+                    }
+                    candidate
+                } else {
+                    // Peer is on a fork, replay it from the common ancestor
+                    let mut blocks = bc.blocks[..=index].to_vec();
+                    blocks.extend(new_blocks);
+                    bc.replay_blocks(blocks)?
+                }
+            }
+            None => bc.replay_blocks(new_blocks)?,
+        };
+        bc.replace_chain(candidate)?;
+
+        info!("Adopted {} blocks from peer {} (height {})", adopted, peer_address, bc.blocks.len());
+        Ok(adopted)
+    }
+
+    /// Fetch the blocks with indices in `from..to` from a peer
+    async fn fetch_blocks(peer_address: &str, from: u64, to: u64, max_frame_size: usize) -> Result<Vec<Block>> {
+        let mut blocks = Vec::new();
+        let mut next = from;
+
+        while next < to {
+            let end = (next + SYNC_BATCH_SIZE).min(to);
+            let request = NetworkMessage::GetBlocks { from: next, to: end };
+            let mut batch = match Self::request(peer_address, &request, max_frame_size).await? {
+                NetworkMessage::Blocks(batch) => batch,
+                other => return Err(Self::unexpected_response(peer_address, &other)),
+            };
+            batch.truncate((end - next) as usize);
+
+            if batch.is_empty() {
+                return Err(BlockchainError::NetworkError(format!(
+                    "Peer {} returned no blocks for {}..{}", peer_address, next, end
+                )));
+            }
+            for block in &batch {
+                if block.index != next {
+                    return Err(BlockchainError::NetworkError(format!(
+                        "Peer {} returned block {} where {} was expected", peer_address, block.index, next
+                    )));
+                }
+                next += 1;
+            }
+            blocks.append(&mut batch);
+        }
+
+        Ok(blocks)
+    }
+
+    /// Send a request to a peer and wait for its response
+    async fn request(peer_address: &str, message: &NetworkMessage, max_frame_size: usize) -> Result<NetworkMessage> {
+        let exchange = async {
+            let mut stream = TokioTcpStream::connect(peer_address).await
+                .map_err(|e| BlockchainError::NetworkError(format!("Failed to connect to {}: {}", peer_address, e)))?;
+            Self::write_message(&mut stream, message).await?;
+
+            let payload = match read_frame(&mut stream, max_frame_size).await {
+                Ok(Some(payload)) => payload,
+                Ok(None) => return Err(BlockchainError::NetworkError(format!(
+                    "Peer {} closed the connection without responding", peer_address
+                ))),
+                Err(FrameError::Oversized(size)) => return Err(BlockchainError::NetworkError(format!(
+                    "Peer {} sent a {} byte response, above the {} byte limit", peer_address, size, max_frame_size
+                ))),
+                Err(FrameError::Io(e)) => return Err(BlockchainError::NetworkError(format!(
+                    "Failed to read from {}: {}", peer_address, e
+                ))),
+            };
+            serde_json::from_slice::<NetworkMessage>(&payload)
+                .map_err(|e| BlockchainError::SerializationError(e.to_string()))
+        };
+
+        timeout(REQUEST_TIMEOUT, exchange).await
+            .map_err(|_| BlockchainError::NetworkError(format!("Request to {} timed out", peer_address)))?
+    }
+
+    /// Write a message as a length-prefixed JSON frame
+    async fn write_message<W: AsyncWrite + Unpin>(writer: &mut W, message: &NetworkMessage) -> Result<()> {
+        let data = encode_frame(message)?;
+        writer.write_all(&data).await
+            .map_err(|e| BlockchainError::NetworkError(format!("Failed to send message: {}", e)))
+    }
+
+    /// Build the error for a response of the wrong type
+    fn unexpected_response(peer_address: &str, message: &NetworkMessage) -> BlockchainError {
+        BlockchainError::NetworkError(format!("Unexpected response from {}: {:?}", peer_address, message))
+    }
+
+    /// Lower a peer's score for a protocol violation
+    /// 
+    /// The peer is banned once its score reaches the ban threshold.
+    /// 
+    /// # Arguments
+    /// * `peer_address` - Address of the offending peer
+    /// * `violation` - The violation it committed
+    /// 
+    /// # Returns
+    /// * `bool` - True if the peer is now banned
+    pub async fn penalize_peer(&self, peer_address: &str, violation: PeerViolation) -> bool {
+        Self::record_violation(&self.peers, &self.reputation, peer_address, violation).await
+    }
+
+    /// Ban a peer for the configured ban duration and drop it from the peer list
+    /// 
+    /// # Arguments
+    /// * `peer_address` - Address of the peer to ban
+    pub async fn ban_peer(&self, peer_address: &str) {
+        let host = peer_host(peer_address);
+        self.reputation.lock().await.ban(&host);
+        Self::remove_host(&self.peers, &host).await;
+        warn!("Banned peer {}", peer_address);
+    }
+
+    /// Check whether a peer is currently banned
+    /// 
+    /// # Arguments
+    /// * `peer_address` - Address of the peer
+    /// 
+    /// # Returns
+    /// * `bool` - True if the peer's host is banned and the ban has not expired
+    pub async fn is_banned(&self, peer_address: &str) -> bool {
+        self.reputation.lock().await.is_banned(&peer_host(peer_address))
+    }
+
+    /// Set the score threshold and duration used for banning peers
+    /// 
+    /// # Arguments
+    /// * `policy` - The ban policy
+    pub async fn set_ban_policy(&self, policy: BanPolicy) {
+        self.reputation.lock().await.policy = policy;
+    }
+
+    /// Get a known peer
+    /// 
+    /// # Arguments
+    /// * `peer_address` - Address of the peer
+    /// 
+    /// # Returns
+    /// * `Option<Peer>` - The peer, if known
+    pub async fn get_peer(&self, peer_address: &str) -> Option<Peer> {
+        self.peers.lock().await.get(peer_address).cloned()
+    }
+
+    /// Apply a violation to the reputation of a peer's host and ban it if needed
+    async fn record_violation(
+        peers: &Arc<Mutex<HashMap<String, Peer>>>,
+        reputation: &Arc<Mutex<PeerReputation>>,
+        peer_address: &str,
+        violation: PeerViolation,
+    ) -> bool {
+        let host = peer_host(peer_address);
+        let mut reputation = reputation.lock().await;
+        let score = reputation.scores.entry(host.clone()).or_insert(0);
+        *score -= violation.penalty();
+        let score = *score;
+        warn!("Peer {} committed {:?}, score now {}", peer_address, violation, score);
+
+        if score <= reputation.policy.threshold {
+            reputation.ban(&host);
+            drop(reputation);
+            Self::remove_host(peers, &host).await;
+            warn!("Banned peer {} for misbehaving", peer_address);
+            return true;
+        }
+        drop(reputation);
+
+        for peer in peers.lock().await.values_mut() {
+            if peer_host(&peer.address) == host {
+                peer.score = score;
+            }
+        }
+        false
+    }
+
+    /// Remove every peer on a host from a peers map
+    async fn remove_host(peers: &Arc<Mutex<HashMap<String, Peer>>>, host: &str) {
+        peers.lock().await.retain(|address, _| peer_host(address) != host);
+    }
+
     /// Get list of connected peers
     /// 
     /// # Returns
@@ -300,19 +800,33 @@ impl Network {
     /// # Returns
     /// * `Result<()>` - Ok if successful, error otherwise
     async fn broadcast_message(&self, message: &NetworkMessage) -> Result<()> {
-        let peers = self.peers.lock().await;
+        Self::broadcast_to_peers(&self.peers, &self.monitor, message).await
+    }
+
+    /// Send a message to every peer in a peers map
+    /// 
+    /// # Arguments
+    /// * `peers` - Shared peers map
+    /// * `monitor` - Shared monitor instance
+    /// * `message` - The message to broadcast
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if successful, error otherwise
+    async fn broadcast_to_peers(
+        peers: &Arc<Mutex<HashMap<String, Peer>>>,
+        monitor: &Arc<Mutex<BlockchainMonitor>>,
+        message: &NetworkMessage,
+    ) -> Result<()> {
+        let peers = peers.lock().await;
         let peer_addresses: Vec<String> = peers.keys().cloned().collect();
         let peer_count = peer_addresses.len();
         drop(peers);
 
-        let message_data = serde_json::to_string(message)
-            .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
-
         let mut success_count = 0;
         for peer_address in &peer_addresses {
             match timeout(Duration::from_secs(5), TokioTcpStream::connect(&peer_address)).await {
                 Ok(Ok(mut stream)) => {
-                    if stream.write_all(message_data.as_bytes()).await.is_ok() {
+                    if Self::write_message(&mut stream, message).await.is_ok() {
                         success_count += 1;
                         debug!("Broadcasted message to peer: {}", peer_address);
                     }
@@ -323,7 +837,7 @@ impl Network {
             }
         }
 
-        let mut monitor = self.monitor.lock().await;
+        let mut monitor = monitor.lock().await;
         monitor.record_message_sent();
 
         info!("Broadcasted message to {}/{} peers", success_count, peer_count);
@@ -333,41 +847,50 @@ impl Network {
     /// Run the network server
     /// 
     /// # Arguments
-    /// * `local_address` - Local address to bind to
+    /// * `listener` - Bound listener to accept connections on
     /// * `peers` - Shared peers map
     /// * `blockchain` - Shared blockchain instance
     /// * `monitor` - Shared monitor instance
     /// * `message_sender` - Message sender channel
+    /// * `seen_transactions` - Shared set of transaction IDs already gossiped
+    /// * `reputation` - Shared peer scores and ban list
+    /// * `max_frame_size` - Largest message payload accepted from a peer
     /// 
     /// # Returns
     /// * `Result<()>` - Ok if successful, error otherwise
+    #[allow(clippy::too_many_arguments)]
     async fn run_server(
-        local_address: String,
+        listener: TokioTcpListener,
         peers: Arc<Mutex<HashMap<String, Peer>>>,
         blockchain: Arc<Mutex<Blockchain>>,
         monitor: Arc<Mutex<BlockchainMonitor>>,
-        message_sender: mpsc::UnboundedSender<NetworkMessage>,
+        _message_sender: mpsc::UnboundedSender<NetworkMessage>,
+        seen_transactions: Arc<Mutex<SeenTransactions>>,
+        reputation: Arc<Mutex<PeerReputation>>,
+        max_frame_size: usize,
     ) -> Result<()> {
-        let listener = TokioTcpListener::bind(&local_address).await
-            .map_err(|e| BlockchainError::BlockValidationFailed(
-                format!("Failed to bind to {}: {}", local_address, e)
-            ))?;
-
-        info!("Network server listening on {}", local_address);
+        if let Ok(local_address) = listener.local_addr() {
+            info!("Network server listening on {}", local_address);
+        }
 
         loop {
             match listener.accept().await {
                 Ok((socket, addr)) => {
+                    if reputation.lock().await.is_banned(&addr.ip().to_string()) {
+                        debug!("Refused connection from banned peer: {}", addr);
+                        continue;
+                    }
                     debug!("New connection from: {}", addr);
                     
                     let peers_clone = Arc::clone(&peers);
                     let blockchain_clone = Arc::clone(&blockchain);
                     let monitor_clone = Arc::clone(&monitor);
-                    let message_sender_clone = message_sender.clone();
+                    let seen_clone = Arc::clone(&seen_transactions);
+                    let reputation_clone = Arc::clone(&reputation);
 
                     tokio::spawn(async move {
                         if let Err(e) = Self::handle_connection(
-                            socket, addr, peers_clone, blockchain_clone, monitor_clone, message_sender_clone
+                            socket, addr, peers_clone, blockchain_clone, monitor_clone, seen_clone, reputation_clone, max_frame_size
                         ).await {
                             error!("Connection handler error: {}", e);
                         }
@@ -388,35 +911,53 @@ impl Network {
     /// * `peers` - Shared peers map
     /// * `blockchain` - Shared blockchain instance
     /// * `monitor` - Shared monitor instance
-    /// * `message_sender` - Message sender channel
+    /// * `seen_transactions` - Shared set of transaction IDs already gossiped
+    /// * `reputation` - Shared peer scores and ban list
+    /// * `max_frame_size` - Largest message payload accepted from the peer
     /// 
     /// # Returns
     /// * `Result<()>` - Ok if successful, error otherwise
+    #[allow(clippy::too_many_arguments)]
     async fn handle_connection(
-        mut socket: TokioTcpStream,
+        socket: TokioTcpStream,
         addr: SocketAddr,
         peers: Arc<Mutex<HashMap<String, Peer>>>,
         blockchain: Arc<Mutex<Blockchain>>,
         monitor: Arc<Mutex<BlockchainMonitor>>,
-        message_sender: mpsc::UnboundedSender<NetworkMessage>,
+        seen_transactions: Arc<Mutex<SeenTransactions>>,
+        reputation: Arc<Mutex<PeerReputation>>,
+        max_frame_size: usize,
     ) -> Result<()> {
-        let mut buffer = [0; 4096];
+        // Messages are length-prefixed JSON frames
+        let (mut reader, mut writer) = socket.into_split();
         
         loop {
-            match socket.read(&mut buffer).await {
-                Ok(0) => {
+            match read_frame(&mut reader, max_frame_size).await {
+                Ok(None) => {
                     debug!("Connection closed by peer: {}", addr);
                     break;
                 }
-                Ok(n) => {
-                    let data = String::from_utf8_lossy(&buffer[..n]);
-                    
-                    match serde_json::from_str::<NetworkMessage>(&data) {
+                Err(FrameError::Oversized(size)) => {
+                    // The payload is never read, so the stream cannot be resynchronized
+                    warn!("Oversized message ({} bytes) from {}, disconnecting", size, addr);
+                    Self::record_violation(
+                        &peers, &reputation, &addr.to_string(), PeerViolation::OversizedMessage
+                    ).await;
+                    break;
+                }
+                Ok(Some(payload)) => {
+                    match serde_json::from_slice::<NetworkMessage>(&payload) {
                         Ok(message) => {
-                            if let Err(e) = Self::handle_message(
-                                message, &peers, &blockchain, &monitor, &message_sender, &mut socket
+                            match Self::handle_message(
+                                message, &peers, &blockchain, &monitor, &seen_transactions, &mut writer
                             ).await {
-                                error!("Message handling error: {}", e);
+                                Ok(Some(violation)) => {
+                                    if Self::record_violation(&peers, &reputation, &addr.to_string(), violation).await {
+                                        break;
+                                    }
+                                }
+                                Ok(None) => {}
+                                Err(e) => error!("Message handling error: {}", e),
                             }
                         }
                         Err(e) => {
@@ -424,7 +965,7 @@ impl Network {
                         }
                     }
                 }
-                Err(e) => {
+                Err(FrameError::Io(e)) => {
                     error!("Read error from {}: {}", addr, e);
                     break;
                 }
@@ -441,55 +982,79 @@ impl Network {
     /// * `peers` - Shared peers map
     /// * `blockchain` - Shared blockchain instance
     /// * `monitor` - Shared monitor instance
-    /// * `message_sender` - Message sender channel
-    /// * `socket` - TCP socket for response
+    /// * `seen_transactions` - Shared set of transaction IDs already gossiped
+    /// * `socket` - Write half of the connection for responses
     /// 
     /// # Returns
-    /// * `Result<()>` - Ok if successful, error otherwise
+    /// * `Result<Option<PeerViolation>>` - The protocol violation the sender committed, if any
     async fn handle_message(
         message: NetworkMessage,
         peers: &Arc<Mutex<HashMap<String, Peer>>>,
         blockchain: &Arc<Mutex<Blockchain>>,
         monitor: &Arc<Mutex<BlockchainMonitor>>,
-        _message_sender: &mpsc::UnboundedSender<NetworkMessage>,
-        socket: &mut TokioTcpStream,
-    ) -> Result<()> {
+        seen_transactions: &Arc<Mutex<SeenTransactions>>,
+        socket: &mut OwnedWriteHalf,
+    ) -> Result<Option<PeerViolation>> {
+        let mut violation = None;
+
         match message {
             NetworkMessage::NewBlock(block) => {
                 debug!("Received new block: {}", block.index);
                 
-                // Add block to blockchain
-                let mut bc = blockchain.lock().await;
-                if let Err(e) = bc.add_block(block) {
+                if let Err(e) = block.validate() {
+                    warn!("Received invalid block {}: {}", block.index, e);
+                    violation = Some(PeerViolation::InvalidBlock);
+                } else if let Err(e) = blockchain.lock().await.receive_block(block) {
+                    // Valid blocks can still be stale or forks, which is not misbehaviour;
+                    // blocks arriving ahead of their parent are buffered as orphans
                     warn!("Failed to add received block: {}", e);
                 }
             }
+            NetworkMessage::NewTransaction(transaction) if !has_valid_signature(&transaction) => {
+                warn!("Received transaction {} with an invalid signature", transaction.id);
+                violation = Some(PeerViolation::InvalidSignature);
+            }
             NetworkMessage::NewTransaction(transaction) => {
                 debug!("Received new transaction: {}", transaction.id);
-                
-                // Add transaction to blockchain
-                let mut bc = blockchain.lock().await;
-                if let Err(e) = bc.add_transaction(
-                    transaction.sender.clone(),
-                    transaction.receiver.clone(),
-                    transaction.amount,
-                    transaction.message.clone(),
-                ) {
-                    warn!("Failed to add received transaction: {}", e);
+
+                // Only remember and gossip transactions our own mempool accepted, so an
+                // invalid copy arriving first does not suppress a later valid one. The
+                // seen-set stays locked from the check to the insert so two concurrent
+                // copies cannot both be accepted.
+                let accepted = {
+                    let mut seen = seen_transactions.lock().await;
+                    if seen.contains(&transaction.id) {
+                        debug!("Ignoring already seen transaction: {}", transaction.id);
+                        false
+                    } else {
+                        match blockchain.lock().await.add_transaction_object(transaction.clone()) {
+                            Ok(()) => {
+                                seen.insert(transaction.id.clone());
+                                true
+                            }
+                            Err(e) => {
+                                warn!("Failed to add received transaction: {}", e);
+                                false
+                            }
+                        }
+                    }
+                };
+                if accepted {
+                    let message = NetworkMessage::NewTransaction(transaction);
+                    if let Err(e) = Self::broadcast_to_peers(peers, monitor, &message).await {
+                        warn!("Failed to gossip transaction: {}", e);
+                    }
                 }
             }
             NetworkMessage::SyncRequest => {
                 debug!("Received sync request");
                 
                 // Send blockchain data
-                let bc = blockchain.lock().await;
-                let blocks = bc.blocks.clone();
+                let blocks = blockchain.lock().await.blocks.clone();
                 let response = NetworkMessage::SyncResponse(blocks);
                 
-                if let Ok(response_data) = serde_json::to_string(&response) {
-                    if let Err(e) = socket.write_all(response_data.as_bytes()).await {
-                        error!("Failed to send sync response: {}", e);
-                    }
+                if let Err(e) = Self::write_message(socket, &response).await {
+                    error!("Failed to send sync response: {}", e);
                 }
             }
             NetworkMessage::SyncResponse(blocks) => {
@@ -498,7 +1063,12 @@ impl Network {
                 // Process received blocks
                 let mut bc = blockchain.lock().await;
                 for block in blocks {
-                    if let Err(e) = bc.add_block(block) {
+                    if let Err(e) = block.validate() {
+                        warn!("Received invalid synced block {}: {}", block.index, e);
+                        violation = Some(PeerViolation::InvalidBlock);
+                        break;
+                    }
+                    if let Err(e) = bc.receive_block(block) {
                         warn!("Failed to add synced block: {}", e);
                     }
                 }
@@ -507,11 +1077,8 @@ impl Network {
                 debug!("Received ping");
                 
                 // Send pong response
-                let pong = NetworkMessage::Pong;
-                if let Ok(pong_data) = serde_json::to_string(&pong) {
-                    if let Err(e) = socket.write_all(pong_data.as_bytes()).await {
-                        error!("Failed to send pong: {}", e);
-                    }
+                if let Err(e) = Self::write_message(socket, &NetworkMessage::Pong).await {
+                    error!("Failed to send pong: {}", e);
                 }
             }
             NetworkMessage::Pong => {
@@ -527,10 +1094,8 @@ impl Network {
                 };
                 
                 let response = NetworkMessage::PeerList(peer_list);
-                if let Ok(response_data) = serde_json::to_string(&response) {
-                    if let Err(e) = socket.write_all(response_data.as_bytes()).await {
-                        error!("Failed to send peer list: {}", e);
-                    }
+                if let Err(e) = Self::write_message(socket, &response).await {
+                    error!("Failed to send peer list: {}", e);
                 }
             }
             NetworkMessage::PeerList(peer_addresses) => {
@@ -545,18 +1110,111 @@ impl Network {
                             last_seen: chrono::Utc::now().timestamp(),
                             connected: false,
                             version: "1.0.0".to_string(),
+                            score: 0,
                         });
                     }
                 }
             }
+            NetworkMessage::GetChainInfo => {
+                debug!("Received chain info request");
+
+                let response = {
+                    let bc = blockchain.lock().await;
+                    NetworkMessage::ChainInfo {
+                        height: bc.blocks.len() as u64,
+                        cumulative_difficulty: bc.cumulative_difficulty(),
+                    }
+                };
+                if let Err(e) = Self::write_message(socket, &response).await {
+                    error!("Failed to send chain info: {}", e);
+                }
+            }
+            NetworkMessage::GetBlocks { from, to } => {
+                debug!("Received request for blocks {}..{}", from, to);
+
+                let blocks = {
+                    let bc = blockchain.lock().await;
+                    let len = bc.blocks.len() as u64;
+                    let from = from.min(len);
+                    let to = to.min(from.saturating_add(SYNC_BATCH_SIZE)).clamp(from, len);
+                    bc.blocks[from as usize..to as usize].to_vec()
+                };
+                if let Err(e) = Self::write_message(socket, &NetworkMessage::Blocks(blocks)).await {
+                    error!("Failed to send blocks: {}", e);
+                }
+            }
+            NetworkMessage::ChainInfo { height, .. } => {
+                debug!("Ignoring unsolicited chain info (height {})", height);
+            }
+            NetworkMessage::Blocks(blocks) => {
+                debug!("Ignoring {} unsolicited blocks", blocks.len());
+            }
         }
 
         // Update monitor
         let mut monitor_guard = monitor.lock().await;
         monitor_guard.record_message_received();
 
-        Ok(())
+        Ok(violation)
+    }
+}
+
+/// Encode a message as a frame: a big-endian `u32` payload length followed by the JSON payload
+/// 
+/// # Arguments
+/// * `message` - The message to encode
+/// 
+/// # Returns
+/// * `Result<Vec<u8>>` - The frame bytes, or an error if the message cannot be serialized
+///   or does not fit a frame
+pub fn encode_frame(message: &NetworkMessage) -> Result<Vec<u8>> {
+    let payload = serde_json::to_vec(message)
+        .map_err(|e| BlockchainError::SerializationError(e.to_string()))?;
+    let length = u32::try_from(payload.len()).map_err(|_| BlockchainError::NetworkError(format!(
+        "Message of {} bytes is too large to frame", payload.len()
+    )))?;
+
+    let mut frame = Vec::with_capacity(FRAME_HEADER_SIZE + payload.len());
+    frame.extend_from_slice(&length.to_be_bytes());
+    frame.extend_from_slice(&payload);
+    Ok(frame)
+}
+
+/// Read one frame's payload, rejecting lengths above `max_frame_size` before reading the payload
+/// 
+/// Returns `Ok(None)` if the stream ends cleanly before a new frame starts.
+async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R, max_frame_size: usize) -> std::result::Result<Option<Vec<u8>>, FrameError> {
+    let mut header = [0u8; FRAME_HEADER_SIZE];
+    let mut filled = 0;
+    while filled < FRAME_HEADER_SIZE {
+        match reader.read(&mut header[filled..]).await.map_err(FrameError::Io)? {
+            0 if filled == 0 => return Ok(None),
+            0 => return Err(FrameError::Io(std::io::ErrorKind::UnexpectedEof.into())),
+            read => filled += read,
+        }
     }
+
+    let length = u32::from_be_bytes(header) as usize;
+    if length > max_frame_size {
+        return Err(FrameError::Oversized(length));
+    }
+
+    let mut payload = vec![0u8; length];
+    reader.read_exact(&mut payload).await.map_err(FrameError::Io)?;
+    Ok(Some(payload))
+}
+
+/// Get the host part of a peer address, which scores and bans apply to
+fn peer_host(address: &str) -> String {
+    match address.parse::<SocketAddr>() {
+        Ok(socket_address) => socket_address.ip().to_string(),
+        Err(_) => address.rsplit_once(':').map_or(address, |(host, _)| host).to_string(),
+    }
+}
+
+/// Check that a transaction's signature, if it has one, covers its contents
+fn has_valid_signature(transaction: &Transaction) -> bool {
+    !transaction.is_signed() || transaction.verify_signature().unwrap_or(false)
 }
 
 #[cfg(test)]
@@ -586,6 +1244,55 @@ mod tests {
         assert!(!network.is_running().await);
     }
 
+    #[test]
+    fn test_seen_transactions_evicts_oldest() {
+        let mut seen = SeenTransactions::new(2);
+        seen.insert("a".to_string());
+        seen.insert("b".to_string());
+        seen.insert("a".to_string());
+        seen.insert("c".to_string());
+
+        assert!(!seen.contains("a"));
+        assert!(seen.contains("b"));
+        assert!(seen.contains("c"));
+        assert_eq!(seen.order.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_read_frame() {
+        let frame = encode_frame(&NetworkMessage::Ping).unwrap();
+        let payload = read_frame(&mut frame.as_slice(), MAX_MESSAGE_SIZE).await.unwrap().unwrap();
+        assert!(matches!(serde_json::from_slice(&payload).unwrap(), NetworkMessage::Ping));
+
+        // The length is rejected before any payload is read
+        let oversized = (1024u32).to_be_bytes();
+        assert!(matches!(read_frame(&mut oversized.as_slice(), 1023).await, Err(FrameError::Oversized(1024))));
+
+        // Clean end of stream, then streams cut off inside the header or payload
+        assert!(read_frame(&mut [].as_slice(), MAX_MESSAGE_SIZE).await.unwrap().is_none());
+        assert!(matches!(read_frame(&mut &frame[..2], MAX_MESSAGE_SIZE).await, Err(FrameError::Io(_))));
+        assert!(matches!(read_frame(&mut &frame[..frame.len() - 1], MAX_MESSAGE_SIZE).await, Err(FrameError::Io(_))));
+    }
+
+    #[tokio::test]
+    async fn test_read_frame_across_partial_reads() {
+        let frame = encode_frame(&NetworkMessage::GetBlocks { from: 3, to: 9 }).unwrap();
+        let (mut client, mut server) = tokio::io::duplex(64);
+        let writer = tokio::spawn(async move {
+            for chunk in frame.chunks(3) {
+                client.write_all(chunk).await.unwrap();
+                tokio::task::yield_now().await;
+            }
+        });
+
+        let payload = read_frame(&mut server, MAX_MESSAGE_SIZE).await.unwrap().unwrap();
+        writer.await.unwrap();
+        assert!(matches!(
+            serde_json::from_slice(&payload).unwrap(),
+            NetworkMessage::GetBlocks { from: 3, to: 9 }
+        ));
+    }
+
     #[test]
     fn test_network_message_serialization() {
         let block = Block::genesis().unwrap();
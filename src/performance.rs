@@ -5,6 +5,26 @@ use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use serde::{Deserialize, Serialize};
 
+use crate::blockchain::Blockchain;
+use crate::smart_contract::SmartContract;
+use crate::storage::{BlockchainStorage, StorageError};
+use crate::units;
+
+/// Key prefix of cached account balances
+pub const BALANCE_CACHE_PREFIX: &str = "balance:";
+
+/// Key prefix of cached smart contracts
+pub const CONTRACT_CACHE_PREFIX: &str = "contract:";
+
+/// Cache key of an account's balance
+pub fn balance_cache_key(address: &str) -> String {
+    format!("{}{}", BALANCE_CACHE_PREFIX, address)
+}
+
+/// Cache key of a smart contract
+pub fn contract_cache_key(address: &str) -> String {
+    format!("{}{}", CONTRACT_CACHE_PREFIX, address)
+}
 
 /// Performance optimization manager for the blockchain
 pub struct PerformanceManager {
@@ -25,6 +45,9 @@ pub struct PerformanceConfig {
     pub memory_threshold: f64,
     pub gc_interval: Duration,
     pub enable_metrics: bool,
+    /// Chain state size in bytes above which in-memory state snapshots are pruned (0 = never)
+    #[serde(default)]
+    pub memory_high_water_mark: u64,
 }
 
 impl Default for PerformanceConfig {
@@ -36,11 +59,13 @@ impl Default for PerformanceConfig {
             memory_threshold: 0.8, // 80% memory usage threshold
             gc_interval: Duration::from_secs(60), // 1 minute
             enable_metrics: true,
+            memory_high_water_mark: 512 * 1024 * 1024, // 512 MiB
         }
     }
 }
 
 /// Advanced caching system with TTL and LRU eviction
+#[derive(Debug)]
 pub struct CacheManager {
     cache: Arc<RwLock<HashMap<String, CacheEntry>>>,
     config: CacheConfig,
@@ -120,28 +145,46 @@ impl CacheManager {
 
         let mut cache = self.cache.write().unwrap();
         
-        // Check if we need to evict entries
-        if cache.len() >= self.config.max_size {
+        // Make room for a new key once the cache is full
+        if !cache.contains_key(&key) {
             self.evict_entries(&mut cache);
         }
         
         cache.insert(key, entry);
     }
 
+    /// Evict entries until there is room for one more, least recently used first when LRU is enabled
     fn evict_entries(&self, cache: &mut HashMap<String, CacheEntry>) {
         let mut stats = self.stats.lock().unwrap();
-        
-        // Simple eviction: just clear the cache if it's too large
-        if cache.len() > self.config.max_size {
-            let to_remove = cache.len() - self.config.max_size;
-            let keys_to_remove: Vec<_> = cache.keys().take(to_remove).cloned().collect();
-            for key in keys_to_remove {
+
+        while !cache.is_empty() && cache.len() >= self.config.max_size {
+            let victim = if self.config.enable_lru {
+                cache.iter().min_by_key(|(_, entry)| entry.last_accessed).map(|(key, _)| key.clone())
+            } else {
+                cache.keys().next().cloned()
+            };
+            if let Some(key) = victim {
                 cache.remove(&key);
                 stats.evictions += 1;
             }
         }
     }
 
+    /// Remove a single entry
+    pub fn invalidate(&self, key: &str) {
+        self.cache.write().unwrap().remove(key);
+    }
+
+    /// Remove every entry whose key starts with `prefix`
+    pub fn invalidate_prefix(&self, prefix: &str) {
+        self.cache.write().unwrap().retain(|key, _| !key.starts_with(prefix));
+    }
+
+    /// Remove every entry
+    pub fn invalidate_all(&self) {
+        self.cache.write().unwrap().clear();
+    }
+
     pub async fn get_stats(&self) -> CacheStats {
         self.stats.lock().unwrap().clone()
     }
@@ -204,10 +247,12 @@ impl ParallelProcessor {
 }
 
 /// Memory optimization and garbage collection
+#[derive(Debug)]
 pub struct MemoryOptimizer {
     memory_usage: Arc<Mutex<MemoryUsage>>,
     gc_interval: Duration,
     threshold: f64,
+    high_water_mark: u64,
     running: Arc<Mutex<bool>>,
 }
 
@@ -220,6 +265,25 @@ pub struct MemoryUsage {
     #[serde(with = "timestamp_serde")]
     pub last_gc: Instant,
     pub gc_count: u64,
+    /// Approximate bytes held by blocks
+    #[serde(default)]
+    pub blocks_bytes: u64,
+    /// Approximate bytes held by account balances
+    #[serde(default)]
+    pub balances_bytes: u64,
+    /// Approximate bytes held by smart contracts
+    #[serde(default)]
+    pub contracts_bytes: u64,
+    /// Approximate bytes held by in-memory state snapshots
+    #[serde(default)]
+    pub snapshots_bytes: u64,
+}
+
+impl MemoryUsage {
+    /// Approximate bytes held by the chain state
+    pub fn chain_bytes(&self) -> u64 {
+        self.blocks_bytes + self.balances_bytes + self.contracts_bytes + self.snapshots_bytes
+    }
 }
 
 impl MemoryOptimizer {
@@ -232,9 +296,14 @@ impl MemoryOptimizer {
                 cache_memory: 0,
                 last_gc: Instant::now(),
                 gc_count: 0,
+                blocks_bytes: 0,
+                balances_bytes: 0,
+                contracts_bytes: 0,
+                snapshots_bytes: 0,
             })),
             gc_interval,
             threshold,
+            high_water_mark: 0,
             running: Arc::new(Mutex::new(true)),
         }
     }
@@ -283,15 +352,104 @@ impl MemoryOptimizer {
         usage.free_memory = usage.total_memory - usage.used_memory;
     }
 
+    /// Create an optimizer with the memory settings of a performance configuration
+    pub fn from_config(config: &PerformanceConfig) -> Self {
+        Self::new(config.gc_interval, config.memory_threshold)
+            .with_high_water_mark(config.memory_high_water_mark)
+    }
+
+    /// Prune state snapshots once the chain state grows past `bytes` (0 = never)
+    pub fn with_high_water_mark(mut self, bytes: u64) -> Self {
+        self.high_water_mark = bytes;
+        self
+    }
+
     pub async fn get_memory_usage(&self) -> MemoryUsage {
         self.memory_usage.lock().unwrap().clone()
     }
 
+    /// Measure the approximate memory held by a chain's state
+    ///
+    /// Sizes are those of the JSON encoding of blocks, contracts and snapshots, and
+    /// of the address plus a 16 byte amount per balance.
+    pub fn current_usage(&self, blockchain: &Blockchain) -> MemoryUsage {
+        let mut usage = self.memory_usage.lock().unwrap();
+        usage.blocks_bytes = blockchain.blocks.iter().map(|block| block.size() as u64).sum();
+        usage.balances_bytes = blockchain.balances.keys().map(|address| address.len() as u64 + 16).sum();
+        usage.contracts_bytes = blockchain.contracts.values()
+            .map(|contract| serde_json::to_vec(contract).map(|json| json.len() as u64).unwrap_or(0))
+            .sum();
+        usage.snapshots_bytes = Self::snapshot_sizes(blockchain).iter().sum();
+        usage.clone()
+    }
+
+    fn snapshot_sizes(blockchain: &Blockchain) -> Vec<u64> {
+        blockchain.state_snapshots.iter()
+            .map(|snapshot| serde_json::to_vec(snapshot).map(|json| json.len() as u64).unwrap_or(0))
+            .collect()
+    }
+
+    /// Prune in-memory state snapshots if the chain state exceeds the high-water mark
+    ///
+    /// The oldest snapshots are saved to `storage` and then dropped from memory until
+    /// the state fits under the mark. The snapshot of a pending rollback stays in
+    /// memory regardless.
+    ///
+    /// # Returns
+    /// * `Result<usize>` - Number of snapshots pruned from memory
+    pub fn relieve_pressure(&self, blockchain: &mut Blockchain, storage: &BlockchainStorage) -> Result<usize, StorageError> {
+        let usage = self.current_usage(blockchain);
+        if self.high_water_mark == 0 || usage.chain_bytes() <= self.high_water_mark {
+            return Ok(0);
+        }
+
+        // Keep as many of the newest snapshots as fit under the mark
+        let sizes = Self::snapshot_sizes(blockchain);
+        let mut retained_bytes = usage.chain_bytes() - usage.snapshots_bytes;
+        let mut keep = 0;
+        for size in sizes.iter().rev() {
+            if retained_bytes + size > self.high_water_mark {
+                break;
+            }
+            retained_bytes += size;
+            keep += 1;
+        }
+
+        let cutoff = sizes.len() - keep;
+        for snapshot in &blockchain.state_snapshots[..cutoff] {
+            storage.save_state_snapshot(snapshot)?;
+        }
+        let pruned = blockchain.prune_snapshots(keep);
+
+        let mut usage = self.memory_usage.lock().unwrap();
+        usage.last_gc = Instant::now();
+        usage.gc_count += 1;
+        drop(usage);
+        self.current_usage(blockchain);
+
+        Ok(pruned)
+    }
+
     pub async fn stop_monitoring(&self) {
         *self.running.lock().unwrap() = false;
     }
 }
 
+/// Where and when a chain prunes its in-memory state snapshots
+#[derive(Debug, Clone)]
+pub struct SnapshotPruning {
+    /// Optimizer deciding how many snapshots stay in memory
+    pub optimizer: Arc<MemoryOptimizer>,
+    /// Storage that pruned snapshots are saved to and reloaded from
+    pub storage: Arc<BlockchainStorage>,
+}
+
+impl SnapshotPruning {
+    pub fn new(optimizer: Arc<MemoryOptimizer>, storage: Arc<BlockchainStorage>) -> Self {
+        Self { optimizer, storage }
+    }
+}
+
 /// Metrics collection and monitoring
 pub struct MetricsCollector {
     metrics: Arc<RwLock<HashMap<String, MetricValue>>>,
@@ -388,7 +546,7 @@ impl PerformanceManager {
         Self {
             cache_manager: Arc::new(CacheManager::new(cache_config)),
             parallel_processor: Arc::new(ParallelProcessor::new(parallel_config)),
-            memory_optimizer: Arc::new(MemoryOptimizer::new(config.gc_interval, config.memory_threshold)),
+            memory_optimizer: Arc::new(MemoryOptimizer::from_config(&config)),
             metrics_collector: Arc::new(MetricsCollector::new(metrics_config)),
             config,
         }
@@ -422,6 +580,66 @@ impl PerformanceManager {
         });
     }
 
+    /// Get the cache that blockchain reads go through
+    pub fn cache_manager(&self) -> Arc<CacheManager> {
+        self.cache_manager.clone()
+    }
+
+    /// Route a chain's balance and contract reads through this manager's cache
+    ///
+    /// The chain invalidates cached entries as its state changes, so reads never
+    /// return stale values.
+    pub fn attach(&self, blockchain: &mut Blockchain) {
+        blockchain.set_read_cache(self.cache_manager.clone());
+    }
+
+    /// Whether `blockchain` keeps this manager's cache up to date
+    fn is_attached(&self, blockchain: &Blockchain) -> bool {
+        blockchain.read_cache.as_ref().is_some_and(|cache| Arc::ptr_eq(cache, &self.cache_manager))
+    }
+
+    /// Read an account balance through the cache
+    ///
+    /// Chains that are not attached are read directly.
+    pub async fn get_balance(&self, blockchain: &Blockchain, address: &str) -> f64 {
+        if !self.is_attached(blockchain) {
+            return blockchain.get_balance(address);
+        }
+
+        let key = balance_cache_key(address);
+        if let Some(bytes) = self.cache_manager.get(&key).await {
+            if let Ok(bytes) = <[u8; 16]>::try_from(bytes.as_slice()) {
+                return units::from_base_units(u128::from_be_bytes(bytes));
+            }
+        }
+
+        let balance = blockchain.get_balance_units(address);
+        self.cache_manager.set(key, balance.to_be_bytes().to_vec()).await;
+        units::from_base_units(balance)
+    }
+
+    /// Read a smart contract through the cache
+    ///
+    /// Chains that are not attached are read directly. Missing contracts are not cached.
+    pub async fn get_contract(&self, blockchain: &Blockchain, address: &str) -> Option<SmartContract> {
+        if !self.is_attached(blockchain) {
+            return blockchain.get_contract(address).cloned();
+        }
+
+        let key = contract_cache_key(address);
+        if let Some(bytes) = self.cache_manager.get(&key).await {
+            if let Ok(contract) = serde_json::from_slice(&bytes) {
+                return Some(contract);
+            }
+        }
+
+        let contract = blockchain.get_contract(address)?.clone();
+        if let Ok(bytes) = serde_json::to_vec(&contract) {
+            self.cache_manager.set(key, bytes).await;
+        }
+        Some(contract)
+    }
+
     pub async fn get_performance_stats(&self) -> PerformanceStats {
         let cache_stats = self.cache_manager.get_stats().await;
         let memory_usage = self.memory_optimizer.get_memory_usage().await;
@@ -506,6 +724,144 @@ mod tests {
         assert_eq!(value, None);
     }
 
+    #[tokio::test]
+    async fn test_cache_evicts_least_recently_used() {
+        let cache = CacheManager::new(CacheConfig {
+            max_size: 2,
+            ttl: Duration::from_secs(60),
+            enable_lru: true,
+        });
+        cache.set("a".to_string(), b"1".to_vec()).await;
+        cache.set("b".to_string(), b"2".to_vec()).await;
+        sleep(Duration::from_millis(5)).await;
+        assert!(cache.get("a").await.is_some());
+
+        cache.set("c".to_string(), b"3".to_vec()).await;
+        assert!(cache.get("a").await.is_some());
+        assert!(cache.get("b").await.is_none());
+        assert!(cache.get("c").await.is_some());
+        assert_eq!(cache.get_stats().await.evictions, 1);
+    }
+
+    #[test]
+    fn test_snapshots_pruned_under_memory_pressure() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = BlockchainStorage::new(temp_dir.path().join("db")).unwrap();
+        let allocations = HashMap::from([(alice.clone(), units::gil(500))]);
+        let mut blockchain = Blockchain::new_pow_with_genesis(1, 50.0, allocations).unwrap();
+        for i in 0..12 {
+            blockchain.add_transaction(&alice_key, format!("user{}", i), units::gil(1), None).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+        }
+        // The oldest snapshot is pinned by a rollback in progress
+        let pinned = blockchain.state_snapshots[0].block_index;
+        blockchain.rollback_target = Some(pinned);
+
+        let usage = MemoryOptimizer::new(Duration::from_secs(1), 0.8).current_usage(&blockchain);
+        assert!(usage.blocks_bytes > 0 && usage.balances_bytes > 0 && usage.snapshots_bytes > 0);
+        let snapshots_before = blockchain.state_snapshots.len();
+
+        // Below the mark nothing is pruned
+        let relaxed = MemoryOptimizer::new(Duration::from_secs(1), 0.8).with_high_water_mark(usage.chain_bytes());
+        assert_eq!(relaxed.relieve_pressure(&mut blockchain, &storage).unwrap(), 0);
+
+        let high_water_mark = usage.chain_bytes() - usage.snapshots_bytes / 2;
+        let optimizer = MemoryOptimizer::new(Duration::from_secs(1), 0.8).with_high_water_mark(high_water_mark);
+        let pruned = optimizer.relieve_pressure(&mut blockchain, &storage).unwrap();
+        assert!(pruned > 0);
+        assert_eq!(blockchain.state_snapshots.len(), snapshots_before - pruned);
+        assert!(blockchain.state_snapshots.iter().any(|snapshot| snapshot.block_index == pinned));
+
+        let after = optimizer.current_usage(&blockchain);
+        assert!(after.snapshots_bytes < usage.snapshots_bytes);
+        assert_eq!(after.gc_count, 1);
+
+        // Every pruned snapshot can be loaded back from disk
+        for block_index in 1..=blockchain.height() {
+            if !blockchain.state_snapshots.iter().any(|snapshot| snapshot.block_index == block_index) {
+                let snapshot = storage.load_state_snapshot(block_index).unwrap().unwrap();
+                assert_eq!(snapshot.block_index, block_index);
+                assert_eq!(snapshot.balances.get(&alice).copied(),
+                           Some(units::gil(500) - units::gil(block_index - 1)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_added_blocks_prune_snapshots_and_rollback_reloads_them() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(BlockchainStorage::new(temp_dir.path().join("db")).unwrap());
+        let allocations = HashMap::from([(alice.clone(), units::gil(500))]);
+        let mut blockchain = Blockchain::new_pow_with_genesis(1, 50.0, allocations).unwrap();
+        // A mark below the size of the blocks alone prunes every snapshot
+        let optimizer = MemoryOptimizer::new(Duration::from_secs(1), 0.8).with_high_water_mark(1);
+        blockchain.set_snapshot_pruning(SnapshotPruning::new(Arc::new(optimizer), storage.clone()));
+
+        for i in 0..4 {
+            blockchain.add_transaction(&alice_key, format!("user{}", i), units::gil(1), None).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+        }
+        assert!(blockchain.state_snapshots.is_empty());
+        assert!(storage.load_state_snapshot(2).unwrap().is_some());
+
+        // Rolling back to a pruned snapshot reloads it and restores its state
+        blockchain.rollback_to_snapshot(2).unwrap();
+        assert_eq!(blockchain.get_balance(&alice), 499.0);
+        assert!(blockchain.rollback_to_snapshot(3).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_blockchain_reads_invalidated_on_change() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let manager = PerformanceManager::new(PerformanceConfig::default());
+        let allocations = HashMap::from([
+            (alice.clone(), units::gil(500)),
+            ("bob".to_string(), units::gil(100)),
+        ]);
+        let mut blockchain = Blockchain::new_pow_with_genesis(1, 50.0, allocations).unwrap();
+        let contract_id = blockchain.deploy_contract(
+            alice.clone(), "PUSH 7\nRETURN".to_string(), 10_000, 0.000001,
+        ).unwrap();
+
+        // Reads are not cached until the chain is attached
+        assert_eq!(manager.get_balance(&blockchain, &alice).await, 500.0);
+        assert_eq!(manager.cache_manager().get_stats().await.total_requests, 0);
+
+        // Warm the cache
+        manager.attach(&mut blockchain);
+        assert_eq!(manager.get_balance(&blockchain, &alice).await, 500.0);
+        assert_eq!(manager.get_balance(&blockchain, "bob").await, 100.0);
+        assert!(manager.get_contract(&blockchain, &contract_id).await.is_some());
+        assert_eq!(manager.get_balance(&blockchain, &alice).await, 500.0);
+        let stats = manager.cache_manager().get_stats().await;
+        assert_eq!((stats.hits, stats.misses), (1, 3));
+
+        // Mining a transfer invalidates exactly the balances it changed
+        blockchain.add_transaction(&alice_key, "carol".to_string(), units::gil(100), None).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+        let cache = manager.cache_manager();
+        assert!(cache.get(&balance_cache_key(&alice)).await.is_none());
+        assert!(cache.get(&balance_cache_key("bob")).await.is_some());
+        assert!(cache.get(&contract_cache_key(&contract_id)).await.is_some());
+        assert_eq!(manager.get_balance(&blockchain, &alice).await, 400.0);
+        assert_eq!(manager.get_balance(&blockchain, "carol").await, 100.0);
+        assert_eq!(manager.get_balance(&blockchain, "miner").await, 50.0);
+
+        // Contract calls invalidate cached contracts, and the funds they receive show up
+        blockchain.call_contract(
+            "bob".to_string(), contract_id.clone(), "run".to_string(), units::gil(1), 10_000, 0.000001,
+        ).unwrap();
+        assert!(cache.get(&balance_cache_key(&alice)).await.is_some());
+        let contract = manager.get_contract(&blockchain, &contract_id).await.unwrap();
+        assert_eq!(contract.balance, units::gil(1));
+        assert_eq!(manager.get_balance(&blockchain, "bob").await, blockchain.get_balance("bob"));
+    }
+
     #[tokio::test]
     async fn test_memory_optimizer() {
         let optimizer = MemoryOptimizer::new(Duration::from_secs(1), 0.8);
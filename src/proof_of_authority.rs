@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use log::debug;
+use std::any::Any;
+use crate::{
+    Result, BlockchainError,
+    block::Block,
+    consensus::{Consensus, ConsensusStateRef, ConsensusType},
+    crypto::{self, KeyPair, PublicKey},
+};
+
+/// Proof of Authority implementation for permissioned chains
+///
+/// A fixed set of authorities take turns producing blocks in round-robin order:
+/// the block at height `h` must be produced and signed by authority
+/// `h % authorities.len()`. Blocks are paid to and identified by the address
+/// of the authority's public key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofOfAuthority {
+    /// Authorized signers in turn order
+    pub authorities: Vec<PublicKey>,
+    /// Key this node seals its blocks with, if it is an authority
+    #[serde(skip)]
+    signer: Option<KeyPair>,
+}
+
+impl ProofOfAuthority {
+    /// Create a new Proof of Authority instance
+    ///
+    /// # Arguments
+    /// * `authorities` - Authorized signers in turn order
+    ///
+    /// # Returns
+    /// * `Result<ProofOfAuthority>` - The PoA instance, or an error if the set is empty or has duplicates
+    pub fn new(authorities: Vec<PublicKey>) -> Result<Self> {
+        if authorities.is_empty() {
+            return Err(BlockchainError::ConsensusError(
+                "PoA requires at least one authority".to_string(),
+            ));
+        }
+        for (i, authority) in authorities.iter().enumerate() {
+            if authorities[..i].contains(authority) {
+                return Err(BlockchainError::ConsensusError(
+                    format!("Duplicate authority {}", authority.short()),
+                ));
+            }
+        }
+
+        Ok(Self {
+            authorities,
+            signer: None,
+        })
+    }
+
+    /// Set the key this node seals blocks with
+    ///
+    /// # Arguments
+    /// * `keypair` - Key pair of one of the authorities
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if set, error if the key is not an authority
+    pub fn set_signer(&mut self, keypair: KeyPair) -> Result<()> {
+        if !self.authorities.contains(&keypair.public_key()) {
+            return Err(BlockchainError::ConsensusError(
+                format!("{} is not an authority", keypair.public_key().short()),
+            ));
+        }
+        self.signer = Some(keypair);
+        Ok(())
+    }
+
+    /// Get the authority whose turn it is to produce the block at `height`
+    ///
+    /// # Arguments
+    /// * `height` - Block index
+    ///
+    /// # Returns
+    /// * `&PublicKey` - The in-turn authority
+    pub fn authority_at(&self, height: u64) -> &PublicKey {
+        &self.authorities[(height % self.authorities.len() as u64) as usize]
+    }
+}
+
+impl Consensus for ProofOfAuthority {
+    fn consensus_type(&self) -> ConsensusType {
+        ConsensusType::ProofOfAuthority
+    }
+
+    fn select_producer(&self, height: u64, _previous_hash: &str) -> Result<Option<String>> {
+        Ok(Some(crypto::create_address(self.authority_at(height))))
+    }
+
+    fn seal_block(&self, block: &mut Block) -> Result<()> {
+        let signer = self.signer.as_ref()
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                "No authority key configured to seal blocks".to_string(),
+            ))?;
+        let authority = self.authority_at(block.index);
+        if &signer.public_key() != authority {
+            return Err(BlockchainError::ConsensusError(format!(
+                "Block {} must be produced by {}, not {}",
+                block.index, authority.short(), signer.public_key().short()
+            )));
+        }
+
+        block.validator = Some(crypto::create_address(authority));
+        block.hash = block.calculate_current_hash();
+        block.validator_signature = Some(signer.sign(block.hash.as_bytes())?);
+        debug!("Sealed block {} as authority {}", block.index, authority.short());
+        Ok(())
+    }
+
+    fn validate_block(&self, block: &Block) -> Result<()> {
+        let authority = self.authority_at(block.index);
+        let signature = block.validator_signature.as_ref()
+            .ok_or_else(|| BlockchainError::ConsensusError(
+                format!("PoA block {} is not signed", block.index),
+            ))?;
+
+        if signature.public_key != authority.key {
+            let signer = PublicKey { key: signature.public_key.clone() };
+            let reason = if self.authorities.contains(&signer) { "out of turn" } else { "not an authority" };
+            return Err(BlockchainError::ConsensusError(format!(
+                "Block {} signed by {} which is {}",
+                block.index, signer.short(), reason
+            )));
+        }
+        if block.validator.as_deref() != Some(crypto::create_address(authority).as_str()) {
+            return Err(BlockchainError::ConsensusError(
+                format!("Block {} validator does not match its signer", block.index),
+            ));
+        }
+        if !signature.verify(block.hash.as_bytes())? {
+            return Err(BlockchainError::ConsensusError(
+                format!("Block {} has an invalid authority signature", block.index),
+            ));
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn Consensus> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn state(&self) -> ConsensusStateRef<'_> {
+        ConsensusStateRef::ProofOfAuthority(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authority_set_validation() {
+        let keypair = KeyPair::generate().unwrap();
+        assert!(ProofOfAuthority::new(Vec::new()).is_err());
+        assert!(ProofOfAuthority::new(vec![keypair.public_key(), keypair.public_key()]).is_err());
+
+        let mut poa = ProofOfAuthority::new(vec![keypair.public_key()]).unwrap();
+        assert!(poa.set_signer(KeyPair::generate().unwrap()).is_err());
+        assert!(poa.set_signer(keypair).is_ok());
+    }
+
+    #[test]
+    fn test_round_robin_turns() {
+        let keys: Vec<PublicKey> = (0..3).map(|_| KeyPair::generate().unwrap().public_key()).collect();
+        let poa = ProofOfAuthority::new(keys.clone()).unwrap();
+        for height in 0..6 {
+            assert_eq!(poa.authority_at(height), &keys[height as usize % 3]);
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use log::{debug, info, warn};
-use crate::{Result, BlockchainError, utils};
+use rayon::prelude::*;
+use crate::{Result, BlockchainError, utils, block::Block, consensus::{Consensus, ConsensusStateRef, ConsensusType}};
+use std::any::Any;
 
 /// Proof of Work implementation for blockchain mining
 /// 
@@ -109,6 +111,58 @@ impl ProofOfWork {
         }
     }
 
+    /// Mine a block across all rayon worker threads
+    ///
+    /// The nonce range `1..=max_attempts` is split between the workers. Once a worker
+    /// finds a valid hash the others stop searching beyond it, and the lowest valid
+    /// nonce is returned, so the result is identical to [`ProofOfWork::mine`].
+    ///
+    /// # Arguments
+    /// * `block_data` - The block data to mine (without nonce)
+    /// * `previous_hash` - Hash of the previous block
+    ///
+    /// # Returns
+    /// * `Result<(u64, String)>` - Tuple of (nonce, hash) or an error
+    ///
+    /// # Example
+    /// ```
+    /// use gillean::proof_of_work::ProofOfWork;
+    ///
+    /// let pow = ProofOfWork::new(2, 100_000).unwrap();
+    /// let (nonce, hash) = pow.mine_parallel("block data", "previous_hash").unwrap();
+    /// assert_eq!(pow.mine("block data", "previous_hash").unwrap(), (nonce, hash));
+    /// ```
+    pub fn mine_parallel(&self, block_data: &str, previous_hash: &str) -> Result<(u64, String)> {
+        info!(
+            "Starting parallel mining with difficulty {} on {} threads",
+            self.difficulty,
+            rayon::current_num_threads()
+        );
+
+        let start_time = std::time::Instant::now();
+        let solution = (1..=self.max_attempts)
+            .into_par_iter()
+            .map(|nonce| {
+                let data = format!("{}:{}:{}", block_data, previous_hash, nonce);
+                (nonce, utils::calculate_hash(data))
+            })
+            .find_first(|(_, hash)| utils::hash_meets_difficulty(hash, self.difficulty));
+
+        match solution {
+            Some((nonce, hash)) => {
+                info!(
+                    "Parallel mining successful! Nonce: {}, Hash: {}, Time: {:?}",
+                    nonce, hash, start_time.elapsed()
+                );
+                Ok((nonce, hash))
+            }
+            None => {
+                warn!("Parallel mining timeout after {} attempts", self.max_attempts);
+                Err(BlockchainError::MiningTimeout(self.max_attempts))
+            }
+        }
+    }
+
     /// Validate that a hash meets the proof of work requirements
     /// 
     /// # Arguments
@@ -232,6 +286,63 @@ impl Default for ProofOfWork {
     }
 }
 
+impl Consensus for ProofOfWork {
+    fn consensus_type(&self) -> ConsensusType {
+        ConsensusType::ProofOfWork
+    }
+
+    fn select_producer(&self, _height: u64, _previous_hash: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn seal_block(&self, block: &mut Block) -> Result<()> {
+        block.mine(self)
+    }
+
+    fn validate_block(&self, block: &Block) -> Result<()> {
+        // Blocks stored before targets were recorded carry 0 and are weighted as minimal work
+        if block.difficulty != 0 && block.difficulty != self.difficulty {
+            return Err(BlockchainError::InvalidProofOfWork(format!(
+                "Block {} claims difficulty {} but {} is required",
+                block.index, block.difficulty, self.difficulty
+            )));
+        }
+        if !self.validate_hash(&block.hash) {
+            return Err(BlockchainError::InvalidProofOfWork(
+                format!("Block {} hash does not meet difficulty requirement", block.index),
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_historical_block(&self, block: &Block) -> Result<()> {
+        // Difficulty is retargeted as the chain grows, so each block is held to the one it was mined at
+        let difficulty = if block.difficulty == 0 { self.difficulty } else { block.difficulty };
+        if !utils::hash_meets_difficulty(&block.hash, difficulty) {
+            return Err(BlockchainError::InvalidProofOfWork(
+                format!("Block {} hash does not meet difficulty requirement", block.index),
+            ));
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn Consensus> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn state(&self) -> ConsensusStateRef<'_> {
+        ConsensusStateRef::ProofOfWork(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,6 +376,18 @@ mod tests {
         assert!(nonce > 0);
     }
 
+    #[test]
+    fn test_parallel_mining_matches_serial() {
+        let pow = ProofOfWork::new(2, 100_000).unwrap();
+        let (nonce, hash) = pow.mine_parallel("test data", "previous_hash").unwrap();
+
+        assert!(pow.validate_solution("test data", "previous_hash", nonce, &hash).unwrap());
+        assert_eq!(pow.mine("test data", "previous_hash").unwrap(), (nonce, hash));
+
+        let exhausted = ProofOfWork::new(8, 10).unwrap();
+        assert!(exhausted.mine_parallel("test data", "previous_hash").is_err());
+    }
+
     #[test]
     fn test_validate_hash() {
         let pow = ProofOfWork::new(2, 1000).unwrap();
@@ -0,0 +1,417 @@
+//! # Optimistic Rollups
+//!
+//! Layer 2 scaling by aggregating many off-chain transfers into a single on-chain
+//! commitment. A batch commits to the state root before and after its transactions
+//! plus a hash of the transaction list, and is accepted optimistically: it finalizes
+//! once its challenge window has passed unless someone submits a fraud proof showing
+//! that the claimed post-state root is not the result of applying the transactions.
+
+use crate::{Result, BlockchainError, Transaction, units};
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Digest};
+use std::collections::HashMap;
+use log::{info, warn};
+
+/// Off-chain rollup state: account balances on layer 2
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RollupState {
+    /// Balances of all layer 2 accounts, in base units
+    pub balances: HashMap<String, u128>,
+}
+
+/// Lifecycle of a submitted rollup batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BatchStatus {
+    /// Accepted optimistically and still open to challenges
+    Pending,
+    /// Challenge window passed without a successful challenge
+    Finalized,
+    /// Reverted by a fraud proof, or built on top of a reverted batch
+    Reverted,
+}
+
+/// A batch of off-chain transactions committed on-chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollupBatch {
+    /// Batch ID
+    pub id: String,
+    /// Transactions aggregated into the batch
+    pub transactions: Vec<Transaction>,
+    /// State root before the transactions are applied
+    pub pre_state_root: String,
+    /// Claimed state root after the transactions are applied
+    pub post_state_root: String,
+    /// Hash of the ordered transaction list
+    pub tx_list_hash: String,
+    /// Block height at which the batch was submitted
+    pub submitted_at: u64,
+    /// Batch status
+    pub status: BatchStatus,
+}
+
+/// Evidence that a batch claims an invalid state transition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FraudProof {
+    /// ID of the challenged batch
+    pub batch_id: String,
+    /// Full state the batch was applied to, matching its pre-state root
+    pub pre_state: RollupState,
+}
+
+/// On-chain side of an optimistic rollup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimisticRollup {
+    /// State root the first batch must build on
+    pub genesis_root: String,
+    /// Submitted batches in submission order
+    pub batches: Vec<RollupBatch>,
+    /// Number of blocks a batch stays open to challenges
+    pub challenge_window: u64,
+    /// Current layer 1 block height
+    pub current_block: u64,
+}
+
+impl RollupState {
+    /// Create a rollup state from initial balances
+    ///
+    /// # Arguments
+    /// * `balances` - Initial layer 2 balances
+    pub fn new(balances: HashMap<String, u128>) -> Self {
+        Self { balances }
+    }
+
+    /// Compute the state root committing to every balance
+    ///
+    /// # Returns
+    /// * `String` - Hex-encoded SHA-256 over the balances in address order
+    pub fn root(&self) -> String {
+        let mut accounts: Vec<(&String, &u128)> = self.balances.iter().collect();
+        accounts.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut hasher = Sha256::new();
+        for (address, balance) in accounts {
+            hasher.update(format!("{}:{};", address, units::format_units(*balance)).as_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Apply transfers to the state
+    ///
+    /// # Arguments
+    /// * `transactions` - Transfers to apply in order
+    ///
+    /// # Returns
+    /// * `Result<()>` - Error if a transfer is invalid against the state
+    pub fn apply(&mut self, transactions: &[Transaction]) -> Result<()> {
+        for transaction in transactions {
+            if transaction.amount == 0 {
+                return Err(BlockchainError::InvalidTransaction(
+                    format!("Transaction {} has a non-positive amount", transaction.id),
+                ));
+            }
+            let balance = self.balances.get(&transaction.sender).copied().unwrap_or(0);
+            if balance < transaction.amount {
+                return Err(BlockchainError::InsufficientBalance {
+                    address: transaction.sender.clone(),
+                    balance: units::from_base_units(balance),
+                    required: units::from_base_units(transaction.amount),
+                });
+            }
+            *self.balances.entry(transaction.sender.clone()).or_insert(0) -= transaction.amount;
+            let receiver = self.balances.entry(transaction.receiver.clone()).or_insert(0);
+            *receiver = receiver.checked_add(transaction.amount).ok_or_else(|| BlockchainError::InvalidTransaction(
+                format!("Transaction {} overflows the balance of {}", transaction.id, transaction.receiver),
+            ))?;
+        }
+        Ok(())
+    }
+}
+
+impl RollupBatch {
+    /// Build a batch by applying transactions to the current rollup state
+    ///
+    /// # Arguments
+    /// * `transactions` - Off-chain transfers to aggregate
+    /// * `state` - Rollup state before the batch
+    ///
+    /// # Returns
+    /// * `Result<RollupBatch>` - The batch, or an error if a transfer is invalid
+    pub fn new(transactions: Vec<Transaction>, state: &RollupState) -> Result<Self> {
+        if transactions.is_empty() {
+            return Err(BlockchainError::InvalidInput("Cannot create empty batch".to_string()));
+        }
+
+        let pre_state_root = state.root();
+        let mut post_state = state.clone();
+        post_state.apply(&transactions)?;
+        let tx_list_hash = Self::hash_transactions(&transactions)?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(pre_state_root.as_bytes());
+        hasher.update(tx_list_hash.as_bytes());
+        let id = format!("batch_{}", hex::encode(&hasher.finalize()[..8]));
+
+        Ok(Self {
+            id,
+            transactions,
+            pre_state_root,
+            post_state_root: post_state.root(),
+            tx_list_hash,
+            submitted_at: 0,
+            status: BatchStatus::Pending,
+        })
+    }
+
+    /// Hash an ordered transaction list
+    ///
+    /// Covers the canonical bytes of every transaction, not just its ID, so editing a
+    /// transaction's contents changes the hash. Each entry is length-prefixed so
+    /// adjacent transactions cannot be re-split.
+    pub fn hash_transactions(transactions: &[Transaction]) -> Result<String> {
+        let mut hasher = Sha256::new();
+        for transaction in transactions {
+            let bytes = transaction.canonical_bytes()?;
+            hasher.update((bytes.len() as u64).to_be_bytes());
+            hasher.update(&bytes);
+        }
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Single on-chain commitment covering the state transition and transaction list
+    pub fn commitment(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.pre_state_root.as_bytes());
+        hasher.update(self.post_state_root.as_bytes());
+        hasher.update(self.tx_list_hash.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+}
+
+impl OptimisticRollup {
+    /// Create a rollup starting from a genesis state
+    ///
+    /// # Arguments
+    /// * `genesis` - Initial layer 2 state
+    /// * `challenge_window` - Number of blocks a batch stays open to challenges
+    pub fn new(genesis: &RollupState, challenge_window: u64) -> Self {
+        Self {
+            genesis_root: genesis.root(),
+            batches: Vec::new(),
+            challenge_window,
+            current_block: 0,
+        }
+    }
+
+    /// State root the next batch must build on
+    pub fn latest_root(&self) -> &str {
+        self.batches
+            .iter()
+            .rev()
+            .find(|batch| batch.status != BatchStatus::Reverted)
+            .map(|batch| batch.post_state_root.as_str())
+            .unwrap_or(&self.genesis_root)
+    }
+
+    /// Get a batch by ID
+    pub fn get_batch(&self, batch_id: &str) -> Option<&RollupBatch> {
+        self.batches.iter().find(|batch| batch.id == batch_id)
+    }
+
+    /// Post a batch optimistically
+    ///
+    /// The batch is accepted without re-executing its transactions; it only has to
+    /// build on the latest state root and commit to its own transaction list.
+    ///
+    /// # Arguments
+    /// * `batch` - The batch to submit
+    ///
+    /// # Returns
+    /// * `Result<String>` - The batch ID, or an error if the batch does not chain
+    pub fn submit_batch(&mut self, mut batch: RollupBatch) -> Result<String> {
+        if batch.tx_list_hash != RollupBatch::hash_transactions(&batch.transactions)? {
+            return Err(BlockchainError::InvalidInput(
+                "Transaction list hash does not match the batch transactions".to_string(),
+            ));
+        }
+        if batch.pre_state_root != self.latest_root() {
+            return Err(BlockchainError::InvalidInput(format!(
+                "Batch builds on state root {} but the rollup is at {}",
+                batch.pre_state_root, self.latest_root()
+            )));
+        }
+        if self.get_batch(&batch.id).is_some() {
+            return Err(BlockchainError::InvalidInput(format!("Batch {} already submitted", batch.id)));
+        }
+
+        batch.submitted_at = self.current_block;
+        batch.status = BatchStatus::Pending;
+        let batch_id = batch.id.clone();
+        info!("Submitted rollup batch {} with {} transactions", batch_id, batch.transactions.len());
+        self.batches.push(batch);
+
+        Ok(batch_id)
+    }
+
+    /// Advance the layer 1 block height, finalizing batches whose challenge window passed
+    ///
+    /// # Arguments
+    /// * `height` - New block height
+    ///
+    /// # Returns
+    /// * `Vec<String>` - IDs of the batches finalized by this call
+    pub fn advance_to_block(&mut self, height: u64) -> Vec<String> {
+        self.current_block = self.current_block.max(height);
+
+        let mut finalized = Vec::new();
+        for batch in &mut self.batches {
+            if batch.status == BatchStatus::Pending
+                && self.current_block >= batch.submitted_at + self.challenge_window
+            {
+                batch.status = BatchStatus::Finalized;
+                finalized.push(batch.id.clone());
+            }
+        }
+        finalized
+    }
+
+    /// Challenge a pending batch with a fraud proof
+    ///
+    /// The batch's transactions are re-executed on the proof's pre-state. If that
+    /// fails, or yields a root other than the one the batch claimed, the batch and
+    /// every pending batch built on top of it are reverted.
+    ///
+    /// # Arguments
+    /// * `proof` - Fraud proof for the challenged batch
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if the batch was reverted, error if the challenge is invalid
+    pub fn challenge_batch(&mut self, proof: FraudProof) -> Result<()> {
+        let position = self.batches
+            .iter()
+            .position(|batch| batch.id == proof.batch_id)
+            .ok_or_else(|| BlockchainError::NotFound(format!("Batch not found: {}", proof.batch_id)))?;
+        let batch = &self.batches[position];
+
+        match batch.status {
+            BatchStatus::Pending => {}
+            BatchStatus::Finalized => {
+                return Err(BlockchainError::InvalidInput(format!(
+                    "Challenge window for batch {} has closed", batch.id
+                )));
+            }
+            BatchStatus::Reverted => {
+                return Err(BlockchainError::InvalidInput(format!("Batch {} is already reverted", batch.id)));
+            }
+        }
+        if proof.pre_state.root() != batch.pre_state_root {
+            return Err(BlockchainError::InvalidInput(
+                "Fraud proof pre-state does not match the batch pre-state root".to_string(),
+            ));
+        }
+
+        let mut state = proof.pre_state;
+        let valid = state.apply(&batch.transactions).is_ok() && state.root() == batch.post_state_root;
+        if valid {
+            return Err(BlockchainError::InvalidInput(
+                "Fraud proof does not show an invalid state transition".to_string(),
+            ));
+        }
+
+        for later in &mut self.batches[position..] {
+            if later.status == BatchStatus::Pending {
+                later.status = BatchStatus::Reverted;
+                warn!("Reverted rollup batch {}", later.id);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn genesis() -> RollupState {
+        RollupState::new(HashMap::from([
+            ("alice".to_string(), units::gil(100)),
+            ("bob".to_string(), units::gil(50)),
+        ]))
+    }
+
+    fn transfer(sender: &str, receiver: &str, amount: u64) -> Transaction {
+        Transaction::new_transfer(sender.to_string(), receiver.to_string(), units::gil(amount), None).unwrap()
+    }
+
+    #[test]
+    fn test_valid_batch_finalizes_after_challenge_window() {
+        let state = genesis();
+        let mut rollup = OptimisticRollup::new(&state, 10);
+        let batch = RollupBatch::new(
+            vec![transfer("alice", "bob", 30), transfer("bob", "carol", 20)],
+            &state,
+        ).unwrap();
+        let post_root = batch.post_state_root.clone();
+
+        rollup.advance_to_block(5);
+        let batch_id = rollup.submit_batch(batch).unwrap();
+        assert_eq!(rollup.latest_root(), post_root);
+
+        // An honest batch cannot be challenged
+        let proof = FraudProof { batch_id: batch_id.clone(), pre_state: state };
+        assert!(rollup.challenge_batch(proof.clone()).is_err());
+
+        assert!(rollup.advance_to_block(14).is_empty());
+        assert_eq!(rollup.advance_to_block(15), vec![batch_id.clone()]);
+        assert_eq!(rollup.get_batch(&batch_id).unwrap().status, BatchStatus::Finalized);
+        assert!(rollup.challenge_batch(proof).is_err());
+    }
+
+    #[test]
+    fn test_invalid_batch_is_challenged() {
+        let state = genesis();
+        let mut rollup = OptimisticRollup::new(&state, 10);
+
+        // The aggregator claims alice ended up with more than she had
+        let mut forged = state.clone();
+        forged.balances.insert("alice".to_string(), units::gil(1_000));
+        let mut batch = RollupBatch::new(vec![transfer("alice", "bob", 30)], &state).unwrap();
+        batch.post_state_root = forged.root();
+        let batch_id = rollup.submit_batch(batch).unwrap();
+
+        // A batch built on the forged root is reverted along with it
+        let next = RollupBatch::new(vec![transfer("alice", "bob", 1)], &forged).unwrap();
+        let next_id = rollup.submit_batch(next).unwrap();
+
+        let mut wrong_state = state.clone();
+        wrong_state.balances.insert("bob".to_string(), 0);
+        assert!(rollup.challenge_batch(FraudProof { batch_id: batch_id.clone(), pre_state: wrong_state }).is_err());
+
+        rollup.challenge_batch(FraudProof { batch_id: batch_id.clone(), pre_state: state.clone() }).unwrap();
+        assert_eq!(rollup.get_batch(&batch_id).unwrap().status, BatchStatus::Reverted);
+        assert_eq!(rollup.get_batch(&next_id).unwrap().status, BatchStatus::Reverted);
+        assert_eq!(rollup.latest_root(), state.root());
+        assert!(rollup.advance_to_block(100).is_empty());
+    }
+
+    #[test]
+    fn test_submit_rejects_broken_chain() {
+        let state = genesis();
+        let mut rollup = OptimisticRollup::new(&state, 10);
+
+        let mut other = state.clone();
+        other.balances.insert("dave".to_string(), units::gil(1));
+        let detached = RollupBatch::new(vec![transfer("alice", "bob", 1)], &other).unwrap();
+        assert!(rollup.submit_batch(detached).is_err());
+
+        let mut tampered = RollupBatch::new(vec![transfer("alice", "bob", 1)], &state).unwrap();
+        tampered.transactions.push(transfer("bob", "alice", 1));
+        assert!(rollup.submit_batch(tampered).is_err());
+
+        // Changing a transaction's contents while keeping its ID is caught too
+        let mut edited = RollupBatch::new(vec![transfer("alice", "bob", 1)], &state).unwrap();
+        edited.transactions[0].amount = units::gil(90);
+        assert!(rollup.submit_batch(edited).is_err());
+
+        assert!(RollupBatch::new(vec![transfer("carol", "bob", 1)], &state).is_err());
+    }
+}
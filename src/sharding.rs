@@ -144,7 +144,10 @@ impl Shard {
         let _storage = BlockchainStorage::new(&db_path)?;
         let blockchain = match consensus_type {
             ConsensusType::ProofOfWork => Blockchain::new_pow(4, 50.0)?,
-            ConsensusType::ProofOfStake => Blockchain::new_pos(50.0, 100.0, 5)?,
+            ConsensusType::ProofOfStake => Blockchain::new_pos(50.0, crate::units::gil(100), 5)?,
+            ConsensusType::ProofOfAuthority => return Err(BlockchainError::ConsensusError(
+                "Shards do not support PoA consensus".to_string(),
+            )),
         };
         
         let (tx_sender, tx_receiver) = bounded(1000);
@@ -186,13 +189,8 @@ impl Shard {
     fn process_single_shard_transaction(&mut self, mut shard_tx: ShardTransaction) -> Result<()> {
         shard_tx.status = ShardTransactionStatus::Processing;
         
-        // Add transaction to blockchain
-        self.blockchain.add_transaction(
-            shard_tx.transaction.sender.clone(),
-            shard_tx.transaction.receiver.clone(),
-            shard_tx.transaction.amount,
-            shard_tx.transaction.message.clone(),
-        )?;
+        // Add the signed transaction to the blockchain as submitted
+        self.blockchain.add_transaction_object(shard_tx.transaction.clone())?;
         
         // Try to mine a block if we have enough transactions
         if self.blockchain.pending_transactions.len() >= 10 {
@@ -2,30 +2,133 @@ use crate::error::BlockchainError;
 use crate::storage::BlockchainStorage;
 use crate::blockchain::Blockchain;
 use crate::transaction::Transaction;
-use crate::wallet::WalletManager;
+use crate::crypto::{self, KeyPair};
+use crate::units;
 use crate::ethereum::EthereumBridge;
 use crate::did::DecentralizedIdentity;
 use crate::governance::Governance;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
+/// Balance each simulated wallet is funded with before the run starts, in base units
+pub const SIMULATION_WALLET_BALANCE: u128 = units::gil(1_000);
+
+/// Simulated time to produce a block, before network latency
+pub const SIMULATED_BLOCK_INTERVAL_MS: u64 = 1_000;
+
+/// Longest simulation a configuration may request
+pub const MAX_SIMULATION_BLOCKS: u64 = 100_000;
+
 /// Simulation configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationConfig {
     pub duration_blocks: u64,
     pub num_nodes: u64,
     pub num_wallets: u64,
-    pub transaction_rate: f64, // transactions per block
+    pub transaction_rate: f64, // transactions per wallet per block
+    #[serde(default)]
     pub zkp_enabled: bool,
+    #[serde(default)]
     pub state_channels_enabled: bool,
+    #[serde(default)]
     pub ethereum_integration_enabled: bool,
+    #[serde(default)]
     pub governance_enabled: bool,
     pub network_conditions: NetworkConditions,
     pub shard_config: ShardConfig,
+    #[serde(default)]
     pub failure_scenarios: Vec<FailureScenario>,
+    /// Seed for the simulation RNG; runs with the same seed are reproducible
+    #[serde(default)]
+    pub seed: u64,
+}
+
+impl SimulationConfig {
+    /// Load a simulation configuration from a TOML file
+    /// 
+    /// # Arguments
+    /// * `path` - Path to the TOML file
+    /// 
+    /// # Returns
+    /// * `Result<SimulationConfig>` - The validated configuration or an error
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self, BlockchainError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| BlockchainError::InvalidInput(
+                format!("Failed to read simulation config {}: {}", path.display(), e)
+            ))?;
+        Self::from_toml_str(&content)
+    }
+
+    /// Parse a simulation configuration from TOML
+    /// 
+    /// # Arguments
+    /// * `content` - TOML document
+    /// 
+    /// # Returns
+    /// * `Result<SimulationConfig>` - The validated configuration or an error
+    pub fn from_toml_str(content: &str) -> Result<Self, BlockchainError> {
+        let config: Self = toml::from_str(content)
+            .map_err(|e| BlockchainError::InvalidInput(format!("Invalid simulation config: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that all values are within range
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if valid, otherwise an error naming the offending field
+    pub fn validate(&self) -> Result<(), BlockchainError> {
+        let invalid = |field: &str, requirement: &str| {
+            Err(BlockchainError::InvalidInput(
+                format!("Invalid simulation config: {} must be {}", field, requirement)
+            ))
+        };
+        let is_rate = |value: f64| (0.0..=1.0).contains(&value);
+
+        if self.duration_blocks == 0 || self.duration_blocks > MAX_SIMULATION_BLOCKS {
+            return invalid("duration_blocks", &format!("between 1 and {}", MAX_SIMULATION_BLOCKS));
+        }
+        if self.num_nodes == 0 {
+            return invalid("num_nodes", "at least 1");
+        }
+        if self.num_wallets < 2 {
+            return invalid("num_wallets", "at least 2");
+        }
+        if !self.transaction_rate.is_finite() || self.transaction_rate < 0.0 {
+            return invalid("transaction_rate", "a non-negative number");
+        }
+        if !self.network_conditions.bandwidth_mbps.is_finite() || self.network_conditions.bandwidth_mbps <= 0.0 {
+            return invalid("network_conditions.bandwidth_mbps", "positive");
+        }
+        if !is_rate(self.network_conditions.packet_loss_rate) {
+            return invalid("network_conditions.packet_loss_rate", "between 0 and 1");
+        }
+        if !is_rate(self.network_conditions.node_failure_rate) {
+            return invalid("network_conditions.node_failure_rate", "between 0 and 1");
+        }
+        if self.shard_config.num_shards == 0 {
+            return invalid("shard_config.num_shards", "at least 1");
+        }
+        if !is_rate(self.shard_config.cross_shard_tx_rate) {
+            return invalid("shard_config.cross_shard_tx_rate", "between 0 and 1");
+        }
+        for scenario in &self.failure_scenarios {
+            if let FailureScenario::NodeFailure { node_id, .. } = scenario {
+                if *node_id >= self.num_nodes {
+                    return invalid("failure_scenarios.NodeFailure.node_id", "less than num_nodes");
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Network conditions for simulation
@@ -66,16 +169,21 @@ pub struct SimulationResult {
 }
 
 /// Simulation metrics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 
+/// Times are measured on the simulated clock, so runs with the same seed
+/// produce identical metrics.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SimulationMetrics {
     pub total_blocks: u64,
     pub total_transactions: u64,
+    #[serde(default)]
+    pub failed_transactions: u64,
     pub total_zkp_transactions: u64,
     pub total_state_channel_transactions: u64,
     pub total_ethereum_transfers: u64,
     pub total_governance_proposals: u64,
-    pub average_block_time: f64,
-    pub average_transaction_throughput: f64,
+    pub average_block_time: f64, // simulated seconds per block
+    pub average_transaction_throughput: f64, // transactions per simulated second
     pub zkp_generation_time: f64,
     pub state_channel_success_rate: f64,
     pub ethereum_bridge_success_rate: f64,
@@ -85,7 +193,7 @@ pub struct SimulationMetrics {
 }
 
 /// Node performance metrics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct NodePerformance {
     pub blocks_mined: u64,
     pub transactions_processed: u64,
@@ -128,11 +236,34 @@ pub struct SimulationManager {
     did_system: Option<Arc<DecentralizedIdentity>>,
     governance: Option<Arc<Governance>>,
     config: SimulationConfig,
-    wallets: Arc<RwLock<HashMap<String, WalletManager>>>,
+    wallets: Arc<RwLock<HashMap<String, KeyPair>>>,
     events: Arc<RwLock<Vec<SimulationEvent>>>,
     metrics: Arc<RwLock<SimulationMetrics>>,
     current_block: Arc<RwLock<u64>>,
     start_time: chrono::DateTime<chrono::Utc>,
+    rng: Arc<Mutex<StdRng>>,
+    tracker: Arc<RwLock<SimulationTracker>>,
+}
+
+/// Running totals the metrics are derived from
+#[derive(Debug, Default)]
+struct SimulationTracker {
+    simulated_time_ms: f64,
+    blocks_elapsed: u64,
+    down_nodes: HashSet<u64>,
+    node_up_blocks: HashMap<u64, u64>,
+    node_latency_ms: HashMap<u64, f64>,
+    shard_transactions: HashMap<u64, u64>,
+    state_channels_opened: u64,
+    state_channels_closed: u64,
+    ethereum_transfers_completed: u64,
+    governance_votes: u64,
+}
+
+/// Network state for a single simulated block
+struct BlockConditions {
+    latency_ms: f64,
+    partitioned: bool,
 }
 
 impl SimulationManager {
@@ -142,18 +273,22 @@ impl SimulationManager {
         blockchain: Arc<Mutex<Blockchain>>,
         config: SimulationConfig,
     ) -> Result<Self, BlockchainError> {
+        config.validate()?;
+
         let simulation = Self {
             storage,
             blockchain,
             ethereum_bridge: None,
             did_system: None,
             governance: None,
-            config,
             wallets: Arc::new(RwLock::new(HashMap::new())),
             events: Arc::new(RwLock::new(Vec::new())),
             metrics: Arc::new(RwLock::new(SimulationMetrics::default())),
             current_block: Arc::new(RwLock::new(0)),
             start_time: chrono::Utc::now(),
+            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(config.seed))),
+            tracker: Arc::new(RwLock::new(SimulationTracker::default())),
+            config,
         };
 
         // Initialize optional components based on config
@@ -174,32 +309,38 @@ impl SimulationManager {
     }
 
     /// Run the simulation
+    /// 
+    /// Drives the blockchain for `duration_blocks` blocks on a simulated clock,
+    /// generating transactions from the seeded RNG.
     pub async fn run_simulation(&self) -> Result<SimulationResult, BlockchainError> {
         info!("Starting blockchain simulation with config: {:?}", self.config);
         
-        let _start_time = chrono::Utc::now();
-        
         // Run simulation for specified number of blocks
         for block_number in 0..self.config.duration_blocks {
             *self.current_block.write().await = block_number;
             
             // Process failure scenarios
-            self.process_failure_scenarios(block_number).await?;
+            let partitioned = self.process_failure_scenarios(block_number).await?;
             
             // Simulate network conditions
-            self.simulate_network_conditions().await?;
+            let mut conditions = self.simulate_network_conditions(block_number).await?;
+            conditions.partitioned |= partitioned;
             
             // Generate and process transactions
-            self.generate_transactions(block_number).await?;
+            self.generate_transactions(block_number, &conditions).await?;
             
             // Mine block
             self.mine_block(block_number).await?;
             
+            // Advance the simulated clock
+            {
+                let mut tracker = self.tracker.write().await;
+                tracker.simulated_time_ms += SIMULATED_BLOCK_INTERVAL_MS as f64 + conditions.latency_ms;
+                tracker.blocks_elapsed += 1;
+            }
+            
             // Update metrics
             self.update_metrics(block_number).await?;
-            
-            // Add small delay to simulate real-time
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
         }
         
         let end_time = chrono::Utc::now();
@@ -223,17 +364,28 @@ impl SimulationManager {
     }
 
     /// Initialize wallets for simulation
+    /// 
+    /// Each wallet gets its own signing key and is funded with
+    /// `SIMULATION_WALLET_BALANCE` in a block mined before the simulation starts.
     async fn initialize_wallets(&self) -> Result<(), BlockchainError> {
         let mut wallets = self.wallets.write().await;
         
-        for i in 0..self.config.num_wallets {
-            let wallet_name = format!("sim_wallet_{}", i);
-                    let wallet = WalletManager::new();
-            
-            // Add some initial balance for simulation
-            // In a real implementation, you'd add this to the blockchain state
-            
-            wallets.insert(wallet_name, wallet);
+        for _ in 0..self.config.num_wallets {
+            let keypair = KeyPair::generate()?;
+            wallets.insert(crypto::create_address(&keypair.public_key()), keypair);
+        }
+
+        if !wallets.is_empty() {
+            let mut blockchain = self.blockchain.lock().unwrap();
+            for address in wallets.keys() {
+                blockchain.add_transaction_object(Transaction::new_transfer(
+                    "COINBASE".to_string(),
+                    address.clone(),
+                    SIMULATION_WALLET_BALANCE,
+                    Some("Simulation funding".to_string()),
+                )?)?;
+            }
+            blockchain.mine_block("simulation_faucet".to_string())?;
         }
         
         info!("Initialized {} wallets for simulation", self.config.num_wallets);
@@ -241,27 +393,62 @@ impl SimulationManager {
     }
 
     /// Generate transactions for current block
-    async fn generate_transactions(&self, block_number: u64) -> Result<(), BlockchainError> {
+    async fn generate_transactions(&self, block_number: u64, conditions: &BlockConditions) -> Result<(), BlockchainError> {
         let num_transactions = (self.config.transaction_rate * self.config.num_wallets as f64) as u64;
+        let mut submitted = 0;
+        let mut failed = 0;
         
         for _ in 0..num_transactions {
-            let transaction = self.create_random_transaction(block_number).await?;
+            let (shard_id, transaction) = self.create_random_transaction(block_number).await?;
+            let sender = self.wallets.read().await[&transaction.sender].clone();
+
+            // Dropped by the network
+            if conditions.partitioned || self.random_f64() < self.config.network_conditions.packet_loss_rate {
+                failed += 1;
+                continue;
+            }
             
-            // Add transaction to blockchain
-            {
+            // Add transaction to blockchain, keeping pending transfers funded
+            let result = {
                 let mut blockchain = self.blockchain.lock().unwrap();
-                blockchain.add_transaction(
-                    transaction.sender.clone(),
-                    transaction.receiver.clone(),
-                    transaction.amount,
-                    transaction.message.clone(),
-                )?;
+                let pending_outgoing: u128 = blockchain.pending_transactions.iter()
+                    .filter(|tx| tx.sender == transaction.sender)
+                    .map(|tx| tx.amount)
+                    .sum();
+                let available = blockchain.get_balance_units(&transaction.sender).saturating_sub(pending_outgoing);
+                if available < transaction.amount {
+                    Err(BlockchainError::InsufficientBalance {
+                        address: transaction.sender.clone(),
+                        balance: units::from_base_units(available),
+                        required: units::from_base_units(transaction.amount),
+                    })
+                } else {
+                    blockchain.add_transaction(
+                        &sender,
+                        transaction.receiver.clone(),
+                        transaction.amount,
+                        transaction.message.clone(),
+                    )
+                }
+            };
+
+            if result.is_err() {
+                failed += 1;
+                continue;
             }
+            submitted += 1;
+            *self.tracker.write().await.shard_transactions.entry(shard_id).or_insert(0) += 1;
             
             // Record event
             self.record_event(block_number, SimulationEventType::TransactionProcessed, 
                 [("transaction_type".to_string(), "regular".to_string())].into()).await;
         }
+
+        {
+            let mut metrics = self.metrics.write().await;
+            metrics.total_transactions += submitted;
+            metrics.failed_transactions += failed;
+        }
         
         // Generate ZKP transactions if enabled
         if self.config.zkp_enabled {
@@ -287,33 +474,38 @@ impl SimulationManager {
     }
 
     /// Create a random transaction
-    async fn create_random_transaction(&self, block_number: u64) -> Result<Transaction, BlockchainError> {
+    /// 
+    /// Returns the shard of the sender along with the transaction.
+    async fn create_random_transaction(&self, block_number: u64) -> Result<(u64, Transaction), BlockchainError> {
         let wallets = self.wallets.read().await;
-        let wallet_names: Vec<String> = wallets.keys().cloned().collect();
+        let mut wallet_names: Vec<String> = wallets.keys().cloned().collect();
+        wallet_names.sort();
         
         if wallet_names.len() < 2 {
             return Err(BlockchainError::ValidatorError("Not enough wallets for transaction".to_string()));
         }
         
-        let sender = &wallet_names[rand::random::<usize>() % wallet_names.len()];
-        let receiver = &wallet_names[rand::random::<usize>() % wallet_names.len()];
+        let sender_index = self.random_index(wallet_names.len());
+        let receiver_index = (sender_index + 1 + self.random_index(wallet_names.len() - 1)) % wallet_names.len();
         
-        let amount = rand::random::<f64>() * 100.0 + 1.0; // 1-101 GIL
+        let amount = units::to_base_units(self.random_f64() * 100.0 + 1.0)?; // 1-101 GIL
         
         let transaction = Transaction::new_transfer(
-            sender.to_string(),
-            receiver.to_string(),
+            wallet_names[sender_index].clone(),
+            wallet_names[receiver_index].clone(),
             amount,
             Some(format!("Simulation transaction at block {}", block_number)),
         )?;
         
-        Ok(transaction)
+        let shard_id = sender_index as u64 % self.config.shard_config.num_shards.max(1);
+        Ok((shard_id, transaction))
     }
 
     /// Generate ZKP transactions
     async fn generate_zkp_transactions(&self, block_number: u64) -> Result<(), BlockchainError> {
         // Simulate ZKP transaction creation
         // In a real implementation, this would create actual ZKP transactions
+        self.metrics.write().await.total_zkp_transactions += 1;
         
         self.record_event(block_number, SimulationEventType::ZKPGenerated, 
             [("zkp_type".to_string(), "private_transfer".to_string())].into()).await;
@@ -326,12 +518,14 @@ impl SimulationManager {
         // Simulate state channel operations
         if block_number % 10 == 0 {
             // Open new state channel every 10 blocks
+            self.tracker.write().await.state_channels_opened += 1;
             self.record_event(block_number, SimulationEventType::StateChannelOpened, 
                 [("participants".to_string(), "2".to_string())].into()).await;
         }
         
         if block_number % 50 == 0 {
             // Close state channel every 50 blocks
+            self.tracker.write().await.state_channels_closed += 1;
             self.record_event(block_number, SimulationEventType::StateChannelClosed, 
                 [("final_balance".to_string(), "100.0".to_string())].into()).await;
         }
@@ -343,11 +537,13 @@ impl SimulationManager {
     async fn generate_ethereum_transfers(&self, block_number: u64) -> Result<(), BlockchainError> {
         // Simulate Ethereum bridge transfers
         if block_number % 20 == 0 {
+            self.metrics.write().await.total_ethereum_transfers += 1;
             self.record_event(block_number, SimulationEventType::EthereumTransferInitiated, 
                 [("amount".to_string(), "50.0".to_string())].into()).await;
         }
         
         if block_number % 25 == 0 {
+            self.tracker.write().await.ethereum_transfers_completed += 1;
             self.record_event(block_number, SimulationEventType::EthereumTransferCompleted, 
                 [("tx_hash".to_string(), "0x123...".to_string())].into()).await;
         }
@@ -359,11 +555,13 @@ impl SimulationManager {
     async fn generate_governance_activity(&self, block_number: u64) -> Result<(), BlockchainError> {
         // Simulate governance proposals and votes
         if block_number % 100 == 0 {
+            self.metrics.write().await.total_governance_proposals += 1;
             self.record_event(block_number, SimulationEventType::GovernanceProposalCreated, 
                 [("proposal_type".to_string(), "parameter_change".to_string())].into()).await;
         }
         
         if block_number % 110 == 0 {
+            self.tracker.write().await.governance_votes += 1;
             self.record_event(block_number, SimulationEventType::GovernanceVoteCast, 
                 [("vote".to_string(), "yes".to_string())].into()).await;
         }
@@ -372,95 +570,167 @@ impl SimulationManager {
     }
 
     /// Mine a block
+    /// 
+    /// The block is mined by a random node that is up. No block is produced if
+    /// every node is down or there are no pending transactions.
     async fn mine_block(&self, block_number: u64) -> Result<(), BlockchainError> {
-        // Simulate block mining
-        {
+        let up_nodes: Vec<u64> = {
+            let tracker = self.tracker.read().await;
+            (0..self.config.num_nodes).filter(|id| !tracker.down_nodes.contains(id)).collect()
+        };
+        if up_nodes.is_empty() {
+            warn!("No nodes available to mine block {}", block_number);
+            return Ok(());
+        }
+        let miner = up_nodes[self.random_index(up_nodes.len())];
+
+        let (transactions_processed, difficulty) = {
             let mut blockchain = self.blockchain.lock().unwrap();
-            blockchain.mine_block("simulation_miner".to_string())?;
+            if blockchain.pending_transactions.is_empty() {
+                return Ok(());
+            }
+            let block = blockchain.mine_block(format!("sim_node_{}", miner))?;
+            // Exclude the mining reward
+            (block.transaction_count().saturating_sub(1) as u64, blockchain.difficulty)
+        };
+
+        {
+            let mut metrics = self.metrics.write().await;
+            metrics.total_blocks += 1;
+            let performance = metrics.node_performance.entry(miner).or_default();
+            performance.blocks_mined += 1;
+            performance.transactions_processed += transactions_processed;
         }
         
         self.record_event(block_number, SimulationEventType::BlockMined, 
-            [("difficulty".to_string(), "4".to_string())].into()).await;
+            [
+                ("difficulty".to_string(), difficulty.to_string()),
+                ("miner".to_string(), miner.to_string()),
+            ].into()).await;
         
         Ok(())
     }
 
     /// Process failure scenarios
-    async fn process_failure_scenarios(&self, block_number: u64) -> Result<(), BlockchainError> {
+    /// 
+    /// Returns whether the network is partitioned during this block.
+    async fn process_failure_scenarios(&self, block_number: u64) -> Result<bool, BlockchainError> {
+        let mut partitioned = false;
+
         for scenario in &self.config.failure_scenarios {
             match scenario {
                 FailureScenario::NodeFailure { node_id, block_number: failure_block } => {
                     if block_number == *failure_block {
+                        self.tracker.write().await.down_nodes.insert(*node_id);
                         self.record_event(block_number, SimulationEventType::NodeFailure, 
                             [("node_id".to_string(), node_id.to_string())].into()).await;
                     }
                 }
                 FailureScenario::NetworkPartition { duration_blocks, block_number: start_block } => {
                     if block_number >= *start_block && block_number < start_block + duration_blocks {
+                        partitioned = true;
                         self.record_event(block_number, SimulationEventType::NetworkPartition, 
                             [("duration".to_string(), duration_blocks.to_string())].into()).await;
                     }
                 }
-                FailureScenario::HighLatency { duration_blocks: _, latency_ms } => {
-                    // Simulate high latency
-                    tokio::time::sleep(tokio::time::Duration::from_millis(*latency_ms)).await;
+                FailureScenario::HighLatency { .. } => {
+                    // Applied by simulate_network_conditions
                 }
                 FailureScenario::InvalidTransaction { transaction_id, block_number: failure_block } => {
                     if block_number == *failure_block {
-                        // Simulate invalid transaction
+                        // Submit a self-transfer, which the blockchain rejects
                         warn!("Simulating invalid transaction: {}", transaction_id);
+                        let wallet = self.wallets.read().await.iter()
+                            .min_by(|a, b| a.0.cmp(b.0))
+                            .map(|(address, keypair)| (address.clone(), keypair.clone()));
+                        let result = match wallet {
+                            Some((address, keypair)) => self.blockchain.lock().unwrap().add_transaction(
+                                &keypair,
+                                address,
+                                units::gil(1),
+                                Some(transaction_id.clone()),
+                            ),
+                            None => Err(BlockchainError::ValidatorError("No wallets to submit from".to_string())),
+                        };
+                        if result.is_err() {
+                            self.metrics.write().await.failed_transactions += 1;
+                        }
                     }
                 }
             }
         }
         
-        Ok(())
+        Ok(partitioned)
     }
 
     /// Simulate network conditions
-    async fn simulate_network_conditions(&self) -> Result<(), BlockchainError> {
+    /// 
+    /// Draws this block's latency and random node failures from the RNG. Packet
+    /// loss is applied per transaction.
+    async fn simulate_network_conditions(&self, block_number: u64) -> Result<BlockConditions, BlockchainError> {
         let conditions = &self.config.network_conditions;
         
-        // Simulate latency
-        if conditions.latency_ms > 0 {
-            tokio::time::sleep(tokio::time::Duration::from_millis(conditions.latency_ms)).await;
-        }
-        
-        // Simulate packet loss
-        if rand::random::<f64>() < conditions.packet_loss_rate {
-            warn!("Simulating packet loss");
+        // Simulate latency, jittered between 50% and 150% of the configured value
+        let mut latency_ms = conditions.latency_ms as f64 * (0.5 + self.random_f64());
+        for scenario in &self.config.failure_scenarios {
+            if let FailureScenario::HighLatency { duration_blocks, latency_ms: extra } = scenario {
+                if block_number < *duration_blocks {
+                    latency_ms += *extra as f64;
+                }
+            }
         }
         
         // Simulate node failure
-        if rand::random::<f64>() < conditions.node_failure_rate {
-            warn!("Simulating node failure");
+        let mut tracker = self.tracker.write().await;
+        for node_id in 0..self.config.num_nodes {
+            if tracker.down_nodes.contains(&node_id) {
+                continue;
+            }
+            if self.random_f64() < conditions.node_failure_rate {
+                warn!("Simulating node failure: {}", node_id);
+                tracker.down_nodes.insert(node_id);
+                continue;
+            }
+            *tracker.node_up_blocks.entry(node_id).or_insert(0) += 1;
+            *tracker.node_latency_ms.entry(node_id).or_insert(0.0) += latency_ms;
         }
         
-        Ok(())
+        Ok(BlockConditions { latency_ms, partitioned: false })
     }
 
     /// Update metrics for current block
-    async fn update_metrics(&self, block_number: u64) -> Result<(), BlockchainError> {
+    async fn update_metrics(&self, _block_number: u64) -> Result<(), BlockchainError> {
+        let tracker = self.tracker.read().await;
         let mut metrics = self.metrics.write().await;
         
-        metrics.total_blocks = block_number + 1;
+        let simulated_seconds = tracker.simulated_time_ms / 1000.0;
+        if metrics.total_blocks > 0 {
+            metrics.average_block_time = simulated_seconds / metrics.total_blocks as f64;
+        }
+        if simulated_seconds > 0.0 {
+            metrics.average_transaction_throughput = metrics.total_transactions as f64 / simulated_seconds;
+        }
         
         // Update shard utilization
         for shard_id in 0..self.config.shard_config.num_shards {
-            let utilization = rand::random::<f64>() * 100.0;
+            let shard_transactions = *tracker.shard_transactions.get(&shard_id).unwrap_or(&0);
+            let utilization = percentage(shard_transactions, metrics.total_transactions);
             metrics.shard_utilization.insert(shard_id, utilization);
         }
         
         // Update node performance
         for node_id in 0..self.config.num_nodes {
-            let performance = NodePerformance {
-                blocks_mined: rand::random::<u64>() % 10,
-                transactions_processed: rand::random::<u64>() % 100,
-                uptime_percentage: 95.0 + rand::random::<f64>() * 5.0,
-                average_response_time: 10.0 + rand::random::<f64>() * 50.0,
-            };
-            metrics.node_performance.insert(node_id, performance);
+            let up_blocks = *tracker.node_up_blocks.get(&node_id).unwrap_or(&0);
+            let latency_ms = *tracker.node_latency_ms.get(&node_id).unwrap_or(&0.0);
+            let performance = metrics.node_performance.entry(node_id).or_default();
+            performance.uptime_percentage = percentage(up_blocks, tracker.blocks_elapsed);
+            performance.average_response_time = if up_blocks > 0 { latency_ms / up_blocks as f64 } else { 0.0 };
         }
+
+        metrics.total_state_channel_transactions = tracker.state_channels_opened + tracker.state_channels_closed;
+        metrics.state_channel_success_rate = percentage(tracker.state_channels_closed, tracker.state_channels_opened);
+        metrics.ethereum_bridge_success_rate = percentage(tracker.ethereum_transfers_completed, metrics.total_ethereum_transfers);
+        metrics.governance_participation_rate = percentage(tracker.governance_votes, metrics.total_governance_proposals);
         
         Ok(())
     }
@@ -480,17 +750,17 @@ impl SimulationManager {
 
     /// Collect final metrics
     async fn collect_final_metrics(&self) -> Result<SimulationMetrics, BlockchainError> {
-        let mut metrics = self.metrics.write().await.clone();
-        
-        // Calculate averages and final statistics
-        metrics.average_block_time = 1.0; // 1 second per block in simulation
-        metrics.average_transaction_throughput = metrics.total_transactions as f64 / metrics.total_blocks as f64;
-        metrics.zkp_generation_time = 0.5; // Simulated ZKP generation time
-        metrics.state_channel_success_rate = 95.0; // Simulated success rate
-        metrics.ethereum_bridge_success_rate = 90.0; // Simulated success rate
-        metrics.governance_participation_rate = 75.0; // Simulated participation rate
-        
-        Ok(metrics)
+        Ok(self.metrics.read().await.clone())
+    }
+
+    /// Draw a uniform value in [0, 1) from the simulation RNG
+    fn random_f64(&self) -> f64 {
+        self.rng.lock().unwrap().gen()
+    }
+
+    /// Draw a uniform index in [0, len) from the simulation RNG
+    fn random_index(&self, len: usize) -> usize {
+        self.rng.lock().unwrap().gen_range(0..len)
     }
 
     /// Get simulation progress
@@ -513,6 +783,8 @@ impl SimulationManager {
             events: self.events.clone(),
             current_block: self.current_block.clone(),
             start_time: self.start_time,
+            rng: self.rng.clone(),
+            tracker: self.tracker.clone(),
         }
     }
 
@@ -531,6 +803,14 @@ impl SimulationManager {
     }
 }
 
+/// Share of `part` in `total` as a percentage, capped at 100
+fn percentage(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (part as f64 / total as f64 * 100.0).min(100.0)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationState {
     pub current_block: u64,
@@ -544,6 +824,7 @@ impl Default for SimulationMetrics {
         Self {
             total_blocks: 0,
             total_transactions: 0,
+            failed_transactions: 0,
             total_zkp_transactions: 0,
             total_state_channel_transactions: 0,
             total_ethereum_transfers: 0,
@@ -595,6 +876,7 @@ mod tests {
                 shard_load_balancing: true,
             },
             failure_scenarios: vec![],
+            seed: 0,
         };
 
         let simulation = SimulationManager::new(storage, blockchain, config).await.unwrap();
@@ -626,10 +908,134 @@ mod tests {
             failure_scenarios: vec![
                 FailureScenario::NodeFailure { node_id: 1, block_number: 50 },
             ],
+            seed: 42,
         };
 
         assert_eq!(config.duration_blocks, 100);
         assert_eq!(config.num_nodes, 5);
         assert!(config.zkp_enabled);
     }
+
+    fn seeded_config(seed: u64) -> SimulationConfig {
+        SimulationConfig {
+            duration_blocks: 8,
+            num_nodes: 3,
+            num_wallets: 4,
+            transaction_rate: 2.0,
+            zkp_enabled: true,
+            state_channels_enabled: true,
+            ethereum_integration_enabled: false,
+            governance_enabled: false,
+            network_conditions: NetworkConditions {
+                latency_ms: 20,
+                bandwidth_mbps: 100.0,
+                packet_loss_rate: 0.1,
+                node_failure_rate: 0.05,
+            },
+            shard_config: ShardConfig {
+                num_shards: 2,
+                cross_shard_tx_rate: 0.1,
+                shard_load_balancing: true,
+            },
+            failure_scenarios: vec![
+                FailureScenario::InvalidTransaction { transaction_id: "bad_tx".to_string(), block_number: 2 },
+            ],
+            seed,
+        }
+    }
+
+    async fn run_seeded(seed: u64) -> SimulationResult {
+        let temp_dir = tempdir().unwrap();
+        let storage = Arc::new(BlockchainStorage::new(temp_dir.path().to_str().unwrap()).unwrap());
+        let blockchain = Arc::new(Mutex::new(Blockchain::new_pow(1, 50.0).unwrap()));
+        let simulation = SimulationManager::new(storage, blockchain.clone(), seeded_config(seed)).await.unwrap();
+        let result = simulation.run_simulation().await.unwrap();
+
+        // The blockchain actually advanced: funding block plus mined simulation blocks
+        let block_count = blockchain.lock().unwrap().get_stats().block_count as u64;
+        assert_eq!(block_count, 2 + result.metrics.total_blocks);
+        result
+    }
+
+    #[tokio::test]
+    async fn test_simulation_is_reproducible() {
+        let first = run_seeded(7).await;
+        let second = run_seeded(7).await;
+
+        assert!(first.success);
+        assert!(first.metrics.total_blocks > 0);
+        assert!(first.metrics.total_transactions > 0);
+        assert!(first.metrics.failed_transactions >= 1);
+        assert!(first.metrics.average_block_time >= 1.0);
+        assert_eq!(first.metrics, second.metrics);
+
+        let other = run_seeded(8).await;
+        assert_ne!(first.metrics, other.metrics);
+    }
+
+    const VALID_TOML: &str = r#"
+seed = 11
+duration_blocks = 5
+num_nodes = 2
+num_wallets = 3
+transaction_rate = 1.0
+zkp_enabled = true
+
+[network_conditions]
+latency_ms = 10
+bandwidth_mbps = 100.0
+packet_loss_rate = 0.0
+node_failure_rate = 0.0
+
+[shard_config]
+num_shards = 2
+cross_shard_tx_rate = 0.1
+shard_load_balancing = true
+
+[[failure_scenarios]]
+NodeFailure = { node_id = 1, block_number = 3 }
+"#;
+
+    #[tokio::test]
+    async fn test_simulation_config_from_toml_file() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("simulation.toml");
+        std::fs::write(&config_path, VALID_TOML).unwrap();
+
+        let config = SimulationConfig::from_toml_file(&config_path).unwrap();
+        assert_eq!(config.seed, 11);
+        assert_eq!(config.duration_blocks, 5);
+        assert!(config.zkp_enabled);
+        assert!(!config.governance_enabled);
+        assert_eq!(config.failure_scenarios.len(), 1);
+
+        let storage = Arc::new(BlockchainStorage::new(temp_dir.path().join("db").to_str().unwrap()).unwrap());
+        let blockchain = Arc::new(Mutex::new(Blockchain::new_pow(1, 50.0).unwrap()));
+        let simulation = SimulationManager::new(storage, blockchain, config).await.unwrap();
+        let result = simulation.run_simulation().await.unwrap();
+        assert!(result.success);
+        assert!(result.metrics.total_blocks > 0);
+    }
+
+    #[test]
+    fn test_simulation_config_rejects_invalid_toml() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("broken.toml");
+        std::fs::write(&config_path, "duration_blocks = [").unwrap();
+
+        let result = SimulationConfig::from_toml_file(&config_path);
+        assert!(matches!(result, Err(BlockchainError::InvalidInput(_))));
+
+        // Missing required field
+        let missing = VALID_TOML.replace("num_nodes = 2\n", "");
+        let error = SimulationConfig::from_toml_str(&missing).unwrap_err().to_string();
+        assert!(error.contains("num_nodes"), "{}", error);
+
+        // Out of range value
+        let out_of_range = VALID_TOML.replace("packet_loss_rate = 0.0", "packet_loss_rate = 1.5");
+        let error = SimulationConfig::from_toml_str(&out_of_range).unwrap_err().to_string();
+        assert!(error.contains("packet_loss_rate"), "{}", error);
+
+        assert!(SimulationConfig::from_toml_file(temp_dir.path().join("missing.toml")).is_err());
+    }
 }
@@ -5,6 +5,31 @@ use crate::{Result, BlockchainError};
 use std::time::{SystemTime, UNIX_EPOCH};
 use regex::Regex;
 
+/// Patterns rejected in contract code, with the reason reported for each
+pub(crate) const BANNED_PATTERNS: &[(&str, &str)] = &[
+    (r"eval\s*\(", "Use of eval() is not allowed"),
+    (r"exec\s*\(", "Use of exec() is not allowed"),
+    (r"system\s*\(", "Use of system() is not allowed"),
+    (r"shell_exec\s*\(", "Use of shell_exec() is not allowed"),
+    (r"file_get_contents\s*\(", "Use of file_get_contents() is not allowed"),
+    (r"fopen\s*\(", "Use of fopen() is not allowed"),
+    (r"curl_exec\s*\(", "Use of curl_exec() is not allowed"),
+    (r"passthru\s*\(", "Use of passthru() is not allowed"),
+    (r"proc_open\s*\(", "Use of proc_open() is not allowed"),
+    (r"popen\s*\(", "Use of popen() is not allowed"),
+    (r"include\s+", "Use of include is not allowed"),
+    (r"require\s+", "Use of require is not allowed"),
+    (r"import\s+", "Use of import is not allowed"),
+    (r"__import__\s*\(", "Use of __import__ is not allowed"),
+    (r"subprocess\s*\.", "Use of subprocess is not allowed"),
+    (r"os\s*\.", "Use of os module is not allowed"),
+    (r"sys\s*\.", "Use of sys module is not allowed"),
+    (r"pickle\s*\.", "Use of pickle is not allowed"),
+    (r"marshal\s*\.", "Use of marshal is not allowed"),
+    (r"compile\s*\(", "Use of compile() is not allowed"),
+    (r"execfile\s*\(", "Use of execfile() is not allowed"),
+];
+
 /// Represents a smart contract with code and state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SmartContract {
@@ -13,11 +38,12 @@ pub struct SmartContract {
     /// Contract code as a string
     pub code: String,
     /// Contract storage (key-value pairs)
+    #[serde(serialize_with = "crate::utils::sorted_map")]
     pub storage: HashMap<String, String>,
     /// Contract owner address
     pub owner: String,
-    /// Contract balance
-    pub balance: f64,
+    /// Contract balance, in base units
+    pub balance: u128,
     /// Whether the contract is active
     pub active: bool,
     /// Creation timestamp
@@ -43,10 +69,62 @@ pub struct ContractContext {
     pub contract_address: String,
     /// Maximum stack depth
     pub max_stack_depth: usize,
-    /// Maximum storage size
+    /// Maximum total size of a contract's storage keys and values, in bytes
     pub max_storage_size: usize,
     /// Execution timeout in milliseconds
     pub execution_timeout: u64,
+    /// Maximum number of instructions executed, shared by nested calls
+    pub max_instructions: u64,
+    /// Contracts currently executing in this call chain, outermost first
+    pub call_stack: Vec<String>,
+    /// Record a gas profile of the execution in its result
+    pub profile: bool,
+}
+
+/// Instruction categories used for gas accounting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GasCategory {
+    /// Stack manipulation (PUSH, POP, DUP, SWAP, NOP)
+    Stack,
+    /// Storage access (STORE, LOAD)
+    Storage,
+    /// Arithmetic (ADD, SUB, MUL, DIV)
+    Arithmetic,
+    /// Comparisons (EQ, GT, LT, GTE, LTE)
+    Comparison,
+    /// Control flow (IF, ENDIF, LOOP, ENDLOOP, JUMP, JUMPIF, RETURN)
+    ControlFlow,
+    /// Contract calls (CALL)
+    Call,
+}
+
+/// Gas cost of each VM instruction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GasSchedule {
+    /// Cost of PUSH
+    pub push: u64,
+    /// Cost of POP
+    pub pop: u64,
+    /// Cost of STORE
+    pub store: u64,
+    /// Cost of LOAD
+    pub load: u64,
+    /// Cost of ADD and SUB
+    pub add: u64,
+    /// Cost of MUL and DIV
+    pub mul: u64,
+    /// Cost of EQ, GT, LT, GTE and LTE
+    pub compare: u64,
+    /// Cost of IF, ENDIF, LOOP, ENDLOOP, JUMP and JUMPIF
+    pub branch: u64,
+    /// Cost of CALL
+    pub call: u64,
+    /// Cost of RETURN
+    pub ret: u64,
+    /// Cost of EMIT
+    pub emit: u64,
+    /// Cost of DUP, SWAP, NOP and any other instruction
+    pub base: u64,
 }
 
 /// Stack-based virtual machine for executing smart contracts
@@ -60,6 +138,40 @@ pub struct ContractVM {
     gas_used: u64,
     /// Gas limit
     gas_limit: u64,
+    /// Gas cost per instruction
+    gas_schedule: GasSchedule,
+    /// Gas used per instruction category
+    gas_by_category: HashMap<GasCategory, u64>,
+    /// Events emitted so far
+    events: Vec<ContractEvent>,
+    /// Total size of the storage keys and values, in bytes
+    storage_bytes: usize,
+    /// Instructions executed so far, including those of nested calls
+    instructions_executed: u64,
+    /// Gas used per contract line, recorded when profiling
+    line_profiles: HashMap<(String, u32), LineProfile>,
+    /// Storage of each contract written by a nested call, as it was before the write
+    storage_journal: Vec<(String, HashMap<String, String>)>,
+}
+
+/// Event emitted by a contract during execution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractEvent {
+    /// Index of the block the emitting execution belongs to
+    pub block_index: u64,
+    /// Address of the emitting contract
+    pub contract_id: String,
+    /// Event name
+    pub event_name: String,
+    /// Event payload
+    pub payload: String,
+}
+
+/// Historical log of contract events
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContractEventLog {
+    /// Events in emission order
+    pub events: Vec<ContractEvent>,
 }
 
 /// Smart contract execution result
@@ -75,6 +187,39 @@ pub struct ContractResult {
     pub error: Option<String>,
     /// Storage changes
     pub storage_changes: HashMap<String, String>,
+    /// Gas used per instruction category
+    #[serde(default)]
+    pub gas_by_category: HashMap<GasCategory, u64>,
+    /// Events emitted during execution, including those of called contracts
+    #[serde(default)]
+    pub events: Vec<ContractEvent>,
+    /// Gas profile, present when the context requested profiling
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<GasProfile>,
+}
+
+/// Where the gas of a contract execution went
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GasProfile {
+    /// Gas used per instruction type, e.g. `PUSH`
+    pub by_instruction: HashMap<String, u64>,
+    /// Lines ordered by the gas they used, most expensive first
+    pub hotspots: Vec<LineProfile>,
+}
+
+/// Gas used by one line of contract code
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineProfile {
+    /// Contract the line belongs to
+    pub contract_address: String,
+    /// 1-based line number
+    pub line: u32,
+    /// Instruction on the line
+    pub instruction: String,
+    /// Number of times the line was executed
+    pub executions: u64,
+    /// Total gas used by the line
+    pub gas_used: u64,
 }
 
 impl SmartContract {
@@ -97,9 +242,25 @@ impl SmartContract {
     /// ).unwrap();
     /// 
     /// assert_eq!(contract.owner, "alice");
-    /// assert_eq!(contract.balance, 0.0);
+    /// assert_eq!(contract.balance, 0);
     /// ```
     pub fn new(code: String, owner: String) -> Result<Self> {
+        Self::with_nonce(code, owner, 0)
+    }
+
+    /// Create a smart contract as the owner's `deployment_nonce`-th deployment
+    /// 
+    /// The nonce is part of the contract ID, so deploying the same code again
+    /// from the same owner yields a distinct but reproducible address.
+    /// 
+    /// # Arguments
+    /// * `code` - Contract code as a string
+    /// * `owner` - Contract owner address
+    /// * `deployment_nonce` - Number of contracts the owner deployed before this one
+    /// 
+    /// # Returns
+    /// * `Result<SmartContract>` - The created contract or an error
+    pub fn with_nonce(code: String, owner: String, deployment_nonce: u64) -> Result<Self> {
         // Validate inputs
         if code.is_empty() {
             return Err(BlockchainError::ContractValidationFailed(
@@ -130,7 +291,7 @@ impl SmartContract {
             ));
         }
 
-        let id = Self::generate_id(&code, &owner);
+        let id = Self::generate_id(&code, &owner, deployment_nonce);
         let created_at = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -141,7 +302,7 @@ impl SmartContract {
             code,
             storage: HashMap::new(),
             owner,
-            balance: 0.0,
+            balance: 0,
             active: true,
             created_at,
         };
@@ -153,31 +314,7 @@ impl SmartContract {
     /// Validate contract code for security vulnerabilities
     fn validate_contract_code(code: &str) -> Result<()> {
         // Check for dangerous patterns
-        let dangerous_patterns = vec![
-            (r"eval\s*\(", "Use of eval() is not allowed"),
-            (r"exec\s*\(", "Use of exec() is not allowed"),
-            (r"system\s*\(", "Use of system() is not allowed"),
-            (r"shell_exec\s*\(", "Use of shell_exec() is not allowed"),
-            (r"file_get_contents\s*\(", "Use of file_get_contents() is not allowed"),
-            (r"fopen\s*\(", "Use of fopen() is not allowed"),
-            (r"curl_exec\s*\(", "Use of curl_exec() is not allowed"),
-            (r"passthru\s*\(", "Use of passthru() is not allowed"),
-            (r"proc_open\s*\(", "Use of proc_open() is not allowed"),
-            (r"popen\s*\(", "Use of popen() is not allowed"),
-            (r"include\s+", "Use of include is not allowed"),
-            (r"require\s+", "Use of require is not allowed"),
-            (r"import\s+", "Use of import is not allowed"),
-            (r"__import__\s*\(", "Use of __import__ is not allowed"),
-            (r"subprocess\s*\.", "Use of subprocess is not allowed"),
-            (r"os\s*\.", "Use of os module is not allowed"),
-            (r"sys\s*\.", "Use of sys module is not allowed"),
-            (r"pickle\s*\.", "Use of pickle is not allowed"),
-            (r"marshal\s*\.", "Use of marshal is not allowed"),
-            (r"compile\s*\(", "Use of compile() is not allowed"),
-            (r"execfile\s*\(", "Use of execfile() is not allowed"),
-        ];
-
-        for (pattern, message) in dangerous_patterns {
+        for (pattern, message) in BANNED_PATTERNS {
             let regex = Regex::new(pattern).unwrap();
             if regex.is_match(code) {
                 return Err(BlockchainError::ContractValidationFailed(
@@ -260,7 +397,7 @@ impl SmartContract {
         let valid_instructions = vec![
             "PUSH", "POP", "STORE", "LOAD", "ADD", "SUB", "MUL", "DIV",
             "EQ", "GT", "LT", "GTE", "LTE", "IF", "ENDIF", "LOOP", "ENDLOOP",
-            "RETURN", "CALL", "JUMP", "JUMPIF", "DUP", "SWAP", "NOP"
+            "RETURN", "CALL", "JUMP", "JUMPIF", "DUP", "SWAP", "NOP", "REENTRANT", "EMIT", "METHOD"
         ];
 
         for (line_num, line) in lines.iter().enumerate() {
@@ -297,7 +434,16 @@ impl SmartContract {
                         ));
                     }
                 }
-                "STORE" | "LOAD" => {
+                "METHOD" => {
+                    let valid_name = parts.get(1).is_some_and(|name| Self::is_valid_identifier(name));
+                    let valid_modifier = parts.get(2).is_none_or(|modifier| modifier.eq_ignore_ascii_case("VIEW"));
+                    if !valid_name || !valid_modifier || parts.len() > 3 {
+                        return Err(BlockchainError::ContractValidationFailed(
+                            format!("METHOD requires a name and an optional VIEW modifier at line {}", line_num + 1),
+                        ));
+                    }
+                }
+                "STORE" | "LOAD" | "EMIT" => {
                     if parts.len() < 2 {
                         return Err(BlockchainError::ContractValidationFailed(
                             format!("{} requires a key at line {}", instruction, line_num + 1),
@@ -363,17 +509,112 @@ impl SmartContract {
         address.chars().all(|c| c.is_alphanumeric() || c.eq(&'_') || c.eq(&'-'))
     }
 
-    /// Generate a unique contract ID
-    fn generate_id(code: &str, owner: &str) -> String {
+    /// Derive a contract ID from its code, owner and the owner's deployment nonce
+    /// 
+    /// # Arguments
+    /// * `code` - Contract code
+    /// * `owner` - Contract owner address
+    /// * `deployment_nonce` - Number of contracts the owner deployed before this one
+    /// 
+    /// # Returns
+    /// * `String` - The hex-encoded contract ID
+    pub fn generate_id(code: &str, owner: &str, deployment_nonce: u64) -> String {
         use sha2::{Sha256, Digest};
         let mut hasher = Sha256::new();
         hasher.update(code.as_bytes());
         hasher.update(owner.as_bytes());
-        // Remove timestamp to make ID deterministic for testing
-        // hasher.update(chrono::Utc::now().timestamp().to_string().as_bytes());
+        hasher.update(deployment_nonce.to_be_bytes());
         format!("{:x}", hasher.finalize())
     }
 
+    /// Select the code to run for a call
+    /// 
+    /// Contracts may split their code into sections headed by `METHOD <name>` lines.
+    /// Code before the first section is the constructor, run on deployment and when no
+    /// method is named. Contracts without sections run their whole code for every call.
+    /// Lines outside the selected section are blanked so line numbers in errors still
+    /// match the deployed code.
+    /// 
+    /// # Arguments
+    /// * `method` - Method named by the call data, if any
+    /// 
+    /// # Returns
+    /// * `Result<String>` - Code to execute, or an error if the method does not exist
+    pub fn entry_point(&self, method: Option<&str>) -> Result<String> {
+        Self::select_entry_point(&self.code, method)
+    }
+
+    /// Names of the methods declared with `METHOD` sections
+    pub fn methods(&self) -> Vec<String> {
+        self.code
+            .lines()
+            .filter_map(Self::method_header)
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    /// Check whether a method is declared read-only with `METHOD <name> VIEW`
+    pub fn is_view(&self, method: &str) -> bool {
+        self.code.lines().any(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            parts.len() == 3
+                && parts[0].eq_ignore_ascii_case("METHOD")
+                && parts[1] == method
+                && parts[2].eq_ignore_ascii_case("VIEW")
+        })
+    }
+
+    fn method_header(line: &str) -> Option<&str> {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some(instruction) if instruction.eq_ignore_ascii_case("METHOD") => parts.next(),
+            _ => None,
+        }
+    }
+
+    fn select_entry_point(code: &str, method: Option<&str>) -> Result<String> {
+        let has_methods = code.lines().any(|line| Self::method_header(line).is_some());
+        let target = match method {
+            Some(name) if has_methods => Some(name),
+            _ if has_methods => None,
+            _ => return Ok(code.to_string()),
+        };
+
+        if let Some(name) = target {
+            if !code.lines().any(|line| Self::method_header(line) == Some(name)) {
+                return Err(BlockchainError::ContractValidationFailed(
+                    format!("Unknown method: {}", name),
+                ));
+            }
+        }
+
+        let mut section: Option<&str> = None;
+        let selected: Vec<&str> = code
+            .lines()
+            .map(|line| {
+                if let Some(name) = Self::method_header(line) {
+                    section = Some(name);
+                    ""
+                } else if section == target {
+                    line
+                } else {
+                    ""
+                }
+            })
+            .collect();
+
+        Ok(selected.join("\n"))
+    }
+
+    /// Check whether the contract opts in to reentrant calls via the `REENTRANT` directive
+    pub fn is_reentrant(&self) -> bool {
+        Self::code_allows_reentrancy(&self.code)
+    }
+
+    fn code_allows_reentrancy(code: &str) -> bool {
+        code.lines().any(|line| line.trim().eq_ignore_ascii_case("REENTRANT"))
+    }
+
     /// Execute the smart contract
     /// 
     /// # Arguments
@@ -388,9 +629,11 @@ impl SmartContract {
             ));
         }
 
-        let mut vm = ContractVM::new(context.gas_limit);
+        let code = self.entry_point(None)?;
+        let mut vm = ContractVM::new(context.gas_limit, None);
+        vm.load_storage(&self.storage);
         
-        match vm.execute(&self.code, &context) {
+        match vm.execute(&code, &context) {
             Ok(result) => {
                 // Apply storage changes
                 for (key, value) in &result.storage_changes {
@@ -405,27 +648,69 @@ impl SmartContract {
         }
     }
 
+    /// Execute the smart contract with access to other deployed contracts
+    ///
+    /// `CALL` instructions resolve their target in `contracts` and apply the
+    /// callee's storage changes there.
+    ///
+    /// # Arguments
+    /// * `context` - Execution context
+    /// * `contracts` - Deployed contracts reachable through `CALL`
+    ///
+    /// # Returns
+    /// * `Result<ContractResult>` - Execution result or error
+    pub fn execute_with_contracts(
+        &mut self,
+        context: ContractContext,
+        contracts: &mut HashMap<String, SmartContract>,
+    ) -> Result<ContractResult> {
+        if !self.active {
+            return Err(BlockchainError::ContractValidationFailed(
+                "Contract is not active".to_string(),
+            ));
+        }
+
+        let code = self.entry_point(None)?;
+        let mut vm = ContractVM::new(context.gas_limit, None);
+        vm.load_storage(&self.storage);
+
+        match vm.execute_with_contracts(&code, &context, contracts) {
+            Ok(result) => {
+                for (key, value) in &result.storage_changes {
+                    self.storage.insert(key.clone(), value.clone());
+                }
+                Ok(result)
+            }
+            Err(e) => {
+                error!("Contract execution failed: {}", e);
+                Err(e)
+            }
+        }
+    }
+
     /// Add funds to contract balance
-    pub fn add_funds(&mut self, amount: f64) -> Result<()> {
-        if amount <= 0.0 {
+    pub fn add_funds(&mut self, amount: u128) -> Result<()> {
+        if amount == 0 {
             return Err(BlockchainError::ContractValidationFailed(
                 "Amount must be positive".to_string(),
             ));
         }
-        self.balance += amount;
+        self.balance = self.balance.checked_add(amount).ok_or_else(|| {
+            BlockchainError::ContractValidationFailed("Contract balance overflow".to_string())
+        })?;
         debug!("Added {} to contract {} balance", amount, self.id);
         Ok(())
     }
 
     /// Withdraw funds from contract balance
-    pub fn withdraw_funds(&mut self, amount: f64, caller: &str) -> Result<()> {
+    pub fn withdraw_funds(&mut self, amount: u128, caller: &str) -> Result<()> {
         if caller != self.owner {
             return Err(BlockchainError::ContractValidationFailed(
                 "Only owner can withdraw funds".to_string(),
             ));
         }
 
-        if amount <= 0.0 {
+        if amount == 0 {
             return Err(BlockchainError::ContractValidationFailed(
                 "Amount must be positive".to_string(),
             ));
@@ -455,14 +740,116 @@ impl SmartContract {
     }
 }
 
+impl GasSchedule {
+    /// Get the category and cost of an instruction
+    pub fn cost_of(&self, instruction: &str) -> (GasCategory, u64) {
+        match instruction {
+            "PUSH" => (GasCategory::Stack, self.push),
+            "POP" => (GasCategory::Stack, self.pop),
+            "STORE" => (GasCategory::Storage, self.store),
+            "LOAD" => (GasCategory::Storage, self.load),
+            "ADD" | "SUB" => (GasCategory::Arithmetic, self.add),
+            "MUL" | "DIV" => (GasCategory::Arithmetic, self.mul),
+            "EQ" | "GT" | "LT" | "GTE" | "LTE" => (GasCategory::Comparison, self.compare),
+            "IF" | "ENDIF" | "LOOP" | "ENDLOOP" | "JUMP" | "JUMPIF" => (GasCategory::ControlFlow, self.branch),
+            "RETURN" => (GasCategory::ControlFlow, self.ret),
+            "CALL" => (GasCategory::Call, self.call),
+            "EMIT" => (GasCategory::Storage, self.emit),
+            _ => (GasCategory::Stack, self.base),
+        }
+    }
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        Self {
+            push: 3,
+            pop: 2,
+            store: 20,
+            load: 5,
+            add: 3,
+            mul: 5,
+            compare: 3,
+            branch: 8,
+            call: 40,
+            ret: 1,
+            emit: 10,
+            base: 1,
+        }
+    }
+}
+
 impl ContractVM {
     /// Create a new contract virtual machine
-    pub fn new(gas_limit: u64) -> Self {
+    ///
+    /// # Arguments
+    /// * `gas_limit` - Maximum gas the execution may consume
+    /// * `gas_schedule` - Instruction costs, or `None` for the default schedule
+    pub fn new(gas_limit: u64, gas_schedule: Option<GasSchedule>) -> Self {
         ContractVM {
             stack: Vec::new(),
             variables: HashMap::new(),
             gas_used: 0,
             gas_limit,
+            gas_schedule: gas_schedule.unwrap_or_default(),
+            gas_by_category: HashMap::new(),
+            events: Vec::new(),
+            storage_bytes: 0,
+            instructions_executed: 0,
+            line_profiles: HashMap::new(),
+            storage_journal: Vec::new(),
+        }
+    }
+
+    /// Make a contract's persisted storage readable through `LOAD`
+    ///
+    /// # Arguments
+    /// * `storage` - The contract's current storage
+    pub fn load_storage(&mut self, storage: &HashMap<String, String>) {
+        self.variables.extend(storage.iter().map(|(key, value)| (key.clone(), value.clone())));
+        self.storage_bytes = self.variables.iter().map(|(key, value)| key.len() + value.len()).sum();
+    }
+
+    /// Push call arguments onto the stack, first argument deepest
+    ///
+    /// # Arguments
+    /// * `args` - Arguments passed to the called method
+    pub fn push_arguments(&mut self, args: &[String]) {
+        self.stack.extend(args.iter().cloned());
+    }
+
+    /// Charge gas for an instruction, failing if the gas limit would be exceeded
+    ///
+    /// # Returns
+    /// * `Result<u64>` - The gas charged
+    fn charge_gas(&mut self, instruction: &str, line_num: usize) -> Result<u64> {
+        let (category, cost) = self.gas_schedule.cost_of(instruction);
+        let total = self.gas_used.saturating_add(cost);
+        if total > self.gas_limit {
+            let error = BlockchainError::ContractExecutionError(
+                format!("Out of gas: gas limit {} exceeded at line {} ({} used, {} required)",
+                        self.gas_limit, line_num + 1, self.gas_used, cost),
+            );
+            // Running out of gas consumes all of it
+            self.gas_used = self.gas_limit;
+            return Err(error);
+        }
+        self.gas_used = total;
+        *self.gas_by_category.entry(category).or_insert(0) += cost;
+        Ok(cost)
+    }
+
+    /// Add gas used by a line to the execution's profile
+    fn record_line_gas(&mut self, line: LineProfile) {
+        let key = (line.contract_address.clone(), line.line);
+        match self.line_profiles.get_mut(&key) {
+            Some(profile) => {
+                profile.executions += line.executions;
+                profile.gas_used += line.gas_used;
+            }
+            None => {
+                self.line_profiles.insert(key, line);
+            }
         }
     }
 
@@ -474,224 +861,497 @@ impl ContractVM {
     /// 
     /// # Returns
     /// * `Result<ContractResult>` - Execution result or error
-    pub fn execute(&mut self, code: &str, _context: &ContractContext) -> Result<ContractResult> {
+    pub fn execute(&mut self, code: &str, context: &ContractContext) -> Result<ContractResult> {
+        self.run(code, context, None)
+    }
+
+    /// Execute contract code with access to other deployed contracts
+    ///
+    /// # Arguments
+    /// * `code` - Contract code to execute
+    /// * `context` - Execution context
+    /// * `contracts` - Deployed contracts reachable through `CALL`
+    ///
+    /// # Returns
+    /// * `Result<ContractResult>` - Execution result or error
+    pub fn execute_with_contracts(
+        &mut self,
+        code: &str,
+        context: &ContractContext,
+        contracts: &mut HashMap<String, SmartContract>,
+    ) -> Result<ContractResult> {
+        self.run(code, context, Some(contracts))
+    }
+
+    /// Invoke another contract in a new frame sharing the remaining gas
+    fn call_contract(
+        &mut self,
+        target: &str,
+        context: &ContractContext,
+        contracts: &mut HashMap<String, SmartContract>,
+        line_num: usize,
+    ) -> Result<Option<String>> {
+        if context.call_stack.len() + 1 > context.max_stack_depth {
+            return Err(BlockchainError::ContractExecutionError(
+                format!("Call depth limit {} exceeded calling {} at line {}",
+                        context.max_stack_depth, target, line_num + 1),
+            ));
+        }
+
+        let (callee_code, callee_storage, callee_reentrant) = match contracts.get(target) {
+            Some(callee) if callee.active => (callee.entry_point(None)?, callee.storage.clone(), callee.is_reentrant()),
+            Some(_) => {
+                return Err(BlockchainError::ContractExecutionError(
+                    format!("Contract {} is not active at line {}", target, line_num + 1),
+                ));
+            }
+            None => {
+                return Err(BlockchainError::ContractExecutionError(
+                    format!("Contract not found: {} at line {}", target, line_num + 1),
+                ));
+            }
+        };
+
+        let mut callee_context = context.clone();
+        callee_context.call_stack.push(context.contract_address.clone());
+        if callee_context.call_stack.iter().any(|address| address == target)
+            && !callee_reentrant
+        {
+            return Err(BlockchainError::ContractExecutionError(
+                format!("Reentrant call into contract {} blocked at line {}", target, line_num + 1),
+            ));
+        }
+        callee_context.caller = context.contract_address.clone();
+        callee_context.contract_address = target.to_string();
+        callee_context.gas_limit = self.gas_limit - self.gas_used;
+
+        let mut frame = ContractVM::new(callee_context.gas_limit, Some(self.gas_schedule.clone()));
+        frame.instructions_executed = self.instructions_executed;
+        frame.load_storage(&callee_storage);
+        let result = frame.run(&callee_code, &callee_context, Some(&mut *contracts));
+        self.instructions_executed = frame.instructions_executed;
+        self.gas_used += frame.gas_used;
+        let result = result?;
+
+        self.storage_journal.append(&mut frame.storage_journal);
+        if let Some(callee) = contracts.get_mut(target) {
+            self.storage_journal.push((target.to_string(), callee.storage.clone()));
+            for (key, value) in &result.storage_changes {
+                callee.storage.insert(key.clone(), value.clone());
+            }
+        }
+
+        for (category, gas) in result.gas_by_category {
+            *self.gas_by_category.entry(category).or_insert(0) += gas;
+        }
+        self.events.extend(result.events);
+        for line in result.profile.into_iter().flat_map(|profile| profile.hotspots) {
+            self.record_line_gas(line);
+        }
+
+        debug!("Contract {} called {} using {} gas", context.contract_address, target, result.gas_used);
+        Ok(result.return_value)
+    }
+
+    /// Execute contract code, resolving `CALL` targets in `contracts` when available
+    fn run(
+        &mut self,
+        code: &str,
+        context: &ContractContext,
+        mut contracts: Option<&mut HashMap<String, SmartContract>>,
+    ) -> Result<ContractResult> {
         let lines: Vec<&str> = code.lines().collect();
         let mut storage_changes = HashMap::new();
         let mut return_value = None;
 
-        for (line_num, line) in lines.iter().enumerate() {
-            let line = line.trim();
-            if line.is_empty() || line.starts_with('#') {
-                continue;
+        for line_num in 0..lines.len() {
+            match self.execute_line(
+                &lines, line_num, context, contracts.as_deref_mut(), &mut storage_changes, &mut return_value,
+            ) {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(e) => {
+                    if let Some(contracts) = contracts {
+                        self.revert_nested_writes(contracts);
+                    }
+                    return Err(e);
+                }
             }
+        }
 
-            // Check gas limit
-            if self.gas_used >= self.gas_limit {
-                return Err(BlockchainError::ContractValidationFailed(
-                    "Gas limit exceeded".to_string(),
-                ));
-            }
+        Ok(self.finish(context, return_value, storage_changes))
+    }
 
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.is_empty() {
-                continue;
+    /// Undo the storage writes of nested calls made during a failed execution
+    fn revert_nested_writes(&mut self, contracts: &mut HashMap<String, SmartContract>) {
+        while let Some((address, storage)) = self.storage_journal.pop() {
+            if let Some(contract) = contracts.get_mut(&address) {
+                contract.storage = storage;
             }
+        }
+    }
 
-            let instruction = parts[0].to_uppercase();
-            self.gas_used += 1; // Basic gas cost per instruction
+    /// Execute a single line of contract code
+    ///
+    /// Blank lines and comments are skipped without charging gas.
+    ///
+    /// # Arguments
+    /// * `lines` - All lines of the contract code
+    /// * `line_num` - Index of the line to execute
+    /// * `context` - Execution context
+    /// * `contracts` - Deployed contracts reachable through `CALL`, if any
+    /// * `storage_changes` - Storage writes made so far
+    /// * `return_value` - Set when the line returns a value
+    ///
+    /// # Returns
+    /// * `Result<bool>` - Whether execution halted at this line
+    pub(crate) fn execute_line(
+        &mut self,
+        lines: &[&str],
+        line_num: usize,
+        context: &ContractContext,
+        contracts: Option<&mut HashMap<String, SmartContract>>,
+        storage_changes: &mut HashMap<String, String>,
+        return_value: &mut Option<String>,
+    ) -> Result<bool> {
+        let line = lines[line_num].trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(false);
+        }
 
-            match instruction.as_str() {
-                "PUSH" => {
-                    if parts.len() < 2 {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("PUSH requires a value at line {}", line_num + 1),
-                        ));
-                    }
-                    self.stack.push(parts[1].to_string());
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.is_empty() {
+            return Ok(false);
+        }
+
+        let instruction = parts[0].to_uppercase();
+        let cost = self.charge_gas(&instruction, line_num)?;
+        if context.profile {
+            self.record_line_gas(LineProfile {
+                contract_address: context.contract_address.clone(),
+                line: line_num as u32 + 1,
+                instruction: instruction.clone(),
+                executions: 1,
+                gas_used: cost,
+            });
+        }
+
+        // Counted rather than timed so every node aborts at the same instruction
+        self.instructions_executed += 1;
+        if self.instructions_executed > context.max_instructions {
+            warn!("Contract {} exceeded {} instructions at line {}",
+                  context.contract_address, context.max_instructions, line_num + 1);
+            return Err(BlockchainError::ContractInstructionLimitExceeded(context.max_instructions));
+        }
+
+        match instruction.as_str() {
+            "PUSH" => {
+                if parts.len() < 2 {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("PUSH requires a value at line {}", line_num + 1),
+                    ));
                 }
-                "POP" => {
-                    if self.stack.is_empty() {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Stack underflow at line {}", line_num + 1),
-                        ));
-                    }
-                    self.stack.pop();
+                self.stack.push(parts[1].to_string());
+            }
+            "POP" => {
+                if self.stack.is_empty() {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Stack underflow at line {}", line_num + 1),
+                    ));
                 }
-                "STORE" => {
-                    if parts.len() < 2 {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("STORE requires a key at line {}", line_num + 1),
-                        ));
-                    }
-                    if self.stack.is_empty() {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Stack underflow at line {}", line_num + 1),
-                        ));
-                    }
-                    let value = self.stack.pop().unwrap();
-                    let key = parts[1].to_string();
-                    self.variables.insert(key.clone(), value.clone());
-                    storage_changes.insert(key, value);
+                self.stack.pop();
+            }
+            "STORE" => {
+                if parts.len() < 2 {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("STORE requires a key at line {}", line_num + 1),
+                    ));
                 }
-                "LOAD" => {
-                    if parts.len() < 2 {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("LOAD requires a key at line {}", line_num + 1),
-                        ));
-                    }
-                    let key = parts[1];
-                    if let Some(value) = self.variables.get(key) {
-                        self.stack.push(value.clone());
-                    } else {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Variable '{}' not found at line {}", key, line_num + 1),
-                        ));
-                    }
+                if self.stack.is_empty() {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Stack underflow at line {}", line_num + 1),
+                    ));
                 }
-                "ADD" => {
-                    if self.stack.len() < 2 {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Stack underflow at line {}", line_num + 1),
-                        ));
-                    }
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    
-                    if let (Ok(a_val), Ok(b_val)) = (a.parse::<f64>(), b.parse::<f64>()) {
-                        self.stack.push((a_val + b_val).to_string());
-                    } else {
-                        // String concatenation
-                        self.stack.push(format!("{}{}", a, b));
-                    }
+                let value = self.stack.pop().unwrap();
+                let key = parts[1].to_string();
+                let replaced = self.variables.get(&key).map_or(0, |old| key.len() + old.len());
+                let size = self.storage_bytes - replaced + key.len() + value.len();
+                if size > context.max_storage_size {
+                    return Err(BlockchainError::ContractStorageLimitExceeded {
+                        size,
+                        limit: context.max_storage_size,
+                    });
                 }
-                "SUB" => {
-                    if self.stack.len() < 2 {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Stack underflow at line {}", line_num + 1),
-                        ));
-                    }
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    
-                    if let (Ok(a_val), Ok(b_val)) = (a.parse::<f64>(), b.parse::<f64>()) {
-                        self.stack.push((a_val - b_val).to_string());
-                    } else {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Cannot subtract non-numeric values at line {}", line_num + 1),
-                        ));
-                    }
+                self.storage_bytes = size;
+                self.variables.insert(key.clone(), value.clone());
+                storage_changes.insert(key, value);
+            }
+            "LOAD" => {
+                if parts.len() < 2 {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("LOAD requires a key at line {}", line_num + 1),
+                    ));
                 }
-                "MUL" => {
-                    if self.stack.len() < 2 {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Stack underflow at line {}", line_num + 1),
-                        ));
-                    }
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    
-                    if let (Ok(a_val), Ok(b_val)) = (a.parse::<f64>(), b.parse::<f64>()) {
-                        self.stack.push((a_val * b_val).to_string());
-                    } else {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Cannot multiply non-numeric values at line {}", line_num + 1),
-                        ));
-                    }
+                let key = parts[1];
+                if let Some(value) = self.variables.get(key) {
+                    self.stack.push(value.clone());
+                } else {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Variable '{}' not found at line {}", key, line_num + 1),
+                    ));
                 }
-                "DIV" => {
-                    if self.stack.len() < 2 {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Stack underflow at line {}", line_num + 1),
-                        ));
-                    }
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    
-                    if let (Ok(a_val), Ok(b_val)) = (a.parse::<f64>(), b.parse::<f64>()) {
-                        if b_val == 0.0 {
-                            return Err(BlockchainError::ContractValidationFailed(
-                                format!("Division by zero at line {}", line_num + 1),
-                            ));
-                        }
-                        self.stack.push((a_val / b_val).to_string());
-                    } else {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Cannot divide non-numeric values at line {}", line_num + 1),
-                        ));
-                    }
+            }
+            "ADD" => {
+                if self.stack.len() < 2 {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Stack underflow at line {}", line_num + 1),
+                    ));
                 }
-                "EQ" => {
-                    if self.stack.len() < 2 {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Stack underflow at line {}", line_num + 1),
-                        ));
-                    }
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    self.stack.push(if a == b { "1".to_string() } else { "0".to_string() });
+                let b = self.stack.pop().unwrap();
+                let a = self.stack.pop().unwrap();
+                
+                if let (Ok(a_val), Ok(b_val)) = (a.parse::<f64>(), b.parse::<f64>()) {
+                    self.stack.push((a_val + b_val).to_string());
+                } else {
+                    // String concatenation
+                    self.stack.push(format!("{}{}", a, b));
                 }
-                "GT" => {
-                    if self.stack.len() < 2 {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Stack underflow at line {}", line_num + 1),
-                        ));
-                    }
-                    let b = self.stack.pop().unwrap();
-                    let a = self.stack.pop().unwrap();
-                    
-                    if let (Ok(a_val), Ok(b_val)) = (a.parse::<f64>(), b.parse::<f64>()) {
-                        self.stack.push(if a_val > b_val { "1".to_string() } else { "0".to_string() });
-                    } else {
-                        return Err(BlockchainError::ContractValidationFailed(
-                            format!("Cannot compare non-numeric values at line {}", line_num + 1),
-                        ));
-                    }
+            }
+            "SUB" => {
+                if self.stack.len() < 2 {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Stack underflow at line {}", line_num + 1),
+                    ));
+                }
+                let b = self.stack.pop().unwrap();
+                let a = self.stack.pop().unwrap();
+                
+                if let (Ok(a_val), Ok(b_val)) = (a.parse::<f64>(), b.parse::<f64>()) {
+                    self.stack.push((a_val - b_val).to_string());
+                } else {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Cannot subtract non-numeric values at line {}", line_num + 1),
+                    ));
+                }
+            }
+            "MUL" => {
+                if self.stack.len() < 2 {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Stack underflow at line {}", line_num + 1),
+                    ));
                 }
-                "IF" => {
-                    if self.stack.is_empty() {
+                let b = self.stack.pop().unwrap();
+                let a = self.stack.pop().unwrap();
+                
+                if let (Ok(a_val), Ok(b_val)) = (a.parse::<f64>(), b.parse::<f64>()) {
+                    self.stack.push((a_val * b_val).to_string());
+                } else {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Cannot multiply non-numeric values at line {}", line_num + 1),
+                    ));
+                }
+            }
+            "DIV" => {
+                if self.stack.len() < 2 {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Stack underflow at line {}", line_num + 1),
+                    ));
+                }
+                let b = self.stack.pop().unwrap();
+                let a = self.stack.pop().unwrap();
+                
+                if let (Ok(a_val), Ok(b_val)) = (a.parse::<f64>(), b.parse::<f64>()) {
+                    if b_val == 0.0 {
                         return Err(BlockchainError::ContractValidationFailed(
-                            format!("Stack underflow at line {}", line_num + 1),
+                            format!("Division by zero at line {}", line_num + 1),
                         ));
                     }
-                    let condition = self.stack.pop().unwrap();
-                    if condition != "1" && condition != "true" {
-                        // Skip until ENDIF
-                        let mut depth = 1;
-                        for next_line in lines.iter().skip(line_num + 1) {
-                            let next_line = next_line.trim();
-                            if next_line == "IF" {
-                                depth += 1;
-                            } else if next_line == "ENDIF" {
-                                depth -= 1;
-                                if depth == 0 {
-                                    break;
-                                }
+                    self.stack.push((a_val / b_val).to_string());
+                } else {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Cannot divide non-numeric values at line {}", line_num + 1),
+                    ));
+                }
+            }
+            "EQ" => {
+                if self.stack.len() < 2 {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Stack underflow at line {}", line_num + 1),
+                    ));
+                }
+                let b = self.stack.pop().unwrap();
+                let a = self.stack.pop().unwrap();
+                self.stack.push(if a == b { "1".to_string() } else { "0".to_string() });
+            }
+            "GT" => {
+                if self.stack.len() < 2 {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Stack underflow at line {}", line_num + 1),
+                    ));
+                }
+                let b = self.stack.pop().unwrap();
+                let a = self.stack.pop().unwrap();
+                
+                if let (Ok(a_val), Ok(b_val)) = (a.parse::<f64>(), b.parse::<f64>()) {
+                    self.stack.push(if a_val > b_val { "1".to_string() } else { "0".to_string() });
+                } else {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Cannot compare non-numeric values at line {}", line_num + 1),
+                    ));
+                }
+            }
+            "IF" => {
+                if self.stack.is_empty() {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Stack underflow at line {}", line_num + 1),
+                    ));
+                }
+                let condition = self.stack.pop().unwrap();
+                if condition != "1" && condition != "true" {
+                    // Skip until ENDIF
+                    let mut depth = 1;
+                    for next_line in lines.iter().skip(line_num + 1) {
+                        let next_line = next_line.trim();
+                        if next_line == "IF" {
+                            depth += 1;
+                        } else if next_line == "ENDIF" {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
                             }
                         }
                     }
                 }
-                "ENDIF" => {
-                    // End of IF block - do nothing
+            }
+            "ENDIF" => {
+                // End of IF block - do nothing
+            }
+            "EMIT" => {
+                if parts.len() < 2 {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("EMIT requires an event name at line {}", line_num + 1),
+                    ));
                 }
-                "RETURN" => {
-                    if !self.stack.is_empty() {
-                        return_value = Some(self.stack.pop().unwrap());
-                    }
-                    break;
+                if self.stack.is_empty() {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Stack underflow at line {}", line_num + 1),
+                    ));
                 }
-                _ => {
-                    warn!("Unknown instruction: {} at line {}", instruction, line_num + 1);
+                let payload = self.stack.pop().unwrap();
+                self.events.push(ContractEvent {
+                    block_index: context.block_height,
+                    contract_id: context.contract_address.clone(),
+                    event_name: parts[1].to_string(),
+                    payload,
+                });
+            }
+            "REENTRANT" => {
+                // Directive allowing reentrant calls - checked by the caller
+            }
+            "RETURN" => {
+                if !self.stack.is_empty() {
+                    *return_value = Some(self.stack.pop().unwrap());
                 }
+                return Ok(true);
+            }
+            "CALL" => {
+                if self.stack.is_empty() {
+                    return Err(BlockchainError::ContractValidationFailed(
+                        format!("Stack underflow at line {}", line_num + 1),
+                    ));
+                }
+                let target = self.stack.pop().unwrap();
+                let target = target.trim_matches('"');
+                let registry = contracts.ok_or_else(|| {
+                    BlockchainError::ContractExecutionError(
+                        format!("CALL is not available in this execution at line {}", line_num + 1),
+                    )
+                })?;
+                let value = self.call_contract(target, context, registry, line_num)?;
+                self.stack.push(value.unwrap_or_else(|| "0".to_string()));
+            }
+            _ => {
+                warn!("Unknown instruction: {} at line {}", instruction, line_num + 1);
             }
         }
 
-        Ok(ContractResult {
+        Ok(false)
+    }
+
+    /// Build the execution result from the VM's current state
+    pub(crate) fn finish(
+        &self,
+        context: &ContractContext,
+        return_value: Option<String>,
+        storage_changes: HashMap<String, String>,
+    ) -> ContractResult {
+        let profile = context.profile.then(|| {
+            let mut hotspots: Vec<LineProfile> = self.line_profiles.values().cloned().collect();
+            hotspots.sort_by(|a, b| {
+                b.gas_used.cmp(&a.gas_used)
+                    .then_with(|| a.contract_address.cmp(&b.contract_address))
+                    .then_with(|| a.line.cmp(&b.line))
+            });
+
+            let mut by_instruction = HashMap::new();
+            for line in &hotspots {
+                *by_instruction.entry(line.instruction.clone()).or_insert(0) += line.gas_used;
+            }
+            GasProfile { by_instruction, hotspots }
+        });
+
+        ContractResult {
             success: true,
             return_value,
             gas_used: self.gas_used,
             error: None,
             storage_changes,
-        })
+            gas_by_category: self.gas_by_category.clone(),
+            events: self.events.clone(),
+            profile,
+        }
+    }
+
+    /// Get the current execution stack, bottom first
+    pub fn stack(&self) -> &[String] {
+        &self.stack
+    }
+
+    /// Get the variables loaded or stored so far
+    pub fn variables(&self) -> &HashMap<String, String> {
+        &self.variables
+    }
+
+    /// Get the gas consumed so far
+    pub fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+}
+
+impl ContractEventLog {
+    /// Create an empty event log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append events from an execution
+    pub fn record(&mut self, events: &[ContractEvent]) {
+        self.events.extend_from_slice(events);
+    }
+
+    /// Drop events emitted in blocks after `block_index`
+    pub fn truncate_after(&mut self, block_index: u64) {
+        self.events.retain(|event| event.block_index <= block_index);
+    }
+
+    /// Query events, optionally filtered by contract and event name, from a starting block
+    pub fn query(&self, contract_id: Option<&str>, event_name: Option<&str>, from_block: u64) -> Vec<&ContractEvent> {
+        self.events
+            .iter()
+            .filter(|event| event.block_index >= from_block)
+            .filter(|event| contract_id.is_none_or(|id| event.contract_id == id))
+            .filter(|event| event_name.is_none_or(|name| event.event_name == name))
+            .collect()
     }
 }
 
@@ -712,6 +1372,9 @@ impl ContractContext {
             max_stack_depth: 1000,
             max_storage_size: 10000,
             execution_timeout: 5000, // 5 seconds
+            max_instructions: 100_000,
+            call_stack: Vec::new(),
+            profile: false,
         }
     }
 
@@ -846,7 +1509,7 @@ mod tests {
         ).unwrap();
 
         assert_eq!(contract.owner, "alice123");
-        assert_eq!(contract.balance, 0.0);
+        assert_eq!(contract.balance, 0);
         assert!(contract.active);
     }
 
@@ -866,6 +1529,50 @@ mod tests {
         assert_eq!(contract.storage.get("total"), Some(&"150".to_string()));
     }
 
+    #[test]
+    fn test_storage_size_limit() {
+        let mut contract = SmartContract::new(
+            "PUSH 1234\nSTORE a\nPUSH 5678\nSTORE b\nRETURN".to_string(),
+            "alice123".to_string()
+        ).unwrap();
+        let mut context = ContractContext::new(1, 1000, "alice123".to_string(), "contract1".to_string());
+        context.max_storage_size = 10;
+
+        // Two 5-byte entries fill the storage exactly
+        assert!(contract.execute(context.clone()).unwrap().success);
+        assert_eq!(contract.storage.get("b"), Some(&"5678".to_string()));
+
+        // Overwriting in place keeps the size within the limit
+        contract.code = "PUSH 4321\nSTORE a\nRETURN".to_string();
+        assert!(contract.execute(context.clone()).unwrap().success);
+        assert_eq!(contract.storage.get("a"), Some(&"4321".to_string()));
+
+        // Shrinking one entry does not make room for a larger new one
+        contract.code = "PUSH 1\nSTORE a\nPUSH 123456\nSTORE c\nRETURN".to_string();
+        assert!(matches!(
+            contract.execute(context),
+            Err(BlockchainError::ContractStorageLimitExceeded { size: 14, limit: 10 })
+        ));
+        assert_eq!(contract.storage.get("a"), Some(&"4321".to_string()));
+        assert!(!contract.storage.contains_key("c"));
+    }
+
+    #[test]
+    fn test_instruction_limit() {
+        // Cheap in gas per instruction but long
+        let code = "NOP\n".repeat(10_000) + "PUSH 1\nRETURN";
+        let mut contract = SmartContract::new(code, "alice123".to_string()).unwrap();
+
+        let mut context = ContractContext::new(1, 1_000_000, "alice123".to_string(), "contract1".to_string());
+        assert!(contract.execute(context.clone()).unwrap().success);
+
+        context.max_instructions = 5_000;
+        assert!(matches!(
+            contract.execute(context),
+            Err(BlockchainError::ContractInstructionLimitExceeded(5_000))
+        ));
+    }
+
     #[test]
     fn test_simple_contract_execution() {
         let mut contract = SmartContract::new(
@@ -1021,4 +1728,226 @@ mod tests {
         let result = SmartContract::new(invalid_code.to_string(), "alice123".to_string());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_gas_schedule_changes_gas_totals() {
+        let code = "PUSH 2\nPUSH 3\nADD\nSTORE total\nLOAD total\nRETURN";
+        let context = ContractContext::new(1, 1000, "alice123".to_string(), "contract1".to_string());
+
+        let mut default_vm = ContractVM::new(1000, None);
+        let default_result = default_vm.execute(code, &context).unwrap();
+        // 2 PUSH (3) + ADD (3) + STORE (20) + LOAD (5) + RETURN (1)
+        assert_eq!(default_result.gas_used, 35);
+
+        let expensive_storage = GasSchedule {
+            store: 100,
+            load: 50,
+            ..GasSchedule::default()
+        };
+        let mut custom_vm = ContractVM::new(1000, Some(expensive_storage));
+        let custom_result = custom_vm.execute(code, &context).unwrap();
+        assert_eq!(custom_result.gas_used, 160);
+        assert_eq!(custom_result.return_value, default_result.return_value);
+
+        assert_eq!(default_result.gas_by_category.get(&GasCategory::Storage), Some(&25));
+        assert_eq!(custom_result.gas_by_category.get(&GasCategory::Storage), Some(&150));
+        assert_eq!(custom_result.gas_by_category.get(&GasCategory::Stack), Some(&6));
+        assert_eq!(custom_result.gas_by_category.get(&GasCategory::Arithmetic), Some(&3));
+        assert_eq!(custom_result.gas_by_category.get(&GasCategory::ControlFlow), Some(&1));
+    }
+
+    #[test]
+    fn test_out_of_gas() {
+        let code = "PUSH 100\nSTORE balance\nLOAD balance\nRETURN";
+        let context = ContractContext::new(1, 10, "alice123".to_string(), "contract1".to_string());
+
+        // PUSH (3) fits, STORE (20) would exceed the limit of 10
+        let mut vm = ContractVM::new(10, None);
+        let result = vm.execute(code, &context);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Out of gas"));
+        assert_eq!(vm.gas_used(), 10);
+
+        // Exactly enough gas succeeds
+        let mut vm = ContractVM::new(29, None);
+        assert_eq!(vm.execute(code, &context).unwrap().gas_used, 29);
+    }
+
+    #[test]
+    fn test_gas_profile_accounts_for_all_gas() {
+        let callee = SmartContract::new(
+            "PUSH 42\nSTORE answer\nLOAD answer\nRETURN".to_string(),
+            "bob123".to_string()
+        ).unwrap();
+        let callee_id = callee.id.clone();
+        let mut contracts = HashMap::new();
+        contracts.insert(callee_id.clone(), callee);
+
+        let code = format!("PUSH \"{}\"\nCALL\nPUSH 1\nADD\nSTORE result\nLOAD result\nRETURN", callee_id);
+        let mut context = ContractContext::new(1, 1000, "alice123".to_string(), "caller".to_string());
+
+        let mut vm = ContractVM::new(1000, None);
+        let plain = vm.execute_with_contracts(&code, &context, &mut contracts.clone()).unwrap();
+        assert!(plain.profile.is_none());
+
+        context.profile = true;
+        let mut vm = ContractVM::new(1000, None);
+        let profiled = vm.execute_with_contracts(&code, &context, &mut contracts).unwrap();
+        assert_eq!(profiled.gas_used, plain.gas_used);
+        assert_eq!(profiled.return_value, plain.return_value);
+
+        let profile = profiled.profile.unwrap();
+        assert_eq!(profile.by_instruction.values().sum::<u64>(), profiled.gas_used);
+        assert_eq!(profile.hotspots.iter().map(|line| line.gas_used).sum::<u64>(), profiled.gas_used);
+        assert_eq!(profile.by_instruction.get("STORE"), Some(&40));
+        assert_eq!(profile.by_instruction.get("PUSH"), Some(&9));
+
+        // The CALL instruction is the most expensive line; the callee's STORE follows
+        assert_eq!(profile.hotspots[0].instruction, "CALL");
+        assert_eq!(profile.hotspots[0].line, 2);
+        assert!(profile.hotspots.iter().any(|line| line.contract_address == callee_id && line.instruction == "STORE"));
+    }
+
+    #[test]
+    fn test_contract_calls_contract() {
+        let callee = SmartContract::new(
+            "PUSH 42\nSTORE answer\nLOAD answer\nRETURN".to_string(),
+            "bob123".to_string()
+        ).unwrap();
+        let callee_id = callee.id.clone();
+        let mut contracts = HashMap::new();
+        contracts.insert(callee_id.clone(), callee);
+
+        let mut caller = SmartContract::new(
+            format!("PUSH \"{}\"\nCALL\nPUSH 1\nADD\nSTORE result\nLOAD result\nRETURN", callee_id),
+            "alice123".to_string()
+        ).unwrap();
+
+        let context = ContractContext::new(1, 1000, "alice123".to_string(), caller.id.clone());
+        let result = caller.execute_with_contracts(context, &mut contracts).unwrap();
+
+        assert_eq!(result.return_value, Some("43".to_string()));
+        assert_eq!(caller.storage.get("result"), Some(&"43".to_string()));
+        assert_eq!(contracts[&callee_id].storage.get("answer"), Some(&"42".to_string()));
+        assert!(result.gas_by_category.contains_key(&GasCategory::Call));
+    }
+
+    #[test]
+    fn test_failed_caller_reverts_callee_writes() {
+        let callee = SmartContract::new(
+            "PUSH 42\nSTORE answer\nLOAD answer\nRETURN".to_string(),
+            "bob123".to_string()
+        ).unwrap();
+        let callee_id = callee.id.clone();
+        let mut contracts = HashMap::new();
+        contracts.insert(callee_id.clone(), callee);
+
+        // The caller underflows the stack after the call has returned
+        let code = format!("PUSH \"{}\"\nCALL\nPOP\nPOP\nRETURN", callee_id);
+        let context = ContractContext::new(1, 1000, "alice123".to_string(), "caller".to_string());
+        let mut vm = ContractVM::new(context.gas_limit, None);
+
+        assert!(vm.execute_with_contracts(&code, &context, &mut contracts).is_err());
+        assert!(contracts[&callee_id].storage.is_empty());
+    }
+
+    #[test]
+    fn test_call_requires_contract_registry() {
+        let mut contract = SmartContract::new(
+            "PUSH \"other\"\nCALL\nRETURN".to_string(),
+            "alice123".to_string()
+        ).unwrap();
+
+        let context = ContractContext::new(1, 1000, "alice123".to_string(), "contract1".to_string());
+        assert!(contract.execute(context).is_err());
+    }
+
+    #[test]
+    fn test_recursive_call_hits_depth_limit() {
+        let recursive = SmartContract::new(
+            "REENTRANT\nPUSH \"looper\"\nCALL\nRETURN".to_string(),
+            "alice123".to_string()
+        ).unwrap();
+        let code = recursive.code.clone();
+        let mut contracts = HashMap::new();
+        contracts.insert("looper".to_string(), recursive);
+
+        let mut context = ContractContext::new(1, 100_000, "alice123".to_string(), "looper".to_string());
+        context.max_stack_depth = 5;
+
+        let mut vm = ContractVM::new(context.gas_limit, None);
+        let result = vm.execute_with_contracts(&code, &context, &mut contracts);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Call depth limit 5 exceeded"));
+    }
+
+    #[test]
+    fn test_reentrant_withdraw_blocked_by_default() {
+        // The bank pays out by calling the recipient before zeroing the balance,
+        // and the attacker calls back into the bank to withdraw again.
+        let bank = SmartContract::new(
+            "PUSH 100\nSTORE balance\nPUSH \"attacker\"\nCALL\nPOP\nPUSH 0\nSTORE balance\nRETURN".to_string(),
+            "bank_owner".to_string()
+        ).unwrap();
+        let attacker = SmartContract::new(
+            "PUSH \"bank\"\nCALL\nRETURN".to_string(),
+            "mallory123".to_string()
+        ).unwrap();
+        let bank_code = bank.code.clone();
+
+        let mut contracts = HashMap::new();
+        contracts.insert("bank".to_string(), bank);
+        contracts.insert("attacker".to_string(), attacker);
+
+        let context = ContractContext::new(1, 100_000, "mallory123".to_string(), "bank".to_string());
+        let mut vm = ContractVM::new(context.gas_limit, None);
+        let result = vm.execute_with_contracts(&bank_code, &context, &mut contracts);
+
+        assert!(result.is_err());
+        let error = result.unwrap_err();
+        assert!(matches!(error, BlockchainError::ContractExecutionError(_)));
+        assert!(error.to_string().contains("Reentrant call into contract bank blocked"));
+    }
+
+    #[test]
+    fn test_emit_event() {
+        let mut contract = SmartContract::new(
+            "PUSH 5\nEMIT CounterIncremented\nRETURN".to_string(),
+            "alice123".to_string()
+        ).unwrap();
+
+        let context = ContractContext::new(3, 1000, "alice123".to_string(), "counter".to_string());
+        let result = contract.execute(context).unwrap();
+
+        assert_eq!(result.events, vec![ContractEvent {
+            block_index: 3,
+            contract_id: "counter".to_string(),
+            event_name: "CounterIncremented".to_string(),
+            payload: "5".to_string(),
+        }]);
+    }
+
+    #[test]
+    fn test_method_sections() {
+        let mut contract = SmartContract::new(
+            "PUSH 0\nSTORE count\nRETURN\nMETHOD increment\nLOAD count\nPUSH 1\nADD\nSTORE count\nRETURN".to_string(),
+            "alice123".to_string()
+        ).unwrap();
+        assert_eq!(contract.methods(), vec!["increment".to_string()]);
+
+        // Deployment runs only the constructor section
+        let context = ContractContext::new(1, 1000, "alice123".to_string(), "counter".to_string());
+        contract.execute(context.clone()).unwrap();
+        assert_eq!(contract.storage.get("count"), Some(&"0".to_string()));
+
+        // Methods read persisted storage through LOAD
+        let code = contract.entry_point(Some("increment")).unwrap();
+        assert_eq!(code.lines().count(), contract.code.lines().count());
+        let mut vm = ContractVM::new(1000, None);
+        vm.load_storage(&contract.storage);
+        let result = vm.execute(&code, &context).unwrap();
+        assert_eq!(result.storage_changes.get("count"), Some(&"1".to_string()));
+
+        assert!(contract.entry_point(Some("missing")).is_err());
+    }
 }
@@ -1,10 +1,13 @@
-use crate::{Blockchain, Block, Transaction, BlockchainError};
+use crate::{Blockchain, Block, Transaction, TransactionReceipt, BlockchainError, Htlc, HtlcAction, SmartContract, Consensus};
+use crate::transaction::TransactionType;
+use crate::blockchain::StateSnapshot;
 use sled::{Db, Tree};
 use serde::{Serialize, Deserialize};
 use log::{info, error, debug, warn};
 use std::path::Path;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 use sha2::{Sha256, Digest};
 use thiserror::Error;
@@ -43,6 +46,18 @@ pub enum StorageError {
     Io(#[from] std::io::Error),
 }
 
+/// Format marker of a stored payload written as plain JSON
+const FORMAT_RAW: u8 = 0x00;
+
+/// Format marker of a stored payload compressed with zstd
+const FORMAT_ZSTD: u8 = 0x01;
+
+/// zstd compression level used for block payloads
+const ZSTD_LEVEL: i32 = 3;
+
+/// Names of the sled trees used by `BlockchainStorage`
+const STORAGE_TREES: [&str; 13] = ["blocks", "chain", "transactions", "balances", "metadata", "wallets", "backups", "integrity", "receipts", "mempool", "snapshots", "htlcs", "contracts"];
+
 impl From<StorageError> for BlockchainError {
     fn from(err: StorageError) -> Self {
         BlockchainError::StorageError(err.to_string())
@@ -128,25 +143,50 @@ pub struct PerformanceMetrics {
     pub cache_hit_rate: f64,
 }
 
+/// Configuration of `BlockchainStorage`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageConfig {
+    /// Compress block payloads with zstd before writing them
+    pub compression: bool,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self { compression: true }
+    }
+}
+
 /// Persistent storage for the blockchain using sled
 #[derive(Debug)]
 pub struct BlockchainStorage {
     db: Arc<Db>,
     blocks_tree: Tree,
+    chain_tree: Tree,
     transactions_tree: Tree,
     balances_tree: Tree,
     metadata_tree: Tree,
     wallets_tree: Tree,
     backups_tree: Tree,
     integrity_tree: Tree,
+    receipts_tree: Tree,
+    /// Pending transactions keyed by their big-endian position in the mempool
+    mempool_tree: Tree,
+    /// State snapshots pruned from memory, keyed by their big-endian block index
+    snapshots_tree: Tree,
+    /// Hash time-locked contracts keyed by their ID
+    htlcs_tree: Tree,
+    /// Smart contracts keyed by their address
+    contracts_tree: Tree,
     backup_path: String,
     db_path: String,
     #[allow(dead_code)]
     last_integrity_check: Option<chrono::DateTime<chrono::Utc>>,
+    bytes_written: AtomicU64,
+    config: StorageConfig,
 }
 
 impl BlockchainStorage {
-    /// Create a new storage instance
+    /// Create a new storage instance with the default configuration
     /// 
     /// # Arguments
     /// * `path` - Path to the database directory
@@ -154,6 +194,18 @@ impl BlockchainStorage {
     /// # Returns
     /// * `Result<BlockchainStorage>` - The storage instance or an error
     pub fn new<P: AsRef<Path>>(path: P) -> std::result::Result<Self, StorageError> {
+        Self::with_config(path, StorageConfig::default())
+    }
+
+    /// Create a new storage instance
+    /// 
+    /// # Arguments
+    /// * `path` - Path to the database directory
+    /// * `config` - Storage configuration
+    /// 
+    /// # Returns
+    /// * `Result<BlockchainStorage>` - The storage instance or an error
+    pub fn with_config<P: AsRef<Path>>(path: P, config: StorageConfig) -> std::result::Result<Self, StorageError> {
         let path_str = path.as_ref().to_string_lossy().to_string();
         
         // Try to open the database with retry logic
@@ -177,27 +229,41 @@ impl BlockchainStorage {
         });
         
         let blocks_tree = db.open_tree("blocks")?;
+        let chain_tree = db.open_tree("chain")?;
         let transactions_tree = db.open_tree("transactions")?;
         let balances_tree = db.open_tree("balances")?;
         let metadata_tree = db.open_tree("metadata")?;
         let wallets_tree = db.open_tree("wallets")?;
         let backups_tree = db.open_tree("backups")?;
         let integrity_tree = db.open_tree("integrity")?;
+        let receipts_tree = db.open_tree("receipts")?;
+        let mempool_tree = db.open_tree("mempool")?;
+        let snapshots_tree = db.open_tree("snapshots")?;
+        let htlcs_tree = db.open_tree("htlcs")?;
+        let contracts_tree = db.open_tree("contracts")?;
         
         info!("Initialized blockchain storage with enhanced features");
         
         Ok(BlockchainStorage {
             db,
             blocks_tree,
+            chain_tree,
             transactions_tree,
             balances_tree,
             metadata_tree,
             wallets_tree,
             backups_tree,
             integrity_tree,
+            receipts_tree,
+            mempool_tree,
+            snapshots_tree,
+            htlcs_tree,
+            contracts_tree,
             backup_path: format!("{}/backups", path_str),
             db_path: path_str,
             last_integrity_check: None,
+            bytes_written: AtomicU64::new(0),
+            config,
         })
     }
     
@@ -231,21 +297,81 @@ impl BlockchainStorage {
     
     /// Save a block to storage
     /// 
+    /// Blocks are stored individually keyed by index, so saving a block only
+    /// writes that block and the updated metadata.
+    /// 
     /// # Arguments
     /// * `block` - The block to save
     /// 
     /// # Returns
     /// * `Result<()>` - Ok if saved successfully
     pub fn save_block(&self, block: &Block) -> std::result::Result<(), StorageError> {
-        let key = block.index.to_string();
-        let value = serde_json::to_vec(block)?;
-        
-        self.blocks_tree.insert(key, value)?;
+        self.write_block(block)?;
+        self.update_metadata_for_block(block)?;
         self.flush()?;
         
         debug!("Saved block #{} to storage", block.index);
         Ok(())
     }
+
+    /// Append a newly added block together with the balances, receipts and HTLCs it changed
+    /// 
+    /// This is the incremental counterpart of `save_blockchain`: only the block,
+    /// every balance it changed (as recorded in `Blockchain::balance_changes`), the
+    /// receipts of its transactions, the HTLCs its transactions lock or settle, the
+    /// consensus state and the metadata are written. Contract execution can write to
+    /// any contract, so the contracts are rewritten when the block touches one.
+    /// 
+    /// # Arguments
+    /// * `block` - The block that was added to the chain, which must be its latest block
+    /// * `blockchain` - The blockchain the block was just added to
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if saved successfully
+    pub fn append_block(&self, block: &Block, blockchain: &Blockchain) -> std::result::Result<(), StorageError> {
+        self.write_block(block)?;
+
+        for address in &blockchain.balance_changes {
+            match blockchain.balances.get(address) {
+                Some(balance) => self.write(&self.balances_tree, address.as_bytes(), &serde_json::to_vec(balance)?)?,
+                None => {
+                    self.balances_tree.remove(address.as_bytes())?;
+                }
+            }
+        }
+
+        let (receipts, htlcs) = (&blockchain.receipts, &blockchain.htlcs);
+        for transaction in &block.transactions {
+            if let Some(receipt) = receipts.get(&transaction.id) {
+                self.write(&self.receipts_tree, transaction.id.as_bytes(), &serde_json::to_vec(receipt)?)?;
+            }
+            let htlc_id = match transaction.htlc_action() {
+                Some(HtlcAction::Lock { .. }) => Some(&transaction.id),
+                Some(HtlcAction::Redeem { htlc_id, .. } | HtlcAction::Refund { htlc_id }) => Some(htlc_id),
+                None => None,
+            };
+            if let Some(htlc) = htlc_id.and_then(|id| htlcs.get(id)) {
+                self.write(&self.htlcs_tree, htlc.id.as_bytes(), &serde_json::to_vec(htlc)?)?;
+            }
+        }
+
+        let touches_contracts = block.transactions.iter().any(|transaction| {
+            matches!(
+                transaction.transaction_type,
+                TransactionType::ContractDeploy | TransactionType::ContractCall | TransactionType::ContractWithdraw
+            ) || blockchain.contracts.contains_key(&transaction.receiver)
+        });
+        if touches_contracts {
+            self.save_contracts(&blockchain.contracts, &blockchain.contract_metrics)?;
+        }
+        self.save_consensus(blockchain.consensus.as_ref())?;
+
+        self.update_metadata_for_block(block)?;
+        self.flush()?;
+
+        debug!("Appended block #{} to storage", block.index);
+        Ok(())
+    }
     
     /// Load a block from storage
     /// 
@@ -255,10 +381,8 @@ impl BlockchainStorage {
     /// # Returns
     /// * `Result<Option<Block>>` - The block if found, None otherwise
     pub fn load_block(&self, index: u64) -> std::result::Result<Option<Block>, StorageError> {
-        let key = index.to_string();
-        
-        if let Some(value) = self.blocks_tree.get(key)? {
-            let block: Block = serde_json::from_slice(&value)?;
+        if let Some(value) = self.chain_tree.get(index.to_be_bytes())? {
+            let block: Block = serde_json::from_slice(&decode_payload(&value)?)?;
             Ok(Some(block))
         } else {
             Ok(None)
@@ -274,8 +398,14 @@ impl BlockchainStorage {
     /// * `Result<()>` - Ok if saved successfully
     pub fn save_all_blocks(&self, blockchain: &Blockchain) -> std::result::Result<(), StorageError> {
         for block in &blockchain.blocks {
-            self.save_block(block)?;
+            self.write_block(block)?;
         }
+        // Drop blocks beyond the saved chain so a shorter chain fully replaces a longer one
+        let stale_from = (blockchain.blocks.len() as u64).to_be_bytes();
+        for key in self.chain_tree.range(stale_from..).keys() {
+            self.chain_tree.remove(key?)?;
+        }
+        self.flush()?;
         
         info!("Saved {} blocks to storage", blockchain.blocks.len());
         Ok(())
@@ -283,23 +413,86 @@ impl BlockchainStorage {
     
     /// Load all blocks from storage
     /// 
+    /// Databases written before blocks moved to their own tree are read from the
+    /// legacy `blocks` tree.
+    /// 
     /// # Returns
     /// * `Result<Vec<Block>>` - All blocks in order
     pub fn load_all_blocks(&self) -> std::result::Result<Vec<Block>, StorageError> {
         let mut blocks = Vec::new();
         
-        for result in self.blocks_tree.iter() {
+        // Keys are big-endian indices, so iteration is already in chain order
+        for result in self.chain_tree.iter() {
             let (_, value) = result?;
-            let block: Block = serde_json::from_slice(&value)?;
+            let block: Block = serde_json::from_slice(&decode_payload(&value)?)?;
             blocks.push(block);
         }
-        
-        // Sort blocks by index
-        blocks.sort_by_key(|block| block.index);
+
+        if blocks.is_empty() {
+            for result in self.blocks_tree.iter() {
+                let (key, value) = result?;
+                if String::from_utf8_lossy(&key).parse::<u64>().is_ok() {
+                    let block: Block = serde_json::from_slice(&value)?;
+                    blocks.push(block);
+                }
+            }
+            blocks.sort_by_key(|block| block.index);
+        }
         
         info!("Loaded {} blocks from storage", blocks.len());
         Ok(blocks)
     }
+
+    /// Get the number of bytes written to the database by this instance
+    /// 
+    /// # Returns
+    /// * `u64` - Total size of all keys and values written
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written.load(Ordering::Relaxed)
+    }
+
+    /// Insert a key-value pair, tracking the bytes written
+    fn write(&self, tree: &Tree, key: &[u8], value: &[u8]) -> std::result::Result<(), StorageError> {
+        tree.insert(key, value)?;
+        self.bytes_written.fetch_add((key.len() + value.len()) as u64, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Write a block under its big-endian index
+    fn write_block(&self, block: &Block) -> std::result::Result<(), StorageError> {
+        let payload = self.encode_payload(&serde_json::to_vec(block)?)?;
+        self.write(&self.chain_tree, &block.index.to_be_bytes(), &payload)
+    }
+
+    /// Prefix a payload with its format marker, compressing it if enabled
+    fn encode_payload(&self, json: &[u8]) -> std::result::Result<Vec<u8>, StorageError> {
+        let mut payload = Vec::with_capacity(json.len() + 1);
+        if self.config.compression {
+            payload.push(FORMAT_ZSTD);
+            payload.extend_from_slice(&zstd::bulk::compress(json, ZSTD_LEVEL)?);
+        } else {
+            payload.push(FORMAT_RAW);
+            payload.extend_from_slice(json);
+        }
+        Ok(payload)
+    }
+
+    /// Update the stored metadata after a block has been written
+    fn update_metadata_for_block(&self, block: &Block) -> std::result::Result<(), StorageError> {
+        let Some(mut metadata) = self.load_metadata()? else {
+            return Ok(());
+        };
+
+        let block_count = block.index as usize + 1;
+        if block_count > metadata.total_blocks {
+            metadata.total_blocks = block_count;
+            metadata.total_transactions += block.transactions.len();
+            metadata.last_block_hash = block.hash.clone();
+        }
+        metadata.last_updated = chrono::Utc::now();
+
+        self.write(&self.metadata_tree, b"metadata", &serde_json::to_vec(&metadata)?)
+    }
     
     /// Save pending transactions
     /// 
@@ -310,13 +503,12 @@ impl BlockchainStorage {
     /// * `Result<()>` - Ok if saved successfully
     pub fn save_pending_transactions(&self, transactions: &[Transaction]) -> std::result::Result<(), StorageError> {
         // Clear existing pending transactions
-        self.transactions_tree.clear()?;
+        self.mempool_tree.clear()?;
         
-        // Save new pending transactions
+        // Save new pending transactions in mempool order
         for (i, transaction) in transactions.iter().enumerate() {
-            let key = format!("pending_{}", i);
             let value = serde_json::to_vec(transaction)?;
-            self.transactions_tree.insert(key, value)?;
+            self.write(&self.mempool_tree, &(i as u64).to_be_bytes(), &value)?;
         }
         
         self.flush()?;
@@ -324,21 +516,45 @@ impl BlockchainStorage {
         Ok(())
     }
     
+    /// Save a state snapshot, replacing any saved snapshot of the same block
+    /// 
+    /// # Arguments
+    /// * `snapshot` - The snapshot to save
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if saved successfully
+    pub fn save_state_snapshot(&self, snapshot: &StateSnapshot) -> std::result::Result<(), StorageError> {
+        let value = serde_json::to_vec(snapshot)?;
+        self.write(&self.snapshots_tree, &snapshot.block_index.to_be_bytes(), &value)?;
+        self.flush()?;
+        debug!("Saved state snapshot for block {} to storage", snapshot.block_index);
+        Ok(())
+    }
+    
+    /// Load a saved state snapshot
+    /// 
+    /// # Arguments
+    /// * `block_index` - Block index of the snapshot
+    /// 
+    /// # Returns
+    /// * `Result<Option<StateSnapshot>>` - The snapshot, or None if none was saved for the block
+    pub fn load_state_snapshot(&self, block_index: u64) -> std::result::Result<Option<StateSnapshot>, StorageError> {
+        match self.snapshots_tree.get(block_index.to_be_bytes())? {
+            Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+    
     /// Load pending transactions from storage
     /// 
     /// # Returns
-    /// * `Result<Vec<Transaction>>` - Pending transactions
+    /// * `Result<Vec<Transaction>>` - Pending transactions in the order they were saved
     pub fn load_pending_transactions(&self) -> std::result::Result<Vec<Transaction>, StorageError> {
         let mut transactions = Vec::new();
         
-        for result in self.transactions_tree.iter() {
-            let (key, value) = result?;
-            let key_str = String::from_utf8_lossy(&key);
-            
-            if key_str.starts_with("pending_") {
-                let transaction: Transaction = serde_json::from_slice(&value)?;
-                transactions.push(transaction);
-            }
+        for result in self.mempool_tree.iter() {
+            let (_, value) = result?;
+            transactions.push(serde_json::from_slice(&value)?);
         }
         
         debug!("Loaded {} pending transactions from storage", transactions.len());
@@ -352,14 +568,14 @@ impl BlockchainStorage {
     /// 
     /// # Returns
     /// * `Result<()>` - Ok if saved successfully
-    pub fn save_balances(&self, balances: &HashMap<String, f64>) -> std::result::Result<(), StorageError> {
+    pub fn save_balances(&self, balances: &HashMap<String, u128>) -> std::result::Result<(), StorageError> {
         // Clear existing balances
         self.balances_tree.clear()?;
         
         // Save new balances
         for (address, balance) in balances {
             let value = serde_json::to_vec(balance)?;
-            self.balances_tree.insert(address, value)?;
+            self.write(&self.balances_tree, address.as_bytes(), &value)?;
         }
         
         self.flush()?;
@@ -369,15 +585,25 @@ impl BlockchainStorage {
     
     /// Load balances from storage
     /// 
+    /// Balances written before the switch to base units were stored as decimal
+    /// amounts and are converted on load.
+    /// 
     /// # Returns
-    /// * `Result<HashMap<String, f64>>` - Balances
-    pub fn load_balances(&self) -> std::result::Result<HashMap<String, f64>, StorageError> {
+    /// * `Result<HashMap<String, u128>>` - Balances in base units
+    pub fn load_balances(&self) -> std::result::Result<HashMap<String, u128>, StorageError> {
         let mut balances = HashMap::new();
         
         for result in self.balances_tree.iter() {
             let (key, value) = result?;
             let address = String::from_utf8_lossy(&key).to_string();
-            let balance: f64 = serde_json::from_slice(&value)?;
+            let balance = match serde_json::from_slice::<u128>(&value) {
+                Ok(balance) => balance,
+                Err(_) => {
+                    let legacy: f64 = serde_json::from_slice(&value)?;
+                    crate::units::to_base_units(legacy)
+                        .map_err(|e| StorageError::InvalidFormat(format!("Balance of {}: {}", address, e)))?
+                }
+            };
             balances.insert(address, balance);
         }
         
@@ -385,6 +611,158 @@ impl BlockchainStorage {
         Ok(balances)
     }
     
+    /// Save transaction receipts, replacing any previously stored
+    /// 
+    /// # Arguments
+    /// * `receipts` - Receipts by transaction ID
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if saved successfully
+    pub fn save_receipts(&self, receipts: &HashMap<String, TransactionReceipt>) -> std::result::Result<(), StorageError> {
+        self.receipts_tree.clear()?;
+
+        for (tx_id, receipt) in receipts {
+            self.write(&self.receipts_tree, tx_id.as_bytes(), &serde_json::to_vec(receipt)?)?;
+        }
+
+        debug!("Saved {} receipts to storage", receipts.len());
+        Ok(())
+    }
+
+    /// Load transaction receipts
+    /// 
+    /// # Returns
+    /// * `Result<HashMap<String, TransactionReceipt>>` - Receipts by transaction ID
+    pub fn load_receipts(&self) -> std::result::Result<HashMap<String, TransactionReceipt>, StorageError> {
+        let mut receipts = HashMap::new();
+
+        for result in self.receipts_tree.iter() {
+            let (key, value) = result?;
+            let receipt: TransactionReceipt = serde_json::from_slice(&value)?;
+            receipts.insert(String::from_utf8_lossy(&key).to_string(), receipt);
+        }
+
+        debug!("Loaded {} receipts from storage", receipts.len());
+        Ok(receipts)
+    }
+
+    /// Save hash time-locked contracts, replacing any previously stored
+    /// 
+    /// # Arguments
+    /// * `htlcs` - HTLCs by ID
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if saved successfully
+    pub fn save_htlcs(&self, htlcs: &HashMap<String, Htlc>) -> std::result::Result<(), StorageError> {
+        self.htlcs_tree.clear()?;
+
+        for (id, htlc) in htlcs {
+            self.write(&self.htlcs_tree, id.as_bytes(), &serde_json::to_vec(htlc)?)?;
+        }
+
+        debug!("Saved {} HTLCs to storage", htlcs.len());
+        Ok(())
+    }
+
+    /// Load hash time-locked contracts
+    /// 
+    /// # Returns
+    /// * `Result<HashMap<String, Htlc>>` - HTLCs by ID
+    pub fn load_htlcs(&self) -> std::result::Result<HashMap<String, Htlc>, StorageError> {
+        let mut htlcs = HashMap::new();
+
+        for result in self.htlcs_tree.iter() {
+            let (key, value) = result?;
+            let htlc: Htlc = serde_json::from_slice(&value)?;
+            htlcs.insert(String::from_utf8_lossy(&key).to_string(), htlc);
+        }
+
+        debug!("Loaded {} HTLCs from storage", htlcs.len());
+        Ok(htlcs)
+    }
+
+    /// Save smart contracts and their execution metrics, replacing any previously stored
+    /// 
+    /// # Arguments
+    /// * `contracts` - Contracts by address
+    /// * `metrics` - Contract execution metrics
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if saved successfully
+    pub fn save_contracts(&self, contracts: &HashMap<String, SmartContract>, metrics: &HashMap<String, u64>) -> std::result::Result<(), StorageError> {
+        self.contracts_tree.clear()?;
+
+        for (address, contract) in contracts {
+            self.write(&self.contracts_tree, address.as_bytes(), &serde_json::to_vec(contract)?)?;
+        }
+        self.write(&self.metadata_tree, b"contract_metrics", &serde_json::to_vec(metrics)?)?;
+
+        debug!("Saved {} contracts to storage", contracts.len());
+        Ok(())
+    }
+
+    /// Load smart contracts and their execution metrics
+    /// 
+    /// # Returns
+    /// * `Result<(HashMap<String, SmartContract>, HashMap<String, u64>)>` - Contracts by
+    ///   address and the execution metrics, both empty if none were stored
+    pub fn load_contracts(&self) -> std::result::Result<(HashMap<String, SmartContract>, HashMap<String, u64>), StorageError> {
+        let mut contracts = HashMap::new();
+
+        for result in self.contracts_tree.iter() {
+            let (key, value) = result?;
+            let contract: SmartContract = serde_json::from_slice(&value)?;
+            contracts.insert(String::from_utf8_lossy(&key).to_string(), contract);
+        }
+        let metrics = match self.metadata_tree.get("contract_metrics")? {
+            Some(value) => serde_json::from_slice(&value)?,
+            None => HashMap::new(),
+        };
+
+        debug!("Loaded {} contracts from storage", contracts.len());
+        Ok((contracts, metrics))
+    }
+
+    /// Save the consensus mechanism and its state, such as the PoS validators
+    /// 
+    /// # Arguments
+    /// * `consensus` - The blockchain's consensus mechanism
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if saved successfully
+    pub fn save_consensus(&self, consensus: &dyn Consensus) -> std::result::Result<(), StorageError> {
+        self.write(&self.metadata_tree, b"consensus", &serde_json::to_vec(&consensus.state())?)?;
+        debug!("Saved {} consensus state to storage", consensus.consensus_type());
+        Ok(())
+    }
+
+    /// Load the consensus mechanism and its state
+    /// 
+    /// # Returns
+    /// * `Result<Option<Box<dyn Consensus>>>` - The consensus, or None if none was stored
+    pub fn load_consensus(&self) -> std::result::Result<Option<Box<dyn Consensus>>, StorageError> {
+        match self.metadata_tree.get("consensus")? {
+            Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Check whether no blocks have been stored yet
+    /// 
+    /// # Returns
+    /// * `Result<bool>` - True if neither the block tree nor the legacy tree holds a block
+    pub fn is_empty(&self) -> std::result::Result<bool, StorageError> {
+        if !self.chain_tree.is_empty() {
+            return Ok(false);
+        }
+        for key in self.blocks_tree.iter().keys() {
+            if String::from_utf8_lossy(&key?).parse::<u64>().is_ok() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+    
     /// Save metadata
     /// 
     /// # Arguments
@@ -394,7 +772,7 @@ impl BlockchainStorage {
     /// * `Result<()>` - Ok if saved successfully
     pub fn save_metadata(&self, metadata: &BlockchainMetadata) -> std::result::Result<(), StorageError> {
         let value = serde_json::to_vec(metadata)?;
-        self.metadata_tree.insert("metadata", value)?;
+        self.write(&self.metadata_tree, b"metadata", &value)?;
         self.flush()?;
         Ok(())
     }
@@ -488,24 +866,63 @@ impl BlockchainStorage {
         
         // Load balances
         let balances = self.load_balances()?;
+
+        // Load transaction receipts
+        let receipts = self.load_receipts()?;
+
+        // Load hash time-locked contracts
+        let htlcs = self.load_htlcs()?;
+
+        // Load smart contracts
+        let (contracts, contract_metrics) = self.load_contracts()?;
+
+        // Databases written before the consensus was stored were Proof of Work
+        let consensus = match self.load_consensus()? {
+            Some(consensus) => consensus,
+            None => Box::new(crate::ProofOfWork::new(difficulty, 1_000_000).map_err(|e| StorageError::Corruption(e.to_string()))?),
+        };
+
+        // Account nonces are the number of transfers each sender has had mined
+        let mut nonces = HashMap::new();
+        for transaction in blocks.iter().flat_map(|block| &block.transactions).filter(|tx| tx.uses_nonce()) {
+            *nonces.entry(transaction.sender.clone()).or_insert(0) += 1;
+        }
         
         // Create blockchain
-        let blockchain = Blockchain {
+        let mut blockchain = Blockchain {
             blocks,
             pending_transactions,
             difficulty,
             mining_reward,
-            proof_of_work: crate::ProofOfWork::new(difficulty, 1_000_000).map_err(|e| StorageError::Corruption(e.to_string()))?,
+            max_txs_per_block: 0,
+            min_relay_fee: 0.0,
             version: crate::BLOCKCHAIN_VERSION.to_string(),
             balances,
-            consensus_type: crate::ConsensusType::ProofOfWork, // Default to PoW for backward compatibility
-            proof_of_stake: None, // Default to None for backward compatibility
-            contracts: HashMap::new(), // Default to empty for backward compatibility
-            contract_metrics: HashMap::new(), // Default to empty for backward compatibility
+            nonces,
+            consensus,
+            contracts,
+            contract_metrics,
+            event_log: crate::smart_contract::ContractEventLog::new(), // Default to empty for backward compatibility
+            receipts,
+            htlcs,
+            orphan_blocks: Vec::new(),
+            max_orphan_age_secs: crate::blockchain::DEFAULT_MAX_ORPHAN_AGE_SECS,
+            target_block_time_secs: crate::blockchain::DEFAULT_TARGET_BLOCK_TIME_SECS,
+            retarget_interval: crate::blockchain::DEFAULT_RETARGET_INTERVAL,
+            max_reorg_depth: crate::blockchain::DEFAULT_MAX_REORG_DEPTH,
             state_snapshots: Vec::new(), // Default to empty for backward compatibility
+            rollback_target: None,
             state_tree: crate::blockchain::StateMerkleTree::new(), // Default to empty for backward compatibility
             state_lock: std::sync::Arc::new(std::sync::Mutex::new(())), // Default to new lock
+            event_sender: None,
+            auto_miner_stop: None,
+            read_cache: None,
+            snapshot_pruning: None,
+            address_index: HashMap::new(),
+            balance_changes: Vec::new(),
         };
+        blockchain.rebuild_address_index();
+        blockchain.resume_difficulty().map_err(|e| StorageError::Corruption(e.to_string()))?;
         
         info!("Successfully loaded blockchain from storage");
         Ok(blockchain)
@@ -527,6 +944,16 @@ impl BlockchainStorage {
         
         // Save balances
         self.save_balances(&blockchain.balances)?;
+
+        // Save transaction receipts
+        self.save_receipts(&blockchain.receipts)?;
+
+        // Save hash time-locked contracts
+        self.save_htlcs(&blockchain.htlcs)?;
+
+        // Save smart contracts and the consensus state
+        self.save_contracts(&blockchain.contracts, &blockchain.contract_metrics)?;
+        self.save_consensus(blockchain.consensus.as_ref())?;
         
         // Update metadata
         let metadata = BlockchainMetadata {
@@ -561,6 +988,15 @@ impl BlockchainStorage {
         Ok(())
     }
     
+    /// Check that the database can be read
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if the database answered a read
+    pub fn ping(&self) -> std::result::Result<(), StorageError> {
+        self.metadata_tree.get("metadata")?;
+        Ok(())
+    }
+    
     /// Get database size in bytes
     /// 
     /// # Returns
@@ -574,8 +1010,21 @@ impl BlockchainStorage {
     /// # Returns
     /// * `Result<()>` - Ok if compacted successfully
     pub fn compact(&self) -> std::result::Result<(), StorageError> {
-        // Note: sled doesn't have a compact method, so we'll just return Ok
-        info!("Database compaction requested (not implemented in sled)");
+        // Drop trees left behind by older layouts; none of our code reads them anymore
+        let mut dropped = 0;
+        for name in self.db.tree_names() {
+            let is_default = name.as_ref() == b"__sled__default";
+            let is_known = STORAGE_TREES.iter().any(|tree| tree.as_bytes() == name.as_ref());
+            if !is_default && !is_known && self.db.drop_tree(&name)? {
+                dropped += 1;
+            }
+        }
+
+        // sled reclaims segments freed by dropped trees and overwritten values on flush
+        let size_before = self.size()?;
+        self.flush()?;
+        info!("Compacted database: dropped {} orphaned trees, {} -> {} bytes",
+              dropped, size_before, self.size()?);
         Ok(())
     }
 
@@ -654,12 +1103,16 @@ impl BlockchainStorage {
         let mut hasher = Sha256::new();
         
         // Check all blocks
-        for result in self.blocks_tree.iter() {
+        for result in self.chain_tree.iter() {
             let (key, value) = result?;
-            let block_index = String::from_utf8_lossy(&key);
+            let block_index = key.as_ref().try_into().map(u64::from_be_bytes)
+                .map(|index| index.to_string())
+                .unwrap_or_else(|_| String::from_utf8_lossy(&key).to_string());
             
-            // Try to deserialize the block
-            match serde_json::from_slice::<Block>(&value) {
+            // Try to decode and deserialize the block
+            let block = decode_payload(&value)
+                .and_then(|json| serde_json::from_slice::<Block>(&json).map_err(StorageError::from));
+            match block {
                 Ok(block) => {
                     block_count += 1;
                     // Verify block hash
@@ -770,7 +1223,7 @@ impl BlockchainStorage {
         let backup_db = sled::open(&backup_file)?;
         
         // Copy all trees
-        for tree_name in ["blocks", "transactions", "balances", "metadata", "wallets", "backups", "integrity"] {
+        for tree_name in STORAGE_TREES {
             if let Ok(source_tree) = self.db.open_tree(tree_name) {
                 let backup_tree = backup_db.open_tree(tree_name)?;
                 for result in source_tree.iter() {
@@ -840,6 +1293,7 @@ impl BlockchainStorage {
         
         // Close current database connections
         drop(self.blocks_tree.clone());
+        drop(self.chain_tree.clone());
         drop(self.transactions_tree.clone());
         drop(self.balances_tree.clone());
         drop(self.metadata_tree.clone());
@@ -994,6 +1448,20 @@ impl BlockchainStorage {
     }
 }
 
+/// Decode a stored payload into its JSON bytes
+/// 
+/// The first byte marks the format. Payloads written before format markers were
+/// introduced are bare JSON objects and are returned unchanged.
+fn decode_payload(payload: &[u8]) -> std::result::Result<Vec<u8>, StorageError> {
+    match payload.split_first() {
+        Some((&FORMAT_RAW, json)) => Ok(json.to_vec()),
+        Some((&FORMAT_ZSTD, compressed)) => Ok(zstd::stream::decode_all(compressed)?),
+        Some((b'{', _)) => Ok(payload.to_vec()),
+        Some((marker, _)) => Err(StorageError::InvalidFormat(format!("Unknown payload format marker {:#04x}", marker))),
+        None => Err(StorageError::InvalidFormat("Empty payload".to_string())),
+    }
+}
+
 impl Drop for BlockchainStorage {
     fn drop(&mut self) {
         if let Err(e) = self.flush() {
@@ -1007,6 +1475,7 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
     use crate::Blockchain;
+    use crate::units;
     
     #[test]
     fn test_storage_creation() {
@@ -1017,14 +1486,16 @@ mod tests {
     
     #[test]
     fn test_blockchain_save_load() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
         let temp_dir = tempdir().unwrap();
         let storage = BlockchainStorage::new(temp_dir.path()).unwrap();
         
         // Create a blockchain
         let mut blockchain = Blockchain::new_pow(2, 50.0).unwrap();
         // Add initial balance to alice
-        blockchain.balances.insert("alice".to_string(), 1000.0);
-        blockchain.add_transaction("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(1000));
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(100), None).unwrap();
         blockchain.mine_block("miner".to_string()).unwrap();
         
         // Save blockchain
@@ -1037,8 +1508,260 @@ mod tests {
         assert_eq!(blockchain.blocks.len(), loaded_blockchain.blocks.len());
         assert_eq!(blockchain.pending_transactions.len(), loaded_blockchain.pending_transactions.len());
         assert_eq!(blockchain.balances.len(), loaded_blockchain.balances.len());
+        let transfer_id = &blockchain.blocks[1].transactions[0].id;
+        assert!(loaded_blockchain.get_receipt(transfer_id).unwrap().is_success());
+        assert_eq!(loaded_blockchain.receipts.len(), blockchain.receipts.len());
     }
     
+    #[test]
+    fn test_incremental_block_storage() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let incremental_dir = tempdir().unwrap();
+        let full_dir = tempdir().unwrap();
+        let incremental = BlockchainStorage::new(incremental_dir.path()).unwrap();
+        let full = BlockchainStorage::new(full_dir.path()).unwrap();
+
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        // Blocks mined back to back would otherwise keep raising the difficulty
+        blockchain.retarget_interval = 0;
+        blockchain.balances.insert(alice.clone(), units::gil(1_000_000));
+        incremental.save_blockchain(&blockchain).unwrap();
+        full.save_blockchain(&blockchain).unwrap();
+        let incremental_start = incremental.bytes_written();
+        let full_start = full.bytes_written();
+
+        for _ in 0..100 {
+            blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), None).unwrap();
+            let block = blockchain.mine_block("miner".to_string()).unwrap();
+
+            incremental.append_block(&block, &blockchain).unwrap();
+            incremental.save_pending_transactions(&blockchain.pending_transactions).unwrap();
+            full.save_blockchain(&blockchain).unwrap();
+        }
+
+        let incremental_bytes = incremental.bytes_written() - incremental_start;
+        let full_bytes = full.bytes_written() - full_start;
+        assert!(
+            incremental_bytes * 10 < full_bytes,
+            "incremental wrote {} bytes, full save wrote {} bytes",
+            incremental_bytes,
+            full_bytes
+        );
+
+        let loaded = incremental.load_blockchain(1, 50.0).unwrap();
+        assert_eq!(loaded.blocks.len(), blockchain.blocks.len());
+        for (loaded_block, block) in loaded.blocks.iter().zip(&blockchain.blocks) {
+            assert_eq!(loaded_block.hash, block.hash);
+        }
+        assert_eq!(loaded.balances, blockchain.balances);
+
+        let metadata = incremental.load_metadata().unwrap().unwrap();
+        assert_eq!(metadata.total_blocks, blockchain.blocks.len());
+        assert_eq!(metadata.last_block_hash, blockchain.blocks.last().unwrap().hash);
+    }
+    
+    #[test]
+    fn test_htlcs_survive_restart() {
+        let temp_dir = tempdir().unwrap();
+        let storage = BlockchainStorage::new(temp_dir.path()).unwrap();
+
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        let keypair = crate::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&keypair.public_key());
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        storage.save_blockchain(&blockchain).unwrap();
+
+        let hashlock = Htlc::hash_preimage(b"swap secret");
+        let id = blockchain.create_htlc(&keypair, "bob".to_string(), units::gil(40), hashlock, 10).unwrap();
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        storage.append_block(&block, &blockchain).unwrap();
+
+        let mut loaded = storage.load_blockchain(1, 50.0).unwrap();
+        assert_eq!(loaded.get_htlc(&id).unwrap().amount, units::gil(40));
+        assert_eq!(loaded.get_balance(&alice), 60.0);
+
+        // The locked funds can still be redeemed after the restart
+        loaded.redeem_htlc(&id, "swap secret").unwrap();
+        loaded.mine_block("miner".to_string()).unwrap();
+        assert_eq!(loaded.get_balance("bob"), 40.0);
+    }
+
+    #[test]
+    fn test_consensus_and_contracts_survive_restart() {
+        let validator_key = crate::KeyPair::generate().unwrap();
+        let validator = crate::crypto::create_address(&validator_key.public_key());
+        let mut blockchain = Blockchain::new_pos_with_validators(10.0, units::gil(100), 10, vec![
+            (validator_key.public_key_hex(), validator.clone(), units::gil(1000)),
+        ]).unwrap();
+        blockchain.add_validator_signer(validator_key).unwrap();
+        blockchain.balances.insert("alice".to_string(), units::gil(100));
+        let incremental_dir = tempdir().unwrap();
+        let full_dir = tempdir().unwrap();
+        let incremental = BlockchainStorage::new(incremental_dir.path()).unwrap();
+        let full = BlockchainStorage::new(full_dir.path()).unwrap();
+        incremental.save_blockchain(&blockchain).unwrap();
+
+        let deploy = Transaction::new_contract_deploy(
+            "alice".to_string(), "PUSH 7\nSTORE value\nRETURN".to_string(), 10_000, 0.000001,
+        ).unwrap();
+        blockchain.add_transaction_object(deploy).unwrap();
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        incremental.append_block(&block, &blockchain).unwrap();
+        full.save_blockchain(&blockchain).unwrap();
+        assert_eq!(blockchain.contracts.len(), 1);
+
+        for storage in [&incremental, &full] {
+            let loaded = storage.load_blockchain(1, 50.0).unwrap();
+            assert_eq!(loaded.get_consensus_type(), crate::ConsensusType::ProofOfStake);
+            let pos = loaded.proof_of_stake().unwrap();
+            assert_eq!(pos.validators[&validator].stake_amount, units::gil(1000));
+            assert_eq!(pos.validators[&validator].blocks_validated, blockchain.proof_of_stake().unwrap().validators[&validator].blocks_validated);
+            for (address, contract) in &blockchain.contracts {
+                assert_eq!(loaded.contracts[address].storage, contract.storage);
+            }
+            assert_eq!(loaded.contract_metrics, blockchain.contract_metrics);
+            // The validator-signed block checks out against the restored validator set
+            loaded.consensus().validate_block(&block).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_unreadable_chain_is_not_overwritten() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let temp_dir = tempdir().unwrap();
+        let storage = std::sync::Arc::new(BlockchainStorage::new(temp_dir.path()).unwrap());
+        let mut blockchain = Blockchain::with_storage(1, 50.0, &storage).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(100));
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(10), None).unwrap();
+        let block = blockchain.mine_block("miner".to_string()).unwrap();
+        storage.append_block(&block, &blockchain).unwrap();
+
+        storage.chain_tree.insert(1u64.to_be_bytes(), vec![0x7f, 1, 2]).unwrap();
+        assert!(Blockchain::with_storage(1, 50.0, &storage).is_err());
+
+        // The stored chain is left as it was rather than replaced by a new genesis
+        assert_eq!(storage.chain_tree.len(), 2);
+        assert_eq!(storage.load_block(0).unwrap().unwrap().hash, blockchain.blocks[0].hash);
+        assert_eq!(storage.load_balances().unwrap(), blockchain.balances);
+    }
+
+    #[test]
+    fn test_pending_transactions_revalidated_on_restart() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let bob_key = crate::crypto::KeyPair::generate().unwrap();
+        let bob = crate::crypto::create_address(&bob_key.public_key());
+        let temp_dir = tempdir().unwrap();
+        let storage = std::sync::Arc::new(BlockchainStorage::new(temp_dir.path()).unwrap());
+        let mut blockchain = Blockchain::with_storage(1, 50.0, &storage).unwrap();
+        for address in [alice.as_str(), bob.as_str(), "carol"] {
+            blockchain.balances.insert(address.to_string(), units::gil(100));
+        }
+
+        for amount in 1..=11 {
+            blockchain.add_transaction(&alice_key, "dave".to_string(), units::gil(amount), None).unwrap();
+        }
+        let expected: Vec<String> = blockchain.pending_transactions.iter().map(|tx| tx.id.clone()).collect();
+        blockchain.add_transaction(&bob_key, "dave".to_string(), units::gil(60), None).unwrap();
+        let mut stale = crate::transaction::Transaction::new_transfer("carol".to_string(), "dave".to_string(), units::gil(5), None).unwrap();
+        stale.timestamp -= crate::blockchain::MEMPOOL_EXPIRY_SECS + 1;
+        blockchain.pending_transactions.push(stale);
+        storage.save_blockchain(&blockchain).unwrap();
+
+        // Bob's funds move elsewhere while the node is down
+        let mut balances = blockchain.balances.clone();
+        balances.insert(bob.clone(), units::gil(50));
+        storage.save_balances(&balances).unwrap();
+        drop(blockchain);
+        drop(storage);
+
+        let storage = std::sync::Arc::new(BlockchainStorage::new(temp_dir.path()).unwrap());
+        let restored = Blockchain::with_storage(1, 50.0, &storage).unwrap();
+        let ids: Vec<String> = restored.pending_transactions.iter().map(|tx| tx.id.clone()).collect();
+        assert_eq!(ids, expected);
+        assert_eq!(restored.next_nonce(&alice), 11);
+
+        let persisted: Vec<String> = storage.load_pending_transactions().unwrap().into_iter().map(|tx| tx.id).collect();
+        assert_eq!(persisted, expected);
+    }
+
+    #[test]
+    fn test_compressed_block_round_trip() {
+        let alice_key = crate::crypto::KeyPair::generate().unwrap();
+        let alice = crate::crypto::create_address(&alice_key.public_key());
+        let compressed_dir = tempdir().unwrap();
+        let raw_dir = tempdir().unwrap();
+        let compressed = BlockchainStorage::new(compressed_dir.path()).unwrap();
+        let raw = BlockchainStorage::with_config(raw_dir.path(), StorageConfig { compression: false }).unwrap();
+
+        let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        blockchain.balances.insert(alice.clone(), units::gil(1000));
+        for i in 0..5 {
+            blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(1), Some(format!("payment {}", i))).unwrap();
+            blockchain.add_transaction(&alice_key, "carol".to_string(), units::gil(2), Some(format!("payment {}", i))).unwrap();
+            blockchain.mine_block("miner".to_string()).unwrap();
+        }
+        compressed.save_all_blocks(&blockchain).unwrap();
+        raw.save_all_blocks(&blockchain).unwrap();
+
+        for block in &blockchain.blocks {
+            let stored = compressed.chain_tree.get(block.index.to_be_bytes()).unwrap().unwrap();
+            assert_eq!(stored[0], FORMAT_ZSTD);
+            let plain = raw.chain_tree.get(block.index.to_be_bytes()).unwrap().unwrap();
+            assert_eq!(plain[0], FORMAT_RAW);
+            assert!(stored.len() < plain.len());
+
+            let loaded = compressed.load_block(block.index).unwrap().unwrap();
+            assert_eq!(loaded.hash, block.hash);
+            assert_eq!(loaded.transactions, block.transactions);
+        }
+
+        let loaded = compressed.load_all_blocks().unwrap();
+        assert_eq!(loaded.len(), blockchain.blocks.len());
+        assert!(compressed.perform_integrity_check().unwrap().is_valid);
+    }
+
+    #[test]
+    fn test_reads_uncompressed_block_payloads() {
+        let temp_dir = tempdir().unwrap();
+        let storage = BlockchainStorage::new(temp_dir.path()).unwrap();
+        let blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+        let genesis = &blockchain.blocks[0];
+        let json = serde_json::to_vec(genesis).unwrap();
+
+        // Raw payload behind an explicit marker
+        let mut marked = vec![FORMAT_RAW];
+        marked.extend_from_slice(&json);
+        storage.chain_tree.insert(0u64.to_be_bytes(), marked).unwrap();
+        assert_eq!(storage.load_block(0).unwrap().unwrap().hash, genesis.hash);
+
+        // Payload written before format markers existed
+        storage.chain_tree.insert(0u64.to_be_bytes(), json).unwrap();
+        assert_eq!(storage.load_block(0).unwrap().unwrap().hash, genesis.hash);
+
+        storage.chain_tree.insert(0u64.to_be_bytes(), vec![0x7f, 1, 2]).unwrap();
+        assert!(matches!(storage.load_block(0), Err(StorageError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn test_compact_drops_orphaned_trees() {
+        let temp_dir = tempdir().unwrap();
+        let storage = BlockchainStorage::new(temp_dir.path()).unwrap();
+
+        let orphan = storage.db.open_tree("legacy_state").unwrap();
+        orphan.insert("key", "value").unwrap();
+        storage.save_wallet("alice", b"wallet").unwrap();
+
+        storage.compact().unwrap();
+
+        let names: Vec<_> = storage.db.tree_names();
+        assert!(!names.iter().any(|name| name.as_ref() == b"legacy_state"));
+        assert!(names.iter().any(|name| name.as_ref() == b"wallets"));
+        assert_eq!(storage.load_wallet("alice").unwrap().unwrap(), b"wallet");
+    }
+
     #[test]
     fn test_wallet_storage() {
         let temp_dir = tempdir().unwrap();
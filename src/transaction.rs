@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use chrono::Utc;
 use log::debug;
-use crate::{Result, BlockchainError, utils, crypto::{KeyPair, DigitalSignature}};
+use crate::{Result, BlockchainError, utils, units, crypto::{self, AddressFormat, KeyPair, DigitalSignature, PublicKey}, htlc::{Htlc, HtlcAction}};
 
 /// Transaction types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -14,8 +14,26 @@ pub enum TransactionType {
     ContractCall,
     /// Staking transaction
     Staking,
+    /// Value transfer that cannot execute before the chain reaches `unlock_height`
+    TimeLocked {
+        /// Block height from which the transfer may be included
+        unlock_height: u64,
+    },
+    /// Withdrawal from a contract's balance to its owner
+    ContractWithdraw,
+    /// Lock, redemption or refund of a hash time-locked contract
+    Htlc(HtlcAction),
 }
 
+/// Staking action registering the sender as a validator
+pub const STAKING_REGISTER: &str = "register";
+/// Staking action adding to the sender's validator stake
+pub const STAKING_STAKE: &str = "stake";
+/// Staking action moving part of the sender's validator stake into unbonding
+pub const STAKING_UNSTAKE: &str = "unstake";
+/// Staking action crediting the sender with unstaked tokens whose unbonding has passed
+pub const STAKING_WITHDRAW: &str = "withdraw";
+
 /// Represents a transaction in the blockchain
 /// 
 /// A transaction contains information about a transfer of value between two parties.
@@ -30,8 +48,8 @@ pub struct Transaction {
     pub sender: String,
     /// Address of the receiver
     pub receiver: String,
-    /// Amount being transferred
-    pub amount: f64,
+    /// Amount being transferred, in base units
+    pub amount: u128,
     /// Timestamp when the transaction was created
     pub timestamp: i64,
     /// Optional message or note for the transaction
@@ -46,6 +64,9 @@ pub struct Transaction {
     pub gas_limit: Option<u64>,
     /// Gas price for contract execution
     pub gas_price: Option<f64>,
+    /// Sender's account nonce, covered by the signature to prevent replay
+    #[serde(default)]
+    pub nonce: u64,
 }
 
 impl Transaction {
@@ -54,7 +75,7 @@ impl Transaction {
     /// # Arguments
     /// * `sender` - The sender's address
     /// * `receiver` - The receiver's address
-    /// * `amount` - The amount to transfer
+    /// * `amount` - The amount to transfer, in base units
     /// * `message` - Optional message for the transaction
     /// 
     /// # Returns
@@ -62,23 +83,23 @@ impl Transaction {
     /// 
     /// # Example
     /// ```
-    /// use gillean::transaction::Transaction;
+    /// use gillean::{transaction::Transaction, units};
     /// 
     /// let tx = Transaction::new_transfer(
     ///     "alice".to_string(),
     ///     "bob".to_string(),
-    ///     100.0,
+    ///     units::gil(100),
     ///     Some("Payment for services".to_string())
     /// ).unwrap();
     /// 
     /// assert_eq!(tx.sender, "alice");
     /// assert_eq!(tx.receiver, "bob");
-    /// assert_eq!(tx.amount, 100.0);
+    /// assert_eq!(tx.amount, units::gil(100));
     /// ```
     pub fn new_transfer(
         sender: String,
         receiver: String,
-        amount: f64,
+        amount: u128,
         message: Option<String>,
     ) -> Result<Self> {
         // Validate inputs
@@ -88,11 +109,7 @@ impl Transaction {
             ));
         }
 
-        if amount <= 0.0 {
-            return Err(BlockchainError::TransactionValidationFailed(
-                "Transaction amount must be positive".to_string(),
-            ));
-        }
+        units::require_positive(amount, "Transaction amount")?;
 
         if sender == receiver {
             return Err(BlockchainError::TransactionValidationFailed(
@@ -101,7 +118,7 @@ impl Transaction {
         }
 
         let timestamp = Utc::now().timestamp();
-        let id = Self::generate_id(&sender, &receiver, amount, timestamp);
+        let id = Self::generate_id(&sender, &receiver, amount, 0, timestamp);
         
         let transaction = Transaction {
             id,
@@ -116,12 +133,108 @@ impl Transaction {
             contract_data: None,
             gas_limit: None,
             gas_price: None,
+            nonce: 0,
         };
 
         debug!("Created transfer transaction: {}", transaction.id);
         Ok(transaction)
     }
 
+    /// Create a new time-locked transfer transaction
+    /// 
+    /// The transfer stays pending until a block at or above `unlock_height` is mined.
+    /// 
+    /// # Arguments
+    /// * `sender` - The sender's address
+    /// * `receiver` - The receiver's address
+    /// * `amount` - The amount to transfer, in base units
+    /// * `unlock_height` - Earliest block height the transfer may be included in
+    /// * `message` - Optional message for the transaction
+    /// 
+    /// # Returns
+    /// * `Result<Transaction>` - The created transaction or an error
+    pub fn new_time_locked(
+        sender: String,
+        receiver: String,
+        amount: u128,
+        unlock_height: u64,
+        message: Option<String>,
+    ) -> Result<Self> {
+        let mut transaction = Self::new_transfer(sender, receiver, amount, message)?;
+        transaction.transaction_type = TransactionType::TimeLocked { unlock_height };
+
+        debug!("Created time-locked transaction: {} (unlocks at {})", transaction.id, unlock_height);
+        Ok(transaction)
+    }
+
+    /// Create a transaction locking funds in a hash time-locked contract
+    /// 
+    /// When mined, `amount` is moved out of the sender's balance into an HTLC whose ID
+    /// is this transaction's ID. The receiver can redeem it with the preimage of
+    /// `hashlock` below `timeout_height`; from `timeout_height` on the sender can
+    /// refund it.
+    /// 
+    /// # Arguments
+    /// * `sender` - Address locking the funds
+    /// * `receiver` - Address that can redeem the funds
+    /// * `amount` - Amount to lock, in base units
+    /// * `hashlock` - Hex-encoded SHA-256 hash of the secret preimage
+    /// * `timeout_height` - Block height at which the lock expires
+    /// 
+    /// # Returns
+    /// * `Result<Transaction>` - The created transaction or an error
+    pub fn new_htlc_lock(
+        sender: String,
+        receiver: String,
+        amount: u128,
+        hashlock: String,
+        timeout_height: u64,
+    ) -> Result<Self> {
+        let action = HtlcAction::Lock { hashlock: hashlock.to_lowercase(), timeout_height };
+        Self::new_htlc(sender, receiver, amount, action, "Lock HTLC")
+    }
+
+    /// Create a transaction redeeming a hash time-locked contract
+    /// 
+    /// The transaction names the HTLC's sender, receiver and amount. When mined before
+    /// the timeout height with a matching preimage, the amount is paid to the receiver.
+    /// 
+    /// # Arguments
+    /// * `htlc` - The HTLC to redeem
+    /// * `preimage` - Secret whose SHA-256 hash matches the hashlock
+    /// 
+    /// # Returns
+    /// * `Result<Transaction>` - The created transaction or an error
+    pub fn new_htlc_redeem(htlc: &Htlc, preimage: String) -> Result<Self> {
+        let action = HtlcAction::Redeem { htlc_id: htlc.id.clone(), preimage };
+        Self::new_htlc(htlc.sender.clone(), htlc.receiver.clone(), htlc.amount, action, "Redeem HTLC")
+    }
+
+    /// Create a transaction refunding an expired hash time-locked contract
+    /// 
+    /// The transaction names the HTLC's sender, receiver and amount. When mined at or
+    /// above the timeout height, the amount is returned to the sender.
+    /// 
+    /// # Arguments
+    /// * `htlc` - The HTLC to refund
+    /// 
+    /// # Returns
+    /// * `Result<Transaction>` - The created transaction or an error
+    pub fn new_htlc_refund(htlc: &Htlc) -> Result<Self> {
+        let action = HtlcAction::Refund { htlc_id: htlc.id.clone() };
+        Self::new_htlc(htlc.sender.clone(), htlc.receiver.clone(), htlc.amount, action, "Refund HTLC")
+    }
+
+    /// Create an HTLC transaction performing `action`
+    fn new_htlc(sender: String, receiver: String, amount: u128, action: HtlcAction, message: &str) -> Result<Self> {
+        let mut transaction = Self::new_transfer(sender, receiver, amount, Some(message.to_string()))?;
+        transaction.transaction_type = TransactionType::Htlc(action);
+        transaction.id = transaction.expected_id();
+
+        debug!("Created HTLC transaction: {}", transaction.id);
+        Ok(transaction)
+    }
+
     /// Create a new contract deployment transaction
     /// 
     /// # Arguments
@@ -150,17 +263,21 @@ impl Transaction {
             ));
         }
 
+        if contract_code.len() > crate::MAX_CONTRACT_SIZE {
+            return Err(BlockchainError::TransactionValidationFailed(format!(
+                "Contract code is {} bytes, exceeding the maximum of {} bytes",
+                contract_code.len(),
+                crate::MAX_CONTRACT_SIZE
+            )));
+        }
+
         if gas_limit == 0 {
             return Err(BlockchainError::TransactionValidationFailed(
                 "Gas limit must be greater than 0".to_string(),
             ));
         }
 
-        if gas_price <= 0.0 {
-            return Err(BlockchainError::TransactionValidationFailed(
-                "Gas price must be positive".to_string(),
-            ));
-        }
+        utils::validate_amount(gas_price, "Gas price", false)?;
 
         let timestamp = Utc::now().timestamp();
         let id = Self::generate_contract_id(&sender, &contract_code, timestamp);
@@ -170,7 +287,7 @@ impl Transaction {
             transaction_type: TransactionType::ContractDeploy,
             sender,
             receiver: "".to_string(), // Contract deployment doesn't have a receiver
-            amount: 0.0, // No value transfer for deployment
+            amount: 0, // No value transfer for deployment
             timestamp,
             message: Some("Contract deployment".to_string()),
             signature: None,
@@ -178,6 +295,7 @@ impl Transaction {
             contract_data: None,
             gas_limit: Some(gas_limit),
             gas_price: Some(gas_price),
+            nonce: 0,
         };
 
         debug!("Created contract deployment transaction: {}", transaction.id);
@@ -190,7 +308,7 @@ impl Transaction {
     /// * `sender` - The sender's address
     /// * `contract_address` - The contract's address
     /// * `contract_data` - Data to pass to the contract
-    /// * `amount` - Amount to send with the call
+    /// * `amount` - Amount to send with the call, in base units
     /// * `gas_limit` - Gas limit for execution
     /// * `gas_price` - Gas price for execution
     /// 
@@ -200,7 +318,7 @@ impl Transaction {
         sender: String,
         contract_address: String,
         contract_data: String,
-        amount: f64,
+        amount: u128,
         gas_limit: u64,
         gas_price: f64,
     ) -> Result<Self> {
@@ -216,14 +334,10 @@ impl Transaction {
             ));
         }
 
-        if gas_price <= 0.0 {
-            return Err(BlockchainError::TransactionValidationFailed(
-                "Gas price must be positive".to_string(),
-            ));
-        }
+        utils::validate_amount(gas_price, "Gas price", false)?;
 
         let timestamp = Utc::now().timestamp();
-        let id = Self::generate_contract_id(&sender, &contract_address, timestamp);
+        let id = Self::generate_contract_id(&sender, &format!("{}:{}", contract_address, contract_data), timestamp);
         
         let transaction = Transaction {
             id,
@@ -238,24 +352,69 @@ impl Transaction {
             contract_data: Some(contract_data),
             gas_limit: Some(gas_limit),
             gas_price: Some(gas_price),
+            nonce: 0,
         };
 
         debug!("Created contract call transaction: {}", transaction.id);
         Ok(transaction)
     }
 
+    /// Create a new contract withdrawal transaction
+    /// 
+    /// When mined, `amount` is moved from the contract's balance to `owner`,
+    /// provided `owner` owns the contract.
+    /// 
+    /// # Arguments
+    /// * `owner` - The contract owner's address, credited with the funds
+    /// * `contract_address` - The contract's address
+    /// * `amount` - Amount to withdraw, in base units
+    /// 
+    /// # Returns
+    /// * `Result<Transaction>` - The created transaction or an error
+    pub fn new_contract_withdraw(owner: String, contract_address: String, amount: u128) -> Result<Self> {
+        if owner.is_empty() || contract_address.is_empty() {
+            return Err(BlockchainError::TransactionValidationFailed(
+                "Owner and contract address cannot be empty".to_string(),
+            ));
+        }
+
+        units::require_positive(amount, "Withdrawal amount")?;
+
+        let timestamp = Utc::now().timestamp();
+        let id = Self::generate_id(&owner, &contract_address, amount, 0, timestamp);
+
+        let transaction = Transaction {
+            id,
+            transaction_type: TransactionType::ContractWithdraw,
+            sender: owner,
+            receiver: contract_address,
+            amount,
+            timestamp,
+            message: Some("Contract withdrawal".to_string()),
+            signature: None,
+            contract_code: None,
+            contract_data: None,
+            gas_limit: None,
+            gas_price: None,
+            nonce: 0,
+        };
+
+        debug!("Created contract withdrawal transaction: {}", transaction.id);
+        Ok(transaction)
+    }
+
     /// Create a new staking transaction
     /// 
     /// # Arguments
     /// * `validator_address` - The validator's address
-    /// * `stake_amount` - Amount to stake
+    /// * `stake_amount` - Amount to stake or unstake, in base units
     /// * `is_stake` - Whether this is a stake or unstake operation
     /// 
     /// # Returns
     /// * `Result<Transaction>` - The created transaction or an error
     pub fn new_staking(
         validator_address: String,
-        stake_amount: f64,
+        stake_amount: u128,
         is_stake: bool,
     ) -> Result<Self> {
         if validator_address.is_empty() {
@@ -264,14 +423,10 @@ impl Transaction {
             ));
         }
 
-        if stake_amount <= 0.0 {
-            return Err(BlockchainError::TransactionValidationFailed(
-                "Stake amount must be positive".to_string(),
-            ));
-        }
+        units::require_positive(stake_amount, "Stake amount")?;
 
         let timestamp = Utc::now().timestamp();
-        let id = Self::generate_id(&validator_address, &validator_address, stake_amount, timestamp);
+        let id = Self::generate_id(&validator_address, &validator_address, stake_amount, 0, timestamp);
         
         let transaction = Transaction {
             id,
@@ -283,27 +438,66 @@ impl Transaction {
             message: Some(if is_stake { "Stake tokens".to_string() } else { "Unstake tokens".to_string() }),
             signature: None,
             contract_code: None,
-            contract_data: Some(if is_stake { "stake".to_string() } else { "unstake".to_string() }),
+            contract_data: Some(if is_stake { STAKING_STAKE.to_string() } else { STAKING_UNSTAKE.to_string() }),
             gas_limit: None,
             gas_price: None,
+            nonce: 0,
         };
 
         debug!("Created staking transaction: {}", transaction.id);
         Ok(transaction)
     }
 
+    /// Create a transaction registering the sender as a PoS validator
+    /// 
+    /// When mined, `stake_amount` is moved from the sender's balance into the new
+    /// validator's stake, and the validator signs blocks with the key the
+    /// transaction is signed with.
+    /// 
+    /// # Arguments
+    /// * `validator_address` - The validator's address, which funds the stake
+    /// * `stake_amount` - Amount to stake, in base units
+    /// 
+    /// # Returns
+    /// * `Result<Transaction>` - The created transaction or an error
+    pub fn new_validator_registration(validator_address: String, stake_amount: u128) -> Result<Self> {
+        let mut transaction = Self::new_staking(validator_address, stake_amount, true)?;
+        transaction.message = Some("Register validator".to_string());
+        transaction.contract_data = Some(STAKING_REGISTER.to_string());
+        Ok(transaction)
+    }
+
+    /// Create a transaction withdrawing a validator's unbonded stake
+    /// 
+    /// When mined, `amount` of the sender's unstaked tokens whose unbonding period
+    /// has passed is credited back to the sender's balance.
+    /// 
+    /// # Arguments
+    /// * `validator_address` - The validator's address, credited with the tokens
+    /// * `amount` - Amount to withdraw, in base units
+    /// 
+    /// # Returns
+    /// * `Result<Transaction>` - The created transaction or an error
+    pub fn new_unbonded_withdrawal(validator_address: String, amount: u128) -> Result<Self> {
+        let mut transaction = Self::new_staking(validator_address, amount, false)?;
+        transaction.message = Some("Withdraw unbonded tokens".to_string());
+        transaction.contract_data = Some(STAKING_WITHDRAW.to_string());
+        Ok(transaction)
+    }
+
     /// Generate a unique transaction ID based on transaction data
     /// 
     /// # Arguments
     /// * `sender` - The sender's address
     /// * `receiver` - The receiver's address
-    /// * `amount` - The transaction amount
+    /// * `amount` - The transaction amount, in base units
+    /// * `nonce` - The sender's nonce, so repeated transfers get distinct IDs
     /// * `timestamp` - The transaction timestamp
     /// 
     /// # Returns
     /// * `String` - The generated transaction ID
-    fn generate_id(sender: &str, receiver: &str, amount: f64, timestamp: i64) -> String {
-        let data = format!("{}:{}:{}:{}", sender, receiver, amount, timestamp);
+    fn generate_id(sender: &str, receiver: &str, amount: u128, nonce: u64, timestamp: i64) -> String {
+        let data = format!("{}:{}:{}:{}:{}", sender, receiver, amount, nonce, timestamp);
         utils::calculate_hash(data)
     }
 
@@ -311,7 +505,7 @@ impl Transaction {
     /// 
     /// # Arguments
     /// * `sender` - The sender's address
-    /// * `contract_code` - The contract code or address
+    /// * `contract_code` - The contract code, or the address and call data of a call
     /// * `timestamp` - The transaction timestamp
     /// 
     /// # Returns
@@ -321,49 +515,89 @@ impl Transaction {
         utils::calculate_hash(data)
     }
 
+    /// Derive the ID the transaction's contents call for
+    fn expected_id(&self) -> String {
+        match &self.transaction_type {
+            TransactionType::Transfer => Self::generate_id(&self.sender, &self.receiver, self.amount, self.nonce, self.timestamp),
+            TransactionType::ContractDeploy => Self::generate_contract_id(&self.sender, self.contract_code.as_ref().unwrap(), self.timestamp),
+            TransactionType::ContractCall => Self::generate_contract_id(
+                &self.sender,
+                &format!("{}:{}", self.receiver, self.contract_data.as_deref().unwrap_or_default()),
+                self.timestamp,
+            ),
+            TransactionType::Staking => Self::generate_id(&self.sender, &self.receiver, self.amount, self.nonce, self.timestamp),
+            TransactionType::TimeLocked { .. } => Self::generate_id(&self.sender, &self.receiver, self.amount, self.nonce, self.timestamp),
+            TransactionType::ContractWithdraw => Self::generate_id(&self.sender, &self.receiver, self.amount, self.nonce, self.timestamp),
+            TransactionType::Htlc(action) => utils::calculate_hash(format!(
+                "{}:{}:{}:{}:{}:{}", self.sender, self.receiver, self.amount, self.nonce, self.timestamp, action.id_data()
+            )),
+        }
+    }
+
+    /// Move the transaction to a fixed timestamp, re-deriving its ID
+    /// 
+    /// # Arguments
+    /// * `timestamp` - The new timestamp
+    /// 
+    /// # Returns
+    /// * `Transaction` - The transaction with the new timestamp and matching ID
+    pub fn with_timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = timestamp;
+        self.id = self.expected_id();
+        self
+    }
+
+    /// Set the sender's nonce, re-deriving the ID
+    /// 
+    /// # Arguments
+    /// * `nonce` - The nonce the transaction is sent with
+    pub fn set_nonce(&mut self, nonce: u64) {
+        self.nonce = nonce;
+        self.id = self.expected_id();
+    }
+
     /// Validate the transaction
     /// 
     /// # Returns
     /// * `Result<()>` - Ok if valid, error otherwise
     pub fn validate(&self) -> Result<()> {
         // Check if ID is valid
-        let expected_id = match self.transaction_type {
-            TransactionType::Transfer => Self::generate_id(&self.sender, &self.receiver, self.amount, self.timestamp),
-            TransactionType::ContractDeploy => Self::generate_contract_id(&self.sender, self.contract_code.as_ref().unwrap(), self.timestamp),
-            TransactionType::ContractCall => Self::generate_contract_id(&self.sender, &self.receiver, self.timestamp),
-            TransactionType::Staking => Self::generate_id(&self.sender, &self.receiver, self.amount, self.timestamp),
-        };
+        let expected_id = self.expected_id();
         if self.id != expected_id {
             return Err(BlockchainError::TransactionValidationFailed(
                 format!("Invalid transaction ID: expected {}, got {}", expected_id, self.id),
             ));
         }
 
-        // Check if addresses are valid
-        if self.sender.is_empty() || self.receiver.is_empty() {
+        // Check if addresses are valid; deployments have no receiver
+        let is_deploy = self.transaction_type == TransactionType::ContractDeploy;
+        if self.sender.is_empty() || (self.receiver.is_empty() && !is_deploy) {
             return Err(BlockchainError::TransactionValidationFailed(
                 "Invalid addresses".to_string(),
             ));
         }
 
-        // Check if amount is positive
-        if self.amount <= 0.0 {
+        // Check if amount is positive; contract transactions may carry no value
+        let allow_zero = matches!(self.transaction_type, TransactionType::ContractDeploy | TransactionType::ContractCall);
+        if self.amount == 0 && !allow_zero {
             return Err(BlockchainError::TransactionValidationFailed(
                 "Transaction amount must be positive".to_string(),
             ));
         }
 
-        // Check if sender and receiver are different
-        if self.sender == self.receiver {
+        // Check if sender and receiver are different; staking names the validator as both
+        if self.sender == self.receiver && self.transaction_type != TransactionType::Staking {
             return Err(BlockchainError::TransactionValidationFailed(
                 "Sender and receiver cannot be the same".to_string(),
             ));
         }
 
-        // Check if timestamp is reasonable (not too far in the past or future)
+        // Check if timestamp is reasonable (not too far in the past or future).
+        // Coinbase transactions are not submitted by users, and genesis allocations
+        // carry the fixed `GENESIS_TIMESTAMP`, so they are exempt.
         let now = Utc::now().timestamp();
         let time_diff = (now - self.timestamp).abs();
-        if time_diff > 3600 * 24 * 365 { // 1 year
+        if !self.is_coinbase() && time_diff > 3600 * 24 * 365 { // 1 year
             return Err(BlockchainError::TransactionValidationFailed(
                 "Transaction timestamp is too far from current time".to_string(),
             ));
@@ -409,6 +643,106 @@ impl Transaction {
         self.sender == "COINBASE"
     }
 
+    /// Check whether executing the transaction debits its amount from the sender
+    /// 
+    /// # Returns
+    /// * `bool` - False for coinbase transactions, contract withdrawals, unstaking,
+    ///   unbonded stake withdrawals and HTLC redemptions and refunds
+    pub fn debits_sender(&self) -> bool {
+        !self.is_coinbase()
+            && self.transaction_type != TransactionType::ContractWithdraw
+            && !matches!(self.staking_action(), Some(STAKING_UNSTAKE | STAKING_WITHDRAW))
+            && !matches!(self.htlc_action(), Some(HtlcAction::Redeem { .. } | HtlcAction::Refund { .. }))
+    }
+
+    /// Get the operation an HTLC transaction performs
+    /// 
+    /// # Returns
+    /// * `Option<&HtlcAction>` - The action, or None if this is not an HTLC transaction
+    pub fn htlc_action(&self) -> Option<&HtlcAction> {
+        match &self.transaction_type {
+            TransactionType::Htlc(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    /// Get the operation a staking transaction performs
+    /// 
+    /// # Returns
+    /// * `Option<&str>` - One of `STAKING_REGISTER`, `STAKING_STAKE`, `STAKING_UNSTAKE` or
+    ///   `STAKING_WITHDRAW` (or an unknown action), or None if this is not a staking transaction
+    pub fn staking_action(&self) -> Option<&str> {
+        match self.transaction_type {
+            TransactionType::Staking => Some(self.contract_data.as_deref().unwrap_or(STAKING_STAKE)),
+            _ => None,
+        }
+    }
+
+    /// Get the fee the transaction offers
+    /// 
+    /// # Returns
+    /// * `f64` - Gas limit times gas price, or 0 if the transaction carries no gas
+    pub fn fee(&self) -> f64 {
+        match (self.gas_limit, self.gas_price) {
+            (Some(gas_limit), Some(gas_price)) => gas_limit as f64 * gas_price,
+            _ => 0.0,
+        }
+    }
+
+    /// Get the fee the transaction offers per byte of its serialized size
+    /// 
+    /// # Returns
+    /// * `f64` - Fee divided by size in bytes
+    pub fn fee_per_byte(&self) -> f64 {
+        match self.size() {
+            0 => 0.0,
+            size => self.fee() / size as f64,
+        }
+    }
+
+    /// Check if this transaction consumes a sender account nonce
+    /// 
+    /// # Returns
+    /// * `bool` - True for non-coinbase transfers, contract withdrawals, staking and HTLC locks
+    pub fn uses_nonce(&self) -> bool {
+        match self.transaction_type {
+            TransactionType::Transfer => !self.is_coinbase(),
+            TransactionType::ContractWithdraw | TransactionType::Staking => true,
+            TransactionType::Htlc(HtlcAction::Lock { .. }) => true,
+            _ => false,
+        }
+    }
+
+    /// Check if the transaction must be signed by its sender
+    ///
+    /// # Returns
+    /// * `bool` - True for nonce-bearing transactions and time-locked transfers
+    pub fn requires_sender_signature(&self) -> bool {
+        self.uses_nonce() || self.unlock_height().is_some()
+    }
+
+    /// Get the block height a time-locked transaction unlocks at
+    /// 
+    /// # Returns
+    /// * `Option<u64>` - The unlock height, or None if the transaction is not time-locked
+    pub fn unlock_height(&self) -> Option<u64> {
+        match self.transaction_type {
+            TransactionType::TimeLocked { unlock_height } => Some(unlock_height),
+            _ => None,
+        }
+    }
+
+    /// Check if the transaction may be included in a block at the given height
+    /// 
+    /// # Arguments
+    /// * `height` - Index of the block the transaction would be included in
+    /// 
+    /// # Returns
+    /// * `bool` - False only for time-locked transactions that are still locked
+    pub fn is_unlocked_at(&self, height: u64) -> bool {
+        self.unlock_height().is_none_or(|unlock_height| height >= unlock_height)
+    }
+
     /// Get the transaction size in bytes (approximate)
     /// 
     /// # Returns
@@ -427,17 +761,17 @@ impl Transaction {
     /// 
     /// # Example
     /// ```
-    /// use gillean::transaction::Transaction;
+    /// use gillean::{transaction::Transaction, units};
     /// use gillean::crypto::KeyPair;
     /// 
     /// let keypair = KeyPair::generate().unwrap();
-    /// let mut tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
+    /// let mut tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
     /// tx.sign(&keypair).unwrap();
     /// assert!(tx.is_signed());
     /// ```
     pub fn sign(&mut self, keypair: &KeyPair) -> Result<()> {
-        let message = self.to_json()?;
-        let signature = keypair.sign(message.as_bytes())?;
+        let message = self.canonical_bytes()?;
+        let signature = keypair.sign(&message)?;
         self.signature = Some(signature);
         
         debug!("Signed transaction: {}", self.id);
@@ -451,19 +785,18 @@ impl Transaction {
     /// 
     /// # Example
     /// ```
-    /// use gillean::transaction::Transaction;
+    /// use gillean::{transaction::Transaction, units};
     /// use gillean::crypto::KeyPair;
     /// 
     /// let keypair = KeyPair::generate().unwrap();
-    /// let mut tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, None).unwrap();
+    /// let mut tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), None).unwrap();
     /// tx.sign(&keypair).unwrap();
-    /// // Note: In a real implementation, signature verification would work correctly
-    /// // For now, this is a simplified implementation
+    /// assert!(tx.verify_signature().unwrap());
     /// ```
     pub fn verify_signature(&self) -> Result<bool> {
         if let Some(ref signature) = self.signature {
-            let message = self.to_json()?;
-            signature.verify(message.as_bytes())
+            let message = self.canonical_bytes()?;
+            signature.verify(&message)
         } else {
             Ok(false)
         }
@@ -477,6 +810,22 @@ impl Transaction {
         self.signature.is_some()
     }
 
+    /// Check that the transaction is signed by the key its sender address derives from
+    /// 
+    /// # Returns
+    /// * `Result<bool>` - True if the signature is valid and its public key hashes to
+    ///   the sender address, in either address format
+    pub fn is_signed_by_sender(&self) -> Result<bool> {
+        let Some(signature) = &self.signature else {
+            return Ok(false);
+        };
+        let signer = PublicKey::from_bytes(signature.public_key.clone())?;
+        let is_sender = [AddressFormat::Checksummed, AddressFormat::Legacy]
+            .into_iter()
+            .any(|format| crypto::create_address_with_format(&signer, format) == self.sender);
+        Ok(is_sender && self.verify_signature()?)
+    }
+
     /// Get the signer's public key if the transaction is signed
     /// 
     /// # Returns
@@ -487,15 +836,57 @@ impl Transaction {
 
     /// Convert transaction to bytes for signing
     /// 
+    /// Equivalent to [`Transaction::canonical_bytes`].
+    /// 
     /// # Returns
     /// * `Result<Vec<u8>>` - The transaction as bytes or an error
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
-        // Create a copy without signature for consistent hashing
-        let mut tx_for_hash = self.clone();
-        tx_for_hash.signature = None;
-        
-        let json = tx_for_hash.to_json()?;
-        Ok(json.into_bytes())
+        self.canonical_bytes()
+    }
+
+    /// Deterministic encoding of the transaction that signatures cover
+    /// 
+    /// Fields are written in a fixed (alphabetical) order, the signature is left out,
+    /// and contract data holding JSON is re-encoded with sorted object keys, so the same
+    /// logical transaction produces identical bytes on every platform.
+    /// 
+    /// # Returns
+    /// * `Result<Vec<u8>>` - The canonical bytes or an error
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>> {
+        let contract_data = self.contract_data.as_ref().map(|data| {
+            match serde_json::from_str::<serde_json::Value>(data) {
+                Ok(value) => canonical_json(&value).to_string(),
+                Err(_) => data.clone(),
+            }
+        });
+
+        let fields: [(&str, serde_json::Value); 13] = [
+            ("amount", serde_json::to_value(self.amount)?),
+            ("contract_code", serde_json::to_value(&self.contract_code)?),
+            ("contract_data", serde_json::to_value(contract_data)?),
+            ("gas_limit", serde_json::to_value(self.gas_limit)?),
+            ("gas_price", serde_json::to_value(self.gas_price)?),
+            ("id", serde_json::to_value(&self.id)?),
+            ("message", serde_json::to_value(&self.message)?),
+            ("nonce", serde_json::to_value(self.nonce)?),
+            ("receiver", serde_json::to_value(&self.receiver)?),
+            ("sender", serde_json::to_value(&self.sender)?),
+            ("timestamp", serde_json::to_value(self.timestamp)?),
+            ("transaction_type", serde_json::to_value(&self.transaction_type)?),
+            ("version", serde_json::to_value(CANONICAL_ENCODING_VERSION)?),
+        ];
+
+        let mut bytes = Vec::from(&b"{"[..]);
+        for (index, (name, value)) in fields.iter().enumerate() {
+            if index > 0 {
+                bytes.push(b',');
+            }
+            bytes.extend_from_slice(serde_json::to_string(name)?.as_bytes());
+            bytes.push(b':');
+            bytes.extend_from_slice(value.to_string().as_bytes());
+        }
+        bytes.push(b'}');
+        Ok(bytes)
     }
 
     /// Set the signature and public key for the transaction
@@ -508,7 +899,7 @@ impl Transaction {
     /// * `Result<()>` - Ok if successful, error otherwise
     pub fn set_signature(&mut self, signature: DigitalSignature, _public_key: crate::PublicKey) -> Result<()> {
         // Verify the signature matches the transaction
-        let transaction_data = self.to_bytes()?;
+        let transaction_data = self.canonical_bytes()?;
         if !signature.verify(&transaction_data)? {
             return Err(BlockchainError::TransactionValidationFailed(
                 "Invalid signature for transaction".to_string(),
@@ -521,6 +912,24 @@ impl Transaction {
     }
 }
 
+/// Version tag of the canonical signing encoding (2 since amounts are base units)
+const CANONICAL_ENCODING_VERSION: u32 = 2;
+
+/// Rebuild a JSON value with every object's keys in sorted order
+fn canonical_json(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<(&String, &serde_json::Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            serde_json::Value::Object(
+                entries.into_iter().map(|(key, value)| (key.clone(), canonical_json(value))).collect(),
+            )
+        }
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.iter().map(canonical_json).collect()),
+        other => other.clone(),
+    }
+}
+
 impl std::fmt::Display for Transaction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -529,11 +938,121 @@ impl std::fmt::Display for Transaction {
             &self.id[..8],
             self.sender,
             self.receiver,
-            self.amount
+            units::format_units(self.amount)
         )
     }
 }
 
+/// Outcome of executing a transaction
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ReceiptStatus {
+    /// The transaction executed and its state changes were applied
+    Success,
+    /// Contract execution failed; no state changes were applied
+    Failed {
+        /// Execution error
+        error: String,
+    },
+}
+
+/// Where a transaction is in its lifecycle
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "status")]
+pub enum TransactionStatus {
+    /// Waiting in the pending pool to be mined
+    Pending,
+    /// Mined and executed successfully
+    Confirmed {
+        /// Index of the including block
+        block_number: u64,
+        /// Number of blocks on top of and including the including block
+        confirmations: u64,
+    },
+    /// Mined, but contract execution failed
+    Failed {
+        /// Index of the including block
+        block_number: u64,
+        /// Number of blocks on top of and including the including block
+        confirmations: u64,
+        /// Execution error
+        reason: String,
+    },
+}
+
+impl TransactionStatus {
+    /// Number of blocks on top of and including the block holding the transaction (0 while pending)
+    pub fn confirmations(&self) -> u64 {
+        match self {
+            TransactionStatus::Pending => 0,
+            TransactionStatus::Confirmed { confirmations, .. }
+            | TransactionStatus::Failed { confirmations, .. } => *confirmations,
+        }
+    }
+}
+
+/// Record of what happened when a mined transaction was executed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    /// ID of the executed transaction
+    pub tx_id: String,
+    /// Index of the block that included the transaction
+    pub block_index: u64,
+    /// Execution outcome
+    pub status: ReceiptStatus,
+    /// Gas consumed by contract execution (0 for non-contract transactions)
+    pub gas_used: u64,
+    /// Value returned by the contract, if any
+    pub return_value: Option<String>,
+    /// Events emitted during execution
+    pub events: Vec<crate::smart_contract::ContractEvent>,
+}
+
+impl TransactionReceipt {
+    /// Build the receipt of a transaction that executed successfully
+    ///
+    /// # Arguments
+    /// * `transaction` - The executed transaction
+    /// * `block_index` - Index of the including block
+    /// * `result` - Contract execution result, if the transaction ran a contract
+    pub fn success(transaction: &Transaction, block_index: u64, result: Option<crate::smart_contract::ContractResult>) -> Self {
+        let (gas_used, return_value, events) = match result {
+            Some(result) => (result.gas_used, result.return_value, result.events),
+            None => (0, None, Vec::new()),
+        };
+        Self {
+            tx_id: transaction.id.clone(),
+            block_index,
+            status: ReceiptStatus::Success,
+            gas_used,
+            return_value,
+            events,
+        }
+    }
+
+    /// Build the receipt of a transaction whose contract execution failed
+    ///
+    /// # Arguments
+    /// * `transaction` - The executed transaction
+    /// * `block_index` - Index of the including block
+    /// * `error` - Execution error
+    /// * `gas_used` - Gas consumed before the execution failed
+    pub fn failed(transaction: &Transaction, block_index: u64, error: String, gas_used: u64) -> Self {
+        Self {
+            tx_id: transaction.id.clone(),
+            block_index,
+            status: ReceiptStatus::Failed { error },
+            gas_used,
+            return_value: None,
+            events: Vec::new(),
+        }
+    }
+
+    /// Check whether the transaction executed successfully
+    pub fn is_success(&self) -> bool {
+        self.status == ReceiptStatus::Success
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -543,13 +1062,13 @@ mod tests {
         let tx = Transaction::new_transfer(
             "alice".to_string(),
             "bob".to_string(),
-            100.0,
+            units::gil(100),
             Some("Test transaction".to_string()),
         ).unwrap();
 
         assert_eq!(tx.sender, "alice");
         assert_eq!(tx.receiver, "bob");
-        assert_eq!(tx.amount, 100.0);
+        assert_eq!(tx.amount, units::gil(100));
         assert_eq!(tx.message, Some("Test transaction".to_string()));
         assert!(!tx.id.is_empty());
     }
@@ -559,7 +1078,7 @@ mod tests {
         let tx = Transaction::new_transfer(
             "alice".to_string(),
             "bob".to_string(),
-            100.0,
+            units::gil(100),
             None,
         ).unwrap();
 
@@ -571,21 +1090,30 @@ mod tests {
         let result = Transaction::new_transfer(
             "".to_string(),
             "bob".to_string(),
-            100.0,
+            units::gil(100),
             None,
         );
         assert!(result.is_err());
     }
 
     #[test]
-    fn test_invalid_transaction_negative_amount() {
-        let result = Transaction::new_transfer(
-            "alice".to_string(),
-            "bob".to_string(),
-            -100.0,
-            None,
-        );
-        assert!(result.is_err());
+    fn test_zero_amounts_rejected() {
+        assert!(matches!(
+            Transaction::new_transfer("alice".to_string(), "bob".to_string(), 0, None),
+            Err(BlockchainError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            Transaction::new_staking("validator".to_string(), 0, true),
+            Err(BlockchainError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            Transaction::new_contract_withdraw("alice".to_string(), "contract".to_string(), 0),
+            Err(BlockchainError::InvalidInput(_))
+        ));
+
+        assert!(Transaction::new_transfer("alice".to_string(), "bob".to_string(), 1, None).is_ok());
+        assert!(Transaction::new_contract_call("alice".to_string(), "contract".to_string(), "call".to_string(), 0, 1000, 1.0).is_ok());
+        assert!(Transaction::new_staking("validator".to_string(), units::gil(100), true).is_ok());
     }
 
     #[test]
@@ -593,7 +1121,7 @@ mod tests {
         let result = Transaction::new_transfer(
             "alice".to_string(),
             "alice".to_string(),
-            100.0,
+            units::gil(100),
             None,
         );
         assert!(result.is_err());
@@ -604,7 +1132,7 @@ mod tests {
         let tx = Transaction::new_transfer(
             "alice".to_string(),
             "bob".to_string(),
-            100.0,
+            units::gil(100),
             Some("Test".to_string()),
         ).unwrap();
 
@@ -619,7 +1147,7 @@ mod tests {
         let tx = Transaction::new_transfer(
             "COINBASE".to_string(),
             "miner".to_string(),
-            50.0,
+            units::gil(50),
             None,
         ).unwrap();
 
@@ -631,10 +1159,72 @@ mod tests {
         let tx = Transaction::new_transfer(
             "alice".to_string(),
             "bob".to_string(),
-            100.0,
+            units::gil(100),
             None,
         ).unwrap();
 
         assert!(tx.size() > 0);
     }
+
+    fn fixed_transaction() -> Transaction {
+        Transaction {
+            id: "tx1".to_string(),
+            transaction_type: TransactionType::ContractCall,
+            sender: "alice".to_string(),
+            receiver: "contract".to_string(),
+            amount: 150_000_000,
+            timestamp: 1_700_000_000,
+            message: None,
+            signature: None,
+            contract_code: None,
+            contract_data: Some(r#"{"method":"transfer","args":{"to":"bob","amount":5}}"#.to_string()),
+            gas_limit: Some(1000),
+            gas_price: Some(0.5),
+            nonce: 3,
+        }
+    }
+
+    #[test]
+    fn test_canonical_bytes_deterministic() {
+        let tx = fixed_transaction();
+        let mut reordered = fixed_transaction();
+        reordered.contract_data = Some(r#"{"args":{"amount":5,"to":"bob"},"method":"transfer"}"#.to_string());
+
+        assert_eq!(tx.canonical_bytes().unwrap(), tx.canonical_bytes().unwrap());
+        assert_eq!(tx.canonical_bytes().unwrap(), reordered.canonical_bytes().unwrap());
+        assert_eq!(
+            String::from_utf8(tx.canonical_bytes().unwrap()).unwrap(),
+            concat!(
+                r#"{"amount":150000000,"contract_code":null,"#,
+                r#""contract_data":"{\"args\":{\"amount\":5,\"to\":\"bob\"},\"method\":\"transfer\"}","#,
+                r#""gas_limit":1000,"gas_price":0.5,"id":"tx1","message":null,"nonce":3,"#,
+                r#""receiver":"contract","sender":"alice","timestamp":1700000000,"#,
+                r#""transaction_type":"ContractCall","version":2}"#,
+            )
+        );
+
+        // The signature is not part of the signed bytes
+        let keypair = KeyPair::generate().unwrap();
+        let mut signed = fixed_transaction();
+        signed.sign(&keypair).unwrap();
+        assert_eq!(signed.canonical_bytes().unwrap(), tx.canonical_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_signature_verifies_after_transport() {
+        let keypair = KeyPair::generate().unwrap();
+        let mut tx = fixed_transaction();
+        tx.sign(&keypair).unwrap();
+        assert!(tx.verify_signature().unwrap());
+
+        // Another node receives the transaction as JSON and verifies the same canonical bytes
+        let received: Transaction = serde_json::from_str(&tx.to_json().unwrap()).unwrap();
+        assert!(received.verify_signature().unwrap());
+        let signature = received.signature.as_ref().unwrap();
+        assert!(signature.verify(&fixed_transaction().canonical_bytes().unwrap()).unwrap());
+
+        let mut tampered = received.clone();
+        tampered.amount = units::gil(2);
+        assert!(!tampered.verify_signature().unwrap());
+    }
 }
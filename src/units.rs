@@ -0,0 +1,183 @@
+//! # Base Units
+//!
+//! Exact integer representation of GIL amounts. Balances are kept as `u128` counts of
+//! the smallest unit, with [`UNITS_PER_GIL`] units in one GIL, so adding and subtracting
+//! them never drifts. Decimal `f64` amounts are only used at the edges (API requests and
+//! responses, the CLI) and are converted with the helpers below.
+
+use crate::{Result, BlockchainError, utils};
+
+/// Number of decimal places in a GIL amount
+pub const DECIMALS: u32 = 8;
+
+/// Number of base units in one GIL
+pub const UNITS_PER_GIL: u128 = 10u128.pow(DECIMALS);
+
+/// Convert a whole number of GIL to base units
+///
+/// # Arguments
+/// * `amount` - Whole GIL amount
+///
+/// # Returns
+/// * `u128` - The amount in base units
+pub const fn gil(amount: u64) -> u128 {
+    amount as u128 * UNITS_PER_GIL
+}
+
+/// Convert a decimal amount to base units, rounding to the nearest unit
+///
+/// # Arguments
+/// * `amount` - Decimal GIL amount
+///
+/// # Returns
+/// * `Result<u128>` - The amount in base units, or an error for NaN, infinite,
+///   negative or out-of-range amounts
+///
+/// # Example
+/// ```
+/// use gillean::units;
+///
+/// assert_eq!(units::to_base_units(1.5).unwrap(), 150_000_000);
+/// assert!(units::to_base_units(f64::NAN).is_err());
+/// ```
+pub fn to_base_units(amount: f64) -> Result<u128> {
+    utils::validate_amount(amount, "Amount", true)?;
+
+    let units = (amount * UNITS_PER_GIL as f64).round();
+    if units >= u128::MAX as f64 {
+        return Err(BlockchainError::InvalidInput(format!(
+            "Amount {} is too large to represent",
+            amount
+        )));
+    }
+    Ok(units as u128)
+}
+
+/// Check that an amount in base units is not zero
+///
+/// # Arguments
+/// * `units` - Amount in base units
+/// * `name` - Name of the amount used in the error message
+///
+/// # Returns
+/// * `Result<()>` - Ok if the amount is positive, `InvalidInput` otherwise
+pub fn require_positive(units: u128, name: &str) -> Result<()> {
+    if units == 0 {
+        return Err(BlockchainError::InvalidInput(format!("{} must be positive, got 0", name)));
+    }
+    Ok(())
+}
+
+/// Take a fraction of an amount in base units, rounding down
+///
+/// The fraction is applied in parts per billion so the result stays an exact
+/// integer no larger than `units`.
+///
+/// # Arguments
+/// * `units` - Amount in base units
+/// * `fraction` - Fraction between 0.0 and 1.0; values outside are clamped
+///
+/// # Returns
+/// * `u128` - The fraction of `units`
+pub fn fraction_of(units: u128, fraction: f64) -> u128 {
+    const PARTS: u128 = 1_000_000_000;
+    let parts = (fraction.clamp(0.0, 1.0) * PARTS as f64).round() as u128;
+    (units / PARTS) * parts + (units % PARTS) * parts / PARTS
+}
+
+/// Convert base units to a decimal amount for display
+///
+/// # Arguments
+/// * `units` - Amount in base units
+///
+/// # Returns
+/// * `f64` - The decimal GIL amount
+pub fn from_base_units(units: u128) -> f64 {
+    (units / UNITS_PER_GIL) as f64 + (units % UNITS_PER_GIL) as f64 / UNITS_PER_GIL as f64
+}
+
+/// Format base units as an exact decimal string
+///
+/// # Arguments
+/// * `units` - Amount in base units
+///
+/// # Returns
+/// * `String` - The amount with all decimal places, e.g. `"1.50000000"`
+pub fn format_units(units: u128) -> String {
+    format!(
+        "{}.{:0width$}",
+        units / UNITS_PER_GIL,
+        units % UNITS_PER_GIL,
+        width = DECIMALS as usize
+    )
+}
+
+/// Parse an exact decimal string into base units
+///
+/// # Arguments
+/// * `amount` - Decimal amount with at most [`DECIMALS`] fractional digits
+///
+/// # Returns
+/// * `Result<u128>` - The amount in base units, or an error if the string is malformed
+pub fn parse_units(amount: &str) -> Result<u128> {
+    let invalid = || BlockchainError::InvalidInput(format!("Invalid amount: {}", amount));
+
+    let (whole, fraction) = amount.trim().split_once('.').unwrap_or((amount.trim(), ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+    if fraction.len() > DECIMALS as usize
+        || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    let whole: u128 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+    let fraction: u128 = if fraction.is_empty() {
+        0
+    } else {
+        fraction.parse::<u128>().map_err(|_| invalid())? * 10u128.pow(DECIMALS - fraction.len() as u32)
+    };
+
+    whole
+        .checked_mul(UNITS_PER_GIL)
+        .and_then(|units| units.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_conversions() {
+        assert_eq!(gil(3), 300_000_000);
+        assert_eq!(to_base_units(0.1).unwrap(), 10_000_000);
+        assert_eq!(to_base_units(0.00000001).unwrap(), 1);
+        assert_eq!(from_base_units(150_000_000), 1.5);
+        assert_eq!(format_units(123_456_789), "1.23456789");
+        assert_eq!(parse_units("1.23456789").unwrap(), 123_456_789);
+        assert_eq!(parse_units("42").unwrap(), gil(42));
+        assert_eq!(parse_units(".5").unwrap(), 50_000_000);
+        assert_eq!(fraction_of(gil(1000), 0.1), gil(100));
+        assert_eq!(fraction_of(7, 0.5), 3);
+        assert_eq!(fraction_of(u128::MAX, 1.0), u128::MAX);
+
+        assert!(to_base_units(-1.0).is_err());
+        assert!(to_base_units(f64::INFINITY).is_err());
+        assert!(parse_units("1.000000001").is_err());
+        assert!(parse_units("-1").is_err());
+        assert!(parse_units("abc").is_err());
+    }
+
+    #[test]
+    fn test_summing_tiny_amounts_is_exact() {
+        let tenth = to_base_units(0.1).unwrap();
+        let total: u128 = (0..1_000_000).map(|_| tenth).sum();
+        assert_eq!(total, gil(100_000));
+
+        // The same sum in floating point drifts
+        let drifted: f64 = (0..1_000_000).map(|_| 0.1).sum();
+        assert_ne!(drifted, 100_000.0);
+    }
+}
@@ -1,6 +1,8 @@
 use sha2::{Sha256, Digest};
 use hex;
 use log::debug;
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 /// Calculate SHA-256 hash of the given data
 /// 
@@ -110,6 +112,35 @@ pub fn is_valid_hex(hex_str: &str) -> bool {
     hex_str.chars().all(|c| c.is_ascii_hexdigit())
 }
 
+/// Validate a monetary amount
+/// 
+/// Rejects NaN, infinite and negative values, and zero unless `allow_zero` is set.
+/// 
+/// # Arguments
+/// * `amount` - The amount to validate
+/// * `name` - Name of the amount used in the error message
+/// * `allow_zero` - Whether zero is an acceptable value
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if the amount is valid, `InvalidInput` otherwise
+pub fn validate_amount(amount: f64, name: &str, allow_zero: bool) -> crate::Result<()> {
+    if !amount.is_finite() {
+        return Err(crate::BlockchainError::InvalidInput(format!(
+            "{} must be a finite number, got {}",
+            name, amount
+        )));
+    }
+    if amount < 0.0 || (amount == 0.0 && !allow_zero) {
+        return Err(crate::BlockchainError::InvalidInput(format!(
+            "{} must be {}, got {}",
+            name,
+            if allow_zero { "non-negative" } else { "positive" },
+            amount
+        )));
+    }
+    Ok(())
+}
+
 /// Calculate the size of a block in bytes (approximate)
 /// 
 /// # Arguments
@@ -143,10 +174,60 @@ pub fn debug_log(prefix: &str, message: &str) {
     debug!("[{}] {}", prefix, message);
 }
 
+/// Serialize a `HashMap` in sorted key order
+///
+/// `HashMap` iteration order is randomized per process, so two nodes holding
+/// identical state would otherwise produce different bytes. Use with
+/// `#[serde(serialize_with = "crate::utils::sorted_map")]`.
+///
+/// # Arguments
+/// * `map` - The map to serialize
+/// * `serializer` - The serde serializer
+///
+/// # Returns
+/// * `Result<S::Ok, S::Error>` - The serializer output
+pub fn sorted_map<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+/// Serialize a `HashSet` in sorted order
+///
+/// The set counterpart of `sorted_map`. Use with
+/// `#[serde(serialize_with = "crate::utils::sorted_set")]`.
+///
+/// # Arguments
+/// * `set` - The set to serialize
+/// * `serializer` - The serde serializer
+///
+/// # Returns
+/// * `Result<S::Ok, S::Error>` - The serializer output
+pub fn sorted_set<T, S>(set: &HashSet<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Ord + Serialize,
+    S: Serializer,
+{
+    set.iter().collect::<BTreeSet<_>>().serialize(serializer)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate_amount() {
+        assert!(validate_amount(10.5, "Amount", false).is_ok());
+        assert!(validate_amount(0.0, "Amount", true).is_ok());
+        assert!(validate_amount(0.0, "Amount", false).is_err());
+        assert!(validate_amount(-1.0, "Amount", true).is_err());
+        assert!(validate_amount(f64::NAN, "Amount", true).is_err());
+        assert!(validate_amount(f64::NEG_INFINITY, "Amount", true).is_err());
+    }
+
     #[test]
     fn test_calculate_hash() {
         let hash = calculate_hash("test");
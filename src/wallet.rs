@@ -5,10 +5,12 @@ use aes_gcm::{
     aead::{Aead, KeyInit},
     Aes256Gcm, Nonce,
 };
+use argon2::{Algorithm, Argon2, Params, Version};
 use rand::Rng;
 use serde::{Serialize, Deserialize};
 use log::{info, error};
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 // use base64::engine::general_purpose; // Unused import
 
@@ -43,6 +45,69 @@ impl From<WalletError> for BlockchainError {
     }
 }
 
+/// Argon2id parameters used to derive a wallet's encryption key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory cost in KiB
+    pub memory_kib: u32,
+    /// Number of passes over memory
+    pub iterations: u32,
+    /// Degree of parallelism (lanes)
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Build an Argon2id hasher from these parameters
+    /// 
+    /// # Returns
+    /// * `Result<Argon2>` - The hasher, or an error if the parameters are out of range
+    fn argon2(&self) -> Result<Argon2<'static>> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(32))
+            .map_err(|e| WalletError::Encryption(format!("Invalid KDF parameters: {}", e)))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+}
+
+/// Estimate a password's strength in bits of entropy
+/// 
+/// The estimate is the password's length times log2 of the number of characters
+/// in the classes it draws from (lowercase, uppercase, digits, symbols, other).
+/// 
+/// # Arguments
+/// * `password` - The password to score
+/// 
+/// # Returns
+/// * `u32` - Estimated entropy in bits
+pub fn password_strength(password: &str) -> u32 {
+    let has = |matches: fn(&char) -> bool| password.chars().any(|c| matches(&c));
+    let pool = [
+        (has(char::is_ascii_lowercase), 26),
+        (has(char::is_ascii_uppercase), 26),
+        (has(char::is_ascii_digit), 10),
+        (has(|c| c.is_ascii_punctuation() || *c == ' '), 33),
+        (has(|c| !c.is_ascii()), 100),
+    ]
+    .iter()
+    .filter(|(present, _)| *present)
+    .map(|(_, size)| size)
+    .sum::<u32>();
+    if pool == 0 {
+        return 0;
+    }
+
+    (password.chars().count() as f64 * (pool as f64).log2()) as u32
+}
+
 /// Encrypted wallet data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncryptedWallet {
@@ -51,6 +116,14 @@ pub struct EncryptedWallet {
     pub encrypted_data: Vec<u8>,
     pub salt: Vec<u8>,
     pub nonce: Vec<u8>,
+    /// KDF parameters the key was derived with; `None` for wallets encrypted before
+    /// parameters were stored, which use the legacy derivation
+    #[serde(default)]
+    pub kdf_params: Option<KdfParams>,
+    /// Password strength in bits recorded at creation (never the password itself);
+    /// `None` for wallets created before strengths were recorded
+    #[serde(default)]
+    pub password_strength: Option<u32>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub last_accessed: chrono::DateTime<chrono::Utc>,
 }
@@ -70,6 +143,8 @@ pub struct WalletInfo {
 pub struct WalletManager {
     wallets: HashMap<String, EncryptedWallet>,
     storage_path: Option<String>,
+    /// Storage instance shared with the node, used instead of reopening `storage_path`
+    storage: Option<Arc<crate::storage::BlockchainStorage>>,
 }
 
 impl Default for WalletManager {
@@ -87,6 +162,7 @@ impl WalletManager {
         WalletManager {
             wallets: HashMap::new(),
             storage_path: None,
+            storage: None,
         }
     }
     
@@ -101,6 +177,7 @@ impl WalletManager {
         WalletManager {
             wallets: HashMap::new(),
             storage_path: Some(storage_path),
+            storage: None,
         }
     }
     
@@ -111,18 +188,42 @@ impl WalletManager {
     pub fn set_storage_path(&mut self, storage_path: String) {
         self.storage_path = Some(storage_path);
     }
+
+    /// Persist wallets through a storage instance that is already open
+    /// 
+    /// The node keeps its database open, so wallets are written through the same
+    /// instance rather than by reopening `storage_path`.
+    /// 
+    /// # Arguments
+    /// * `storage` - Storage shared with the node
+    pub fn set_shared_storage(&mut self, storage: Arc<crate::storage::BlockchainStorage>) {
+        self.storage = Some(storage);
+    }
     
     /// Create a new wallet
     /// 
     /// # Arguments
     /// * `password` - Password to encrypt the wallet
     /// * `name` - Optional wallet name
+    /// * `kdf_params` - Optional key derivation parameters, defaulting to `KdfParams::default()`
     /// 
     /// # Returns
     /// * `Result<WalletInfo>` - The created wallet info
-    pub fn create_wallet(&mut self, password: &str, name: Option<String>) -> Result<WalletInfo> {
-        // Generate new key pair
-        let keypair = KeyPair::generate()?;
+    pub fn create_wallet(&mut self, password: &str, name: Option<String>, kdf_params: Option<KdfParams>) -> Result<WalletInfo> {
+        self.store_keypair(KeyPair::generate()?, password, name, kdf_params)
+    }
+
+    /// Store an existing key pair as an encrypted wallet
+    /// 
+    /// # Arguments
+    /// * `keypair` - Key pair to store
+    /// * `password` - Password to encrypt the wallet
+    /// * `name` - Optional wallet name
+    /// * `kdf_params` - Optional key derivation parameters, defaulting to `KdfParams::default()`
+    /// 
+    /// # Returns
+    /// * `Result<WalletInfo>` - The stored wallet info
+    pub fn store_keypair(&mut self, keypair: KeyPair, password: &str, name: Option<String>, kdf_params: Option<KdfParams>) -> Result<WalletInfo> {
         let public_key = keypair.public_key();
         let address = create_address(&public_key);
         
@@ -134,13 +235,15 @@ impl WalletManager {
             private_key: keypair.private_key_hex(),
             public_key: keypair.public_key_hex(),
             name: name.unwrap_or_else(|| format!("Wallet-{}", &id[..8])),
+            ethereum_private_key: None,
         };
         
         let serialized_data = serde_json::to_vec(&wallet_data)
             .map_err(|e| WalletError::Encryption(format!("Serialization failed: {}", e)))?;
         
         // Encrypt wallet data
-        let encrypted_wallet = self.encrypt_wallet_data(&serialized_data, password, &id)?;
+        let kdf_params = kdf_params.unwrap_or_default();
+        let encrypted_wallet = self.encrypt_wallet_data(&serialized_data, password, &id, &address, kdf_params)?;
         
         // Create wallet info
         let wallet_info = WalletInfo {
@@ -154,12 +257,7 @@ impl WalletManager {
         
         // Store wallet
         self.wallets.insert(address.clone(), encrypted_wallet);
-        
-        // Save to storage if available (skip in API context to avoid database locks)
-        if let Some(ref _storage_path) = self.storage_path {
-            // Skip storage operations in API context to avoid database locks
-            // TODO: Implement shared storage instance
-        }
+        self.persist_wallet(&address)?;
         
         info!("Created new wallet: {}", address);
         Ok(wallet_info)
@@ -174,17 +272,12 @@ impl WalletManager {
     /// # Returns
     /// * `Result<WalletInfo>` - The wallet info
     pub fn load_wallet(&mut self, address: &str, password: &str) -> Result<WalletInfo> {
-        // Try to get from memory first
+        // Try memory first, then the shared storage
+        self.ensure_loaded(address)?;
         if let Some(encrypted_wallet) = self.wallets.get(address) {
             return self.decrypt_wallet_info(encrypted_wallet, password);
         }
         
-        // Try to load from storage (skip in API context to avoid database locks)
-        if let Some(ref _storage_path) = self.storage_path {
-            // Skip storage operations in API context to avoid database locks
-            // TODO: Implement shared storage instance
-        }
-        
         Err(WalletError::WalletNotFound(address.to_string()).into())
     }
     
@@ -217,6 +310,18 @@ impl WalletManager {
         Ok(wallet_infos)
     }
     
+    /// Get the password strength recorded for each wallet
+    /// 
+    /// # Returns
+    /// * `Vec<(String, Option<u32>)>` - Wallet addresses with their strength in bits, sorted by address
+    pub fn password_strengths(&self) -> Vec<(String, Option<u32>)> {
+        let mut strengths: Vec<(String, Option<u32>)> = self.wallets.iter()
+            .map(|(address, wallet)| (address.clone(), wallet.password_strength))
+            .collect();
+        strengths.sort();
+        strengths
+    }
+    
     /// Sign a transaction with a wallet
     /// 
     /// # Arguments
@@ -293,6 +398,53 @@ impl WalletManager {
         Ok(private_key_bytes)
     }
 
+    /// Get the wallet's Ethereum signing key, generating one on first use
+    /// 
+    /// The key is a separate secp256k1 key kept encrypted with the wallet; it is never
+    /// derived from the wallet's Ed25519 key.
+    /// 
+    /// # Arguments
+    /// * `address` - Wallet address
+    /// * `password` - Wallet password
+    /// 
+    /// # Returns
+    /// * `Result<Vec<u8>>` - Ethereum private key bytes
+    pub fn get_ethereum_private_key(&mut self, address: &str, password: &str) -> Result<Vec<u8>> {
+        let mut wallet_data = self.get_wallet_data(address, password)?;
+        if let Some(key) = &wallet_data.ethereum_private_key {
+            return crate::utils::hex_to_bytes(key);
+        }
+
+        let signer = ethers::signers::LocalWallet::new(&mut rand::thread_rng());
+        let key = signer.signer().to_bytes().to_vec();
+        wallet_data.ethereum_private_key = Some(hex::encode(&key));
+        self.update_wallet_data(address, password, &wallet_data)?;
+
+        info!("Generated Ethereum key for wallet: {}", address);
+        Ok(key)
+    }
+
+    /// Import an existing Ethereum signing key into a wallet, replacing any previous one
+    /// 
+    /// # Arguments
+    /// * `address` - Wallet address
+    /// * `password` - Wallet password
+    /// * `private_key` - secp256k1 private key bytes
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if the key was stored
+    pub fn import_ethereum_key(&mut self, address: &str, password: &str, private_key: &[u8]) -> Result<()> {
+        ethers::signers::LocalWallet::from_bytes(private_key)
+            .map_err(|e| WalletError::KeyGeneration(format!("Invalid Ethereum key: {}", e)))?;
+
+        let mut wallet_data = self.get_wallet_data(address, password)?;
+        wallet_data.ethereum_private_key = Some(hex::encode(private_key));
+        self.update_wallet_data(address, password, &wallet_data)?;
+
+        info!("Imported Ethereum key for wallet: {}", address);
+        Ok(())
+    }
+
     /// Get wallet address
     /// 
     /// # Arguments
@@ -336,8 +488,10 @@ impl WalletManager {
     pub fn import_wallet(&mut self, encrypted_data: &[u8]) -> Result<WalletInfo> {
         let encrypted_wallet: EncryptedWallet = serde_json::from_slice(encrypted_data)?;
         
-        // Verify we can decrypt it (test with empty password)
-        let _ = self.decrypt_wallet_data(&encrypted_wallet.encrypted_data, &encrypted_wallet.salt, &encrypted_wallet.nonce, "")?;
+        // Decryption needs the password, so only check that the stored KDF parameters are usable
+        if let Some(kdf_params) = &encrypted_wallet.kdf_params {
+            kdf_params.argon2()?;
+        }
         
         self.wallets.insert(encrypted_wallet.address.clone(), encrypted_wallet.clone());
         
@@ -376,7 +530,9 @@ impl WalletManager {
         self.wallets.remove(address);
         
         // Remove from storage
-        if let Some(ref storage_path) = self.storage_path {
+        if let Some(storage) = &self.storage {
+            storage.save_wallet(address, &[])?;
+        } else if let Some(ref storage_path) = self.storage_path {
             let storage = crate::storage::BlockchainStorage::new(storage_path)?;
             // Note: sled doesn't have a delete method, we'll overwrite with empty data
             storage.save_wallet(address, &[])?;
@@ -388,7 +544,7 @@ impl WalletManager {
     
     // Private helper methods
     
-    fn encrypt_wallet_data(&self, data: &[u8], password: &str, wallet_id: &str) -> Result<EncryptedWallet> {
+    fn encrypt_wallet_data(&self, data: &[u8], password: &str, wallet_id: &str, address: &str, kdf_params: KdfParams) -> Result<EncryptedWallet> {
         // Generate salt and nonce
         let mut salt = [0u8; 32];
         let mut nonce_bytes = [0u8; 12];
@@ -396,7 +552,7 @@ impl WalletManager {
         rand::thread_rng().fill(&mut nonce_bytes);
         
         // Derive key from password and salt
-        let key = self.derive_key(password, &salt)?;
+        let key = self.derive_key(password, &salt, Some(&kdf_params))?;
         let cipher = Aes256Gcm::new_from_slice(&key)
             .map_err(|e| WalletError::Encryption(format!("Failed to create cipher: {}", e)))?;
         
@@ -406,37 +562,47 @@ impl WalletManager {
         let encrypted_data = cipher.encrypt(nonce, data)
             .map_err(|e| WalletError::Encryption(format!("Encryption failed: {}", e)))?;
         
-        let address = self.get_address_from_wallet_id(wallet_id)?;
-        
         Ok(EncryptedWallet {
             id: wallet_id.to_string(),
-            address,
+            address: address.to_string(),
             encrypted_data,
             salt: salt.to_vec(),
             nonce: nonce_bytes.to_vec(),
+            kdf_params: Some(kdf_params),
+            password_strength: Some(password_strength(password)),
             created_at: chrono::Utc::now(),
             last_accessed: chrono::Utc::now(),
         })
     }
     
-    fn decrypt_wallet_data(&self, encrypted_data: &[u8], salt: &[u8], nonce: &[u8], password: &str) -> Result<Vec<u8>> {
-        // Derive key from password and salt
-        let key = self.derive_key(password, salt)?;
+    fn decrypt_wallet_data(&self, encrypted_wallet: &EncryptedWallet, password: &str) -> Result<Vec<u8>> {
+        // Derive key with the parameters stored alongside the wallet
+        let key = self.derive_key(password, &encrypted_wallet.salt, encrypted_wallet.kdf_params.as_ref())?;
         let cipher = Aes256Gcm::new_from_slice(&key)
             .map_err(|e| WalletError::Decryption(format!("Failed to create cipher: {}", e)))?;
         
-        let nonce = Nonce::from_slice(nonce);
+        let nonce = Nonce::from_slice(&encrypted_wallet.nonce);
         
         // Decrypt data
-        let decrypted_data = cipher.decrypt(nonce, encrypted_data)
+        let decrypted_data = cipher.decrypt(nonce, encrypted_wallet.encrypted_data.as_slice())
             .map_err(|e| WalletError::Decryption(format!("Decryption failed: {}", e)))?;
         
         Ok(decrypted_data)
     }
     
-    fn derive_key(&self, password: &str, salt: &[u8]) -> Result<Vec<u8>> {
-        // Simple key derivation using PBKDF2-like approach
-        // In production, use a proper KDF like Argon2
+    fn derive_key(&self, password: &str, salt: &[u8], kdf_params: Option<&KdfParams>) -> Result<Vec<u8>> {
+        if let Some(kdf_params) = kdf_params {
+            let mut key = vec![0u8; 32];
+            kdf_params.argon2()?
+                .hash_password_into(password.as_bytes(), salt, &mut key)
+                .map_err(|e| WalletError::Encryption(format!("Key derivation failed: {}", e)))?;
+            return Ok(key);
+        }
+        
+        // Legacy derivation for wallets stored without KDF parameters
+        if password.is_empty() {
+            return Err(WalletError::InvalidPassword.into());
+        }
         let mut key = vec![0u8; 32];
         let password_bytes = password.as_bytes();
         
@@ -452,15 +618,11 @@ impl WalletManager {
     }
     
     fn get_wallet_data(&mut self, address: &str, password: &str) -> Result<WalletData> {
+        self.ensure_loaded(address)?;
         let encrypted_wallet = self.wallets.get(address)
             .ok_or_else(|| WalletError::WalletNotFound(address.to_string()))?;
         
-        let decrypted_data = self.decrypt_wallet_data(
-            &encrypted_wallet.encrypted_data,
-            &encrypted_wallet.salt,
-            &encrypted_wallet.nonce,
-            password,
-        )?;
+        let decrypted_data = self.decrypt_wallet_data(encrypted_wallet, password)?;
         
         let wallet_data: WalletData = serde_json::from_slice(&decrypted_data)
             .map_err(|_| WalletError::InvalidWalletData)?;
@@ -468,13 +630,45 @@ impl WalletManager {
         Ok(wallet_data)
     }
     
+    fn update_wallet_data(&mut self, address: &str, password: &str, wallet_data: &WalletData) -> Result<()> {
+        let existing = self.wallets.get(address)
+            .ok_or_else(|| WalletError::WalletNotFound(address.to_string()))?;
+        
+        let serialized_data = serde_json::to_vec(wallet_data)
+            .map_err(|e| WalletError::Encryption(format!("Serialization failed: {}", e)))?;
+        let kdf_params = existing.kdf_params.unwrap_or_default();
+        let mut encrypted_wallet = self.encrypt_wallet_data(&serialized_data, password, &existing.id, address, kdf_params)?;
+        encrypted_wallet.created_at = existing.created_at;
+        
+        self.wallets.insert(address.to_string(), encrypted_wallet);
+        self.persist_wallet(address)
+    }
+    
+    /// Write a wallet to the shared storage, if one is set
+    fn persist_wallet(&self, address: &str) -> Result<()> {
+        if let (Some(storage), Some(encrypted_wallet)) = (&self.storage, self.wallets.get(address)) {
+            storage.save_wallet(address, &serde_json::to_vec(encrypted_wallet)?)?;
+        }
+        Ok(())
+    }
+    
+    /// Load a wallet from the shared storage if it is not in memory yet
+    fn ensure_loaded(&mut self, address: &str) -> Result<()> {
+        if self.wallets.contains_key(address) {
+            return Ok(());
+        }
+        if let Some(storage) = &self.storage {
+            // Deleted wallets are left behind as empty entries
+            if let Some(data) = storage.load_wallet(address)?.filter(|data| !data.is_empty()) {
+                let encrypted_wallet: EncryptedWallet = serde_json::from_slice(&data)?;
+                self.wallets.insert(address.to_string(), encrypted_wallet);
+            }
+        }
+        Ok(())
+    }
+    
     fn decrypt_wallet_info(&self, encrypted_wallet: &EncryptedWallet, password: &str) -> Result<WalletInfo> {
-        let decrypted_data = self.decrypt_wallet_data(
-            &encrypted_wallet.encrypted_data,
-            &encrypted_wallet.salt,
-            &encrypted_wallet.nonce,
-            password,
-        )?;
+        let decrypted_data = self.decrypt_wallet_data(encrypted_wallet, password)?;
         
         let wallet_data: WalletData = serde_json::from_slice(&decrypted_data)
             .map_err(|_| WalletError::InvalidWalletData)?;
@@ -489,18 +683,12 @@ impl WalletManager {
         })
     }
     
-    fn get_address_from_wallet_id(&self, wallet_id: &str) -> Result<String> {
-        // This is a simplified approach - in a real implementation,
-        // you'd need to store the address with the wallet data
-        // For now, we'll generate a placeholder
-        Ok(format!("wallet_{}", &wallet_id[..8]))
-    }
-    
     /// Clone the wallet manager for background operations
     pub fn clone_for_background(&self) -> Self {
         WalletManager {
             wallets: self.wallets.clone(),
             storage_path: self.storage_path.clone(),
+            storage: self.storage.clone(),
         }
     }
 }
@@ -511,6 +699,9 @@ struct WalletData {
     private_key: String,
     public_key: String,
     name: String,
+    /// Hex-encoded secp256k1 key used for Ethereum transfers, created on first use
+    #[serde(default)]
+    ethereum_private_key: Option<String>,
 }
 
 #[cfg(test)]
@@ -521,7 +712,7 @@ mod tests {
     #[test]
     fn test_wallet_creation() {
         let mut wallet_manager = WalletManager::new();
-        let wallet_info = wallet_manager.create_wallet("test_password", Some("Test Wallet".to_string())).unwrap();
+        let wallet_info = wallet_manager.create_wallet("test_password", Some("Test Wallet".to_string()), None).unwrap();
         
         assert!(!wallet_info.address.is_empty());
         assert!(!wallet_info.public_key.is_empty());
@@ -531,7 +722,7 @@ mod tests {
     #[test]
     fn test_wallet_encryption_decryption() {
         let mut wallet_manager = WalletManager::new();
-        let wallet_info = wallet_manager.create_wallet("test_password", None).unwrap();
+        let wallet_info = wallet_manager.create_wallet("test_password", None, None).unwrap();
         
         // Try to load the wallet from the same manager instance (should be in memory)
         let loaded_info = wallet_manager.load_wallet(&wallet_info.address, "test_password").unwrap();
@@ -546,7 +737,7 @@ mod tests {
         
         let mut wallet_manager = WalletManager::with_storage(storage_path.clone());
         
-        let wallet_info = wallet_manager.create_wallet("test_password", None).unwrap();
+        let wallet_info = wallet_manager.create_wallet("test_password", None, None).unwrap();
         
         // Since storage operations are not yet implemented (TODO), 
         // we'll test that the wallet can be loaded from the same manager instance
@@ -558,11 +749,31 @@ mod tests {
         // Test that the wallet is in memory
         assert!(wallet_manager.wallets.contains_key(&wallet_info.address));
     }
-    
+
+    #[test]
+    fn test_wallet_with_shared_storage() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Arc::new(crate::storage::BlockchainStorage::new(temp_dir.path()).unwrap());
+
+        let mut wallet_manager = WalletManager::new();
+        wallet_manager.set_shared_storage(storage.clone());
+        let wallet_info = wallet_manager.create_wallet("test_password", None, None).unwrap();
+        let private_key = wallet_manager.get_private_key_bytes(&wallet_info.address, "test_password").unwrap();
+
+        // A fresh manager on the same storage finds the wallet after a restart
+        let mut restarted = WalletManager::new();
+        restarted.set_shared_storage(storage);
+        assert_eq!(restarted.get_private_key_bytes(&wallet_info.address, "test_password").unwrap(), private_key);
+        assert!(restarted.load_wallet(&wallet_info.address, "wrong_password").is_err());
+
+        restarted.delete_wallet(&wallet_info.address, "test_password").unwrap();
+        assert!(restarted.load_wallet(&wallet_info.address, "test_password").is_err());
+    }
+
     #[test]
     fn test_wallet_signing() {
         let mut wallet_manager = WalletManager::new();
-        let wallet_info = wallet_manager.create_wallet("test_password", None).unwrap();
+        let wallet_info = wallet_manager.create_wallet("test_password", None, None).unwrap();
         
         let transaction_data = b"test transaction data";
         let signature = wallet_manager.sign_transaction(&wallet_info.address, "test_password", transaction_data).unwrap();
@@ -579,4 +790,78 @@ mod tests {
         use ed25519_dalek::{Verifier, VerifyingKey};
         assert!(verifying_key.verify(transaction_data, &ed25519_signature).is_ok());
     }
+
+    #[test]
+    fn test_wallet_custom_kdf_params_survive_reload() {
+        let kdf_params = KdfParams { memory_kib: 32 * 1024, iterations: 3, parallelism: 2 };
+        let mut wallet_manager = WalletManager::new();
+        let wallet_info = wallet_manager.create_wallet("test_password", None, Some(kdf_params)).unwrap();
+        let private_key = wallet_manager.get_private_key_bytes(&wallet_info.address, "test_password").unwrap();
+
+        let exported = wallet_manager.export_wallet(&wallet_info.address).unwrap();
+        let stored: EncryptedWallet = serde_json::from_slice(&exported).unwrap();
+        assert_eq!(stored.kdf_params, Some(kdf_params));
+        assert_eq!(stored.address, wallet_info.address);
+
+        let mut reloaded = WalletManager::new();
+        reloaded.import_wallet(&exported).unwrap();
+        let loaded_info = reloaded.load_wallet(&wallet_info.address, "test_password").unwrap();
+        assert_eq!(loaded_info.public_key, wallet_info.public_key);
+        assert_eq!(reloaded.get_private_key_bytes(&wallet_info.address, "test_password").unwrap(), private_key);
+        assert!(reloaded.load_wallet(&wallet_info.address, "wrong_password").is_err());
+
+        // Deriving with the default parameters instead of the stored ones must not decrypt
+        let mut tampered = stored.clone();
+        tampered.kdf_params = Some(KdfParams::default());
+        assert!(reloaded.decrypt_wallet_data(&tampered, "test_password").is_err());
+    }
+
+    #[test]
+    fn test_wallet_ethereum_key_is_separate() {
+        let mut wallet_manager = WalletManager::new();
+        let address = wallet_manager.create_wallet("test_password", None, None).unwrap().address;
+        let private_key = wallet_manager.get_private_key_bytes(&address, "test_password").unwrap();
+
+        let ethereum_key = wallet_manager.get_ethereum_private_key(&address, "test_password").unwrap();
+        assert_ne!(ethereum_key, private_key);
+        assert_eq!(wallet_manager.get_ethereum_private_key(&address, "test_password").unwrap(), ethereum_key);
+        assert!(wallet_manager.get_ethereum_private_key(&address, "wrong_password").is_err());
+        // Storing the key re-encrypts the wallet without touching its own key
+        assert_eq!(wallet_manager.get_private_key_bytes(&address, "test_password").unwrap(), private_key);
+
+        let imported = [7u8; 32];
+        wallet_manager.import_ethereum_key(&address, "test_password", &imported).unwrap();
+        assert_eq!(wallet_manager.get_ethereum_private_key(&address, "test_password").unwrap(), imported);
+        assert!(wallet_manager.import_ethereum_key(&address, "test_password", &[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn test_ethereum_key_survives_restart() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Arc::new(crate::storage::BlockchainStorage::new(temp_dir.path()).unwrap());
+
+        let mut wallet_manager = WalletManager::new();
+        wallet_manager.set_shared_storage(storage.clone());
+        let address = wallet_manager.create_wallet("test_password", None, None).unwrap().address;
+        let ethereum_key = wallet_manager.get_ethereum_private_key(&address, "test_password").unwrap();
+
+        let mut restarted = WalletManager::new();
+        restarted.set_shared_storage(storage);
+        assert_eq!(restarted.get_ethereum_private_key(&address, "test_password").unwrap(), ethereum_key);
+    }
+
+    #[test]
+    fn test_password_strength() {
+        assert_eq!(password_strength(""), 0);
+        assert!(password_strength("abc123") < password_strength("abc123abc123"));
+        assert!(password_strength("abcdefgh") < password_strength("abcdEFG1"));
+        assert_eq!(password_strength("aaaa"), 18);
+    }
+
+    #[test]
+    fn test_wallet_rejects_invalid_kdf_params() {
+        let mut wallet_manager = WalletManager::new();
+        let kdf_params = KdfParams { memory_kib: 1, iterations: 0, parallelism: 0 };
+        assert!(wallet_manager.create_wallet("test_password", None, Some(kdf_params)).is_err());
+    }
 }
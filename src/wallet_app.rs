@@ -327,11 +327,12 @@ impl WalletApp {
         })
     }
     
-    /// Send transaction
+    /// Send a transaction signed with the session wallet's key
     pub async fn send_transaction(
         &self,
         session_id: &str,
         request: TransactionRequest,
+        signer: &crate::crypto::KeyPair,
     ) -> Result<TransactionResponse> {
         let session = self.get_session(session_id).await?;
         let address = &session.wallet_address;
@@ -342,25 +343,25 @@ impl WalletApp {
                 "Insufficient permissions to send transactions".to_string()
             ));
         }
+        if crate::crypto::create_address(&signer.public_key()) != *address {
+            return Err(crate::error::BlockchainError::InvalidInput(
+                "Signing key does not belong to the session wallet".to_string()
+            ));
+        }
         
-        // Create transaction
-        let memo_clone = request.memo.clone();
-        let transaction = Transaction::new_transfer(
-            address.clone(),
-            request.to.clone(),
-            request.amount,
-            request.memo,
-        )?;
-        
-        // Submit transaction (simplified)
+        // Submit the signed transfer
         let mut blockchain = self.blockchain.write().await;
-        let tx_hash = transaction.id.clone();
         blockchain.add_transaction(
-            address.clone(),
+            signer,
             request.to.clone(),
-            request.amount,
-            memo_clone,
+            crate::units::to_base_units(request.amount)?,
+            request.memo,
         )?;
+        let transaction = blockchain.pending_transactions.last().cloned()
+            .ok_or_else(|| crate::error::BlockchainError::InvalidState(
+                "Submitted transaction is not pending".to_string()
+            ))?;
+        let tx_hash = transaction.id.clone();
         
         // Create transaction record
         let transaction_record = TransactionRecord {
@@ -549,33 +550,29 @@ impl WalletApp {
         let mut transactions = Vec::new();
         
         // Load transactions from blockchain
-        for (height, block) in blockchain.blocks.iter().enumerate() {
-            for tx in &block.transactions {
-                if tx.sender == address || tx.receiver == address {
-                    let direction = if tx.sender == address {
-                        TransactionDirection::Sent
-                    } else {
-                        TransactionDirection::Received
-                    };
-                    
-                    let counterparty = if tx.sender == address {
-                        tx.receiver.clone()
-                    } else {
-                        tx.sender.clone()
-                    };
-                    
-                    transactions.push(TransactionRecord {
-                        transaction: tx.clone(),
-                        block_height: Some(height as u64),
-                        confirmations: blockchain.blocks.len() as u64 - height as u64,
-                        status: TransactionStatus::Confirmed,
-                        timestamp: block.timestamp as u64,
-                        direction,
-                        counterparty,
-                    });
-                        }
-    }
-}
+        for (height, tx) in blockchain.transaction_history(address) {
+            let direction = if tx.sender == address {
+                TransactionDirection::Sent
+            } else {
+                TransactionDirection::Received
+            };
+            
+            let counterparty = if tx.sender == address {
+                tx.receiver.clone()
+            } else {
+                tx.sender.clone()
+            };
+            
+            transactions.push(TransactionRecord {
+                transaction: tx.clone(),
+                block_height: Some(height),
+                confirmations: blockchain.blocks.len() as u64 - height,
+                status: TransactionStatus::Confirmed,
+                timestamp: blockchain.blocks[height as usize].timestamp as u64,
+                direction,
+                counterparty,
+            });
+        }
         
         // Sort by timestamp
         transactions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
@@ -2,6 +2,7 @@ use crate::{Result, crypto::{KeyPair, PublicKey}};
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest as ShaDigest};
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 use log::{info, warn};
 
 /// Zero-knowledge proof for private transactions
@@ -39,14 +40,30 @@ pub struct PrivateTransaction {
 /// ZKP manager for generating and verifying proofs
 pub struct ZKPManager {
     /// Cache of generated proofs
-    proof_cache: HashMap<String, ZKProof>,
+    proof_cache: Arc<RwLock<HashMap<String, ZKProof>>>,
+}
+
+impl ZKProof {
+    /// Get the proof identifier (hex-encoded SHA-256 of the proof data)
+    pub fn id(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.proof_data);
+        hex::encode(hasher.finalize())
+    }
 }
 
 impl ZKPManager {
     /// Create a new ZKP manager
     pub fn new() -> Self {
         Self {
-            proof_cache: HashMap::new(),
+            proof_cache: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Clone the ZKP manager for background operations
+    pub fn clone_for_background(&self) -> Self {
+        Self {
+            proof_cache: self.proof_cache.clone(),
         }
     }
 
@@ -66,7 +83,7 @@ impl ZKPManager {
         );
 
         // Check cache first
-        if let Some(cached_proof) = self.proof_cache.get(&proof_id) {
+        if let Some(cached_proof) = self.proof_cache.read().unwrap().get(&proof_id) {
             info!("Using cached ZKP for transaction");
             return Ok(cached_proof.clone());
         }
@@ -100,7 +117,7 @@ impl ZKPManager {
         };
 
         // Cache the proof
-        self.proof_cache.insert(proof_id, proof.clone());
+        self.proof_cache.write().unwrap().insert(proof_id, proof.clone());
 
         info!("ZKP generated successfully");
         Ok(proof)
@@ -288,7 +305,7 @@ impl ZKPManager {
     /// Get proof generation statistics
     pub fn get_stats(&self) -> ZKPStats {
         ZKPStats {
-            total_proofs_generated: self.proof_cache.len(),
+            total_proofs_generated: self.proof_cache.read().unwrap().len(),
             cache_hit_rate: 0.0, // Would calculate this in a real implementation
         }
     }
@@ -1,4 +1,4 @@
-use gillean::{BlockchainError, Transaction};
+use gillean::{BlockchainError, Transaction, units};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
@@ -153,7 +153,7 @@ impl AnomalyDetector {
     }
 
     pub fn analyze_transaction(&mut self, transaction: &Transaction) -> AnomalyScore {
-        let amount = transaction.amount;
+        let amount = units::from_base_units(transaction.amount);
         let avg_amount = self.baseline_stats.get("avg_amount").unwrap_or(&100.0);
         let std_amount = self.baseline_stats.get("std_amount").unwrap_or(&50.0);
         
@@ -183,11 +183,11 @@ impl AnomalyDetector {
             return;
         }
         
-        let total_amount: f64 = new_transactions.iter().map(|tx| tx.amount).sum();
+        let total_amount: f64 = new_transactions.iter().map(|tx| units::from_base_units(tx.amount)).sum();
         let avg_amount = total_amount / new_transactions.len() as f64;
         
         let variance: f64 = new_transactions.iter()
-            .map(|tx| (tx.amount - avg_amount).powi(2))
+            .map(|tx| (units::from_base_units(tx.amount) - avg_amount).powi(2))
             .sum::<f64>() / new_transactions.len() as f64;
         let std_amount = variance.sqrt();
         
@@ -233,7 +233,7 @@ impl FraudDetector {
         let mut risk_factors = Vec::new();
         
         // Amount-based risk
-        if transaction.amount > 10000.0 {
+        if transaction.amount > units::gil(10000) {
             risk_score += self.risk_weights.get("amount").unwrap_or(&0.3);
             risk_factors.push("High transaction amount".to_string());
         }
@@ -326,14 +326,14 @@ impl AIIntegrationSuite {
         let _normal_tx = Transaction::new_transfer(
             "sender1".to_string(),
             "receiver1".to_string(),
-            100.0,
+            units::gil(100),
             Some("normal transaction".to_string()),
         )?;
         
         let large_tx = Transaction::new_transfer(
             "sender2".to_string(),
             "receiver2".to_string(),
-            50000.0,
+            units::gil(50000),
             Some("large transaction".to_string()),
         )?;
         
@@ -352,14 +352,14 @@ impl AIIntegrationSuite {
         let _normal_tx = Transaction::new_transfer(
             "sender1".to_string(),
             "receiver1".to_string(),
-            100.0,
+            units::gil(100),
             Some("normal transaction".to_string()),
         )?;
         
         let suspicious_tx = Transaction::new_transfer(
             "sender2".to_string(),
             "receiver2".to_string(),
-            15000.0,
+            units::gil(15000),
             Some("suspicious transaction".to_string()),
         )?;
         
@@ -376,9 +376,9 @@ impl AIIntegrationSuite {
         println!("  Testing model training...");
         
         let training_data = vec![
-            Transaction::new_transfer("sender1".to_string(), "receiver1".to_string(), 100.0, Some("tx1".to_string()))?,
-            Transaction::new_transfer("sender2".to_string(), "receiver2".to_string(), 200.0, Some("tx2".to_string()))?,
-            Transaction::new_transfer("sender3".to_string(), "receiver3".to_string(), 150.0, Some("tx3".to_string()))?,
+            Transaction::new_transfer("sender1".to_string(), "receiver1".to_string(), units::gil(100), Some("tx1".to_string()))?,
+            Transaction::new_transfer("sender2".to_string(), "receiver2".to_string(), units::gil(200), Some("tx2".to_string()))?,
+            Transaction::new_transfer("sender3".to_string(), "receiver3".to_string(), units::gil(150), Some("tx3".to_string()))?,
         ];
         
         let model = self._manager.train_model("fraud_detection_v1", &training_data)?;
@@ -397,7 +397,7 @@ impl AIIntegrationSuite {
         let tx = Transaction::new_transfer(
             "sender1".to_string(),
             "receiver1".to_string(),
-            1000.0,
+            units::gil(1000),
             Some("test transaction".to_string()),
         )?;
         
@@ -423,7 +423,7 @@ impl AIIntegrationSuite {
             let tx = Transaction::new_transfer(
                 format!("sender{}", i),
                 format!("receiver{}", i),
-                100.0 + (i as f64 * 10.0),
+                units::gil(100 + i * 10),
                 Some(format!("tx{}", i)),
             )?;
             self._manager.update_transaction_history(tx);
@@ -445,8 +445,8 @@ impl AIIntegrationSuite {
         
         // Simulate new transaction data
         let new_transactions = vec![
-            Transaction::new_transfer("sender1".to_string(), "receiver1".to_string(), 120.0, Some("new1".to_string()))?,
-            Transaction::new_transfer("sender2".to_string(), "receiver2".to_string(), 130.0, Some("new2".to_string()))?,
+            Transaction::new_transfer("sender1".to_string(), "receiver1".to_string(), units::gil(120), Some("new1".to_string()))?,
+            Transaction::new_transfer("sender2".to_string(), "receiver2".to_string(), units::gil(130), Some("new2".to_string()))?,
         ];
         
         // Update baseline with new data
@@ -473,7 +473,7 @@ impl AIIntegrationSuite {
         let invalid_tx = Transaction::new_transfer(
             "".to_string(),
             "".to_string(),
-            -100.0,
+            0,
             Some("invalid".to_string()),
         )?;
         
@@ -517,7 +517,7 @@ mod tests {
         let transaction = Transaction::new_transfer(
             "alice".to_string(),
             "bob".to_string(),
-            100.0,
+            units::gil(100),
             Some("test transaction".to_string()),
         ).unwrap();
         
@@ -532,7 +532,7 @@ mod tests {
         let transaction = Transaction::new_transfer(
             "alice".to_string(),
             "bob".to_string(),
-            100.0,
+            units::gil(100),
             Some("test transaction".to_string()),
         ).unwrap();
         
@@ -544,8 +544,8 @@ mod tests {
     fn test_model_training() {
         let manager = AIManager::new();
         let training_data = vec![
-            Transaction::new_transfer("sender1".to_string(), "receiver1".to_string(), 100.0, Some("tx1".to_string())).unwrap(),
-            Transaction::new_transfer("sender2".to_string(), "receiver2".to_string(), 200.0, Some("tx2".to_string())).unwrap(),
+            Transaction::new_transfer("sender1".to_string(), "receiver1".to_string(), units::gil(100), Some("tx1".to_string())).unwrap(),
+            Transaction::new_transfer("sender2".to_string(), "receiver2".to_string(), units::gil(200), Some("tx2".to_string())).unwrap(),
         ];
         
         let result = manager.train_model("test_model", &training_data);
@@ -0,0 +1,14 @@
+use gillean::blockchain::Blockchain;
+use gillean::monitor::BlockchainMonitor;
+use gillean::network::Network;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Start a network node on an ephemeral port serving the given chain
+pub async fn start_node(blockchain: Blockchain) -> (Network, Arc<Mutex<Blockchain>>) {
+    let blockchain = Arc::new(Mutex::new(blockchain));
+    let monitor = Arc::new(Mutex::new(BlockchainMonitor::new()));
+    let mut network = Network::new("127.0.0.1:0".to_string(), Arc::clone(&blockchain), monitor).unwrap();
+    network.start().await.unwrap();
+    (network, blockchain)
+}
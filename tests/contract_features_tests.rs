@@ -1,4 +1,5 @@
 use gillean::blockchain::Blockchain;
+use gillean::units;
 
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -435,7 +436,7 @@ pub struct ContractFeaturesSuite {
 
 impl ContractFeaturesSuite {
     pub fn new() -> Result<Self, String> {
-        let blockchain = Blockchain::new_pos(10.0, 100.0, 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
+        let blockchain = Blockchain::new_pos(10.0, units::gil(100), 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
         let manager = ContractManager::new(blockchain);
         
         Ok(Self {
@@ -446,7 +447,7 @@ impl ContractFeaturesSuite {
     pub async fn test_contract_deployment() -> Result<(), String> {
         println!("🧪 Testing contract deployment...");
         
-        let blockchain = Blockchain::new_pos(10.0, 100.0, 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
+        let blockchain = Blockchain::new_pos(10.0, units::gil(100), 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
         let mut manager = ContractManager::new(blockchain);
 
         // Test standard contract deployment
@@ -488,7 +489,7 @@ impl ContractFeaturesSuite {
     pub async fn test_library_management() -> Result<(), String> {
         println!("🧪 Testing library management...");
         
-        let blockchain = Blockchain::new_pos(10.0, 100.0, 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
+        let blockchain = Blockchain::new_pos(10.0, units::gil(100), 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
         let mut manager = ContractManager::new(blockchain);
 
         // Create library functions
@@ -550,7 +551,7 @@ impl ContractFeaturesSuite {
     pub async fn test_contract_upgrades() -> Result<(), String> {
         println!("🧪 Testing contract upgrades...");
         
-        let blockchain = Blockchain::new_pos(10.0, 100.0, 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
+        let blockchain = Blockchain::new_pos(10.0, units::gil(100), 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
         let mut manager = ContractManager::new(blockchain);
 
         // Deploy upgradeable contract
@@ -615,7 +616,7 @@ impl ContractFeaturesSuite {
     pub async fn test_proxy_contracts() -> Result<(), String> {
         println!("🧪 Testing proxy contracts...");
         
-        let blockchain = Blockchain::new_pos(10.0, 100.0, 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
+        let blockchain = Blockchain::new_pos(10.0, units::gil(100), 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
         let mut manager = ContractManager::new(blockchain);
 
         // Deploy implementation contract
@@ -677,7 +678,7 @@ impl ContractFeaturesSuite {
     pub async fn test_gas_optimization() -> Result<(), String> {
         println!("🧪 Testing gas optimization...");
         
-        let blockchain = Blockchain::new_pos(10.0, 100.0, 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
+        let blockchain = Blockchain::new_pos(10.0, units::gil(100), 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
         let mut manager = ContractManager::new(blockchain);
 
         // Deploy contract
@@ -735,7 +736,7 @@ impl ContractFeaturesSuite {
     pub async fn test_contract_inheritance() -> Result<(), String> {
         println!("🧪 Testing contract inheritance...");
         
-        let blockchain = Blockchain::new_pos(10.0, 100.0, 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
+        let blockchain = Blockchain::new_pos(10.0, units::gil(100), 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
         let mut manager = ContractManager::new(blockchain);
 
         // Deploy base contract
@@ -795,7 +796,7 @@ impl ContractFeaturesSuite {
     pub async fn test_invalid_operations() -> Result<(), String> {
         println!("🧪 Testing invalid operations...");
         
-        let blockchain = Blockchain::new_pos(10.0, 100.0, 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
+        let blockchain = Blockchain::new_pos(10.0, units::gil(100), 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
         let mut manager = ContractManager::new(blockchain);
 
         // Test upgrading non-upgradeable contract
@@ -852,7 +853,7 @@ impl ContractFeaturesSuite {
     pub async fn test_contract_lifecycle() -> Result<(), String> {
         println!("🧪 Testing complete contract lifecycle...");
         
-        let blockchain = Blockchain::new_pos(10.0, 100.0, 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
+        let blockchain = Blockchain::new_pos(10.0, units::gil(100), 21).map_err(|e| format!("Failed to create blockchain: {:?}", e))?;
         let mut manager = ContractManager::new(blockchain);
 
         // Phase 1: Deploy base library
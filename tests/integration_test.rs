@@ -1,7 +1,8 @@
 use gillean::{
     Blockchain, Result, Transaction, TransactionType, ConsensusType,
-    DEFAULT_GAS_LIMIT, DEFAULT_GAS_PRICE
+    DEFAULT_GAS_LIMIT, DEFAULT_GAS_PRICE, units
 };
+use gillean::crypto::{create_address, KeyPair};
 use gillean::smart_contract::examples;
 use tempfile::TempDir;
 
@@ -17,14 +18,14 @@ async fn test_smart_contract_deployment_and_execution() -> Result<()> {
     blockchain.add_transaction_object(Transaction::new_transfer(
         "COINBASE".to_string(),
         "alice".to_string(),
-        1000.0,
+        units::gil(1000),
         None,
     )?)?;
     
     blockchain.add_transaction_object(Transaction::new_transfer(
         "COINBASE".to_string(),
         "bob".to_string(),
-        1000.0,
+        units::gil(1000),
         None,
     )?)?;
     
@@ -57,7 +58,7 @@ RETURN
         "bob".to_string(),
         contract_address.clone(),
         "increment".to_string(),
-        1.0, // Small amount for the call
+        units::gil(1), // Small amount for the call
         DEFAULT_GAS_LIMIT,
         DEFAULT_GAS_PRICE,
     )?;
@@ -74,36 +75,51 @@ async fn test_proof_of_stake_consensus() -> Result<()> {
     let temp_dir = TempDir::new()?;
     let _db_path = temp_dir.path().join("test_db");
     
-    // Create a PoS blockchain
-    let mut blockchain = Blockchain::new_pos(50.0, 100.0, 10)?;
-    
-    // Register validators
-    blockchain.register_validator(
-        "validator1".to_string(),
-        "validator1".to_string(),
-        1000.0,
-    )?;
-    
-    blockchain.register_validator(
-        "validator2".to_string(),
-        "validator2".to_string(),
-        2000.0,
-    )?;
+    // Create a PoS blockchain whose genesis validator mines the registrations
+    let bootstrap = KeyPair::generate()?;
+    let mut blockchain = Blockchain::new_pos_with_validators(50.0, units::gil(100), 10, vec![
+        (bootstrap.public_key_hex(), "bootstrap".to_string(), units::gil(1000)),
+    ])?;
+    blockchain.add_validator_signer(bootstrap)?;
+    
+    // Fund the new validators
+    let keys = [KeyPair::generate()?, KeyPair::generate()?];
+    let addresses: Vec<String> = keys.iter().map(|keypair| create_address(&keypair.public_key())).collect();
+    for address in &addresses {
+        blockchain.add_transaction_object(Transaction::new_transfer(
+            "COINBASE".to_string(),
+            address.clone(),
+            units::gil(3000),
+            None,
+        )?)?;
+    }
+    blockchain.mine_block("bootstrap".to_string())?;
+    
+    // Register validators with transactions signed by their own keys
+    for ((keypair, address), stake) in keys.iter().zip(&addresses).zip([units::gil(1000), units::gil(2000)]) {
+        let mut registration = Transaction::new_validator_registration(address.clone(), stake)?;
+        registration.set_nonce(blockchain.next_nonce(address));
+        registration.sign(keypair)?;
+        blockchain.add_transaction_object(registration)?;
+    }
+    blockchain.mine_block("bootstrap".to_string())?;
     
-    // Verify validators were registered
+    // Verify validators were registered and their stakes debited
     let pos_stats = blockchain.get_pos_stats();
     assert!(pos_stats.is_some());
     
     let stats = pos_stats.unwrap();
-    assert_eq!(stats.get("total_validators").unwrap(), &2.0);
-    assert_eq!(stats.get("total_stake").unwrap(), &3000.0);
+    assert_eq!(stats.get("total_validators").unwrap(), &3.0);
+    assert_eq!(stats.get("total_stake").unwrap(), &4000.0);
+    assert_eq!(blockchain.get_balance(&addresses[0]), 2000.0);
+    assert_eq!(blockchain.get_balance(&addresses[1]), 1000.0);
     
     // Test validator selection
     let selected_validator = blockchain.select_validator();
     assert!(selected_validator.is_some());
     
     let validator = selected_validator.unwrap();
-    assert!(validator == "validator1" || validator == "validator2");
+    assert!(validator == "bootstrap" || addresses.contains(&validator));
     
     Ok(())
 }
@@ -116,12 +132,12 @@ async fn test_transaction_types() -> Result<()> {
     let transfer_tx = Transaction::new_transfer(
         "alice".to_string(),
         "bob".to_string(),
-        100.0,
+        units::gil(100),
         Some("Payment".to_string()),
     )?;
     
     assert_eq!(transfer_tx.transaction_type, TransactionType::Transfer);
-    assert_eq!(transfer_tx.amount, 100.0);
+    assert_eq!(transfer_tx.amount, units::gil(100));
     
     // Test contract deployment transaction
     let contract_tx = Transaction::new_contract_deploy(
@@ -139,7 +155,7 @@ async fn test_transaction_types() -> Result<()> {
         "bob".to_string(),
         "contract_address".to_string(),
         "increment".to_string(),
-        0.0,
+        0,
         DEFAULT_GAS_LIMIT,
         DEFAULT_GAS_PRICE,
     )?;
@@ -150,35 +166,35 @@ async fn test_transaction_types() -> Result<()> {
     // Test staking transaction
     let stake_tx = Transaction::new_staking(
         "validator1".to_string(),
-        1000.0,
+        units::gil(1000),
         true,
     )?;
     
     assert_eq!(stake_tx.transaction_type, TransactionType::Staking);
-    assert_eq!(stake_tx.amount, 1000.0);
+    assert_eq!(stake_tx.amount, units::gil(1000));
     
     Ok(())
 }
 
 #[tokio::test]
 async fn test_blockchain_with_contracts_and_pos() -> Result<()> {
-    let mut blockchain = Blockchain::new_pos(50.0, 100.0, 5)?;
+    // Start with a genesis validator (required for PoS) and sign its blocks
+    let validator_key = KeyPair::generate()?;
+    let mut blockchain = Blockchain::new_pos_with_validators(50.0, units::gil(100), 5, vec![
+        (validator_key.public_key_hex(), "validator1".to_string(), units::gil(1000)),
+    ])?;
+    blockchain.add_validator_signer(validator_key)?;
     
     // Give alice some initial balance using coinbase
+    let alice_key = KeyPair::generate()?;
+    let alice = create_address(&alice_key.public_key());
     blockchain.add_transaction_object(Transaction::new_transfer(
         "COINBASE".to_string(),
-        "alice".to_string(),
-        1000.0,
+        alice.clone(),
+        units::gil(1000),
         None,
     )?)?;
     
-    // Register a validator first (required for PoS)
-    blockchain.register_validator(
-        "validator1".to_string(),
-        "validator1".to_string(),
-        1000.0,
-    )?;
-    
     // Mine a block to process the initial transaction
     blockchain.mine_block("validator1".to_string())?;
     
@@ -195,22 +211,20 @@ RETURN
 "#;
 
     let _contract_address = blockchain.deploy_contract(
-        "alice".to_string(),
+        alice.clone(),
         contract_code.to_string(),
         DEFAULT_GAS_LIMIT,
         DEFAULT_GAS_PRICE,
     )?;
     
     // Create a transaction
-    let tx = Transaction::new_transfer(
-        "alice".to_string(),
+    blockchain.add_transaction(
+        &alice_key,
         "bob".to_string(),
-        50.0,
+        units::gil(50),
         None,
     )?;
     
-    blockchain.add_transaction_object(tx)?;
-    
     // Verify blockchain state
     assert_eq!(blockchain.get_consensus_type(), ConsensusType::ProofOfStake);
     assert_eq!(blockchain.get_contracts().len(), 1);
@@ -247,14 +261,10 @@ async fn test_contract_examples() -> Result<()> {
 
 #[tokio::test]
 async fn test_pos_validator_performance() -> Result<()> {
-    let mut blockchain = Blockchain::new_pos(50.0, 100.0, 5)?;
-    
-    // Register a validator
-    blockchain.register_validator(
-        "validator1".to_string(),
-        "validator1".to_string(),
-        1000.0,
-    )?;
+    // Start with a single genesis validator
+    let blockchain = Blockchain::new_pos_with_validators(50.0, units::gil(100), 5, vec![
+        ("validator1".to_string(), "validator1".to_string(), units::gil(1000)),
+    ])?;
     
     // Get validator stats
     let stats = blockchain.get_pos_stats().unwrap();
@@ -1,7 +1,7 @@
 // Integration_tests Test Suite
 // Tests for integration tests
 
-use gillean::Blockchain;
+use gillean::{Blockchain, Transaction, units};
 
 pub struct IntegrationTestsSuite {
     // Placeholder for integration_tests test suite
@@ -31,14 +31,16 @@ mod tests {
 
     #[test]
     fn test_basic_blockchain_integration() {
+        let alice_key = gillean::crypto::KeyPair::generate().unwrap();
+        let alice = gillean::crypto::create_address(&alice_key.public_key());
         let mut blockchain = Blockchain::new_pow(2, 50.0).unwrap();
         
         // Add initial balance to alice first
-        blockchain.add_transaction("COINBASE".to_string(), "alice".to_string(), 100.0, Some("initial balance".to_string())).unwrap();
+        blockchain.add_transaction_object(Transaction::new_transfer("COINBASE".to_string(), alice.clone(), units::gil(100), Some("initial balance".to_string())).unwrap()).unwrap();
         blockchain.mine_block("miner".to_string()).unwrap();
         
         // Now test basic transaction
-        blockchain.add_transaction("alice".to_string(), "bob".to_string(), 10.0, Some("test transaction".to_string())).unwrap();
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(10), Some("test transaction".to_string())).unwrap();
         
         // Test mining again
         blockchain.mine_block("miner".to_string()).unwrap();
@@ -49,17 +51,19 @@ mod tests {
 
     #[test]
     fn test_transaction_validation_integration() {
+        let alice_key = gillean::crypto::KeyPair::generate().unwrap();
+        let alice = gillean::crypto::create_address(&alice_key.public_key());
         let mut blockchain = Blockchain::new_pow(2, 50.0).unwrap();
         
         // Add initial balance
-        blockchain.add_transaction("COINBASE".to_string(), "alice".to_string(), 100.0, Some("initial balance".to_string())).unwrap();
+        blockchain.add_transaction_object(Transaction::new_transfer("COINBASE".to_string(), alice.clone(), units::gil(100), Some("initial balance".to_string())).unwrap()).unwrap();
         blockchain.mine_block("miner".to_string()).unwrap();
         
         // Valid transaction
-        blockchain.add_transaction("alice".to_string(), "bob".to_string(), 50.0, Some("valid transaction".to_string())).unwrap();
+        blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(50), Some("valid transaction".to_string())).unwrap();
         
         // Invalid transaction (insufficient balance)
-        let result = blockchain.add_transaction("alice".to_string(), "bob".to_string(), 200.0, Some("invalid transaction".to_string()));
+        let result = blockchain.add_transaction(&alice_key, "bob".to_string(), units::gil(200), Some("invalid transaction".to_string()));
         assert!(result.is_err());
     }
 }
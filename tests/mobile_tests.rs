@@ -1,4 +1,5 @@
-use gillean::{Blockchain, Transaction, BlockchainError};
+use gillean::{Blockchain, Transaction, BlockchainError, units};
+use gillean::crypto::KeyPair;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use uuid::Uuid;
@@ -39,6 +40,7 @@ pub struct MobileWallet {
     pub wallet_id: String,
     pub device_id: String,
     pub encrypted_private_key: String,
+    pub signing_key: KeyPair,
     pub public_address: String,
     pub balance: f64,
     pub transaction_history: Vec<Transaction>,
@@ -151,6 +153,7 @@ impl MobileManager {
             wallet_id: wallet_id.clone(),
             device_id: device_id.to_string(),
             encrypted_private_key: format!("encrypted_key_{}", wallet_id),
+            signing_key: KeyPair::generate()?,
             public_address,
             balance: 0.0,
             transaction_history: Vec::new(),
@@ -195,14 +198,14 @@ impl MobileManager {
         let blockchain_tx = Transaction::new_transfer(
             wallet.public_address.clone(),
             recipient.to_string(),
-            amount,
+            units::to_base_units(amount)?,
             Some(format!("Mobile transaction {}", transaction_id)),
         )?;
 
         // Add to blockchain
         let mut blockchain = self.blockchain.lock().unwrap();
         blockchain.add_transaction(
-            blockchain_tx.sender.clone(),
+            &wallet.signing_key,
             blockchain_tx.receiver.clone(),
             blockchain_tx.amount,
             blockchain_tx.message.clone(),
@@ -320,7 +323,7 @@ pub struct MobileSupportSuite {
 
 impl MobileSupportSuite {
     pub fn new() -> Result<Self, BlockchainError> {
-        let blockchain = Blockchain::new_pos(10.0, 100.0, 21)?;
+        let blockchain = Blockchain::new_pos(10.0, units::gil(100), 21)?;
         let manager = MobileManager::new(blockchain);
         
         Ok(Self {
@@ -22,7 +22,7 @@ pub mod test_runner;
 
 // Common test utilities and fixtures
 pub mod test_utils {
-    use gillean::{Blockchain, Result, Transaction};
+    use gillean::{Blockchain, Result, Transaction, units};
     use tempfile::TempDir;
     use std::path::PathBuf;
     use std::sync::Arc;
@@ -56,7 +56,7 @@ pub mod test_utils {
             let temp_dir = TempDir::new()?;
             let db_path = temp_dir.path().join("test_db");
 
-            let blockchain = Blockchain::new_pos(reward, min_stake, max_validators as usize)?;
+            let blockchain = Blockchain::new_pos(reward, units::to_base_units(min_stake)?, max_validators as usize)?;
             let blockchain = Arc::new(Mutex::new(blockchain));
 
             Ok(Self {
@@ -74,7 +74,7 @@ pub mod test_utils {
                 blockchain.add_transaction_object(Transaction::new_transfer(
                     "COINBASE".to_string(),
                     account.to_string(),
-                    *balance,
+                    units::to_base_units(*balance)?,
                     None,
                 )?)?;
             }
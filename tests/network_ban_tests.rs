@@ -0,0 +1,143 @@
+use gillean::block::Block;
+use gillean::blockchain::Blockchain;
+use gillean::monitor::BlockchainMonitor;
+use gillean::network::{self, BanPolicy, Network, NetworkMessage, PeerViolation};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+async fn start_node() -> Network {
+    start_node_with_frame_limit(network::MAX_MESSAGE_SIZE).await
+}
+
+async fn start_node_with_frame_limit(max_frame_size: usize) -> Network {
+    let blockchain = Arc::new(Mutex::new(Blockchain::new_pow(1, 50.0).unwrap()));
+    let monitor = Arc::new(Mutex::new(BlockchainMonitor::new()));
+    let mut network = Network::new("127.0.0.1:0".to_string(), blockchain, monitor).unwrap();
+    network.set_max_frame_size(max_frame_size);
+    network.start().await.unwrap();
+    network
+}
+
+fn invalid_block() -> Block {
+    let mut block = Block::new(1, vec![], "0".repeat(64), "1.0".to_string(), "pow".to_string()).unwrap();
+    block.hash = "f".repeat(64);
+    block
+}
+
+async fn send(stream: &mut TcpStream, message: &NetworkMessage) {
+    stream.write_all(&network::encode_frame(message).unwrap()).await.unwrap();
+}
+
+/// Read one length-prefixed message
+async fn receive(stream: &mut TcpStream) -> std::io::Result<NetworkMessage> {
+    let mut header = [0u8; network::FRAME_HEADER_SIZE];
+    stream.read_exact(&mut header).await?;
+    let mut payload = vec![0u8; u32::from_be_bytes(header) as usize];
+    stream.read_exact(&mut payload).await?;
+    Ok(serde_json::from_slice(&payload)?)
+}
+
+/// Check whether a node still answers a ping from this host
+async fn answers_ping(address: &str) -> bool {
+    let Ok(mut stream) = TcpStream::connect(address).await else {
+        return false;
+    };
+    send(&mut stream, &NetworkMessage::Ping).await;
+    let read = tokio::time::timeout(Duration::from_secs(2), receive(&mut stream)).await;
+    matches!(read, Ok(Ok(NetworkMessage::Pong)))
+}
+
+#[tokio::test]
+async fn test_framed_message_is_parsed() {
+    let node = start_node().await;
+    let mut stream = TcpStream::connect(node.local_address()).await.unwrap();
+
+    // A frame split across writes is reassembled before parsing
+    let frame = network::encode_frame(&NetworkMessage::GetChainInfo).unwrap();
+    let (head, tail) = frame.split_at(3);
+    stream.write_all(head).await.unwrap();
+    stream.flush().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    stream.write_all(tail).await.unwrap();
+
+    let response = tokio::time::timeout(Duration::from_secs(2), receive(&mut stream)).await.unwrap().unwrap();
+    assert!(matches!(response, NetworkMessage::ChainInfo { height: 1, .. }));
+}
+
+#[tokio::test]
+async fn test_oversized_frame_penalizes_peer() {
+    let node = start_node_with_frame_limit(1024).await;
+    node.set_ban_policy(BanPolicy { threshold: -10, duration: Duration::from_secs(60) }).await;
+    let address = node.local_address().to_string();
+
+    let mut stream = TcpStream::connect(&address).await.unwrap();
+    let peer_address = stream.local_addr().unwrap().to_string();
+    stream.write_all(&(1025u32).to_be_bytes()).await.unwrap();
+
+    // The node drops the connection without waiting for the payload
+    let mut buffer = [0u8; 1];
+    let read = tokio::time::timeout(Duration::from_secs(2), stream.read(&mut buffer)).await.unwrap();
+    assert!(matches!(read, Ok(0) | Err(_)));
+    assert!(node.is_banned(&peer_address).await);
+    assert!(!answers_ping(&address).await);
+}
+
+#[tokio::test]
+async fn test_peer_sending_invalid_blocks_is_banned() {
+    let node = start_node().await;
+    let address = node.local_address().to_string();
+    assert!(answers_ping(&address).await);
+
+    let mut stream = TcpStream::connect(&address).await.unwrap();
+    let peer_address = stream.local_addr().unwrap().to_string();
+    for _ in 0..4 {
+        send(&mut stream, &NetworkMessage::NewBlock(invalid_block())).await;
+    }
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while !node.is_banned(&peer_address).await {
+        assert!(tokio::time::Instant::now() < deadline, "peer was not banned");
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    // Reconnecting from the banned host is refused
+    assert!(!answers_ping(&address).await);
+}
+
+#[tokio::test]
+async fn test_score_drops_before_ban() {
+    let peer = start_node().await;
+    let mut node = start_node().await;
+    node.connect_to_peer(peer.local_address()).await.unwrap();
+    assert_eq!(node.get_peer(peer.local_address()).await.unwrap().score, 0);
+
+    assert!(!node.penalize_peer(peer.local_address(), PeerViolation::InvalidBlock).await);
+    assert!(!node.penalize_peer(peer.local_address(), PeerViolation::OversizedMessage).await);
+    assert_eq!(node.get_peer(peer.local_address()).await.unwrap().score, -35);
+    assert!(!node.is_banned(peer.local_address()).await);
+
+    assert!(!node.penalize_peer(peer.local_address(), PeerViolation::InvalidBlock).await);
+    assert!(!node.penalize_peer(peer.local_address(), PeerViolation::InvalidBlock).await);
+    assert!(node.penalize_peer(peer.local_address(), PeerViolation::InvalidBlock).await);
+    assert!(node.is_banned(peer.local_address()).await);
+    assert!(node.get_peer(peer.local_address()).await.is_none());
+    assert!(node.connect_to_peer(peer.local_address()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_ban_expires() {
+    let peer = start_node().await;
+    let mut node = start_node().await;
+    node.set_ban_policy(BanPolicy { threshold: -10, duration: Duration::from_millis(200) }).await;
+
+    assert!(node.penalize_peer(peer.local_address(), PeerViolation::OversizedMessage).await);
+    assert!(node.connect_to_peer(peer.local_address()).await.is_err());
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(!node.is_banned(peer.local_address()).await);
+    node.connect_to_peer(peer.local_address()).await.unwrap();
+    assert_eq!(node.get_peer(peer.local_address()).await.unwrap().score, 0);
+}
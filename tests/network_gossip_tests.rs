@@ -0,0 +1,127 @@
+mod common;
+
+use gillean::blockchain::Blockchain;
+use gillean::crypto::{create_address, KeyPair};
+use gillean::network::Network;
+use gillean::transaction::Transaction;
+use gillean::units;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+use common::start_node;
+
+/// Start three nodes sharing a chain where `funded` has funds, connected in a ring
+async fn start_ring(funded: &KeyPair) -> Vec<(Network, Arc<Mutex<Blockchain>>)> {
+    let mut blockchain = Blockchain::new_pow(1, 50.0).unwrap();
+    blockchain.add_transaction_object(Transaction::new_transfer("COINBASE".to_string(), create_address(&funded.public_key()), units::gil(100), None).unwrap()).unwrap();
+    blockchain.mine_block("miner".to_string()).unwrap();
+
+    let mut nodes = Vec::new();
+    for _ in 0..3 {
+        nodes.push(start_node(blockchain.clone()).await);
+    }
+    for i in 0..3 {
+        let next = nodes[(i + 1) % 3].0.local_address().to_string();
+        nodes[i].0.connect_to_peer(&next).await.unwrap();
+    }
+    nodes
+}
+
+/// Create a first transfer from the keypair's address, signed by it
+fn signed_transfer(sender: &KeyPair, receiver: &str, amount: u128) -> Transaction {
+    let mut tx = Transaction::new_transfer(create_address(&sender.public_key()), receiver.to_string(), amount, None).unwrap();
+    tx.sign(sender).unwrap();
+    tx
+}
+
+async fn in_mempool(blockchain: &Arc<Mutex<Blockchain>>, tx_id: &str) -> bool {
+    blockchain.lock().await.pending_transactions.iter().any(|tx| tx.id == tx_id)
+}
+
+#[tokio::test]
+async fn test_transaction_gossips_to_all_nodes() {
+    let alice = KeyPair::generate().unwrap();
+    let nodes = start_ring(&alice).await;
+    let tx = signed_transfer(&alice, "bob", units::gil(10));
+
+    nodes[0].0.submit_transaction(tx.clone()).await.unwrap();
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    loop {
+        let mut everywhere = true;
+        for (_, blockchain) in &nodes {
+            everywhere &= in_mempool(blockchain, &tx.id).await;
+        }
+        if everywhere {
+            break;
+        }
+        assert!(tokio::time::Instant::now() < deadline, "transaction did not reach every node");
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    // The ring is not gossiped around forever and nobody accepts a second copy
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    for (_, blockchain) in &nodes {
+        let count = blockchain.lock().await.pending_transactions.iter().filter(|t| t.id == tx.id).count();
+        assert_eq!(count, 1);
+    }
+    assert!(nodes[0].0.submit_transaction(tx).await.is_err());
+}
+
+#[tokio::test]
+async fn test_unsigned_transfer_is_not_gossiped() {
+    let alice = KeyPair::generate().unwrap();
+    let nodes = start_ring(&alice).await;
+    let mut tx = signed_transfer(&alice, "bob", units::gil(10));
+    tx.signature = None;
+
+    assert!(nodes[0].0.submit_transaction(tx.clone()).await.is_err());
+    nodes[1].0.broadcast_transaction(&tx).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    for (_, blockchain) in &nodes {
+        assert!(!in_mempool(blockchain, &tx.id).await);
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_transaction_is_not_gossiped() {
+    let nodes = start_ring(&KeyPair::generate().unwrap()).await;
+    // Bob has no funds, so no node accepts this
+    let tx = signed_transfer(&KeyPair::generate().unwrap(), "carol", units::gil(10));
+
+    assert!(nodes[0].0.submit_transaction(tx.clone()).await.is_err());
+
+    // Pushing it straight to a peer that has not seen it yet gets it rejected there too
+    nodes[1].0.broadcast_transaction(&tx).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    for (_, blockchain) in &nodes {
+        assert!(!in_mempool(blockchain, &tx.id).await);
+    }
+}
+
+#[tokio::test]
+async fn test_rejected_transaction_is_not_remembered() {
+    let nodes = start_ring(&KeyPair::generate().unwrap()).await;
+    let bob = KeyPair::generate().unwrap();
+    let tx = signed_transfer(&bob, "carol", units::gil(10));
+
+    // Rejected while bob has no funds
+    assert!(nodes[0].0.submit_transaction(tx.clone()).await.is_err());
+
+    // Once bob is funded everywhere the same transaction goes through
+    for (_, blockchain) in &nodes {
+        let mut blockchain = blockchain.lock().await;
+        blockchain.add_transaction_object(Transaction::new_transfer("COINBASE".to_string(), create_address(&bob.public_key()), units::gil(50), None).unwrap()).unwrap();
+        blockchain.mine_block("miner".to_string()).unwrap();
+    }
+    nodes[0].0.submit_transaction(tx.clone()).await.unwrap();
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    for (_, blockchain) in &nodes {
+        while !in_mempool(blockchain, &tx.id).await {
+            assert!(tokio::time::Instant::now() < deadline, "transaction did not reach every node");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+}
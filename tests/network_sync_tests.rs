@@ -0,0 +1,114 @@
+mod common;
+
+use gillean::blockchain::Blockchain;
+use gillean::units;
+use gillean::transaction::Transaction;
+
+use common::start_node;
+
+fn mine_blocks(blockchain: &mut Blockchain, miner: &str, count: usize) {
+    for _ in 0..count {
+        let receiver = format!("{}-user{}", miner, blockchain.blocks.len());
+        blockchain.add_transaction_object(Transaction::new_transfer("COINBASE".to_string(), receiver, units::gil(10), None).unwrap()).unwrap();
+        blockchain.mine_block(miner.to_string()).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_node_behind_catches_up() {
+    let mut ahead = Blockchain::new_pow(1, 50.0).unwrap();
+    // Blocks mined back to back would otherwise keep raising the difficulty
+    ahead.retarget_interval = 0;
+    mine_blocks(&mut ahead, "miner", 10);
+    let behind = ahead.clone();
+    // More than one batch of blocks is missing
+    mine_blocks(&mut ahead, "miner", 110);
+
+    let (ahead_node, ahead_chain) = start_node(ahead).await;
+    let (mut behind_node, behind_chain) = start_node(behind).await;
+
+    behind_node.connect_to_peer(ahead_node.local_address()).await.unwrap();
+
+    let ahead_chain = ahead_chain.lock().await;
+    let behind_chain = behind_chain.lock().await;
+    assert_eq!(behind_chain.blocks.len(), 121);
+    assert_eq!(behind_chain.blocks.last().unwrap().hash, ahead_chain.blocks.last().unwrap().hash);
+    assert_eq!(behind_chain.balances, ahead_chain.balances);
+}
+
+#[tokio::test]
+async fn test_fork_resolved_by_cumulative_difficulty() {
+    let mut common = Blockchain::new_pow(1, 50.0).unwrap();
+    mine_blocks(&mut common, "miner", 5);
+    let mut fork_a = common.clone();
+    let mut fork_b = common;
+    mine_blocks(&mut fork_a, "alice", 3);
+    mine_blocks(&mut fork_b, "bob", 8);
+
+    // Every mined block's work is 16^n with n >= 1, so forks of 3 and 8 blocks can never tie
+    let heavier = if fork_a.cumulative_difficulty() > fork_b.cumulative_difficulty() {
+        fork_a.blocks.last().unwrap().hash.clone()
+    } else {
+        fork_b.blocks.last().unwrap().hash.clone()
+    };
+
+    let (mut node_a, chain_a) = start_node(fork_a).await;
+    let (mut node_b, chain_b) = start_node(fork_b).await;
+    let address_a = node_a.local_address().to_string();
+    let address_b = node_b.local_address().to_string();
+
+    // Whichever node is on the lighter fork switches to the heavier one
+    node_a.connect_to_peer(&address_b).await.unwrap();
+    node_b.connect_to_peer(&address_a).await.unwrap();
+
+    let chain_a = chain_a.lock().await;
+    let chain_b = chain_b.lock().await;
+    assert_eq!(chain_a.blocks.last().unwrap().hash, heavier);
+    assert_eq!(chain_b.blocks.last().unwrap().hash, heavier);
+    assert_eq!(chain_a.blocks.len(), chain_b.blocks.len());
+    assert_eq!(chain_a.balances, chain_b.balances);
+}
+
+#[tokio::test]
+async fn test_lighter_chain_is_not_adopted() {
+    let mut longer = Blockchain::new_pow(1, 50.0).unwrap();
+    mine_blocks(&mut longer, "miner", 4);
+    let shorter = {
+        let mut chain = longer.clone();
+        chain.blocks.truncate(2);
+        chain
+    };
+
+    let (shorter_node, _) = start_node(shorter).await;
+    let (longer_node, longer_chain) = start_node(longer).await;
+
+    let adopted = longer_node.sync_with_peer(shorter_node.local_address()).await.unwrap();
+    assert_eq!(adopted, 0);
+    assert_eq!(longer_chain.lock().await.blocks.len(), 5);
+}
+
+#[tokio::test]
+async fn test_blocks_received_out_of_order_are_connected() {
+    let mut chain = Blockchain::new_pow(1, 50.0).unwrap();
+    mine_blocks(&mut chain, "miner", 2);
+    let mut ahead = chain.clone();
+    mine_blocks(&mut ahead, "miner", 2);
+    let (first, second) = (ahead.blocks[3].clone(), ahead.blocks[4].clone());
+
+    let (mut sender, _) = start_node(chain.clone()).await;
+    let (receiver, receiver_chain) = start_node(chain).await;
+    sender.connect_to_peer(receiver.local_address()).await.unwrap();
+
+    // The child arrives first and waits in the orphan pool for its parent
+    sender.broadcast_block(&second).await.unwrap();
+    sender.broadcast_block(&first).await.unwrap();
+
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+    while receiver_chain.lock().await.blocks.len() < 5 {
+        assert!(tokio::time::Instant::now() < deadline, "blocks were not connected");
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    let receiver_chain = receiver_chain.lock().await;
+    assert_eq!(receiver_chain.blocks.last().unwrap().hash, second.hash);
+    assert!(receiver_chain.orphan_blocks.is_empty());
+}
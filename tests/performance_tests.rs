@@ -21,6 +21,7 @@ impl PerformanceTestSuite {
             memory_threshold: 0.8,
             gc_interval: Duration::from_secs(30),
             enable_metrics: true,
+            memory_high_water_mark: 64 * 1024 * 1024,
         };
 
         Self {
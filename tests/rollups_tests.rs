@@ -1,7 +1,7 @@
 // Rollups Test Suite
 // Tests for Layer 2 scaling with optimistic and zk-rollups
 
-use gillean::{Result, Blockchain, Transaction, BlockchainError};
+use gillean::{Result, Blockchain, Transaction, BlockchainError, units};
 use std::collections::HashMap;
 use tokio::sync::Mutex;
 use std::sync::Arc;
@@ -212,9 +212,9 @@ impl RollupsSuite {
 
         // Create test transactions
         let transactions = vec![
-            Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some("tx1".to_string()))?,
-            Transaction::new_transfer("bob".to_string(), "charlie".to_string(), 5.0, Some("tx2".to_string()))?,
-            Transaction::new_transfer("charlie".to_string(), "alice".to_string(), 3.0, Some("tx3".to_string()))?,
+            Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), Some("tx1".to_string()))?,
+            Transaction::new_transfer("bob".to_string(), "charlie".to_string(), units::gil(5), Some("tx2".to_string()))?,
+            Transaction::new_transfer("charlie".to_string(), "alice".to_string(), units::gil(3), Some("tx3".to_string()))?,
         ];
 
         let batch_id = manager.create_batch(transactions.clone())?;
@@ -240,8 +240,8 @@ impl RollupsSuite {
 
         // Create test transactions
         let transactions = vec![
-            Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some("tx1".to_string()))?,
-            Transaction::new_transfer("bob".to_string(), "charlie".to_string(), 5.0, Some("tx2".to_string()))?,
+            Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), Some("tx1".to_string()))?,
+            Transaction::new_transfer("bob".to_string(), "charlie".to_string(), units::gil(5), Some("tx2".to_string()))?,
         ];
 
         let batch_id = manager.create_batch(transactions.clone())?;
@@ -267,7 +267,7 @@ impl RollupsSuite {
 
         // Create and submit batch
         let transactions = vec![
-            Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some("tx1".to_string()))?,
+            Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), Some("tx1".to_string()))?,
         ];
 
         let batch_id = manager.create_batch(transactions)?;
@@ -287,7 +287,7 @@ impl RollupsSuite {
 
         // Create batch
         let transactions = vec![
-            Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some("tx1".to_string()))?,
+            Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), Some("tx1".to_string()))?,
         ];
 
         let batch_id = manager.create_batch(transactions)?;
@@ -310,7 +310,7 @@ impl RollupsSuite {
 
         // Create batch
         let transactions = vec![
-            Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some("tx1".to_string()))?,
+            Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), Some("tx1".to_string()))?,
         ];
 
         let batch_id = manager.create_batch(transactions)?;
@@ -342,7 +342,7 @@ impl RollupsSuite {
         );
         
         let transactions = vec![
-            Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some("tx1".to_string()))?,
+            Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), Some("tx1".to_string()))?,
         ];
         let batch_id = zk_manager.create_batch(transactions)?;
         
@@ -362,8 +362,8 @@ impl RollupsSuite {
         );
 
         let transactions = vec![
-            Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some("tx1".to_string()))?,
-            Transaction::new_transfer("bob".to_string(), "charlie".to_string(), 5.0, Some("tx2".to_string()))?,
+            Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), Some("tx1".to_string()))?,
+            Transaction::new_transfer("bob".to_string(), "charlie".to_string(), units::gil(5), Some("tx2".to_string()))?,
         ];
 
         let merkle_root = manager.calculate_merkle_root(&transactions);
@@ -413,7 +413,7 @@ mod tests {
         );
         
         let transactions = vec![
-            Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some("tx1".to_string())).unwrap(),
+            Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), Some("tx1".to_string())).unwrap(),
         ];
         
         let batch_id = manager.create_batch(transactions).unwrap();
@@ -431,7 +431,7 @@ mod tests {
         );
         
         let transactions = vec![
-            Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some("tx1".to_string())).unwrap(),
+            Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), Some("tx1".to_string())).unwrap(),
         ];
         
         let batch_id = manager.create_batch(transactions).unwrap();
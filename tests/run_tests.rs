@@ -1,7 +1,7 @@
 // Main test runner script
 // Execute this to run all comprehensive tests
 
-use gillean::{Result, BlockchainError, Transaction};
+use gillean::{Result, BlockchainError, Transaction, units};
 use uuid::Uuid;
 
 #[tokio::test]
@@ -49,7 +49,7 @@ async fn run_basic_blockchain_test() -> Result<()> {
     let transaction = gillean::Transaction::new_transfer(
         "COINBASE".to_string(),
         "alice".to_string(),
-        100.0,
+        units::gil(100),
         Some("Initial funding for Alice".to_string()),
     )?;
     blockchain.add_transaction_object(transaction)?;
@@ -96,14 +96,14 @@ async fn run_ai_integration_tests() -> Result<()> {
     let _normal_tx = Transaction::new_transfer(
         "sender1".to_string(),
         "receiver1".to_string(),
-        100.0,
+        units::gil(100),
         Some("normal transaction".to_string()),
     )?;
     
     let large_tx = Transaction::new_transfer(
         "sender2".to_string(),
         "receiver2".to_string(),
-        50000.0,
+        units::gil(50000),
         Some("large transaction".to_string()),
     )?;
     
@@ -122,7 +122,7 @@ async fn run_ai_integration_tests() -> Result<()> {
 }
 
 fn analyze_transaction_anomaly(transaction: &Transaction) -> AnomalyScore {
-    let amount = transaction.amount;
+    let amount = units::from_base_units(transaction.amount);
     let avg_amount = 100.0;
     let std_amount = 50.0;
     
@@ -152,7 +152,7 @@ fn predict_fraud(transaction: &Transaction) -> FraudPrediction {
     let mut risk_factors = Vec::new();
     
     // Amount-based risk
-    if transaction.amount > 10000.0 {
+    if transaction.amount > units::gil(10000) {
         risk_score += 0.3;
         risk_factors.push("High transaction amount".to_string());
     }
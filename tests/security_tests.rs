@@ -22,6 +22,7 @@ impl SecurityTestSuite {
             threat_detection_enabled: true,
             max_failed_attempts: 5,
             session_timeout: Duration::from_secs(1800),
+            min_password_strength: 50,
         };
 
         Self {
@@ -179,12 +180,7 @@ impl SecurityTestSuite {
     async fn test_threat_detector(&self) -> Result<(), String> {
         println!("    Testing Threat Detector...");
 
-        let threat_config = ThreatDetectionConfig {
-            enable_real_time_detection: true,
-            detection_threshold: 0.7,
-            auto_mitigation: false,
-        };
-        let detector = ThreatDetector::new(threat_config);
+        let detector = ThreatDetector::new(ThreatDetectionConfig::default());
 
         // Add threat patterns
         let sql_injection = ThreatPattern {
@@ -377,9 +373,8 @@ mod tests {
     #[tokio::test]
     async fn test_threat_detection() {
         let config = ThreatDetectionConfig {
-            enable_real_time_detection: true,
             detection_threshold: 0.5,
-            auto_mitigation: false,
+            ..ThreatDetectionConfig::default()
         };
         let detector = ThreatDetector::new(config);
 
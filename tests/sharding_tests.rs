@@ -1,7 +1,7 @@
 // Advanced Sharding Test Suite
 // Tests for dynamic shard allocation and rebalancing
 
-use gillean::{Result, Blockchain, Transaction, BlockchainError};
+use gillean::{Result, Blockchain, Transaction, BlockchainError, units};
 use std::collections::HashMap;
 use tokio::sync::Mutex;
 use std::sync::Arc;
@@ -333,7 +333,7 @@ impl ShardingSuite {
             let tx = Transaction::new_transfer(
                 format!("user_{}", i),
                 format!("user_{}", i + 1),
-                10.0,
+                units::gil(10),
                 Some(format!("tx_{}", i))
             )?;
             
@@ -363,7 +363,7 @@ impl ShardingSuite {
         );
 
         // Create a cross-shard transaction
-        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, Some("cross_tx".to_string()))?;
+        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(100), Some("cross_tx".to_string()))?;
         let cross_tx_id = manager.create_cross_shard_transaction("shard_0", "shard_1", tx)?;
 
         // Verify transaction was created
@@ -395,7 +395,7 @@ impl ShardingSuite {
             let tx = Transaction::new_transfer(
                 format!("user_{}", i),
                 format!("user_{}", i + 1),
-                10.0,
+                units::gil(10),
                 Some(format!("tx_{}", i))
             )?;
             
@@ -431,12 +431,12 @@ impl ShardingSuite {
         );
 
         // Test adding transaction to non-existent shard
-        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some("tx".to_string()))?;
+        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), Some("tx".to_string()))?;
         let result = manager.add_transaction_to_shard("non_existent", tx);
         assert!(result.is_err());
 
         // Test cross-shard transaction within same shard
-        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some("tx".to_string()))?;
+        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), Some("tx".to_string()))?;
         let result = manager.create_cross_shard_transaction("shard_0", "shard_0", tx);
         assert!(result.is_err());
 
@@ -445,7 +445,7 @@ impl ShardingSuite {
             let tx = Transaction::new_transfer(
                 format!("user_{}", i),
                 format!("user_{}", i + 1),
-                10.0,
+                units::gil(10),
                 Some(format!("tx_{}", i))
             )?;
             manager.add_transaction_to_shard("shard_0", tx)?;
@@ -471,7 +471,7 @@ impl ShardingSuite {
             let tx = Transaction::new_transfer(
                 format!("user_{}", i),
                 format!("user_{}", i + 1),
-                10.0,
+                units::gil(10),
                 Some(format!("tx_{}", i))
             )?;
             
@@ -548,7 +548,7 @@ mod tests {
             2
         );
         
-        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some("tx".to_string())).unwrap();
+        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), units::gil(10), Some("tx".to_string())).unwrap();
         
         let result = manager.add_transaction_to_shard("shard_0", tx);
         assert!(result.is_ok());
@@ -6,16 +6,21 @@
 use gillean::{
     storage::{BlockchainStorage, BackupType},
     blockchain::Blockchain,
-    transaction::Transaction,
-    crypto::KeyPair,
+    crypto::{create_address, KeyPair},
+    units,
 };
 use tempfile::TempDir;
 
 /// Helper function to set up initial balances for test accounts
-fn setup_test_balances(blockchain: &mut Blockchain) {
-    blockchain.balances.insert("alice".to_string(), 1000.0);
-    blockchain.balances.insert("bob".to_string(), 1000.0);
-    blockchain.balances.insert("charlie".to_string(), 1000.0);
+/// 
+/// Returns the signing keys of the two sending accounts, alice and bob.
+fn setup_test_balances(blockchain: &mut Blockchain) -> (KeyPair, KeyPair) {
+    let alice = KeyPair::generate().unwrap();
+    let bob = KeyPair::generate().unwrap();
+    blockchain.balances.insert(create_address(&alice.public_key()), units::gil(1000));
+    blockchain.balances.insert(create_address(&bob.public_key()), units::gil(1000));
+    blockchain.balances.insert("charlie".to_string(), units::gil(1000));
+    (alice, bob)
 }
 
 /// Test data integrity check functionality
@@ -32,14 +37,11 @@ fn test_data_integrity_check() {
     let _keypair = KeyPair::generate();
     
     // Set up initial balances
-    setup_test_balances(&mut blockchain);
+    let (alice, bob) = setup_test_balances(&mut blockchain);
     
     // Add some transactions
-    let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, Some("Test transaction 1".to_string())).unwrap();
-    let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), 50.0, Some("Test transaction 2".to_string())).unwrap();
-    
-    blockchain.add_transaction_object(tx1).unwrap();
-    blockchain.add_transaction_object(tx2).unwrap();
+    blockchain.add_transaction(&alice, "bob".to_string(), units::gil(100), Some("Test transaction 1".to_string())).unwrap();
+    blockchain.add_transaction(&bob, "charlie".to_string(), units::gil(50), Some("Test transaction 2".to_string())).unwrap();
     
     // Mine a block
     blockchain.mine_block("miner".to_string()).unwrap();
@@ -72,12 +74,11 @@ fn test_backup_and_restore() {
     let _keypair = KeyPair::generate();
     
     // Set up initial balances
-    setup_test_balances(&mut blockchain);
+    let (alice, _) = setup_test_balances(&mut blockchain);
     
     // Add transactions and mine blocks
     for i in 1..6 { // Start from 1 to avoid 0 amount transaction
-        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0 * i as f64, Some(format!("Test {}", i))).unwrap();
-        blockchain.add_transaction_object(tx).unwrap();
+        blockchain.add_transaction(&alice, "bob".to_string(), units::gil(10 * i), Some(format!("Test {}", i))).unwrap();
         blockchain.mine_block("miner".to_string()).unwrap();
     }
     
@@ -134,11 +135,10 @@ fn test_backup_cleanup() {
     
     // Create multiple backups
     let mut blockchain = Blockchain::new_pow(4, 50.0).unwrap();
-    setup_test_balances(&mut blockchain);
+    let (alice, _) = setup_test_balances(&mut blockchain);
     
     for i in 1..6 { // Start from 1 to avoid 0 amount transaction
-        let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 10.0, Some(format!("Test {}", i))).unwrap();
-        blockchain.add_transaction_object(tx).unwrap();
+        blockchain.add_transaction(&alice, "bob".to_string(), units::gil(10), Some(format!("Test {}", i))).unwrap();
         blockchain.mine_block("miner".to_string()).unwrap();
         storage.save_blockchain(&blockchain).unwrap();
         
@@ -189,10 +189,9 @@ fn test_incremental_backup() {
     
     // Create initial blockchain
     let mut blockchain = Blockchain::new_pow(4, 50.0).unwrap();
-    setup_test_balances(&mut blockchain);
+    let (alice, bob) = setup_test_balances(&mut blockchain);
     
-    let tx1 = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, Some("Initial transaction".to_string())).unwrap();
-    blockchain.add_transaction_object(tx1).unwrap();
+    blockchain.add_transaction(&alice, "bob".to_string(), units::gil(100), Some("Initial transaction".to_string())).unwrap();
     blockchain.mine_block("miner".to_string()).unwrap();
     storage.save_blockchain(&blockchain).unwrap();
     
@@ -201,8 +200,7 @@ fn test_incremental_backup() {
     assert_eq!(full_backup.backup_type, BackupType::Full);
     
     // Add more data
-    let tx2 = Transaction::new_transfer("bob".to_string(), "charlie".to_string(), 50.0, Some("Additional transaction".to_string())).unwrap();
-    blockchain.add_transaction_object(tx2).unwrap();
+    blockchain.add_transaction(&bob, "charlie".to_string(), units::gil(50), Some("Additional transaction".to_string())).unwrap();
     blockchain.mine_block("miner".to_string()).unwrap();
     storage.save_blockchain(&blockchain).unwrap();
     
@@ -226,10 +224,9 @@ fn test_corruption_detection() {
     
     // Create blockchain with data
     let mut blockchain = Blockchain::new_pow(4, 50.0).unwrap();
-    setup_test_balances(&mut blockchain);
+    let (alice, _) = setup_test_balances(&mut blockchain);
     
-    let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, Some("Test transaction".to_string())).unwrap();
-    blockchain.add_transaction_object(tx).unwrap();
+    blockchain.add_transaction(&alice, "bob".to_string(), units::gil(100), Some("Test transaction".to_string())).unwrap();
     blockchain.mine_block("miner".to_string()).unwrap();
     storage.save_blockchain(&blockchain).unwrap();
     
@@ -262,10 +259,9 @@ fn test_metadata_updates() {
     
     // Create blockchain and save
     let mut blockchain = Blockchain::new_pow(4, 50.0).unwrap();
-    setup_test_balances(&mut blockchain);
+    let (alice, _) = setup_test_balances(&mut blockchain);
     
-    let tx = Transaction::new_transfer("alice".to_string(), "bob".to_string(), 100.0, Some("Test transaction".to_string())).unwrap();
-    blockchain.add_transaction_object(tx).unwrap();
+    blockchain.add_transaction(&alice, "bob".to_string(), units::gil(100), Some("Test transaction".to_string())).unwrap();
     blockchain.mine_block("miner".to_string()).unwrap();
     storage.save_blockchain(&blockchain).unwrap();
     
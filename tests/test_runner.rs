@@ -1,7 +1,8 @@
 // Simple Test Runner
 // Basic test runner for the comprehensive test suite
 
-use gillean::{Blockchain, Transaction, BlockchainError};
+use gillean::{Blockchain, Transaction, BlockchainError, units};
+use gillean::crypto::{create_address, KeyPair};
 use std::time::{Instant, Duration};
 use std::collections::HashMap;
 
@@ -52,32 +53,34 @@ impl TestRunner {
         println!("🧪 Running basic blockchain test...");
         
         // Create a new PoS blockchain
-        let mut blockchain = Blockchain::new_pos(10.0, 100.0, 21)?;
+        let mut blockchain = Blockchain::new_pos(10.0, units::gil(100), 21)?;
         
         // Add some transactions
+        let alice = KeyPair::generate()?;
+        let bob = KeyPair::generate()?;
         let tx1 = Transaction::new_transfer(
-            "alice".to_string(),
-            "bob".to_string(),
-            50.0,
+            create_address(&alice.public_key()),
+            create_address(&bob.public_key()),
+            units::gil(50),
             Some("test transaction 1".to_string()),
         )?;
         
         let tx2 = Transaction::new_transfer(
-            "bob".to_string(),
+            create_address(&bob.public_key()),
             "charlie".to_string(),
-            25.0,
+            units::gil(25),
             Some("test transaction 2".to_string()),
         )?;
         
         blockchain.add_transaction(
-            tx1.sender.clone(),
+            &alice,
             tx1.receiver.clone(),
             tx1.amount,
             tx1.message.clone(),
         )?;
         
         blockchain.add_transaction(
-            tx2.sender.clone(),
+            &bob,
             tx2.receiver.clone(),
             tx2.amount,
             tx2.message.clone(),